@@ -8,6 +8,8 @@ export type {
   PluginInstallInfo,
   PluginSource,
   FileAccessor,
+  RandomAccessFile,
+  VirtualFileSystem,
   PluginInitializeContext,
 } from './types';
 
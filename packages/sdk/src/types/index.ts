@@ -81,6 +81,51 @@ export interface FileAccessor {
    * 获取文件的文本内容（自动智能编码检测）
    */
   getTextContent: () => Promise<string>;
+
+  /**
+   * 打开随机读取句柄，读取结果在后端按块缓存，适合需要自行定位的查看器
+   * 每个插件同时打开的句柄数有上限，使用完毕后需要调用 close
+   */
+  openRandomAccess?: () => Promise<RandomAccessFile>;
+
+  /**
+   * 读取与当前文件同一目录（或其子目录）下的文件，如模型引用的贴图
+   * 读取使用当前连接的认证在后端完成，不允许使用绝对路径或 ..，单次最多 16MB
+   */
+  readRelativeFile?: (relativePath: string) => Promise<ArrayBuffer>;
+
+  /**
+   * 虚拟文件系统，用同一种路径写法访问存储中的文件和压缩包内的条目
+   */
+  vfs?: VirtualFileSystem;
+}
+
+/**
+ * 虚拟文件系统
+ * 路径格式为 `<协议>://<路径>[!<压缩包内条目>]`，如 `oss://bucket/data.zip!images/001.png`
+ * 协议必须与当前连接一致，不支持嵌套压缩包
+ */
+export interface VirtualFileSystem {
+  /** 获取文件大小，以及是否为压缩包内的条目 */
+  stat: (path: string) => Promise<{ size: number; inArchive: boolean }>;
+  /**
+   * 从指定偏移读取数据，未指定 length 时读取到末尾，单次最多 16MB
+   */
+  read: (path: string, offset?: number, length?: number) => Promise<ArrayBuffer>;
+}
+
+/**
+ * 随机读取句柄
+ */
+export interface RandomAccessFile {
+  /** 文件大小（字节） */
+  size: number;
+  /**
+   * 从指定偏移读取数据，单次最多 16MB，读到文件末尾时返回的数据更短
+   */
+  pread: (offset: number, length: number) => Promise<ArrayBuffer>;
+  /** 关闭句柄 */
+  close: () => Promise<void>;
 }
 
 /**
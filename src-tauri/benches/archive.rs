@@ -0,0 +1,184 @@
+// 压缩包与范围读取基准测试
+// 运行：cargo bench --features bench
+// 样本在系统临时目录中生成，读取经过本地存储客户端，与远程存储走同一套 StorageClient 接口
+
+use std::ops::Range;
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dataset_viewer_lib::bench_support::{self, BenchStorage, Fixtures};
+
+const ENTRY_COUNTS: [usize; 2] = [1_000, 10_000];
+const DATA_SIZE: usize = 16 * 1024 * 1024; // 16MB
+const PREVIEW_SIZE: u32 = 1024 * 1024; // 1MB
+/// 超过 gzip 建立随机访问索引的阈值
+const INDEXED_GZIP_SIZE: usize = 96 * 1024 * 1024; // 96MB
+
+fn fixtures_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join("dataset-viewer-bench").join(name)
+}
+
+fn fixtures(name: &str, entries: usize) -> (PathBuf, Fixtures) {
+    sized_fixtures(name, entries, DATA_SIZE)
+}
+
+fn sized_fixtures(name: &str, entries: usize, size: usize) -> (PathBuf, Fixtures) {
+    let dir = fixtures_dir(name);
+    let fixtures = bench_support::write_fixtures(&dir, entries, size)
+        .expect("failed to generate bench fixtures");
+    (dir, fixtures)
+}
+
+/// 本地存储客户端按完整路径访问文件，不会拼接连接的根目录
+fn full_path(path: &std::path::Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// 分散的小区间：每 64KB 读取 4KB，相邻区间之间部分重叠
+fn scattered_ranges(file_size: u64) -> Vec<Range<u64>> {
+    (0..file_size / 65536)
+        .flat_map(|i| {
+            let start = i * 65536;
+            [start..start + 4096, start + 2048..start + 8192]
+        })
+        .collect()
+}
+
+fn central_directory(c: &mut Criterion) {
+    let mut group = c.benchmark_group("zip_central_directory");
+    for entries in ENTRY_COUNTS {
+        let (dir, fixtures) = fixtures(&format!("cd-{}", entries), entries);
+        let data = std::fs::read(&fixtures.many_entries_zip).unwrap();
+        let storage = BenchStorage::local(&dir).unwrap();
+        let name = full_path(&fixtures.many_entries_zip);
+
+        group.throughput(Throughput::Elements(entries as u64));
+        group.bench_with_input(BenchmarkId::new("parse", entries), &data, |b, data| {
+            b.iter(|| bench_support::parse_zip_central_directory(data).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("analyze", entries), &name, |b, name| {
+            b.iter(|| storage.analyze_archive(name).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn deflate_streaming(c: &mut Criterion) {
+    let (dir, fixtures) = fixtures("deflate", 1);
+    let storage = BenchStorage::local(&dir).unwrap();
+    let name = full_path(&fixtures.deflate_zip);
+
+    let mut group = c.benchmark_group("deflate_streaming");
+    group.throughput(Throughput::Bytes(PREVIEW_SIZE as u64));
+    group.bench_function("zip_entry_preview", |b| {
+        b.iter(|| {
+            storage
+                .preview_entry(&name, "data.jsonl", PREVIEW_SIZE, None)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn gzip(c: &mut Criterion) {
+    let (dir, fixtures) = fixtures("gzip", 1);
+    let storage = BenchStorage::local(&dir).unwrap();
+    let data = std::fs::read(&fixtures.gzip).unwrap();
+    let name = full_path(&fixtures.gzip);
+
+    let mut group = c.benchmark_group("gzip");
+    group.throughput(Throughput::Bytes(PREVIEW_SIZE as u64));
+    group.bench_function("decompress_sample", |b| {
+        b.iter(|| bench_support::decompress_gzip_sample(&data, PREVIEW_SIZE as usize).unwrap())
+    });
+    group.bench_function("preview", |b| {
+        b.iter(|| {
+            storage
+                .preview_entry(&name, "", PREVIEW_SIZE, None)
+                .unwrap()
+        })
+    });
+    // 从解压后数据的中部开始预览，衡量定位到指定偏移的开销
+    let offset = (DATA_SIZE / 2) as u64;
+    group.bench_function("preview_at_offset", |b| {
+        b.iter(|| {
+            storage
+                .preview_entry(&name, "", PREVIEW_SIZE, Some(offset))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn gzip_seek_index(c: &mut Criterion) {
+    let (dir, fixtures) = sized_fixtures("gzip-index", 1, INDEXED_GZIP_SIZE);
+    let storage = BenchStorage::local(&dir).unwrap();
+    let name = full_path(&fixtures.gzip);
+    let offset = (INDEXED_GZIP_SIZE * 3 / 4) as u64;
+
+    let mut group = c.benchmark_group("gzip_seek_index");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(PREVIEW_SIZE as u64));
+    // 每次都删除索引，从头解压并建立检查点
+    group.bench_function("build", |b| {
+        b.iter(|| {
+            storage
+                .gzip_read_at(&name, offset, PREVIEW_SIZE as usize, true)
+                .unwrap()
+        })
+    });
+    // 索引已写入磁盘，从最近的检查点开始解压
+    storage
+        .gzip_read_at(&name, offset, PREVIEW_SIZE as usize, true)
+        .unwrap();
+    group.bench_function("indexed", |b| {
+        b.iter(|| {
+            storage
+                .gzip_read_at(&name, offset, PREVIEW_SIZE as usize, false)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn range_reads(c: &mut Criterion) {
+    let (dir, fixtures) = fixtures("ranges", 1);
+    let storage = BenchStorage::local(&dir).unwrap();
+    let name = full_path(&fixtures.raw);
+    let file_size = std::fs::metadata(&fixtures.raw).unwrap().len();
+    let ranges = scattered_ranges(file_size);
+
+    let mut group = c.benchmark_group("range_reads");
+    group.throughput(Throughput::Elements(ranges.len() as u64));
+    group.bench_function("merge", |b| {
+        b.iter(|| bench_support::merge_ranges(ranges.clone(), file_size))
+    });
+    group.bench_function("individual", |b| {
+        b.iter(|| storage.read_ranges(&name, &ranges).unwrap())
+    });
+    group.bench_function("merged", |b| {
+        b.iter(|| {
+            storage
+                .read_ranges_merged(&name, ranges.clone(), file_size)
+                .unwrap()
+        })
+    });
+    group.bench_function("block_reader", |b| {
+        b.iter(|| {
+            storage
+                .read_through_block_reader(&name, file_size, &ranges)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    central_directory,
+    deflate_streaming,
+    gzip,
+    gzip_seek_index,
+    range_reads
+);
+criterion_main!(benches);
@@ -1,3 +1,169 @@
+// 应用命令清单
+// 清单中的命令需要由 capability 显式授权后窗口才能调用，tauri-build 为每个命令生成
+// allow-<命令名> 权限，各类窗口可用的命令集合定义在 permissions/windows.toml
+// 新增命令时需同时加入此清单和对应的命令集合
+const COMMANDS: &[&str] = &[
+    // 统一存储接口命令
+    "storage_connect",
+    "storage_disconnect",
+    "storage_list",
+    "storage_restore_object",
+    "storage_list_object_versions",
+    "storage_list_revisions",
+    "huggingface_get_dataset_info",
+    "huggingface_list_parquet_splits",
+    "storage_detect_mime",
+    "storage_get_metadata",
+    "generate_thumbnail",
+    "storage_upload",
+    "storage_upload_cancel",
+    "storage_delete",
+    "storage_rename",
+    "storage_mkdir",
+    "storage_compute_stats",
+    "storage_compute_stats_cancel",
+    "storage_watch_start",
+    "storage_watch_stop",
+    "storage_get_capabilities",
+    "storage_sso_open",
+    "storage_sso_capture",
+    "storage_sso_clear",
+    "storage_get_visibility_settings",
+    "storage_set_visibility_settings",
+    "storage_get_http_pool_settings",
+    "storage_set_http_pool_settings",
+    // 连接配置管理命令
+    "connection_save",
+    "connection_list",
+    "connection_delete",
+    "connection_test",
+    "connection_connect",
+    // 下载管理命令
+    "download_start",
+    "download_directory",
+    "download_resume",
+    "download_list_resumable",
+    "download_pause",
+    "download_resume_task",
+    "download_reorder",
+    "download_set_max_concurrent",
+    "download_get_queue",
+    "download_list_tasks",
+    "download_find_existing",
+    "download_to_memory",
+    "download_cancel",
+    "download_cancel_all",
+    "download_extract_file",
+    // 系统对话框命令
+    "system_select_folder",
+    "system_select_file",
+    // 压缩包处理命令（统一接口）
+    "archive_get_file_info",
+    "archive_get_entry_thumbnail",
+    "archive_search",
+    "archive_search_cancel",
+    "archive_extract_all",
+    "archive_extract_cancel",
+    "archive_preview_pause",
+    "archive_preview_resume",
+    "archive_preview_cancel",
+    "archive_get_settings",
+    "archive_set_settings",
+    // 数据集维护命令
+    "dataset_rename_preview",
+    "dataset_rename_apply",
+    "dataset_rename_undo",
+    "dataset_find_duplicates",
+    "dataset_chunk_signature",
+    "dataset_compare_signatures",
+    "dataset_estimate_operation",
+    "dataset_confirm_operation",
+    "dataset_verify_integrity",
+    "dataset_get_ignore_rules",
+    "dataset_set_ignore_rules",
+    "dataset_list_flat",
+    "dataset_compute_facets",
+    "dataset_index_build",
+    "dataset_index_refresh",
+    "dataset_index_status",
+    "dataset_index_search",
+    "dataset_index_search_content",
+    "dataset_index_get_content_settings",
+    "dataset_index_set_content_settings",
+    "dataset_index_purge",
+    "dataset_scan_pii",
+    "dataset_detect_licenses",
+    "dataset_budget_list",
+    "dataset_budget_save",
+    "dataset_budget_delete",
+    "dataset_budget_check",
+    // 搜索历史命令
+    "search_history_list",
+    "search_history_pin",
+    "search_history_delete",
+    "search_history_clear",
+    "search_history_rerun",
+    // 快速打开命令
+    "quick_open_search",
+    // 内容预览命令
+    "preview_run",
+    "preview_list_sheets",
+    "preview_read_sheet_range",
+    "dataset_preview_table",
+    "parquet_get_metadata",
+    "parquet_read_rows",
+    "arrow_get_schema",
+    "arrow_read_batch",
+    "jsonl_read_records",
+    "file_search_content",
+    "file_search_cancel",
+    // 插件发现命令
+    "plugin_discover",
+    // 插件文件加载命令
+    "load_plugin_file",
+    "plugin_check_file_exists",
+    // 插件文件随机读取命令
+    "file_open",
+    "file_pread",
+    "file_close",
+    "plugin_storage_read",
+    "plugin_viewer_attach",
+    "plugin_viewer_detach",
+    "vfs_stat",
+    "vfs_read",
+    // 插件管理命令
+    "plugin_install",
+    "plugin_uninstall",
+    "plugin_toggle",
+    "plugin_get_active",
+    // 插件版本管理命令
+    "plugin_check_updates",
+    "plugin_update",
+    // 窗口主题设置命令
+    "system_set_theme",
+    // 缓存清理命令
+    "system_get_retention_policies",
+    "system_set_retention_policies",
+    "system_cleanup_now",
+    "cache_clear",
+    // 应用数据自检命令
+    "system_get_self_check_report",
+    // 网络配置命令
+    "system_get_network_config",
+    "system_set_network_config",
+    // 文件查看窗口命令
+    "system_get_viewer_window_settings",
+    "system_set_viewer_window_settings",
+    "system_set_window_zoom",
+    "viewer_open_file",
+    "viewer_get_tabs",
+    "viewer_set_tabs",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
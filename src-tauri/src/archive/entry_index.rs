@@ -0,0 +1,139 @@
+// 压缩包条目的随机访问索引
+// TAR / TAR.GZ 只能从头顺序查找条目，分析和预览时记录每个条目数据在（解压后的）TAR 流中的偏移，
+// 持久化到磁盘后，之后的预览直接定位到条目而不必从头扫描；索引按连接 + 压缩包路径 + 文件版本（ETag、修改时间和大小）区分
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::storage::manager::current_connection_key;
+use crate::storage::traits::StorageClient;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 小于该大小的压缩包从头扫描也很快，不建立索引
+const MIN_INDEXED_ARCHIVE_SIZE: u64 = 16 * 1024 * 1024; // 16MB
+
+/// 条目数据在 TAR 流中的位置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryLocation {
+    /// 条目数据（不含 512 字节头部）在 TAR 流中的偏移
+    pub offset: u64,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// 单个压缩包的条目索引（条目路径 -> 位置）
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntryIndex {
+    entries: HashMap<String, EntryLocation>,
+    /// 下一个尚未扫描的头部在 TAR 流中的偏移
+    scanned_offset: u64,
+    /// 是否已扫描到 TAR 结束，完整的索引中找不到的条目一定不存在
+    complete: bool,
+    #[serde(skip)]
+    file: PathBuf,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl ArchiveEntryIndex {
+    /// 打开压缩包的索引，磁盘上没有时返回空索引
+    /// 压缩包太小或缓存目录不可用时返回 None，调用方按原方式扫描
+    pub async fn open(
+        client: &dyn StorageClient,
+        archive_path: &str,
+        file_size: u64,
+    ) -> Option<Self> {
+        if file_size < MIN_INDEXED_ARCHIVE_SIZE {
+            return None;
+        }
+
+        let file = index_file(client, archive_path, file_size, "json").await?;
+
+        let mut index = std::fs::read(&file)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<Self>(&data).ok())
+            .unwrap_or_default();
+        index.file = file;
+        Some(index)
+    }
+
+    pub fn get(&self, entry_path: &str) -> Option<EntryLocation> {
+        self.entries.get(entry_path).copied()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn scanned_offset(&self) -> u64 {
+        self.scanned_offset
+    }
+
+    /// 记录条目位置，next_header_offset 为该条目之后下一个头部的偏移
+    pub fn record(&mut self, entry_path: &str, location: EntryLocation, next_header_offset: u64) {
+        if !self.entries.contains_key(entry_path) {
+            self.entries.insert(entry_path.to_string(), location);
+            self.dirty = true;
+        }
+        if next_header_offset > self.scanned_offset {
+            self.scanned_offset = next_header_offset;
+            self.dirty = true;
+        }
+    }
+
+    /// 标记已扫描到 TAR 结束
+    pub fn mark_complete(&mut self) {
+        if !self.complete {
+            self.complete = true;
+            self.dirty = true;
+        }
+    }
+
+    /// 有新记录时写入磁盘，失败只记录日志
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let result = serde_json::to_vec(self)
+            .map_err(|e| e.to_string())
+            .and_then(|data| persist::write_atomic(&self.file, data).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => self.dirty = false,
+            Err(e) => log::warn!("写入压缩包索引失败 {}: {}", self.file.display(), e),
+        }
+    }
+}
+
+/// 索引文件所在的目录
+pub fn index_dir() -> Result<PathBuf, String> {
+    get_app_data_subdir("archive-index")
+}
+
+/// 压缩包的索引文件路径，按连接 + 压缩包路径 + 文件版本区分，不同存储上的同名文件互不影响
+/// 缓存目录不可用时返回 None
+pub async fn index_file(
+    client: &dyn StorageClient,
+    archive_path: &str,
+    file_size: u64,
+    extension: &str,
+) -> Option<PathBuf> {
+    let version = match client.get_metadata(archive_path).await {
+        Ok(metadata) => metadata.cache_version(),
+        Err(_) => format!("size:{}", file_size),
+    };
+    let connection_key = current_connection_key().await;
+    let mut hasher = Sha256::new();
+    for part in [connection_key.as_str(), archive_path, version.as_str()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let dir = index_dir()
+        .map_err(|e| log::warn!("无法创建压缩包索引目录: {}", e))
+        .ok()?;
+    Some(dir.join(format!("{}.{}", hex::encode(hasher.finalize()), extension)))
+}
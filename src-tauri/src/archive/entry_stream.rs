@@ -0,0 +1,191 @@
+// 压缩包条目的顺序读取
+// 按偏移分块读取预览时，只能顺序解压的格式每次都要从头解压到偏移处，完整读取大条目或逐个读取所有条目的耗时与数据量的平方成正比；
+// 这里在阻塞线程中一次解压到底，按块（或按条目）通过有界通道交给调用方，调用方丢弃接收端即停止解压
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// 每次交给调用方的数据量
+pub const STREAM_CHUNK_SIZE: usize = 4 * 1024 * 1024; // 4MB
+
+/// 通道中最多缓存的块数，调用方处理较慢时解压线程等待
+const STREAM_BUFFER: usize = 2;
+
+/// 单个条目内容的顺序数据流
+pub struct EntryStream {
+    receiver: mpsc::Receiver<Result<Vec<u8>, String>>,
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+impl EntryStream {
+    /// 在阻塞线程中运行 produce，把写入 ChunkWriter 的数据按块发送出去
+    pub fn spawn_blocking<F>(produce: F) -> Self
+    where
+        F: FnOnce(&mut ChunkWriter) -> Result<(), String> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let size = Arc::new(Mutex::new(None));
+        let mut writer = ChunkWriter {
+            sender,
+            buffer: Vec::with_capacity(STREAM_CHUNK_SIZE),
+            size: size.clone(),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let result = produce(&mut writer).and_then(|_| {
+                writer
+                    .flush()
+                    .map_err(|e| format!("Failed to read archive entry: {}", e))
+            });
+            // 调用方已停止读取时不再报告错误
+            if let Err(e) = result {
+                if !writer.sender.is_closed() {
+                    let _ = writer.sender.blocking_send(Err(e));
+                }
+            }
+        });
+
+        Self { receiver, size }
+    }
+
+    /// 由异步任务按块产生数据的数据流，用于没有顺序读取实现的格式
+    pub fn channel() -> (StreamSender, Self) {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let size = Arc::new(Mutex::new(None));
+        let stream_sender = StreamSender {
+            sender,
+            size: size.clone(),
+        };
+        (stream_sender, Self { receiver, size })
+    }
+
+    /// 读取下一块数据，读完时返回 None
+    pub async fn next_chunk(&mut self) -> Option<Result<Vec<u8>, String>> {
+        self.receiver.recv().await
+    }
+
+    /// 条目解压后的大小，格式未记录或尚未读到条目头时为 None
+    pub fn size(&self) -> Option<u64> {
+        *self.size.lock().unwrap()
+    }
+}
+
+/// 异步产生数据时顺序数据流的发送端
+pub struct StreamSender {
+    sender: mpsc::Sender<Result<Vec<u8>, String>>,
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+impl StreamSender {
+    /// 记录条目解压后的大小
+    pub fn set_size(&self, size: u64) {
+        *self.size.lock().unwrap() = Some(size);
+    }
+
+    /// 发送一块数据或错误，调用方已停止读取时返回 false
+    pub async fn send(&self, chunk: Result<Vec<u8>, String>) -> bool {
+        self.sender.send(chunk).await.is_ok()
+    }
+}
+
+/// 顺序数据流的写入端，写满一块时发送给调用方；调用方已丢弃数据流时写入返回 BrokenPipe
+pub struct ChunkWriter {
+    sender: mpsc::Sender<Result<Vec<u8>, String>>,
+    buffer: Vec<u8>,
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+impl ChunkWriter {
+    /// 记录条目解压后的大小
+    pub fn set_size(&self, size: u64) {
+        *self.size.lock().unwrap() = Some(size);
+    }
+
+    /// 从解压流中读取全部数据，调用方中途停止读取时正常返回
+    pub fn copy_from(&mut self, reader: &mut dyn std::io::Read) -> Result<(), String> {
+        match std::io::copy(reader, self) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            Err(e) => Err(format!("Failed to read archive entry: {}", e)),
+        }
+    }
+
+    fn send_buffer(&mut self) -> std::io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(STREAM_CHUNK_SIZE));
+        self.sender
+            .blocking_send(Ok(chunk))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let count = buf.len().min(STREAM_CHUNK_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..count]);
+        if self.buffer.len() >= STREAM_CHUNK_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.send_buffer()
+    }
+}
+
+/// 多个条目的内容，按在压缩包中的顺序逐个返回（条目路径, 内容）
+pub struct EntryScan {
+    receiver: mpsc::Receiver<Result<(String, Vec<u8>), String>>,
+}
+
+impl EntryScan {
+    /// 在阻塞线程中运行 produce，通过 ScanSender 逐个发送条目内容
+    pub fn spawn_blocking<F>(produce: F) -> Self
+    where
+        F: FnOnce(&ScanSender) -> Result<(), String> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let sender = ScanSender { sender };
+
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = produce(&sender) {
+                if !sender.is_closed() {
+                    let _ = sender.sender.blocking_send(Err(e));
+                }
+            }
+        });
+
+        Self { receiver }
+    }
+
+    /// 由异步任务逐个产生条目的扫描，用于没有顺序读取实现的格式
+    pub fn channel() -> (mpsc::Sender<Result<(String, Vec<u8>), String>>, Self) {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        (sender, Self { receiver })
+    }
+
+    /// 读取下一个条目，全部读完时返回 None
+    pub async fn next_entry(&mut self) -> Option<Result<(String, Vec<u8>), String>> {
+        self.receiver.recv().await
+    }
+}
+
+/// 条目扫描的发送端
+pub struct ScanSender {
+    sender: mpsc::Sender<Result<(String, Vec<u8>), String>>,
+}
+
+impl ScanSender {
+    /// 发送一个条目的内容，调用方已停止读取时返回 false
+    pub fn send(&self, entry_path: String, content: Vec<u8>) -> bool {
+        self.sender.blocking_send(Ok((entry_path, content))).is_ok()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
@@ -0,0 +1,134 @@
+use crate::archive::entry_stream::EntryStream;
+use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
+/// Brotli 格式处理器
+/// 通过 StorageReader 按需读取压缩数据并流式解压
+/// Brotli 没有魔数也不记录原始大小，只能按扩展名识别，原始大小按样本压缩比估算
+use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+
+/// 解码器内部缓冲区大小
+const BUFFER_SIZE: usize = 64 * 1024;
+
+pub struct BrotliHandler;
+
+#[async_trait::async_trait]
+impl CompressionHandlerDispatcher for BrotliHandler {
+    async fn analyze_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        _max_size: Option<u32>,
+    ) -> Result<ArchiveInfo, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        let entry_name = stream_entry_name(filename, &[".br"]);
+        tokio::task::spawn_blocking(move || Self::analyze(reader, file_size, entry_name))
+            .await
+            .map_err(|e| format!("Brotli analysis task failed: {}", e))?
+    }
+
+    async fn extract_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        tokio::task::spawn_blocking(move || {
+            read_stream_preview(
+                Self::decoder(reader)?,
+                offset.unwrap_or(0),
+                max_size,
+                None,
+                progress_callback,
+            )
+        })
+        .await
+        .map_err(|e| format!("Brotli extraction task failed: {}", e))?
+    }
+
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            writer.copy_from(&mut Self::decoder(reader)?)
+        })))
+    }
+
+    fn compression_type(&self) -> CompressionType {
+        CompressionType::Brotli
+    }
+
+    /// Brotli 没有文件头签名，无法通过内容识别
+    fn validate_format(&self, _data: &[u8]) -> bool {
+        false
+    }
+}
+
+impl BrotliHandler {
+    fn decoder<R: Read>(reader: R) -> Result<brotli::Decompressor<R>, String> {
+        Ok(brotli::Decompressor::new(reader, BUFFER_SIZE))
+    }
+
+    fn analyze(
+        reader: StorageReader,
+        file_size: u64,
+        entry_name: String,
+    ) -> Result<ArchiveInfo, String> {
+        let uncompressed_size = estimate_stream_size(reader, file_size, Self::decoder)
+            .map_err(|e| format!("Invalid Brotli data: {}", e))?;
+
+        let entry = ArchiveEntry {
+            path: entry_name,
+            size: uncompressed_size.to_string(),
+            compressed_size: Some(file_size.to_string()),
+            is_dir: false,
+            modified_time: None,
+            crc32: None,
+            encrypted: false,
+            index: 0,
+            metadata: HashMap::new(),
+        };
+
+        Ok(ArchiveInfoBuilder::new(CompressionType::Brotli)
+            .entries(vec![entry])
+            .total_uncompressed_size(uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(false)
+            .analysis_status(AnalysisStatus::Complete)
+            .build())
+    }
+}
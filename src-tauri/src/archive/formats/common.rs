@@ -1,67 +1,19 @@
 /// 共享的工具函数和常用逻辑
+use crate::utils::mime::{is_text_content, mime_from_magic, DEFAULT_MIME};
 
 /// 检测 MIME 类型
 pub fn detect_mime_type(data: &[u8]) -> String {
     // 检查文件头部特征
-    if data.len() >= 8 {
-        // PNG
-        if &data[0..8] == b"\x89PNG\r\n\x1a\n" {
-            return "image/png".to_string();
-        }
-        // JPEG
-        if &data[0..3] == b"\xff\xd8\xff" {
-            return "image/jpeg".to_string();
-        }
-        // GIF
-        if &data[0..6] == b"GIF87a" || &data[0..6] == b"GIF89a" {
-            return "image/gif".to_string();
-        }
-        // PDF
-        if &data[0..5] == b"%PDF-" {
-            return "application/pdf".to_string();
-        }
+    if let Some(mime) = mime_from_magic(data) {
+        return mime.to_string();
     }
 
     // 尝试解析为文本
     if is_text_content(data) {
         "text/plain".to_string()
     } else {
-        "application/octet-stream".to_string()
-    }
-}
-
-/// 检查是否为文本内容
-pub fn is_text_content(data: &[u8]) -> bool {
-    if data.is_empty() {
-        return true;
+        DEFAULT_MIME.to_string()
     }
-
-    // 检查前1024字节或全部数据
-    let check_len = data.len().min(1024);
-    let sample = &data[0..check_len];
-
-    // 统计非文本字符数量
-    let mut non_text_count = 0;
-    let mut total_checked = 0;
-
-    for &byte in sample {
-        total_checked += 1;
-
-        // 允许的文本字符：
-        // - ASCII 可打印字符 (32-126)
-        // - 常见空白字符 (9, 10, 13)
-        // - UTF-8 序列起始字节 (128-255)
-        if !(32..=126).contains(&byte) && // 可打印ASCII
-           ![9, 10, 13].contains(&byte) && // 制表符、换行符、回车符
-           byte < 128
-        {
-            // 非UTF-8起始字节
-            non_text_count += 1;
-        }
-    }
-
-    // 如果非文本字符比例小于10%，认为是文本
-    (non_text_count as f64 / total_checked as f64) < 0.1
 }
 
 /// 文件预览构建器
@@ -105,6 +57,7 @@ impl PreviewBuilder {
             is_truncated: self.is_truncated,
             total_size: self.total_size,
             preview_size: self.preview_size,
+            encoding: None,
         }
     }
 }
@@ -184,3 +137,407 @@ impl ArchiveInfoBuilder {
         }
     }
 }
+
+/// 基于 StorageClient 范围读取的同步 Read + Seek 适配器
+/// 用于只接受 Read + Seek 的解析库（如 7z），按块缓存以减少远程请求次数
+/// 内部使用 block_on 发起异步读取，只能在 spawn_blocking 线程中使用
+pub struct StorageReader {
+    client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    path: String,
+    size: u64,
+    position: u64,
+    block_size: u64,
+    /// 当前缓存的块（起始偏移, 数据）
+    block: Option<(u64, Vec<u8>)>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl StorageReader {
+    pub fn new(
+        client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+        path: &str,
+        size: u64,
+        runtime: tokio::runtime::Handle,
+    ) -> Self {
+        Self {
+            client,
+            path: path.to_string(),
+            size,
+            position: 0,
+            block_size: 1024 * 1024,
+            block: None,
+            runtime,
+        }
+    }
+
+    fn load_block(&mut self, start: u64) -> std::io::Result<()> {
+        let length = self.block_size.min(self.size - start);
+        let data = self
+            .runtime
+            .block_on(self.client.read_file_range(&self.path, start, length))
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        self.block = Some((start, data));
+        Ok(())
+    }
+}
+
+impl std::io::Read for StorageReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        let cached = self.block.as_ref().is_some_and(|(start, data)| {
+            self.position >= *start && self.position < *start + data.len() as u64
+        });
+        if !cached {
+            let start = self.position - self.position % self.block_size;
+            self.load_block(start)?;
+        }
+
+        let Some((start, data)) = self.block.as_ref() else {
+            return Ok(0);
+        };
+        let offset = (self.position - start) as usize;
+        let available = &data[offset..];
+        if available.is_empty() {
+            return Ok(0);
+        }
+
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl std::io::Seek for StorageReader {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let target = match pos {
+            std::io::SeekFrom::Start(offset) => offset as i128,
+            std::io::SeekFrom::End(offset) => self.size as i128 + offset as i128,
+            std::io::SeekFrom::Current(offset) => self.position as i128 + offset as i128,
+        };
+        if target < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Seek before start of file",
+            ));
+        }
+
+        self.position = target as u64;
+        Ok(self.position)
+    }
+}
+
+/// 获取文件大小并创建 StorageReader
+pub async fn open_storage_reader(
+    client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    file_path: &str,
+) -> Result<StorageReader, String> {
+    let file_size = client
+        .get_file_size(file_path)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    Ok(StorageReader::new(
+        client,
+        file_path,
+        file_size,
+        tokio::runtime::Handle::current(),
+    ))
+}
+
+/// 统计已读取字节数的 Read 包装，计数可在包装被解码器持有后读取
+pub struct CountingReader<R> {
+    inner: R,
+    count: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl<R> CountingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            count: Default::default(),
+        }
+    }
+
+    /// 共享的计数器
+    pub fn counter(&self) -> std::sync::Arc<std::sync::atomic::AtomicU64> {
+        self.count.clone()
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count
+            .fetch_add(n as u64, std::sync::atomic::Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// 单文件压缩流（zstd / lz4 / brotli 等）用于估算压缩比的样本大小
+const STREAM_SAMPLE_SIZE: u64 = 1024 * 1024;
+
+/// 去掉压缩扩展名作为单文件压缩流的条目名（data.jsonl.zst -> data.jsonl）
+pub fn stream_entry_name(filename: &str, extensions: &[&str]) -> String {
+    let name = filename.rsplit('/').next().unwrap_or(filename);
+    let lower = name.to_lowercase();
+    extensions
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| name[..name.len() - ext.len()].to_string())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "compressed_content".to_string())
+}
+
+/// 解压开头的样本，按压缩比估算单文件压缩流的原始大小
+/// 样本内即解压完毕时返回准确大小
+pub fn estimate_stream_size<D, F>(
+    reader: StorageReader,
+    file_size: u64,
+    make_decoder: F,
+) -> Result<u64, String>
+where
+    D: std::io::Read,
+    F: FnOnce(CountingReader<StorageReader>) -> Result<D, String>,
+{
+    use std::io::Read;
+
+    let counting = CountingReader::new(reader);
+    let counter = counting.counter();
+    let decoder = make_decoder(counting)?;
+
+    let mut sample = Vec::new();
+    decoder
+        .take(STREAM_SAMPLE_SIZE)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("Failed to decompress data: {}", e))?;
+    if (sample.len() as u64) < STREAM_SAMPLE_SIZE {
+        return Ok(sample.len() as u64);
+    }
+
+    // 解码器内部有读缓冲，实际消耗的压缩数据略少于计数，估算结果偏保守
+    let consumed = counter.load(std::sync::atomic::Ordering::Relaxed);
+    if consumed == 0 {
+        return Ok(sample.len() as u64);
+    }
+    Ok((file_size as f64 * sample.len() as f64 / consumed as f64) as u64)
+}
+
+/// 丢弃 offset 之前的解压数据时每跳过多少字节回调一次进度
+const SKIP_PROGRESS_INTERVAL: u64 = 4 * 1024 * 1024;
+
+/// 从解压流中读取 offset 开始的 max_size 字节作为预览
+/// 流式格式不支持随机访问，offset 之前的数据需要解压后丢弃，
+/// 跳过期间按（已解压字节数, offset + max_size）回调进度
+/// known_size 为压缩格式头中记录的原始大小，未记录且未读完时总大小只能给出已知的下限
+pub fn read_stream_preview(
+    mut decoder: impl std::io::Read,
+    offset: u64,
+    max_size: usize,
+    known_size: Option<u64>,
+    progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+) -> Result<crate::archive::types::FilePreview, String> {
+    use std::io::Read;
+
+    let target = offset + max_size as u64;
+    let mut skipped = 0u64;
+    while skipped < offset {
+        let chunk = (offset - skipped).min(SKIP_PROGRESS_INTERVAL);
+        let copied = std::io::copy(&mut (&mut decoder).take(chunk), &mut std::io::sink())
+            .map_err(|e| format!("Failed to decompress data: {}", e))?;
+        if copied == 0 {
+            break;
+        }
+        skipped += copied;
+        if let Some(callback) = &progress_callback {
+            callback(skipped, target);
+        }
+    }
+
+    let mut content = Vec::new();
+    (&mut decoder)
+        .take(max_size as u64)
+        .read_to_end(&mut content)
+        .map_err(|e| format!("Failed to decompress data: {}", e))?;
+    if let Some(callback) = &progress_callback {
+        callback(skipped + content.len() as u64, target);
+    }
+
+    // 多读一个字节判断是否还有剩余数据
+    let mut probe = [0u8; 1];
+    let is_truncated = decoder.read(&mut probe).map(|n| n > 0).unwrap_or(false);
+    let read_end = offset + content.len() as u64;
+    let total_size = match known_size {
+        Some(size) => size,
+        None if is_truncated => read_end + 1,
+        None => read_end,
+    };
+
+    Ok(PreviewBuilder::new()
+        .content(content)
+        .total_size(total_size)
+        .with_truncated(is_truncated)
+        .build())
+}
+
+/// 完整解压时每写入多少字节回调一次进度
+const EXTRACT_PROGRESS_INTERVAL: u64 = 8 * 1024 * 1024;
+
+/// 完整解压的公共逻辑：校验条目路径、创建目录、分块写入文件并检查取消信号
+/// 在 spawn_blocking 线程中使用，进度回调参数为（已解压条目数, 已写入字节数）
+pub struct EntryExtractor {
+    target_dir: std::path::PathBuf,
+    progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    entries: u64,
+    bytes: u64,
+    skipped: u64,
+    last_reported: u64,
+}
+
+impl EntryExtractor {
+    pub fn new(
+        target_dir: std::path::PathBuf,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Self {
+        Self {
+            target_dir,
+            progress_callback,
+            cancel_rx,
+            entries: 0,
+            bytes: 0,
+            skipped: 0,
+            last_reported: 0,
+        }
+    }
+
+    /// 解压一个目录或普通文件条目
+    /// 条目路径为绝对路径或包含 .. 时跳过，防止写到目标目录之外
+    pub fn extract(
+        &mut self,
+        entry_path: &str,
+        is_dir: bool,
+        reader: &mut dyn std::io::Read,
+    ) -> Result<(), String> {
+        self.check_cancelled()?;
+        let Some(relative) = safe_relative_path(entry_path) else {
+            log::warn!("跳过路径不安全的压缩包条目: {}", entry_path);
+            self.skip();
+            return Ok(());
+        };
+        let output = self.target_dir.join(relative);
+
+        if is_dir {
+            return std::fs::create_dir_all(&output)
+                .map_err(|e| format!("Failed to create directory: {}", e));
+        }
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        let mut file = std::fs::File::create(&output)
+            .map_err(|e| format!("Failed to create file {}: {}", output.display(), e))?;
+        let mut buffer = vec![0u8; 256 * 1024];
+        loop {
+            let n = reader
+                .read(&mut buffer)
+                .map_err(|e| format!("Failed to extract {}: {}", entry_path, e))?;
+            if n == 0 {
+                break;
+            }
+            std::io::Write::write_all(&mut file, &buffer[..n])
+                .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+            self.bytes += n as u64;
+
+            if self.bytes - self.last_reported >= EXTRACT_PROGRESS_INTERVAL {
+                self.check_cancelled()?;
+                self.report();
+            }
+        }
+
+        self.entries += 1;
+        self.report();
+        Ok(())
+    }
+
+    /// 记录一个跳过的条目（如符号链接等不支持的类型）
+    pub fn skip(&mut self) {
+        self.skipped += 1;
+    }
+
+    pub fn finish(self) -> crate::archive::types::ExtractSummary {
+        crate::archive::types::ExtractSummary {
+            target_dir: self.target_dir.display().to_string(),
+            extracted_entries: self.entries as u32,
+            extracted_bytes: self.bytes.to_string(),
+            skipped_entries: self.skipped as u32,
+        }
+    }
+
+    fn check_cancelled(&mut self) -> Result<(), String> {
+        use tokio::sync::broadcast::error::TryRecvError;
+        match self.cancel_rx.try_recv() {
+            Ok(()) | Err(TryRecvError::Lagged(_)) => Err("download.cancelled".to_string()),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn report(&mut self) {
+        self.last_reported = self.bytes;
+        if let Some(callback) = &self.progress_callback {
+            callback(self.entries, self.bytes);
+        }
+    }
+}
+
+/// 将压缩包条目路径转换为安全的相对路径，绝对路径或包含 .. 时返回 None
+fn safe_relative_path(entry_path: &str) -> Option<std::path::PathBuf> {
+    let mut path = std::path::PathBuf::new();
+    for (index, part) in entry_path.split(['/', '\\']).enumerate() {
+        match part {
+            "" if index == 0 => return None,
+            "" | "." => {}
+            ".." => return None,
+            // Windows 盘符
+            part if index == 0 && part.ends_with(':') => return None,
+            part => path.push(part),
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+/// 完整解压的公共流程：在阻塞线程中通过 StorageReader 读取压缩包，由 extract 逐个写出条目
+pub async fn extract_all_blocking<F>(
+    client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
+    file_path: &str,
+    target_dir: &std::path::Path,
+    progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    extract: F,
+) -> Result<crate::archive::types::ExtractSummary, String>
+where
+    F: FnOnce(StorageReader, &mut EntryExtractor) -> Result<(), String> + Send + 'static,
+{
+    let file_size = client
+        .get_file_size(file_path)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let reader = StorageReader::new(
+        client,
+        file_path,
+        file_size,
+        tokio::runtime::Handle::current(),
+    );
+    let mut extractor = EntryExtractor::new(target_dir.to_path_buf(), progress_callback, cancel_rx);
+
+    tokio::task::spawn_blocking(move || {
+        extract(reader, &mut extractor)?;
+        Ok(extractor.finish())
+    })
+    .await
+    .map_err(|e| format!("Extraction task failed: {}", e))?
+}
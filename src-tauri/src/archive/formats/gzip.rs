@@ -1,8 +1,10 @@
+use crate::archive::entry_stream::EntryStream;
+use crate::archive::formats::gzip_index::GzipSeekIndex;
 use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
 /// GZIP 格式处理器
 use crate::archive::types::*;
 use crate::storage::traits::{ProgressCallback, StorageClient};
-use flate2::read::GzDecoder;
+use flate2::read::{GzDecoder, MultiGzDecoder};
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
 use std::sync::Arc;
@@ -41,6 +43,19 @@ impl CompressionHandlerDispatcher for GzipHandler {
         .await
     }
 
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            writer.copy_from(&mut MultiGzDecoder::new(reader))
+        })))
+    }
+
     fn compression_type(&self) -> CompressionType {
         CompressionType::Gzip
     }
@@ -121,6 +136,7 @@ impl GzipHandler {
             is_dir: false,
             modified_time: None,
             crc32: None,
+            encrypted: false,
             index: 0,
             metadata: HashMap::new(),
         };
@@ -141,7 +157,7 @@ impl GzipHandler {
         client: Arc<dyn StorageClient>,
         file_path: &str,
         max_size: usize,
-        _offset: Option<u64>, // GZIP 格式不支持偏移量
+        offset: Option<u64>,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
@@ -152,6 +168,20 @@ impl GzipHandler {
             .await
             .map_err(|e| format!("Failed to get file size: {}", e))?;
 
+        let offset = offset.unwrap_or(0);
+        if offset > 0 {
+            return Self::extract_gzip_preview_at_offset(
+                client,
+                file_path,
+                file_size,
+                offset,
+                max_size,
+                progress_callback,
+                cancel_rx.map(|rx| rx.resubscribe()),
+            )
+            .await;
+        }
+
         // 估算需要读取的压缩数据大小（考虑压缩比）
         // 通常文本压缩比在3-5倍，二进制文件1.5-2倍
         let estimated_compressed_size = (max_size * 3).max(4096); // 至少读取4KB
@@ -200,8 +230,53 @@ impl GzipHandler {
             .build())
     }
 
+    /// 从解压后的 offset 处提取预览
+    /// 大文件通过随机访问索引从最近的检查点恢复解压，小文件直接从头解压并丢弃 offset 之前的数据
+    async fn extract_gzip_preview_at_offset(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        offset: u64,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let seek_index = GzipSeekIndex::open(client.as_ref(), file_path, file_size).await;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        tokio::task::spawn_blocking(move || match seek_index {
+            Some(mut seek_index) => {
+                let result = seek_index.read_at(
+                    reader,
+                    file_size,
+                    offset,
+                    max_size,
+                    progress_callback.as_deref(),
+                    cancel_rx.as_mut(),
+                );
+                // 取消或出错时已建立的检查点同样有效
+                seek_index.save();
+                result
+            }
+            None => read_stream_preview(
+                MultiGzDecoder::new(reader),
+                offset,
+                max_size,
+                None,
+                progress_callback,
+            ),
+        })
+        .await
+        .map_err(|e| format!("Preview task failed: {}", e))?
+    }
+
     /// 解压缩样本数据
-    fn decompress_sample(
+    pub(crate) fn decompress_sample(
         compressed_data: &[u8],
         max_output_size: usize,
     ) -> Result<Vec<u8>, String> {
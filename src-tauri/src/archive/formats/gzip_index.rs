@@ -0,0 +1,958 @@
+// gzip 随机访问索引（zran 方式）
+// deflate 流只能从头解压。解压时每输出 CHECKPOINT_SPAN 字节，在 deflate 块边界记录一个检查点：
+// 块在压缩流中的比特位置、对应的解压偏移和此前 32KB 的解压窗口。之后读取任意偏移时，
+// 从不超过该偏移的最近检查点恢复解压，最多只需解压 CHECKPOINT_SPAN 字节。
+// 索引随预览逐步建立（只记录已经解压经过的部分），检查点窗口压缩后保存在压缩包索引目录中。
+// flate2 不支持从比特位置恢复解压，这里自带一个只用于该场景的 inflate 实现
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use crate::archive::entry_index;
+use crate::archive::formats::common::PreviewBuilder;
+use crate::archive::types::FilePreview;
+use crate::storage::traits::StorageClient;
+
+/// 小于该大小的 gzip 从头解压也很快，不建立索引
+const MIN_INDEXED_GZIP_SIZE: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 相邻检查点之间的解压数据量
+const CHECKPOINT_SPAN: u64 = 4 * 1024 * 1024; // 4MB
+
+/// deflate 回溯窗口大小
+const WINDOW_SIZE: usize = 32 * 1024;
+
+/// 单次解压最多输出的数据量，更长的 deflate 块分多次解压，避免整块输出占满内存
+const MAX_BLOCK_OUTPUT: usize = 1024 * 1024; // 1MB
+
+/// 每解压多少压缩数据回调一次进度
+const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+const INDEX_MAGIC: &[u8; 4] = b"GZIX";
+const INDEX_VERSION: u32 = 1;
+
+/// 解压检查点
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    /// 检查点对应的解压偏移
+    out_offset: u64,
+    /// deflate 块在压缩流中的比特位置
+    bit_offset: u64,
+    /// 检查点之前最多 32KB 的解压数据
+    window: Vec<u8>,
+}
+
+/// 单个 gzip 文件的随机访问索引
+pub struct GzipSeekIndex {
+    /// 按解压偏移升序排列，第一个检查点为第一个 deflate 块
+    checkpoints: Vec<Checkpoint>,
+    /// 已解压到流末尾时的完整解压大小
+    total_size: Option<u64>,
+    file: PathBuf,
+    dirty: bool,
+}
+
+impl GzipSeekIndex {
+    /// 打开 gzip 文件的索引，磁盘上没有时返回空索引
+    /// 文件太小或缓存目录不可用时返回 None，调用方直接从头解压
+    pub async fn open(client: &dyn StorageClient, file_path: &str, file_size: u64) -> Option<Self> {
+        if file_size < MIN_INDEXED_GZIP_SIZE {
+            return None;
+        }
+
+        let file = entry_index::index_file(client, file_path, file_size, "gzi").await?;
+        let (checkpoints, total_size) = match std::fs::read(&file) {
+            Ok(data) => Self::decode(&data).unwrap_or_else(|e| {
+                log::warn!("gzip 索引损坏，重新建立 {}: {}", file.display(), e);
+                (Vec::new(), None)
+            }),
+            Err(_) => (Vec::new(), None),
+        };
+
+        Some(Self {
+            checkpoints,
+            total_size,
+            file,
+            dirty: false,
+        })
+    }
+
+    /// 从解压后的 offset 处读取 max_size 字节作为预览，途经尚未索引的部分时补充检查点
+    /// 在 spawn_blocking 线程中使用
+    pub fn read_at<R: Read + Seek>(
+        &mut self,
+        mut reader: R,
+        file_size: u64,
+        offset: u64,
+        max_size: usize,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let mut inflater = match self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.out_offset <= offset)
+        {
+            Some(checkpoint) => Inflater::at_checkpoint(reader, checkpoint)?,
+            None => {
+                reader
+                    .seek(SeekFrom::Start(0))
+                    .map_err(|e| format!("Failed to read GZIP data: {}", e))?;
+                let inflater = Inflater::at_stream_start(reader)?;
+                self.checkpoints.push(inflater.checkpoint());
+                self.dirty = true;
+                inflater
+            }
+        };
+
+        let end = offset.saturating_add(max_size as u64);
+        let mut content = Vec::new();
+        let mut has_more = false;
+        let mut reported = 0u64;
+
+        loop {
+            if let Some(ref mut cancel_rx) = cancel_rx {
+                if cancel_rx.try_recv().is_ok() {
+                    return Err("download.cancelled".to_string());
+                }
+            }
+
+            // 块边界：距离最后一个检查点足够远时补充检查点
+            let last_indexed = self.checkpoints.last().map(|c| c.out_offset).unwrap_or(0);
+            if !inflater.finished
+                && inflater.at_block_boundary()
+                && inflater.out_offset >= last_indexed + CHECKPOINT_SPAN
+            {
+                self.checkpoints.push(inflater.checkpoint());
+                self.dirty = true;
+            }
+
+            if !inflater.next_block()? {
+                if self.total_size != Some(inflater.out_offset) {
+                    self.total_size = Some(inflater.out_offset);
+                    self.dirty = true;
+                }
+                break;
+            }
+
+            let output = inflater.block_output();
+            let block_end = inflater.out_offset;
+            let block_start = block_end - output.len() as u64;
+            if block_end > offset && block_start < end {
+                let from = offset.saturating_sub(block_start) as usize;
+                let to = (end.min(block_end) - block_start) as usize;
+                content.extend_from_slice(&output[from..to]);
+            }
+            if block_end > end {
+                has_more = true;
+                break;
+            }
+
+            if let Some(callback) = progress_callback {
+                let position = inflater.bits.position() / 8;
+                if position >= reported + PROGRESS_INTERVAL {
+                    reported = position;
+                    callback(position, file_size);
+                }
+            }
+        }
+
+        if let Some(callback) = progress_callback {
+            callback(file_size, file_size);
+        }
+
+        // 未解压到末尾时按已解压部分的压缩比估算总大小
+        let read_end = offset + content.len() as u64;
+        let total_size = match self.total_size {
+            Some(size) => size,
+            None => {
+                let consumed = (inflater.bits.position() / 8).max(1);
+                let estimated =
+                    (file_size as f64 * inflater.out_offset as f64 / consumed as f64) as u64;
+                estimated.max(read_end + has_more as u64)
+            }
+        };
+
+        Ok(PreviewBuilder::new()
+            .content(content)
+            .total_size(total_size)
+            .with_truncated(has_more)
+            .build())
+    }
+
+    /// 有新检查点时写入磁盘，失败只记录日志
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let result = self.encode().and_then(|data| {
+            crate::utils::persist::write_atomic(&self.file, data).map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(()) => self.dirty = false,
+            Err(e) => log::warn!("写入 gzip 索引失败 {}: {}", self.file.display(), e),
+        }
+    }
+
+    /// 索引文件格式：魔数、版本、完整解压大小（未知时为 u64::MAX）、检查点数量，
+    /// 之后每个检查点依次为解压偏移、比特位置、压缩后的窗口长度和窗口数据，整数均为小端序
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut data = Vec::new();
+        data.extend_from_slice(INDEX_MAGIC);
+        data.extend_from_slice(&INDEX_VERSION.to_le_bytes());
+        data.extend_from_slice(&self.total_size.unwrap_or(u64::MAX).to_le_bytes());
+        data.extend_from_slice(&(self.checkpoints.len() as u32).to_le_bytes());
+
+        for checkpoint in &self.checkpoints {
+            let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+            encoder
+                .write_all(&checkpoint.window)
+                .map_err(|e| format!("Failed to compress window: {}", e))?;
+            let window = encoder
+                .finish()
+                .map_err(|e| format!("Failed to compress window: {}", e))?;
+
+            data.extend_from_slice(&checkpoint.out_offset.to_le_bytes());
+            data.extend_from_slice(&checkpoint.bit_offset.to_le_bytes());
+            data.extend_from_slice(&(window.len() as u32).to_le_bytes());
+            data.extend_from_slice(&window);
+        }
+        Ok(data)
+    }
+
+    fn decode(data: &[u8]) -> Result<(Vec<Checkpoint>, Option<u64>), String> {
+        let mut cursor = data;
+        let mut take = |len: usize| take_bytes(&mut cursor, len);
+
+        if take(4)? != INDEX_MAGIC {
+            return Err("Invalid index magic".to_string());
+        }
+        let version = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        if version != INDEX_VERSION {
+            return Err(format!("Unsupported index version: {}", version));
+        }
+        let total_size = u64::from_le_bytes(take(8)?.try_into().unwrap());
+        let count = u32::from_le_bytes(take(4)?.try_into().unwrap());
+
+        let mut checkpoints = Vec::new();
+        for _ in 0..count {
+            let out_offset = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let bit_offset = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let window_len = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+            let mut window = Vec::with_capacity(WINDOW_SIZE);
+            DeflateDecoder::new(take(window_len)?)
+                .read_to_end(&mut window)
+                .map_err(|e| format!("Failed to decompress window: {}", e))?;
+            checkpoints.push(Checkpoint {
+                out_offset,
+                bit_offset,
+                window,
+            });
+        }
+
+        let total_size = (total_size != u64::MAX).then_some(total_size);
+        Ok((checkpoints, total_size))
+    }
+}
+
+fn take_bytes<'a>(cursor: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if cursor.len() < len {
+        return Err("Truncated index".to_string());
+    }
+    let (head, rest) = cursor.split_at(len);
+    *cursor = rest;
+    Ok(head)
+}
+
+/// 按 LSB 顺序读取比特的输入缓冲
+struct BitReader<R> {
+    inner: R,
+    buffer: Vec<u8>,
+    pos: usize,
+    len: usize,
+    /// buffer[len] 对应的文件偏移
+    end_offset: u64,
+    bit_buf: u64,
+    bit_count: u32,
+    eof: bool,
+}
+
+impl<R: Read> BitReader<R> {
+    fn new(inner: R, byte_offset: u64) -> Self {
+        Self {
+            inner,
+            buffer: vec![0u8; 64 * 1024],
+            pos: 0,
+            len: 0,
+            end_offset: byte_offset,
+            bit_buf: 0,
+            bit_count: 0,
+            eof: false,
+        }
+    }
+
+    /// 下一个未读比特在文件中的比特位置
+    fn position(&self) -> u64 {
+        (self.end_offset - (self.len - self.pos) as u64) * 8 - self.bit_count as u64
+    }
+
+    fn refill(&mut self) -> Result<(), String> {
+        while self.bit_count <= 56 {
+            if self.pos == self.len {
+                if self.eof {
+                    break;
+                }
+                let n = self
+                    .inner
+                    .read(&mut self.buffer)
+                    .map_err(|e| format!("Failed to read GZIP data: {}", e))?;
+                if n == 0 {
+                    self.eof = true;
+                    break;
+                }
+                self.pos = 0;
+                self.len = n;
+                self.end_offset += n as u64;
+            }
+            self.bit_buf |= (self.buffer[self.pos] as u64) << self.bit_count;
+            self.pos += 1;
+            self.bit_count += 8;
+        }
+        Ok(())
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        if count == 0 {
+            return Ok(0);
+        }
+        if self.bit_count < count {
+            self.refill()?;
+            if self.bit_count < count {
+                return Err("Unexpected end of compressed data".to_string());
+            }
+        }
+        let value = (self.bit_buf & ((1u64 << count) - 1)) as u32;
+        self.bit_buf >>= count;
+        self.bit_count -= count;
+        Ok(value)
+    }
+
+    /// 已到达输入末尾时返回 None
+    fn try_byte(&mut self) -> Result<Option<u8>, String> {
+        if self.bit_count < 8 {
+            self.refill()?;
+            if self.bit_count < 8 {
+                return Ok(None);
+            }
+        }
+        self.bits(8).map(|b| Some(b as u8))
+    }
+
+    fn align_to_byte(&mut self) {
+        let skip = self.bit_count % 8;
+        self.bit_buf >>= skip;
+        self.bit_count -= skip;
+    }
+
+    fn decode(&mut self, table: &Huffman) -> Result<u16, String> {
+        self.refill()?;
+        let entry = table.fast[(self.bit_buf & FAST_MASK) as usize];
+        let len = (entry >> 9) as u32;
+        if entry != 0 && len <= self.bit_count {
+            self.bit_buf >>= len;
+            self.bit_count -= len;
+            return Ok(entry & 0x1ff);
+        }
+
+        // 长码字逐比特解码
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for len in 1..=MAX_BITS {
+            code |= self.bits(1)? as i32;
+            let count = table.counts[len] as i32;
+            if code - first < count {
+                return Ok(table.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("Invalid deflate Huffman code".to_string())
+    }
+}
+
+const MAX_BITS: usize = 15;
+const FAST_BITS: usize = 9;
+const FAST_MASK: u64 = (1 << FAST_BITS) - 1;
+
+/// 规范 Huffman 解码表，短码字直接查表
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+    /// 以接下来 FAST_BITS 个比特为下标，值为 (码长 << 9) | 符号，0 表示码字更长
+    fast: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err("Invalid deflate Huffman table".to_string());
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        let mut next_code = [0u32; MAX_BITS + 1];
+        let mut code = 0u32;
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+            code = (code + counts[len - 1] as u32) << 1;
+            next_code[len] = code;
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        let mut fast = vec![0u16; 1 << FAST_BITS];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            let len = len as usize;
+            if len == 0 {
+                continue;
+            }
+            symbols[offsets[len] as usize] = symbol as u16;
+            offsets[len] += 1;
+
+            let code = next_code[len];
+            next_code[len] += 1;
+            if len <= FAST_BITS {
+                // deflate 按 LSB 顺序存放码字，查表下标需要反转比特
+                let mut index = (code.reverse_bits() >> (32 - len)) as usize;
+                let entry = ((len as u16) << 9) | symbol as u16;
+                while index < fast.len() {
+                    fast[index] = entry;
+                    index += 1 << len;
+                }
+            }
+        }
+
+        Ok(Self {
+            counts,
+            symbols,
+            fast,
+        })
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// 动态 Huffman 块中码长的存放顺序
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+static FIXED_TABLES: LazyLock<(Huffman, Huffman)> = LazyLock::new(|| {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    let literal = Huffman::new(&lengths).expect("fixed literal table");
+    let distance = Huffman::new(&[5u8; 30]).expect("fixed distance table");
+    (literal, distance)
+});
+
+/// 解压中的 deflate 块
+enum Block {
+    Stored,
+    Fixed,
+    Dynamic(Huffman, Huffman),
+}
+
+/// 逐块解压 gzip（可包含多个成员），每次最多输出 MAX_BLOCK_OUTPUT 字节，块边界可记录检查点
+struct Inflater<R> {
+    bits: BitReader<R>,
+    /// 回溯窗口加上最近一次解压的输出
+    history: Vec<u8>,
+    /// history 中最近一次解压输出的起始位置
+    block_start: usize,
+    /// 已解压数据的末尾偏移
+    out_offset: u64,
+    /// 尚未解压完的块及其是否为成员的最后一个块，为空时位于块边界
+    block: Option<(Block, bool)>,
+    finished: bool,
+}
+
+impl<R: Read + Seek> Inflater<R> {
+    /// 从文件开头（reader 已位于偏移 0）解析 gzip 头部，定位到第一个 deflate 块
+    fn at_stream_start(reader: R) -> Result<Self, String> {
+        let mut inflater = Self {
+            bits: BitReader::new(reader, 0),
+            history: Vec::new(),
+            block_start: 0,
+            out_offset: 0,
+            block: None,
+            finished: false,
+        };
+        if !inflater.member_header(true)? {
+            return Err("Invalid GZIP header".to_string());
+        }
+        Ok(inflater)
+    }
+
+    fn at_checkpoint(mut reader: R, checkpoint: &Checkpoint) -> Result<Self, String> {
+        let byte_offset = checkpoint.bit_offset / 8;
+        reader
+            .seek(SeekFrom::Start(byte_offset))
+            .map_err(|e| format!("Failed to read GZIP data: {}", e))?;
+        let mut bits = BitReader::new(reader, byte_offset);
+        bits.bits((checkpoint.bit_offset % 8) as u32)?;
+
+        Ok(Self {
+            bits,
+            history: checkpoint.window.clone(),
+            block_start: checkpoint.window.len(),
+            out_offset: checkpoint.out_offset,
+            block: None,
+            finished: false,
+        })
+    }
+
+    /// 是否位于块边界，只有块边界处可以记录检查点
+    fn at_block_boundary(&self) -> bool {
+        self.block.is_none()
+    }
+
+    /// 当前块边界处的检查点
+    fn checkpoint(&self) -> Checkpoint {
+        let window_start = self.history.len().saturating_sub(WINDOW_SIZE);
+        Checkpoint {
+            out_offset: self.out_offset,
+            bit_offset: self.bits.position(),
+            window: self.history[window_start..].to_vec(),
+        }
+    }
+
+    /// 最近一次 next_block 解压出的数据，最多 MAX_BLOCK_OUTPUT 字节
+    fn block_output(&self) -> &[u8] {
+        &self.history[self.block_start..]
+    }
+}
+
+impl<R: Read> Inflater<R> {
+    /// 继续解压当前 deflate 块或解压下一个块，输出达到 MAX_BLOCK_OUTPUT 时暂停在块内，
+    /// 流已结束时返回 false
+    fn next_block(&mut self) -> Result<bool, String> {
+        if self.finished {
+            return Ok(false);
+        }
+        // 只保留回溯窗口，丢弃更早的输出
+        let excess = self.history.len().saturating_sub(WINDOW_SIZE);
+        self.history.drain(..excess);
+        self.block_start = self.history.len();
+
+        let (block, last) = match self.block.take() {
+            Some(block) => block,
+            None => {
+                let last = self.bits.bits(1)? == 1;
+                let block = match self.bits.bits(2)? {
+                    0 => Block::Stored,
+                    1 => Block::Fixed,
+                    2 => {
+                        let (literal, distance) = self.dynamic_tables()?;
+                        Block::Dynamic(literal, distance)
+                    }
+                    _ => return Err("Invalid deflate block type".to_string()),
+                };
+                (block, last)
+            }
+        };
+        let block_done = match &block {
+            // 存储块最长 64KB，一次读完
+            Block::Stored => {
+                self.stored_block()?;
+                true
+            }
+            Block::Fixed => {
+                let (literal, distance) = &*FIXED_TABLES;
+                self.compressed_block(literal, distance)?
+            }
+            Block::Dynamic(literal, distance) => self.compressed_block(literal, distance)?,
+        };
+        self.out_offset += (self.history.len() - self.block_start) as u64;
+
+        if !block_done {
+            self.block = Some((block, last));
+            return Ok(true);
+        }
+        if last {
+            // 跳过 CRC32 和 ISIZE，继续下一个成员
+            self.bits.align_to_byte();
+            for _ in 0..8 {
+                if self.bits.try_byte()?.is_none() {
+                    return Err("Unexpected end of compressed data".to_string());
+                }
+            }
+            if !self.member_header(false)? {
+                self.finished = true;
+            }
+        }
+        Ok(true)
+    }
+
+    /// 解析 gzip 成员头部；非首个成员处没有 gzip 魔数时视为流结束
+    fn member_header(&mut self, first: bool) -> Result<bool, String> {
+        let magic = [self.bits.try_byte()?, self.bits.try_byte()?];
+        if magic != [Some(0x1f), Some(0x8b)] {
+            return if first {
+                Err("Invalid GZIP header".to_string())
+            } else {
+                Ok(false)
+            };
+        }
+
+        let method = self.bits.bits(8)?;
+        if method != 8 {
+            return Err(format!("Unsupported GZIP compression method: {}", method));
+        }
+        let flags = self.bits.bits(8)?;
+        // MTIME、XFL、OS
+        for _ in 0..6 {
+            self.bits.bits(8)?;
+        }
+        if flags & 0x04 != 0 {
+            let extra_len = self.bits.bits(16)?;
+            for _ in 0..extra_len {
+                self.bits.bits(8)?;
+            }
+        }
+        // FNAME、FCOMMENT 以 0 结尾
+        for flag in [0x08, 0x10] {
+            if flags & flag != 0 {
+                while self.bits.bits(8)? != 0 {}
+            }
+        }
+        if flags & 0x02 != 0 {
+            self.bits.bits(16)?;
+        }
+        Ok(true)
+    }
+
+    fn stored_block(&mut self) -> Result<(), String> {
+        self.bits.align_to_byte();
+        let len = self.bits.bits(16)?;
+        let nlen = self.bits.bits(16)?;
+        if len != !nlen & 0xffff {
+            return Err("Invalid stored block length".to_string());
+        }
+        self.history.reserve(len as usize);
+        for _ in 0..len {
+            let byte = self.bits.bits(8)? as u8;
+            self.history.push(byte);
+        }
+        Ok(())
+    }
+
+    fn dynamic_tables(&mut self) -> Result<(Huffman, Huffman), String> {
+        let literal_count = self.bits.bits(5)? as usize + 257;
+        let distance_count = self.bits.bits(5)? as usize + 1;
+        let code_length_count = self.bits.bits(4)? as usize + 4;
+        if literal_count > 286 || distance_count > 30 {
+            return Err("Invalid dynamic block header".to_string());
+        }
+
+        let mut code_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+            code_lengths[index] = self.bits.bits(3)? as u8;
+        }
+        let code_length_table = Huffman::new(&code_lengths)?;
+
+        let total = literal_count + distance_count;
+        let mut lengths = [0u8; 286 + 30];
+        let mut index = 0;
+        while index < total {
+            let symbol = self.bits.decode(&code_length_table)?;
+            if symbol < 16 {
+                lengths[index] = symbol as u8;
+                index += 1;
+                continue;
+            }
+
+            let (value, repeat) = match symbol {
+                16 => {
+                    if index == 0 {
+                        return Err("Invalid code length repeat".to_string());
+                    }
+                    (lengths[index - 1], 3 + self.bits.bits(2)? as usize)
+                }
+                17 => (0, 3 + self.bits.bits(3)? as usize),
+                _ => (0, 11 + self.bits.bits(7)? as usize),
+            };
+            if index + repeat > total {
+                return Err("Invalid code length repeat".to_string());
+            }
+            lengths[index..index + repeat].fill(value);
+            index += repeat;
+        }
+
+        if lengths[256] == 0 {
+            return Err("Missing end-of-block code".to_string());
+        }
+        let literal = Huffman::new(&lengths[..literal_count])?;
+        let distance = Huffman::new(&lengths[literal_count..total])?;
+        Ok((literal, distance))
+    }
+
+    /// 解压压缩块直到块结束（返回 true）或本次输出达到 MAX_BLOCK_OUTPUT（返回 false）
+    fn compressed_block(&mut self, literal: &Huffman, distance: &Huffman) -> Result<bool, String> {
+        loop {
+            if self.history.len() - self.block_start >= MAX_BLOCK_OUTPUT {
+                return Ok(false);
+            }
+            let symbol = self.bits.decode(literal)? as usize;
+            if symbol < 256 {
+                self.history.push(symbol as u8);
+                continue;
+            }
+            if symbol == 256 {
+                return Ok(true);
+            }
+
+            let symbol = symbol - 257;
+            if symbol >= LENGTH_BASE.len() {
+                return Err("Invalid deflate length code".to_string());
+            }
+            let length = LENGTH_BASE[symbol] as usize
+                + self.bits.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+            let symbol = self.bits.decode(distance)? as usize;
+            if symbol >= DIST_BASE.len() {
+                return Err("Invalid deflate distance code".to_string());
+            }
+            let dist =
+                DIST_BASE[symbol] as usize + self.bits.bits(DIST_EXTRA[symbol] as u32)? as usize;
+            if dist > self.history.len() {
+                return Err("Invalid deflate distance".to_string());
+            }
+
+            let start = self.history.len() - dist;
+            if dist >= length {
+                self.history.extend_from_within(start..start + length);
+            } else {
+                // 重叠复制需要逐字节进行
+                for i in 0..length {
+                    let byte = self.history[start + i];
+                    self.history.push(byte);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::MultiGzDecoder;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Cursor;
+
+    /// 可复现的伪随机数据，混合随机字节和重复片段，覆盖字面量和各种长度、距离的回溯
+    fn sample_data(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as usize
+        };
+
+        let mut data = Vec::with_capacity(len);
+        while data.len() < len {
+            if data.len() > 16 && next() % 3 != 0 {
+                let dist = 1 + next() % data.len().min(WINDOW_SIZE);
+                let length = (3 + next() % 256).min(len - data.len());
+                let start = data.len() - dist;
+                for i in 0..length {
+                    data.push(data[start + i]);
+                }
+            } else {
+                for _ in 0..(1 + next() % 64).min(len - data.len()) {
+                    data.push(next() as u8);
+                }
+            }
+        }
+        data
+    }
+
+    fn gzip(data: &[u8], level: u32) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level));
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn flate2_decompress(compressed: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        MultiGzDecoder::new(compressed)
+            .read_to_end(&mut output)
+            .unwrap();
+        output
+    }
+
+    fn inflate_all(compressed: &[u8]) -> Vec<u8> {
+        let mut inflater = Inflater::at_stream_start(Cursor::new(compressed)).unwrap();
+        let mut output = Vec::new();
+        while inflater.next_block().unwrap() {
+            // 单次输出不超过上限（回溯复制最多超出一个匹配长度）
+            assert!(inflater.block_output().len() < MAX_BLOCK_OUTPUT + 258);
+            output.extend_from_slice(inflater.block_output());
+        }
+        assert_eq!(inflater.out_offset, output.len() as u64);
+        output
+    }
+
+    fn empty_index() -> GzipSeekIndex {
+        GzipSeekIndex {
+            checkpoints: Vec::new(),
+            total_size: None,
+            file: PathBuf::new(),
+            dirty: false,
+        }
+    }
+
+    #[test]
+    fn matches_flate2_on_random_inputs() {
+        for (seed, len) in [(1, 0), (2, 1), (3, 100), (4, 70_000), (5, 1_000_000)] {
+            let data = sample_data(seed, len);
+            for level in [0, 1, 6, 9] {
+                let compressed = gzip(&data, level);
+                let output = inflate_all(&compressed);
+                assert_eq!(output, flate2_decompress(&compressed));
+                assert_eq!(output, data, "seed {} level {}", seed, level);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_flate2_on_multi_member_inputs() {
+        let mut compressed = Vec::new();
+        let mut data = Vec::new();
+        for (seed, len, level) in [
+            (10, 100_000, 6),
+            (11, 150_000, 0),
+            (12, 0, 9),
+            (13, 50_000, 1),
+        ] {
+            let member = sample_data(seed, len);
+            compressed.extend_from_slice(&gzip(&member, level));
+            data.extend_from_slice(&member);
+        }
+
+        let output = inflate_all(&compressed);
+        assert_eq!(output, flate2_decompress(&compressed));
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn splits_highly_compressible_blocks() {
+        // 全零数据压缩比极高，单个 deflate 块的输出远超 MAX_BLOCK_OUTPUT
+        let data = vec![0u8; 16 * MAX_BLOCK_OUTPUT];
+        let compressed = gzip(&data, 9);
+        assert_eq!(inflate_all(&compressed), data);
+
+        let mut index = empty_index();
+        let offset = 9 * MAX_BLOCK_OUTPUT as u64 + 3;
+        let preview = index
+            .read_at(
+                Cursor::new(&compressed),
+                compressed.len() as u64,
+                offset,
+                4096,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            preview.content,
+            &data[offset as usize..offset as usize + 4096]
+        );
+        assert!(preview.is_truncated);
+    }
+
+    #[test]
+    fn resumes_from_checkpoints() {
+        let data = sample_data(20, 3 * CHECKPOINT_SPAN as usize);
+        let mut compressed = gzip(&data[..CHECKPOINT_SPAN as usize * 2], 6);
+        compressed.extend_from_slice(&gzip(&data[CHECKPOINT_SPAN as usize * 2..], 6));
+        let file_size = compressed.len() as u64;
+
+        let mut index = empty_index();
+        let full = index
+            .read_at(
+                Cursor::new(&compressed),
+                file_size,
+                0,
+                data.len(),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(full.content, data);
+        assert!(!full.is_truncated);
+        assert!(index.checkpoints.len() > 1);
+        assert_eq!(index.total_size, Some(data.len() as u64));
+
+        // 编码后重新加载的检查点与原检查点一致
+        let (checkpoints, total_size) = GzipSeekIndex::decode(&index.encode().unwrap()).unwrap();
+        assert_eq!(total_size, index.total_size);
+        let mut reloaded = GzipSeekIndex {
+            checkpoints,
+            ..empty_index()
+        };
+
+        for offset in [
+            1,
+            CHECKPOINT_SPAN + 12_345,
+            CHECKPOINT_SPAN * 2 + 7,
+            data.len() as u64 - 10,
+        ] {
+            let preview = reloaded
+                .read_at(
+                    Cursor::new(&compressed),
+                    file_size,
+                    offset,
+                    4096,
+                    None,
+                    None,
+                )
+                .unwrap();
+            let start = offset as usize;
+            let end = (start + 4096).min(data.len());
+            assert_eq!(preview.content, &data[start..end], "offset {}", offset);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::archive::entry_stream::EntryStream;
+use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
+/// LZ4 帧格式处理器
+/// 通过 StorageReader 按需读取压缩数据并流式解压，适用于 lz4 压缩的日志、JSONL 等单文件
+use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+/// LZ4 帧魔数
+const LZ4_MAGIC: [u8; 4] = [0x04, 0x22, 0x4d, 0x18];
+
+/// FLG 字节中表示帧头包含原始大小的标志位
+const CONTENT_SIZE_FLAG: u8 = 0x08;
+
+pub struct Lz4Handler;
+
+#[async_trait::async_trait]
+impl CompressionHandlerDispatcher for Lz4Handler {
+    async fn analyze_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        _max_size: Option<u32>,
+    ) -> Result<ArchiveInfo, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        let entry_name = stream_entry_name(filename, &[".lz4"]);
+        tokio::task::spawn_blocking(move || Self::analyze(reader, file_size, entry_name))
+            .await
+            .map_err(|e| format!("LZ4 analysis task failed: {}", e))?
+    }
+
+    async fn extract_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let mut reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        tokio::task::spawn_blocking(move || {
+            let content_size = Self::frame_content_size(&mut reader)?;
+            read_stream_preview(
+                Self::decoder(reader)?,
+                offset.unwrap_or(0),
+                max_size,
+                content_size,
+                progress_callback,
+            )
+        })
+        .await
+        .map_err(|e| format!("LZ4 extraction task failed: {}", e))?
+    }
+
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let mut reader = open_storage_reader(client, file_path).await?;
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            if let Some(size) = Self::frame_content_size(&mut reader)? {
+                writer.set_size(size);
+            }
+            writer.copy_from(&mut Self::decoder(reader)?)
+        })))
+    }
+
+    fn compression_type(&self) -> CompressionType {
+        CompressionType::Lz4
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        CompressionType::from_content(data) == CompressionType::Lz4
+    }
+}
+
+impl Lz4Handler {
+    fn decoder<R: Read>(reader: R) -> Result<lz4::Decoder<R>, String> {
+        lz4::Decoder::new(reader).map_err(|e| format!("Failed to create lz4 decoder: {}", e))
+    }
+
+    /// 读取帧头中可选的原始大小字段（FLG 之后、BD 之后的 8 字节小端整数），读取后回到文件开头
+    fn frame_content_size(reader: &mut StorageReader) -> Result<Option<u64>, String> {
+        let mut header = [0u8; 14];
+        let read = reader
+            .read(&mut header)
+            .map_err(|e| format!("Failed to read LZ4 header: {}", e))?;
+        reader
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| format!("Failed to seek LZ4 data: {}", e))?;
+
+        let header = &header[..read];
+        if !header.starts_with(&LZ4_MAGIC) {
+            return Err("Invalid LZ4 frame header".to_string());
+        }
+        if header.len() < 14 || header[4] & CONTENT_SIZE_FLAG == 0 {
+            return Ok(None);
+        }
+
+        let mut size = [0u8; 8];
+        size.copy_from_slice(&header[6..14]);
+        Ok(Some(u64::from_le_bytes(size)))
+    }
+
+    /// 优先使用帧头记录的原始大小，否则按样本压缩比估算
+    fn analyze(
+        mut reader: StorageReader,
+        file_size: u64,
+        entry_name: String,
+    ) -> Result<ArchiveInfo, String> {
+        let uncompressed_size = match Self::frame_content_size(&mut reader)? {
+            Some(size) => size,
+            None => estimate_stream_size(reader, file_size, Self::decoder)?,
+        };
+
+        let entry = ArchiveEntry {
+            path: entry_name,
+            size: uncompressed_size.to_string(),
+            compressed_size: Some(file_size.to_string()),
+            is_dir: false,
+            modified_time: None,
+            crc32: None,
+            encrypted: false,
+            index: 0,
+            metadata: HashMap::new(),
+        };
+
+        Ok(ArchiveInfoBuilder::new(CompressionType::Lz4)
+            .entries(vec![entry])
+            .total_uncompressed_size(uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(false)
+            .analysis_status(AnalysisStatus::Complete)
+            .build())
+    }
+}
@@ -1,15 +1,25 @@
+pub mod brotli;
 pub mod common;
 pub mod gzip;
+pub mod gzip_index;
+pub mod lz4;
+pub mod rar;
+pub mod seven_zip;
 pub mod tar;
 pub mod tar_gz;
+pub mod tar_zst;
 /// 压缩格式处理模块
 ///
 /// 此模块将不同压缩格式的处理逻辑分离到独立的子模块中，
 /// 提供统一的接口和共享的工具函数。
 pub mod zip;
+pub mod zstd;
 
+use crate::archive::entry_stream::{EntryScan, EntryStream};
 use crate::archive::types::*;
 use crate::storage::traits::StorageClient;
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 
 /// 处理器分发接口（统一的流式压缩文件处理）
@@ -36,8 +46,72 @@ pub trait CompressionHandlerDispatcher: Send + Sync {
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String>;
 
+    /// 使用密码提取加密条目的预览；默认实现：该格式不支持加密条目，忽略密码按普通条目提取
+    #[allow(clippy::too_many_arguments)]
+    async fn extract_encrypted_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        _password: &str,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        self.extract_preview_with_client(
+            client,
+            file_path,
+            entry_path,
+            max_size,
+            offset,
+            progress_callback,
+            cancel_rx,
+        )
+        .await
+    }
+
+    /// 通过存储客户端将所有条目解压到本地目录（支持进度回调和取消信号）
+    /// 进度回调参数为（已解压条目数, 已写入字节数）；默认实现：该格式不支持完整解压
+    async fn extract_all_with_client(
+        &self,
+        _client: Arc<dyn StorageClient>,
+        _file_path: &str,
+        _target_dir: &Path,
+        _progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        _cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<ExtractSummary, String> {
+        Err(format!(
+            "Extracting all entries is not supported for {:?} archives",
+            self.compression_type()
+        ))
+    }
+
+    /// 打开条目内容的顺序数据流，一次解压读完整个条目
+    /// 默认返回 None：该格式可以按偏移直接读取，调用方按块读取预览
+    async fn open_entry_stream_with_client(
+        &self,
+        _client: Arc<dyn StorageClient>,
+        _file_path: &str,
+        _entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        Ok(None)
+    }
+
+    /// 在一次遍历中读取多个条目的内容，每个条目最多读取 max_size 字节
+    /// 默认返回 None：该格式可以直接定位条目，调用方逐个读取条目预览
+    async fn scan_entries_with_client(
+        &self,
+        _client: Arc<dyn StorageClient>,
+        _file_path: &str,
+        _entry_paths: HashSet<String>,
+        _max_size: u64,
+    ) -> Result<Option<EntryScan>, String> {
+        Ok(None)
+    }
+
     /// 获取压缩类型
-    #[allow(dead_code)] // API 保留方法，保持接口完整性
     fn compression_type(&self) -> CompressionType;
 
     /// 验证文件格式
@@ -53,11 +127,12 @@ pub fn get_handler(
         CompressionType::Gzip => Some(Box::new(gzip::GzipHandler)),
         CompressionType::Tar => Some(Box::new(tar::TarHandler)),
         CompressionType::TarGz => Some(Box::new(tar_gz::TarGzHandler)),
-        CompressionType::SevenZip => None, // 7Z 格式不支持流式处理
-        CompressionType::Rar => None,      // RAR 格式不支持流式处理
-        CompressionType::Brotli => None,   // Brotli 格式暂不支持
-        CompressionType::Lz4 => None,      // LZ4 格式暂不支持
-        CompressionType::Zstd => None,     // Zstd 格式暂不支持
+        CompressionType::TarZst => Some(Box::new(tar_zst::TarZstHandler)),
+        CompressionType::SevenZip => Some(Box::new(seven_zip::SevenZipHandler)),
+        CompressionType::Rar => Some(Box::new(rar::RarHandler)),
+        CompressionType::Brotli => Some(Box::new(brotli::BrotliHandler)),
+        CompressionType::Lz4 => Some(Box::new(lz4::Lz4Handler)),
+        CompressionType::Zstd => Some(Box::new(zstd::ZstdHandler)),
         CompressionType::Unknown => None,
     }
 }
@@ -67,8 +142,13 @@ pub fn detect_format_and_get_handler(data: &[u8]) -> Option<Box<dyn CompressionH
     let handlers: Vec<Box<dyn CompressionHandlerDispatcher>> = vec![
         Box::new(zip::ZipHandler),
         Box::new(gzip::GzipHandler),
-        Box::new(tar_gz::TarGzHandler), // TAR.GZ 需要在 TAR 之前检查
+        Box::new(tar_gz::TarGzHandler),   // TAR.GZ 需要在 TAR 之前检查
+        Box::new(tar_zst::TarZstHandler), // TAR.ZST 需要在 ZSTD 之前检查
+        Box::new(zstd::ZstdHandler),
+        Box::new(lz4::Lz4Handler),
         Box::new(tar::TarHandler),
+        Box::new(rar::RarHandler),
+        Box::new(seven_zip::SevenZipHandler),
     ];
 
     handlers
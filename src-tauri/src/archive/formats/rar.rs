@@ -1,59 +1,92 @@
 use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
 /// RAR 格式处理器
+/// RAR 的目录信息分散在各个块头中，且 unrar 只能处理本地文件：
+/// 本地存储直接打开原文件；其他存储按区间读取逐个块头，只下载块头（列出条目）或块头加单个条目的数据（非固实压缩包的预览），
+/// 区间之间以空洞填充后交给 unrar；块头加密、固实压缩包或块头过多时才下载完整的压缩包
 use crate::archive::types::*;
+use crate::storage::local_copy::{local_copy, range_copy, sampled_copy};
+use crate::storage::manager::current_connection_key;
+use crate::storage::traits::{ProgressCallback, StorageClient};
 use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
 use unrar::Archive as RarArchive;
 
+const RAR4_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x00";
+const RAR5_SIGNATURE: &[u8] = b"Rar!\x1a\x07\x01\x00";
+
+/// 读取块头时每次请求的大小，小条目的块头通常落在同一次读取中
+const HEADER_READ_SIZE: u64 = 64 * 1024; // 64KB
+
+/// 按区间读取块头的请求次数上限，超出后改为下载完整的压缩包
+const MAX_HEADER_READS: usize = 1024;
+
+/// 最近一次扫描的块布局（连接标识 + 路径 + 大小，布局），列出条目后预览同一压缩包时复用
+static LAST_LAYOUT: LazyLock<Mutex<Option<(String, Option<Arc<RarLayout>>)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
 pub struct RarHandler;
 
+/// 压缩包中各个块的位置
+struct RarLayout {
+    /// 签名和所有块头所在的区间
+    header_ranges: Vec<Range<u64>>,
+    /// 文件块的数据区间，顺序与 unrar 列出的条目一致
+    file_data: Vec<Range<u64>>,
+    /// 固实压缩包的条目需要从头连续解压，无法只下载单个条目的数据
+    solid: bool,
+}
+
 #[async_trait::async_trait]
 impl CompressionHandlerDispatcher for RarHandler {
-    async fn analyze_complete(&self, data: &[u8]) -> Result<ArchiveInfo, String> {
-        Self::analyze_rar_complete(data)
-    }
-
-    async fn analyze_streaming(
-        &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
-    ) -> Result<ArchiveInfo, String> {
-        // RAR 格式不支持流式分析，需要下载完整文件
-        Self::analyze_rar_by_download(url, headers, filename, file_size).await
-    }
-
-    async fn analyze_streaming_without_size(
+    async fn analyze_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<u32>,
     ) -> Result<ArchiveInfo, String> {
-        // RAR 格式不支持流式分析，需要下载完整文件
-        Self::analyze_rar_by_download_no_size(url, headers, filename).await
+        let archive_path = match client.local_path(file_path) {
+            Some(local) => local,
+            None => Self::header_copy(client, file_path).await?,
+        };
+
+        tokio::task::spawn_blocking(move || Self::list_entries(&archive_path))
+            .await
+            .map_err(|e| format!("RAR analysis task failed: {}", e))?
     }
 
-    async fn extract_preview(
+    async fn extract_preview_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
         entry_path: &str,
         max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
-        Self::extract_rar_preview(url, headers, entry_path, max_size).await
-    }
+        let offset = offset.unwrap_or(0);
+        if let Some(local) = client.local_path(file_path) {
+            return Self::extract_blocking(local, entry_path, max_size, offset).await;
+        }
 
-    async fn extract_preview_with_client(
-        &self,
-        _client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
-        _file_path: &str,
-        _entry_path: &str,
-        _max_size: usize,
-        _offset: Option<u64>,
-        _progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
-        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
-    ) -> Result<FilePreview, String> {
-        Err("RAR format does not support client-based extraction yet".to_string())
+        if let Some(entry_copy) = Self::entry_copy(client.clone(), file_path, entry_path).await? {
+            let result =
+                Self::extract_blocking(entry_copy.clone(), entry_path, max_size, offset).await;
+            let _ = tokio::fs::remove_file(&entry_copy).await;
+            return result;
+        }
+
+        let progress_cb = progress_callback.map(|cb| {
+            Arc::new(move |current: u64, total: u64| {
+                cb(current, total);
+            }) as ProgressCallback
+        });
+        let archive_path = local_copy(client, file_path, "rar", progress_cb, cancel_rx).await?;
+        Self::extract_blocking(archive_path, entry_path, max_size, offset).await
     }
 
     fn compression_type(&self) -> CompressionType {
@@ -61,218 +94,481 @@ impl CompressionHandlerDispatcher for RarHandler {
     }
 
     fn validate_format(&self, data: &[u8]) -> bool {
-        Self::validate_rar_header(data)
+        CompressionType::from_content(data) == CompressionType::Rar
     }
 }
 
 impl RarHandler {
-    /// 完整RAR文件分析
-    fn analyze_rar_complete(data: &[u8]) -> Result<ArchiveInfo, String> {
-        println!("开始分析RAR文件，数据长度: {} 字节", data.len());
+    /// 只包含块头的本地副本，用于列出条目；无法按区间读取块头时下载完整的压缩包
+    async fn header_copy(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+    ) -> Result<PathBuf, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        match Self::layout(&*client, file_path, file_size).await? {
+            Some(layout) => Ok(sampled_copy(
+                client,
+                file_path,
+                "rar",
+                file_size,
+                layout.header_ranges.clone(),
+            )
+            .await?
+            .path),
+            None => local_copy(client, file_path, "rar", None, None).await,
+        }
+    }
 
-        if !Self::validate_rar_header(data) {
-            return Err("Invalid RAR header".to_string());
+    /// 只包含块头和指定条目数据的临时副本，用完后由调用方删除；
+    /// 固实压缩包或无法按区间读取块头时返回 None
+    async fn entry_copy(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+    ) -> Result<Option<PathBuf>, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let Some(layout) = Self::layout(&*client, file_path, file_size).await? else {
+            return Ok(None);
+        };
+        if layout.solid {
+            return Ok(None);
         }
 
-        // 创建临时文件，因为unrar库需要文件路径
-        let temp_path = format!("/tmp/temp_rar_{}.rar", uuid::Uuid::new_v4());
-        std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+        // 条目在块头中的序号需要由 unrar 解析文件名得到
+        let header_copy = sampled_copy(
+            client.clone(),
+            file_path,
+            "rar",
+            file_size,
+            layout.header_ranges.clone(),
+        )
+        .await?
+        .path;
+        let entry = entry_path.to_string();
+        let index = tokio::task::spawn_blocking(move || Self::find_entry(&header_copy, &entry))
+            .await
+            .map_err(|e| format!("RAR analysis task failed: {}", e))??;
+        let Some(data) = index.and_then(|index| layout.file_data.get(index)) else {
+            return Err(format!("File '{}' not found in RAR archive", entry_path));
+        };
+
+        let mut ranges = layout.header_ranges.clone();
+        ranges.push(data.clone());
+        range_copy(client, file_path, "rar", file_size, ranges)
+            .await
+            .map(Some)
+    }
+
+    /// 压缩包的块布局，复用最近一次扫描的结果
+    async fn layout(
+        client: &dyn StorageClient,
+        file_path: &str,
+        file_size: u64,
+    ) -> Result<Option<Arc<RarLayout>>, String> {
+        let key = format!(
+            "{}\0{}\0{}",
+            current_connection_key().await,
+            file_path,
+            file_size
+        );
+        if let Some((cached, layout)) = LAST_LAYOUT.lock().unwrap().as_ref() {
+            if *cached == key {
+                return Ok(layout.clone());
+            }
+        }
 
-        let result = Self::analyze_rar_file(&temp_path);
+        let layout = scan_layout(client, file_path, file_size)
+            .await?
+            .map(Arc::new);
+        if layout.is_none() {
+            log::debug!("无法按区间读取RAR块头，将下载完整文件: {}", file_path);
+        }
+        *LAST_LAYOUT.lock().unwrap() = Some((key, layout.clone()));
+        Ok(layout)
+    }
 
-        // 清理临时文件
-        let _ = std::fs::remove_file(&temp_path);
+    /// 条目在 unrar 列出的文件块中的序号
+    fn find_entry(archive_path: &Path, entry_path: &str) -> Result<Option<usize>, String> {
+        let archive = RarArchive::new(archive_path)
+            .open_for_listing()
+            .map_err(|e| format!("Failed to open RAR archive: {}", e))?;
+        for (index, header) in archive.enumerate() {
+            let header = header.map_err(|e| format!("Failed to read RAR header: {}", e))?;
+            if !header.is_directory() && entry_name(&header.filename) == entry_path {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
 
-        result
+    async fn extract_blocking(
+        archive_path: PathBuf,
+        entry_path: &str,
+        max_size: usize,
+        offset: u64,
+    ) -> Result<FilePreview, String> {
+        let entry_path = entry_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Self::extract_entry(&archive_path, &entry_path, max_size, offset)
+        })
+        .await
+        .map_err(|e| format!("RAR extraction task failed: {}", e))?
     }
 
-    /// 分析RAR文件
-    fn analyze_rar_file(file_path: &str) -> Result<ArchiveInfo, String> {
-        let archive = RarArchive::new(file_path.to_string());
+    /// 列出压缩包中的所有条目
+    fn list_entries(archive_path: &Path) -> Result<ArchiveInfo, String> {
+        let archive = RarArchive::new(archive_path)
+            .open_for_listing()
+            .map_err(|e| format!("Failed to open RAR archive: {}", e))?;
+
         let mut entries = Vec::new();
-        let mut total_uncompressed_size = 0;
-        let mut total_compressed_size = 0;
-
-        for (index, entry_result) in archive.iter().enumerate() {
-            match entry_result {
-                Ok(entry) => {
-                    let path = entry.filename.clone();
-                    let size = entry.unpacked_size;
-                    let compressed_size = entry.packed_size;
-                    let is_dir = entry.is_directory();
-
-                    total_uncompressed_size += size;
-                    total_compressed_size += compressed_size;
-
-                    // 处理修改时间
-                    let modified_time = entry.file_time.and_then(|_ft| {
-                        // DOS时间转换为Unix时间戳（简化处理）
-                        // 这里需要具体的时间转换逻辑
-                        None // 暂时返回None，可以后续完善
-                    });
-
-                    entries.push(ArchiveEntry {
-                        path,
-                        size,
-                        compressed_size: Some(compressed_size),
-                        is_dir,
-                        modified_time,
-                        crc32: Some(entry.crc),
-                        index,
-                        metadata: HashMap::new(),
-                    });
-                }
+        let mut total_uncompressed_size = 0u64;
+
+        for (index, header) in archive.enumerate() {
+            let header = match header {
+                Ok(header) => header,
                 Err(e) => {
-                    println!("Warning: Failed to read RAR entry {}: {}", index, e);
+                    log::warn!("读取RAR条目失败 {}: {}", index, e);
                     continue;
                 }
+            };
+
+            // 分卷压缩包中跨卷的文件会出现多次，只保留第一段
+            let path = entry_name(&header.filename);
+            if header.is_split() && entries.iter().any(|e: &ArchiveEntry| e.path == path) {
+                continue;
             }
+
+            total_uncompressed_size += header.unpacked_size;
+            entries.push(ArchiveEntry {
+                path,
+                size: header.unpacked_size.to_string(),
+                compressed_size: None, // unrar 不提供单个条目的压缩大小
+                is_dir: header.is_directory(),
+                modified_time: dos_time_to_rfc3339(header.file_time),
+                crc32: Some(header.file_crc),
+                encrypted: false,
+                index: entries.len() as u32,
+                metadata: HashMap::new(),
+            });
         }
 
+        let archive_size = std::fs::metadata(archive_path)
+            .map(|m| m.len())
+            .unwrap_or(0);
+
         Ok(ArchiveInfoBuilder::new(CompressionType::Rar)
             .entries(entries)
             .total_uncompressed_size(total_uncompressed_size)
-            .total_compressed_size(total_compressed_size)
+            .total_compressed_size(archive_size)
             .supports_streaming(false)
-            .supports_random_access(true)
+            .supports_random_access(false)
             .analysis_status(AnalysisStatus::Complete)
             .build())
     }
 
-    /// 通过下载完整文件分析RAR（有文件大小）
-    async fn analyze_rar_by_download(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
-    ) -> Result<ArchiveInfo, String> {
-        println!(
-            "开始下载RAR文件进行分析: {} (大小: {} 字节)",
-            filename, file_size
-        );
-
-        let data = HttpClient::download_file(url, headers).await?;
-        Self::analyze_rar_complete(&data)
-    }
-
-    /// 通过下载完整文件分析RAR（无文件大小）
-    async fn analyze_rar_by_download_no_size(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        println!("开始下载RAR文件进行分析: {}", filename);
-
-        let data = HttpClient::download_file(url, headers).await?;
-
-        Self::analyze_rar_complete(&data)
-    }
-
-    /// 提取RAR文件预览
-    async fn extract_rar_preview(
-        url: &str,
-        headers: &HashMap<String, String>,
+    /// 解压指定条目并读取预览
+    /// 条目先解压到临时文件，避免大文件整体读入内存
+    fn extract_entry(
+        archive_path: &Path,
         entry_path: &str,
         max_size: usize,
+        offset: u64,
     ) -> Result<FilePreview, String> {
-        println!("开始从RAR文件提取预览: {}", entry_path);
-
-        // 下载完整文件
-        let data = HttpClient::download_file(url, headers).await?;
+        let mut archive = RarArchive::new(archive_path)
+            .open_for_processing()
+            .map_err(|e| format!("Failed to open RAR archive: {}", e))?;
 
-        // 创建临时文件
-        let temp_path = format!("/tmp/temp_rar_{}.rar", uuid::Uuid::new_v4());
-        std::fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+        while let Some(header) = archive
+            .read_header()
+            .map_err(|e| format!("Failed to read RAR header: {}", e))?
+        {
+            let entry = header.entry();
+            if entry.is_directory() || entry_name(&entry.filename) != entry_path {
+                archive = header
+                    .skip()
+                    .map_err(|e| format!("Failed to skip RAR entry: {}", e))?;
+                continue;
+            }
 
-        let result = Self::extract_from_rar_file(&temp_path, entry_path, max_size);
+            let total_size = entry.unpacked_size;
+            let extracted = archive_path.with_file_name(format!("{}.entry", uuid::Uuid::new_v4()));
+            header
+                .extract_to(&extracted)
+                .map_err(|e| format!("Failed to extract RAR entry: {}", e))?;
 
-        // 清理临时文件
-        let _ = std::fs::remove_file(&temp_path);
+            let result = Self::read_preview(&extracted, max_size, offset, total_size);
+            let _ = std::fs::remove_file(&extracted);
+            return result;
+        }
 
-        result
+        Err(format!("File '{}' not found in RAR archive", entry_path))
     }
 
-    /// 从RAR文件提取内容
-    fn extract_from_rar_file(
-        file_path: &str,
-        entry_path: &str,
+    fn read_preview(
+        path: &Path,
         max_size: usize,
+        offset: u64,
+        total_size: u64,
     ) -> Result<FilePreview, String> {
-        let archive = RarArchive::new(file_path.to_string());
-
-        for entry_result in archive.iter() {
-            match entry_result {
-                Ok(entry) => {
-                    if entry.filename == entry_path && !entry.is_directory() {
-                        // 提取文件到内存
-                        let mut output = Vec::new();
-                        entry
-                            .extract_to_writer(&mut output)
-                            .map_err(|e| e.to_string())?;
-                        let total_size = entry.unpacked_size;
-                        let preview_size = max_size.min(output.len());
-
-                        // 限制预览大小
-                        if output.len() > preview_size {
-                            output.truncate(preview_size);
-                        }
-
-                        let content = if file_type.is_text() {
-                            match String::from_utf8(output.clone()) {
-                                Ok(text) => text,
-                                Err(_) => TextDecoder::try_decode_text(output)?,
-                            }
-                        } else {
-                            TextDecoder::format_binary_preview(output)
-                        };
-
-                        return Ok(PreviewBuilder::new()
-                            .content(content)
-                            .with_truncated(preview_size < total_size as usize)
-                            .total_size(total_size)
-                            .build());
-                    }
-                }
-                Err(e) => {
-                    println!("Warning: Failed to read RAR entry: {}", e);
-                    continue;
-                }
+        let mut file =
+            std::fs::File::open(path).map_err(|e| format!("Failed to open entry: {}", e))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("Failed to seek entry: {}", e))?;
+
+        let read_size = (max_size as u64).min(total_size.saturating_sub(offset));
+        let mut content = Vec::with_capacity(read_size as usize);
+        file.take(read_size)
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read entry: {}", e))?;
+
+        let is_truncated = offset + (content.len() as u64) < total_size;
+        Ok(PreviewBuilder::new()
+            .content(content)
+            .total_size(total_size)
+            .with_truncated(is_truncated)
+            .build())
+    }
+}
+
+/// 按区间读取块头，每次请求 HEADER_READ_SIZE 并缓存，连续的小块头只需一次请求
+struct HeaderReader<'a> {
+    client: &'a dyn StorageClient,
+    file_path: &'a str,
+    file_size: u64,
+    buffer_start: u64,
+    buffer: Vec<u8>,
+    reads: usize,
+}
+
+impl HeaderReader<'_> {
+    /// 读取 [pos, pos + length)，超出文件末尾或请求次数上限时返回 None
+    async fn read(&mut self, pos: u64, length: u64) -> Result<Option<Vec<u8>>, String> {
+        if pos + length > self.file_size {
+            return Ok(None);
+        }
+        let buffer_end = self.buffer_start + self.buffer.len() as u64;
+        if pos < self.buffer_start || pos + length > buffer_end {
+            if self.reads >= MAX_HEADER_READS {
+                return Ok(None);
+            }
+            self.reads += 1;
+            let read_size = length.max(HEADER_READ_SIZE).min(self.file_size - pos);
+            self.buffer = self
+                .client
+                .read_file_range(self.file_path, pos, read_size)
+                .await
+                .map_err(|e| format!("Failed to read RAR header: {}", e))?;
+            self.buffer_start = pos;
+            if (self.buffer.len() as u64) < length {
+                return Ok(None);
             }
         }
+        let start = (pos - self.buffer_start) as usize;
+        Ok(Some(self.buffer[start..start + length as usize].to_vec()))
+    }
+}
 
-        Err(format!("File '{}' not found in RAR archive", entry_path))
+/// 逐个读取块头得到压缩包的块布局；块头加密、格式无法识别或超出请求次数上限时返回 None
+async fn scan_layout(
+    client: &dyn StorageClient,
+    file_path: &str,
+    file_size: u64,
+) -> Result<Option<RarLayout>, String> {
+    let mut reader = HeaderReader {
+        client,
+        file_path,
+        file_size,
+        buffer_start: 0,
+        buffer: Vec::new(),
+        reads: 0,
+    };
+    let signature_size = RAR5_SIGNATURE.len() as u64;
+    let Some(signature) = reader.read(0, signature_size.min(file_size)).await? else {
+        return Ok(None);
+    };
+    if signature.starts_with(RAR5_SIGNATURE) {
+        scan_rar5(&mut reader).await
+    } else if signature.starts_with(RAR4_SIGNATURE) {
+        scan_rar4(&mut reader).await
+    } else {
+        Ok(None)
     }
+}
 
-    /// 验证RAR文件头
-    fn validate_rar_header(data: &[u8]) -> bool {
-        if data.len() < 7 {
-            return false;
+/// RAR 5.0 块：CRC32、块头大小（vint）、类型、标志、[附加区大小]、[数据区大小] ...
+async fn scan_rar5(reader: &mut HeaderReader<'_>) -> Result<Option<RarLayout>, String> {
+    let signature_size = RAR5_SIGNATURE.len() as u64;
+    let mut layout = RarLayout {
+        header_ranges: vec![0..signature_size],
+        file_data: Vec::new(),
+        solid: false,
+    };
+
+    let mut pos = signature_size;
+    while pos < reader.file_size {
+        let prefix_size = 7.min(reader.file_size - pos);
+        let Some(prefix) = reader.read(pos, prefix_size).await? else {
+            return Ok(None);
+        };
+        let mut cursor = 4;
+        // 块头大小不超过 2MB，更大的值说明数据损坏
+        let header_size = match read_vint(&prefix, &mut cursor) {
+            Some(size) if size <= 2 * 1024 * 1024 => size,
+            _ => return Ok(None),
+        };
+        let header_end = pos + cursor as u64 + header_size;
+        let Some(header) = reader.read(pos, header_end - pos).await? else {
+            return Ok(None);
+        };
+
+        let fields = (|| {
+            let header_type = read_vint(&header, &mut cursor)?;
+            let flags = read_vint(&header, &mut cursor)?;
+            if flags & 0x01 != 0 {
+                read_vint(&header, &mut cursor)?;
+            }
+            let data_size = if flags & 0x02 != 0 {
+                read_vint(&header, &mut cursor)?
+            } else {
+                0
+            };
+            // 主块头的压缩包标志：0x04 为固实压缩包
+            let archive_flags = if header_type == 1 {
+                read_vint(&header, &mut cursor)?
+            } else {
+                0
+            };
+            Some((header_type, data_size, archive_flags))
+        })();
+        let Some((header_type, data_size, archive_flags)) = fields else {
+            return Ok(None);
+        };
+
+        layout.header_ranges.push(pos..header_end);
+        match header_type {
+            1 => layout.solid = archive_flags & 0x04 != 0,
+            2 => layout.file_data.push(header_end..header_end + data_size),
+            // 块头加密时后续块头无法直接读取
+            4 => return Ok(None),
+            5 => break,
+            _ => {}
         }
+        pos = header_end + data_size;
+    }
+    Ok(Some(layout))
+}
 
-        // RAR 4.x 签名: "Rar!\x1a\x07\x00"
-        if data[0] == 0x52
-            && data[1] == 0x61
-            && data[2] == 0x72
-            && data[3] == 0x21
-            && data[4] == 0x1a
-            && data[5] == 0x07
-            && data[6] == 0x00
-        {
-            return true;
+/// RAR 1.5 - 4.x 块：CRC16、类型、标志、块头大小，带数据区的块在块头后记录数据区大小
+async fn scan_rar4(reader: &mut HeaderReader<'_>) -> Result<Option<RarLayout>, String> {
+    const MAIN_HEADER: u8 = 0x73;
+    const FILE_HEADER: u8 = 0x74;
+    const SERVICE_HEADER: u8 = 0x7a;
+    const END_HEADER: u8 = 0x7b;
+
+    let signature_size = RAR4_SIGNATURE.len() as u64;
+    let mut layout = RarLayout {
+        header_ranges: vec![0..signature_size],
+        file_data: Vec::new(),
+        solid: false,
+    };
+
+    let mut pos = signature_size;
+    while pos < reader.file_size {
+        let Some(base) = reader.read(pos, 7).await? else {
+            return Ok(None);
+        };
+        let header_type = base[2];
+        let flags = u16::from_le_bytes([base[3], base[4]]);
+        let header_size = u16::from_le_bytes([base[5], base[6]]) as u64;
+        if header_size < 7 {
+            return Ok(None);
         }
-
-        // RAR 5.x 签名: "Rar!\x1a\x07\x01"
-        if data[0] == 0x52
-            && data[1] == 0x61
-            && data[2] == 0x72
-            && data[3] == 0x21
-            && data[4] == 0x1a
-            && data[5] == 0x07
-            && data[6] == 0x01
-        {
-            return true;
+        let header_end = pos + header_size;
+        let Some(header) = reader.read(pos, header_size).await? else {
+            return Ok(None);
+        };
+
+        let le_u32 = |at: usize| {
+            header
+                .get(at..at + 4)
+                .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64)
+        };
+        let is_file = header_type == FILE_HEADER || header_type == SERVICE_HEADER;
+        let data_size = if is_file || flags & 0x8000 != 0 {
+            let Some(low) = le_u32(7) else {
+                return Ok(None);
+            };
+            // 文件块标志 0x100：大文件，高 32 位在属性字段之后
+            let high = if is_file && flags & 0x100 != 0 {
+                let Some(high) = le_u32(32) else {
+                    return Ok(None);
+                };
+                high
+            } else {
+                0
+            };
+            low | (high << 32)
+        } else {
+            0
+        };
+
+        layout.header_ranges.push(pos..header_end);
+        match header_type {
+            MAIN_HEADER => {
+                // 主块头标志：0x08 为固实压缩包，0x80 为块头加密
+                if flags & 0x80 != 0 {
+                    return Ok(None);
+                }
+                layout.solid = flags & 0x08 != 0;
+            }
+            FILE_HEADER => layout.file_data.push(header_end..header_end + data_size),
+            END_HEADER => break,
+            _ => {}
         }
+        pos = header_end + data_size;
+    }
+    Ok(Some(layout))
+}
 
-        false
+/// 读取 RAR 5.0 的变长整数（每字节低 7 位，最高位表示后面还有字节）
+fn read_vint(data: &[u8], cursor: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..70).step_by(7) {
+        let byte = *data.get(*cursor)?;
+        *cursor += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
     }
+    None
+}
+
+/// 统一使用 "/" 作为条目路径分隔符
+fn entry_name(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// DOS 日期时间（高 16 位日期，低 16 位时间）转换为 RFC3339
+fn dos_time_to_rfc3339(dos_time: u32) -> Option<String> {
+    let date = (dos_time >> 16) as u16;
+    let time = dos_time as u16;
+
+    let year = 1980 + (date >> 9) as i32;
+    let month = ((date >> 5) & 0x0f) as u32;
+    let day = (date & 0x1f) as u32;
+    let hour = (time >> 11) as u32;
+    let minute = ((time >> 5) & 0x3f) as u32;
+    let second = ((time & 0x1f) * 2) as u32;
+
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|d| d.and_hms_opt(hour, minute, second))
+        .map(|dt| dt.and_utc().to_rfc3339())
 }
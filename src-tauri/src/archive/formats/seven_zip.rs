@@ -1,59 +1,77 @@
 use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
 /// 7z 格式处理器
+/// 7z 的目录信息位于文件末尾，通过 StorageReader 按需读取文件头和尾部，列出条目时无需下载整个文件
 use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use sevenz_rust::{Password, SevenZArchiveEntry, SevenZReader};
 use std::collections::HashMap;
-use std::io::Cursor;
+use std::io::Read;
+use std::sync::Arc;
+
+/// Windows FILETIME（1601 年起的 100 纳秒数）与 Unix 纪元的差值
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
 
 pub struct SevenZipHandler;
 
 #[async_trait::async_trait]
 impl CompressionHandlerDispatcher for SevenZipHandler {
-    async fn analyze_complete(&self, data: &[u8]) -> Result<ArchiveInfo, String> {
-        Self::analyze_7z_complete(data)
-    }
-
-    async fn analyze_streaming(
+    async fn analyze_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-        file_size: u64,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<u32>,
     ) -> Result<ArchiveInfo, String> {
-        // 7z 格式不支持流式分析，需要下载完整文件
-        Self::analyze_7z_by_download(url, headers, filename, file_size).await
-    }
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
 
-    async fn analyze_streaming_without_size(
-        &self,
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        // 7z 格式不支持流式分析，需要下载完整文件
-        Self::analyze_7z_by_download_no_size(url, headers, filename).await
+        tokio::task::spawn_blocking(move || Self::list_entries(reader, file_size))
+            .await
+            .map_err(|e| format!("7z analysis task failed: {}", e))?
     }
 
-    async fn extract_preview(
+    async fn extract_preview_with_client(
         &self,
-        url: &str,
-        headers: &HashMap<String, String>,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
         entry_path: &str,
         max_size: usize,
-    ) -> Result<FilePreview, String> {
-        Self::extract_7z_preview(url, headers, entry_path, max_size).await
-    }
-
-    async fn extract_preview_with_client(
-        &self,
-        _client: std::sync::Arc<dyn crate::storage::traits::StorageClient>,
-        _file_path: &str,
-        _entry_path: &str,
-        _max_size: usize,
-        _offset: Option<u64>,
-        _progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
         _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
-        Err("7z format does not support client-based extraction yet".to_string())
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        let entry_path = entry_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Self::extract_entry(
+                reader,
+                file_size,
+                &entry_path,
+                max_size,
+                offset.unwrap_or(0),
+                progress_callback,
+            )
+        })
+        .await
+        .map_err(|e| format!("7z extraction task failed: {}", e))?
     }
 
     fn compression_type(&self) -> CompressionType {
@@ -61,158 +79,105 @@ impl CompressionHandlerDispatcher for SevenZipHandler {
     }
 
     fn validate_format(&self, data: &[u8]) -> bool {
-        Self::validate_7z_header(data)
+        CompressionType::from_content(data) == CompressionType::SevenZip
     }
 }
 
 impl SevenZipHandler {
-    /// 完整7z文件分析
-    fn analyze_7z_complete(data: &[u8]) -> Result<ArchiveInfo, String> {
-        println!("开始分析7z文件，数据长度: {} 字节", data.len());
-
-        if !Self::validate_7z_header(data) {
-            return Err("Invalid 7z header".to_string());
-        }
+    fn open(reader: StorageReader, file_size: u64) -> Result<SevenZReader<StorageReader>, String> {
+        SevenZReader::new(reader, file_size, Password::empty())
+            .map_err(|e| format!("Failed to open 7z archive: {}", e))
+    }
 
-        let cursor = Cursor::new(data);
-        let mut archive =
-            SevenZReader::new(cursor, data.len() as u64).map_err(|e| e.to_string())?;
-        let mut entries = Vec::new();
-        let mut total_uncompressed_size = 0;
-
-        for (index, entry) in archive.entries().iter().enumerate() {
-            let path = entry.name().to_string();
-            let size = entry.size();
-            let is_dir = entry.is_directory();
-            let compressed_size = Some(entry.size()); // 7z 没有单独的压缩大小概念
-
-            total_uncompressed_size += size;
-
-            // 处理修改时间
-            let modified_time = entry.last_write_time().and_then(|timestamp| {
-                use chrono::{DateTime, Utc};
-                use std::time::{Duration, UNIX_EPOCH};
-
-                // Windows FILETIME 转换为 Unix 时间戳
-                let unix_timestamp = (timestamp - 116444736000000000) / 10000000;
-                let duration = Duration::from_secs(unix_timestamp);
-                let datetime = UNIX_EPOCH + duration;
-                let datetime: DateTime<Utc> = datetime.into();
-                Some(datetime.to_rfc3339())
-            });
-
-            entries.push(ArchiveEntry {
-                path,
-                size,
-                compressed_size: Some(compressed_size),
-                is_dir,
-                modified_time,
-                crc32: entry.crc32(),
-                index,
-                metadata: HashMap::new(),
-            });
-        }
+    /// 读取末尾的目录信息并列出所有条目
+    fn list_entries(reader: StorageReader, file_size: u64) -> Result<ArchiveInfo, String> {
+        let archive = Self::open(reader, file_size)?;
+
+        let mut total_uncompressed_size = 0u64;
+        let entries: Vec<ArchiveEntry> = archive
+            .archive()
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                total_uncompressed_size += entry.size();
+                ArchiveEntry {
+                    path: entry.name().to_string(),
+                    size: entry.size().to_string(),
+                    // 固实压缩时多个文件共享数据块，没有单独的压缩大小
+                    compressed_size: (entry.compressed_size > 0)
+                        .then(|| entry.compressed_size.to_string()),
+                    is_dir: entry.is_directory(),
+                    modified_time: Self::modified_time(entry),
+                    crc32: entry.has_crc.then_some(entry.crc as u32),
+                    encrypted: false,
+                    index: index as u32,
+                    metadata: HashMap::new(),
+                }
+            })
+            .collect();
 
         Ok(ArchiveInfoBuilder::new(CompressionType::SevenZip)
             .entries(entries)
             .total_uncompressed_size(total_uncompressed_size)
-            .total_compressed_size(data.len() as u64)
+            .total_compressed_size(file_size)
             .supports_streaming(false)
-            .supports_random_access(true)
+            .supports_random_access(false)
             .analysis_status(AnalysisStatus::Complete)
             .build())
     }
 
-    /// 通过下载完整文件分析7z（有文件大小）
-    async fn analyze_7z_by_download(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
+    /// 按顺序解码直到目标条目，读取 offset 开始的 max_size 字节
+    /// 固实压缩包中目标条目之前的数据也需要解码，但不会保留在内存中
+    fn extract_entry(
+        reader: StorageReader,
         file_size: u64,
-    ) -> Result<ArchiveInfo, String> {
-        println!(
-            "开始下载7z文件进行分析: {} (大小: {} 字节)",
-            filename, file_size
-        );
-
-        let data = HttpClient::download_file(url, headers).await?;
-        Self::analyze_7z_complete(&data)
-    }
-
-    /// 通过下载完整文件分析7z（无文件大小）
-    async fn analyze_7z_by_download_no_size(
-        url: &str,
-        headers: &HashMap<String, String>,
-        filename: &str,
-    ) -> Result<ArchiveInfo, String> {
-        println!("开始下载7z文件进行分析: {}", filename);
-
-        let data = HttpClient::download_file(url, headers).await?;
-
-        Self::analyze_7z_complete(&data)
-    }
-
-    /// 提取7z文件预览
-    async fn extract_7z_preview(
-        url: &str,
-        headers: &HashMap<String, String>,
         entry_path: &str,
         max_size: usize,
+        offset: u64,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
     ) -> Result<FilePreview, String> {
-        println!("开始从7z文件提取预览: {}", entry_path);
-
-        // 下载完整文件
-        let data = HttpClient::download_file(url, headers).await?;
-        let cursor = Cursor::new(data);
-        let mut archive = SevenZArchive::read(cursor).map_err(|e| e.to_string())?;
-
-        // 查找目标文件
-        for entry in archive.entries() {
-            if entry.name() == entry_path && !entry.is_directory() {
-                let mut output = Vec::new();
-                archive
-                    .extract_to_writer(entry, &mut output)
-                    .map_err(|e| e.to_string())?;
-                let total_size = entry.size();
-                let preview_size = max_size.min(output.len());
+        let mut archive = Self::open(reader, file_size)?;
+        let mut preview: Option<FilePreview> = None;
 
-                // 限制预览大小
-                if output.len() > preview_size {
-                    output.truncate(preview_size);
+        archive
+            .for_each_entries(|entry, data| {
+                if entry.is_directory() || entry.name() != entry_path {
+                    return Ok(true);
                 }
 
-                let content = if file_type.is_text() {
-                    match String::from_utf8(output.clone()) {
-                        Ok(text) => text,
-                        Err(_) => TextDecoder::try_decode_text(output)?,
-                    }
-                } else {
-                    TextDecoder::format_binary_preview(output)
-                };
-
-                return Ok(PreviewBuilder::new()
-                    .content(content)
-                    .with_truncated(preview_size < total_size as usize)
-                    .total_size(total_size)
-                    .build());
-            }
-        }
+                let total_size = entry.size();
+                std::io::copy(&mut (&mut *data).take(offset), &mut std::io::sink())?;
+
+                let read_size = (max_size as u64).min(total_size.saturating_sub(offset));
+                let mut content = Vec::with_capacity(read_size as usize);
+                (&mut *data).take(read_size).read_to_end(&mut content)?;
+                if let Some(callback) = &progress_callback {
+                    callback(content.len() as u64, read_size);
+                }
 
-        Err(format!("File '{}' not found in 7z archive", entry_path))
+                let is_truncated = offset + (content.len() as u64) < total_size;
+                preview = Some(
+                    PreviewBuilder::new()
+                        .content(content)
+                        .total_size(total_size)
+                        .with_truncated(is_truncated)
+                        .build(),
+                );
+                Ok(false)
+            })
+            .map_err(|e| format!("Failed to extract 7z entry: {}", e))?;
+
+        preview.ok_or_else(|| format!("File '{}' not found in 7z archive", entry_path))
     }
 
-    /// 验证7z文件头
-    fn validate_7z_header(data: &[u8]) -> bool {
-        if data.len() < 6 {
-            return false;
+    fn modified_time(entry: &SevenZArchiveEntry) -> Option<String> {
+        if !entry.has_last_modified_date {
+            return None;
         }
 
-        // 7z 文件签名: "7z\xbc\xaf\x27\x1c"
-        data[0] == 0x37
-            && data[1] == 0x7a
-            && data[2] == 0xbc
-            && data[3] == 0xaf
-            && data[4] == 0x27
-            && data[5] == 0x1c
+        let filetime = u64::from(entry.last_modified_date());
+        let unix_seconds = filetime.checked_sub(FILETIME_UNIX_EPOCH)? / 10_000_000;
+        chrono::DateTime::from_timestamp(unix_seconds as i64, 0).map(|dt| dt.to_rfc3339())
     }
 }
@@ -1,8 +1,11 @@
+use crate::archive::entry_index::{ArchiveEntryIndex, EntryLocation};
+use crate::archive::entry_stream::{ChunkWriter, ScanSender};
 use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
 /// TAR 格式处理器
 use crate::archive::types::*;
 use crate::storage::traits::StorageClient;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 pub struct TarHandler;
 
@@ -40,6 +43,25 @@ impl CompressionHandlerDispatcher for TarHandler {
         .await
     }
 
+    async fn extract_all_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        target_dir: &Path,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<ExtractSummary, String> {
+        extract_all_blocking(
+            client,
+            file_path,
+            target_dir,
+            progress_callback,
+            cancel_rx,
+            |reader, extractor| Self::extract_all_entries(reader, extractor),
+        )
+        .await
+    }
+
     fn compression_type(&self) -> CompressionType {
         CompressionType::Tar
     }
@@ -55,6 +77,99 @@ impl CompressionHandlerDispatcher for TarHandler {
 }
 
 impl TarHandler {
+    /// 顺序读取 TAR 流并解压所有目录和普通文件条目，TAR.GZ / TAR.ZST 解压后复用
+    pub fn extract_all_entries(
+        reader: impl std::io::Read,
+        extractor: &mut EntryExtractor,
+    ) -> Result<(), String> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("Failed to read TAR entries: {}", e))?
+        {
+            let mut entry = entry.map_err(|e| format!("Failed to read TAR entry: {}", e))?;
+            let entry_type = entry.header().entry_type();
+            if !entry_type.is_dir() && !entry_type.is_file() {
+                extractor.skip();
+                continue;
+            }
+            let path = entry
+                .path()
+                .map(|p| p.to_string_lossy().into_owned())
+                .map_err(|e| format!("Invalid TAR entry path: {}", e))?;
+            extractor.extract(&path, entry_type.is_dir(), &mut entry)?;
+        }
+        Ok(())
+    }
+
+    /// 顺序读取 TAR 流直到目标条目，把条目内容写入数据流，TAR.GZ / TAR.ZST 解压后复用
+    pub fn stream_entry(
+        reader: impl std::io::Read,
+        entry_path: &str,
+        writer: &mut ChunkWriter,
+    ) -> Result<(), String> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("Failed to read TAR entries: {}", e))?
+        {
+            let mut entry = entry.map_err(|e| format!("Failed to read TAR entry: {}", e))?;
+            let matches = entry
+                .path()
+                .map(|p| p.to_string_lossy() == entry_path)
+                .unwrap_or(false);
+            if !matches {
+                continue;
+            }
+            if entry.header().entry_type().is_dir() {
+                return Err("Cannot preview directory".to_string());
+            }
+            writer.set_size(entry.size());
+            return writer.copy_from(&mut entry);
+        }
+        Err(format!("File not found in TAR archive: {}", entry_path))
+    }
+
+    /// 顺序读取 TAR 流，逐个发送需要的条目内容（每个最多 max_size 字节），全部找到后停止
+    pub fn scan_entries(
+        reader: impl std::io::Read,
+        mut entry_paths: HashSet<String>,
+        max_size: u64,
+        sender: &ScanSender,
+    ) -> Result<(), String> {
+        use std::io::Read;
+
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("Failed to read TAR entries: {}", e))?
+        {
+            if entry_paths.is_empty() {
+                break;
+            }
+            let mut entry = entry.map_err(|e| format!("Failed to read TAR entry: {}", e))?;
+            if entry.header().entry_type().is_dir() {
+                continue;
+            }
+            let Ok(path) = entry.path().map(|p| p.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            if !entry_paths.remove(&path) {
+                continue;
+            }
+
+            let mut content = Vec::new();
+            (&mut entry)
+                .take(max_size)
+                .read_to_end(&mut content)
+                .map_err(|e| format!("Failed to read TAR entry {}: {}", path, e))?;
+            if !sender.send(path, content) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// 使用存储客户端分析TAR文件（流式分析）
     async fn analyze_with_storage_client(
         client: Arc<dyn StorageClient>,
@@ -91,6 +206,7 @@ impl TarHandler {
     }
 
     /// 流式提取TAR文件预览（支持进度回调和取消信号）
+    /// 大文件先查条目索引直接定位，索引中没有时从上次扫描结束的位置继续逐个读取头部
     async fn extract_tar_preview_with_progress(
         client: Arc<dyn StorageClient>,
         file_path: &str,
@@ -111,16 +227,48 @@ impl TarHandler {
             .await
             .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        let mut current_offset = 0u64;
+        let mut entry_index = ArchiveEntryIndex::open(client.as_ref(), file_path, file_size).await;
+        if let Some(ref index) = entry_index {
+            if let Some(location) = index.get(entry_path) {
+                log::debug!(
+                    "通过条目索引定位TAR条目: {} @ {}",
+                    entry_path,
+                    location.offset
+                );
+                return Self::read_entry_preview(
+                    client.as_ref(),
+                    file_path,
+                    file_size,
+                    location,
+                    max_size,
+                    offset,
+                    progress_callback.as_deref(),
+                    cancel_rx,
+                )
+                .await;
+            }
+            if index.is_complete() {
+                return Err("File not found in TAR archive".to_string());
+            }
+        }
+
+        let mut current_offset = entry_index
+            .as_ref()
+            .map(|index| index.scanned_offset())
+            .unwrap_or(0);
         let mut index: u32 = 0;
 
         // TAR文件以512字节为一个块
         const BLOCK_SIZE: u64 = 512;
 
+        let mut found = None;
         while current_offset < file_size {
             // 检查取消信号
             if let Some(ref mut cancel_rx) = cancel_rx {
                 if let Ok(_) = cancel_rx.try_recv() {
+                    if let Some(ref mut entry_index) = entry_index {
+                        entry_index.save();
+                    }
                     return Err("download.cancelled".to_string());
                 }
             }
@@ -149,105 +297,38 @@ impl TarHandler {
 
             // 检查是否为空块（TAR文件末尾标识）
             if header_data.iter().all(|&b| b == 0) {
+                if let Some(ref mut entry_index) = entry_index {
+                    entry_index.mark_complete();
+                }
                 break;
             }
 
             // 解析TAR头部
             if let Ok(entry_info) = Self::parse_tar_header(&header_data, index) {
-                // 检查是否是我们要找的文件
-                if entry_info.path == entry_path {
-                    if entry_info.is_dir {
-                        return Err("Cannot preview directory".to_string());
-                    }
-
-                    // 找到了目标文件，分块读取其内容
-                    let file_offset = current_offset + BLOCK_SIZE;
-
-                    // 解析文件大小
-                    let file_size = entry_info.size.parse::<u64>().unwrap_or(0);
-
-                    // 计算实际的读取偏移量和大小
-                    let read_offset = offset.unwrap_or(0);
-                    if read_offset >= file_size {
-                        // 偏移量超出文件大小，返回空内容
-                        return Ok(PreviewBuilder::new()
-                            .content(Vec::new())
-                            .total_size(file_size)
-                            .with_truncated(false)
-                            .build());
-                    }
-
-                    let remaining_size = file_size - read_offset;
-                    let preview_size = (max_size as u64).min(remaining_size) as usize;
-                    let actual_file_offset = file_offset + read_offset;
-
-                    let content_data = if let Some(ref callback) = progress_callback {
-                        // 分块读取以显示进度
-                        let chunk_size = 64 * 1024; // 64KB chunks
-                        let mut all_data = Vec::with_capacity(preview_size);
-                        let mut read_offset_in_chunk = 0u64;
-
-                        while read_offset_in_chunk < preview_size as u64 {
-                            // 检查取消信号
-                            if let Some(ref mut cancel_rx) = cancel_rx {
-                                if let Ok(_) = cancel_rx.try_recv() {
-                                    return Err("download.cancelled".to_string());
-                                }
-                            }
-
-                            let current_chunk_size = std::cmp::min(
-                                chunk_size,
-                                preview_size as u64 - read_offset_in_chunk,
-                            );
-                            let chunk = client
-                                .read_file_range(
-                                    file_path,
-                                    actual_file_offset + read_offset_in_chunk,
-                                    current_chunk_size,
-                                )
-                                .await
-                                .map_err(|e| format!("Failed to read file content chunk: {}", e))?;
-
-                            all_data.extend_from_slice(&chunk);
-                            read_offset_in_chunk += chunk.len() as u64;
-
-                            // 更新进度（基于文件内容读取）
-                            let total_progress =
-                                current_offset + BLOCK_SIZE + read_offset + read_offset_in_chunk;
-                            callback(total_progress, file_size);
-                        }
-
-                        all_data
-                    } else {
-                        // 直接读取全部内容
-                        client
-                            .read_file_range(file_path, actual_file_offset, preview_size as u64)
-                            .await
-                            .map_err(|e| format!("Failed to read file content: {}", e))?
-                    };
-
-                    let _mime_type = detect_mime_type(&content_data);
-
-                    let data_len = content_data.len();
-                    let is_truncated =
-                        data_len >= max_size || (read_offset + data_len as u64) < file_size;
-
-                    return Ok(PreviewBuilder::new()
-                        .content(content_data)
-                        .total_size(file_size)
-                        .with_truncated(is_truncated)
-                        .build());
-                }
-
                 // 计算文件数据的大小（向上舍入到512字节的倍数）
-                let file_size = entry_info.size.parse::<u64>().unwrap_or(0);
-                let file_size_blocks = (file_size + BLOCK_SIZE - 1) / BLOCK_SIZE;
+                let entry_size = entry_info.size.parse::<u64>().unwrap_or(0);
+                let file_size_blocks = (entry_size + BLOCK_SIZE - 1) / BLOCK_SIZE;
                 let file_data_size = file_size_blocks * BLOCK_SIZE;
 
+                let location = EntryLocation {
+                    offset: current_offset + BLOCK_SIZE,
+                    size: entry_size,
+                    is_dir: entry_info.is_dir,
+                };
                 // 跳过头部和文件数据
                 current_offset += BLOCK_SIZE + file_data_size;
                 index += 1;
 
+                if let Some(ref mut entry_index) = entry_index {
+                    entry_index.record(&entry_info.path, location, current_offset);
+                }
+
+                // 检查是否是我们要找的文件
+                if entry_info.path == entry_path {
+                    found = Some(location);
+                    break;
+                }
+
                 // 防止无限循环
                 if index >= 10000 {
                     log::warn!("TAR条目搜索达到限制(10000)，停止搜索");
@@ -259,7 +340,114 @@ impl TarHandler {
             }
         }
 
-        Err("File not found in TAR archive".to_string())
+        if let Some(ref mut entry_index) = entry_index {
+            entry_index.save();
+        }
+
+        match found {
+            Some(location) => {
+                Self::read_entry_preview(
+                    client.as_ref(),
+                    file_path,
+                    file_size,
+                    location,
+                    max_size,
+                    offset,
+                    progress_callback.as_deref(),
+                    cancel_rx,
+                )
+                .await
+            }
+            None => Err("File not found in TAR archive".to_string()),
+        }
+    }
+
+    /// 从条目数据的起始位置读取预览内容
+    #[allow(clippy::too_many_arguments)]
+    async fn read_entry_preview(
+        client: &dyn StorageClient,
+        file_path: &str,
+        archive_size: u64,
+        location: EntryLocation,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<&(dyn Fn(u64, u64) + Send + Sync)>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        if location.is_dir {
+            return Err("Cannot preview directory".to_string());
+        }
+
+        let file_offset = location.offset;
+        let file_size = location.size;
+
+        // 计算实际的读取偏移量和大小
+        let read_offset = offset.unwrap_or(0);
+        if read_offset >= file_size {
+            // 偏移量超出文件大小，返回空内容
+            return Ok(PreviewBuilder::new()
+                .content(Vec::new())
+                .total_size(file_size)
+                .with_truncated(false)
+                .build());
+        }
+
+        let remaining_size = file_size - read_offset;
+        let preview_size = (max_size as u64).min(remaining_size) as usize;
+        let actual_file_offset = file_offset + read_offset;
+
+        let content_data = if let Some(callback) = progress_callback {
+            // 分块读取以显示进度
+            let chunk_size = 64 * 1024; // 64KB chunks
+            let mut all_data = Vec::with_capacity(preview_size);
+            let mut read_offset_in_chunk = 0u64;
+
+            while read_offset_in_chunk < preview_size as u64 {
+                // 检查取消信号
+                if let Some(ref mut cancel_rx) = cancel_rx {
+                    if cancel_rx.try_recv().is_ok() {
+                        return Err("download.cancelled".to_string());
+                    }
+                }
+
+                let current_chunk_size =
+                    std::cmp::min(chunk_size, preview_size as u64 - read_offset_in_chunk);
+                let chunk = client
+                    .read_file_range(
+                        file_path,
+                        actual_file_offset + read_offset_in_chunk,
+                        current_chunk_size,
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to read file content chunk: {}", e))?;
+                if chunk.is_empty() {
+                    break;
+                }
+
+                all_data.extend_from_slice(&chunk);
+                read_offset_in_chunk += chunk.len() as u64;
+
+                // 更新进度（基于文件内容读取）
+                callback(actual_file_offset + read_offset_in_chunk, archive_size);
+            }
+
+            all_data
+        } else {
+            // 直接读取全部内容
+            client
+                .read_file_range(file_path, actual_file_offset, preview_size as u64)
+                .await
+                .map_err(|e| format!("Failed to read file content: {}", e))?
+        };
+
+        let data_len = content_data.len();
+        let is_truncated = data_len >= max_size || (read_offset + data_len as u64) < file_size;
+
+        Ok(PreviewBuilder::new()
+            .content(content_data)
+            .total_size(file_size)
+            .with_truncated(is_truncated)
+            .build())
     }
 
     /// 流式分析TAR文件，逐块读取头部信息
@@ -280,6 +468,7 @@ impl TarHandler {
         let mut total_uncompressed_size = 0u64;
         let mut current_offset = 0u64;
         let mut index: u32 = 0;
+        let mut entry_index = ArchiveEntryIndex::open(client.as_ref(), file_path, file_size).await;
 
         // TAR文件以512字节为一个块
         const BLOCK_SIZE: u64 = 512;
@@ -305,6 +494,9 @@ impl TarHandler {
 
             // 检查是否为空块（TAR文件末尾标识）
             if header_data.iter().all(|&b| b == 0) {
+                if let Some(ref mut entry_index) = entry_index {
+                    entry_index.mark_complete();
+                }
                 break;
             }
 
@@ -312,16 +504,25 @@ impl TarHandler {
             if let Ok(entry_info) = Self::parse_tar_header(&header_data, index) {
                 let file_size = entry_info.size.parse::<u64>().unwrap_or(0);
                 total_uncompressed_size += file_size;
-                entries.push(entry_info);
 
                 // 计算文件数据的大小（向上舍入到512字节的倍数）
                 let file_size_blocks = (file_size + BLOCK_SIZE - 1) / BLOCK_SIZE;
                 let file_data_size = file_size_blocks * BLOCK_SIZE;
 
+                let location = EntryLocation {
+                    offset: current_offset + BLOCK_SIZE,
+                    size: file_size,
+                    is_dir: entry_info.is_dir,
+                };
                 // 跳过头部和文件数据
                 current_offset += BLOCK_SIZE + file_data_size;
                 index += 1;
 
+                if let Some(ref mut entry_index) = entry_index {
+                    entry_index.record(&entry_info.path, location, current_offset);
+                }
+                entries.push(entry_info);
+
                 // 限制条目数量以避免内存问题
                 if entries.len() >= 10000 {
                     log::warn!("TAR条目数量达到限制(10000)，停止分析");
@@ -335,6 +536,10 @@ impl TarHandler {
 
         log::debug!("流式分析完成，找到 {} 个条目", entries.len());
 
+        if let Some(ref mut entry_index) = entry_index {
+            entry_index.save();
+        }
+
         let entry_count = entries.len();
 
         Ok(ArchiveInfoBuilder::new(CompressionType::Tar)
@@ -403,6 +608,7 @@ impl TarHandler {
             is_dir: is_directory,
             modified_time: last_modified,
             crc32: None,
+            encrypted: false,
             index,
             metadata: HashMap::new(),
         })
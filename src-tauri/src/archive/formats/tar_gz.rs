@@ -1,14 +1,27 @@
-use crate::archive::formats::common::ArchiveInfoBuilder;
+use crate::archive::entry_index::{ArchiveEntryIndex, EntryLocation};
+use crate::archive::entry_stream::{EntryScan, EntryStream};
+use crate::archive::formats::common::{
+    extract_all_blocking, open_storage_reader, read_stream_preview, ArchiveInfoBuilder,
+    CountingReader, PreviewBuilder, StorageReader,
+};
+use crate::archive::formats::gzip_index::GzipSeekIndex;
+use crate::archive::formats::tar::TarHandler;
 use crate::archive::formats::CompressionHandlerDispatcher;
+use crate::archive::settings::ArchiveSettings;
 use crate::archive::types::{
-    AnalysisStatus, ArchiveEntry, ArchiveInfo, CompressionType, FilePreview,
+    AnalysisStatus, ArchiveEntry, ArchiveInfo, CompressionType, ExtractSummary, FilePreview,
 };
 use crate::storage::traits::StorageClient;
 use flate2::read::GzDecoder;
-use std::collections::HashMap;
-use std::io::{Cursor, Read};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+/// 分析时最多列出的条目数
+const MAX_ENTRIES: usize = 10000;
+
 pub struct TarGzHandler;
 
 #[async_trait::async_trait]
@@ -20,7 +33,9 @@ impl CompressionHandlerDispatcher for TarGzHandler {
         _filename: &str,
         _max_size: Option<u32>,
     ) -> Result<ArchiveInfo, String> {
-        Self::analyze_tar_gz_streaming(client, file_path).await
+        // 解压量上限来自压缩包分析设置，超出后返回部分结果
+        let budget = ArchiveSettings::current().tar_gz_scan_budget();
+        Self::analyze_tar_gz_streaming(client, file_path, budget).await
     }
 
     async fn extract_preview_with_client(
@@ -29,20 +44,68 @@ impl CompressionHandlerDispatcher for TarGzHandler {
         file_path: &str,
         entry_path: &str,
         max_size: usize,
-        _offset: Option<u64>,
+        offset: Option<u64>,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
-        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
         Self::extract_tar_gz_preview_with_progress(
             client,
             file_path,
             entry_path,
             max_size,
+            offset,
+            progress_callback,
+            cancel_rx.map(|rx| rx.resubscribe()),
+        )
+        .await
+    }
+
+    async fn extract_all_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        target_dir: &Path,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<ExtractSummary, String> {
+        extract_all_blocking(
+            client,
+            file_path,
+            target_dir,
             progress_callback,
+            cancel_rx,
+            |reader, extractor| TarHandler::extract_all_entries(GzDecoder::new(reader), extractor),
         )
         .await
     }
 
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        let entry_path = entry_path.to_string();
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            TarHandler::stream_entry(GzDecoder::new(reader), &entry_path, writer)
+        })))
+    }
+
+    async fn scan_entries_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_paths: HashSet<String>,
+        max_size: u64,
+    ) -> Result<Option<EntryScan>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        Ok(Some(EntryScan::spawn_blocking(move |sender| {
+            TarHandler::scan_entries(GzDecoder::new(reader), entry_paths, max_size, sender)
+        })))
+    }
+
     fn compression_type(&self) -> CompressionType {
         CompressionType::TarGz
     }
@@ -53,116 +116,136 @@ impl CompressionHandlerDispatcher for TarGzHandler {
 }
 
 impl TarGzHandler {
-    /// 高效流式分析TAR.GZ文件，采用增量解压缩策略
+    /// 流式分析TAR.GZ文件：通过 StorageReader 按需读取并解压，逐个解析 TAR 条目
+    /// 解压数据量超过预算或条目数达到上限时停止，返回已发现的条目并标记为部分结果
     async fn analyze_tar_gz_streaming(
         client: Arc<dyn StorageClient>,
         file_path: &str,
+        scan_budget: u64,
     ) -> Result<ArchiveInfo, String> {
-        log::debug!("开始高效流式分析TAR.GZ文件: {}", file_path);
+        log::debug!("开始流式分析TAR.GZ文件: {}", file_path);
 
         let file_size = client
             .get_file_size(file_path)
             .await
             .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        log::info!(
-            "TAR.GZ文件大小: {:.2} MB",
-            file_size as f64 / (1024.0 * 1024.0)
+        let entry_index = ArchiveEntryIndex::open(client.as_ref(), file_path, file_size).await;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
         );
 
-        // 采用极小的初始读取策略，类似TAR格式的高效处理
+        tokio::task::spawn_blocking(move || {
+            Self::list_entries(reader, file_size, scan_budget, entry_index)
+        })
+        .await
+        .map_err(|e| format!("TAR.GZ analysis task failed: {}", e))?
+    }
+
+    fn list_entries(
+        reader: StorageReader,
+        file_size: u64,
+        scan_budget: u64,
+        mut entry_index: Option<ArchiveEntryIndex>,
+    ) -> Result<ArchiveInfo, String> {
+        let counting = CountingReader::new(reader);
+        let consumed = counting.counter();
+        let mut archive = tar::Archive::new(GzDecoder::new(counting));
         let mut entries = Vec::new();
         let mut total_uncompressed_size = 0u64;
-        let mut compressed_offset = 0u64;
-        let mut decompressed_buffer = Vec::new();
-
-        // 初始读取量很小，类似于TAR只读头部的策略
-        let initial_read_size = 32 * 1024; // 32KB 开始
-        let max_read_size = 2 * 1024 * 1024; // 最多读取2MB用于分析
-        let target_entries = 100; // 目标获取100个条目就足够了
+        let mut complete = true;
 
-        let mut current_read_size = initial_read_size;
-
-        while compressed_offset < file_size
-            && compressed_offset < max_read_size
-            && entries.len() < target_entries
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("Failed to read TAR.GZ entries: {}", e))?
         {
-            let remaining = std::cmp::min(file_size - compressed_offset, current_read_size);
-
-            log::debug!(
-                "读取压缩数据块: offset={}, size={}",
-                compressed_offset,
-                remaining
-            );
-
-            let chunk = client
-                .read_file_range(file_path, compressed_offset, remaining)
-                .await
-                .map_err(|e| format!("Failed to read chunk: {}", e))?;
-
-            compressed_offset += chunk.len() as u64;
-
-            // 尝试增量解压缩这个chunk
-            match Self::incremental_decompress_chunk(&chunk, &mut decompressed_buffer) {
-                Ok(newly_decompressed) => {
-                    log::debug!("成功解压缩 {} 字节", newly_decompressed);
-
-                    // 解析新的TAR条目
-                    let new_entries =
-                        Self::parse_new_tar_entries(&decompressed_buffer, entries.len())?;
-                    for entry in new_entries {
-                        if let Ok(size) = entry.size.parse::<u64>() {
-                            total_uncompressed_size += size;
-                        }
-                        entries.push(entry);
-                    }
-
-                    log::debug!("当前已解析 {} 个条目", entries.len());
-
-                    // 如果获得了足够的条目，提前停止
-                    if entries.len() >= target_entries {
-                        log::debug!("已获得足够的文件条目 ({}), 停止分析", entries.len());
-                        break;
-                    }
-                }
-                Err(e) if e.contains("need more data") => {
-                    // 需要更多数据，增加读取量
-                    current_read_size = std::cmp::min(current_read_size * 2, 256 * 1024);
-                    log::debug!("需要更多数据，增加读取量到 {}", current_read_size);
-                    continue;
-                }
-                Err(e) => {
-                    log::warn!("解压缩失败: {}, 尝试用现有数据", e);
-                    // 即使失败也尝试解析已有数据
-                    let new_entries =
-                        Self::parse_new_tar_entries(&decompressed_buffer, entries.len())?;
-                    entries.extend(new_entries);
+            let entry = match entry {
+                Ok(entry) => entry,
+                // 已列出部分条目时保留结果，只记录错误
+                Err(e) if !entries.is_empty() => {
+                    log::warn!(
+                        "读取TAR.GZ条目失败，返回已列出的 {} 个条目: {}",
+                        entries.len(),
+                        e
+                    );
+                    complete = false;
                     break;
                 }
+                Err(e) => return Err(format!("Failed to read TAR.GZ entry: {}", e)),
+            };
+            if entries.len() >= MAX_ENTRIES || entry.raw_file_position() > scan_budget {
+                log::warn!(
+                    "TAR.GZ 分析达到限制，已列出 {} 个条目，停止分析",
+                    entries.len()
+                );
+                complete = false;
+                break;
+            }
+
+            let header = entry.header();
+            let size = header.size().unwrap_or(0);
+            let path = entry
+                .path()
+                .map(|p| p.to_string_lossy().into_owned())
+                .map_err(|e| format!("Invalid TAR entry path: {}", e))?;
+            let is_dir = header.entry_type().is_dir() || path.ends_with('/');
+            let modified_time = header
+                .mtime()
+                .ok()
+                .filter(|mtime| *mtime > 0)
+                .and_then(|mtime| chrono::DateTime::from_timestamp(mtime as i64, 0))
+                .map(|dt| dt.to_rfc3339());
+
+            if let Some(ref mut entry_index) = entry_index {
+                let location = EntryLocation {
+                    offset: entry.raw_file_position(),
+                    size,
+                    is_dir,
+                };
+                let next_header_offset = location.offset + size.div_ceil(512) * 512;
+                entry_index.record(&path, location, next_header_offset);
             }
 
-            // 适度增加读取大小，但保持较小以维持性能
-            current_read_size = std::cmp::min(current_read_size + 16384, 128 * 1024);
+            total_uncompressed_size += size;
+            entries.push(ArchiveEntry {
+                is_dir,
+                path,
+                size: size.to_string(),
+                compressed_size: None,
+                modified_time,
+                crc32: None,
+                encrypted: false,
+                index: entries.len() as u32,
+                metadata: HashMap::new(),
+            });
+        }
+
+        if let Some(ref mut entry_index) = entry_index {
+            if complete {
+                entry_index.mark_complete();
+            }
+            entry_index.save();
         }
 
         log::info!(
-            "高效分析完成：读取 {:.2} MB 压缩数据，找到 {} 个条目",
-            compressed_offset as f64 / (1024.0 * 1024.0),
+            "TAR.GZ分析完成：读取 {:.2} MB 压缩数据，找到 {} 个条目",
+            consumed.load(Ordering::Relaxed) as f64 / (1024.0 * 1024.0),
             entries.len()
         );
 
-        // 确定分析状态
-        let analysis_status = if compressed_offset < file_size || entries.len() >= target_entries {
+        let analysis_status = if complete {
+            AnalysisStatus::Complete
+        } else {
             AnalysisStatus::Partial {
                 analyzed_entries: entries.len() as u32,
             }
-        } else {
-            AnalysisStatus::Complete
         };
 
         Ok(ArchiveInfoBuilder::new(CompressionType::TarGz)
-            .entries(entries.clone())
-            .total_entries(entries.len() as u32)
+            .entries(entries)
             .total_uncompressed_size(total_uncompressed_size)
             .total_compressed_size(file_size)
             .supports_streaming(true)
@@ -171,163 +254,17 @@ impl TarGzHandler {
             .build())
     }
 
-    /// 增量解压缩单个数据块
-    fn incremental_decompress_chunk(
-        chunk: &[u8],
-        decompressed_buffer: &mut Vec<u8>,
-    ) -> Result<usize, String> {
-        let mut decoder = GzDecoder::new(chunk);
-        let mut temp_buffer = vec![0u8; 64 * 1024]; // 64KB临时缓冲区
-        let initial_len = decompressed_buffer.len();
-
-        loop {
-            match decoder.read(&mut temp_buffer) {
-                Ok(0) => {
-                    // 读取完成
-                    break;
-                }
-                Ok(bytes_read) => {
-                    decompressed_buffer.extend_from_slice(&temp_buffer[..bytes_read]);
-                }
-                Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
-                    // 需要更多压缩数据
-                    if decompressed_buffer.len() == initial_len {
-                        return Err("need more data".to_string());
-                    } else {
-                        // 已经解压了一些数据，返回成功
-                        break;
-                    }
-                }
-                Err(e) => {
-                    return Err(format!("Decompression error: {}", e));
-                }
-            }
-        }
-
-        let newly_decompressed = decompressed_buffer.len() - initial_len;
-        Ok(newly_decompressed)
-    }
-
-    /// 解析新的TAR条目（从指定位置开始）
-    fn parse_new_tar_entries(
-        decompressed_buffer: &[u8],
-        existing_entries_count: usize,
-    ) -> Result<Vec<ArchiveEntry>, String> {
-        let mut entries = Vec::new();
-        let mut tar_offset = 0;
-        let mut current_entry_index = 0;
-
-        // 跳过已经解析的条目
-        while tar_offset + 512 <= decompressed_buffer.len()
-            && current_entry_index < existing_entries_count
-        {
-            let header = &decompressed_buffer[tar_offset..tar_offset + 512];
-
-            if header.iter().all(|&b| b == 0) {
-                tar_offset += 512;
-                continue;
-            }
-
-            // 解析文件大小来跳过
-            if let Ok(file_size) = Self::parse_tar_file_size(header) {
-                let aligned_size = (file_size + 511) & !511;
-                tar_offset += 512 + aligned_size as usize;
-                current_entry_index += 1;
-            } else {
-                tar_offset += 512;
-            }
-        }
-
-        // 解析新的条目
-        while tar_offset + 512 <= decompressed_buffer.len() && entries.len() < 50 {
-            let header = &decompressed_buffer[tar_offset..tar_offset + 512];
-
-            if header.iter().all(|&b| b == 0) {
-                // TAR结束标记
-                break;
-            }
-
-            match Self::parse_tar_header_from_bytes(
-                header,
-                (existing_entries_count + entries.len()) as u32,
-            ) {
-                Ok(entry) => {
-                    let file_size = entry.size.parse::<u64>().unwrap_or(0);
-                    entries.push(entry);
-
-                    // 跳过文件内容
-                    let aligned_size = (file_size + 511) & !511;
-                    tar_offset += 512 + aligned_size as usize;
-                }
-                Err(_) => {
-                    // 跳过无效头部
-                    tar_offset += 512;
-                }
-            }
-        }
-
-        Ok(entries)
-    }
-
-    /// 快速解析TAR文件大小（不完整解析，只获取大小）
-    fn parse_tar_file_size(header: &[u8]) -> Result<u64, String> {
-        if header.len() < 136 {
-            return Err("Header too short".to_string());
-        }
-
-        let size_bytes = &header[124..136];
-        let size_binding = String::from_utf8_lossy(size_bytes);
-        let size_str = size_binding.trim_end_matches('\0');
-
-        u64::from_str_radix(size_str.trim(), 8)
-            .map_err(|_| format!("Invalid size field: {}", size_str))
-    }
-
-    /// 从字节解析TAR头部
-    fn parse_tar_header_from_bytes(header: &[u8], index: u32) -> Result<ArchiveEntry, String> {
-        if header.len() < 512 {
-            return Err("Header too short".to_string());
-        }
-
-        // 解析文件名 (0-99)
-        let name_bytes = &header[0..100];
-        let name_end = name_bytes.iter().position(|&b| b == 0).unwrap_or(100);
-        let file_name = String::from_utf8_lossy(&name_bytes[..name_end]).to_string();
-
-        if file_name.is_empty() {
-            return Err("Empty filename".to_string());
-        }
-
-        // 解析文件大小 (124-135)
-        let size_bytes = &header[124..136];
-        let size_binding = String::from_utf8_lossy(size_bytes);
-        let size_str = size_binding.trim_end_matches('\0');
-        let file_size = u64::from_str_radix(size_str.trim(), 8)
-            .map_err(|_| format!("Invalid file size: {}", size_str))?;
-
-        // 解析文件类型 (156)
-        let type_flag = header[156];
-        let is_dir = type_flag == b'5' || file_name.ends_with('/');
-
-        Ok(ArchiveEntry {
-            path: file_name,
-            size: file_size.to_string(),
-            compressed_size: None,
-            is_dir,
-            modified_time: None,
-            crc32: None,
-            index,
-            metadata: HashMap::new(),
-        })
-    }
-
-    /// 提取TAR.GZ文件预览，支持进度回调
+    /// 提取TAR.GZ文件预览，支持进度回调和取消信号
+    /// 通过 StorageReader 流式解压，不在内存中缓存整个 TAR 流；
+    /// 大文件先查条目索引，命中时直接跳到条目数据，否则逐个扫描条目并把位置写入索引
     async fn extract_tar_gz_preview_with_progress(
         client: Arc<dyn StorageClient>,
         file_path: &str,
         entry_path: &str,
         max_size: usize,
+        offset: Option<u64>,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<FilePreview, String> {
         log::debug!("开始提取TAR.GZ文件预览: {}", entry_path);
 
@@ -336,146 +273,181 @@ impl TarGzHandler {
             .await
             .map_err(|e| format!("Failed to get file size: {}", e))?;
 
-        // 使用渐进式读取策略
-        let initial_size = 1024 * 1024; // 1MB
-        let max_read = (file_size / 4).max(initial_size).min(50 * 1024 * 1024); // 最多50MB
-
-        let compressed_data = client
-            .read_file_range(file_path, 0, max_read)
-            .await
-            .map_err(|e| format!("Failed to read compressed data: {}", e))?;
-
-        if let Some(callback) = progress_callback.as_ref() {
-            callback(max_read, file_size);
-        }
-
-        // 解压缩数据
-        let mut decoder = GzDecoder::new(Cursor::new(&compressed_data));
-        let mut decompressed_data = Vec::new();
-
-        match decoder.read_to_end(&mut decompressed_data) {
-            Ok(_) => {
-                log::debug!("解压缩成功，开始搜索文件");
-            }
-            Err(e) => {
-                log::warn!("解压缩部分失败: {}", e);
-                // 即使失败也尝试处理部分数据
-            }
-        }
-
-        // 在TAR数据中查找目标文件
-        match Self::extract_file_from_tar_buffer(&decompressed_data, entry_path, max_size) {
-            Ok(content) => {
-                log::debug!("成功找到目标文件: {}", entry_path);
-                return Ok(FilePreview {
-                    content: content.clone(),
-                    is_truncated: content.len() >= max_size,
-                    total_size: content.len().to_string(),
-                    preview_size: content.len() as u32,
-                });
-            }
-            Err(e) => {
-                log::warn!("在初始数据中未找到文件: {}", e);
-            }
+        let entry_index = ArchiveEntryIndex::open(client.as_ref(), file_path, file_size).await;
+        let location = entry_index.as_ref().and_then(|index| index.get(entry_path));
+        if location.is_none()
+            && entry_index
+                .as_ref()
+                .is_some_and(|index| index.is_complete())
+        {
+            return Err(format!("File not found in TAR.GZ archive: {}", entry_path));
         }
 
-        // 如果初始读取未找到，尝试读取更多数据
-        if max_read < file_size {
-            log::debug!(
-                "扩展搜索范围到 {:.2} MB",
-                file_size as f64 / (1024.0 * 1024.0)
-            );
-
-            let extended_size = file_size.min(100 * 1024 * 1024); // 最多100MB
-            let extended_data = client
-                .read_file_range(file_path, 0, extended_size)
-                .await
-                .map_err(|e| format!("Failed to read extended data: {}", e))?;
-
-            if let Some(callback) = progress_callback.as_ref() {
-                callback(extended_size, file_size);
-            }
-
-            let mut decoder = GzDecoder::new(Cursor::new(&extended_data));
-            let mut extended_tar_data = Vec::new();
+        // 已定位条目时从最近的 gzip 检查点恢复解压，不必从头解压条目之前的数据
+        let seek_index = match location {
+            Some(_) => GzipSeekIndex::open(client.as_ref(), file_path, file_size).await,
+            None => None,
+        };
 
-            match decoder.read_to_end(&mut extended_tar_data) {
-                Ok(_) => {
-                    match Self::extract_file_from_tar_buffer(
-                        &extended_tar_data,
-                        entry_path,
-                        max_size,
-                    ) {
-                        Ok(content) => {
-                            log::debug!("在扩展数据中找到目标文件: {}", entry_path);
-                            return Ok(FilePreview {
-                                content: content.clone(),
-                                is_truncated: content.len() >= max_size,
-                                total_size: content.len().to_string(),
-                                preview_size: content.len() as u32,
-                            });
-                        }
-                        Err(e) => {
-                            log::warn!("扩展搜索也未找到文件: {}", e);
-                        }
-                    }
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+        let entry_path = entry_path.to_string();
+        let read_offset = offset.unwrap_or(0);
+
+        tokio::task::spawn_blocking(move || match location {
+            Some(location) => {
+                log::debug!(
+                    "通过条目索引定位TAR.GZ条目: {} @ {}",
+                    entry_path,
+                    location.offset
+                );
+                if location.is_dir {
+                    return Err("Cannot preview directory".to_string());
                 }
-                Err(e) => {
-                    log::warn!("扩展数据解压缩失败: {}", e);
+                match seek_index {
+                    Some(seek_index) => Self::read_indexed_entry(
+                        seek_index,
+                        reader,
+                        file_size,
+                        location,
+                        read_offset,
+                        max_size,
+                        progress_callback,
+                        cancel_rx,
+                    ),
+                    // 小文件不建立 gzip 索引：解压流截断到条目末尾，offset 之前的数据解压后丢弃
+                    None => read_stream_preview(
+                        GzDecoder::new(reader).take(location.offset + location.size),
+                        location.offset + read_offset,
+                        max_size,
+                        Some(location.size),
+                        progress_callback,
+                    ),
                 }
             }
-        }
+            None => Self::scan_for_entry(
+                reader,
+                file_size,
+                &entry_path,
+                read_offset,
+                max_size,
+                progress_callback,
+                cancel_rx,
+                entry_index,
+            ),
+        })
+        .await
+        .map_err(|e| format!("Preview task failed: {}", e))?
+    }
 
-        Err(format!("File not found in TAR.GZ archive: {}", entry_path))
+    /// 通过 gzip 检查点读取已定位条目的 read_offset 开始的数据，
+    /// 读取范围限制在条目内，解压过程中建立的检查点写回索引
+    #[allow(clippy::too_many_arguments)]
+    fn read_indexed_entry(
+        mut seek_index: GzipSeekIndex,
+        reader: StorageReader,
+        file_size: u64,
+        location: EntryLocation,
+        read_offset: u64,
+        max_size: usize,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let remaining = location.size.saturating_sub(read_offset);
+        let result = seek_index.read_at(
+            reader,
+            file_size,
+            location.offset + read_offset,
+            remaining.min(max_size as u64) as usize,
+            progress_callback.as_deref(),
+            cancel_rx.as_mut(),
+        );
+        // 取消或出错时已建立的检查点同样有效
+        seek_index.save();
+
+        let content = result?.content;
+        let is_truncated = read_offset + (content.len() as u64) < location.size;
+        Ok(PreviewBuilder::new()
+            .content(content)
+            .total_size(location.size)
+            .with_truncated(is_truncated)
+            .build())
     }
 
-    /// 从TAR缓冲区提取指定文件
-    fn extract_file_from_tar_buffer(
-        buffer: &[u8],
-        target_path: &str,
+    /// 顺序扫描 TAR.GZ 中的条目直到找到目标，扫描过的条目位置记录到索引
+    #[allow(clippy::too_many_arguments)]
+    fn scan_for_entry(
+        reader: StorageReader,
+        file_size: u64,
+        entry_path: &str,
+        read_offset: u64,
         max_size: usize,
-    ) -> Result<Vec<u8>, String> {
-        let mut offset = 0;
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        mut cancel_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+        mut entry_index: Option<ArchiveEntryIndex>,
+    ) -> Result<FilePreview, String> {
+        let counting = CountingReader::new(reader);
+        let consumed = counting.counter();
+        let mut archive = tar::Archive::new(GzDecoder::new(counting));
+
+        let result = (|| {
+            let entries = archive
+                .entries()
+                .map_err(|e| format!("Failed to read TAR entries: {}", e))?;
+            for entry in entries {
+                if let Some(ref mut cancel_rx) = cancel_rx {
+                    if cancel_rx.try_recv().is_ok() {
+                        return Err("download.cancelled".to_string());
+                    }
+                }
 
-        while offset + 512 <= buffer.len() {
-            let header = &buffer[offset..offset + 512];
+                let mut entry = entry.map_err(|e| format!("Failed to read TAR entry: {}", e))?;
+                let path = entry
+                    .path()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .map_err(|e| format!("Invalid TAR entry path: {}", e))?;
+                let location = EntryLocation {
+                    offset: entry.raw_file_position(),
+                    size: entry.size(),
+                    is_dir: entry.header().entry_type().is_dir(),
+                };
+                if let Some(ref mut entry_index) = entry_index {
+                    let next_header_offset = location.offset + location.size.div_ceil(512) * 512;
+                    entry_index.record(&path, location, next_header_offset);
+                }
 
-            // 检查是否为空块
-            if header.iter().all(|&b| b == 0) {
-                offset += 512;
-                continue;
-            }
+                if let Some(ref callback) = progress_callback {
+                    callback(consumed.load(Ordering::Relaxed), file_size);
+                }
 
-            // 解析文件名
-            let name_bytes = &header[0..100];
-            let name_end = name_bytes.iter().position(|&b| b == 0).unwrap_or(100);
-            let file_name = String::from_utf8_lossy(&name_bytes[..name_end]);
-
-            // 解析文件大小
-            let size_bytes = &header[124..136];
-            let size_binding = String::from_utf8_lossy(size_bytes);
-            let size_str = size_binding.trim_end_matches('\0');
-            let file_size = u64::from_str_radix(size_str.trim(), 8)
-                .map_err(|_| format!("Invalid file size in TAR header: {}", size_str))?;
-
-            offset += 512; // 跳过头部
-
-            // 检查是否为目标文件
-            if file_name == target_path {
-                let content_size = (file_size as usize).min(max_size);
-                if offset + content_size <= buffer.len() {
-                    return Ok(buffer[offset..offset + content_size].to_vec());
-                } else {
-                    return Err("File content not fully available in buffer".to_string());
+                if path == entry_path {
+                    if location.is_dir {
+                        return Err("Cannot preview directory".to_string());
+                    }
+                    return read_stream_preview(
+                        &mut entry,
+                        read_offset,
+                        max_size,
+                        Some(location.size),
+                        None,
+                    );
                 }
             }
 
-            // 跳过文件内容（512字节对齐）
-            let aligned_size = (file_size + 511) & !511;
-            offset += aligned_size as usize;
-        }
+            if let Some(ref mut entry_index) = entry_index {
+                entry_index.mark_complete();
+            }
+            Err(format!("File not found in TAR.GZ archive: {}", entry_path))
+        })();
 
-        Err(format!("File '{}' not found in TAR buffer", target_path))
+        if let Some(ref mut entry_index) = entry_index {
+            entry_index.save();
+        }
+        result
     }
 
     /// 验证TAR.GZ头部
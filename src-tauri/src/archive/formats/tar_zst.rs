@@ -0,0 +1,267 @@
+use crate::archive::entry_stream::{EntryScan, EntryStream};
+use crate::archive::formats::tar::TarHandler;
+use crate::archive::formats::zstd::{ZstdHandler, ZSTD_MAGIC};
+use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
+/// TAR.ZST 格式处理器
+/// 通过 StorageReader 顺序读取压缩数据，流式解压并逐个解析 TAR 条目
+/// ZSTD 流无法跳转，列出条目和提取文件都需要从头解压到目标位置
+use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 分析时最多列出的条目数
+const MAX_ENTRIES: usize = 10000;
+
+/// 分析时最多解压的数据量，超出后返回部分结果
+const MAX_SCAN_BYTES: u64 = 1024 * 1024 * 1024; // 1GB
+
+pub struct TarZstHandler;
+
+#[async_trait::async_trait]
+impl CompressionHandlerDispatcher for TarZstHandler {
+    async fn analyze_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _filename: &str,
+        _max_size: Option<u32>,
+    ) -> Result<ArchiveInfo, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        tokio::task::spawn_blocking(move || Self::list_entries(reader, file_size))
+            .await
+            .map_err(|e| format!("TAR.ZST analysis task failed: {}", e))?
+    }
+
+    async fn extract_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        let entry_path = entry_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Self::extract_entry(
+                reader,
+                &entry_path,
+                max_size,
+                offset.unwrap_or(0),
+                progress_callback,
+            )
+        })
+        .await
+        .map_err(|e| format!("TAR.ZST extraction task failed: {}", e))?
+    }
+
+    async fn extract_all_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        target_dir: &Path,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<ExtractSummary, String> {
+        extract_all_blocking(
+            client,
+            file_path,
+            target_dir,
+            progress_callback,
+            cancel_rx,
+            |reader, extractor| {
+                TarHandler::extract_all_entries(ZstdHandler::decoder(reader)?, extractor)
+            },
+        )
+        .await
+    }
+
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        let entry_path = entry_path.to_string();
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            TarHandler::stream_entry(ZstdHandler::decoder(reader)?, &entry_path, writer)
+        })))
+    }
+
+    async fn scan_entries_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_paths: HashSet<String>,
+        max_size: u64,
+    ) -> Result<Option<EntryScan>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        Ok(Some(EntryScan::spawn_blocking(move |sender| {
+            TarHandler::scan_entries(ZstdHandler::decoder(reader)?, entry_paths, max_size, sender)
+        })))
+    }
+
+    fn compression_type(&self) -> CompressionType {
+        CompressionType::TarZst
+    }
+
+    /// 解压文件头检查 TAR 的 ustar 标记
+    fn validate_format(&self, data: &[u8]) -> bool {
+        if !data.starts_with(&ZSTD_MAGIC) {
+            return false;
+        }
+
+        let Ok(decoder) = ZstdHandler::decoder(data) else {
+            return false;
+        };
+        let mut header = Vec::with_capacity(512);
+        // 文件头数据不完整，解压出错时使用已解压的部分
+        let _ = decoder.take(512).read_to_end(&mut header);
+        header.get(257..262) == Some(&b"ustar"[..])
+    }
+}
+
+impl TarZstHandler {
+    fn list_entries(reader: StorageReader, file_size: u64) -> Result<ArchiveInfo, String> {
+        let mut archive = tar::Archive::new(ZstdHandler::decoder(reader)?);
+        let mut entries = Vec::new();
+        let mut total_uncompressed_size = 0u64;
+        let mut complete = true;
+
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("Failed to read TAR.ZST entries: {}", e))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read TAR.ZST entry: {}", e))?;
+            if entries.len() >= MAX_ENTRIES || entry.raw_file_position() > MAX_SCAN_BYTES {
+                log::warn!(
+                    "TAR.ZST 分析达到限制，已列出 {} 个条目，停止分析",
+                    entries.len()
+                );
+                complete = false;
+                break;
+            }
+
+            let header = entry.header();
+            let size = header.size().unwrap_or(0);
+            let path = entry
+                .path()
+                .map(|p| p.to_string_lossy().into_owned())
+                .map_err(|e| format!("Invalid TAR entry path: {}", e))?;
+            let modified_time = header
+                .mtime()
+                .ok()
+                .filter(|mtime| *mtime > 0)
+                .and_then(|mtime| chrono::DateTime::from_timestamp(mtime as i64, 0))
+                .map(|dt| dt.to_rfc3339());
+
+            total_uncompressed_size += size;
+            entries.push(ArchiveEntry {
+                is_dir: header.entry_type().is_dir() || path.ends_with('/'),
+                path,
+                size: size.to_string(),
+                compressed_size: None,
+                modified_time,
+                crc32: None,
+                encrypted: false,
+                index: entries.len() as u32,
+                metadata: HashMap::new(),
+            });
+        }
+
+        let analysis_status = if complete {
+            AnalysisStatus::Complete
+        } else {
+            AnalysisStatus::Partial {
+                analyzed_entries: entries.len() as u32,
+            }
+        };
+
+        Ok(ArchiveInfoBuilder::new(CompressionType::TarZst)
+            .entries(entries)
+            .total_uncompressed_size(total_uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(false)
+            .analysis_status(analysis_status)
+            .build())
+    }
+
+    /// 顺序解压直到目标条目，读取 offset 开始的 max_size 字节
+    fn extract_entry(
+        reader: StorageReader,
+        entry_path: &str,
+        max_size: usize,
+        offset: u64,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    ) -> Result<FilePreview, String> {
+        let mut archive = tar::Archive::new(ZstdHandler::decoder(reader)?);
+
+        for entry in archive
+            .entries()
+            .map_err(|e| format!("Failed to read TAR.ZST entries: {}", e))?
+        {
+            let mut entry = entry.map_err(|e| format!("Failed to read TAR.ZST entry: {}", e))?;
+            let matches = entry
+                .path()
+                .map(|p| p.to_string_lossy() == entry_path)
+                .unwrap_or(false);
+            if !matches || entry.header().entry_type().is_dir() {
+                continue;
+            }
+
+            let total_size = entry.size();
+            std::io::copy(&mut (&mut entry).take(offset), &mut std::io::sink())
+                .map_err(|e| format!("Failed to extract TAR.ZST entry: {}", e))?;
+
+            let read_size = (max_size as u64).min(total_size.saturating_sub(offset));
+            let mut content = Vec::with_capacity(read_size as usize);
+            (&mut entry)
+                .take(read_size)
+                .read_to_end(&mut content)
+                .map_err(|e| format!("Failed to extract TAR.ZST entry: {}", e))?;
+            if let Some(callback) = &progress_callback {
+                callback(content.len() as u64, read_size);
+            }
+
+            let is_truncated = offset + (content.len() as u64) < total_size;
+            return Ok(PreviewBuilder::new()
+                .content(content)
+                .total_size(total_size)
+                .with_truncated(is_truncated)
+                .build());
+        }
+
+        Err(format!(
+            "File '{}' not found in TAR.ZST archive",
+            entry_path
+        ))
+    }
+}
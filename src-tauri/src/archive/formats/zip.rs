@@ -1,8 +1,10 @@
+use crate::archive::entry_stream::EntryStream;
 use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
 /// ZIP 格式处理器
 use crate::archive::types::*;
 use crate::storage::traits::StorageClient;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 pub struct ZipHandler;
@@ -33,6 +35,7 @@ impl CompressionHandlerDispatcher for ZipHandler {
             client,
             file_path,
             entry_path,
+            None,
             max_size,
             offset,
             progress_callback,
@@ -41,6 +44,104 @@ impl CompressionHandlerDispatcher for ZipHandler {
         .await
     }
 
+    async fn extract_encrypted_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        password: &str,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        Self::extract_zip_preview_with_progress(
+            client,
+            file_path,
+            entry_path,
+            Some(password),
+            max_size,
+            offset,
+            progress_callback,
+            cancel_rx,
+        )
+        .await
+    }
+
+    async fn extract_all_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        target_dir: &Path,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<ExtractSummary, String> {
+        extract_all_blocking(
+            client,
+            file_path,
+            target_dir,
+            progress_callback,
+            cancel_rx,
+            |reader, extractor| {
+                let mut archive = zip::ZipArchive::new(reader)
+                    .map_err(|e| format!("Failed to open ZIP archive: {}", e))?;
+                for index in 0..archive.len() {
+                    let mut file = match archive.by_index(index) {
+                        Ok(file) => file,
+                        // 加密条目需要密码，跳过不解压
+                        Err(zip::result::ZipError::UnsupportedArchive(reason))
+                            if reason == zip::result::ZipError::PASSWORD_REQUIRED =>
+                        {
+                            extractor.skip();
+                            continue;
+                        }
+                        Err(e) => return Err(format!("Failed to read ZIP entry: {}", e)),
+                    };
+                    // 符号链接可能指向目标目录之外，跳过不解压
+                    if file
+                        .unix_mode()
+                        .is_some_and(|mode| mode & 0o170000 == 0o120000)
+                    {
+                        extractor.skip();
+                        continue;
+                    }
+                    let path = file.name().to_string();
+                    extractor.extract(&path, file.is_dir(), &mut file)?;
+                }
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// 压缩条目按偏移读取时每次都要从条目开头解压，完整读取时通过 zip 库顺序解压
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let reader = open_storage_reader(client, file_path).await?;
+        let entry_path = entry_path.to_string();
+        let password = password.map(str::to_string);
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            let mut archive = zip::ZipArchive::new(reader)
+                .map_err(|e| format!("Failed to open ZIP archive: {}", e))?;
+            let mut file = match &password {
+                Some(password) => archive
+                    .by_name_decrypt(&entry_path, password.as_bytes())
+                    .map_err(|e| format!("Failed to read ZIP entry: {}", e))?
+                    .map_err(|_| "archive.invalidPassword".to_string())?,
+                None => archive
+                    .by_name(&entry_path)
+                    .map_err(|e| format!("Failed to read ZIP entry: {}", e))?,
+            };
+            writer.set_size(file.size());
+            writer.copy_from(&mut file)
+        })))
+    }
+
     fn compression_type(&self) -> CompressionType {
         CompressionType::Zip
     }
@@ -73,7 +174,7 @@ impl ZipHandler {
     // 这些方法从之前工作的代码迁移过来
 
     /// 在数据中查找EOCD记录位置
-    fn find_eocd(data: &[u8]) -> Option<usize> {
+    pub(crate) fn find_eocd(data: &[u8]) -> Option<usize> {
         const EOCD_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x05, 0x06];
         const MIN_EOCD_SIZE: usize = 22;
 
@@ -373,7 +474,7 @@ impl ZipHandler {
     }
 
     /// 解析中央目录数据
-    fn parse_central_directory(
+    pub(crate) fn parse_central_directory(
         cd_data: &[u8],
         total_entries: u64,
     ) -> Result<Vec<ArchiveEntry>, String> {
@@ -407,6 +508,9 @@ impl ZipHandler {
                 ));
             }
 
+            // 通用标志位的第 0 位表示条目已加密（ZipCrypto 或 AES）
+            let general_flags = u16::from_le_bytes([cd_data[offset + 8], cd_data[offset + 9]]);
+
             let compressed_size_32 = u32::from_le_bytes([
                 cd_data[offset + 20],
                 cd_data[offset + 21],
@@ -495,6 +599,7 @@ impl ZipHandler {
                     cd_data[offset + 18],
                     cd_data[offset + 19],
                 ])),
+                encrypted: general_flags & 0x0001 != 0,
                 index: parsed_entries as u32,
                 metadata: HashMap::new(),
             });
@@ -532,6 +637,8 @@ impl ZipHandler {
                 break;
             }
 
+            let general_flags = u16::from_le_bytes([cd_data[offset + 8], cd_data[offset + 9]]);
+
             let compression_method =
                 u16::from_le_bytes([cd_data[offset + 10], cd_data[offset + 11]]);
 
@@ -598,6 +705,7 @@ impl ZipHandler {
                     compression_method,
                     compressed_size,
                     local_header_offset,
+                    encrypted: general_flags & 0x0001 != 0,
                 }));
             }
 
@@ -784,10 +892,13 @@ impl ZipHandler {
     }
 
     /// 通过存储客户端提取ZIP文件预览（支持进度回调和取消信号）
+    /// 加密条目需要提供密码，未提供时返回 archive.passwordRequired
+    #[allow(clippy::too_many_arguments)]
     async fn extract_zip_preview_with_progress(
         client: Arc<dyn StorageClient>,
         file_path: &str,
         entry_path: &str,
+        password: Option<&str>,
         max_size: usize,
         offset: Option<u64>,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
@@ -804,6 +915,21 @@ impl ZipHandler {
                 .await?
                 .ok_or_else(|| "File not found in archive".to_string())?;
 
+        if file_info.encrypted {
+            let password = password.ok_or_else(|| "archive.passwordRequired".to_string())?;
+            return Self::read_encrypted_content(
+                client,
+                file_path,
+                file_size,
+                entry_path,
+                password,
+                max_size,
+                offset.unwrap_or(0),
+                progress_callback,
+            )
+            .await;
+        }
+
         // 空文件直接返回
         if file_info.compressed_size == 0 {
             return Ok(PreviewBuilder::new()
@@ -826,6 +952,42 @@ impl ZipHandler {
         .await
     }
 
+    /// 解密并读取加密条目（ZipCrypto / AES），密码错误时返回 archive.invalidPassword
+    /// 加密数据无法按范围直接读取，通过 StorageReader 顺序解密解压到 offset 处
+    #[allow(clippy::too_many_arguments)]
+    async fn read_encrypted_content(
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        file_size: u64,
+        entry_path: &str,
+        password: &str,
+        max_size: usize,
+        offset: u64,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    ) -> Result<FilePreview, String> {
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+        let entry_path = entry_path.to_string();
+        let password = password.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let mut archive = zip::ZipArchive::new(reader)
+                .map_err(|e| format!("Failed to open ZIP archive: {}", e))?;
+            let file = archive
+                .by_name_decrypt(&entry_path, password.as_bytes())
+                .map_err(|e| format!("Failed to read ZIP entry: {}", e))?
+                .map_err(|_| "archive.invalidPassword".to_string())?;
+            let total_size = file.size();
+            read_stream_preview(file, offset, max_size, Some(total_size), progress_callback)
+        })
+        .await
+        .map_err(|e| format!("Preview task failed: {}", e))?
+    }
+
     /// 根据压缩方法选择读取策略
     async fn read_zip_content_with_strategy(
         client: Arc<dyn StorageClient>,
@@ -1217,4 +1379,5 @@ struct ZipFileInfo {
     compression_method: u16,
     compressed_size: u64,
     local_header_offset: u64,
+    encrypted: bool,
 }
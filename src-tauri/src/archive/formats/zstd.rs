@@ -0,0 +1,189 @@
+use crate::archive::entry_stream::EntryStream;
+use crate::archive::formats::{common::*, CompressionHandlerDispatcher};
+/// ZSTD 格式处理器
+/// 通过 StorageReader 按需读取压缩数据并流式解压，预览时只解压到所需位置
+use crate::archive::types::*;
+use crate::storage::traits::StorageClient;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+/// ZSTD 帧魔数
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+pub struct ZstdHandler;
+
+#[async_trait::async_trait]
+impl CompressionHandlerDispatcher for ZstdHandler {
+    async fn analyze_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        filename: &str,
+        _max_size: Option<u32>,
+    ) -> Result<ArchiveInfo, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        let entry_name = stream_entry_name(filename, &[".zst", ".zstd"]);
+        tokio::task::spawn_blocking(move || Self::analyze(reader, file_size, entry_name))
+            .await
+            .map_err(|e| format!("ZSTD analysis task failed: {}", e))?
+    }
+
+    async fn extract_preview_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        max_size: usize,
+        offset: Option<u64>,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+        _cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<FilePreview, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+
+        tokio::task::spawn_blocking(move || {
+            Self::extract(
+                reader,
+                file_size,
+                max_size,
+                offset.unwrap_or(0),
+                progress_callback,
+            )
+        })
+        .await
+        .map_err(|e| format!("ZSTD extraction task failed: {}", e))?
+    }
+
+    async fn open_entry_stream_with_client(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        _entry_path: &str,
+        _password: Option<&str>,
+    ) -> Result<Option<EntryStream>, String> {
+        let file_size = client
+            .get_file_size(file_path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let mut reader = StorageReader::new(
+            client,
+            file_path,
+            file_size,
+            tokio::runtime::Handle::current(),
+        );
+        Ok(Some(EntryStream::spawn_blocking(move |writer| {
+            if let Some(size) = Self::frame_content_size(&mut reader, file_size)? {
+                writer.set_size(size);
+            }
+            writer.copy_from(&mut Self::decoder(reader)?)
+        })))
+    }
+
+    fn compression_type(&self) -> CompressionType {
+        CompressionType::Zstd
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.starts_with(&ZSTD_MAGIC)
+    }
+}
+
+impl ZstdHandler {
+    /// 创建流式解码器
+    pub fn decoder<R: Read>(
+        reader: R,
+    ) -> Result<zstd::stream::read::Decoder<'static, std::io::BufReader<R>>, String> {
+        zstd::stream::read::Decoder::new(reader)
+            .map_err(|e| format!("Failed to create zstd decoder: {}", e))
+    }
+
+    /// 读取帧头记录的原始大小（压缩时未写入则为 None），读取后回到文件开头
+    fn frame_content_size(
+        reader: &mut StorageReader,
+        file_size: u64,
+    ) -> Result<Option<u64>, String> {
+        let mut header = vec![0u8; 18.min(file_size as usize)];
+        reader
+            .read_exact(&mut header)
+            .map_err(|e| format!("Failed to read ZSTD header: {}", e))?;
+        reader
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| format!("Failed to seek ZSTD data: {}", e))?;
+
+        if !header.starts_with(&ZSTD_MAGIC) {
+            return Err("Invalid ZSTD header".to_string());
+        }
+        Ok(zstd::zstd_safe::get_frame_content_size(&header)
+            .ok()
+            .flatten())
+    }
+
+    /// 优先使用帧头记录的原始大小，否则按样本压缩比估算
+    fn analyze(
+        mut reader: StorageReader,
+        file_size: u64,
+        entry_name: String,
+    ) -> Result<ArchiveInfo, String> {
+        let uncompressed_size = match Self::frame_content_size(&mut reader, file_size)? {
+            Some(size) => size,
+            None => estimate_stream_size(reader, file_size, Self::decoder)?,
+        };
+
+        let entry = ArchiveEntry {
+            path: entry_name,
+            size: uncompressed_size.to_string(),
+            compressed_size: Some(file_size.to_string()),
+            is_dir: false,
+            modified_time: None,
+            crc32: None,
+            encrypted: false,
+            index: 0,
+            metadata: HashMap::new(),
+        };
+
+        Ok(ArchiveInfoBuilder::new(CompressionType::Zstd)
+            .entries(vec![entry])
+            .total_uncompressed_size(uncompressed_size)
+            .total_compressed_size(file_size)
+            .supports_streaming(true)
+            .supports_random_access(false)
+            .analysis_status(AnalysisStatus::Complete)
+            .build())
+    }
+
+    fn extract(
+        mut reader: StorageReader,
+        file_size: u64,
+        max_size: usize,
+        offset: u64,
+        progress_callback: Option<Box<dyn Fn(u64, u64) + Send + Sync>>,
+    ) -> Result<FilePreview, String> {
+        let content_size = Self::frame_content_size(&mut reader, file_size)?;
+        read_stream_preview(
+            Self::decoder(reader)?,
+            offset,
+            max_size,
+            content_size,
+            progress_callback,
+        )
+    }
+}
@@ -1,13 +1,27 @@
+use crate::archive::entry_stream::{EntryScan, EntryStream, STREAM_CHUNK_SIZE};
+use crate::archive::formats::CompressionHandlerDispatcher;
+use crate::archive::nested::resolve_nested_entry;
 use crate::archive::{formats, types::*};
+use crate::preview::steps::text_encoding;
 use crate::storage::traits::StorageClient;
+use std::path::Path;
 use std::sync::Arc;
 
 /// 压缩包处理器的统一入口
-pub struct ArchiveHandler;
+pub struct ArchiveHandler {
+    /// 加密条目的密码
+    password: Option<String>,
+}
 
 impl ArchiveHandler {
     pub fn new() -> Self {
-        Self
+        Self { password: None }
+    }
+
+    /// 设置读取加密条目使用的密码
+    pub fn with_password(mut self, password: Option<String>) -> Self {
+        self.password = password.filter(|p| !p.is_empty());
+        self
     }
 
     /// 分析压缩包结构（统一StorageClient接口）
@@ -18,45 +32,31 @@ impl ArchiveHandler {
         filename: String,
         max_size: Option<u32>,
     ) -> Result<ArchiveInfo, String> {
-        let compression_type = CompressionType::from_filename(&filename);
+        let handler = Self::select_handler(client.as_ref(), &file_path, &filename).await?;
 
-        // 检查是否支持该格式
-        match compression_type {
-            CompressionType::SevenZip => {
-                return Err("archive.format.7z.not.supported".to_string());
-            }
-            CompressionType::Rar => {
-                return Err("archive.format.rar.not.supported".to_string());
-            }
-            CompressionType::Brotli => {
-                return Err("archive.format.brotli.not.supported".to_string());
-            }
-            CompressionType::Lz4 => {
-                return Err("archive.format.lz4.not.supported".to_string());
-            }
-            CompressionType::Zstd => {
-                return Err("archive.format.zstd.not.supported".to_string());
+        // 通过 StorageClient 进行流式分析
+        let info = handler
+            .analyze_with_client(client.clone(), &file_path, &filename, max_size)
+            .await?;
+
+        // 提供了密码时用第一个加密条目校验，密码错误尽早返回
+        if let Some(password) = &self.password {
+            if let Some(entry) = info.entries.iter().find(|e| e.encrypted && !e.is_dir) {
+                handler
+                    .extract_encrypted_preview_with_client(
+                        client,
+                        &file_path,
+                        &entry.path,
+                        password,
+                        1,
+                        None,
+                        None,
+                        None,
+                    )
+                    .await?;
             }
-            _ => {}
         }
-
-        let handler = if matches!(compression_type, CompressionType::Unknown) {
-            // 通过 StorageClient 读取文件头部来检测格式
-            let header_data = client
-                .read_file_range(&file_path, 0, 512)
-                .await
-                .map_err(|e| format!("Failed to read file header: {}", e))?;
-            formats::detect_format_and_get_handler(&header_data)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        } else {
-            formats::get_handler(&compression_type)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        };
-
-        // 通过 StorageClient 进行流式分析
-        handler
-            .analyze_with_client(client, &file_path, &filename, max_size)
-            .await
+        Ok(info)
     }
 
     /// 获取文件预览
@@ -74,60 +74,267 @@ impl ArchiveHandler {
     where
         F: Fn(u64, u64) + Send + Sync + 'static,
     {
-        let compression_type = CompressionType::from_filename(&filename);
-
-        // 检查是否支持该格式
-        match compression_type {
-            CompressionType::SevenZip => {
-                return Err("archive.format.7z.not.supported".to_string());
-            }
-            CompressionType::Rar => {
-                return Err("archive.format.rar.not.supported".to_string());
-            }
-            CompressionType::Brotli => {
-                return Err("archive.format.brotli.not.supported".to_string());
-            }
-            CompressionType::Lz4 => {
-                return Err("archive.format.lz4.not.supported".to_string());
-            }
-            CompressionType::Zstd => {
-                return Err("archive.format.zstd.not.supported".to_string());
-            }
-            _ => {}
-        }
-
-        let handler = if matches!(compression_type, CompressionType::Unknown) {
-            let header_data = client
-                .read_file_range(&file_path, 0, 512)
-                .await
-                .map_err(|e| format!("Failed to read file header: {}", e))?;
-            formats::detect_format_and_get_handler(&header_data)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
-        } else {
-            formats::get_handler(&compression_type)
-                .ok_or_else(|| "Unsupported archive format".to_string())?
+        // 条目在内层压缩包中时，改为从内层压缩包读取
+        let (client, inner_path, entry_path) =
+            resolve_nested_entry(client, file_path.clone(), entry_path, self.password.clone());
+        let filename = match inner_path.strip_prefix(&file_path) {
+            Some("") | None => filename,
+            Some(inner) => inner.rsplit(['/', '!']).next().unwrap_or(inner).to_string(),
         };
+        let file_path = inner_path;
+        let handler = Self::select_handler(client.as_ref(), &file_path, &filename).await?;
 
         // 如果没有指定大小限制，使用尽可能大的限制（用于下载完整文件）
         let max_size = max_preview_size.map(|s| s as usize).unwrap_or(usize::MAX); // 使用 usize 的最大值
 
         // 统一使用支持进度回调的方法
+        let boxed_callback = progress_callback.map(|callback| {
+            let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
+            boxed
+        });
+        let mut preview = match &self.password {
+            Some(password) => {
+                handler
+                    .extract_encrypted_preview_with_client(
+                        client,
+                        &file_path,
+                        &entry_path,
+                        password,
+                        max_size,
+                        offset,
+                        boxed_callback,
+                        cancel_rx,
+                    )
+                    .await
+            }
+            None => {
+                handler
+                    .extract_preview_with_client(
+                        client,
+                        &file_path,
+                        &entry_path,
+                        max_size,
+                        offset,
+                        boxed_callback,
+                        cancel_rx,
+                    )
+                    .await
+            }
+        }?;
+
+        preview.encoding = text_encoding(&preview.content, preview.is_truncated)
+            .map(|encoding| encoding.name().to_string());
+        Ok(preview)
+    }
+
+    /// 将压缩包的所有条目解压到本地目录
+    /// 进度回调参数为（已解压条目数, 已写入字节数），收到取消信号时停止，已写出的文件保留
+    pub async fn extract_all_with_client<F>(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        target_dir: &Path,
+        progress_callback: Option<F>,
+        cancel_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<ExtractSummary, String>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        let handler = Self::select_handler(client.as_ref(), &file_path, &filename).await?;
+
+        std::fs::create_dir_all(target_dir)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+
         let boxed_callback = progress_callback.map(|callback| {
             let boxed: Box<dyn Fn(u64, u64) + Send + Sync> = Box::new(callback);
             boxed
         });
         handler
-            .extract_preview_with_client(
-                client,
+            .extract_all_with_client(client, &file_path, target_dir, boxed_callback, cancel_rx)
+            .await
+    }
+
+    /// 顺序读取条目的全部内容
+    /// 只能顺序解压的格式在一次解压中读完，其他格式按偏移分块读取预览
+    pub async fn open_entry_stream(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        entry_path: String,
+    ) -> Result<EntryStream, String> {
+        let (client, inner_path, entry_path) =
+            resolve_nested_entry(client, file_path.clone(), entry_path, self.password.clone());
+        let filename = match inner_path.strip_prefix(&file_path) {
+            Some("") | None => filename,
+            Some(inner) => inner.rsplit(['/', '!']).next().unwrap_or(inner).to_string(),
+        };
+        let file_path = inner_path;
+        let handler = Self::select_handler(client.as_ref(), &file_path, &filename).await?;
+
+        if let Some(stream) = handler
+            .open_entry_stream_with_client(
+                client.clone(),
                 &file_path,
                 &entry_path,
+                self.password.as_deref(),
+            )
+            .await?
+        {
+            return Ok(stream);
+        }
+
+        let (sender, stream) = EntryStream::channel();
+        let password = self.password.clone();
+        tokio::spawn(async move {
+            let mut offset = 0u64;
+            loop {
+                let preview = Self::extract_preview(
+                    handler.as_ref(),
+                    password.as_deref(),
+                    client.clone(),
+                    &file_path,
+                    &entry_path,
+                    STREAM_CHUNK_SIZE,
+                    offset,
+                )
+                .await;
+                let preview = match preview {
+                    Ok(preview) => preview,
+                    Err(e) => {
+                        sender.send(Err(e)).await;
+                        break;
+                    }
+                };
+                if let Ok(size) = preview.total_size.parse() {
+                    sender.set_size(size);
+                }
+                let done = !preview.is_truncated || preview.content.is_empty();
+                offset += preview.content.len() as u64;
+                if !sender.send(Ok(preview.content)).await || done {
+                    break;
+                }
+            }
+        });
+        Ok(stream)
+    }
+
+    /// 读取多个条目的内容（每个最多 max_size 字节），按读取顺序逐个返回
+    /// 只能顺序解压的格式在一次遍历中读完，其他格式逐个读取条目预览，读取失败的条目被跳过
+    pub async fn scan_entries(
+        &self,
+        client: Arc<dyn StorageClient>,
+        file_path: String,
+        filename: String,
+        entry_paths: Vec<String>,
+        max_size: u64,
+    ) -> Result<EntryScan, String> {
+        let handler = Self::select_handler(client.as_ref(), &file_path, &filename).await?;
+
+        if let Some(scan) = handler
+            .scan_entries_with_client(
+                client.clone(),
+                &file_path,
+                entry_paths.iter().cloned().collect(),
                 max_size,
-                offset,
-                boxed_callback,
-                cancel_rx,
             )
+            .await?
+        {
+            return Ok(scan);
+        }
+
+        let (sender, scan) = EntryScan::channel();
+        let password = self.password.clone();
+        let max_size = max_size.min(usize::MAX as u64) as usize;
+        tokio::spawn(async move {
+            for entry_path in entry_paths {
+                let preview = Self::extract_preview(
+                    handler.as_ref(),
+                    password.as_deref(),
+                    client.clone(),
+                    &file_path,
+                    &entry_path,
+                    max_size,
+                    0,
+                )
+                .await;
+                match preview {
+                    Ok(preview) => {
+                        if sender
+                            .send(Ok((entry_path, preview.content)))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Err(e) => log::debug!("读取压缩包条目失败 {}!{}: {}", file_path, entry_path, e),
+                }
+            }
+        });
+        Ok(scan)
+    }
+
+    /// 按文件名选择格式处理器，无法从文件名判断时读取文件头部检测
+    async fn select_handler(
+        client: &dyn StorageClient,
+        file_path: &str,
+        filename: &str,
+    ) -> Result<Box<dyn CompressionHandlerDispatcher>, String> {
+        let compression_type = CompressionType::from_filename(filename);
+        if !matches!(compression_type, CompressionType::Unknown) {
+            return formats::get_handler(&compression_type)
+                .ok_or_else(|| "Unsupported archive format".to_string());
+        }
+
+        // 通过 StorageClient 读取文件头部来检测格式
+        let header_data = client
+            .read_file_range(file_path, 0, 512)
             .await
+            .map_err(|e| format!("Failed to read file header: {}", e))?;
+        formats::detect_format_and_get_handler(&header_data)
+            .ok_or_else(|| "Unsupported archive format".to_string())
     }
 
-    // 辅助方法
+    /// 读取条目从 offset 开始的一段，有密码时按加密条目读取
+    async fn extract_preview(
+        handler: &dyn CompressionHandlerDispatcher,
+        password: Option<&str>,
+        client: Arc<dyn StorageClient>,
+        file_path: &str,
+        entry_path: &str,
+        max_size: usize,
+        offset: u64,
+    ) -> Result<FilePreview, String> {
+        match password {
+            Some(password) => {
+                handler
+                    .extract_encrypted_preview_with_client(
+                        client,
+                        file_path,
+                        entry_path,
+                        password,
+                        max_size,
+                        Some(offset),
+                        None,
+                        None,
+                    )
+                    .await
+            }
+            None => {
+                handler
+                    .extract_preview_with_client(
+                        client,
+                        file_path,
+                        entry_path,
+                        max_size,
+                        Some(offset),
+                        None,
+                        None,
+                    )
+                    .await
+            }
+        }
+    }
 }
@@ -1,3 +1,9 @@
+pub mod entry_index;
+pub mod entry_stream;
 pub mod formats;
 pub mod handlers;
+pub mod nested;
+pub mod search;
+pub mod settings;
+pub mod stream_control;
 pub mod types;
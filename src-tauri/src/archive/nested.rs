@@ -0,0 +1,295 @@
+// 嵌套压缩包（压缩包中的压缩包）
+// 条目路径可以继续指向内层压缩包中的条目，如 `outer.zip!inner.tar.gz!data.csv`：
+// 外层条目被包装为只读的虚拟文件，范围读取转换为外层压缩包的条目预览，现有的格式处理器无需解压整个内层压缩包；
+// 从头开始的顺序读取从同一个数据流继续，只能顺序解压的外层格式不会为每一块从头解压
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
+
+use crate::archive::entry_stream::EntryStream;
+use crate::preview::source::PreviewSource;
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
+};
+use crate::storage::vfs::split_archive_entry;
+
+/// 每次从外层压缩包读取的最小大小，内层格式处理器的小块读取（头部、目录）从缓存中返回
+const READ_AHEAD_SIZE: u64 = 4 * 1024 * 1024; // 4MB
+
+/// 读取位置在数据流当前位置之后不超过该距离时从数据流继续读取，更远或向前的读取按偏移读取预览
+const MAX_STREAM_SKIP: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 条目路径中包含内层压缩包时，逐层把外层条目包装为虚拟文件
+/// 返回可以直接读取最内层压缩包的客户端、最内层压缩包的路径和剩余的条目路径，没有嵌套时原样返回
+pub fn resolve_nested_entry(
+    client: Arc<dyn StorageClient>,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+) -> (Arc<dyn StorageClient>, String, String) {
+    let mut client = client;
+    let mut archive_path = archive_path;
+    let mut entry_path = entry_path;
+
+    while let Some((inner, rest)) = split_archive_entry(&entry_path)
+        .map(|(inner, rest)| (inner.to_string(), rest.trim_start_matches('/').to_string()))
+    {
+        let entry_client = ArchiveEntryClient::new(client, archive_path, inner, password.clone());
+        archive_path = entry_client.path.clone();
+        entry_path = rest;
+        client = Arc::new(entry_client);
+    }
+
+    (client, archive_path, entry_path)
+}
+
+/// 把压缩包中的一个条目作为单个只读文件提供给压缩包处理器
+/// 文件路径为 `<外层压缩包路径>!<条目路径>`，其他路径返回 NotFound
+pub struct ArchiveEntryClient {
+    parent: Arc<dyn StorageClient>,
+    archive_path: String,
+    entry_path: String,
+    password: Option<String>,
+    path: String,
+    /// 条目解压后的大小，第一次读取后得到
+    size: Mutex<Option<u64>>,
+    /// 最近一次读取的数据块（起始偏移，数据）
+    block: Mutex<Option<(u64, Arc<Vec<u8>>)>>,
+    /// 从条目开头打开的数据流（已读取到的位置，数据流）
+    stream: tokio::sync::Mutex<Option<(u64, EntryStream)>>,
+}
+
+impl ArchiveEntryClient {
+    pub fn new(
+        parent: Arc<dyn StorageClient>,
+        archive_path: String,
+        entry_path: String,
+        password: Option<String>,
+    ) -> Self {
+        let path = format!("{}!{}", archive_path, entry_path);
+        Self {
+            parent,
+            archive_path,
+            entry_path,
+            password,
+            path,
+            size: Mutex::new(None),
+            block: Mutex::new(None),
+            stream: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// 条目作为文件时的路径
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn check_path(&self, path: &str) -> Result<(), StorageError> {
+        if path == self.path {
+            Ok(())
+        } else {
+            Err(StorageError::NotFound(path.to_string()))
+        }
+    }
+
+    /// 从外层压缩包读取条目的一段数据
+    async fn read_entry(&self, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        let data = PreviewSource::new(self.archive_path.clone(), Some(self.entry_path.clone()))
+            .with_password(self.password.clone())
+            .read(self.parent.clone(), start, length)
+            .await
+            .map_err(StorageError::RequestFailed)?;
+        *self.size.lock().unwrap() = Some(data.total_size);
+        Ok(data.bytes)
+    }
+
+    /// 读取 [start, start + length)，优先使用最近读取的数据块
+    async fn read_range(&self, start: u64, length: u64) -> Result<Vec<u8>, StorageError> {
+        let size = *self.size.lock().unwrap();
+        if let Some((block_start, block)) = self.block.lock().unwrap().as_ref() {
+            let block_end = block_start + block.len() as u64;
+            let end = start.saturating_add(length);
+            if start >= *block_start && (end <= block_end || Some(block_end) == size) {
+                let from = (start - block_start) as usize;
+                let to = end.min(block_end) as usize - *block_start as usize;
+                return Ok(block[from.min(to)..to].to_vec());
+            }
+        }
+
+        let bytes = match self.read_stream(start, length).await? {
+            Some(bytes) => bytes,
+            None => self.read_entry(start, length.max(READ_AHEAD_SIZE)).await?,
+        };
+        let result = bytes[..bytes.len().min(length as usize)].to_vec();
+        *self.block.lock().unwrap() = Some((start, Arc::new(bytes)));
+        Ok(result)
+    }
+
+    /// 从数据流读取 [start, start + length) 所在的整块数据，返回从 start 开始的数据
+    /// 从头读取时打开新的数据流；读取位置不在数据流当前位置附近时返回 None
+    async fn read_stream(&self, start: u64, length: u64) -> Result<Option<Vec<u8>>, StorageError> {
+        let mut guard = self.stream.lock().await;
+        let reusable = matches!(
+            guard.as_ref(),
+            Some((position, _)) if *position <= start && start - position <= MAX_STREAM_SKIP
+        );
+        if !reusable {
+            if start > 0 {
+                return Ok(None);
+            }
+            let stream =
+                PreviewSource::new(self.archive_path.clone(), Some(self.entry_path.clone()))
+                    .with_password(self.password.clone())
+                    .open_stream(self.parent.clone())
+                    .await
+                    .map_err(StorageError::RequestFailed)?;
+            *guard = Some((0, stream));
+        }
+
+        let Some((position, stream)) = guard.as_mut() else {
+            return Ok(None);
+        };
+        let end = start.saturating_add(length);
+        let mut data = Vec::new();
+        while *position < end {
+            let chunk = match stream.next_chunk().await {
+                Some(Ok(chunk)) if !chunk.is_empty() => chunk,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => {
+                    *guard = None;
+                    return Err(StorageError::RequestFailed(e));
+                }
+                None => {
+                    // 条目已读完，当前位置即为条目大小
+                    *self.size.lock().unwrap() = Some(*position);
+                    break;
+                }
+            };
+            let chunk_start = *position;
+            *position += chunk.len() as u64;
+            if *position > start {
+                let from = start.saturating_sub(chunk_start) as usize;
+                data.extend_from_slice(&chunk[from..]);
+            }
+        }
+        if let Some(size) = stream.size() {
+            *self.size.lock().unwrap() = Some(size);
+        }
+        Ok(Some(data))
+    }
+}
+
+#[async_trait]
+impl StorageClient for ArchiveEntryClient {
+    async fn connect(&mut self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        // 外层客户端在创建前已经连接
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.parent.is_connected().await
+    }
+
+    async fn list_directory(
+        &self,
+        path: &str,
+        _options: Option<&ListOptions>,
+    ) -> Result<DirectoryResult, StorageError> {
+        Err(StorageError::ProtocolNotSupported(format!(
+            "{} is a file inside an archive",
+            path
+        )))
+    }
+
+    async fn read_file_range(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.check_path(path)?;
+        self.read_range(start, length).await
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let size = self.get_file_size(path).await?;
+        if size > u32::MAX as u64 {
+            return Err(StorageError::RequestFailed(format!(
+                "Archive entry {} is too large to read at once",
+                path
+            )));
+        }
+        self.read_entry(0, size).await
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        self.check_path(path)?;
+        if let Some(size) = *self.size.lock().unwrap() {
+            return Ok(size);
+        }
+        self.read_range(0, 1).await?;
+        if let Some(size) = *self.size.lock().unwrap() {
+            return Ok(size);
+        }
+        // 数据流未提供大小的格式（如单个 gzip）从预览结果中获取
+        self.read_entry(0, 1).await?;
+        Ok(self.size.lock().unwrap().unwrap_or(0))
+    }
+
+    async fn download_file(
+        &self,
+        path: &str,
+        save_path: &std::path::Path,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        self.check_path(path)?;
+
+        let mut stream =
+            PreviewSource::new(self.archive_path.clone(), Some(self.entry_path.clone()))
+                .with_password(self.password.clone())
+                .open_stream(self.parent.clone())
+                .await
+                .map_err(StorageError::RequestFailed)?;
+        let mut file = tokio::fs::File::create(save_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to create file: {}", e)))?;
+        let mut offset = 0u64;
+        loop {
+            if let Some(rx) = cancel_rx.as_mut() {
+                if rx.try_recv().is_ok() {
+                    drop(file);
+                    let _ = tokio::fs::remove_file(save_path).await;
+                    return Err(StorageError::RequestFailed(
+                        "download.cancelled".to_string(),
+                    ));
+                }
+            }
+            let Some(bytes) = stream.next_chunk().await else {
+                break;
+            };
+            let bytes = bytes.map_err(StorageError::RequestFailed)?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|e| StorageError::IoError(format!("Failed to write file: {}", e)))?;
+            offset += bytes.len() as u64;
+            if let Some(callback) = &progress_callback {
+                callback(offset, stream.size().unwrap_or(offset));
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to write file: {}", e)))
+    }
+
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        // 条目随外层压缩包变化，使用外层压缩包的 ETag
+        self.check_path(path)?;
+        self.parent.get_object_etag(&self.archive_path).await
+    }
+
+    fn validate_config(&self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,362 @@
+// 跨压缩包内容搜索
+// 在多个压缩包（如同一目录下的所有分片）中查找条目路径或文本内容包含关键字的位置，
+// 每个压缩包搜索完成后立即回调该压缩包的匹配结果，所有搜索共享同一个并发上限；
+// 每个压缩包的条目内容在一次遍历中读取，只能顺序解压的格式不会为每个条目从头解压
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock};
+use tokio::sync::{broadcast, Semaphore};
+
+use crate::archive::handlers::ArchiveHandler;
+use crate::archive::nested::{resolve_nested_entry, ArchiveEntryClient};
+use crate::storage::traits::StorageClient;
+use crate::storage::vfs::split_archive_entry;
+use crate::utils::mime::is_binary_content;
+use crate::utils::result_limit::ResultLimit;
+
+/// 所有搜索同时处理的压缩包数量上限
+const MAX_CONCURRENT_ARCHIVES: usize = 4;
+
+/// 默认只搜索不超过该大小的条目内容
+const DEFAULT_MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024; // 16MB
+
+/// 默认最多返回的匹配数量
+const DEFAULT_MAX_RESULTS: u32 = 1000;
+
+/// 匹配行在结果中保留的最大字符数
+const MAX_LINE_CHARS: usize = 200;
+
+static SEARCH_PERMITS: LazyLock<Arc<Semaphore>> =
+    LazyLock::new(|| Arc::new(Semaphore::new(MAX_CONCURRENT_ARCHIVES)));
+
+/// 压缩包搜索选项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSearchOptions {
+    /// 是否区分大小写，默认不区分
+    pub case_sensitive: Option<bool>,
+    /// 是否搜索条目内容，默认 true；为 false 时只匹配条目路径
+    pub search_content: Option<bool>,
+    /// 只搜索指定扩展名的条目内容（不含点，大小写不敏感），为空表示全部
+    pub extensions: Option<Vec<String>>,
+    /// 跳过大于该大小的条目内容，默认 16MB
+    pub max_entry_size: Option<String>,
+    /// 最多返回的匹配数量，默认 1000
+    pub max_results: Option<u32>,
+    /// 本次搜索同时处理的压缩包数量，不超过全局上限 4
+    pub concurrency: Option<u32>,
+}
+
+/// 一处匹配
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSearchMatch {
+    pub archive_path: String,
+    pub entry_path: String,
+    /// 匹配所在行（从 1 开始），条目路径匹配时为空
+    pub line: Option<u32>,
+    /// 匹配行的内容（过长时截断），条目路径匹配时为条目路径
+    pub text: String,
+}
+
+/// 搜索失败的压缩包
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSearchFailure {
+    pub archive_path: String,
+    pub error: String,
+}
+
+/// 一个压缩包的搜索结果，搜索过程中逐个发送
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSearchBatch {
+    pub search_id: String,
+    pub archive_path: String,
+    pub matches: Vec<ArchiveSearchMatch>,
+}
+
+/// 搜索汇总
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSearchReport {
+    pub search_id: String,
+    pub query: String,
+    pub total_matches: u32,
+    pub searched_archives: u32,
+    pub searched_entries: u32,
+    pub failures: Vec<ArchiveSearchFailure>,
+    /// 是否因达到匹配数量上限而提前结束
+    pub truncated: bool,
+    /// 是否被取消，取消时只包含已完成的压缩包的结果
+    pub cancelled: bool,
+}
+
+/// 跨压缩包搜索器
+pub struct ArchiveSearcher {
+    query: String,
+    case_sensitive: bool,
+    search_content: bool,
+    extensions: Option<Vec<String>>,
+    max_entry_size: u64,
+    results: ResultLimit,
+    searched_entries: AtomicU32,
+}
+
+impl ArchiveSearcher {
+    /// 并发搜索多个压缩包，每个压缩包完成后通过 on_batch 回调其匹配结果
+    /// 收到取消信号时停止所有未完成的压缩包
+    pub async fn search<F>(
+        client: Arc<dyn StorageClient>,
+        search_id: &str,
+        archive_paths: Vec<String>,
+        query: &str,
+        options: &ArchiveSearchOptions,
+        mut cancel_rx: broadcast::Receiver<()>,
+        on_batch: F,
+    ) -> Result<ArchiveSearchReport, String>
+    where
+        F: Fn(ArchiveSearchBatch) + Send + Sync,
+    {
+        if query.is_empty() {
+            return Err("Search query is required".to_string());
+        }
+
+        let case_sensitive = options.case_sensitive.unwrap_or(false);
+        let searcher = Self {
+            query: if case_sensitive {
+                query.to_string()
+            } else {
+                query.to_lowercase()
+            },
+            case_sensitive,
+            search_content: options.search_content.unwrap_or(true),
+            extensions: options.extensions.as_ref().map(|exts| {
+                exts.iter()
+                    .map(|e| e.trim_start_matches('.').to_lowercase())
+                    .collect()
+            }),
+            max_entry_size: options
+                .max_entry_size
+                .as_ref()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_MAX_ENTRY_SIZE),
+            results: ResultLimit::new(options.max_results.unwrap_or(DEFAULT_MAX_RESULTS)),
+            searched_entries: AtomicU32::new(0),
+        };
+        let concurrency = options
+            .concurrency
+            .unwrap_or(MAX_CONCURRENT_ARCHIVES as u32)
+            .clamp(1, MAX_CONCURRENT_ARCHIVES as u32) as usize;
+
+        let searched_archives = archive_paths.len() as u32;
+        let mut results = futures_util::stream::iter(archive_paths)
+            .map(|archive_path| {
+                let client = client.clone();
+                let searcher = &searcher;
+                async move {
+                    let result = searcher.search_archive(client, &archive_path).await;
+                    (archive_path, result)
+                }
+            })
+            .buffer_unordered(concurrency);
+
+        let mut failures = Vec::new();
+        let mut cancelled = false;
+        let mut cancellable = true;
+        loop {
+            let next = tokio::select! {
+                next = results.next() => next,
+                signal = cancel_rx.recv(), if cancellable => match signal {
+                    // 发送端已关闭表示没有取消来源，继续等待结果
+                    Err(broadcast::error::RecvError::Closed) => {
+                        cancellable = false;
+                        continue;
+                    }
+                    _ => {
+                        cancelled = true;
+                        break;
+                    }
+                },
+            };
+            let Some((archive_path, result)) = next else {
+                break;
+            };
+            match result {
+                Ok(matches) if matches.is_empty() => {}
+                Ok(matches) => on_batch(ArchiveSearchBatch {
+                    search_id: search_id.to_string(),
+                    archive_path,
+                    matches,
+                }),
+                Err(error) => failures.push(ArchiveSearchFailure {
+                    archive_path,
+                    error,
+                }),
+            }
+        }
+
+        // 丢弃未完成的搜索，正在进行的条目读取随之停止
+        drop(results);
+
+        Ok(ArchiveSearchReport {
+            search_id: search_id.to_string(),
+            query: query.to_string(),
+            total_matches: searcher.results.found(),
+            searched_archives,
+            searched_entries: searcher.searched_entries.load(Ordering::Relaxed),
+            failures,
+            truncated: searcher.results.truncated(),
+            cancelled,
+        })
+    }
+
+    /// 搜索单个压缩包，先匹配条目路径，再在一次遍历中读取需要搜索的条目内容逐行匹配
+    async fn search_archive(
+        &self,
+        client: Arc<dyn StorageClient>,
+        archive_path: &str,
+    ) -> Result<Vec<ArchiveSearchMatch>, String> {
+        let _permit = SEARCH_PERMITS
+            .acquire()
+            .await
+            .map_err(|e| format!("Failed to acquire search permit: {}", e))?;
+
+        // 嵌套在其他压缩包中的压缩包（`outer.zip!inner.tar`）包装为单个文件后搜索，结果中仍使用原路径
+        let (client, source_path) = match split_archive_entry(archive_path) {
+            Some((outer, inner)) => {
+                let (client, outer, inner) = resolve_nested_entry(
+                    client,
+                    outer.to_string(),
+                    inner.trim_start_matches('/').to_string(),
+                    None,
+                );
+                let entry_client = ArchiveEntryClient::new(client, outer, inner, None);
+                let path = entry_client.path().to_string();
+                (Arc::new(entry_client) as Arc<dyn StorageClient>, path)
+            }
+            None => (client, archive_path.to_string()),
+        };
+        let filename = archive_path
+            .rsplit(['/', '!'])
+            .next()
+            .unwrap_or(archive_path)
+            .to_string();
+        let handler = ArchiveHandler::new();
+        let info = handler
+            .analyze_archive_with_client(
+                client.clone(),
+                source_path.clone(),
+                filename.clone(),
+                None,
+            )
+            .await?;
+
+        let mut matches = Vec::new();
+        let mut content_entries = Vec::new();
+        for entry in info.entries.iter().filter(|entry| !entry.is_dir) {
+            if self.results.is_full() {
+                self.results.mark_truncated();
+                break;
+            }
+            self.searched_entries.fetch_add(1, Ordering::Relaxed);
+
+            if self.contains(&entry.path) && self.results.claim() {
+                matches.push(ArchiveSearchMatch {
+                    archive_path: archive_path.to_string(),
+                    entry_path: entry.path.clone(),
+                    line: None,
+                    text: entry.path.clone(),
+                });
+            }
+
+            let size = entry.size.parse::<u64>().unwrap_or(0);
+            if self.search_content
+                && size > 0
+                && size <= self.max_entry_size
+                && self.extension_allowed(&entry.path)
+            {
+                content_entries.push(entry.path.clone());
+            }
+        }
+
+        if content_entries.is_empty() || self.results.is_full() {
+            return Ok(matches);
+        }
+
+        let mut scan = handler
+            .scan_entries(
+                client,
+                source_path,
+                filename,
+                content_entries,
+                self.max_entry_size,
+            )
+            .await?;
+        while let Some(entry) = scan.next_entry().await {
+            let (entry_path, content) = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::debug!("读取压缩包条目失败 {}: {}", archive_path, e);
+                    break;
+                }
+            };
+            self.search_lines(archive_path, &entry_path, &content, &mut matches);
+            if self.results.is_full() {
+                self.results.mark_truncated();
+                break;
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// 逐行匹配文本内容，跳过二进制数据
+    fn search_lines(
+        &self,
+        archive_path: &str,
+        entry_path: &str,
+        content: &[u8],
+        matches: &mut Vec<ArchiveSearchMatch>,
+    ) {
+        if is_binary_content(content) {
+            return;
+        }
+
+        let text = String::from_utf8_lossy(content);
+        for (index, line) in text.lines().enumerate() {
+            if !self.contains(line) {
+                continue;
+            }
+            if !self.results.claim() {
+                break;
+            }
+            matches.push(ArchiveSearchMatch {
+                archive_path: archive_path.to_string(),
+                entry_path: entry_path.to_string(),
+                line: Some(index as u32 + 1),
+                text: line.chars().take(MAX_LINE_CHARS).collect(),
+            });
+        }
+    }
+
+    fn contains(&self, text: &str) -> bool {
+        if self.case_sensitive {
+            text.contains(&self.query)
+        } else {
+            text.to_lowercase().contains(&self.query)
+        }
+    }
+
+    fn extension_allowed(&self, path: &str) -> bool {
+        match &self.extensions {
+            Some(exts) => path
+                .rsplit_once('.')
+                .map(|(_, ext)| exts.contains(&ext.to_lowercase()))
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+}
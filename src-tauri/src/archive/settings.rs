@@ -0,0 +1,68 @@
+// 压缩包分析设置
+// 只能顺序解压的格式（如 tar.gz）列出条目时需要解压整个流，这里限制每次分析解压的数据量
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 压缩包分析设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveSettings {
+    /// 分析 tar.gz 时最多解压的数据量（MB，解压后的大小），超出后返回部分条目
+    pub tar_gz_scan_budget_mb: u32,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self {
+            tar_gz_scan_budget_mb: 256,
+        }
+    }
+}
+
+impl ArchiveSettings {
+    /// 分析 tar.gz 时最多解压的字节数
+    pub fn tar_gz_scan_budget(&self) -> u64 {
+        self.tar_gz_scan_budget_mb.max(1) as u64 * 1024 * 1024
+    }
+
+    fn settings_file() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join("archive.json"))
+    }
+
+    /// 从设置文件加载，文件不存在或损坏时使用默认值
+    fn load() -> Self {
+        Self::settings_file()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    /// 获取当前生效的设置
+    pub fn current() -> Self {
+        ARCHIVE_SETTINGS
+            .read()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// 更新并持久化设置，之后的分析生效
+    pub fn update(settings: ArchiveSettings) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        persist::write_atomic(Self::settings_file()?, content)
+            .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+        if let Ok(mut current) = ARCHIVE_SETTINGS.write() {
+            *current = settings;
+        }
+        Ok(())
+    }
+}
+
+static ARCHIVE_SETTINGS: LazyLock<RwLock<ArchiveSettings>> =
+    LazyLock::new(|| RwLock::new(ArchiveSettings::load()));
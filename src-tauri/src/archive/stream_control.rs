@@ -0,0 +1,368 @@
+// 预览流控制
+// 耗时的压缩包预览可以按请求标识暂停、恢复和取消：预览期间对存储的读取都经过 ControlledClient，
+// 大块读取和下载拆分为小块，每块之前检查状态，暂停时读取挂起，取消时进行中和之后的读取返回 preview.cancelled；
+// 解压循环通过 cancel_receiver 和 pause_gate 接收取消信号、在进度回调中等待恢复
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::{broadcast, watch};
+
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, ObjectMetadata, ProgressCallback,
+    StorageClient, StorageError,
+};
+use crate::storage::validators::Validators;
+
+/// 预览流的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamState {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// 经过 ControlledClient 的读取拆分成的块大小，暂停和取消在块之间生效
+const CONTROL_CHUNK_SIZE: u64 = 4 * 1024 * 1024; // 4MB
+
+// 进行中的预览（请求标识 -> 状态）
+static ACTIVE_STREAMS: LazyLock<Mutex<HashMap<String, Arc<watch::Sender<StreamState>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 已登记的预览流，drop 时从登记表中移除
+pub struct StreamControl {
+    request_id: String,
+    state: Arc<watch::Sender<StreamState>>,
+}
+
+impl StreamControl {
+    /// 登记预览流，同一标识已在进行时返回错误
+    pub fn register(request_id: String) -> Result<Self, String> {
+        let mut streams = ACTIVE_STREAMS.lock().unwrap();
+        if streams.contains_key(&request_id) {
+            return Err(format!("Preview is already running: {}", request_id));
+        }
+        let state = Arc::new(watch::Sender::new(StreamState::Running));
+        streams.insert(request_id.clone(), state.clone());
+        Ok(Self { request_id, state })
+    }
+
+    /// 包装存储客户端，读取时遵循暂停和取消
+    pub fn wrap(&self, client: Arc<dyn StorageClient>) -> Arc<dyn StorageClient> {
+        Arc::new(ControlledClient {
+            inner: client,
+            state: self.state.subscribe(),
+        })
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.borrow() == StreamState::Cancelled
+    }
+
+    /// 取消信号接收端，交给解压循环等接受 cancel_rx 的读取；预览结束后不再发送
+    pub fn cancel_receiver(&self) -> broadcast::Receiver<()> {
+        cancel_bridge(self.state.subscribe(), None).1
+    }
+
+    /// 进度回调：预览暂停时阻塞当前线程直到恢复或取消，用于 spawn_blocking 中的解压循环
+    pub fn pause_gate(&self) -> impl Fn(u64, u64) + Send + Sync + 'static {
+        let state = self.state.subscribe();
+        move |_, _| {
+            if *state.borrow() != StreamState::Paused {
+                return;
+            }
+            let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                return;
+            };
+            let mut state = state.clone();
+            // 在运行时的工作线程上被调用时让出线程，避免阻塞其他任务
+            tokio::task::block_in_place(|| {
+                let _ = runtime.block_on(state.wait_for(|state| *state != StreamState::Paused));
+            });
+        }
+    }
+
+    pub fn pause(request_id: &str) -> Result<(), String> {
+        Self::set_state(request_id, StreamState::Paused)
+    }
+
+    pub fn resume(request_id: &str) -> Result<(), String> {
+        Self::set_state(request_id, StreamState::Running)
+    }
+
+    pub fn cancel(request_id: &str) -> Result<(), String> {
+        Self::set_state(request_id, StreamState::Cancelled)
+    }
+
+    fn set_state(request_id: &str, next: StreamState) -> Result<(), String> {
+        let streams = ACTIVE_STREAMS.lock().unwrap();
+        let state = streams
+            .get(request_id)
+            .ok_or_else(|| format!("No active preview found for: {}", request_id))?;
+        // 已取消的预览不能再恢复
+        state.send_if_modified(|current| {
+            if *current == StreamState::Cancelled || *current == next {
+                false
+            } else {
+                *current = next;
+                true
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Drop for StreamControl {
+    fn drop(&mut self) {
+        let mut streams = ACTIVE_STREAMS.lock().unwrap();
+        if streams
+            .get(&self.request_id)
+            .is_some_and(|state| Arc::ptr_eq(state, &self.state))
+        {
+            streams.remove(&self.request_id);
+        }
+    }
+}
+
+/// 按预览流状态放行读取的存储客户端
+struct ControlledClient {
+    inner: Arc<dyn StorageClient>,
+    state: watch::Receiver<StreamState>,
+}
+
+impl ControlledClient {
+    /// 暂停时等待恢复，已取消时返回错误
+    async fn proceed(&self) -> Result<(), StorageError> {
+        let mut state = self.state.clone();
+        loop {
+            match *state.borrow_and_update() {
+                StreamState::Running => return Ok(()),
+                StreamState::Cancelled => {
+                    return Err(StorageError::RequestFailed("preview.cancelled".to_string()))
+                }
+                StreamState::Paused => {}
+            }
+            // 登记已移除（预览结束）时不再等待
+            if state.changed().await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// 读取一块数据：进行中的读取通过取消桥接收预览流和调用方的取消信号
+    async fn read_chunk(
+        &self,
+        path: &str,
+        offset: u64,
+        length: u64,
+        caller_cancel: Option<&broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.proceed().await?;
+        let (bridge, mut cancel_rx) = cancel_bridge(self.state.clone(), caller_cancel);
+        let result = self
+            .inner
+            .read_file_range_with_progress(path, offset, length, None, Some(&mut cancel_rx))
+            .await;
+        bridge.abort();
+        if *self.state.borrow() == StreamState::Cancelled {
+            return Err(cancelled());
+        }
+        result
+    }
+
+    /// 按块读取 [start, start + length)，每块之前检查暂停和取消，读到文件末尾时提前结束
+    async fn read_chunked(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        let mut data = Vec::with_capacity(length.min(CONTROL_CHUNK_SIZE) as usize);
+        let end = start.saturating_add(length);
+        let mut offset = start;
+        while offset < end {
+            check_caller_cancel(cancel_rx.as_deref_mut())?;
+            let chunk = self
+                .read_chunk(
+                    path,
+                    offset,
+                    (end - offset).min(CONTROL_CHUNK_SIZE),
+                    cancel_rx.as_deref(),
+                )
+                .await?;
+            if chunk.is_empty() {
+                break;
+            }
+            offset += chunk.len() as u64;
+            data.extend_from_slice(&chunk);
+            if let Some(callback) = &progress_callback {
+                callback(offset - start, length);
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn cancelled() -> StorageError {
+    StorageError::RequestFailed("preview.cancelled".to_string())
+}
+
+/// 调用方在块之间发出的取消
+fn check_caller_cancel(
+    cancel_rx: Option<&mut broadcast::Receiver<()>>,
+) -> Result<(), StorageError> {
+    match cancel_rx.map(|rx| rx.try_recv()) {
+        Some(Ok(())) | Some(Err(TryRecvError::Lagged(_))) => Err(cancelled()),
+        _ => Ok(()),
+    }
+}
+
+/// 把预览流的取消和调用方的取消信号转发到一个新的接收端
+/// 转发任务在预览结束（状态发送端释放）时退出，也可以通过返回的句柄提前结束
+fn cancel_bridge(
+    mut state: watch::Receiver<StreamState>,
+    caller_cancel: Option<&broadcast::Receiver<()>>,
+) -> (tokio::task::JoinHandle<()>, broadcast::Receiver<()>) {
+    let (cancel_tx, cancel_rx) = broadcast::channel(1);
+    let mut caller_cancel = caller_cancel.map(|rx| rx.resubscribe());
+    let bridge = tokio::spawn(async move {
+        let caller_cancelled = async {
+            match caller_cancel.as_mut() {
+                Some(rx) => loop {
+                    match rx.recv().await {
+                        Ok(()) | Err(RecvError::Lagged(_)) => break,
+                        // 发送端已关闭说明调用方不会再取消
+                        Err(RecvError::Closed) => std::future::pending::<()>().await,
+                    }
+                },
+                None => std::future::pending::<()>().await,
+            }
+        };
+        tokio::select! {
+            result = state.wait_for(|state| *state == StreamState::Cancelled) => {
+                if result.is_err() {
+                    return;
+                }
+            }
+            _ = caller_cancelled => {}
+        }
+        let _ = cancel_tx.send(());
+    });
+    (bridge, cancel_rx)
+}
+
+#[async_trait]
+impl StorageClient for ControlledClient {
+    async fn connect(&mut self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        // 包装的客户端在创建前已经连接
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.inner.is_connected().await
+    }
+
+    async fn list_directory(
+        &self,
+        path: &str,
+        options: Option<&ListOptions>,
+    ) -> Result<DirectoryResult, StorageError> {
+        self.proceed().await?;
+        self.inner.list_directory(path, options).await
+    }
+
+    async fn read_file_range(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.read_chunked(path, start, length, None, None).await
+    }
+
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.read_chunked(path, start, length, progress_callback, cancel_rx)
+            .await
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let size = self.get_file_size(path).await?;
+        self.read_chunked(path, 0, size, None, None).await
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        self.proceed().await?;
+        self.inner.get_file_size(path).await
+    }
+
+    async fn download_file(
+        &self,
+        path: &str,
+        save_path: &std::path::Path,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        // 按块读取后写入，暂停和取消在块之间生效
+        let size = self.get_file_size(path).await?;
+        let mut file = tokio::fs::File::create(save_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to create file: {}", e)))?;
+        let mut offset = 0;
+        while offset < size {
+            check_caller_cancel(cancel_rx.as_deref_mut())?;
+            let chunk = self
+                .read_chunk(
+                    path,
+                    offset,
+                    (size - offset).min(CONTROL_CHUNK_SIZE),
+                    cancel_rx.as_deref(),
+                )
+                .await?;
+            if chunk.is_empty() {
+                break;
+            }
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| StorageError::IoError(format!("Failed to write file: {}", e)))?;
+            offset += chunk.len() as u64;
+            if let Some(callback) = &progress_callback {
+                callback(offset, size);
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to write file: {}", e)))?;
+        Ok(())
+    }
+
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        self.inner.get_object_etag(path).await
+    }
+
+    async fn get_metadata(&self, path: &str) -> Result<ObjectMetadata, StorageError> {
+        self.inner.get_metadata(path).await
+    }
+
+    fn observed_validators(&self, path: &str) -> Option<Validators> {
+        self.inner.observed_validators(path)
+    }
+
+    fn local_path(&self, path: &str) -> Option<std::path::PathBuf> {
+        self.inner.local_path(path)
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        self.inner.validate_config(config)
+    }
+}
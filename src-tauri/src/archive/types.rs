@@ -9,6 +9,7 @@ pub enum CompressionType {
     Gzip,
     Tar,
     TarGz,
+    TarZst,
     Brotli,
     Lz4,
     Zstd,
@@ -28,6 +29,11 @@ impl CompressionType {
             CompressionType::Tar
         } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
             CompressionType::TarGz
+        } else if lower.ends_with(".tar.zst")
+            || lower.ends_with(".tar.zstd")
+            || lower.ends_with(".tzst")
+        {
+            CompressionType::TarZst
         } else if lower.ends_with(".br") {
             CompressionType::Brotli
         } else if lower.ends_with(".lz4") {
@@ -43,6 +49,29 @@ impl CompressionType {
         }
     }
 
+    /// 根据文件头签名检测压缩格式
+    /// TAR.GZ / TAR.ZST 需要解压后才能与普通 GZIP / ZSTD 区分，这里统一识别为单文件压缩格式
+    pub fn from_content(data: &[u8]) -> Self {
+        if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") {
+            CompressionType::Zip
+        } else if data.starts_with(&[0x1f, 0x8b]) {
+            CompressionType::Gzip
+        } else if data.starts_with(b"7z\xbc\xaf\x27\x1c") {
+            CompressionType::SevenZip
+        } else if data.starts_with(b"Rar!\x1a\x07\x00") || data.starts_with(b"Rar!\x1a\x07\x01\x00")
+        {
+            CompressionType::Rar
+        } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            CompressionType::Zstd
+        } else if data.starts_with(&[0x04, 0x22, 0x4d, 0x18]) {
+            CompressionType::Lz4
+        } else if data.get(257..262) == Some(&b"ustar"[..]) {
+            CompressionType::Tar
+        } else {
+            CompressionType::Unknown
+        }
+    }
+
     /// 获取压缩类型的字符串表示
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -50,6 +79,7 @@ impl CompressionType {
             CompressionType::Gzip => "gzip",
             CompressionType::Tar => "tar",
             CompressionType::TarGz => "tar.gz",
+            CompressionType::TarZst => "tar.zst",
             CompressionType::Brotli => "brotli",
             CompressionType::Lz4 => "lz4",
             CompressionType::Zstd => "zstd",
@@ -80,6 +110,8 @@ pub struct ArchiveEntry {
     pub is_dir: bool,
     pub modified_time: Option<String>,
     pub crc32: Option<u32>,
+    /// 条目是否加密（需要密码才能读取内容）
+    pub encrypted: bool,
     /// 条目在压缩包中的索引
     pub index: u32,
     /// 额外的元数据
@@ -123,4 +155,25 @@ pub struct FilePreview {
     pub is_truncated: bool,
     pub total_size: String, // 使用字符串表示大数字
     pub preview_size: u32,
+    /// 文本内容识别出的编码（如 UTF-8、GBK），二进制内容为空
+    #[serde(default)]
+    pub encoding: Option<String>,
+}
+
+/// 完整解压结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ExtractSummary {
+    pub target_dir: String,
+    pub extracted_entries: u32,
+    pub extracted_bytes: String, // 使用字符串表示大数字
+    /// 路径不安全（绝对路径或包含 ..）或类型不支持（如符号链接）而跳过的条目数
+    pub skipped_entries: u32,
+}
+
+/// 完整解压进度事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractProgress {
+    pub archive_path: String,
+    pub extracted_entries: u64,
+    pub extracted_bytes: u64,
 }
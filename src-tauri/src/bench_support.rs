@@ -0,0 +1,306 @@
+// 基准测试支持
+// 内部模块保持私有，这里把需要测量的热点路径包装为同步接口供 benches/ 使用：
+// 中央目录解析、deflate 流式解压、gzip 解压、gzip 随机访问索引和范围读取合并；
+// BenchStorage 把本地目录包装为 StorageClient，测量经过存储抽象的完整读取路径
+
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::archive::entry_index;
+use crate::archive::formats::common::StorageReader;
+use crate::archive::formats::gzip::GzipHandler;
+use crate::archive::formats::gzip_index::GzipSeekIndex;
+use crate::archive::formats::zip::ZipHandler;
+use crate::archive::handlers::ArchiveHandler;
+use crate::storage::local_client::LocalFileSystemClient;
+use crate::storage::local_copy;
+use crate::storage::traits::{ConnectionConfig, StorageClient};
+
+/// 基准测试用的压缩包样本路径
+pub struct Fixtures {
+    /// 包含大量小文件的 ZIP（测量中央目录解析）
+    pub many_entries_zip: PathBuf,
+    /// 包含单个大文件的 deflate ZIP（测量流式解压）
+    pub deflate_zip: PathBuf,
+    /// 单文件 gzip
+    pub gzip: PathBuf,
+    /// 未压缩的数据文件（测量范围读取）
+    pub raw: PathBuf,
+}
+
+/// 在指定目录下生成基准测试样本，内容由固定种子生成，多次运行结果一致
+/// size 为 deflate / gzip / 未压缩样本的数据大小，不小于 64 字节
+pub fn write_fixtures(dir: &Path, entries: usize, size: usize) -> Result<Fixtures, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let data = sample_data(size);
+
+    let many_entries_zip = dir.join("many-entries.zip");
+    write_zip(
+        &many_entries_zip,
+        (0..entries).map(|i| (format!("dir{:03}/file{:06}.txt", i % 100, i), &data[..64])),
+    )?;
+
+    let deflate_zip = dir.join("deflate.zip");
+    write_zip(
+        &deflate_zip,
+        std::iter::once(("data.jsonl".to_string(), &data[..])),
+    )?;
+
+    let gzip = dir.join("data.jsonl.gz");
+    let file = std::fs::File::create(&gzip).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    encoder
+        .write_all(&data)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    let raw = dir.join("data.bin");
+    std::fs::write(&raw, &data).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(Fixtures {
+        many_entries_zip,
+        deflate_zip,
+        gzip,
+        raw,
+    })
+}
+
+/// 生成可压缩的伪随机 JSON 行数据
+fn sample_data(size: usize) -> Vec<u8> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut data = Vec::with_capacity(size + 128);
+    while data.len() < size {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let line = format!(
+            "{{\"id\":{},\"label\":\"class_{}\",\"score\":0.{:04}}}\n",
+            data.len(),
+            state % 32,
+            state % 10000
+        );
+        data.extend_from_slice(line.as_bytes());
+    }
+    data.truncate(size);
+    data
+}
+
+fn write_zip<'a>(
+    path: &Path,
+    entries: impl Iterator<Item = (String, &'a [u8])>,
+) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default());
+
+    for (name, data) in entries {
+        writer
+            .start_file(name, options)
+            .map_err(|e| format!("Failed to write zip entry: {}", e))?;
+        writer
+            .write_all(data)
+            .map_err(|e| format!("Failed to write zip entry: {}", e))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finish zip: {}", e))?;
+    Ok(())
+}
+
+/// 从完整的 ZIP 数据中定位并解析中央目录，返回条目数量
+pub fn parse_zip_central_directory(data: &[u8]) -> Result<usize, String> {
+    let footer_start = data.len().saturating_sub(65536);
+    let eocd_pos = ZipHandler::find_eocd(&data[footer_start..])
+        .ok_or_else(|| "Could not find EOCD record".to_string())?;
+    let eocd = &data[footer_start + eocd_pos..];
+
+    let total_entries = u16::from_le_bytes([eocd[10], eocd[11]]) as u64;
+    let cd_size = u32::from_le_bytes([eocd[12], eocd[13], eocd[14], eocd[15]]) as usize;
+    let cd_offset = u32::from_le_bytes([eocd[16], eocd[17], eocd[18], eocd[19]]) as usize;
+    let cd_data = data
+        .get(cd_offset..cd_offset + cd_size)
+        .ok_or_else(|| "Central directory out of bounds".to_string())?;
+
+    ZipHandler::parse_central_directory(cd_data, total_entries).map(|entries| entries.len())
+}
+
+/// 解压 gzip 数据的开头部分，返回解压得到的字节数
+pub fn decompress_gzip_sample(data: &[u8], max_output_size: usize) -> Result<usize, String> {
+    GzipHandler::decompress_sample(data, max_output_size).map(|output| output.len())
+}
+
+/// 排序并合并读取区间
+pub fn merge_ranges(ranges: Vec<Range<u64>>, file_size: u64) -> Vec<Range<u64>> {
+    local_copy::merge_ranges(ranges, file_size)
+}
+
+/// 基于本地目录的存储客户端，读取经过与远程存储相同的 StorageClient 接口
+pub struct BenchStorage {
+    client: Arc<dyn StorageClient>,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl BenchStorage {
+    pub fn local(root: &Path) -> Result<Self, String> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to create runtime: {}", e))?;
+
+        let mut client = LocalFileSystemClient::new();
+        // 其余字段均为可选，缺省时反序列化为 None
+        let config: ConnectionConfig = serde_json::from_value(serde_json::json!({
+            "protocol": "local",
+            "url": root.to_string_lossy(),
+        }))
+        .map_err(|e| format!("Invalid connection config: {}", e))?;
+        runtime
+            .block_on(client.connect(&config))
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+
+        Ok(Self {
+            client: Arc::new(client),
+            runtime,
+        })
+    }
+
+    /// 分析压缩包结构，返回条目数量
+    pub fn analyze_archive(&self, path: &str) -> Result<usize, String> {
+        let filename = path.rsplit('/').next().unwrap_or(path).to_string();
+        let info = self
+            .runtime
+            .block_on(ArchiveHandler::new().analyze_archive_with_client(
+                self.client.clone(),
+                path.to_string(),
+                filename,
+                None,
+            ))?;
+        Ok(info.entries.len())
+    }
+
+    /// 从解压后的 offset 处预览压缩包内的条目，返回预览内容的字节数
+    pub fn preview_entry(
+        &self,
+        path: &str,
+        entry_path: &str,
+        max_preview_size: u32,
+        offset: Option<u64>,
+    ) -> Result<usize, String> {
+        let filename = path.rsplit('/').next().unwrap_or(path).to_string();
+        let preview = self.runtime.block_on(
+            ArchiveHandler::new().get_file_preview_with_client::<fn(u64, u64)>(
+                self.client.clone(),
+                path.to_string(),
+                filename,
+                entry_path.to_string(),
+                Some(max_preview_size),
+                offset,
+                None,
+                None,
+            ),
+        )?;
+        Ok(preview.content.len())
+    }
+
+    /// 通过 gzip 随机访问索引从解压后的 offset 处读取，返回读取内容的字节数
+    /// rebuild 为 true 时先删除磁盘上的索引，测量建立索引的开销；文件小于建立索引的阈值时返回错误
+    pub fn gzip_read_at(
+        &self,
+        path: &str,
+        offset: u64,
+        max_size: usize,
+        rebuild: bool,
+    ) -> Result<usize, String> {
+        let client = self.client.clone();
+        let path = path.to_string();
+        self.runtime.block_on(async move {
+            let file_size = client
+                .get_file_size(&path)
+                .await
+                .map_err(|e| format!("Failed to get file size: {}", e))?;
+            if rebuild {
+                if let Some(file) =
+                    entry_index::index_file(client.as_ref(), &path, file_size, "gzi").await
+                {
+                    let _ = std::fs::remove_file(file);
+                }
+            }
+            let mut seek_index = GzipSeekIndex::open(client.as_ref(), &path, file_size)
+                .await
+                .ok_or_else(|| "GZIP file too small for seek index".to_string())?;
+
+            let reader =
+                StorageReader::new(client, &path, file_size, tokio::runtime::Handle::current());
+            tokio::task::spawn_blocking(move || {
+                let result = seek_index.read_at(reader, file_size, offset, max_size, None, None);
+                seek_index.save();
+                result.map(|preview| preview.content.len())
+            })
+            .await
+            .map_err(|e| format!("Read task failed: {}", e))?
+        })
+    }
+
+    /// 逐个发起范围读取，返回读取的总字节数
+    pub fn read_ranges(&self, path: &str, ranges: &[Range<u64>]) -> Result<u64, String> {
+        self.runtime.block_on(async {
+            let mut total = 0u64;
+            for range in ranges {
+                let data = self
+                    .client
+                    .read_file_range(path, range.start, range.end - range.start)
+                    .await
+                    .map_err(|e| format!("Failed to read file range: {}", e))?;
+                total += data.len() as u64;
+            }
+            Ok(total)
+        })
+    }
+
+    /// 先合并区间再读取，返回读取的总字节数
+    pub fn read_ranges_merged(
+        &self,
+        path: &str,
+        ranges: Vec<Range<u64>>,
+        file_size: u64,
+    ) -> Result<u64, String> {
+        let merged = merge_ranges(ranges, file_size);
+        self.read_ranges(path, &merged)
+    }
+
+    /// 通过按块缓存的同步读取器执行一系列小读取，返回读取的总字节数
+    pub fn read_through_block_reader(
+        &self,
+        path: &str,
+        file_size: u64,
+        reads: &[Range<u64>],
+    ) -> Result<u64, String> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let client = self.client.clone();
+        let handle = self.runtime.handle().clone();
+        let path = path.to_string();
+        let reads = reads.to_vec();
+        self.runtime
+            .block_on(tokio::task::spawn_blocking(move || {
+                let mut reader = StorageReader::new(client, &path, file_size, handle);
+                let mut total = 0u64;
+                for range in reads {
+                    let mut buf = vec![0u8; (range.end - range.start) as usize];
+                    reader
+                        .seek(SeekFrom::Start(range.start))
+                        .and_then(|_| reader.read_exact(&mut buf))
+                        .map_err(|e| format!("Failed to read file: {}", e))?;
+                    total += buf.len() as u64;
+                }
+                Ok(total)
+            }))
+            .map_err(|e| format!("Read task failed: {}", e))?
+    }
+}
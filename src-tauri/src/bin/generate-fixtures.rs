@@ -0,0 +1,351 @@
+// 测试用压缩包生成工具
+// 按需生成超大或损坏的合成压缩包，用于压缩包子系统的性能和正确性验证，仓库中不保存这些大文件。
+// 相同参数和种子生成的文件逐字节相同。
+//
+// 用法：
+//   cargo run --features fixtures --bin generate-fixtures -- <类型> <输出目录> [--entries N] [--depth N] [--size-mb N] [--seed N]
+//
+// 类型：
+//   many-entries  包含大量小条目的 ZIP（默认 700000 个）
+//   zip64         包含超过 4GB 条目的 ZIP64（默认 4608MB），其后的条目偏移也超过 4GB
+//   nested-tar    逐层嵌套的 tar（默认 32 层）
+//   corrupt-eocd  EOCD 损坏的各种 ZIP 变体
+//   all           生成以上全部
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+const DEFAULT_ENTRIES: u64 = 700_000;
+const DEFAULT_DEPTH: u32 = 32;
+const DEFAULT_ZIP64_SIZE_MB: u64 = 4608;
+const DEFAULT_SEED: u64 = 0x5eed;
+
+/// 写入大条目时每次生成的数据块大小
+const WRITE_CHUNK_SIZE: usize = 4 * 1024 * 1024; // 4MB
+
+/// EOCD 记录签名
+const EOCD_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x05, 0x06];
+
+/// EOCD 记录的固定长度（不含注释）
+const EOCD_LEN: usize = 22;
+
+struct Options {
+    kind: String,
+    output_dir: PathBuf,
+    entries: u64,
+    depth: u32,
+    size_mb: u64,
+    seed: u64,
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!(
+                "Usage: generate-fixtures <many-entries|zip64|nested-tar|corrupt-eocd|all> <output-dir> \
+                 [--entries N] [--depth N] [--size-mb N] [--seed N]"
+            );
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(&options) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut positional = Vec::new();
+    let mut options = Options {
+        kind: String::new(),
+        output_dir: PathBuf::new(),
+        entries: DEFAULT_ENTRIES,
+        depth: DEFAULT_DEPTH,
+        size_mb: DEFAULT_ZIP64_SIZE_MB,
+        seed: DEFAULT_SEED,
+    };
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            positional.push(arg);
+            continue;
+        }
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", arg))?;
+        let number = value
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid value for {}: {}", arg, value))?;
+        match arg.as_str() {
+            "--entries" => options.entries = number,
+            "--depth" => options.depth = number as u32,
+            "--size-mb" => options.size_mb = number,
+            "--seed" => options.seed = number,
+            _ => return Err(format!("Unknown option: {}", arg)),
+        }
+    }
+
+    match positional.as_slice() {
+        [kind, output_dir] => {
+            options.kind = kind.clone();
+            options.output_dir = PathBuf::from(output_dir);
+            Ok(options)
+        }
+        _ => Err("Expected <kind> and <output-dir>".to_string()),
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    std::fs::create_dir_all(&options.output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let kinds = match options.kind.as_str() {
+        "all" => vec!["many-entries", "zip64", "nested-tar", "corrupt-eocd"],
+        kind => vec![kind],
+    };
+    for kind in kinds {
+        match kind {
+            "many-entries" => many_entries(options)?,
+            "zip64" => zip64(options)?,
+            "nested-tar" => nested_tar(options)?,
+            "corrupt-eocd" => corrupt_eocd(options)?,
+            other => return Err(format!("Unknown fixture kind: {}", other)),
+        }
+    }
+    Ok(())
+}
+
+/// 固定种子的 xorshift 伪随机数，保证生成结果可复现
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// 固定修改时间，避免生成结果随时间变化
+fn file_options(method: CompressionMethod) -> FileOptions {
+    FileOptions::default()
+        .compression_method(method)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644)
+}
+
+fn create_output(options: &Options, name: &str) -> Result<(PathBuf, BufWriter<File>), String> {
+    let path = options.output_dir.join(name);
+    let file = File::create(&path).map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+    Ok((path, BufWriter::new(file)))
+}
+
+fn write_error(path: &Path, e: impl std::fmt::Display) -> String {
+    format!("Failed to write {:?}: {}", path, e)
+}
+
+/// 大量小条目，分布在多级目录中，条目内容为带编号的文本
+fn many_entries(options: &Options) -> Result<(), String> {
+    let (path, writer) = create_output(options, &format!("entries-{}.zip", options.entries))?;
+    let mut zip = ZipWriter::new(writer);
+    let mut rng = Rng::new(options.seed);
+
+    for index in 0..options.entries {
+        let name = format!(
+            "data/{:03}/{:03}/item-{:07}.txt",
+            index / 1_000_000,
+            index / 1_000 % 1_000,
+            index
+        );
+        // 少量条目使用 Deflate，覆盖两种压缩方式
+        let method = if index % 16 == 0 {
+            CompressionMethod::Deflated
+        } else {
+            CompressionMethod::Stored
+        };
+        zip.start_file(name, file_options(method))
+            .map_err(|e| write_error(&path, e))?;
+        writeln!(zip, "item {} value {:016x}", index, rng.next())
+            .map_err(|e| write_error(&path, e))?;
+    }
+
+    zip.finish().map_err(|e| write_error(&path, e))?;
+    println!("Generated {:?}", path);
+    Ok(())
+}
+
+/// 超过 4GB 的 Stored 条目，之后再写入一个小条目，使其本地头偏移也超过 4GB
+fn zip64(options: &Options) -> Result<(), String> {
+    let (path, writer) = create_output(options, &format!("zip64-{}mb.zip", options.size_mb))?;
+    let mut zip = ZipWriter::new(writer);
+    let mut rng = Rng::new(options.seed);
+
+    zip.start_file(
+        "large.bin",
+        file_options(CompressionMethod::Stored).large_file(true),
+    )
+    .map_err(|e| write_error(&path, e))?;
+    let mut remaining = options.size_mb * 1024 * 1024;
+    let mut chunk = vec![0u8; WRITE_CHUNK_SIZE];
+    while remaining > 0 {
+        let length = remaining.min(chunk.len() as u64) as usize;
+        rng.fill(&mut chunk[..length]);
+        zip.write_all(&chunk[..length])
+            .map_err(|e| write_error(&path, e))?;
+        remaining -= length as u64;
+    }
+
+    zip.start_file("after-large.txt", file_options(CompressionMethod::Stored))
+        .map_err(|e| write_error(&path, e))?;
+    zip.write_all(b"entry located after the 4GB boundary\n")
+        .map_err(|e| write_error(&path, e))?;
+
+    zip.finish().map_err(|e| write_error(&path, e))?;
+    println!("Generated {:?}", path);
+    Ok(())
+}
+
+/// 逐层嵌套的 tar：第 N 层只包含第 N-1 层的 tar 和一个说明文件
+fn nested_tar(options: &Options) -> Result<(), String> {
+    let mut inner = tar_with_entries(vec![(
+        "level-0.txt".to_string(),
+        b"innermost entry\n".to_vec(),
+    )])?;
+    for level in 1..=options.depth {
+        let note = format!("level {}\n", level).into_bytes();
+        inner = tar_with_entries(vec![
+            (format!("level-{}.txt", level), note),
+            (format!("level-{}.tar", level - 1), inner),
+        ])?;
+    }
+
+    let (path, mut writer) = create_output(options, &format!("nested-{}.tar", options.depth))?;
+    writer
+        .write_all(&inner)
+        .and_then(|_| writer.flush())
+        .map_err(|e| write_error(&path, e))?;
+    println!("Generated {:?}", path);
+    Ok(())
+}
+
+fn tar_with_entries(entries: Vec<(String, Vec<u8>)>) -> Result<Vec<u8>, String> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, data) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, data.as_slice())
+            .map_err(|e| format!("Failed to build tar: {}", e))?;
+    }
+    builder
+        .into_inner()
+        .map_err(|e| format!("Failed to build tar: {}", e))
+}
+
+/// 基于同一个有效 ZIP 生成各种 EOCD 损坏的变体
+fn corrupt_eocd(options: &Options) -> Result<(), String> {
+    let valid = small_zip(options.seed)?;
+    let eocd = valid
+        .windows(EOCD_SIGNATURE.len())
+        .rposition(|window| window == EOCD_SIGNATURE)
+        .ok_or_else(|| "Generated ZIP has no EOCD record".to_string())?;
+
+    let mut variants: Vec<(&str, Vec<u8>)> = Vec::new();
+
+    // EOCD 被截断
+    variants.push(("eocd-truncated.zip", valid[..valid.len() - 10].to_vec()));
+
+    // 完全没有 EOCD
+    variants.push(("eocd-missing.zip", valid[..eocd].to_vec()));
+
+    // 中央目录偏移指向文件末尾之后
+    let mut data = valid.clone();
+    data[eocd + 16..eocd + 20].copy_from_slice(&(valid.len() as u32 + 4096).to_le_bytes());
+    variants.push(("eocd-bad-cd-offset.zip", data));
+
+    // 条目数量与中央目录不一致
+    let mut data = valid.clone();
+    data[eocd + 8..eocd + 10].copy_from_slice(&u16::MAX.to_le_bytes());
+    data[eocd + 10..eocd + 12].copy_from_slice(&u16::MAX.to_le_bytes());
+    variants.push(("eocd-bad-entry-count.zip", data));
+
+    // 注释长度超过文件剩余部分
+    let mut data = valid.clone();
+    data[eocd + 20..eocd + 22].copy_from_slice(&u16::MAX.to_le_bytes());
+    variants.push(("eocd-comment-overflow.zip", data));
+
+    // EOCD 之后有超过最大注释长度的垃圾数据，需要越过 64KB 搜索窗口才能找到
+    let mut data = valid.clone();
+    let mut garbage = vec![0u8; 96 * 1024];
+    Rng::new(options.seed).fill(&mut garbage);
+    // 去掉所有 'P' 字节，垃圾数据中不会出现 EOCD 签名
+    garbage
+        .iter_mut()
+        .filter(|byte| **byte == EOCD_SIGNATURE[0])
+        .for_each(|byte| *byte = 0);
+    data.extend_from_slice(&garbage);
+    variants.push(("eocd-trailing-garbage.zip", data));
+
+    // 伪造的 EOCD 签名出现在条目数据中，最后一个才是真正的 EOCD
+    let mut fake_eocd = EOCD_SIGNATURE.to_vec();
+    fake_eocd.resize(EOCD_LEN, 0);
+    let data = small_zip_with_content(options.seed, &fake_eocd)?;
+    variants.push(("eocd-fake-signature.zip", data));
+
+    for (name, data) in variants {
+        let (path, mut writer) = create_output(options, name)?;
+        writer
+            .write_all(&data)
+            .and_then(|_| writer.flush())
+            .map_err(|e| write_error(&path, e))?;
+        println!("Generated {:?}", path);
+    }
+    Ok(())
+}
+
+fn small_zip(seed: u64) -> Result<Vec<u8>, String> {
+    let mut content = vec![0u8; 1024];
+    Rng::new(seed).fill(&mut content);
+    small_zip_with_content(seed, &content)
+}
+
+/// 包含一个目录、一个文本条目和一个二进制条目的小 ZIP
+fn small_zip_with_content(seed: u64, content: &[u8]) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let error = |e: zip::result::ZipError| format!("Failed to build ZIP: {}", e);
+
+    zip.add_directory("docs/", file_options(CompressionMethod::Stored))
+        .map_err(error)?;
+    zip.start_file("docs/readme.txt", file_options(CompressionMethod::Deflated))
+        .map_err(error)?;
+    writeln!(zip, "fixture seed {}", seed).map_err(|e| format!("Failed to build ZIP: {}", e))?;
+    zip.start_file("data.bin", file_options(CompressionMethod::Stored))
+        .map_err(error)?;
+    zip.write_all(content)
+        .map_err(|e| format!("Failed to build ZIP: {}", e))?;
+
+    Ok(zip.finish().map_err(error)?.into_inner())
+}
@@ -1,23 +1,47 @@
 // 压缩包处理命令
 // 提供压缩包分析、预览和格式支持功能
 
+use crate::archive::search::{ArchiveSearchOptions, ArchiveSearchReport, ArchiveSearcher};
+use crate::archive::settings::ArchiveSettings;
+use crate::archive::stream_control::StreamControl;
 use crate::archive::{handlers::ArchiveHandler, types::*};
+use crate::commands::dataset::get_current_connection_key;
+use crate::commands::error::CommandError;
+use crate::dataset::search_history::{self, SearchTarget};
+use crate::media::thumbnails;
 use crate::storage::get_storage_manager;
-use std::sync::{Arc, LazyLock};
+use crate::utils::natural_sort::natural_cmp;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::Emitter;
+use tokio::sync::broadcast;
 
 // 全局压缩包处理器
 static ARCHIVE_HANDLER: LazyLock<Arc<ArchiveHandler>> =
     LazyLock::new(|| Arc::new(ArchiveHandler::new()));
 
+// 进行中的完整解压（压缩包路径 -> 取消信号）
+static ACTIVE_EXTRACTIONS: LazyLock<Mutex<HashMap<String, broadcast::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 进行中的压缩包搜索（搜索标识 -> 取消信号）
+static ACTIVE_ARCHIVE_SEARCHES: LazyLock<Mutex<HashMap<String, broadcast::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// 获取压缩包信息（统一接口）
-/// 支持多种压缩格式的流式分析
+/// 支持多种压缩格式的流式分析，natural_sort 为 true 时条目按路径自然顺序排序
+/// 提供 password 时用其校验加密条目，密码错误返回 archive.invalidPassword
+/// 指定 request_id 时可通过 archive_preview_pause / resume / cancel 暂停、恢复或取消分析
 #[tauri::command]
 #[specta::specta]
 pub async fn archive_get_file_info(
     url: String,
     filename: String,
     max_size: Option<u32>,
-) -> Result<ArchiveInfo, String> {
+    natural_sort: Option<bool>,
+    password: Option<String>,
+    request_id: Option<String>,
+) -> Result<ArchiveInfo, CommandError> {
     // 统一使用StorageClient接口进行流式分析
     let manager_arc = get_storage_manager().await;
     let manager = manager_arc.read().await;
@@ -25,10 +49,237 @@ pub async fn archive_get_file_info(
     if let Some(client) = manager.get_current_client() {
         drop(manager);
 
-        ARCHIVE_HANDLER
+        // 指定请求标识时登记预览流，分析结束后自动移除
+        let stream = request_id
+            .map(StreamControl::register)
+            .transpose()
+            .map_err(CommandError::invalid_input)?;
+        let client = match &stream {
+            Some(stream) => stream.wrap(client),
+            None => client,
+        };
+
+        let result = ArchiveHandler::new()
+            .with_password(password)
             .analyze_archive_with_client(client, url, filename, max_size)
-            .await
+            .await;
+        let mut info = match stream {
+            Some(stream) if result.is_err() && stream.is_cancelled() => {
+                return Err(CommandError::from("preview.cancelled".to_string()));
+            }
+            _ => result?,
+        };
+
+        if natural_sort == Some(true) {
+            info.entries.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+        }
+        Ok(info)
     } else {
-        Err("No storage client available. Please connect to a storage first (Local, WebDAV, S3, or HuggingFace)".to_string())
+        Err(CommandError::NotConnected {
+            message: "No storage client available. Please connect to a storage first (Local, WebDAV, S3, or HuggingFace)".to_string(),
+        })
     }
 }
+
+/// 获取压缩包内图片条目的缩略图（JPEG）
+/// 按连接、压缩包版本和条目路径缓存，同时生成的缩略图数量有上限
+/// max_dimension 为缩略图最长边，默认 256，最大 1024
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_get_entry_thumbnail(
+    url: String,
+    entry_path: String,
+    max_dimension: Option<u32>,
+) -> Result<Vec<u8>, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    thumbnails::archive_entry_thumbnail(client, &url, &entry_path, max_dimension)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 在多个压缩包中搜索条目路径或文本内容，压缩包路径可以指向其他压缩包中的条目（`outer.zip!inner.tar`）
+/// 每个压缩包搜索完成后通过 archive-search-matches 事件发送其匹配结果（搜索标识 + 压缩包 + 条目 + 行号），
+/// 所有搜索共享同一个并发上限，可通过 archive_search_cancel 取消；每次搜索都会记录到搜索历史
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_search(
+    app: tauri::AppHandle,
+    search_id: String,
+    archive_paths: Vec<String>,
+    query: String,
+    options: Option<ArchiveSearchOptions>,
+) -> Result<ArchiveSearchReport, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+    let options = options.unwrap_or_default();
+
+    let cancel_rx = {
+        let mut searches = ACTIVE_ARCHIVE_SEARCHES.lock().unwrap();
+        if searches.contains_key(&search_id) {
+            return Err(CommandError::invalid_input(format!(
+                "Search is already running: {}",
+                search_id
+            )));
+        }
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        searches.insert(search_id.clone(), cancel_tx);
+        cancel_rx
+    };
+
+    let result = ArchiveSearcher::search(
+        client,
+        &search_id,
+        archive_paths.clone(),
+        &query,
+        &options,
+        cancel_rx,
+        move |batch| {
+            let _ = app.emit("archive-search-matches", &batch);
+        },
+    )
+    .await;
+    ACTIVE_ARCHIVE_SEARCHES.lock().unwrap().remove(&search_id);
+    let report = result?;
+
+    if let Ok(connection_key) = get_current_connection_key().await {
+        search_history::record(
+            &connection_key,
+            &query,
+            SearchTarget::Archives {
+                paths: archive_paths,
+                options,
+            },
+            report.total_matches,
+        );
+    }
+    Ok(report)
+}
+
+/// 取消压缩包搜索，archive_search 返回取消前已完成的压缩包的结果
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_search_cancel(search_id: String) -> Result<(), CommandError> {
+    let searches = ACTIVE_ARCHIVE_SEARCHES.lock().unwrap();
+    let cancel_tx = searches.get(&search_id).ok_or_else(|| {
+        CommandError::invalid_input(format!("No active search found for: {}", search_id))
+    })?;
+    let _ = cancel_tx.send(());
+    Ok(())
+}
+
+/// 将整个压缩包（ZIP / TAR / TAR.GZ / TAR.ZST）解压到本地目录
+/// 流式读取远程压缩包，进度通过 archive-extract-progress 事件发送，可通过 archive_extract_cancel 取消；
+/// 路径不安全的条目和符号链接会被跳过
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_extract_all(
+    app: tauri::AppHandle,
+    url: String,
+    filename: String,
+    target_dir: String,
+) -> Result<ExtractSummary, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    let cancel_rx = {
+        let mut extractions = ACTIVE_EXTRACTIONS.lock().unwrap();
+        if extractions.contains_key(&url) {
+            return Err(CommandError::invalid_input(format!(
+                "Archive is already being extracted: {}",
+                url
+            )));
+        }
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        extractions.insert(url.clone(), cancel_tx);
+        cancel_rx
+    };
+
+    let archive_path = url.clone();
+    let result = ARCHIVE_HANDLER
+        .extract_all_with_client(
+            client,
+            url.clone(),
+            filename,
+            std::path::Path::new(&target_dir),
+            Some(move |extracted_entries, extracted_bytes| {
+                let _ = app.emit(
+                    "archive-extract-progress",
+                    &ExtractProgress {
+                        archive_path: archive_path.clone(),
+                        extracted_entries,
+                        extracted_bytes,
+                    },
+                );
+            }),
+            cancel_rx,
+        )
+        .await;
+
+    ACTIVE_EXTRACTIONS.lock().unwrap().remove(&url);
+    result.map_err(CommandError::from)
+}
+
+/// 取消压缩包的完整解压，已写出的文件保留
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_extract_cancel(url: String) -> Result<(), CommandError> {
+    let extractions = ACTIVE_EXTRACTIONS.lock().unwrap();
+    let cancel_tx = extractions.get(&url).ok_or_else(|| {
+        CommandError::invalid_input(format!("No active extraction found for: {}", url))
+    })?;
+    let _ = cancel_tx.send(());
+    Ok(())
+}
+
+/// 暂停进行中的预览（preview_run 或 archive_get_file_info 指定了 requestId），例如标签页切到后台时
+/// 暂停期间不再读取存储，解压循环在下一次进度回调处等待，恢复后从暂停处继续
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_preview_pause(request_id: String) -> Result<(), CommandError> {
+    StreamControl::pause(&request_id).map_err(CommandError::invalid_input)
+}
+
+/// 恢复已暂停的预览
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_preview_resume(request_id: String) -> Result<(), CommandError> {
+    StreamControl::resume(&request_id).map_err(CommandError::invalid_input)
+}
+
+/// 取消进行中或已暂停的预览，preview_run / archive_get_file_info 返回 preview.cancelled
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_preview_cancel(request_id: String) -> Result<(), CommandError> {
+    StreamControl::cancel(&request_id).map_err(CommandError::invalid_input)
+}
+
+/// 获取压缩包分析设置
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_get_settings() -> Result<ArchiveSettings, CommandError> {
+    Ok(ArchiveSettings::current())
+}
+
+/// 更新压缩包分析设置，对之后的分析生效
+#[tauri::command]
+#[specta::specta]
+pub async fn archive_set_settings(settings: ArchiveSettings) -> Result<(), CommandError> {
+    tokio::task::spawn_blocking(move || ArchiveSettings::update(settings))
+        .await
+        .map_err(|e| format!("Save settings task failed: {}", e))?
+        .map_err(CommandError::from)
+}
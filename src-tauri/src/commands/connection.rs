@@ -0,0 +1,81 @@
+// 连接配置管理命令
+// 保存、列出、删除和测试命名的连接配置，敏感字段只保存在系统钥匙串中
+
+use crate::commands::storage::cancel_all_watches;
+use crate::connections::{self, ConnectionProfile, ConnectionProfileInput};
+use crate::storage::get_storage_manager;
+use crate::storage::manager::StorageManager;
+use serde::{Deserialize, Serialize};
+
+/// 连接测试结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    /// 建立连接耗时（毫秒）
+    pub latency_ms: u32,
+    pub error: Option<String>,
+}
+
+/// 保存连接配置，密钥、密码和令牌写入系统钥匙串
+/// 更新已有配置时，未填写的敏感字段保留原值
+#[tauri::command]
+#[specta::specta]
+pub async fn connection_save(profile: ConnectionProfileInput) -> Result<ConnectionProfile, String> {
+    tokio::task::spawn_blocking(move || connections::save(profile))
+        .await
+        .map_err(|e| format!("Connection save task failed: {}", e))?
+}
+
+/// 列出保存的连接配置，返回的配置不含敏感字段
+#[tauri::command]
+#[specta::specta]
+pub async fn connection_list() -> Result<Vec<ConnectionProfile>, String> {
+    Ok(connections::list())
+}
+
+/// 删除连接配置及钥匙串中的敏感字段
+#[tauri::command]
+#[specta::specta]
+pub async fn connection_delete(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || connections::delete(&id))
+        .await
+        .map_err(|e| format!("Connection delete task failed: {}", e))?
+}
+
+/// 使用保存的配置尝试建立连接，不影响当前连接
+#[tauri::command]
+#[specta::specta]
+pub async fn connection_test(id: String) -> Result<ConnectionTestResult, String> {
+    let config = tokio::task::spawn_blocking(move || connections::resolve(&id))
+        .await
+        .map_err(|e| format!("Connection test task failed: {}", e))??;
+
+    let started = std::time::Instant::now();
+    let result = StorageManager::create_client(&config).await;
+    Ok(ConnectionTestResult {
+        success: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u32,
+        error: result.err().map(|e| e.to_string()),
+    })
+}
+
+/// 使用保存的配置连接存储，敏感字段不经过前端
+#[tauri::command]
+#[specta::specta]
+pub async fn connection_connect(id: String) -> Result<bool, String> {
+    let profile_id = id.clone();
+    let config = tokio::task::spawn_blocking(move || connections::resolve(&profile_id))
+        .await
+        .map_err(|e| format!("Connection task failed: {}", e))??;
+
+    cancel_all_watches();
+    let manager_arc = get_storage_manager().await;
+    let mut manager = manager_arc.write().await;
+    manager
+        .connect(&config)
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+    connections::mark_used(&id);
+    Ok(true)
+}
@@ -0,0 +1,493 @@
+// 数据集维护命令
+// 提供批量重命名、重复文件查找、敏感信息扫描等数据集整理功能
+
+use crate::dataset::arrow::{ArrowBatch, ArrowBatchRequest, ArrowReader, ArrowSchemaInfo};
+use crate::dataset::content_index::{self, ContentIndexSettings};
+use crate::dataset::dedup::{DuplicateFinder, DuplicateReport, DuplicateScanOptions};
+use crate::dataset::estimate::{EgressEstimator, OperationEstimate};
+use crate::dataset::facets::{FacetCalculator, FacetOptions, ListingFacets};
+use crate::dataset::filename_index::{
+    ContentSearchResult, FilenameIndex, FilenameIndexProgress, FilenameIndexStatus,
+    FilenameSearchResult,
+};
+use crate::dataset::flat_list::{FlatListOptions, FlatListPage, FlatLister};
+use crate::dataset::hashing::{chunk_signature, compare_signatures, ChunkSignature, SignatureDiff};
+use crate::dataset::ignore_rules::{IgnoreRules, WorkspaceIgnoreStore};
+use crate::dataset::integrity::{verify_against_etag, IntegrityCheck};
+use crate::dataset::license::{LicenseDetector, LicenseReport, LicenseScanOptions};
+use crate::dataset::pii_scan::{PiiScanOptions, PiiScanReport, PiiScanTarget, PiiScanner};
+use crate::dataset::rename::{
+    BatchRenamer, RenamePlan, RenameResult, RenameRule, RenameUndoResult,
+};
+use crate::dataset::search_history::{self, SearchTarget};
+use crate::dataset::size_budget::{self, SizeBudget, SizeBudgetCheck, SizeBudgetInput};
+use crate::storage::get_storage_manager;
+use crate::storage::traits::StorageClient;
+use std::sync::Arc;
+use tauri::Emitter;
+
+/// 预览批量重命名结果（dry-run）
+/// 返回每个文件的新旧名称以及冲突信息，不修改任何文件
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_rename_preview(
+    directory: String,
+    rule: RenameRule,
+) -> Result<RenamePlan, String> {
+    tokio::task::spawn_blocking(move || BatchRenamer::preview(&directory, &rule))
+        .await
+        .map_err(|e| format!("Rename preview task failed: {}", e))?
+}
+
+/// 执行批量重命名
+/// 存在冲突时拒绝执行，成功后返回撤销日志 ID
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_rename_apply(
+    directory: String,
+    rule: RenameRule,
+) -> Result<RenameResult, String> {
+    tokio::task::spawn_blocking(move || BatchRenamer::apply(&directory, &rule))
+        .await
+        .map_err(|e| format!("Rename task failed: {}", e))?
+}
+
+/// 撤销一次批量重命名
+/// 返回恢复的文件数量和跳过的文件，跳过的文件保留在撤销日志中
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_rename_undo(journal_id: String) -> Result<RenameUndoResult, String> {
+    tokio::task::spawn_blocking(move || BatchRenamer::undo(&journal_id))
+        .await
+        .map_err(|e| format!("Rename undo task failed: {}", e))?
+}
+
+/// 查找目录树中的重复文件
+/// 先按大小筛选候选，再流式计算哈希，扫描进度通过 dataset-dedup-progress 事件发送
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_find_duplicates(
+    app: tauri::AppHandle,
+    path: String,
+    options: Option<DuplicateScanOptions>,
+) -> Result<DuplicateReport, String> {
+    let client = get_current_client().await?;
+    let options = options.unwrap_or_default();
+
+    DuplicateFinder::scan(client, &path, &options, move |progress| {
+        let _ = app.emit("dataset-dedup-progress", &progress);
+    })
+    .await
+}
+
+/// 计算大文件的分块哈希签名（默认 8MB 块，xxh3）
+/// 进度通过 dataset-hash-progress 事件发送
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_chunk_signature(
+    app: tauri::AppHandle,
+    path: String,
+    block_size: Option<u32>,
+) -> Result<ChunkSignature, String> {
+    let client = get_current_client().await?;
+    let progress_path = path.clone();
+
+    chunk_signature(
+        &client,
+        &path,
+        block_size.map(|s| s as u64),
+        move |processed, total| {
+            let _ = app.emit(
+                "dataset-hash-progress",
+                serde_json::json!({
+                    "path": progress_path,
+                    "processed": processed.to_string(),
+                    "total": total.to_string(),
+                }),
+            );
+        },
+    )
+    .await
+}
+
+/// 比较两个分块签名，返回内容不同的块索引
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_compare_signatures(
+    left: ChunkSignature,
+    right: ChunkSignature,
+) -> Result<SignatureDiff, String> {
+    compare_signatures(&left, &right)
+}
+
+/// 估算批量下载或同步的文件数量与流量
+/// 返回的令牌需通过 dataset_confirm_operation 确认后才能执行大规模操作
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_estimate_operation(
+    path: String,
+    operation: String,
+    ignore_patterns: Option<Vec<String>>,
+) -> Result<OperationEstimate, String> {
+    let manager_arc = get_storage_manager().await;
+    let (client, protocol) = {
+        let manager = manager_arc.read().await;
+        (
+            manager.get_current_client(),
+            manager.get_current_protocol().unwrap_or_default(),
+        )
+    };
+    let client = client.ok_or_else(|| "No storage client connected".to_string())?;
+
+    EgressEstimator::estimate(
+        client,
+        &protocol,
+        &path,
+        &operation,
+        ignore_patterns.as_deref(),
+    )
+    .await
+}
+
+/// 确认批量操作估算结果
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_confirm_operation(token: String) -> Result<OperationEstimate, String> {
+    EgressEstimator::confirm(&token)
+}
+
+/// 校验本地文件与当前存储中对象是否一致
+/// 支持分片上传产生的 ETag（形如 "md5-N"），part_size 可指定上传时使用的分片大小
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_verify_integrity(
+    local_path: String,
+    remote_path: String,
+    part_size: Option<String>,
+) -> Result<IntegrityCheck, String> {
+    let client = get_current_client().await?;
+    let remote_etag = client
+        .get_object_etag(&remote_path)
+        .await
+        .map_err(|e| format!("Failed to get ETag: {}", e))?;
+    let part_size = part_size.and_then(|s| s.parse::<u64>().ok());
+
+    tokio::task::spawn_blocking(move || {
+        verify_against_etag(&local_path, &remote_path, &remote_etag, part_size)
+    })
+    .await
+    .map_err(|e| format!("Integrity check task failed: {}", e))?
+}
+
+/// 获取工作区保存的忽略规则
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_get_ignore_rules(workspace: String) -> Result<Vec<String>, String> {
+    Ok(WorkspaceIgnoreStore::get(&workspace))
+}
+
+/// 保存工作区的 gitignore 风格忽略规则
+/// 规则对该目录下的所有递归操作生效（重复文件查找、流量估算等）
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_set_ignore_rules(
+    workspace: String,
+    patterns: Vec<String>,
+) -> Result<(), String> {
+    // 先校验规则语法，避免保存无效规则
+    IgnoreRules::build(&workspace, &patterns)?;
+    WorkspaceIgnoreStore::set(&workspace, patterns)
+}
+
+/// 分页获取目录树下所有文件的平铺列表（路径、大小、层级）
+/// 用于在深层嵌套的数据集中查找文件，通过返回的游标继续获取下一页
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_list_flat(
+    path: String,
+    options: Option<FlatListOptions>,
+) -> Result<FlatListPage, String> {
+    let client = get_current_client().await?;
+    FlatLister::list(client, &path, &options.unwrap_or_default()).await
+}
+
+/// 统计目录树的分面（扩展名、大小分桶、修改时间分桶）
+/// 前端据此渲染筛选标签，无需拉取全部条目；筛选时将分桶键传给 dataset_list_flat
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_compute_facets(
+    path: String,
+    options: Option<FacetOptions>,
+) -> Result<ListingFacets, String> {
+    let client = get_current_client().await?;
+    FacetCalculator::compute(client, &path, &options.unwrap_or_default()).await
+}
+
+/// 在后台为当前连接构建文件名索引，立即返回
+/// 构建进度通过 dataset-index-progress 事件发送；已有索引时重新构建，构建期间旧索引仍可搜索
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_build(app: tauri::AppHandle, root: String) -> Result<(), String> {
+    let client = get_current_client().await?;
+    let connection_key = get_current_connection_key().await?;
+    let build = FilenameIndex::begin_build(&connection_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        let result = FilenameIndex::build(build, client, &root, |indexed| {
+            let _ = app.emit(
+                "dataset-index-progress",
+                &FilenameIndexProgress {
+                    indexed_files: indexed.to_string(),
+                    finished: false,
+                    error: None,
+                },
+            );
+        })
+        .await;
+
+        let progress = match result {
+            Ok(status) => FilenameIndexProgress {
+                indexed_files: status.indexed_files,
+                finished: true,
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("构建文件名索引失败: {}", e);
+                FilenameIndexProgress {
+                    indexed_files: "0".to_string(),
+                    finished: true,
+                    error: Some(e),
+                }
+            }
+        };
+        let _ = app.emit("dataset-index-progress", &progress);
+    });
+    Ok(())
+}
+
+/// 按上次索引的根目录手动刷新当前连接的文件名索引
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_refresh(app: tauri::AppHandle) -> Result<(), String> {
+    let connection_key = get_current_connection_key().await?;
+    let status = tokio::task::spawn_blocking(move || FilenameIndex::status(&connection_key))
+        .await
+        .map_err(|e| format!("Filename index task failed: {}", e))??;
+    let root = status
+        .root
+        .ok_or_else(|| "Filename index has not been built for this connection".to_string())?;
+    dataset_index_build(app, root).await
+}
+
+/// 获取当前连接文件名索引的状态和新鲜度
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_status() -> Result<FilenameIndexStatus, String> {
+    let connection_key = get_current_connection_key().await?;
+    tokio::task::spawn_blocking(move || FilenameIndex::status(&connection_key))
+        .await
+        .map_err(|e| format!("Filename index task failed: {}", e))?
+}
+
+/// 在当前连接的文件名索引中搜索，结果附带索引状态以便提示新鲜度
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_search(
+    query: String,
+    limit: Option<u32>,
+) -> Result<FilenameSearchResult, String> {
+    let connection_key = get_current_connection_key().await?;
+    let key = connection_key.clone();
+    let search_query = query.clone();
+    let result =
+        tokio::task::spawn_blocking(move || FilenameIndex::search(&key, &search_query, limit))
+            .await
+            .map_err(|e| format!("Filename index task failed: {}", e))??;
+
+    search_history::record(
+        &connection_key,
+        &query,
+        SearchTarget::Filenames { limit },
+        result.matches.len() as u32,
+    );
+    Ok(result)
+}
+
+/// 在当前连接已索引的文件内容中搜索，需要先开启内容索引并构建索引
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_search_content(
+    query: String,
+    limit: Option<u32>,
+) -> Result<ContentSearchResult, String> {
+    let connection_key = get_current_connection_key().await?;
+    let key = connection_key.clone();
+    let search_query = query.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        FilenameIndex::search_content(&key, &search_query, limit)
+    })
+    .await
+    .map_err(|e| format!("Filename index task failed: {}", e))??;
+
+    search_history::record(
+        &connection_key,
+        &query,
+        SearchTarget::Contents { limit },
+        result.matches.len() as u32,
+    );
+    Ok(result)
+}
+
+/// 获取当前连接的内容索引设置
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_get_content_settings() -> Result<ContentIndexSettings, String> {
+    let connection_key = get_current_connection_key().await?;
+    Ok(content_index::get(&connection_key))
+}
+
+/// 保存当前连接的内容索引设置（是否开启、单文件大小上限、磁盘配额、扩展名），下次构建索引时生效
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_set_content_settings(
+    settings: ContentIndexSettings,
+) -> Result<(), String> {
+    let connection_key = get_current_connection_key().await?;
+    content_index::set(&connection_key, settings)
+}
+
+/// 清除当前连接的索引，content_only 为 true 时只清除文件内容，保留文件名索引
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_index_purge(content_only: Option<bool>) -> Result<(), String> {
+    let connection_key = get_current_connection_key().await?;
+    tokio::task::spawn_blocking(move || {
+        FilenameIndex::purge(&connection_key, content_only.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Filename index task failed: {}", e))?
+}
+
+/// 扫描文件、目录和压缩包中的敏感信息（邮箱、手机号、身份证号、密钥等）及自定义规则
+/// 扫描进度通过 dataset-pii-scan-progress 事件发送
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_scan_pii(
+    app: tauri::AppHandle,
+    targets: Vec<PiiScanTarget>,
+    options: Option<PiiScanOptions>,
+) -> Result<PiiScanReport, String> {
+    let client = get_current_client().await?;
+    let options = options.unwrap_or_default();
+
+    PiiScanner::scan(client, targets, &options, move |progress| {
+        let _ = app.emit("dataset-pii-scan-progress", &progress);
+    })
+    .await
+}
+
+/// 检测目录树或压缩包中的许可证文件、SPDX 标识和数据集卡片 license 字段
+/// 返回按 SPDX 分类的汇总以及嵌套组件之间的许可证冲突
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_detect_licenses(
+    path: String,
+    options: Option<LicenseScanOptions>,
+) -> Result<LicenseReport, String> {
+    let client = get_current_client().await?;
+    LicenseDetector::scan(client, &path, &options.unwrap_or_default()).await
+}
+
+/// 列出当前连接的容量预算及最近一次检查结果
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_budget_list() -> Result<Vec<SizeBudget>, String> {
+    let connection_key = get_current_connection_key().await?;
+    Ok(size_budget::list(&connection_key))
+}
+
+/// 新建或更新当前连接下目录的容量预算，后台按检查间隔统计目录总大小
+/// 超过阈值时发送 dataset-budget-alert 事件
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_budget_save(budget: SizeBudgetInput) -> Result<SizeBudget, String> {
+    let connection_key = get_current_connection_key().await?;
+    size_budget::save(&connection_key, budget)
+}
+
+/// 删除容量预算
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_budget_delete(id: String) -> Result<(), String> {
+    size_budget::remove(&id);
+    Ok(())
+}
+
+/// 立即检查容量预算，首次超过阈值时同样发送 dataset-budget-alert 事件
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_budget_check(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<SizeBudgetCheck, String> {
+    let budget = size_budget::get(&id).ok_or_else(|| format!("Size budget not found: {}", id))?;
+    if budget.connection_key != get_current_connection_key().await? {
+        return Err("Size budget belongs to a different connection".to_string());
+    }
+    let client = get_current_client().await?;
+    let (check, alert) = size_budget::check(client, &budget).await?;
+    if let Some(alert) = alert {
+        size_budget::emit_alert(&app, &alert);
+    }
+    Ok(check)
+}
+
+/// 获取当前连接的存储客户端
+async fn get_current_client() -> Result<Arc<dyn StorageClient>, String> {
+    let manager_arc = get_storage_manager().await;
+    let manager = manager_arc.read().await;
+
+    match manager.get_current_client() {
+        Some(client) => Ok(client),
+        None => Err("No storage client connected".to_string()),
+    }
+}
+
+/// 获取当前连接的标识
+pub(crate) async fn get_current_connection_key() -> Result<String, String> {
+    let manager_arc = get_storage_manager().await;
+    let manager = manager_arc.read().await;
+
+    manager
+        .get_current_connection_key()
+        .ok_or_else(|| "No storage client connected".to_string())
+}
+
+/// 读取 Arrow IPC 文件（.arrow / .feather）的 schema
+/// 文件格式只读取 footer，流格式只读取开头的 schema 消息
+#[tauri::command]
+#[specta::specta]
+pub async fn arrow_get_schema(path: String) -> Result<ArrowSchemaInfo, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    ArrowReader::get_schema(client, &path).await
+}
+
+/// 读取 Arrow IPC 文件中一个记录批次的一页数据
+#[tauri::command]
+#[specta::specta]
+pub async fn arrow_read_batch(request: ArrowBatchRequest) -> Result<ArrowBatch, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    ArrowReader::read_batch(client, request).await
+}
@@ -1,14 +1,26 @@
 // 下载管理命令
 // 提供文件下载、进度监控和取消功能
 
-use crate::download::{DownloadManager, DownloadRequest};
+use crate::commands::error::CommandError;
+use crate::download::{
+    AutoDecompress, DownloadManager, DownloadRequest, DownloadTask, ExistingDownload,
+    PartialDownload, QueuedDownload, ReuseMode,
+};
+use crate::storage::get_storage_manager;
 use std::sync::LazyLock;
 
 // 全局下载管理器
 static DOWNLOAD_MANAGER: LazyLock<DownloadManager> = LazyLock::new(DownloadManager::new);
 
+/// 下载到内存允许的最大文件大小
+const MAX_MEMORY_DOWNLOAD_SIZE: u32 = 32 * 1024 * 1024; // 32MB
+
 /// 开始文件下载
 /// 支持实时进度更新和下载取消功能
+/// reuse 不为空且已下载过相同内容时，直接从本地副本硬链接或复制，不再重新下载
+/// concurrency 大于 1 时，16MB 以上的文件拆分为多个范围并发下载（最多 16 个连接）
+/// priority 越大越先开始，默认 0；同时进行的下载数量达到上限时在队列中等待
+/// decompress 不为空时，下载完成后自动解压 .gz / .zst / .br / .xz 单文件压缩格式
 #[tauri::command]
 #[specta::specta]
 pub async fn download_start(
@@ -16,7 +28,11 @@ pub async fn download_start(
     url: String,
     filename: String,
     save_path: Option<String>,
-) -> Result<String, String> {
+    reuse: Option<ReuseMode>,
+    concurrency: Option<u32>,
+    priority: Option<i32>,
+    decompress: Option<AutoDecompress>,
+) -> Result<String, CommandError> {
     // 如果没有指定保存路径，使用默认下载路径
     let final_save_path = match save_path {
         Some(path) => Some(path),
@@ -26,22 +42,197 @@ pub async fn download_start(
     let request = DownloadRequest { url, filename };
 
     DOWNLOAD_MANAGER
-        .download_with_progress(app, request, final_save_path)
+        .download_with_progress(
+            app,
+            request,
+            final_save_path,
+            reuse,
+            concurrency,
+            priority,
+            decompress,
+        )
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 下载整个远程目录，在保存目录下保留文件的相对路径
+/// 每个文件进入下载队列，文件名为“目录名/相对路径”，汇总进度通过 download-directory-progress 事件发送；
+/// 未指定保存目录时下载到系统下载目录下的同名目录，取消时传入目录地址（url）；
+/// estimate_token 为 dataset_estimate_operation（operation 为 download）返回的令牌，忽略规则与估算时一致
+#[tauri::command]
+#[specta::specta]
+pub async fn download_directory(
+    app: tauri::AppHandle,
+    url: String,
+    save_path: Option<String>,
+    estimate_token: String,
+    ignore_patterns: Option<Vec<String>>,
+    concurrency: Option<u32>,
+    priority: Option<i32>,
+) -> Result<String, CommandError> {
+    let save_dir = match save_path {
+        Some(path) => path,
+        None => get_default_download_path(&DownloadManager::directory_name(&url))?,
+    };
+
+    DOWNLOAD_MANAGER
+        .download_directory(
+            app,
+            url,
+            save_dir.into(),
+            &estimate_token,
+            ignore_patterns.as_deref(),
+            concurrency,
+            priority,
+        )
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 续传中断的下载
+/// 从保存路径下已写入的位置继续，远程文件已变化时重新下载
+#[tauri::command]
+#[specta::specta]
+pub async fn download_resume(
+    app: tauri::AppHandle,
+    save_path: String,
+    concurrency: Option<u32>,
+    decompress: Option<AutoDecompress>,
+) -> Result<String, CommandError> {
+    DOWNLOAD_MANAGER
+        .resume_download(app, save_path, concurrency, decompress)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 列出当前连接下可续传的下载
+#[tauri::command]
+#[specta::specta]
+pub async fn download_list_resumable() -> Result<Vec<PartialDownload>, CommandError> {
+    Ok(DOWNLOAD_MANAGER.list_resumable_downloads().await)
+}
+
+/// 暂停下载，id 为下载事件和队列中的任务标识
+/// 排队中的下载暂不开始；进行中的下载中断传输并回到队列，恢复时从已写入的位置继续
+#[tauri::command]
+#[specta::specta]
+pub async fn download_pause(id: String) -> Result<String, CommandError> {
+    DOWNLOAD_MANAGER
+        .pause_download(&id)
+        .map_err(CommandError::from)
+}
+
+/// 恢复已暂停的下载
+#[tauri::command]
+#[specta::specta]
+pub async fn download_resume_task(id: String) -> Result<String, CommandError> {
+    DOWNLOAD_MANAGER
+        .resume_queued_download(&id)
+        .map_err(CommandError::from)
+}
+
+/// 调整排队中下载的顺序，列出的下载按给定顺序排到最前（优先级仍优先于顺序）
+#[tauri::command]
+#[specta::specta]
+pub async fn download_reorder(ids: Vec<String>) -> Result<(), CommandError> {
+    DOWNLOAD_MANAGER.reorder_downloads(&ids);
+    Ok(())
+}
+
+/// 设置同时进行的下载数量上限（默认 3）
+#[tauri::command]
+#[specta::specta]
+pub async fn download_set_max_concurrent(max_concurrent: u32) -> Result<(), CommandError> {
+    DOWNLOAD_MANAGER.set_max_concurrent_downloads(max_concurrent);
+    Ok(())
+}
+
+/// 列出进行中和排队中的下载
+#[tauri::command]
+#[specta::specta]
+pub async fn download_get_queue() -> Result<Vec<QueuedDownload>, CommandError> {
+    Ok(DOWNLOAD_MANAGER.get_download_queue())
+}
+
+/// 列出进行中和排队中的下载及其传输进度、速度（字节/秒）和预计剩余时间
+/// 速度为最近 5 秒的移动平均，供传输管理面板定期刷新
+#[tauri::command]
+#[specta::specta]
+pub async fn download_list_tasks() -> Result<Vec<DownloadTask>, CommandError> {
+    Ok(DOWNLOAD_MANAGER.list_download_tasks())
+}
+
+/// 查找已下载过的相同内容（按 ETag 和文件大小判断）
+/// 存储不提供 ETag 时返回空
+#[tauri::command]
+#[specta::specta]
+pub async fn download_find_existing(url: String) -> Result<Option<ExistingDownload>, CommandError> {
+    DOWNLOAD_MANAGER
+        .find_existing_download(&url)
         .await
+        .map_err(CommandError::from)
 }
 
-/// 取消指定文件的下载
+/// 将小文件直接读取到内存，不写入下载目录
+/// 供插件和前端读取配置、schema 等需要完整内容的小文件，超过 max_size（上限 32MB）时返回错误
 #[tauri::command]
 #[specta::specta]
-pub async fn download_cancel(filename: String) -> Result<String, String> {
-    DOWNLOAD_MANAGER.cancel_download(&filename)
+pub async fn download_to_memory(path: String, max_size: u32) -> Result<Vec<u8>, CommandError> {
+    let max_size = max_size.min(MAX_MEMORY_DOWNLOAD_SIZE) as u64;
+
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    let file_size = client
+        .get_file_size(&path)
+        .await
+        .map_err(|e| CommandError::storage("Failed to get file size", e))?;
+    if file_size > max_size {
+        return Err(CommandError::invalid_input(format!(
+            "File too large: {} bytes exceeds limit of {} bytes",
+            file_size, max_size
+        )));
+    }
+    if file_size == 0 {
+        return Ok(Vec::new());
+    }
+
+    let data = client
+        .read_file_range(&path, 0, file_size)
+        .await
+        .map_err(|e| CommandError::storage("Failed to read file", e))?;
+
+    // 文件在读取期间可能发生变化，再次校验实际大小
+    if data.len() as u64 > max_size {
+        return Err(CommandError::invalid_input(format!(
+            "File too large: {} bytes exceeds limit of {} bytes",
+            data.len(),
+            max_size
+        )));
+    }
+    Ok(data)
+}
+
+/// 取消指定任务的下载，id 为下载事件和队列中的任务标识
+#[tauri::command]
+#[specta::specta]
+pub async fn download_cancel(id: String) -> Result<String, CommandError> {
+    DOWNLOAD_MANAGER
+        .cancel_download(&id)
+        .map_err(CommandError::from)
 }
 
 /// 取消所有正在进行的下载
 #[tauri::command]
 #[specta::specta]
-pub async fn download_cancel_all() -> Result<String, String> {
-    DOWNLOAD_MANAGER.cancel_all_downloads()
+pub async fn download_cancel_all() -> Result<String, CommandError> {
+    DOWNLOAD_MANAGER
+        .cancel_all_downloads()
+        .map_err(CommandError::from)
 }
 
 /// 从压缩包中提取文件下载
@@ -55,7 +246,7 @@ pub async fn download_extract_file(
     entry_path: String,
     entry_filename: String,
     save_path: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     // 如果没有指定保存路径，使用默认下载路径
     let final_save_path = match save_path {
         Some(path) => Some(path),
@@ -73,6 +264,7 @@ pub async fn download_extract_file(
             final_save_path,
         )
         .await
+        .map_err(CommandError::from)
 }
 
 /// 获取系统默认下载路径的内部函数
@@ -0,0 +1,124 @@
+// 命令错误类型
+// 存储、压缩包和下载命令返回结构化的错误：code 供前端按类型处理，message 保留原始描述（部分为 i18n 键），
+// retryable 表示稍后重试可能成功（网络错误、连接失败）
+
+use serde::Serialize;
+
+use crate::storage::traits::StorageError;
+
+/// 前端可按 code 区分处理的命令错误
+#[derive(Debug, Clone, Serialize, specta::Type, thiserror::Error)]
+#[serde(tag = "code", rename_all = "camelCase")]
+pub enum CommandError {
+    /// 尚未连接存储
+    #[error("{message}")]
+    NotConnected { message: String },
+
+    /// 文件、目录或压缩包条目不存在
+    #[error("{message}")]
+    NotFound { message: String },
+
+    /// 认证失败，包括压缩包密码缺失或错误
+    #[error("{message}")]
+    AuthFailed { message: String },
+
+    /// 操作被用户取消
+    #[error("{message}")]
+    Cancelled { message: String },
+
+    /// 网络或连接错误
+    #[error("{message}")]
+    Network { message: String, retryable: bool },
+
+    /// 不支持的压缩格式或存储操作
+    #[error("{message}")]
+    UnsupportedFormat { message: String },
+
+    /// 归档存储类别中的对象，需要先恢复才能读取
+    #[error("{message}")]
+    ObjectArchived { message: String },
+
+    /// 参数或配置无效
+    #[error("{message}")]
+    InvalidInput { message: String },
+
+    /// 本地文件读写错误
+    #[error("{message}")]
+    Io { message: String },
+
+    /// 其他错误
+    #[error("{message}")]
+    Other { message: String },
+}
+
+impl CommandError {
+    pub fn not_connected() -> Self {
+        Self::NotConnected {
+            message: "No storage client connected".to_string(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    /// 在存储错误的描述前加上操作说明，错误类型保持不变
+    pub fn storage(context: &str, error: StorageError) -> Self {
+        let message = format!("{}: {}", context, error);
+        Self::classify(error, message)
+    }
+
+    fn classify(error: StorageError, message: String) -> Self {
+        match error {
+            StorageError::NotConnected => Self::NotConnected { message },
+            StorageError::NotFound(_) => Self::NotFound { message },
+            StorageError::AuthenticationFailed(_) => Self::AuthFailed { message },
+            StorageError::ConnectionFailed(_) | StorageError::NetworkError(_) => Self::Network {
+                message,
+                retryable: true,
+            },
+            StorageError::ProtocolNotSupported(_)
+            | StorageError::UnsupportedProtocol(_)
+            | StorageError::RangeNotSupported(_) => Self::UnsupportedFormat { message },
+            StorageError::ObjectArchived(_) => Self::ObjectArchived { message },
+            StorageError::InvalidConfig(_) => Self::InvalidInput { message },
+            StorageError::IoError(_) => Self::Io { message },
+            // 请求失败的原始描述可能是取消等 i18n 键，能归类时保留原始描述
+            StorageError::RequestFailed(original) => match Self::from(original) {
+                Self::Other { .. } => Self::Other { message },
+                classified => classified,
+            },
+        }
+    }
+}
+
+impl From<StorageError> for CommandError {
+    fn from(error: StorageError) -> Self {
+        let message = error.to_string();
+        Self::classify(error, message)
+    }
+}
+
+/// 内部模块仍以字符串描述错误，按描述中的 i18n 键和常见前缀归类
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        if message.ends_with(".cancelled") || message == "cancelled" {
+            Self::Cancelled { message }
+        } else if message == "archive.passwordRequired" || message == "archive.invalidPassword" {
+            Self::AuthFailed { message }
+        } else if message.starts_with("Unsupported") {
+            Self::UnsupportedFormat { message }
+        } else if message.starts_with("No storage client") {
+            Self::NotConnected { message }
+        } else if message.starts_with("Network error") || message.starts_with("Connection failed") {
+            Self::Network {
+                message,
+                retryable: true,
+            }
+        } else {
+            Self::Other { message }
+        }
+    }
+}
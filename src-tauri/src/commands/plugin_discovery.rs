@@ -1,4 +1,6 @@
 use crate::commands::plugin_installer::get_plugin_cache_dir;
+use crate::storage::manager::{shared_http_client, HttpClientKind};
+use crate::utils::{http_guard, persist};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
@@ -80,15 +82,11 @@ struct NpmMaintainer {
  * 检查插件是否被显式启用
  * 只有在 enabled_plugins.json 文件中的插件才被认为是启用的
  */
-fn is_plugin_enabled(plugin_id: &str) -> bool {
+pub(crate) fn is_plugin_enabled(plugin_id: &str) -> bool {
     if let Ok(cache_dir) = crate::commands::plugin_installer::get_plugin_cache_dir() {
         let enabled_plugins_file = cache_dir.join("enabled_plugins.json");
-        if enabled_plugins_file.exists() {
-            if let Ok(content) = fs::read_to_string(&enabled_plugins_file) {
-                if let Ok(enabled_plugins) = serde_json::from_str::<Vec<String>>(&content) {
-                    return enabled_plugins.contains(&plugin_id.to_string());
-                }
-            }
+        if let Some(enabled_plugins) = persist::read_json::<Vec<String>>(&enabled_plugins_file) {
+            return enabled_plugins.contains(&plugin_id.to_string());
         }
     }
     false // 新插件默认禁用
@@ -492,7 +490,7 @@ async fn search_npm_registry() -> Result<Vec<LocalPluginInfo>, String> {
     let query = "keywords:dataset-viewer keywords:plugin";
     let size = 50; // 最多返回50个结果
 
-    let client = reqwest::Client::new();
+    let client = shared_http_client(HttpClientKind::Transfer);
     let response = client
         .get(search_url)
         .query(&[("text", query), ("size", &size.to_string())])
@@ -508,10 +506,10 @@ async fn search_npm_registry() -> Result<Vec<LocalPluginInfo>, String> {
         ));
     }
 
-    let search_result: NpmSearchResult = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse npm search response: {}", e))?;
+    let search_result: NpmSearchResult =
+        http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| format!("Failed to parse npm search response: {}", e))?;
 
     println!(
         "npm search returned {} results",
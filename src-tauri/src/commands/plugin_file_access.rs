@@ -0,0 +1,263 @@
+// 插件文件随机读取命令
+// 通过句柄按偏移读取当前存储中的远程文件，每个插件的句柄数和单次读取长度都有上限
+// 插件也可以读取当前文件所在目录下的其他文件（如 3D 模型的贴图），读取在后端使用当前连接的认证完成；
+// 允许的目录由宿主加载插件查看器时登记的文件决定，不信任插件传入的路径
+// 虚拟路径命令用同一种路径写法（storage://<路径>[!<条目>]）读取存储中的文件和压缩包内的条目，
+// 同样只能访问插件打开的文件所在目录
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::commands::plugin_discovery::is_plugin_enabled;
+use crate::storage::file_handles::{self, FileHandleInfo, MAX_READ_LENGTH};
+use crate::storage::get_storage_manager;
+use crate::storage::traits::StorageClient;
+use crate::storage::vfs::{VfsPath, VfsStat};
+use crate::utils::protocol_handler::ProtocolHandler;
+
+// 插件查看器当前打开的文件（插件标识 -> 当前连接中的文件路径）
+static PLUGIN_FILES: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 登记插件查看器打开的文件，插件读取同目录文件时以该文件所在目录为范围
+/// 由宿主在加载插件查看器时调用
+#[tauri::command]
+#[specta::specta]
+pub async fn plugin_viewer_attach(plugin_id: String, path: String) -> Result<(), String> {
+    if !is_plugin_enabled(&plugin_id) {
+        return Err(format!("Plugin {} is not enabled", plugin_id));
+    }
+    if path.is_empty() {
+        return Err("Path is required".to_string());
+    }
+    PLUGIN_FILES
+        .lock()
+        .map_err(|_| "Plugin file table is poisoned".to_string())?
+        .insert(plugin_id, path);
+    Ok(())
+}
+
+/// 插件查看器关闭时移除登记，只有登记的仍是该文件时才移除
+#[tauri::command]
+#[specta::specta]
+pub async fn plugin_viewer_detach(plugin_id: String, path: String) -> Result<(), String> {
+    let mut files = PLUGIN_FILES
+        .lock()
+        .map_err(|_| "Plugin file table is poisoned".to_string())?;
+    if files.get(&plugin_id) == Some(&path) {
+        files.remove(&plugin_id);
+    }
+    Ok(())
+}
+
+/// 打开当前存储中的文件，返回句柄和文件大小，未启用的插件不能打开
+/// 与其他读取命令相同，只能打开插件查看器登记的文件或其所在目录（及子目录）中的文件
+#[tauri::command]
+#[specta::specta]
+pub async fn file_open(plugin_id: String, path: String) -> Result<FileHandleInfo, String> {
+    let (client, protocol) = plugin_storage_client(&plugin_id).await?;
+    let relative = match path.split_once("://") {
+        Some((scheme, _)) if scheme != protocol => {
+            return Err(format!(
+                "Path {} does not belong to the current {} connection",
+                path, protocol
+            ))
+        }
+        Some(_) => ProtocolHandler::extract_relative_path(&path, &*client),
+        None => path.clone(),
+    };
+    check_vfs_scope(&plugin_file(&plugin_id)?, &relative, &*client)?;
+    file_handles::open(client, &plugin_id, &path).await
+}
+
+/// 从句柄的指定偏移读取最多 len 字节（上限 16MB），读到文件末尾时返回的数据更短
+#[tauri::command]
+#[specta::specta]
+pub async fn file_pread(handle: String, offset: String, len: u32) -> Result<Vec<u8>, String> {
+    let offset = offset
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid offset: {}", offset))?;
+    file_handles::pread(&handle, offset, len).await
+}
+
+/// 关闭句柄
+#[tauri::command]
+#[specta::specta]
+pub async fn file_close(handle: String) -> Result<(), String> {
+    file_handles::close(&handle)
+}
+
+/// 读取与插件查看器打开的文件同一目录（或其子目录）下的文件
+/// path 为相对该文件所在目录的路径，不允许使用绝对路径、协议 URL 或 ..
+/// 未指定 length 时读取到文件末尾，单次最多 16MB
+#[tauri::command]
+#[specta::specta]
+pub async fn plugin_storage_read(
+    plugin_id: String,
+    path: String,
+    offset: Option<String>,
+    length: Option<u32>,
+) -> Result<Vec<u8>, String> {
+    let (client, protocol) = plugin_storage_client(&plugin_id).await?;
+    let base_path = plugin_file(&plugin_id)?;
+    let resolved = resolve_relative_path(&protocol, &base_path, &path)?;
+    let offset = parse_offset(offset)?;
+
+    let file_size = client
+        .get_file_size(&resolved)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let remaining = file_size.saturating_sub(offset);
+    let length = match length {
+        Some(length) => (length as u64).min(remaining),
+        None => remaining,
+    };
+    if length > MAX_READ_LENGTH as u64 {
+        return Err(format!(
+            "Read length {} exceeds limit of {} bytes, use file_open for large files",
+            length, MAX_READ_LENGTH
+        ));
+    }
+    if length == 0 {
+        return Ok(Vec::new());
+    }
+
+    client
+        .read_file_range(&resolved, offset, length)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", resolved, e))
+}
+
+/// 获取虚拟路径对应的文件信息
+/// 路径格式为 `storage://<路径>[!<压缩包内条目>]`，只能指向插件打开的文件所在目录（或其子目录）
+#[tauri::command]
+#[specta::specta]
+pub async fn vfs_stat(plugin_id: String, path: String) -> Result<VfsStat, String> {
+    let (client, protocol) = plugin_storage_client(&plugin_id).await?;
+    let vfs_path = VfsPath::parse(&path, Some(&protocol), &*client)?;
+    check_vfs_scope(&plugin_file(&plugin_id)?, &vfs_path.path, &*client)?;
+    vfs_path.stat(client).await
+}
+
+/// 读取虚拟路径对应的文件或压缩包条目
+/// 未指定 length 时读取到末尾，单次最多 16MB
+#[tauri::command]
+#[specta::specta]
+pub async fn vfs_read(
+    plugin_id: String,
+    path: String,
+    offset: Option<String>,
+    length: Option<u32>,
+) -> Result<Vec<u8>, String> {
+    let (client, protocol) = plugin_storage_client(&plugin_id).await?;
+    let vfs_path = VfsPath::parse(&path, Some(&protocol), &*client)?;
+    check_vfs_scope(&plugin_file(&plugin_id)?, &vfs_path.path, &*client)?;
+    vfs_path.read(client, parse_offset(offset)?, length).await
+}
+
+/// 校验插件已启用，返回当前存储客户端和协议
+async fn plugin_storage_client(
+    plugin_id: &str,
+) -> Result<(Arc<dyn StorageClient + Send + Sync>, String), String> {
+    if !is_plugin_enabled(plugin_id) {
+        return Err(format!("Plugin {} is not enabled", plugin_id));
+    }
+
+    let manager_arc = get_storage_manager().await;
+    let manager = manager_arc.read().await;
+    let client = manager
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+    Ok((client, manager.get_current_protocol().unwrap_or_default()))
+}
+
+/// 插件查看器登记的文件，没有登记时插件不能读取其他文件
+fn plugin_file(plugin_id: &str) -> Result<String, String> {
+    PLUGIN_FILES
+        .lock()
+        .map_err(|_| "Plugin file table is poisoned".to_string())?
+        .get(plugin_id)
+        .cloned()
+        .ok_or_else(|| format!("Plugin {} has no opened file", plugin_id))
+}
+
+/// 虚拟路径（压缩包条目时为压缩包）必须是插件打开的文件，或位于该文件所在目录及其子目录中
+fn check_vfs_scope(base_path: &str, path: &str, client: &dyn StorageClient) -> Result<(), String> {
+    let base_path = if base_path.contains("://") {
+        ProtocolHandler::extract_relative_path(base_path, client)
+    } else {
+        base_path.to_string()
+    };
+    if path == base_path {
+        return Ok(());
+    }
+
+    let escapes = path.split(['/', '\\']).any(|segment| segment == "..");
+    let in_dir = match base_path.rsplit_once('/') {
+        Some((dir, _)) => path.starts_with(&format!("{}/", dir)),
+        None => !path.starts_with('/'),
+    };
+    if escapes || !in_dir {
+        return Err(format!(
+            "Plugins may only read files next to the current file: {}",
+            path
+        ));
+    }
+    Ok(())
+}
+
+fn parse_offset(offset: Option<String>) -> Result<u64, String> {
+    match offset {
+        Some(offset) => offset
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid offset: {}", offset)),
+        None => Ok(0),
+    }
+}
+
+/// 将插件请求的相对路径解析为当前连接中的路径，只允许访问 base_path 所在目录及其子目录
+fn resolve_relative_path(protocol: &str, base_path: &str, path: &str) -> Result<String, String> {
+    let path = path.replace('\\', "/");
+    let is_absolute = path.starts_with('/')
+        || path.starts_with('~')
+        || path.contains("://")
+        || path.chars().nth(1) == Some(':');
+    if is_absolute {
+        return Err(format!(
+            "Plugins may only read paths relative to the current file: {}",
+            path
+        ));
+    }
+
+    // 基准路径为协议 URL 时必须属于当前连接
+    if let Some((scheme, _)) = base_path.split_once("://") {
+        if scheme != protocol {
+            return Err(format!(
+                "Path {} does not belong to the current {} connection",
+                base_path, protocol
+            ));
+        }
+    }
+
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                return Err(format!(
+                    "Plugins may not read outside the current directory: {}",
+                    path
+                ))
+            }
+            segment => segments.push(segment),
+        }
+    }
+    if segments.is_empty() {
+        return Err("Path is required".to_string());
+    }
+
+    Ok(match base_path.rsplit_once('/') {
+        Some((dir, _)) => format!("{}/{}", dir, segments.join("/")),
+        None => segments.join("/"),
+    })
+}
@@ -1,5 +1,4 @@
 use hex;
-use reqwest;
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use specta::Type;
@@ -7,6 +6,12 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::command;
 
+use crate::storage::manager::{shared_http_client, HttpClientKind};
+use crate::utils::{http_guard, persist};
+
+/// 插件 tarball 的大小上限
+const MAX_TARBALL_BYTES: u64 = 256 * 1024 * 1024; // 256MB
+
 #[derive(Debug, Serialize, Deserialize, Type)]
 pub struct PluginInstallResult {
     pub success: bool,
@@ -309,7 +314,7 @@ async fn download_and_install_plugin_version(
 ) -> Result<PluginInstallResult, String> {
     // 1. 获取特定版本的包信息
     let registry_url = format!("https://registry.npmjs.org/{}/{}", package_name, version);
-    let client = reqwest::Client::new();
+    let client = shared_http_client(HttpClientKind::Transfer);
 
     let response = client
         .get(&registry_url)
@@ -325,10 +330,10 @@ async fn download_and_install_plugin_version(
         ));
     }
 
-    let package_info: NpmVersionDetail = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse package version info: {}", e))?;
+    let package_info: NpmVersionDetail =
+        http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| format!("Failed to parse package version info: {}", e))?;
 
     // 2. 下载 tarball
     let tarball_response = client
@@ -338,8 +343,7 @@ async fn download_and_install_plugin_version(
         .await
         .map_err(|e| format!("Failed to download tarball: {}", e))?;
 
-    let tarball_bytes = tarball_response
-        .bytes()
+    let tarball_bytes = http_guard::read_body(tarball_response, MAX_TARBALL_BYTES)
         .await
         .map_err(|e| format!("Failed to read tarball: {}", e))?;
 
@@ -559,7 +563,7 @@ async fn download_and_install_plugin(
 ) -> Result<PluginInstallResult, String> {
     // 1. 获取包信息
     let registry_url = format!("https://registry.npmjs.org/{}", package_name);
-    let client = reqwest::Client::new();
+    let client = shared_http_client(HttpClientKind::Transfer);
 
     let response = client
         .get(&registry_url)
@@ -575,10 +579,10 @@ async fn download_and_install_plugin(
         ));
     }
 
-    let package_info: NpmPackageInfo = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse package info: {}", e))?;
+    let package_info: NpmPackageInfo =
+        http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| format!("Failed to parse package info: {}", e))?;
 
     // 获取最新版本的信息
     let latest_version = &package_info.dist_tags.latest;
@@ -597,8 +601,7 @@ async fn download_and_install_plugin(
         .await
         .map_err(|e| format!("Failed to download tarball: {}", e))?;
 
-    let tarball_bytes = tarball_response
-        .bytes()
+    let tarball_bytes = http_guard::read_body(tarball_response, MAX_TARBALL_BYTES)
         .await
         .map_err(|e| format!("Failed to read tarball: {}", e))?;
 
@@ -634,7 +637,7 @@ async fn download_and_install_plugin(
  */
 async fn get_latest_plugin_version(package_name: &str) -> Result<String, String> {
     let registry_url = format!("https://registry.npmjs.org/{}", package_name);
-    let client = reqwest::Client::new();
+    let client = shared_http_client(HttpClientKind::Transfer);
 
     let response = client
         .get(&registry_url)
@@ -647,10 +650,10 @@ async fn get_latest_plugin_version(package_name: &str) -> Result<String, String>
         return Err(format!("Package {} not found", package_name));
     }
 
-    let package_info: NpmPackageInfo = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse package info: {}", e))?;
+    let package_info: NpmPackageInfo =
+        http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| format!("Failed to parse package info: {}", e))?;
 
     Ok(package_info.dist_tags.latest)
 }
@@ -940,14 +943,8 @@ pub async fn plugin_toggle(plugin_id: String, enabled: bool) -> Result<bool, Str
     let enabled_plugins_file = cache_dir.join("enabled_plugins.json");
 
     // 读取现有的启用列表
-    let mut enabled_plugins: Vec<String> = if enabled_plugins_file.exists() {
-        match fs::read_to_string(&enabled_plugins_file) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => Vec::new(),
-        }
-    } else {
-        Vec::new()
-    };
+    let mut enabled_plugins: Vec<String> =
+        persist::read_json(&enabled_plugins_file).unwrap_or_default();
 
     if enabled {
         // 启用插件：添加到启用列表
@@ -967,7 +964,7 @@ pub async fn plugin_toggle(plugin_id: String, enabled: bool) -> Result<bool, Str
     let json_content = serde_json::to_string_pretty(&enabled_plugins)
         .map_err(|e| format!("Failed to serialize enabled plugins: {}", e))?;
 
-    fs::write(&enabled_plugins_file, json_content)
+    persist::write_atomic(&enabled_plugins_file, json_content)
         .map_err(|e| format!("Failed to write enabled plugins file: {}", e))?;
 
     Ok(enabled)
@@ -0,0 +1,201 @@
+// 内容预览命令
+// 通过可组合的预览管线读取并处理文件内容
+
+use crate::archive::stream_control::StreamControl;
+use crate::preview::content_search::{ContentSearcher, FileSearchRequest, FileSearchResult};
+use crate::preview::jsonl::{JsonlReader, JsonlRecords};
+use crate::preview::parquet::{ParquetMetadata, ParquetReader, ParquetRows, ParquetRowsRequest};
+use crate::preview::pipeline::{PreviewPipeline, PreviewRequest, PreviewResult};
+use crate::preview::spreadsheet::{SheetInfo, SheetRange, SheetRangeRequest, SpreadsheetReader};
+use crate::preview::table::{TablePreview, TablePreviewRequest, TableReader};
+use crate::storage::get_storage_manager;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::Emitter;
+use tokio::sync::broadcast;
+
+// 进行中的文件内容搜索（搜索标识 -> 取消信号）
+static ACTIVE_FILE_SEARCHES: LazyLock<Mutex<HashMap<String, broadcast::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 按请求中的步骤预览文件或压缩包条目
+/// 步骤按顺序执行，例如 [decompress, transcode, csvHead] 可直接预览 GBK 编码的 csv.gz
+/// 指定 requestId 时可通过 archive_preview_pause / resume / cancel 暂停、恢复或取消
+/// 指定 encoding 时按该编码转码，结果中的 encoding 为实际使用的编码
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_run(request: PreviewRequest) -> Result<PreviewResult, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    // 指定请求标识时登记预览流，预览结束后自动移除
+    let stream = request
+        .request_id
+        .clone()
+        .map(StreamControl::register)
+        .transpose()?;
+    let client = match &stream {
+        Some(stream) => stream.wrap(client),
+        None => client,
+    };
+
+    let result = PreviewPipeline::run(client, request, stream.as_ref()).await;
+    match stream {
+        // 取消后读取失败的描述各不相同，统一返回取消
+        Some(stream) if result.is_err() && stream.is_cancelled() => {
+            Err("preview.cancelled".to_string())
+        }
+        _ => result,
+    }
+}
+
+/// 列出表格文件（.xlsx / .xls / .ods 等）中的工作表
+/// 远程文件会先下载到本地临时目录，之后的读取复用该副本；
+/// 超大文件默认只抽样下载，full_download 为 true 时完整下载
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_list_sheets(
+    path: String,
+    full_download: Option<bool>,
+) -> Result<Vec<SheetInfo>, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    SpreadsheetReader::list_sheets(client, &path, full_download.unwrap_or(false)).await
+}
+
+/// 读取工作表中的单元格区域
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_read_sheet_range(request: SheetRangeRequest) -> Result<SheetRange, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    SpreadsheetReader::read_range(client, request).await
+}
+
+/// 读取 Parquet 文件的元数据（行组、列 schema、压缩编码、总行数）
+/// 只通过范围读取访问文件尾部，不下载整个文件
+#[tauri::command]
+#[specta::specta]
+pub async fn parquet_get_metadata(path: String) -> Result<ParquetMetadata, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    ParquetReader::get_metadata(client, &path).await
+}
+
+/// 读取 Parquet 文件的一页行数据（offset + limit），可只读取部分列或指定行组
+/// 只通过范围读取下载所需的列数据块，适合预览远程的大文件
+#[tauri::command]
+#[specta::specta]
+pub async fn parquet_read_rows(request: ParquetRowsRequest) -> Result<ParquetRows, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    ParquetReader::read_rows(client, request).await
+}
+
+/// 以结构化表格预览 CSV / TSV 文件或压缩包条目
+/// 读取文件开头（默认 4MB），自动识别编码、分隔符和表头，推断每列的类型
+#[tauri::command]
+#[specta::specta]
+pub async fn dataset_preview_table(request: TablePreviewRequest) -> Result<TablePreview, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    TableReader::preview(client, request).await
+}
+
+/// 读取 JSONL / NDJSON 文件中从 start_record 开始的 count 条记录（空行不计）
+/// 行偏移索引在多次调用间缓存并按需扩展，跳转到已扫描过的位置时不需要从头读取
+#[tauri::command]
+#[specta::specta]
+pub async fn jsonl_read_records(
+    path: String,
+    start_record: String,
+    count: u32,
+) -> Result<JsonlRecords, String> {
+    let start_record = start_record
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid start record: {}", start_record))?;
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    JsonlReader::read_records(client, &path, start_record, count).await
+}
+
+/// 在文件或压缩包条目中搜索文本或正则表达式，返回匹配的行号、偏移和所在行
+/// 分块流式读取，不下载整个文件；进度通过 file-search-progress 事件发送，可通过 file_search_cancel 取消
+#[tauri::command]
+#[specta::specta]
+pub async fn file_search_content(
+    app: tauri::AppHandle,
+    request: FileSearchRequest,
+) -> Result<FileSearchResult, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+
+    let search_id = request.search_id.clone();
+    let cancel_rx = {
+        let mut searches = ACTIVE_FILE_SEARCHES.lock().unwrap();
+        if searches.contains_key(&search_id) {
+            return Err(format!("Search is already running: {}", search_id));
+        }
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        searches.insert(search_id.clone(), cancel_tx);
+        cancel_rx
+    };
+
+    let result = ContentSearcher::search(client, request, cancel_rx, move |progress| {
+        let _ = app.emit("file-search-progress", &progress);
+    })
+    .await;
+
+    ACTIVE_FILE_SEARCHES.lock().unwrap().remove(&search_id);
+    result
+}
+
+/// 取消文件内容搜索，file_search_content 返回取消前找到的匹配
+#[tauri::command]
+#[specta::specta]
+pub async fn file_search_cancel(search_id: String) -> Result<(), String> {
+    let searches = ACTIVE_FILE_SEARCHES.lock().unwrap();
+    let cancel_tx = searches
+        .get(&search_id)
+        .ok_or_else(|| format!("No active search found for: {}", search_id))?;
+    let _ = cancel_tx.send(());
+    Ok(())
+}
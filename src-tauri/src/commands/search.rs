@@ -0,0 +1,103 @@
+// 搜索历史与快速打开命令
+// 列出、收藏、删除和重新执行历史中的搜索，以及快速打开面板的模糊匹配
+
+use crate::archive::search::ArchiveSearchReport;
+use crate::commands::archive::archive_search;
+use crate::commands::dataset::{
+    dataset_index_search, dataset_index_search_content, get_current_connection_key,
+};
+use crate::dataset::filename_index::{ContentSearchResult, FilenameSearchResult};
+use crate::dataset::quick_open::{QuickOpen, QuickOpenRequest, QuickOpenResult};
+use crate::dataset::search_history::{self, SearchRecord, SearchTarget};
+use crate::storage::get_storage_manager;
+use serde::{Deserialize, Serialize};
+
+/// 重新执行搜索的结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SearchRunResult {
+    Archives { report: ArchiveSearchReport },
+    Filenames { result: FilenameSearchResult },
+    Contents { result: ContentSearchResult },
+}
+
+/// 列出当前连接的搜索历史，收藏的搜索在前，其余按执行时间从新到旧
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history_list() -> Result<Vec<SearchRecord>, String> {
+    let connection_key = get_current_connection_key().await?;
+    Ok(search_history::list(&connection_key))
+}
+
+/// 收藏或取消收藏搜索，收藏的搜索不会随历史清理
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history_pin(
+    id: String,
+    pinned: bool,
+    name: Option<String>,
+) -> Result<SearchRecord, String> {
+    search_history::pin(&id, pinned, name)
+}
+
+/// 删除一条搜索记录
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history_delete(id: String) -> Result<(), String> {
+    search_history::remove(&id);
+    Ok(())
+}
+
+/// 清除当前连接的搜索历史，保留收藏的搜索
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history_clear() -> Result<(), String> {
+    let connection_key = get_current_connection_key().await?;
+    search_history::clear(&connection_key);
+    Ok(())
+}
+
+/// 按记录的目标和条件重新执行搜索，并更新记录的结果数量和执行时间
+/// 压缩包搜索的匹配结果同样通过 archive-search-matches 事件发送，search_id 用于标识结果和 archive_search_cancel
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history_rerun(
+    app: tauri::AppHandle,
+    id: String,
+    search_id: String,
+) -> Result<SearchRunResult, String> {
+    let record = search_history::get(&id).ok_or_else(|| format!("Search not found: {}", id))?;
+    if record.connection_key != get_current_connection_key().await? {
+        return Err("Search belongs to a different connection".to_string());
+    }
+
+    match record.target {
+        SearchTarget::Archives { paths, options } => {
+            archive_search(app, search_id, paths, record.query, Some(options))
+                .await
+                .map(|report| SearchRunResult::Archives { report })
+                .map_err(|e| e.to_string())
+        }
+        SearchTarget::Filenames { limit } => dataset_index_search(record.query, limit)
+            .await
+            .map(|result| SearchRunResult::Filenames { result }),
+        SearchTarget::Contents { limit } => dataset_index_search_content(record.query, limit)
+            .await
+            .map(|result| SearchRunResult::Contents { result }),
+    }
+}
+
+/// 快速打开：对最近打开、书签、当前连接的文件名索引和保存的连接做模糊匹配
+/// 最近打开和书签由前端传入，未连接存储时只匹配这些条目和连接
+#[tauri::command]
+#[specta::specta]
+pub async fn quick_open_search(request: QuickOpenRequest) -> Result<QuickOpenResult, String> {
+    let connection_key = get_storage_manager()
+        .await
+        .read()
+        .await
+        .get_current_connection_key();
+    tokio::task::spawn_blocking(move || QuickOpen::search(&request, connection_key.as_deref()))
+        .await
+        .map_err(|e| format!("Quick open task failed: {}", e))?
+}
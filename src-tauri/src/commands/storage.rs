@@ -1,32 +1,70 @@
 // 统一存储接口命令
 // 提供多协议存储连接和文件操作能力
 
+use crate::commands::error::CommandError;
+use crate::dataset::stats::{DirectoryStats, DirectoryStatsCalculator};
+use crate::media::thumbnails;
+use crate::storage::manager::{self, HttpPoolSettings};
+use crate::storage::session::{SessionStore, StoredCookie};
+use crate::storage::traits::{
+    ObjectMetadata, ObjectVersion, ParquetSplit, ProgressCallback, RepositoryInfo,
+    RepositoryRevision, StorageCapabilities, StorageError,
+};
+use crate::storage::upload::UploadProgress;
+use crate::storage::visibility::VisibilitySettings;
+use crate::storage::watch;
 use crate::storage::{get_storage_manager, ConnectionConfig, DirectoryResult, ListOptions};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use tauri::Emitter;
+use tokio::sync::broadcast;
+
+// 进行中的上传（上传标识 -> 取消信号）
+static ACTIVE_UPLOADS: LazyLock<Mutex<HashMap<String, broadcast::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 进行中的目录统计（目录路径 -> 取消信号）
+static ACTIVE_STATS: LazyLock<Mutex<HashMap<String, broadcast::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// 监听中的目录（连接标识 + 目录路径 -> 取消信号）
+static ACTIVE_WATCHES: LazyLock<Mutex<HashMap<String, broadcast::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 停止所有目录监听，切换或断开连接时调用，监听任务不会继续使用旧连接的客户端
+pub(crate) fn cancel_all_watches() {
+    for (_, cancel_tx) in ACTIVE_WATCHES.lock().unwrap().drain() {
+        let _ = cancel_tx.send(());
+    }
+}
 
 /// 连接到存储服务
 /// 支持本地文件系统、WebDAV、S3、HuggingFace 等多种协议
 #[tauri::command]
 #[specta::specta]
-pub async fn storage_connect(config: ConnectionConfig) -> Result<bool, String> {
+pub async fn storage_connect(config: ConnectionConfig) -> Result<bool, CommandError> {
+    cancel_all_watches();
     let manager_arc = get_storage_manager().await;
     let mut manager = manager_arc.write().await;
 
     match manager.connect(&config).await {
         Ok(_) => Ok(true),
-        Err(e) => Err(format!("Connection failed: {}", e)),
+        Err(e) => Err(CommandError::storage("Connection failed", e)),
     }
 }
 
 /// 断开存储连接
 #[tauri::command]
 #[specta::specta]
-pub async fn storage_disconnect() -> Result<bool, String> {
+pub async fn storage_disconnect() -> Result<bool, CommandError> {
+    cancel_all_watches();
     let manager_arc = get_storage_manager().await;
     let mut manager = manager_arc.write().await;
 
     match manager.disconnect().await {
         Ok(_) => Ok(true),
-        Err(e) => Err(format!("Disconnect failed: {}", e)),
+        Err(e) => Err(CommandError::storage("Disconnect failed", e)),
     }
 }
 
@@ -37,12 +75,543 @@ pub async fn storage_disconnect() -> Result<bool, String> {
 pub async fn storage_list(
     path: String,
     options: Option<ListOptions>,
-) -> Result<DirectoryResult, String> {
+) -> Result<DirectoryResult, CommandError> {
     let manager_arc = get_storage_manager().await;
     let manager = manager_arc.read().await;
 
     match manager.list_directory(&path, options.as_ref()).await {
         Ok(result) => Ok(result),
-        Err(e) => Err(format!("List directory failed: {}", e)),
+        Err(e) => Err(CommandError::storage("List directory failed", e)),
+    }
+}
+
+/// 恢复归档存储中的对象（S3 Glacier / OSS Archive）
+/// 读取归档对象返回 "Object is archived" 错误时，前端可调用此命令发起恢复
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_restore_object(
+    path: String,
+    days: Option<u32>,
+    tier: Option<String>,
+) -> Result<String, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .restore_object(&path, days.unwrap_or(1), tier.as_deref())
+        .await
+        .map_err(|e| CommandError::storage("Restore object failed", e))
+}
+
+/// 列出对象的历史版本（需要 bucket 开启版本控制）
+/// 返回的 path 可直接传给读取和下载命令
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_list_object_versions(
+    path: String,
+) -> Result<Vec<ObjectVersion>, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .list_object_versions(&path)
+        .await
+        .map_err(|e| CommandError::storage("List object versions failed", e))
+}
+
+/// 列出仓库的分支、标签和转换分支（HuggingFace）
+/// 返回的 path 带 @revision 后缀，可直接用于浏览、读取和下载该版本的文件
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_list_revisions(path: String) -> Result<Vec<RepositoryRevision>, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .list_revisions(&path)
+        .await
+        .map_err(|e| CommandError::storage("List revisions failed", e))
+}
+
+/// 获取 HuggingFace 数据集卡片（描述、许可证、标签、下载量、各划分大小）
+/// 用于在浏览文件前展示数据集主页，path 为数据集内的任意路径
+#[tauri::command]
+#[specta::specta]
+pub async fn huggingface_get_dataset_info(path: String) -> Result<RepositoryInfo, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .get_repository_info(&path)
+        .await
+        .map_err(|e| CommandError::storage("Get dataset info failed", e))
+}
+
+/// 列出 HuggingFace 自动转换为 Parquet 的配置和划分
+/// 返回各分片的下载 URL 和转换分支中的文件路径，原始仓库不是 Parquet 格式时也可以直接预览表格
+#[tauri::command]
+#[specta::specta]
+pub async fn huggingface_list_parquet_splits(
+    path: String,
+) -> Result<Vec<ParquetSplit>, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .list_parquet_splits(&path)
+        .await
+        .map_err(|e| CommandError::storage("List parquet splits failed", e))
+}
+
+/// 检测文件的 MIME 类型（扩展名优先，无法识别时读取文件头判断）
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_detect_mime(path: String) -> Result<String, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .detect_mime(&path)
+        .await
+        .map_err(|e| CommandError::storage("Detect MIME failed", e))
+}
+
+/// 获取文件的最新元数据（大小、ETag、修改时间），前端据此提示已打开的文件在远程发生了变化
+/// 远程文件已变化时同时清除该文件的读取缓存
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_get_metadata(path: String) -> Result<ObjectMetadata, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .get_metadata(&path)
+        .await
+        .map_err(|e| CommandError::storage("Get metadata failed", e))
+}
+
+/// 生成图片文件（PNG、JPEG、WebP、TIFF 等）的缩略图（JPEG），路径可以指向压缩包内的条目
+/// 结果按文件 ETag + 路径缓存到磁盘，同样的缩略图也可通过 thumbnail:// 协议加载
+/// max_dimension 为缩略图最长边，默认 256，最大 1024
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_thumbnail(
+    path: String,
+    max_dimension: Option<u32>,
+) -> Result<Vec<u8>, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    thumbnails::file_thumbnail(client, &path, max_dimension)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 上传本地文件到当前存储的指定路径，已存在的文件会被覆盖（目前支持 WebDAV 和 S3 兼容存储）
+/// 进度通过 storage-upload-progress 事件发送，可通过 storage_upload_cancel 取消
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_upload(
+    app: tauri::AppHandle,
+    upload_id: String,
+    local_path: String,
+    path: String,
+) -> Result<(), CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    let mut cancel_rx = {
+        let mut uploads = ACTIVE_UPLOADS.lock().unwrap();
+        if uploads.contains_key(&upload_id) {
+            return Err(CommandError::invalid_input(format!(
+                "Upload is already running: {}",
+                upload_id
+            )));
+        }
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        uploads.insert(upload_id.clone(), cancel_tx);
+        cancel_rx
+    };
+
+    // 进度每变化 1% 发送一次
+    let event_id = upload_id.clone();
+    let last_percent = AtomicU64::new(u64::MAX);
+    let progress_callback: ProgressCallback = Arc::new(move |uploaded: u64, total: u64| {
+        let percent = if total > 0 {
+            uploaded * 100 / total
+        } else {
+            100
+        };
+        if last_percent.swap(percent, Ordering::Relaxed) != percent {
+            let progress = UploadProgress {
+                upload_id: event_id.clone(),
+                uploaded: uploaded.to_string(),
+                total: total.to_string(),
+            };
+            let _ = app.emit("storage-upload-progress", &progress);
+        }
+    });
+
+    let result = client
+        .upload_file(
+            std::path::Path::new(&local_path),
+            &path,
+            Some(progress_callback),
+            Some(&mut cancel_rx),
+        )
+        .await
+        .map_err(|e| CommandError::storage("Upload failed", e));
+
+    ACTIVE_UPLOADS.lock().unwrap().remove(&upload_id);
+    result
+}
+
+/// 取消进行中的上传
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_upload_cancel(upload_id: String) -> Result<(), CommandError> {
+    let uploads = ACTIVE_UPLOADS.lock().unwrap();
+    let cancel_tx = uploads.get(&upload_id).ok_or_else(|| {
+        CommandError::invalid_input(format!("No active upload found for: {}", upload_id))
+    })?;
+    let _ = cancel_tx.send(());
+    Ok(())
+}
+
+/// 删除文件或目录，目录会连同其中的所有内容一起删除
+/// 本地文件默认移到系统回收站，permanent 为 true 时永久删除；远程存储没有回收站，总是永久删除
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_delete(
+    path: String,
+    is_directory: bool,
+    permanent: Option<bool>,
+) -> Result<(), CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    if !permanent.unwrap_or(false) {
+        match client.move_to_trash(&path).await {
+            Err(StorageError::ProtocolNotSupported(_)) => {}
+            result => return result.map_err(|e| CommandError::storage("Delete failed", e)),
+        }
+    }
+
+    let result = if is_directory {
+        client.delete_directory(&path).await
+    } else {
+        client.delete_file(&path).await
+    };
+    result.map_err(|e| CommandError::storage("Delete failed", e))
+}
+
+/// 重命名或移动文件、目录，目标已存在时覆盖
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_rename(from: String, to: String) -> Result<(), CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .rename(&from, &to)
+        .await
+        .map_err(|e| CommandError::storage("Rename failed", e))
+}
+
+/// 创建目录，父目录不存在时一并创建
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_mkdir(path: String) -> Result<(), CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    client
+        .create_directory(&path)
+        .await
+        .map_err(|e| CommandError::storage("Create directory failed", e))
+}
+
+/// 递归统计目录的总大小、文件数量和扩展名分布，用于在下载前估算数据集规模
+/// 统计进度通过 storage-stats-progress 事件发送，可通过 storage_compute_stats_cancel 取消
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_compute_stats(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<DirectoryStats, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    let mut cancel_rx = {
+        let mut stats = ACTIVE_STATS.lock().unwrap();
+        if stats.contains_key(&path) {
+            return Err(CommandError::invalid_input(format!(
+                "Stats are already being computed for: {}",
+                path
+            )));
+        }
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        stats.insert(path.clone(), cancel_tx);
+        cancel_rx
+    };
+
+    let result = DirectoryStatsCalculator::compute(client, &path, &mut cancel_rx, |progress| {
+        let _ = app.emit("storage-stats-progress", progress);
+    })
+    .await;
+
+    ACTIVE_STATS.lock().unwrap().remove(&path);
+    result.map_err(CommandError::from)
+}
+
+/// 取消进行中的目录统计
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_compute_stats_cancel(path: String) -> Result<(), CommandError> {
+    let stats = ACTIVE_STATS.lock().unwrap();
+    let cancel_tx = stats.get(&path).ok_or_else(|| {
+        CommandError::invalid_input(format!("No active stats computation found for: {}", path))
+    })?;
+    let _ = cancel_tx.send(());
+    Ok(())
+}
+
+/// 在后台监听目录变化，立即返回；已在监听的目录不会重复监听
+/// 本地目录使用系统文件事件，其他协议定期重新列出目录并按 ETag 比较
+/// 检测到新增、删除或修改的条目时发送 directory-changed 事件，通过 storage_watch_stop 停止
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_watch_start(app: tauri::AppHandle, path: String) -> Result<(), CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+    let key = watch_key(&path).await;
+
+    let (cancel_tx, mut cancel_rx) = {
+        let mut watches = ACTIVE_WATCHES.lock().unwrap();
+        if watches.contains_key(&key) {
+            return Ok(());
+        }
+        let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
+        watches.insert(key.clone(), cancel_tx.clone());
+        (cancel_tx, cancel_rx)
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let result = watch::watch_directory(client, &path, &mut cancel_rx, |changed| {
+            let _ = app.emit("directory-changed", &changed);
+        })
+        .await;
+        if let Err(e) = result {
+            log::warn!("监听目录失败 {}: {}", path, e);
+        }
+
+        // 取消后可能已重新监听同一目录，只移除自己的登记
+        let mut watches = ACTIVE_WATCHES.lock().unwrap();
+        if watches
+            .get(&key)
+            .is_some_and(|registered| registered.same_channel(&cancel_tx))
+        {
+            watches.remove(&key);
+        }
+    });
+    Ok(())
+}
+
+/// 停止监听目录变化
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_watch_stop(path: String) -> Result<(), CommandError> {
+    let key = watch_key(&path).await;
+    if let Some(cancel_tx) = ACTIVE_WATCHES.lock().unwrap().remove(&key) {
+        let _ = cancel_tx.send(());
+    }
+    Ok(())
+}
+
+/// 监听登记的键，不同连接上的同名目录分别监听
+async fn watch_key(path: &str) -> String {
+    format!("{}\0{}", manager::current_connection_key().await, path)
+}
+
+/// 获取当前连接支持的写操作（上传、删除、重命名、创建目录），前端据此隐藏不支持的操作
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_get_capabilities() -> Result<StorageCapabilities, CommandError> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(CommandError::not_connected)?;
+
+    Ok(client.capabilities())
+}
+
+/// 打开 SSO 登录窗口
+/// 用户在窗口中完成登录后，调用 storage_sso_capture 保存会话
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_sso_open(app: tauri::AppHandle, url: String) -> Result<String, CommandError> {
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+    let parsed = url::Url::parse(&url)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid login URL: {}", e)))?;
+    let window_label = format!(
+        "sso-login-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+
+    WebviewWindowBuilder::new(&app, &window_label, WebviewUrl::External(parsed))
+        .title("Sign in")
+        .inner_size(900.0, 700.0)
+        .build()
+        .map_err(|e| format!("Failed to create login window: {}", e))?;
+
+    Ok(window_label)
+}
+
+/// 从 SSO 登录窗口捕获会话 Cookie 并按连接地址持久化，随后关闭登录窗口
+/// 返回保存的 Cookie 数量，重新连接后生效
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_sso_capture(
+    app: tauri::AppHandle,
+    window_label: String,
+    url: String,
+) -> Result<u32, CommandError> {
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| CommandError::NotFound {
+            message: format!("Login window not found: {}", window_label),
+        })?;
+    let parsed = url::Url::parse(&url)
+        .map_err(|e| CommandError::invalid_input(format!("Invalid connection URL: {}", e)))?;
+
+    let cookies: Vec<StoredCookie> = window
+        .cookies_for_url(parsed)
+        .map_err(|e| format!("Failed to read cookies: {}", e))?
+        .iter()
+        .map(|cookie| StoredCookie {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            expires: cookie.expires_datetime().map(|t| t.unix_timestamp()),
+        })
+        .collect();
+
+    if cookies.is_empty() {
+        return Err(CommandError::AuthFailed {
+            message: "No session cookies found, please complete the login first".to_string(),
+        });
     }
+
+    SessionStore::save(&url, &cookies)?;
+    let _ = window.close();
+
+    Ok(cookies.len() as u32)
+}
+
+/// 清除连接地址保存的 SSO 会话
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_sso_clear(url: String) -> Result<(), CommandError> {
+    SessionStore::clear(&url).map_err(CommandError::from)
+}
+
+/// 获取隐藏文件与系统文件的显示设置
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_get_visibility_settings() -> Result<VisibilitySettings, CommandError> {
+    Ok(VisibilitySettings::current())
+}
+
+/// 更新隐藏文件与系统文件的显示设置，对所有存储后端的列表和递归操作生效
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_set_visibility_settings(
+    settings: VisibilitySettings,
+) -> Result<(), CommandError> {
+    tokio::task::spawn_blocking(move || VisibilitySettings::update(settings))
+        .await
+        .map_err(|e| format!("Save settings task failed: {}", e))?
+        .map_err(CommandError::from)
+}
+
+/// 获取共享 HTTP 连接池设置（超时、空闲连接和代理）
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_get_http_pool_settings() -> Result<HttpPoolSettings, CommandError> {
+    Ok(manager::http_pool_settings())
+}
+
+/// 更新共享 HTTP 连接池设置，对之后建立的存储连接生效
+#[tauri::command]
+#[specta::specta]
+pub async fn storage_set_http_pool_settings(
+    settings: HttpPoolSettings,
+) -> Result<(), CommandError> {
+    tokio::task::spawn_blocking(move || manager::set_http_pool_settings(settings))
+        .await
+        .map_err(|e| format!("Save settings task failed: {}", e))?
+        .map_err(CommandError::from)
 }
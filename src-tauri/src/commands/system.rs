@@ -1,6 +1,18 @@
 // 系统控制命令
 // 提供系统集成、窗口管理和平台特定功能
 
+use crate::commands::dataset::get_current_connection_key;
+use crate::connections;
+use crate::storage::chunk_cache;
+use crate::storage::get_storage_manager;
+use crate::storage::manager;
+use crate::utils::mime;
+use crate::utils::network::{self, NetworkConfig};
+use crate::utils::retention::{self, CacheCategory, CleanupReport, RetentionPolicy};
+use crate::utils::self_check::{self, SelfCheckReport};
+use crate::utils::viewer_tabs::{self, ViewerFile};
+use crate::utils::window_state::{self, ViewerWindowSettings};
+
 /// 显示文件夹选择对话框
 /// 跨平台的目录选择功能
 #[tauri::command]
@@ -134,3 +146,160 @@ pub async fn system_set_theme(app: tauri::AppHandle, theme: String) -> Result<St
         Err("Main window not found".to_string())
     }
 }
+
+/// 获取缓存保留策略（每个类别的最长保留天数和最大占用空间）
+#[tauri::command]
+#[specta::specta]
+pub async fn system_get_retention_policies() -> Result<Vec<RetentionPolicy>, String> {
+    Ok(retention::get_policies())
+}
+
+/// 保存缓存保留策略，只更新传入的类别
+#[tauri::command]
+#[specta::specta]
+pub async fn system_set_retention_policies(policies: Vec<RetentionPolicy>) -> Result<(), String> {
+    retention::set_policies(policies)
+}
+
+/// 立即按保留策略清理缓存和临时文件，categories 为空时清理所有类别
+/// 返回每个类别删除的文件数和回收的空间
+#[tauri::command]
+#[specta::specta]
+pub async fn system_cleanup_now(
+    categories: Option<Vec<CacheCategory>>,
+) -> Result<CleanupReport, String> {
+    tokio::task::spawn_blocking(move || retention::cleanup(categories))
+        .await
+        .map_err(|e| format!("Cleanup task failed: {}", e))
+}
+
+/// 清空远程范围读取的磁盘缓存，返回回收的字节数
+#[tauri::command]
+#[specta::specta]
+pub async fn cache_clear() -> Result<String, String> {
+    tokio::task::spawn_blocking(chunk_cache::clear)
+        .await
+        .map_err(|e| format!("Cache clear task failed: {}", e))?
+        .map(|reclaimed| reclaimed.to_string())
+}
+
+/// 获取启动自检的结果（损坏的状态文件及处理方式），自检尚未完成时为空
+#[tauri::command]
+#[specta::specta]
+pub async fn system_get_self_check_report() -> Result<Option<SelfCheckReport>, String> {
+    Ok(self_check::last_report())
+}
+
+/// 获取全局网络配置（代理和自定义 CA 证书），不包含代理密码
+#[tauri::command]
+#[specta::specta]
+pub async fn system_get_network_config() -> Result<NetworkConfig, String> {
+    Ok(network::current())
+}
+
+/// 保存全局网络配置并重新创建共享 HTTP 客户端
+/// 对存储、下载和插件安装的新请求生效，已建立的存储连接需重新连接
+#[tauri::command]
+#[specta::specta]
+pub async fn system_set_network_config(config: NetworkConfig) -> Result<NetworkConfig, String> {
+    tokio::task::spawn_blocking(move || {
+        let config = network::set(config)?;
+        manager::reload_http_clients()?;
+        Ok(config)
+    })
+    .await
+    .map_err(|e| format!("Save network config task failed: {}", e))?
+}
+
+/// 获取文件查看窗口设置
+#[tauri::command]
+#[specta::specta]
+pub async fn system_get_viewer_window_settings() -> Result<ViewerWindowSettings, String> {
+    Ok(window_state::settings())
+}
+
+/// 保存文件查看窗口设置，新打开的窗口生效
+#[tauri::command]
+#[specta::specta]
+pub async fn system_set_viewer_window_settings(
+    settings: ViewerWindowSettings,
+) -> Result<(), String> {
+    window_state::set_settings(settings)
+}
+
+/// 设置当前窗口的缩放比例，文件查看窗口会记住该类文件的缩放
+#[tauri::command]
+#[specta::specta]
+pub async fn system_set_window_zoom(window: tauri::WebviewWindow, zoom: f64) -> Result<(), String> {
+    if !(0.25..=5.0).contains(&zoom) {
+        return Err(format!("Invalid zoom: {}", zoom));
+    }
+    window_state::set_zoom(&window, zoom)
+}
+
+/// 在文件查看窗口中打开当前连接的文件或压缩包条目
+/// 优先复用已打开同一压缩包或同一连接文件的窗口，返回目标窗口标签
+#[tauri::command]
+#[specta::specta]
+pub async fn viewer_open_file(
+    app: tauri::AppHandle,
+    path: String,
+    entry_path: Option<String>,
+    new_window: Option<bool>,
+) -> Result<String, String> {
+    let manager_arc = get_storage_manager().await;
+    let client = manager_arc
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client connected".to_string())?;
+    let connection_key = get_current_connection_key().await?;
+    // 查看窗口通过已保存的连接配置重新连接，未保存的连接无法在其他窗口中打开
+    let key = connection_key.clone();
+    let profile_id = tokio::task::spawn_blocking(move || connections::find_by_key(&key))
+        .await
+        .map_err(|e| format!("Connection lookup task failed: {}", e))?
+        .map(|profile| profile.id)
+        .ok_or_else(|| "Save the connection before opening files in a viewer window".to_string())?;
+
+    // 压缩包条目的大小由查看窗口加载时解析
+    let size = match &entry_path {
+        Some(_) => None,
+        None => Some(
+            client
+                .get_file_size(&path)
+                .await
+                .map_err(|e| format!("Failed to get file size: {}", e))?
+                .to_string(),
+        ),
+    };
+    let file_name = viewer_tabs::display_name(entry_path.as_deref().unwrap_or(&path));
+    let file = ViewerFile {
+        mime_type: mime::mime_from_filename(&file_name),
+        file_name,
+        path,
+        entry_path,
+        size,
+        modified: None,
+        connection_key: Some(connection_key),
+        profile_id: Some(profile_id),
+    };
+    viewer_tabs::open(&app, file, new_window.unwrap_or(false)).await
+}
+
+/// 获取当前查看窗口的标签页，窗口加载或重新加载时恢复标签页
+#[tauri::command]
+#[specta::specta]
+pub async fn viewer_get_tabs(window: tauri::WebviewWindow) -> Result<Vec<ViewerFile>, String> {
+    Ok(viewer_tabs::tabs(window.label()))
+}
+
+/// 同步当前查看窗口的标签页（关闭或调整标签页后调用）
+#[tauri::command]
+#[specta::specta]
+pub async fn viewer_set_tabs(
+    window: tauri::WebviewWindow,
+    tabs: Vec<ViewerFile>,
+) -> Result<(), String> {
+    viewer_tabs::set_tabs(window.label(), tabs)
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage::traits::ConnectionConfig;
+
+/// 系统钥匙串中的服务名
+const KEYCHAIN_SERVICE: &str = "ai.stardust.dataset-viewer";
+
+/// 连接配置中的敏感字段，整体序列化为一个钥匙串条目
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSecrets {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+    /// 密码，也用作 HuggingFace / Bearer 令牌
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// SSH 私钥口令
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<String>,
+    /// S3 临时凭证的会话令牌
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+}
+
+impl ConnectionSecrets {
+    /// 从配置中取出敏感字段，配置中只保留非敏感信息
+    pub fn take_from(config: &mut ConnectionConfig) -> Self {
+        Self {
+            secret_key: config.secret_key.take().filter(|v| !v.is_empty()),
+            password: config.password.take().filter(|v| !v.is_empty()),
+            passphrase: config.passphrase.take().filter(|v| !v.is_empty()),
+            session_token: config.session_token.take().filter(|v| !v.is_empty()),
+        }
+    }
+
+    /// 把敏感字段填回配置
+    pub fn apply_to(self, config: &mut ConnectionConfig) {
+        config.secret_key = self.secret_key;
+        config.password = self.password;
+        config.passphrase = self.passphrase;
+        config.session_token = self.session_token;
+    }
+
+    /// 用 other 中提供的字段覆盖当前值，未提供的字段保留
+    pub fn merge(&mut self, other: ConnectionSecrets) {
+        if other.secret_key.is_some() {
+            self.secret_key = other.secret_key;
+        }
+        if other.password.is_some() {
+            self.password = other.password;
+        }
+        if other.passphrase.is_some() {
+            self.passphrase = other.passphrase;
+        }
+        if other.session_token.is_some() {
+            self.session_token = other.session_token;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .map_err(|e| format!("Failed to access keychain: {}", e))
+}
+
+fn connection_account(profile_id: &str) -> String {
+    format!("connection:{}", profile_id)
+}
+
+/// 读取钥匙串中账号下保存的字符串，没有保存时返回 None
+pub fn load_secret(account: &str) -> Result<Option<String>, String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read keychain: {}", e)),
+    }
+}
+
+/// 保存字符串到钥匙串账号下
+pub fn save_secret(account: &str, secret: &str) -> Result<(), String> {
+    entry(account)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to write keychain: {}", e))
+}
+
+/// 删除钥匙串账号下保存的字符串
+pub fn delete_secret(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete keychain entry: {}", e)),
+    }
+}
+
+/// 读取连接的敏感字段，没有保存时返回空值
+pub fn load(profile_id: &str) -> Result<ConnectionSecrets, String> {
+    match load_secret(&connection_account(profile_id))? {
+        Some(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse keychain entry: {}", e)),
+        None => Ok(ConnectionSecrets::default()),
+    }
+}
+
+/// 保存连接的敏感字段，全部为空时删除钥匙串条目
+pub fn save(profile_id: &str, secrets: &ConnectionSecrets) -> Result<(), String> {
+    if secrets.is_empty() {
+        return delete(profile_id);
+    }
+    let content = serde_json::to_string(secrets)
+        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+    save_secret(&connection_account(profile_id), &content)
+}
+
+/// 删除连接的敏感字段
+pub fn delete(profile_id: &str) -> Result<(), String> {
+    delete_secret(&connection_account(profile_id))
+}
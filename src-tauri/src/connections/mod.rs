@@ -0,0 +1,224 @@
+// 连接配置管理
+// 保存命名的连接配置，密钥、密码、令牌等敏感字段存放在系统钥匙串中，
+// 配置文件只保存非敏感信息，前端不再需要自行保存密钥
+
+pub mod keychain;
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+use crate::storage::manager::connection_key;
+use crate::storage::traits::ConnectionConfig;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+use keychain::ConnectionSecrets;
+
+/// 串行化配置文件的读改写，避免并发保存互相覆盖
+static PROFILES_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// 保存的连接配置
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfile {
+    pub id: String,
+    pub name: String,
+    /// 不含敏感字段的连接配置
+    pub config: ConnectionConfig,
+    /// 钥匙串中已保存的敏感字段（"secretKey"、"password"、"passphrase"、"sessionToken"）
+    pub stored_secrets: Vec<String>,
+    /// 创建、修改和最后连接时间（RFC3339）
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_used_at: Option<String>,
+    /// 连接时检测到的 HTTP Range 请求支持情况，尚未检测时为空
+    #[serde(default)]
+    pub range_support: Option<RangeSupport>,
+    /// 前端连接表单使用的配置（JSON，不含敏感字段），用于回填表单和显示
+    #[serde(default)]
+    pub ui_config: Option<String>,
+}
+
+/// 服务器对 HTTP Range 请求的支持情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RangeSupport {
+    Supported,
+    /// 忽略 Range 返回完整内容，只能读取文件开头的有限范围
+    Unsupported,
+}
+
+/// 保存连接配置的请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionProfileInput {
+    /// 为空时新建，否则更新已有配置
+    pub id: Option<String>,
+    pub name: String,
+    /// 更新时未填写的敏感字段保留钥匙串中已保存的值
+    pub config: ConnectionConfig,
+    /// 前端连接表单使用的配置，为空时保留已保存的值
+    #[serde(default)]
+    pub ui_config: Option<String>,
+}
+
+fn profiles_file() -> Result<PathBuf, String> {
+    Ok(get_app_data_subdir("connections")?.join("profiles.json"))
+}
+
+fn load_profiles() -> Vec<ConnectionProfile> {
+    profiles_file()
+        .ok()
+        .and_then(persist::read_json)
+        .unwrap_or_default()
+}
+
+fn save_profiles(profiles: &[ConnectionProfile]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("Failed to serialize connection profiles: {}", e))?;
+    persist::write_atomic(profiles_file()?, content)
+        .map_err(|e| format!("Failed to save connection profiles: {}", e))
+}
+
+/// 列出保存的连接配置（不含敏感字段）
+pub fn list() -> Vec<ConnectionProfile> {
+    let _guard = PROFILES_LOCK.lock();
+    load_profiles()
+}
+
+/// 新建或更新连接配置，敏感字段写入系统钥匙串
+pub fn save(input: ConnectionProfileInput) -> Result<ConnectionProfile, String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Connection name is required".to_string());
+    }
+
+    let _guard = PROFILES_LOCK.lock();
+    let mut profiles = load_profiles();
+    let mut config = input.config;
+    let provided = ConnectionSecrets::take_from(&mut config);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let existing = input
+        .id
+        .as_ref()
+        .and_then(|id| profiles.iter().position(|profile| &profile.id == id));
+    let (id, mut secrets) = match (&input.id, existing) {
+        (Some(id), Some(_)) => (id.clone(), keychain::load(id)?),
+        (Some(id), None) => return Err(format!("Connection not found: {}", id)),
+        (None, _) => (
+            uuid::Uuid::new_v4().to_string(),
+            ConnectionSecrets::default(),
+        ),
+    };
+    secrets.merge(provided);
+    keychain::save(&id, &secrets)?;
+
+    // 修改了服务器地址等字段后需要重新检测
+    let range_support = existing
+        .filter(|&index| connection_key(&profiles[index].config) == connection_key(&config))
+        .and_then(|index| profiles[index].range_support);
+    let profile = ConnectionProfile {
+        id: id.clone(),
+        name,
+        config,
+        stored_secrets: stored_secret_names(&secrets),
+        created_at: existing
+            .map(|index| profiles[index].created_at.clone())
+            .unwrap_or_else(|| now.clone()),
+        updated_at: now,
+        last_used_at: existing.and_then(|index| profiles[index].last_used_at.clone()),
+        range_support,
+        ui_config: input
+            .ui_config
+            .or_else(|| existing.and_then(|index| profiles[index].ui_config.clone())),
+    };
+    match existing {
+        Some(index) => profiles[index] = profile.clone(),
+        None => profiles.push(profile.clone()),
+    }
+    save_profiles(&profiles)?;
+    Ok(profile)
+}
+
+/// 删除连接配置及其钥匙串条目
+pub fn delete(id: &str) -> Result<(), String> {
+    let _guard = PROFILES_LOCK.lock();
+    let mut profiles = load_profiles();
+    let before = profiles.len();
+    profiles.retain(|profile| profile.id != id);
+    if profiles.len() == before {
+        return Err(format!("Connection not found: {}", id));
+    }
+    keychain::delete(id)?;
+    save_profiles(&profiles)
+}
+
+/// 读取完整的连接配置（包含钥匙串中的敏感字段），只在后端使用
+pub fn resolve(id: &str) -> Result<ConnectionConfig, String> {
+    let mut config = {
+        let _guard = PROFILES_LOCK.lock();
+        load_profiles()
+            .into_iter()
+            .find(|profile| profile.id == id)
+            .map(|profile| profile.config)
+            .ok_or_else(|| format!("Connection not found: {}", id))?
+    };
+    keychain::load(id)?.apply_to(&mut config);
+    Ok(config)
+}
+
+/// 连接标识对应的已保存配置，有多个时返回最近连接的一个
+pub fn find_by_key(key: &str) -> Option<ConnectionProfile> {
+    let _guard = PROFILES_LOCK.lock();
+    load_profiles()
+        .into_iter()
+        .filter(|profile| connection_key(&profile.config) == key)
+        .max_by(|a, b| a.last_used_at.cmp(&b.last_used_at))
+}
+
+/// 记录最后连接时间
+pub fn mark_used(id: &str) {
+    let _guard = PROFILES_LOCK.lock();
+    let mut profiles = load_profiles();
+    if let Some(profile) = profiles.iter_mut().find(|profile| profile.id == id) {
+        profile.last_used_at = Some(chrono::Utc::now().to_rfc3339());
+        if let Err(e) = save_profiles(&profiles) {
+            log::warn!("更新连接使用时间失败: {}", e);
+        }
+    }
+}
+
+/// 记录连接检测到的 Range 请求支持情况，connection_key 与存储管理器的连接标识一致
+pub fn record_range_support(key: &str, support: RangeSupport) {
+    let _guard = PROFILES_LOCK.lock();
+    let mut profiles = load_profiles();
+    let mut changed = false;
+    for profile in profiles
+        .iter_mut()
+        .filter(|profile| connection_key(&profile.config) == key)
+    {
+        if profile.range_support != Some(support) {
+            profile.range_support = Some(support);
+            changed = true;
+        }
+    }
+    if changed {
+        if let Err(e) = save_profiles(&profiles) {
+            log::warn!("更新连接的 Range 支持情况失败: {}", e);
+        }
+    }
+}
+
+fn stored_secret_names(secrets: &ConnectionSecrets) -> Vec<String> {
+    [
+        ("secretKey", &secrets.secret_key),
+        ("password", &secrets.password),
+        ("passphrase", &secrets.passphrase),
+        ("sessionToken", &secrets.session_token),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.is_some())
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
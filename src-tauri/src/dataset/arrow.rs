@@ -0,0 +1,302 @@
+// Arrow IPC / Feather v2 文件预览
+// 通过范围读取解析 IPC 文件的 footer 或流开头的 schema，按批次分页读取数据，只解码请求的列
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_schema::{Schema, SchemaRef};
+use serde::{Deserialize, Serialize};
+use std::io::{Seek, SeekFrom};
+use std::sync::Arc;
+
+use crate::archive::formats::common::StorageReader;
+use crate::preview::parquet::truncate_strings;
+use crate::preview::window::{DEFAULT_MAX_ROWS, HARD_MAX_ROWS};
+use crate::storage::traits::StorageClient;
+
+/// Arrow IPC 文件格式（Feather v2）的魔数，位于文件开头和末尾
+const ARROW_MAGIC: &[u8; 6] = b"ARROW1";
+
+/// Arrow 文件的 schema 信息
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrowSchemaInfo {
+    /// "file"（带 footer 的 IPC 文件 / Feather v2）或 "stream"（IPC 流，如 HuggingFace datasets 缓存）
+    pub format: String,
+    pub file_size: String, // 使用字符串表示大数字
+    pub fields: Vec<ArrowField>,
+    /// 记录批次数量，流格式需要读完整个文件才能得知，为空
+    pub num_batches: Option<u32>,
+    pub metadata: Vec<ArrowKeyValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrowField {
+    pub name: String,
+    /// 数据类型，如 "Int64"、"Utf8"、"List(Float32)"
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrowKeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+/// 记录批次读取请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrowBatchRequest {
+    pub path: String,
+    /// 批次序号，默认 0
+    pub batch: Option<u32>,
+    /// 批次内的起始行，默认 0
+    pub offset: Option<u32>,
+    /// 默认 100 行，上限 10000 行
+    pub limit: Option<u32>,
+    /// 只读取的列，为空时读取所有列
+    pub columns: Option<Vec<String>>,
+}
+
+/// 一页记录批次数据
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrowBatch {
+    pub columns: Vec<String>,
+    /// JSON 数组，每个元素是一行数据对象，过长的字符串会被截断
+    pub rows: String,
+    pub row_count: u32,
+    pub batch: u32,
+    pub offset: u32,
+    /// 当前批次的总行数
+    pub batch_rows: u32,
+    /// 下一页的位置（批次序号, 批次内起始行），已读到末尾时为空
+    pub next_batch: Option<u32>,
+    pub next_offset: Option<u32>,
+}
+
+/// Arrow IPC 读取器，文件格式通过 footer 按需跳转到指定批次，流格式从头顺序读取
+pub struct ArrowReader;
+
+impl ArrowReader {
+    /// 读取 schema
+    pub async fn get_schema(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+    ) -> Result<ArrowSchemaInfo, String> {
+        let (reader, file_size, is_file) = Self::open(client, path).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let (schema, num_batches) = if is_file {
+                let reader = FileReader::try_new(reader, None)
+                    .map_err(|e| format!("Failed to read Arrow footer: {}", e))?;
+                (reader.schema(), Some(reader.num_batches() as u32))
+            } else {
+                let reader = StreamReader::try_new(reader, None)
+                    .map_err(|e| format!("Failed to read Arrow stream: {}", e))?;
+                (reader.schema(), None)
+            };
+
+            Ok(ArrowSchemaInfo {
+                format: if is_file { "file" } else { "stream" }.to_string(),
+                file_size: file_size.to_string(),
+                fields: schema
+                    .fields()
+                    .iter()
+                    .map(|field| ArrowField {
+                        name: field.name().clone(),
+                        data_type: field.data_type().to_string(),
+                        nullable: field.is_nullable(),
+                    })
+                    .collect(),
+                num_batches,
+                metadata: metadata_entries(&schema),
+            })
+        })
+        .await
+        .map_err(|e| format!("Arrow read task failed: {}", e))?
+    }
+
+    /// 读取一个记录批次中的一页数据
+    pub async fn read_batch(
+        client: Arc<dyn StorageClient>,
+        request: ArrowBatchRequest,
+    ) -> Result<ArrowBatch, String> {
+        let (mut reader, _, is_file) = Self::open(client, &request.path).await?;
+        let index = request.batch.unwrap_or(0);
+        let offset = request.offset.unwrap_or(0);
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_MAX_ROWS)
+            .clamp(1, HARD_MAX_ROWS);
+
+        tokio::task::spawn_blocking(move || {
+            // 文件格式可直接跳到目标批次；流格式只能依次读过之前的批次
+            let (batch, has_next_batch) = if is_file {
+                let projection = file_projection(&mut reader, &request.columns)?;
+                let mut reader = FileReader::try_new(reader, projection)
+                    .map_err(|e| format!("Failed to read Arrow footer: {}", e))?;
+                let num_batches = reader.num_batches();
+                if index as usize >= num_batches {
+                    return Err(format!("Batch out of range: {}", index));
+                }
+                reader
+                    .set_index(index as usize)
+                    .map_err(|e| format!("Failed to seek Arrow batch: {}", e))?;
+                let batch = reader
+                    .next()
+                    .ok_or_else(|| format!("Batch out of range: {}", index))?
+                    .map_err(|e| format!("Failed to read Arrow batch: {}", e))?;
+                (batch, (index as usize) + 1 < num_batches)
+            } else {
+                let projection = stream_projection(&mut reader, &request.columns)?;
+                let mut reader = StreamReader::try_new(reader, projection)
+                    .map_err(|e| format!("Failed to read Arrow stream: {}", e))?;
+                let batch = reader
+                    .nth(index as usize)
+                    .ok_or_else(|| format!("Batch out of range: {}", index))?
+                    .map_err(|e| format!("Failed to read Arrow batch: {}", e))?;
+                let has_next_batch = !reader.is_finished();
+                (batch, has_next_batch)
+            };
+
+            let batch_rows = batch.num_rows() as u32;
+            let start = offset.min(batch_rows);
+            let length = limit.min(batch_rows - start);
+            let page = batch.slice(start as usize, length as usize);
+            let end = start + length;
+            let (next_batch, next_offset) = if end < batch_rows {
+                (Some(index), Some(end))
+            } else if has_next_batch {
+                (Some(index + 1), Some(0))
+            } else {
+                (None, None)
+            };
+
+            Ok(ArrowBatch {
+                columns: page
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|field| field.name().clone())
+                    .collect(),
+                rows: rows_to_json(&page)?,
+                row_count: length,
+                batch: index,
+                offset: start,
+                batch_rows,
+                next_batch,
+                next_offset,
+            })
+        })
+        .await
+        .map_err(|e| format!("Arrow read task failed: {}", e))?
+    }
+
+    /// 打开文件并判断是 IPC 文件格式还是流格式
+    async fn open(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+    ) -> Result<(StorageReader, u64, bool), String> {
+        let file_size = client
+            .get_file_size(path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        let head = client
+            .read_file_range(path, 0, (ARROW_MAGIC.len() as u64).min(file_size))
+            .await
+            .map_err(|e| format!("Failed to read Arrow header: {}", e))?;
+        let is_file = head.starts_with(ARROW_MAGIC);
+        if head.starts_with(b"FEA1") {
+            return Err("Feather v1 files are not supported".to_string());
+        }
+
+        let reader = StorageReader::new(client, path, file_size, tokio::runtime::Handle::current());
+        Ok((reader, file_size, is_file))
+    }
+}
+
+/// IPC 文件格式要读取的列序号，为空时读取所有列
+/// 需要先从 footer 读取 schema 解析列名，读取后回到文件开头，由调用方按投影重新打开，只解码选中的列
+fn file_projection(
+    reader: &mut StorageReader,
+    columns: &Option<Vec<String>>,
+) -> Result<Option<Vec<usize>>, String> {
+    let Some(columns) = columns.as_ref().filter(|columns| !columns.is_empty()) else {
+        return Ok(None);
+    };
+    let schema = FileReader::try_new(&mut *reader, None)
+        .map_err(|e| format!("Failed to read Arrow footer: {}", e))?
+        .schema();
+    rewind(reader)?;
+    projection(&schema, columns).map(Some)
+}
+
+/// IPC 流格式要读取的列序号，与 file_projection 相同，schema 从流开头的消息读取
+fn stream_projection(
+    reader: &mut StorageReader,
+    columns: &Option<Vec<String>>,
+) -> Result<Option<Vec<usize>>, String> {
+    let Some(columns) = columns.as_ref().filter(|columns| !columns.is_empty()) else {
+        return Ok(None);
+    };
+    let schema = StreamReader::try_new(&mut *reader, None)
+        .map_err(|e| format!("Failed to read Arrow stream: {}", e))?
+        .schema();
+    rewind(reader)?;
+    projection(&schema, columns).map(Some)
+}
+
+fn rewind(reader: &mut StorageReader) -> Result<(), String> {
+    reader
+        .seek(SeekFrom::Start(0))
+        .map(|_| ())
+        .map_err(|e| format!("Failed to seek Arrow file: {}", e))
+}
+
+/// 按列名解析投影的列序号
+fn projection(schema: &SchemaRef, columns: &[String]) -> Result<Vec<usize>, String> {
+    columns
+        .iter()
+        .map(|name| {
+            schema
+                .index_of(name)
+                .map_err(|_| format!("Column not found: {}", name))
+        })
+        .collect()
+}
+
+fn metadata_entries(schema: &Schema) -> Vec<ArrowKeyValue> {
+    let mut entries: Vec<ArrowKeyValue> = schema
+        .metadata()
+        .iter()
+        .map(|(key, value)| ArrowKeyValue {
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    entries
+}
+
+/// 将记录批次转换为 JSON 对象数组，截断过长的字符串
+fn rows_to_json(batch: &RecordBatch) -> Result<String, String> {
+    let mut writer = arrow_json::ArrayWriter::new(Vec::new());
+    writer
+        .write(batch)
+        .map_err(|e| format!("Failed to convert Arrow batch: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to convert Arrow batch: {}", e))?;
+    let data = writer.into_inner();
+    if data.is_empty() {
+        return Ok("[]".to_string());
+    }
+
+    let mut rows: serde_json::Value = serde_json::from_slice(&data)
+        .map_err(|e| format!("Failed to convert Arrow batch: {}", e))?;
+    truncate_strings(&mut rows);
+    serde_json::to_string(&rows).map_err(|e| format!("Failed to serialize rows: {}", e))
+}
@@ -0,0 +1,137 @@
+// 文件名索引的内容索引设置
+// 按连接选择是否在构建文件名索引时一并索引小文本文件（txt/json/csv 等）的内容，
+// 单个文件和内容总量都有上限，设置保存在应用数据目录
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+use crate::dataset::walker::file_extension;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 设置文件名
+const SETTINGS_FILE: &str = "content-settings.json";
+
+/// 默认只索引不超过该大小的文件内容
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024; // 1MB
+
+/// 默认每个连接最多索引的内容总量
+const DEFAULT_QUOTA: u64 = 512 * 1024 * 1024; // 512MB
+
+/// 默认索引内容的文件扩展名
+const DEFAULT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "json", "jsonl", "csv", "tsv", "yaml", "yml", "xml", "log",
+];
+
+static SETTINGS: LazyLock<Mutex<Option<SettingsState>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 内容索引设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentIndexSettings {
+    /// 是否索引文件内容，默认关闭
+    pub enabled: bool,
+    /// 跳过大于该大小的文件，默认 1MB
+    pub max_file_size: Option<String>,
+    /// 文件内容在索引中占用的磁盘空间上限，默认 512MB
+    pub quota: Option<String>,
+    /// 索引内容的文件扩展名（不含点，大小写不敏感），为空时使用默认的文本格式
+    pub extensions: Option<Vec<String>>,
+}
+
+/// 解析后的内容索引限制
+#[derive(Debug, Clone)]
+pub struct ContentIndexLimits {
+    pub max_file_size: u64,
+    pub quota: u64,
+    extensions: Vec<String>,
+}
+
+impl ContentIndexSettings {
+    /// 开启内容索引时返回解析后的限制
+    pub fn limits(&self) -> Option<ContentIndexLimits> {
+        if !self.enabled {
+            return None;
+        }
+        let parse = |value: &Option<String>, default: u64| {
+            value
+                .as_ref()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(default)
+        };
+        Some(ContentIndexLimits {
+            max_file_size: parse(&self.max_file_size, DEFAULT_MAX_FILE_SIZE),
+            quota: parse(&self.quota, DEFAULT_QUOTA),
+            extensions: match &self.extensions {
+                Some(exts) if !exts.is_empty() => exts
+                    .iter()
+                    .map(|e| e.trim_start_matches('.').to_lowercase())
+                    .collect(),
+                _ => DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            },
+        })
+    }
+}
+
+impl ContentIndexLimits {
+    /// 文件是否需要索引内容
+    pub fn accepts(&self, name: &str, size: u64) -> bool {
+        size > 0
+            && size <= self.max_file_size
+            && file_extension(name).is_some_and(|ext| self.extensions.contains(&ext))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SettingsState {
+    /// 连接标识 -> 内容索引设置
+    connections: HashMap<String, ContentIndexSettings>,
+}
+
+/// 获取连接的内容索引设置
+pub fn get(connection_key: &str) -> ContentIndexSettings {
+    let Ok(mut guard) = SETTINGS.lock() else {
+        return ContentIndexSettings::default();
+    };
+    guard
+        .get_or_insert_with(SettingsState::load)
+        .connections
+        .get(connection_key)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// 保存连接的内容索引设置，下次构建索引时生效
+pub fn set(connection_key: &str, settings: ContentIndexSettings) -> Result<(), String> {
+    let mut guard = SETTINGS
+        .lock()
+        .map_err(|e| format!("Failed to lock content index settings: {}", e))?;
+    let state = guard.get_or_insert_with(SettingsState::load);
+    state
+        .connections
+        .insert(connection_key.to_string(), settings);
+    state.save()
+}
+
+impl SettingsState {
+    fn state_path() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("filename-index")?.join(SETTINGS_FILE))
+    }
+
+    fn load() -> Self {
+        Self::state_path()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::state_path()?;
+        let data = serde_json::to_vec(self)
+            .map_err(|e| format!("Failed to serialize content index settings: {}", e))?;
+        persist::write_atomic(path, data)
+            .map_err(|e| format!("Failed to write content index settings: {}", e))
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::dataset::hashing::sha256_streaming;
+use crate::dataset::ignore_rules::IgnoreRules;
+use crate::dataset::walker::{file_extension, TreeWalker, WalkedFile};
+use crate::storage::traits::StorageClient;
+
+/// 重复文件扫描选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateScanOptions {
+    /// 只扫描指定扩展名（不含点，大小写不敏感），为空表示全部
+    pub extensions: Option<Vec<String>>,
+    /// 忽略小于该大小的文件，默认 1 字节（跳过空文件）
+    pub min_size: Option<String>,
+    /// 最多扫描的文件数量
+    pub max_files: Option<u32>,
+    /// 本次扫描额外使用的 gitignore 风格忽略规则（与工作区规则合并）
+    pub ignore_patterns: Option<Vec<String>>,
+}
+
+/// 一组内容完全相同的文件
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub size: String, // 使用字符串表示大数字
+    pub paths: Vec<String>,
+    /// 除保留一份外其余副本占用的空间
+    pub wasted_bytes: String,
+}
+
+/// 重复文件扫描报告
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateReport {
+    pub root: String,
+    pub groups: Vec<DuplicateGroup>,
+    pub scanned_files: u32,
+    /// 因大小相同而需要计算哈希的文件数
+    pub hashed_files: u32,
+    pub duplicate_files: u32,
+    pub total_wasted_bytes: String,
+    /// 是否因达到文件数量上限而未完整扫描
+    pub truncated: bool,
+}
+
+/// 扫描进度阶段
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateScanProgress {
+    pub phase: String, // "listing", "hashing"
+    pub processed: String,
+    pub total: String,
+}
+
+/// 重复文件查找器
+/// 先按文件大小分组，仅对大小相同的文件进行流式哈希
+pub struct DuplicateFinder;
+
+impl DuplicateFinder {
+    pub async fn scan<F>(
+        client: Arc<dyn StorageClient>,
+        root: &str,
+        options: &DuplicateScanOptions,
+        progress: F,
+    ) -> Result<DuplicateReport, String>
+    where
+        F: Fn(DuplicateScanProgress) + Send + Sync,
+    {
+        let min_size = options
+            .min_size
+            .as_ref()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(1);
+        let extensions: Option<Vec<String>> = options.extensions.as_ref().map(|exts| {
+            exts.iter()
+                .map(|e| e.trim_start_matches('.').to_lowercase())
+                .collect()
+        });
+
+        progress(DuplicateScanProgress {
+            phase: "listing".to_string(),
+            processed: "0".to_string(),
+            total: "0".to_string(),
+        });
+
+        let ignore_rules = IgnoreRules::for_operation(root, options.ignore_patterns.as_deref())?;
+        let mut walker = TreeWalker::new(client.clone()).ignore_rules(ignore_rules);
+        if let Some(max_files) = options.max_files {
+            walker = walker.max_files(max_files as usize);
+        }
+        let (files, truncated) = walker.collect_files(root).await?;
+        let scanned_files = files.len() as u32;
+
+        // 第一阶段：按大小分组，只保留可能重复的候选
+        let mut by_size: HashMap<u64, Vec<WalkedFile>> = HashMap::new();
+        for file in files {
+            let size = file.size();
+            if size < min_size {
+                continue;
+            }
+            if let Some(exts) = &extensions {
+                match file_extension(&file.file.basename) {
+                    Some(ext) if exts.contains(&ext) => {}
+                    _ => continue,
+                }
+            }
+            by_size.entry(size).or_default().push(file);
+        }
+        let candidates: Vec<(u64, Vec<WalkedFile>)> = by_size
+            .into_iter()
+            .filter(|(_, group)| group.len() > 1)
+            .collect();
+
+        let total_bytes: u64 = candidates
+            .iter()
+            .map(|(size, group)| size * group.len() as u64)
+            .sum();
+        let mut hashed_bytes = 0u64;
+        let mut hashed_files = 0u32;
+
+        // 第二阶段：对候选文件流式计算哈希
+        let mut groups = Vec::new();
+        for (size, group) in candidates {
+            let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+            for file in group {
+                let hash = sha256_streaming(&client, &file.path, size, |bytes| {
+                    hashed_bytes += bytes;
+                    progress(DuplicateScanProgress {
+                        phase: "hashing".to_string(),
+                        processed: hashed_bytes.to_string(),
+                        total: total_bytes.to_string(),
+                    });
+                })
+                .await?;
+                hashed_files += 1;
+                by_hash.entry(hash).or_default().push(file.path);
+            }
+
+            for (hash, mut paths) in by_hash {
+                if paths.len() > 1 {
+                    paths.sort();
+                    let wasted = size * (paths.len() as u64 - 1);
+                    groups.push((wasted, hash, size, paths));
+                }
+            }
+        }
+
+        // 按浪费空间从大到小排序
+        groups.sort_by_key(|g| std::cmp::Reverse(g.0));
+
+        let total_wasted: u64 = groups.iter().map(|g| g.0).sum();
+        let duplicate_files: u32 = groups.iter().map(|g| g.3.len() as u32 - 1).sum();
+
+        Ok(DuplicateReport {
+            root: root.to_string(),
+            groups: groups
+                .into_iter()
+                .map(|(wasted, hash, size, paths)| DuplicateGroup {
+                    hash,
+                    size: size.to_string(),
+                    paths,
+                    wasted_bytes: wasted.to_string(),
+                })
+                .collect(),
+            scanned_files,
+            hashed_files,
+            duplicate_files,
+            total_wasted_bytes: total_wasted.to_string(),
+            truncated,
+        })
+    }
+}
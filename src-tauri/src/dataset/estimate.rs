@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::dataset::ignore_rules::IgnoreRules;
+use crate::dataset::walker::TreeWalker;
+use crate::storage::traits::StorageClient;
+
+/// 估算结果的有效期
+const ESTIMATE_TTL_SECONDS: i64 = 10 * 60;
+
+/// 超过该大小的操作需要用户确认
+const CONFIRM_THRESHOLD_BYTES: u64 = 10 * 1024 * 1024 * 1024; // 10GB
+
+/// 超过该文件数量的操作需要用户确认
+const CONFIRM_THRESHOLD_FILES: u64 = 10_000;
+
+/// 批量操作前的流量估算
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationEstimate {
+    /// 确认令牌，传给 dataset_confirm_operation
+    pub token: String,
+    pub operation: String, // "download", "sync"
+    pub root: String,
+    pub protocol: String,
+    pub file_count: String, // 使用字符串表示大数字
+    pub total_bytes: String,
+    /// 流量计费提示的 i18n key
+    pub egress_note: String,
+    /// 该存储是否可能产生出口流量费用
+    pub billable_egress: bool,
+    /// 是否超过阈值，必须确认后才能执行
+    pub requires_confirmation: bool,
+    /// 是否因文件过多而只统计了部分
+    pub truncated: bool,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Clone)]
+struct PendingEstimate {
+    estimate: OperationEstimate,
+    confirmed: bool,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 待确认的估算结果
+static PENDING_ESTIMATES: LazyLock<Mutex<HashMap<String, PendingEstimate>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 批量操作流量估算器
+pub struct EgressEstimator;
+
+impl EgressEstimator {
+    /// 统计目录树的文件数量和总大小，并登记待确认的估算
+    pub async fn estimate(
+        client: Arc<dyn StorageClient>,
+        protocol: &str,
+        root: &str,
+        operation: &str,
+        ignore_patterns: Option<&[String]>,
+    ) -> Result<OperationEstimate, String> {
+        let ignore_rules = IgnoreRules::for_operation(root, ignore_patterns)?;
+        let mut file_count = 0u64;
+        let mut total_bytes = 0u64;
+        let truncated = TreeWalker::new(client)
+            .max_files(1_000_000)
+            .ignore_rules(ignore_rules)
+            .walk(root, |entry| {
+                if !entry.is_directory() {
+                    file_count += 1;
+                    total_bytes += entry.size();
+                }
+                true
+            })
+            .await?;
+
+        let (egress_note, billable_egress) = Self::egress_note(protocol);
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(ESTIMATE_TTL_SECONDS);
+
+        let estimate = OperationEstimate {
+            token: uuid::Uuid::new_v4().to_string(),
+            operation: operation.to_string(),
+            root: root.to_string(),
+            protocol: protocol.to_string(),
+            file_count: file_count.to_string(),
+            total_bytes: total_bytes.to_string(),
+            egress_note: egress_note.to_string(),
+            billable_egress,
+            requires_confirmation: truncated
+                || total_bytes >= CONFIRM_THRESHOLD_BYTES
+                || file_count >= CONFIRM_THRESHOLD_FILES,
+            truncated,
+            expires_at: expires_at.to_rfc3339(),
+        };
+
+        let mut pending = PENDING_ESTIMATES.lock().unwrap();
+        pending.retain(|_, p| p.expires_at > chrono::Utc::now());
+        pending.insert(
+            estimate.token.clone(),
+            PendingEstimate {
+                estimate: estimate.clone(),
+                confirmed: false,
+                expires_at,
+            },
+        );
+
+        Ok(estimate)
+    }
+
+    /// 用户确认估算结果
+    pub fn confirm(token: &str) -> Result<OperationEstimate, String> {
+        let mut pending = PENDING_ESTIMATES.lock().unwrap();
+        let entry = pending
+            .get_mut(token)
+            .filter(|p| p.expires_at > chrono::Utc::now())
+            .ok_or_else(|| "estimate.expired".to_string())?;
+        entry.confirmed = true;
+        Ok(entry.estimate.clone())
+    }
+
+    /// 在执行批量操作前检查确认状态
+    /// 不需要确认的估算直接通过；令牌只能使用一次
+    pub fn take_confirmed(token: &str, root: &str) -> Result<OperationEstimate, String> {
+        let mut pending = PENDING_ESTIMATES.lock().unwrap();
+        let entry = pending
+            .remove(token)
+            .filter(|p| p.expires_at > chrono::Utc::now())
+            .ok_or_else(|| "estimate.expired".to_string())?;
+
+        if entry.estimate.root != root {
+            return Err("estimate.mismatch".to_string());
+        }
+        if entry.estimate.requires_confirmation && !entry.confirmed {
+            return Err("estimate.not.confirmed".to_string());
+        }
+
+        Ok(entry.estimate)
+    }
+
+    /// 各存储协议的出口流量说明
+    fn egress_note(protocol: &str) -> (&'static str, bool) {
+        match protocol {
+            "oss" | "gcs" | "azure" => ("estimate.egress.cloud", true),
+            "huggingface" => ("estimate.egress.huggingface", false),
+            "local" => ("estimate.egress.local", false),
+            "webdav" | "ssh" | "smb" => ("estimate.egress.network", false),
+            _ => ("estimate.egress.unknown", false),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::dataset::ignore_rules::IgnoreRules;
+use crate::dataset::walker::{file_extension, TreeWalker, WalkedFile};
+use crate::storage::traits::StorageClient;
+use crate::utils::timestamp::parse_timestamp;
+
+/// 最多返回的扩展名数量，其余合并到 "other"
+const MAX_EXTENSION_FACETS: usize = 50;
+
+/// 最多分别统计的扩展名数量，之后出现的新扩展名直接计入 "other"，避免随机后缀的文件名占满内存
+const MAX_DISTINCT_EXTENSIONS: usize = 10_000;
+
+/// 最多统计的文件数量，选项中的 max_files 不能超过该值
+const MAX_FACET_FILES: usize = 1_000_000;
+
+/// 大小分桶（桶名, 上限字节数），按顺序匹配第一个满足 size < 上限 的桶
+const SIZE_BUCKETS: [(&str, u64); 5] = [
+    ("empty", 1),
+    ("lt1kb", 1024),
+    ("lt1mb", 1024 * 1024),
+    ("lt100mb", 100 * 1024 * 1024),
+    ("lt1gb", 1024 * 1024 * 1024),
+];
+const SIZE_BUCKET_LARGEST: &str = "gte1gb";
+
+/// 修改时间分桶（桶名, 距今天数上限）
+const AGE_BUCKETS: [(&str, i64); 4] = [("day", 1), ("week", 7), ("month", 30), ("year", 365)];
+const AGE_BUCKET_OLDER: &str = "older";
+const AGE_BUCKET_UNKNOWN: &str = "unknown";
+
+/// 分面统计选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetOptions {
+    /// 最大遍历深度，为空表示不限制
+    pub max_depth: Option<u32>,
+    /// 最多统计的文件数量，默认且最多 1,000,000
+    pub max_files: Option<u32>,
+    /// 本次统计额外使用的 gitignore 风格忽略规则（与工作区规则合并）
+    pub ignore_patterns: Option<Vec<String>>,
+}
+
+/// 按分面筛选文件，多个条件同时满足才匹配
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetFilter {
+    /// 扩展名（不含点，大小写不敏感），空字符串表示无扩展名
+    pub extensions: Option<Vec<String>>,
+    pub size_bucket: Option<String>,
+    pub age_bucket: Option<String>,
+}
+
+/// 单个分面值的统计
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    pub key: String,
+    pub count: String,       // 使用字符串表示大数字
+    pub total_bytes: String, // 使用字符串表示大数字
+}
+
+/// 目录树的分面统计结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingFacets {
+    pub root: String,
+    pub file_count: String,
+    pub total_bytes: String,
+    /// 按数量降序排列，无扩展名的文件键为空字符串
+    pub extensions: Vec<FacetCount>,
+    /// 按大小从小到大排列
+    pub size_buckets: Vec<FacetCount>,
+    /// 按时间从近到远排列
+    pub age_buckets: Vec<FacetCount>,
+    /// 是否因文件过多而只统计了部分
+    pub truncated: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct Tally {
+    pub(crate) count: u64,
+    pub(crate) bytes: u64,
+}
+
+impl Tally {
+    pub(crate) fn add(&mut self, size: u64) {
+        self.count += 1;
+        self.bytes += size;
+    }
+
+    pub(crate) fn to_facet(&self, key: &str) -> FacetCount {
+        FacetCount {
+            key: key.to_string(),
+            count: self.count.to_string(),
+            total_bytes: self.bytes.to_string(),
+        }
+    }
+}
+
+/// 按扩展名累计，分别统计的扩展名数量有上限，超出后新扩展名计入 overflow
+#[derive(Default)]
+pub(crate) struct ExtensionTallies {
+    tallies: HashMap<String, Tally>,
+    overflow: Tally,
+}
+
+impl ExtensionTallies {
+    pub(crate) fn add(&mut self, name: &str, size: u64) {
+        let ext = file_extension(name).unwrap_or_default();
+        if let Some(tally) = self.tallies.get_mut(&ext) {
+            tally.add(size);
+        } else if self.tallies.len() < MAX_DISTINCT_EXTENSIONS {
+            self.tallies.entry(ext).or_default().add(size);
+        } else {
+            self.overflow.add(size);
+        }
+    }
+
+    /// 按数量降序排列扩展名，超出数量上限的合并为 "other"
+    pub(crate) fn into_facets(self) -> Vec<FacetCount> {
+        let mut sorted: Vec<(String, Tally)> = self.tallies.into_iter().collect();
+        sorted.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(&b.0)));
+
+        let mut other = self.overflow;
+        for (_, tally) in sorted.iter().skip(MAX_EXTENSION_FACETS) {
+            other.count += tally.count;
+            other.bytes += tally.bytes;
+        }
+
+        let mut facets: Vec<FacetCount> = sorted
+            .iter()
+            .take(MAX_EXTENSION_FACETS)
+            .map(|(ext, tally)| tally.to_facet(ext))
+            .collect();
+        if other.count > 0 {
+            facets.push(other.to_facet("other"));
+        }
+        facets
+    }
+}
+
+/// 文件大小所在的分桶
+pub fn size_bucket(size: u64) -> &'static str {
+    SIZE_BUCKETS
+        .iter()
+        .find(|(_, limit)| size < *limit)
+        .map(|(name, _)| *name)
+        .unwrap_or(SIZE_BUCKET_LARGEST)
+}
+
+/// 修改时间所在的分桶，无法解析的时间归入 "unknown"
+pub fn age_bucket(lastmod: &str, now: DateTime<Utc>) -> &'static str {
+    let Some(modified) = parse_timestamp(lastmod) else {
+        return AGE_BUCKET_UNKNOWN;
+    };
+
+    let age_days = (now - modified).num_days();
+    AGE_BUCKETS
+        .iter()
+        .find(|(_, limit)| age_days < *limit)
+        .map(|(name, _)| *name)
+        .unwrap_or(AGE_BUCKET_OLDER)
+}
+
+impl FacetFilter {
+    pub fn is_empty(&self) -> bool {
+        self.extensions.as_ref().is_none_or(|e| e.is_empty())
+            && self.size_bucket.is_none()
+            && self.age_bucket.is_none()
+    }
+
+    /// 判断条目是否满足筛选条件，目录不属于任何分面
+    pub fn matches(&self, entry: &WalkedFile, now: DateTime<Utc>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        if entry.is_directory() {
+            return false;
+        }
+
+        if let Some(extensions) = self.extensions.as_ref().filter(|e| !e.is_empty()) {
+            let ext = file_extension(&entry.file.basename).unwrap_or_default();
+            if !extensions.iter().any(|e| e.eq_ignore_ascii_case(&ext)) {
+                return false;
+            }
+        }
+        if let Some(bucket) = &self.size_bucket {
+            if size_bucket(entry.size()) != bucket {
+                return false;
+            }
+        }
+        if let Some(bucket) = &self.age_bucket {
+            if age_bucket(&entry.file.lastmod, now) != bucket {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// 目录树分面统计
+/// 流式遍历并累计，只返回统计结果，不在内存中保留条目
+pub struct FacetCalculator;
+
+impl FacetCalculator {
+    pub async fn compute(
+        client: Arc<dyn StorageClient>,
+        root: &str,
+        options: &FacetOptions,
+    ) -> Result<ListingFacets, String> {
+        let ignore_rules = IgnoreRules::for_operation(root, options.ignore_patterns.as_deref())?;
+        let walker = TreeWalker::new(client)
+            .max_files(
+                options
+                    .max_files
+                    .map_or(MAX_FACET_FILES, |max| (max as usize).min(MAX_FACET_FILES)),
+            )
+            .max_depth(options.max_depth)
+            .ignore_rules(ignore_rules);
+
+        let now = Utc::now();
+        let mut total = Tally::default();
+        let mut extensions = ExtensionTallies::default();
+        let mut sizes: HashMap<&'static str, Tally> = HashMap::new();
+        let mut ages: HashMap<&'static str, Tally> = HashMap::new();
+
+        let truncated = walker
+            .walk(root, |entry| {
+                if entry.is_directory() {
+                    return true;
+                }
+
+                let size = entry.size();
+                total.add(size);
+                extensions.add(&entry.file.basename, size);
+                sizes.entry(size_bucket(size)).or_default().add(size);
+                ages.entry(age_bucket(&entry.file.lastmod, now))
+                    .or_default()
+                    .add(size);
+                true
+            })
+            .await?;
+
+        Ok(ListingFacets {
+            root: root.to_string(),
+            file_count: total.count.to_string(),
+            total_bytes: total.bytes.to_string(),
+            extensions: extensions.into_facets(),
+            size_buckets: ordered_facets(
+                &sizes,
+                SIZE_BUCKETS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .chain([SIZE_BUCKET_LARGEST]),
+            ),
+            age_buckets: ordered_facets(
+                &ages,
+                AGE_BUCKETS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .chain([AGE_BUCKET_OLDER, AGE_BUCKET_UNKNOWN]),
+            ),
+            truncated,
+        })
+    }
+}
+
+/// 按固定顺序输出分桶，跳过没有文件的桶
+fn ordered_facets<'a>(
+    tallies: &HashMap<&'static str, Tally>,
+    order: impl Iterator<Item = &'a str>,
+) -> Vec<FacetCount> {
+    order
+        .filter_map(|name| tallies.get(name).map(|tally| tally.to_facet(name)))
+        .collect()
+}
@@ -0,0 +1,729 @@
+// 连接级文件名索引
+// 在后台遍历整个存储并把文件名写入 SQLite FTS5（trigram 分词）索引，
+// 首次遍历完成后，百万级对象的桶中按文件名搜索也能即时返回；每个连接一个索引文件
+// 连接开启内容索引时，同时写入小文本文件的内容，支持跨数据集的内容搜索
+
+use futures_util::StreamExt;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, LazyLock, Mutex};
+
+use crate::dataset::content_index;
+use crate::dataset::walker::TreeWalker;
+use crate::storage::traits::StorageClient;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::mime::is_binary_content;
+
+/// 最多索引的文件数量
+const MAX_INDEXED_FILES: usize = 10_000_000;
+
+/// 每个写入事务包含的文件数量
+const INSERT_BATCH_SIZE: usize = 10_000;
+
+/// 每个写入事务包含的文件内容总量上限
+const MAX_BATCH_CONTENT_BYTES: usize = 64 * 1024 * 1024; // 64MB
+
+/// 每索引多少个文件回调一次进度
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+/// 同时读取的文件内容数量
+const CONTENT_FETCH_CONCURRENCY: usize = 8;
+
+/// 内容搜索结果片段包含的词元数量
+const SNIPPET_TOKENS: i32 = 16;
+
+/// 默认最多返回的搜索结果数量
+const DEFAULT_SEARCH_LIMIT: u32 = 200;
+
+/// 搜索结果数量上限
+const MAX_SEARCH_LIMIT: u32 = 10_000;
+
+/// trigram 分词器只能匹配不少于 3 个字符的关键字，更短的关键字退回 LIKE 扫描
+const MIN_MATCH_CHARS: usize = 3;
+
+/// 模糊匹配候选的 LIKE 扫描最多检查的文件数量，保证输入时的响应速度
+const MAX_FUZZY_SCAN_ROWS: u32 = 500_000;
+
+/// 正在构建的索引及其已索引的文件数量，按连接标识区分
+static BUILDS: LazyLock<Mutex<HashMap<String, Arc<AtomicU64>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 索引状态及新鲜度
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameIndexStatus {
+    /// 是否已有可用的索引
+    pub built: bool,
+    /// 索引的根目录
+    pub root: Option<String>,
+    pub indexed_files: String, // 使用字符串表示大数字
+    /// 上次完成索引的时间（RFC3339）
+    pub indexed_at: Option<String>,
+    /// 是否因达到文件数量上限而未索引全部文件
+    pub truncated: bool,
+    /// 是否正在后台构建（刷新期间仍可使用旧索引搜索）
+    pub building: bool,
+    /// 本次构建已索引的文件数量
+    pub build_progress: Option<String>,
+    /// 索引是否包含文件内容
+    pub content_indexed: bool,
+    pub content_files: String, // 使用字符串表示大数字
+    /// 文件内容在索引中占用的磁盘空间
+    pub content_bytes: String, // 使用字符串表示大数字
+    /// 是否因达到内容配额而未索引全部文本文件
+    pub content_truncated: bool,
+    /// 索引文件占用的磁盘空间
+    pub disk_size: String, // 使用字符串表示大数字
+}
+
+/// 索引中的文件
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedFile {
+    /// 可直接传给 StorageClient 的完整路径
+    pub path: String,
+    pub name: String,
+    pub size: String, // 使用字符串表示大数字
+    pub lastmod: String,
+}
+
+/// 文件名搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameSearchResult {
+    pub matches: Vec<IndexedFile>,
+    /// 是否还有更多匹配未返回
+    pub has_more: bool,
+    /// 搜索所用索引的状态，前端据此提示索引的新鲜度
+    pub status: FilenameIndexStatus,
+}
+
+/// 内容搜索的一处匹配
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentMatch {
+    pub path: String,
+    /// 匹配位置附近的内容片段
+    pub snippet: String,
+}
+
+/// 内容搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentSearchResult {
+    pub matches: Vec<ContentMatch>,
+    pub has_more: bool,
+    pub status: FilenameIndexStatus,
+}
+
+/// 构建进度事件
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameIndexProgress {
+    pub indexed_files: String, // 使用字符串表示大数字
+    pub finished: bool,
+    pub error: Option<String>,
+}
+
+/// 正在进行的构建，释放时从构建列表中移除
+pub struct IndexBuild {
+    connection_key: String,
+    indexed: Arc<AtomicU64>,
+}
+
+impl Drop for IndexBuild {
+    fn drop(&mut self) {
+        BUILDS.lock().unwrap().remove(&self.connection_key);
+    }
+}
+
+/// 写入线程接收的记录
+enum IndexRecord {
+    File(IndexedFile),
+    Content { path: String, text: String },
+}
+
+/// 内容索引的统计
+#[derive(Default)]
+struct ContentSummary {
+    files: u64,
+    /// 内容在索引中占用的磁盘空间
+    bytes: u64,
+    truncated: bool,
+}
+
+/// 文件名索引
+pub struct FilenameIndex;
+
+impl FilenameIndex {
+    /// 登记一次构建，同一连接同时只能有一个构建
+    pub fn begin_build(connection_key: &str) -> Result<IndexBuild, String> {
+        let mut builds = BUILDS.lock().unwrap();
+        if builds.contains_key(connection_key) {
+            return Err("Filename index is already being built".to_string());
+        }
+        let indexed = Arc::new(AtomicU64::new(0));
+        builds.insert(connection_key.to_string(), indexed.clone());
+        Ok(IndexBuild {
+            connection_key: connection_key.to_string(),
+            indexed,
+        })
+    }
+
+    /// 遍历根目录下的所有文件并重建索引
+    /// 新索引写入临时文件，完成后替换旧索引，构建期间旧索引仍可搜索
+    pub async fn build<F>(
+        build: IndexBuild,
+        client: Arc<dyn StorageClient>,
+        root: &str,
+        on_progress: F,
+    ) -> Result<FilenameIndexStatus, String>
+    where
+        F: Fn(u64),
+    {
+        let index_path = index_path(&build.connection_key)?;
+        let building_path = index_path.with_extension("building");
+        if building_path.exists() {
+            std::fs::remove_file(&building_path)
+                .map_err(|e| format!("Failed to remove stale index: {}", e))?;
+        }
+
+        // 开启内容索引时，遍历过程中挑选配额内的文本文件，遍历结束后再读取内容；
+        // 配额按内容在索引中占用的磁盘空间计算，由写入线程测量，达到配额后停止读取
+        let limits = content_index::get(&build.connection_key).limits();
+        let mut candidates = Vec::new();
+        let mut planned_bytes = 0u64;
+        let mut planned_truncated = false;
+        let quota_reached = Arc::new(AtomicBool::new(false));
+
+        // 遍历在异步任务中进行，写入交给阻塞线程，二者通过通道传递记录
+        let (tx, rx) = mpsc::channel::<IndexRecord>();
+        let writer_path = building_path.clone();
+        let content_quota = limits.as_ref().map(|limits| limits.quota);
+        let writer_quota_reached = quota_reached.clone();
+        let writer = tokio::task::spawn_blocking(move || {
+            write_files(&writer_path, rx, content_quota, &writer_quota_reached)
+        });
+
+        let walked = TreeWalker::new(client.clone())
+            .max_files(MAX_INDEXED_FILES)
+            .walk(root, |entry| {
+                if entry.is_directory() {
+                    return true;
+                }
+                let size = entry.size();
+                if let Some(limits) = &limits {
+                    if limits.accepts(&entry.file.basename, size) {
+                        // 索引占用的空间不小于文本本身，文本总量超过配额的文件不必读取
+                        if planned_bytes + size <= limits.quota {
+                            planned_bytes += size;
+                            candidates.push((entry.path.clone(), size));
+                        } else {
+                            planned_truncated = true;
+                        }
+                    }
+                }
+                let file = IndexedFile {
+                    name: entry.file.basename.clone(),
+                    path: entry.path,
+                    size: entry.file.size,
+                    lastmod: entry.file.lastmod,
+                };
+                // 写入线程出错退出时停止遍历，错误在下面返回
+                if tx.send(IndexRecord::File(file)).is_err() {
+                    return false;
+                }
+                let indexed = build.indexed.fetch_add(1, Ordering::Relaxed) + 1;
+                if indexed % PROGRESS_INTERVAL == 0 {
+                    on_progress(indexed);
+                }
+                true
+            })
+            .await;
+
+        if walked.is_ok() && !candidates.is_empty() {
+            let mut fetched = futures_util::stream::iter(candidates)
+                .map(|(path, size)| {
+                    let client = client.clone();
+                    async move {
+                        let result = client.read_file_range(&path, 0, size).await;
+                        (path, result)
+                    }
+                })
+                .buffer_unordered(CONTENT_FETCH_CONCURRENCY);
+            while let Some((path, result)) = fetched.next().await {
+                if quota_reached.load(Ordering::Relaxed) {
+                    break;
+                }
+                let data = match result {
+                    Ok(data) => data,
+                    Err(e) => {
+                        log::debug!("读取文件内容失败 {}: {}", path, e);
+                        continue;
+                    }
+                };
+                // 扩展名是文本格式但内容是二进制的文件不索引
+                if is_binary_content(&data) {
+                    continue;
+                }
+                let text = String::from_utf8_lossy(&data).into_owned();
+                if tx.send(IndexRecord::Content { path, text }).is_err() {
+                    break;
+                }
+            }
+        }
+        drop(tx);
+
+        let written = writer
+            .await
+            .map_err(|e| format!("Index writer task failed: {}", e))
+            .and_then(|result| result);
+        let (conn, mut content, truncated) = match (walked, written) {
+            (Ok(truncated), Ok((conn, content))) => (conn, content, truncated),
+            (Err(e), _) | (_, Err(e)) => {
+                let _ = std::fs::remove_file(&building_path);
+                return Err(e);
+            }
+        };
+
+        content.truncated |= planned_truncated;
+        let indexed_files = build.indexed.load(Ordering::Relaxed);
+        let content_indexed = limits.is_some();
+        let root = root.to_string();
+        tokio::task::spawn_blocking(move || {
+            let indexed_at = chrono::Utc::now().to_rfc3339();
+            let meta = [
+                ("root", root),
+                ("indexed_at", indexed_at),
+                ("indexed_files", indexed_files.to_string()),
+                ("truncated", flag(truncated)),
+                ("content_indexed", flag(content_indexed)),
+                ("content_files", content.files.to_string()),
+                ("content_bytes", content.bytes.to_string()),
+                ("content_truncated", flag(content.truncated)),
+            ];
+            finish_index(conn, &meta)?;
+            std::fs::rename(&building_path, &index_path)
+                .map_err(|e| format!("Failed to replace filename index: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Index writer task failed: {}", e))??;
+
+        let connection_key = build.connection_key.clone();
+        drop(build);
+        Self::status(&connection_key)
+    }
+
+    /// 查询连接的索引状态
+    pub fn status(connection_key: &str) -> Result<FilenameIndexStatus, String> {
+        let build_progress = BUILDS
+            .lock()
+            .unwrap()
+            .get(connection_key)
+            .map(|indexed| indexed.load(Ordering::Relaxed).to_string());
+
+        let mut status = FilenameIndexStatus {
+            built: false,
+            root: None,
+            indexed_files: "0".to_string(),
+            indexed_at: None,
+            truncated: false,
+            building: build_progress.is_some(),
+            build_progress,
+            content_indexed: false,
+            content_files: "0".to_string(),
+            content_bytes: "0".to_string(),
+            content_truncated: false,
+            disk_size: "0".to_string(),
+        };
+
+        let path = index_path(connection_key)?;
+        if !path.exists() {
+            return Ok(status);
+        }
+        let conn = open_read_only(&path)?;
+        let mut stmt = conn
+            .prepare("SELECT key, value FROM meta")
+            .map_err(|e| format!("Failed to read filename index: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| format!("Failed to read filename index: {}", e))?;
+        for row in rows {
+            let (key, value) = row.map_err(|e| format!("Failed to read filename index: {}", e))?;
+            match key.as_str() {
+                "root" => status.root = Some(value),
+                "indexed_files" => status.indexed_files = value,
+                "indexed_at" => status.indexed_at = Some(value),
+                "truncated" => status.truncated = value == "1",
+                "content_indexed" => status.content_indexed = value == "1",
+                "content_files" => status.content_files = value,
+                "content_bytes" => status.content_bytes = value,
+                "content_truncated" => status.content_truncated = value == "1",
+                _ => {}
+            }
+        }
+        status.built = true;
+        status.disk_size = std::fs::metadata(&path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+            .to_string();
+        Ok(status)
+    }
+
+    /// 按文件名搜索（不区分大小写的子串匹配）
+    pub fn search(
+        connection_key: &str,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<FilenameSearchResult, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err("Search query is required".to_string());
+        }
+        let status = Self::status(connection_key)?;
+        if !status.built {
+            return Err("Filename index has not been built for this connection".to_string());
+        }
+
+        let limit = limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        let conn = open_read_only(&index_path(connection_key)?)?;
+        let (sql, pattern) = if query.chars().count() >= MIN_MATCH_CHARS {
+            (
+                "SELECT path, name, size, lastmod FROM files WHERE files MATCH ?1 LIMIT ?2",
+                format!("\"{}\"", query.replace('"', "\"\"")),
+            )
+        } else {
+            (
+                "SELECT path, name, size, lastmod FROM files WHERE name LIKE ?1 ESCAPE '\\' LIMIT ?2",
+                format!(
+                    "%{}%",
+                    query
+                        .replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_")
+                ),
+            )
+        };
+
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| format!("Failed to search filename index: {}", e))?;
+        // 多取一条用于判断是否还有更多结果
+        let rows = stmt
+            .query_map(params![pattern, limit + 1], |row| {
+                Ok(IndexedFile {
+                    path: row.get(0)?,
+                    name: row.get(1)?,
+                    size: row.get(2)?,
+                    lastmod: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to search filename index: {}", e))?;
+        let mut matches = rows
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to search filename index: {}", e))?;
+
+        let has_more = matches.len() > limit as usize;
+        matches.truncate(limit as usize);
+        Ok(FilenameSearchResult {
+            matches,
+            has_more,
+            status,
+        })
+    }
+
+    /// 为模糊匹配取候选文件：先用 trigram 索引查找包含最长关键字的文件名，
+    /// 数量不足时再按字符顺序（子序列）做有限范围的 LIKE 扫描。索引不存在时返回空列表
+    pub fn fuzzy_candidates(
+        connection_key: &str,
+        tokens: &[String],
+        limit: u32,
+    ) -> Result<Vec<IndexedFile>, String> {
+        let path = index_path(connection_key)?;
+        if tokens.is_empty() || !path.exists() {
+            return Ok(Vec::new());
+        }
+        let conn = open_read_only(&path)?;
+        let read_row = |row: &rusqlite::Row| {
+            Ok(IndexedFile {
+                path: row.get(0)?,
+                name: row.get(1)?,
+                size: row.get(2)?,
+                lastmod: row.get(3)?,
+            })
+        };
+
+        let mut candidates = Vec::new();
+        let longest = tokens.iter().max_by_key(|token| token.chars().count());
+        if let Some(token) = longest.filter(|token| token.chars().count() >= MIN_MATCH_CHARS) {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT path, name, size, lastmod FROM files WHERE files MATCH ?1 LIMIT ?2",
+                )
+                .map_err(|e| format!("Failed to search filename index: {}", e))?;
+            let pattern = format!("\"{}\"", token.replace('"', "\"\""));
+            candidates = stmt
+                .query_map(params![pattern, limit], read_row)
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| format!("Failed to search filename index: {}", e))?;
+        }
+        if candidates.len() >= limit as usize {
+            return Ok(candidates);
+        }
+
+        // 子序列匹配：每个字符之间允许任意字符，只扫描前 MAX_FUZZY_SCAN_ROWS 个文件
+        let pattern = format!(
+            "%{}%",
+            tokens
+                .concat()
+                .chars()
+                .map(|c| match c {
+                    '\\' | '%' | '_' => format!("\\{}", c),
+                    _ => c.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("%")
+        );
+        let mut stmt = conn
+            .prepare(
+                "SELECT path, name, size, lastmod FROM files
+                 WHERE rowid <= ?3 AND name LIKE ?1 ESCAPE '\\' LIMIT ?2",
+            )
+            .map_err(|e| format!("Failed to search filename index: {}", e))?;
+        let rows = stmt
+            .query_map(params![pattern, limit, MAX_FUZZY_SCAN_ROWS], read_row)
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Failed to search filename index: {}", e))?;
+        for file in rows {
+            if candidates.len() >= limit as usize {
+                break;
+            }
+            if !candidates.iter().any(|c| c.path == file.path) {
+                candidates.push(file);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// 在已索引的文件内容中搜索（不区分大小写，关键字不少于 3 个字符）
+    pub fn search_content(
+        connection_key: &str,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<ContentSearchResult, String> {
+        let query = query.trim();
+        if query.chars().count() < MIN_MATCH_CHARS {
+            return Err(format!(
+                "Content search query must be at least {} characters",
+                MIN_MATCH_CHARS
+            ));
+        }
+        let status = Self::status(connection_key)?;
+        if !status.content_indexed {
+            return Err("Content index has not been built for this connection".to_string());
+        }
+
+        let limit = limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        let conn = open_read_only(&index_path(connection_key)?)?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT path, snippet(contents, 1, '', '', '...', ?3) FROM contents
+                 WHERE contents MATCH ?1 LIMIT ?2",
+            )
+            .map_err(|e| format!("Failed to search content index: {}", e))?;
+        let pattern = format!("\"{}\"", query.replace('"', "\"\""));
+        let rows = stmt
+            .query_map(params![pattern, limit + 1, SNIPPET_TOKENS], |row| {
+                Ok(ContentMatch {
+                    path: row.get(0)?,
+                    snippet: row.get(1)?,
+                })
+            })
+            .map_err(|e| format!("Failed to search content index: {}", e))?;
+        let mut matches = rows
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to search content index: {}", e))?;
+
+        let has_more = matches.len() > limit as usize;
+        matches.truncate(limit as usize);
+        Ok(ContentSearchResult {
+            matches,
+            has_more,
+            status,
+        })
+    }
+
+    /// 清除连接的索引以释放磁盘空间
+    /// content_only 为 true 时只清除文件内容，保留文件名索引
+    pub fn purge(connection_key: &str, content_only: bool) -> Result<(), String> {
+        if BUILDS.lock().unwrap().contains_key(connection_key) {
+            return Err("Filename index is being built".to_string());
+        }
+        let path = index_path(connection_key)?;
+        if !path.exists() {
+            return Ok(());
+        }
+        if !content_only {
+            return std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove filename index: {}", e));
+        }
+
+        let conn =
+            Connection::open(&path).map_err(|e| format!("Failed to open filename index: {}", e))?;
+        conn.execute_batch(
+            "DELETE FROM contents;
+             INSERT OR REPLACE INTO meta (key, value) VALUES
+                 ('content_indexed', '0'),
+                 ('content_files', '0'),
+                 ('content_bytes', '0'),
+                 ('content_truncated', '0');
+             VACUUM;",
+        )
+        .map_err(|e| format!("Failed to purge content index: {}", e))
+    }
+}
+
+fn index_path(connection_key: &str) -> Result<PathBuf, String> {
+    Ok(get_app_data_subdir("filename-index")?.join(format!("{}.sqlite", connection_key)))
+}
+
+fn open_read_only(path: &Path) -> Result<Connection, String> {
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open filename index: {}", e))
+}
+
+/// 创建临时索引并写入通道中收到的所有文件，通道关闭后返回连接和内容索引的统计
+/// 指定内容配额时，内容占用的磁盘空间达到配额后设置 quota_reached 并丢弃之后的内容，最多超出一个写入事务
+fn write_files(
+    path: &Path,
+    rx: mpsc::Receiver<IndexRecord>,
+    content_quota: Option<u64>,
+    quota_reached: &AtomicBool,
+) -> Result<(Connection, ContentSummary), String> {
+    let mut conn =
+        Connection::open(path).map_err(|e| format!("Failed to create filename index: {}", e))?;
+    // 临时文件构建完成后才替换旧索引，无需日志保证崩溃安全
+    conn.execute_batch(
+        "PRAGMA journal_mode = OFF;
+         PRAGMA synchronous = OFF;
+         CREATE VIRTUAL TABLE files USING fts5(
+             name, path UNINDEXED, size UNINDEXED, lastmod UNINDEXED,
+             tokenize = 'trigram'
+         );
+         CREATE VIRTUAL TABLE contents USING fts5(
+             path UNINDEXED, content,
+             tokenize = 'trigram'
+         );
+         CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+    )
+    .map_err(|e| format!("Failed to create filename index: {}", e))?;
+
+    let mut batch = Vec::with_capacity(INSERT_BATCH_SIZE);
+    let mut batch_bytes = 0usize;
+    let mut content = ContentSummary::default();
+    // 文件内容在遍历结束后才写入，写入第一条内容前的索引大小即文件名部分的大小
+    let mut content_base = None;
+    for record in rx {
+        if let IndexRecord::Content { text, .. } = &record {
+            if quota_reached.load(Ordering::Relaxed) {
+                content.truncated = true;
+                continue;
+            }
+            if content_base.is_none() {
+                insert_batch(&mut conn, &mut batch)?;
+                batch_bytes = 0;
+                content_base = Some(index_size(&conn)?);
+            }
+            batch_bytes += text.len();
+            content.files += 1;
+        }
+        batch.push(record);
+        if batch.len() >= INSERT_BATCH_SIZE || batch_bytes >= MAX_BATCH_CONTENT_BYTES {
+            insert_batch(&mut conn, &mut batch)?;
+            batch_bytes = 0;
+            if let Some(base) = content_base {
+                content.bytes = index_size(&conn)?.saturating_sub(base);
+                if content_quota.is_some_and(|quota| content.bytes >= quota) {
+                    quota_reached.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+    insert_batch(&mut conn, &mut batch)?;
+    if let Some(base) = content_base {
+        content.bytes = index_size(&conn)?.saturating_sub(base);
+    }
+    Ok((conn, content))
+}
+
+/// 索引数据库当前占用的空间（页数 × 页大小）
+fn index_size(conn: &Connection) -> Result<u64, String> {
+    conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|size| size.max(0) as u64)
+    .map_err(|e| format!("Failed to measure filename index: {}", e))
+}
+
+fn insert_batch(conn: &mut Connection, batch: &mut Vec<IndexRecord>) -> Result<(), String> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to write filename index: {}", e))?;
+    {
+        let mut insert_file = tx
+            .prepare_cached("INSERT INTO files (name, path, size, lastmod) VALUES (?1, ?2, ?3, ?4)")
+            .map_err(|e| format!("Failed to write filename index: {}", e))?;
+        let mut insert_content = tx
+            .prepare_cached("INSERT INTO contents (path, content) VALUES (?1, ?2)")
+            .map_err(|e| format!("Failed to write filename index: {}", e))?;
+        for record in batch.drain(..) {
+            match record {
+                IndexRecord::File(file) => {
+                    insert_file.execute(params![file.name, file.path, file.size, file.lastmod])
+                }
+                IndexRecord::Content { path, text } => insert_content.execute(params![path, text]),
+            }
+            .map_err(|e| format!("Failed to write filename index: {}", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to write filename index: {}", e))
+}
+
+/// 合并索引段并写入元信息
+fn finish_index(conn: Connection, meta: &[(&str, String)]) -> Result<(), String> {
+    conn.execute_batch(
+        "INSERT INTO files (files) VALUES ('optimize');
+         INSERT INTO contents (contents) VALUES ('optimize');",
+    )
+    .map_err(|e| format!("Failed to optimize filename index: {}", e))?;
+
+    for (key, value) in meta {
+        conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )
+        .map_err(|e| format!("Failed to write filename index: {}", e))?;
+    }
+
+    conn.close()
+        .map_err(|(_, e)| format!("Failed to close filename index: {}", e))
+}
+
+fn flag(value: bool) -> String {
+    if value { "1" } else { "0" }.to_string()
+}
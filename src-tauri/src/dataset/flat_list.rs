@@ -0,0 +1,125 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::dataset::facets::FacetFilter;
+use crate::dataset::ignore_rules::IgnoreRules;
+use crate::dataset::walker::TreeWalker;
+use crate::storage::traits::StorageClient;
+
+/// 默认每页条目数
+const DEFAULT_PAGE_SIZE: u32 = 500;
+
+/// 单页条目数上限
+const MAX_PAGE_SIZE: u32 = 5000;
+
+/// 平铺视图的分页选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatListOptions {
+    pub page_size: Option<u32>,
+    /// 上一页返回的游标，为空时从头开始遍历
+    pub cursor: Option<String>,
+    /// 最大遍历深度，为空表示不限制
+    pub max_depth: Option<u32>,
+    /// 是否同时返回目录条目，默认只返回文件
+    pub include_directories: Option<bool>,
+    /// 本次遍历额外使用的 gitignore 风格忽略规则（与工作区规则合并）
+    pub ignore_patterns: Option<Vec<String>>,
+    /// 按扩展名、大小或修改时间分桶筛选（与 dataset_compute_facets 的分面一致）
+    pub filter: Option<FacetFilter>,
+}
+
+/// 平铺视图中的一个条目
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatEntry {
+    /// 完整路径，可直接用于预览和下载
+    pub path: String,
+    /// 相对遍历根目录的路径
+    pub relative_path: String,
+    pub size: String, // 使用字符串表示大数字
+    /// 相对根目录的层级，根目录下的直接子项为 1
+    pub depth: u32,
+    pub lastmod: String,
+    #[serde(rename = "type")]
+    pub file_type: String,
+    pub mime: Option<String>,
+}
+
+/// 平铺视图的一页结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatListPage {
+    pub root: String,
+    pub entries: Vec<FlatEntry>,
+    /// 下一页游标，为空表示已遍历完成
+    pub next_cursor: Option<String>,
+}
+
+/// 递归目录树的平铺列表
+/// 按广度优先顺序流式遍历，每页只遍历到凑满一页为止
+pub struct FlatLister;
+
+impl FlatLister {
+    pub async fn list(
+        client: Arc<dyn StorageClient>,
+        root: &str,
+        options: &FlatListOptions,
+    ) -> Result<FlatListPage, String> {
+        let page_size = options
+            .page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE) as usize;
+        let include_directories = options.include_directories.unwrap_or(false);
+        let filter = options.filter.clone().unwrap_or_default();
+        let now = Utc::now();
+        let ignore_rules = IgnoreRules::for_operation(root, options.ignore_patterns.as_deref())?;
+
+        let walker = TreeWalker::new(client)
+            .max_depth(options.max_depth)
+            .ignore_rules(ignore_rules);
+
+        // 被过滤的条目不计入页大小，需要继续遍历直到凑满一页
+        let mut entries = Vec::new();
+        let mut cursor = options.cursor.clone();
+        loop {
+            let (page, next_cursor) = walker
+                .walk_page(root, cursor.as_deref(), page_size - entries.len())
+                .await?;
+
+            entries.extend(
+                page.into_iter()
+                    .filter(|entry| include_directories || !entry.is_directory())
+                    .filter(|entry| filter.matches(entry, now))
+                    .map(|entry| FlatEntry {
+                        relative_path: relative_to(root, &entry.path),
+                        size: entry.file.size.clone(),
+                        depth: entry.depth,
+                        lastmod: entry.file.lastmod.clone(),
+                        file_type: entry.file.file_type.clone(),
+                        mime: entry.file.mime.clone(),
+                        path: entry.path,
+                    }),
+            );
+
+            cursor = next_cursor;
+            if cursor.is_none() || entries.len() >= page_size {
+                break;
+            }
+        }
+
+        Ok(FlatListPage {
+            root: root.to_string(),
+            entries,
+            next_cursor: cursor,
+        })
+    }
+}
+
+fn relative_to(root: &str, path: &str) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .trim_start_matches('/')
+        .to_string()
+}
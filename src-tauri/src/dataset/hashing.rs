@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::storage::traits::StorageClient;
+
+/// 流式哈希时每次读取的块大小
+const HASH_READ_CHUNK: u64 = 4 * 1024 * 1024; // 4MB
+
+/// 通过 StorageClient 分块读取文件并计算 SHA-256
+/// on_bytes 在每读取一块后以本块字节数回调，便于上层汇总进度
+pub async fn sha256_streaming<F>(
+    client: &Arc<dyn StorageClient>,
+    path: &str,
+    size: u64,
+    mut on_bytes: F,
+) -> Result<String, String>
+where
+    F: FnMut(u64),
+{
+    let mut hasher = Sha256::new();
+    let mut offset = 0u64;
+
+    while offset < size {
+        let length = HASH_READ_CHUNK.min(size - offset);
+        let chunk = client
+            .read_file_range(path, offset, length)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        hasher.update(&chunk);
+        offset += chunk.len() as u64;
+        on_bytes(chunk.len() as u64);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 分块签名默认块大小
+pub const DEFAULT_SIGNATURE_BLOCK_SIZE: u64 = 8 * 1024 * 1024; // 8MB
+
+/// 大文件分块哈希签名
+/// 用于跨存储后端比较文件内容，只需传输签名而非完整数据
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkSignature {
+    pub path: String,
+    pub size: String, // 使用字符串表示大数字
+    pub block_size: String,
+    pub algorithm: String,
+    /// 每个块的 xxh3-64 哈希（十六进制）
+    pub blocks: Vec<String>,
+}
+
+/// 两个签名的比较结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureDiff {
+    pub identical: bool,
+    /// 内容不同（或只存在于一方）的块索引
+    pub changed_blocks: Vec<u32>,
+    pub unchanged_blocks: u32,
+}
+
+/// 计算文件的分块 xxh3 签名
+/// on_progress 以 (已处理字节数, 总字节数) 回调
+pub async fn chunk_signature<F>(
+    client: &Arc<dyn StorageClient>,
+    path: &str,
+    block_size: Option<u64>,
+    on_progress: F,
+) -> Result<ChunkSignature, String>
+where
+    F: Fn(u64, u64),
+{
+    let block_size = block_size
+        .filter(|size| *size > 0)
+        .unwrap_or(DEFAULT_SIGNATURE_BLOCK_SIZE);
+    let size = client
+        .get_file_size(path)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+    let mut blocks = Vec::with_capacity(size.div_ceil(block_size) as usize);
+    let mut offset = 0u64;
+
+    while offset < size {
+        // 范围读取可能返回比请求更短的数据，读满一个块（或到文件末尾）后再计算哈希，
+        // 否则块边界会错位，相同内容的文件得到不同的签名
+        let length = block_size.min(size - offset);
+        let mut block = Vec::with_capacity(length as usize);
+        while (block.len() as u64) < length {
+            let read = block.len() as u64;
+            let data = client
+                .read_file_range(path, offset + read, length - read)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            if data.is_empty() {
+                break;
+            }
+            block.extend_from_slice(&data);
+        }
+
+        if block.is_empty() {
+            break;
+        }
+
+        blocks.push(format!("{:016x}", xxh3_64(&block)));
+        offset += block.len() as u64;
+        on_progress(offset, size);
+        if (block.len() as u64) < length {
+            break;
+        }
+    }
+
+    Ok(ChunkSignature {
+        path: path.to_string(),
+        size: size.to_string(),
+        block_size: block_size.to_string(),
+        algorithm: "xxh3-64".to_string(),
+        blocks,
+    })
+}
+
+/// 比较两个分块签名，块大小不一致时无法比较
+pub fn compare_signatures(
+    left: &ChunkSignature,
+    right: &ChunkSignature,
+) -> Result<SignatureDiff, String> {
+    if left.block_size != right.block_size || left.algorithm != right.algorithm {
+        return Err("Signatures use different block sizes or algorithms".to_string());
+    }
+
+    let block_count = left.blocks.len().max(right.blocks.len());
+    let changed_blocks: Vec<u32> = (0..block_count)
+        .filter(|&i| left.blocks.get(i) != right.blocks.get(i))
+        .map(|i| i as u32)
+        .collect();
+
+    Ok(SignatureDiff {
+        identical: changed_blocks.is_empty() && left.size == right.size,
+        unchanged_blocks: (block_count - changed_blocks.len()) as u32,
+        changed_blocks,
+    })
+}
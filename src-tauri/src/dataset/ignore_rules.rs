@@ -0,0 +1,140 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 一组 gitignore 风格的忽略规则
+/// 规则相对 base 目录匹配，语义与 .gitignore 一致（支持 **、取反 !、目录结尾 /）
+pub struct IgnoreRules {
+    base: String,
+    matcher: Gitignore,
+}
+
+impl IgnoreRules {
+    /// 根据规则文本构建，空行和 # 注释会被忽略；没有有效规则时返回 None
+    pub fn build(base: &str, patterns: &[String]) -> Result<Option<Self>, String> {
+        let mut builder = GitignoreBuilder::new("");
+        let mut has_rules = false;
+
+        for pattern in patterns {
+            let line = pattern.trim_end();
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            builder
+                .add_line(None, line)
+                .map_err(|e| format!("Invalid ignore pattern '{}': {}", line, e))?;
+            has_rules = true;
+        }
+
+        if !has_rules {
+            return Ok(None);
+        }
+
+        let matcher = builder
+            .build()
+            .map_err(|e| format!("Failed to build ignore rules: {}", e))?;
+
+        Ok(Some(Self {
+            base: base.trim_end_matches('/').to_string(),
+            matcher,
+        }))
+    }
+
+    /// 依次应用多组规则判断路径是否被忽略，与 .gitignore 一样后面的规则优先：
+    /// 后一组的取反规则（!pattern）可以重新包含前一组忽略的路径
+    pub fn is_ignored_by(rules: &[Self], path: &str, is_directory: bool) -> bool {
+        rules.iter().fold(false, |ignored, rules| {
+            match rules.matched(path, is_directory) {
+                Match::None => ignored,
+                Match::Ignore(_) => true,
+                Match::Whitelist(_) => false,
+            }
+        })
+    }
+
+    /// 路径在本组规则中的匹配结果，不在 base 目录下的路径不受影响
+    fn matched(&self, path: &str, is_directory: bool) -> Match<()> {
+        // 按路径分段比较，base 为 /data 时不匹配 /dataset
+        let relative = match path.strip_prefix(&self.base) {
+            Some(rest) if self.base.is_empty() || rest.is_empty() || rest.starts_with('/') => {
+                rest.trim_matches('/')
+            }
+            _ => return Match::None,
+        };
+        if relative.is_empty() {
+            return Match::None;
+        }
+
+        self.matcher.matched(relative, is_directory).map(|_| ())
+    }
+
+    /// 组合递归操作使用的规则：工作区保存的规则 + 本次操作指定的规则
+    pub fn for_operation(root: &str, patterns: Option<&[String]>) -> Result<Vec<Self>, String> {
+        let mut rules = Vec::new();
+
+        if let Some((workspace, saved)) = WorkspaceIgnoreStore::find(root) {
+            rules.extend(Self::build(&workspace, &saved)?);
+        }
+        if let Some(patterns) = patterns {
+            rules.extend(Self::build(root, patterns)?);
+        }
+
+        Ok(rules)
+    }
+}
+
+/// 按工作区根路径保存的忽略规则
+pub struct WorkspaceIgnoreStore;
+
+impl WorkspaceIgnoreStore {
+    fn store_file() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join("ignore-rules.json"))
+    }
+
+    fn load_all() -> HashMap<String, Vec<String>> {
+        Self::store_file()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    /// 获取工作区保存的规则
+    pub fn get(workspace: &str) -> Vec<String> {
+        Self::load_all()
+            .remove(workspace.trim_end_matches('/'))
+            .unwrap_or_default()
+    }
+
+    /// 保存工作区规则，规则为空时删除该工作区的记录
+    pub fn set(workspace: &str, patterns: Vec<String>) -> Result<(), String> {
+        let mut all = Self::load_all();
+        let key = workspace.trim_end_matches('/').to_string();
+        if patterns.iter().all(|p| p.trim().is_empty()) {
+            all.remove(&key);
+        } else {
+            all.insert(key, patterns);
+        }
+
+        let content = serde_json::to_string_pretty(&all)
+            .map_err(|e| format!("Failed to serialize ignore rules: {}", e))?;
+        persist::write_atomic(Self::store_file()?, content)
+            .map_err(|e| format!("Failed to save ignore rules: {}", e))
+    }
+
+    /// 查找包含该路径的最近工作区及其规则
+    pub fn find(path: &str) -> Option<(String, Vec<String>)> {
+        Self::load_all()
+            .into_iter()
+            .filter(|(workspace, _)| {
+                path == workspace
+                    || path
+                        .strip_prefix(workspace.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(workspace, _)| workspace.len())
+    }
+}
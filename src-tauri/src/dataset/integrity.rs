@@ -0,0 +1,204 @@
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const MIB: u64 = 1024 * 1024;
+
+/// S3 分片上传允许的最小分片大小
+const MIN_PART_SIZE: u64 = 5 * MIB;
+
+/// 常见上传工具使用的分片大小（aws cli 8MB、s3cmd 15MB、rclone 5MB 等）
+const COMMON_PART_SIZES: [u64; 10] = [
+    8 * MIB,
+    5 * MIB,
+    16 * MIB,
+    15 * MIB,
+    32 * MIB,
+    64 * MIB,
+    100 * MIB,
+    128 * MIB,
+    256 * MIB,
+    512 * MIB,
+];
+
+/// 本地读取缓冲区大小
+const READ_BUFFER_SIZE: usize = 1024 * 1024; // 1MB
+
+/// 本地文件与远程对象的完整性比较结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityCheck {
+    pub local_path: String,
+    pub remote_path: String,
+    pub remote_etag: String,
+    /// 与远程 ETag 采用相同算法计算出的本地 ETag，无法计算时为 None
+    pub local_etag: Option<String>,
+    /// "md5"、"multipart-md5" 或 "unverifiable"（如 SSE-KMS 加密对象的 ETag 不是 MD5）
+    pub method: String,
+    /// 匹配成功的分片大小（仅分片 ETag）
+    pub part_size: Option<String>,
+    pub matches: bool,
+}
+
+/// 解析 ETag，返回 (MD5 十六进制部分, 分片数量)
+/// 非 MD5 形式的 ETag 返回 None
+pub fn parse_etag(etag: &str) -> Option<(String, Option<u64>)> {
+    let etag = etag.trim().trim_start_matches("W/").trim_matches('"');
+    let (hash, parts) = match etag.split_once('-') {
+        Some((hash, parts)) => (hash, Some(parts.parse::<u64>().ok()?)),
+        None => (etag, None),
+    };
+
+    if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some((hash.to_lowercase(), parts))
+}
+
+/// 根据文件大小和分片数量推测可能的分片大小
+/// 优先使用调用方指定的分片大小，其次是按 MiB 取整的平均值和常见工具的默认值
+pub fn candidate_part_sizes(size: u64, part_count: u64, preferred: Option<u64>) -> Vec<u64> {
+    let mut candidates = Vec::new();
+    if part_count == 0 {
+        return candidates;
+    }
+
+    let average = size.div_ceil(part_count);
+    let mut push = |part_size: u64| {
+        if part_size >= MIN_PART_SIZE.min(size)
+            && size.div_ceil(part_size) == part_count
+            && !candidates.contains(&part_size)
+        {
+            candidates.push(part_size);
+        }
+    };
+
+    if let Some(part_size) = preferred.filter(|s| *s > 0) {
+        push(part_size);
+    }
+    push(average.div_ceil(MIB) * MIB);
+    for part_size in COMMON_PART_SIZES {
+        push(part_size);
+    }
+    push(average);
+
+    candidates
+}
+
+/// 计算本地文件的分片 ETag：各分片 MD5 拼接后再取 MD5，附加 "-分片数"
+pub fn multipart_etag(path: &Path, part_size: u64) -> Result<String, String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut combined = Md5::new();
+    let mut part = Md5::new();
+    let mut part_filled = 0u64;
+    let mut part_count = 0u64;
+
+    loop {
+        let want = (part_size - part_filled).min(buffer.len() as u64) as usize;
+        let read = file
+            .read(&mut buffer[..want])
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+
+        part.update(&buffer[..read]);
+        part_filled += read as u64;
+
+        if part_filled == part_size {
+            combined.update(part.finalize_reset());
+            part_filled = 0;
+            part_count += 1;
+        }
+    }
+
+    if part_filled > 0 || part_count == 0 {
+        combined.update(part.finalize());
+        part_count += 1;
+    }
+
+    Ok(format!(
+        "{}-{}",
+        hex::encode(combined.finalize()),
+        part_count
+    ))
+}
+
+/// 计算本地文件的整体 MD5
+pub fn file_md5(path: &Path) -> Result<String, String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut hasher = Md5::new();
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 比较本地文件与远程对象的 ETag
+/// 分片上传的对象依次尝试候选分片大小，直到找到匹配项
+pub fn verify_against_etag(
+    local_path: &str,
+    remote_path: &str,
+    remote_etag: &str,
+    preferred_part_size: Option<u64>,
+) -> Result<IntegrityCheck, String> {
+    let path = Path::new(local_path);
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read metadata of {}: {}", local_path, e))?
+        .len();
+
+    let mut check = IntegrityCheck {
+        local_path: local_path.to_string(),
+        remote_path: remote_path.to_string(),
+        remote_etag: remote_etag.to_string(),
+        local_etag: None,
+        method: "unverifiable".to_string(),
+        part_size: None,
+        matches: false,
+    };
+
+    let Some((remote_hash, part_count)) = parse_etag(remote_etag) else {
+        return Ok(check);
+    };
+
+    match part_count {
+        None => {
+            let local_hash = file_md5(path)?;
+            check.method = "md5".to_string();
+            check.matches = local_hash == remote_hash;
+            check.local_etag = Some(local_hash);
+        }
+        Some(part_count) => {
+            check.method = "multipart-md5".to_string();
+            let expected = format!("{}-{}", remote_hash, part_count);
+
+            for part_size in candidate_part_sizes(size, part_count, preferred_part_size) {
+                let local_etag = multipart_etag(path, part_size)?;
+                let matches = local_etag == expected;
+                check.local_etag = Some(local_etag);
+                check.part_size = Some(part_size.to_string());
+                if matches {
+                    check.matches = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(check)
+}
@@ -0,0 +1,748 @@
+// 许可证检测与汇总
+// 在目录树或压缩包中查找 LICENSE/COPYING/NOTICE 文件、SPDX 标识和数据集卡片（README.md front matter）中的 license 字段，
+// 按特征文本匹配到 SPDX 标识并分类，检查嵌套组件之间的许可证冲突，辅助混合来源数据集的合规审查
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::{Arc, LazyLock};
+
+use crate::archive::handlers::ArchiveHandler;
+use crate::archive::types::CompressionType;
+use crate::dataset::walker::TreeWalker;
+use crate::storage::traits::StorageClient;
+use crate::utils::mime::is_binary_content;
+
+/// 读取许可证文件的最大字节数，完整的 GPL 文本约 35KB
+const MAX_LICENSE_FILE_SIZE: u64 = 256 * 1024; // 256KB
+
+/// 最多遍历的文件数量
+const MAX_SCAN_FILES: usize = 100_000;
+
+static SPDX_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"SPDX-License-Identifier:\s*([A-Za-z0-9.+\-]+)").expect("valid SPDX regex")
+});
+
+/// 许可证分类
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, specta::Type,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum LicenseCategory {
+    PublicDomain,
+    Permissive,
+    /// 弱 copyleft（LGPL、MPL），修改库本身时才需要开源
+    WeakCopyleft,
+    /// 强 copyleft 及相同方式共享（GPL、CC-BY-SA、ODbL），衍生作品必须使用相同许可证
+    Copyleft,
+    /// 禁止商业使用
+    NonCommercial,
+    /// 禁止演绎
+    NoDerivatives,
+    /// 无法识别，需要人工审查
+    Unknown,
+}
+
+/// 许可证来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LicenseSource {
+    /// LICENSE / COPYING / NOTICE 文件全文
+    LicenseFile,
+    /// 文件中的 SPDX-License-Identifier 标记
+    SpdxTag,
+    /// 数据集卡片 front matter 中的 license 字段
+    DatasetCard,
+}
+
+/// 一处许可证声明
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseFinding {
+    pub path: String,
+    /// 压缩包内的条目路径，普通文件为空
+    pub entry_path: Option<String>,
+    pub source: LicenseSource,
+    /// SPDX 标识，无法识别时为文件中的原始值或空
+    pub spdx: Option<String>,
+    pub category: LicenseCategory,
+    /// 许可证适用的范围（声明所在的目录，压缩包内的目录以压缩包路径为前缀）
+    pub scope: String,
+}
+
+/// 按许可证汇总
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseSummary {
+    /// SPDX 标识，无法识别的声明汇总为 "UNKNOWN"
+    pub spdx: String,
+    pub category: LicenseCategory,
+    pub scopes: Vec<String>,
+}
+
+/// 冲突严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictSeverity {
+    /// 条款不兼容，不能合并分发
+    Error,
+    /// 合并后整体受到限制或需要人工审查
+    Warning,
+}
+
+/// 嵌套组件之间的许可证冲突
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseConflict {
+    pub severity: ConflictSeverity,
+    pub licenses: Vec<String>,
+    pub scopes: Vec<String>,
+    pub message: String,
+}
+
+/// 检测选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseScanOptions {
+    /// 遍历目录时是否检查压缩包内的许可证文件，默认 false
+    pub scan_archives: Option<bool>,
+}
+
+/// 许可证检测报告
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseReport {
+    pub findings: Vec<LicenseFinding>,
+    pub licenses: Vec<LicenseSummary>,
+    pub conflicts: Vec<LicenseConflict>,
+    pub scanned_files: u32,
+    /// 是否因达到文件数量上限而未完整遍历
+    pub truncated: bool,
+}
+
+/// 已知许可证的特征文本，按从具体到宽泛的顺序匹配
+struct KnownLicense {
+    spdx: &'static str,
+    category: LicenseCategory,
+    /// 规范化（小写、合并空白）后的全文必须包含的所有片段
+    markers: &'static [&'static str],
+    /// 片段只在开头的标题部分查找（正文可能引用其他许可证的名称）
+    title_only: bool,
+}
+
+/// 许可证标题所在的开头部分（规范化后的字符数）
+const TITLE_LENGTH: usize = 200;
+
+const KNOWN_LICENSES: &[KnownLicense] = &[
+    KnownLicense {
+        spdx: "AGPL-3.0",
+        category: LicenseCategory::Copyleft,
+        // GPL-3.0 正文第 13 条引用了 AGPL 的名称，只按标题识别
+        markers: &["gnu affero general public license", "version 3"],
+        title_only: true,
+    },
+    KnownLicense {
+        spdx: "LGPL-3.0",
+        category: LicenseCategory::WeakCopyleft,
+        markers: &["gnu lesser general public license", "version 3"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "LGPL-2.1",
+        category: LicenseCategory::WeakCopyleft,
+        markers: &["gnu lesser general public license", "version 2.1"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "GPL-3.0",
+        category: LicenseCategory::Copyleft,
+        markers: &["gnu general public license", "version 3"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "GPL-2.0",
+        category: LicenseCategory::Copyleft,
+        markers: &["gnu general public license", "version 2"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "Apache-2.0",
+        category: LicenseCategory::Permissive,
+        markers: &["apache license", "version 2.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "MPL-2.0",
+        category: LicenseCategory::WeakCopyleft,
+        markers: &["mozilla public license", "2.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC-BY-NC-SA-4.0",
+        category: LicenseCategory::NonCommercial,
+        markers: &["attribution-noncommercial-sharealike 4.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC-BY-NC-ND-4.0",
+        category: LicenseCategory::NonCommercial,
+        markers: &["attribution-noncommercial-noderivatives 4.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC-BY-NC-4.0",
+        category: LicenseCategory::NonCommercial,
+        markers: &["attribution-noncommercial 4.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC-BY-ND-4.0",
+        category: LicenseCategory::NoDerivatives,
+        markers: &["attribution-noderivatives 4.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC-BY-SA-4.0",
+        category: LicenseCategory::Copyleft,
+        markers: &["attribution-sharealike 4.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC-BY-4.0",
+        category: LicenseCategory::Permissive,
+        markers: &["creative commons attribution 4.0"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "CC0-1.0",
+        category: LicenseCategory::PublicDomain,
+        markers: &["cc0 1.0 universal"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "ODbL-1.0",
+        category: LicenseCategory::Copyleft,
+        markers: &["open database license"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "ODC-By-1.0",
+        category: LicenseCategory::Permissive,
+        markers: &["open data commons attribution license"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "PDDL-1.0",
+        category: LicenseCategory::PublicDomain,
+        markers: &["public domain dedication and license"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "Unlicense",
+        category: LicenseCategory::PublicDomain,
+        markers: &["free and unencumbered software released into the public domain"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "BSD-3-Clause",
+        category: LicenseCategory::Permissive,
+        markers: &[
+            "redistribution and use in source and binary forms",
+            "neither the name",
+        ],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "BSD-2-Clause",
+        category: LicenseCategory::Permissive,
+        markers: &["redistribution and use in source and binary forms"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "MIT",
+        category: LicenseCategory::Permissive,
+        markers: &["permission is hereby granted, free of charge"],
+        title_only: false,
+    },
+    KnownLicense {
+        spdx: "ISC",
+        category: LicenseCategory::Permissive,
+        markers: &[
+            "permission to use, copy, modify, and/or distribute this software for any purpose",
+        ],
+        title_only: false,
+    },
+];
+
+/// 许可证检测器
+pub struct LicenseDetector;
+
+impl LicenseDetector {
+    /// 检测目录树或单个压缩包中的许可证
+    pub async fn scan(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        options: &LicenseScanOptions,
+    ) -> Result<LicenseReport, String> {
+        let mut findings = Vec::new();
+        let mut scanned_files = 0u32;
+        let mut truncated = false;
+
+        if is_archive(path) {
+            scanned_files += Self::scan_archive(client, path, &mut findings).await?;
+        } else {
+            let (files, walk_truncated) = TreeWalker::new(client.clone())
+                .max_files(MAX_SCAN_FILES)
+                .collect_files(path)
+                .await?;
+            truncated = walk_truncated;
+
+            for file in &files {
+                scanned_files += 1;
+                let name = file_name(&file.path);
+                if options.scan_archives.unwrap_or(false) && is_archive(&file.path) {
+                    match Self::scan_archive(client.clone(), &file.path, &mut findings).await {
+                        Ok(entries) => scanned_files += entries,
+                        Err(e) => log::debug!("检查压缩包许可证失败 {}: {}", file.path, e),
+                    }
+                    continue;
+                }
+                if !is_candidate(name) || file.size() == 0 || file.size() > MAX_LICENSE_FILE_SIZE {
+                    continue;
+                }
+
+                let content = match client.read_file_range(&file.path, 0, file.size()).await {
+                    Ok(content) => content,
+                    Err(e) => {
+                        log::debug!("读取许可证文件失败 {}: {}", file.path, e);
+                        continue;
+                    }
+                };
+                findings.extend(classify_file(
+                    &file.path,
+                    None,
+                    parent_path(&file.path),
+                    &content,
+                ));
+            }
+        }
+
+        findings.sort_by(|a, b| {
+            (&a.scope, &a.path, &a.entry_path).cmp(&(&b.scope, &b.path, &b.entry_path))
+        });
+        let licenses = summarize(&findings);
+        let conflicts = find_conflicts(&findings);
+
+        Ok(LicenseReport {
+            findings,
+            licenses,
+            conflicts,
+            scanned_files,
+            truncated,
+        })
+    }
+
+    /// 检查压缩包中的许可证文件，返回检查的条目数
+    async fn scan_archive(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        findings: &mut Vec<LicenseFinding>,
+    ) -> Result<u32, String> {
+        let filename = file_name(path);
+        let handler = ArchiveHandler::new();
+        let info = handler
+            .analyze_archive_with_client(
+                client.clone(),
+                path.to_string(),
+                filename.to_string(),
+                None,
+            )
+            .await?;
+
+        let mut scanned = 0u32;
+        for entry in info.entries.iter().filter(|entry| !entry.is_dir) {
+            scanned += 1;
+            let size = entry.size.parse::<u64>().unwrap_or(0);
+            if !is_candidate(file_name(&entry.path)) || size == 0 || size > MAX_LICENSE_FILE_SIZE {
+                continue;
+            }
+
+            let preview = match handler
+                .get_file_preview_with_client(
+                    client.clone(),
+                    path.to_string(),
+                    filename.to_string(),
+                    entry.path.clone(),
+                    Some(MAX_LICENSE_FILE_SIZE as u32),
+                    None,
+                    None::<fn(u64, u64)>,
+                    None,
+                )
+                .await
+            {
+                Ok(preview) => preview,
+                Err(e) => {
+                    log::debug!("读取压缩包条目失败 {}!{}: {}", path, entry.path, e);
+                    continue;
+                }
+            };
+
+            let entry_parent = parent_path(&entry.path);
+            let scope = if entry_parent.is_empty() {
+                path.to_string()
+            } else {
+                format!("{}/{}", path, entry_parent)
+            };
+            findings.extend(classify_file(
+                path,
+                Some(&entry.path),
+                &scope,
+                &preview.content,
+            ));
+        }
+        Ok(scanned)
+    }
+}
+
+/// 解析单个候选文件中的许可证声明
+fn classify_file(
+    path: &str,
+    entry_path: Option<&str>,
+    scope: &str,
+    content: &[u8],
+) -> Vec<LicenseFinding> {
+    if is_binary_content(content) {
+        return Vec::new();
+    }
+    let text = String::from_utf8_lossy(content);
+    let name = file_name(entry_path.unwrap_or(path)).to_lowercase();
+    let finding = |source, spdx: Option<String>| {
+        let category = spdx
+            .as_deref()
+            .map(category_of)
+            .unwrap_or(LicenseCategory::Unknown);
+        LicenseFinding {
+            path: path.to_string(),
+            entry_path: entry_path.map(str::to_string),
+            source,
+            spdx,
+            category,
+            scope: scope.to_string(),
+        }
+    };
+
+    // 数据集卡片只看 front matter 中的 license 字段
+    if name == "readme.md" {
+        return card_licenses(&text)
+            .into_iter()
+            .map(|id| finding(LicenseSource::DatasetCard, Some(canonical_spdx(&id))))
+            .collect();
+    }
+
+    let tags: BTreeSet<String> = SPDX_TAG
+        .captures_iter(&text)
+        .map(|captures| canonical_spdx(&captures[1]))
+        .collect();
+    if !tags.is_empty() {
+        return tags
+            .into_iter()
+            .map(|id| finding(LicenseSource::SpdxTag, Some(id)))
+            .collect();
+    }
+
+    let normalized = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let title = match normalized.char_indices().nth(TITLE_LENGTH) {
+        Some((end, _)) => &normalized[..end],
+        None => normalized.as_str(),
+    };
+    let spdx = KNOWN_LICENSES
+        .iter()
+        .find(|known| {
+            let text = if known.title_only { title } else { &normalized };
+            known.markers.iter().all(|marker| text.contains(marker))
+        })
+        .map(|known| known.spdx.to_string());
+    // NOTICE 文件通常只是署名声明，无法识别时不作为许可证报告
+    if spdx.is_none() && name.starts_with("notice") {
+        return Vec::new();
+    }
+    vec![finding(LicenseSource::LicenseFile, spdx)]
+}
+
+/// 解析 README.md front matter 中的 license 字段，支持单值和列表
+fn card_licenses(text: &str) -> Vec<String> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some("---") {
+        return Vec::new();
+    }
+
+    let mut licenses = Vec::new();
+    let mut in_list = false;
+    for line in lines {
+        if line.trim() == "---" {
+            break;
+        }
+        if in_list {
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                licenses.push(unquote(item));
+                continue;
+            }
+            in_list = false;
+        }
+        if let Some(value) = line.strip_prefix("license:") {
+            let value = value.trim();
+            if value.is_empty() {
+                in_list = true;
+            } else if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                licenses.extend(items.split(',').map(unquote).filter(|s| !s.is_empty()));
+            } else {
+                licenses.push(unquote(value));
+            }
+        }
+    }
+    licenses
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+/// 规范化 SPDX 标识：忽略大小写，去掉 -only / -or-later 后缀，补全省略的版本号（如 odc-by）
+fn canonical_spdx(id: &str) -> String {
+    let id = id.trim();
+    let base = id
+        .strip_suffix("-only")
+        .or_else(|| id.strip_suffix("-or-later"))
+        .or_else(|| id.strip_suffix('+'))
+        .unwrap_or(id);
+    KNOWN_LICENSES
+        .iter()
+        .find(|known| known.spdx.eq_ignore_ascii_case(base))
+        .or_else(|| {
+            // 只补全版本号：cc-by-nc 对应 CC-BY-NC-4.0，而不是 CC-BY-NC-SA-4.0
+            let prefix = format!("{}-", base.to_lowercase());
+            KNOWN_LICENSES.iter().find(|known| {
+                known
+                    .spdx
+                    .to_lowercase()
+                    .strip_prefix(&prefix)
+                    .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+            })
+        })
+        .map(|known| known.spdx.to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn category_of(spdx: &str) -> LicenseCategory {
+    KNOWN_LICENSES
+        .iter()
+        .find(|known| known.spdx == spdx)
+        .map(|known| known.category)
+        .unwrap_or(LicenseCategory::Unknown)
+}
+
+fn summarize(findings: &[LicenseFinding]) -> Vec<LicenseSummary> {
+    let mut summaries: Vec<LicenseSummary> = Vec::new();
+    for finding in findings {
+        let spdx = finding
+            .spdx
+            .clone()
+            .unwrap_or_else(|| "UNKNOWN".to_string());
+        match summaries.iter_mut().find(|summary| summary.spdx == spdx) {
+            Some(summary) => {
+                if !summary.scopes.contains(&finding.scope) {
+                    summary.scopes.push(finding.scope.clone());
+                }
+            }
+            None => summaries.push(LicenseSummary {
+                spdx,
+                category: finding.category,
+                scopes: vec![finding.scope.clone()],
+            }),
+        }
+    }
+    summaries.sort_by(|a, b| (a.category, &a.spdx).cmp(&(b.category, &b.spdx)));
+    summaries
+}
+
+/// 检查范围互相嵌套（或相同）的不同许可证之间的冲突，每对许可证只报告一次
+fn find_conflicts(findings: &[LicenseFinding]) -> Vec<LicenseConflict> {
+    let mut conflicts: Vec<LicenseConflict> = Vec::new();
+
+    for finding in findings
+        .iter()
+        .filter(|f| f.category == LicenseCategory::Unknown)
+    {
+        conflicts.push(LicenseConflict {
+            severity: ConflictSeverity::Warning,
+            licenses: vec![finding
+                .spdx
+                .clone()
+                .unwrap_or_else(|| "UNKNOWN".to_string())],
+            scopes: vec![finding.scope.clone()],
+            message: format!(
+                "Unrecognized license in {}, manual review required",
+                finding.entry_path.as_deref().unwrap_or(&finding.path)
+            ),
+        });
+    }
+
+    for (i, a) in findings.iter().enumerate() {
+        for b in &findings[i + 1..] {
+            let (Some(a_id), Some(b_id)) = (&a.spdx, &b.spdx) else {
+                continue;
+            };
+            if a_id == b_id || !scopes_nest(&a.scope, &b.scope) {
+                continue;
+            }
+            let Some((severity, message)) = check_pair(a_id, a.category, b_id, b.category) else {
+                continue;
+            };
+
+            let mut licenses = vec![a_id.clone(), b_id.clone()];
+            licenses.sort();
+            match conflicts.iter_mut().find(|c| c.licenses == licenses) {
+                Some(conflict) => {
+                    for scope in [&a.scope, &b.scope] {
+                        if !conflict.scopes.contains(scope) {
+                            conflict.scopes.push(scope.clone());
+                        }
+                    }
+                }
+                None => conflicts.push(LicenseConflict {
+                    severity,
+                    licenses,
+                    scopes: if a.scope == b.scope {
+                        vec![a.scope.clone()]
+                    } else {
+                        vec![a.scope.clone(), b.scope.clone()]
+                    },
+                    message,
+                }),
+            }
+        }
+    }
+    conflicts
+}
+
+/// 判断两个许可证能否合并分发
+fn check_pair(
+    a: &str,
+    a_category: LicenseCategory,
+    b: &str,
+    b_category: LicenseCategory,
+) -> Option<(ConflictSeverity, String)> {
+    use LicenseCategory::*;
+
+    if a_category == Unknown || b_category == Unknown {
+        return None;
+    }
+    if (a == "GPL-2.0" && b == "Apache-2.0") || (a == "Apache-2.0" && b == "GPL-2.0") {
+        return Some((
+            ConflictSeverity::Error,
+            "GPL-2.0 is incompatible with the patent terms of Apache-2.0".to_string(),
+        ));
+    }
+    if a_category == NoDerivatives || b_category == NoDerivatives {
+        let (nd, other) = if a_category == NoDerivatives {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        return Some((
+            ConflictSeverity::Error,
+            format!(
+                "{} forbids adapted material, it cannot be combined with {}",
+                nd, other
+            ),
+        ));
+    }
+    if a_category == Copyleft && b_category == Copyleft {
+        let compatible = matches!((a, b), ("GPL-3.0", "AGPL-3.0") | ("AGPL-3.0", "GPL-3.0"));
+        if !compatible {
+            return Some((
+                ConflictSeverity::Error,
+                format!(
+                    "{} and {} are both copyleft licenses with incompatible terms",
+                    a, b
+                ),
+            ));
+        }
+        return None;
+    }
+    if a_category == NonCommercial || b_category == NonCommercial {
+        let (nc, other) = if a_category == NonCommercial {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        return Some((
+            ConflictSeverity::Warning,
+            format!(
+                "Non-commercial terms of {} restrict use of material under {}",
+                nc, other
+            ),
+        ));
+    }
+    if a_category == Copyleft || b_category == Copyleft {
+        let (copyleft, other) = if a_category == Copyleft {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        return Some((
+            ConflictSeverity::Warning,
+            format!(
+                "Combined work with {} material must be distributed under {}",
+                other, copyleft
+            ),
+        ));
+    }
+    None
+}
+
+/// 一个范围是否包含另一个范围
+fn scopes_nest(a: &str, b: &str) -> bool {
+    let contains = |outer: &str, inner: &str| {
+        outer.is_empty()
+            || inner == outer
+            || inner
+                .strip_prefix(outer)
+                .is_some_and(|rest| rest.starts_with('/') || outer.ends_with('/'))
+    };
+    contains(a, b) || contains(b, a)
+}
+
+/// LICENSE / LICENCE / COPYING / NOTICE（含扩展名和后缀，如 LICENSE-MIT、COPYING.txt）及 README.md
+fn is_candidate(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    upper == "README.MD"
+        || ["LICENSE", "LICENCE", "COPYING", "NOTICE"]
+            .iter()
+            .any(|prefix| upper.starts_with(prefix))
+}
+
+fn is_archive(path: &str) -> bool {
+    CompressionType::from_filename(file_name(path)) != CompressionType::Unknown
+}
+
+fn file_name(path: &str) -> &str {
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(path)
+}
+
+fn parent_path(path: &str) -> &str {
+    path.trim_end_matches('/')
+        .rsplit_once('/')
+        .map(|(parent, _)| parent)
+        .unwrap_or("")
+}
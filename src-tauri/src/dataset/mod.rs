@@ -0,0 +1,21 @@
+// 数据集维护工具
+// 提供数据集整理、去重、文件名索引、快速打开、容量预算、目录统计、敏感信息扫描、Arrow 文件预览等相关功能
+
+pub mod arrow;
+pub mod content_index;
+pub mod dedup;
+pub mod estimate;
+pub mod facets;
+pub mod filename_index;
+pub mod flat_list;
+pub mod hashing;
+pub mod ignore_rules;
+pub mod integrity;
+pub mod license;
+pub mod pii_scan;
+pub mod quick_open;
+pub mod rename;
+pub mod search_history;
+pub mod size_budget;
+pub mod stats;
+pub mod walker;
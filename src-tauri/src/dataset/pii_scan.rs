@@ -0,0 +1,485 @@
+// 敏感信息扫描
+// 在选定的文件、目录和压缩包中逐行查找邮箱、手机号、身份证号、AWS 密钥、私钥等内置模式及用户自定义的正则规则，
+// 生成带位置（文件、压缩包条目、行列号）的发现报告，报告中的匹配内容默认脱敏
+
+use futures_util::StreamExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crate::archive::handlers::ArchiveHandler;
+use crate::archive::types::CompressionType;
+use crate::dataset::walker::TreeWalker;
+use crate::storage::traits::StorageClient;
+use crate::utils::mime::is_binary_content;
+use crate::utils::result_limit::ResultLimit;
+
+/// 默认跳过大于该大小的文件和压缩包条目
+const DEFAULT_MAX_FILE_SIZE: u64 = 16 * 1024 * 1024; // 16MB
+
+/// 默认最多记录的发现数量
+const DEFAULT_MAX_FINDINGS: u32 = 10_000;
+
+/// 目录展开后最多扫描的文件数量
+const MAX_SCAN_FILES: usize = 100_000;
+
+/// 同时扫描的文件数量
+const SCAN_CONCURRENCY: usize = 4;
+
+/// 报告中保留的匹配内容最大字符数
+const MAX_EXCERPT_CHARS: usize = 120;
+
+/// 内置检测器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PiiDetector {
+    /// 邮箱地址
+    Email,
+    /// 手机号（中国大陆 11 位手机号及带分隔符的国际格式）
+    Phone,
+    /// 身份证号（18 位居民身份证，校验位验证）及美国 SSN
+    NationalId,
+    /// AWS Access Key ID 及 Secret Access Key 赋值
+    AwsKey,
+    /// PEM 私钥块
+    PrivateKey,
+}
+
+impl PiiDetector {
+    const ALL: [PiiDetector; 5] = [
+        Self::Email,
+        Self::Phone,
+        Self::NationalId,
+        Self::AwsKey,
+        Self::PrivateKey,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Phone => "phone",
+            Self::NationalId => "nationalId",
+            Self::AwsKey => "awsKey",
+            Self::PrivateKey => "privateKey",
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Email => r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+            Self::Phone => r"\b1[3-9]\d{9}\b|(?:\+\d{1,3}[ -]?)?\(?\d{3}\)?[ -]\d{3}[ -]\d{4}\b",
+            Self::NationalId => r"\b\d{17}[\dXx]\b|\b\d{3}-\d{2}-\d{4}\b",
+            Self::AwsKey => {
+                r#"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b|(?i)aws_secret_access_key["']?\s*[:=]\s*["']?[A-Za-z0-9/+=]{40}"#
+            }
+            Self::PrivateKey => {
+                r"-----BEGIN (?:RSA |EC |DSA |OPENSSH |ENCRYPTED |PGP )?PRIVATE KEY(?: BLOCK)?-----"
+            }
+        }
+    }
+}
+
+/// 用户自定义规则
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiCustomRule {
+    pub name: String,
+    /// 正则表达式（Rust regex 语法）
+    pub pattern: String,
+}
+
+/// 扫描目标
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PiiScanTarget {
+    /// 单个文件（压缩包会扫描其中的条目）
+    File { path: String },
+    /// 目录或对象前缀下的所有文件
+    Directory { path: String },
+}
+
+/// 扫描选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiScanOptions {
+    /// 启用的内置检测器，为空时启用全部
+    pub detectors: Option<Vec<PiiDetector>>,
+    pub custom_rules: Option<Vec<PiiCustomRule>>,
+    /// 跳过大于该大小的文件和压缩包条目，默认 16MB
+    pub max_file_size: Option<String>,
+    /// 最多记录的发现数量，默认 10000
+    pub max_findings: Option<u32>,
+    /// 是否扫描压缩包内的条目，默认 true
+    pub scan_archives: Option<bool>,
+    /// 报告中是否保留完整的匹配内容，默认 false（脱敏）
+    pub reveal_matches: Option<bool>,
+}
+
+/// 一处发现
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiFinding {
+    pub path: String,
+    /// 压缩包内的条目路径，普通文件为空
+    pub entry_path: Option<String>,
+    /// 行号和列号（从 1 开始，列号按字符计）
+    pub line: u32,
+    pub column: u32,
+    /// 命中的内置检测器名称或自定义规则名称
+    pub rule: String,
+    /// 匹配内容（默认脱敏）
+    pub excerpt: String,
+}
+
+/// 扫描失败的文件
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiScanFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// 扫描报告
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiScanReport {
+    pub findings: Vec<PiiFinding>,
+    /// 每个规则的发现数量
+    pub counts: HashMap<String, u32>,
+    pub scanned_files: u32,
+    /// 扫描的压缩包条目数
+    pub scanned_entries: u32,
+    /// 因过大或为二进制内容而跳过的文件和条目数
+    pub skipped: u32,
+    pub failures: Vec<PiiScanFailure>,
+    /// 是否因达到发现数量或文件数量上限而未完整扫描
+    pub truncated: bool,
+}
+
+/// 扫描进度
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiScanProgress {
+    pub scanned_files: u32,
+    pub total_files: u32,
+    pub findings: u32,
+}
+
+struct Rule {
+    name: String,
+    regex: Regex,
+    /// 额外校验（如身份证校验位），返回 false 时忽略该匹配
+    validate: Option<fn(&str) -> bool>,
+    /// 是否脱敏，私钥块的标记本身不含敏感内容
+    mask: bool,
+}
+
+/// 敏感信息扫描器
+pub struct PiiScanner {
+    rules: Vec<Rule>,
+    max_file_size: u64,
+    scan_archives: bool,
+    reveal_matches: bool,
+    findings: ResultLimit,
+    scanned_entries: AtomicU32,
+    skipped: AtomicU32,
+}
+
+impl PiiScanner {
+    pub async fn scan<F>(
+        client: Arc<dyn StorageClient>,
+        targets: Vec<PiiScanTarget>,
+        options: &PiiScanOptions,
+        progress: F,
+    ) -> Result<PiiScanReport, String>
+    where
+        F: Fn(PiiScanProgress) + Send + Sync,
+    {
+        let scanner = Self {
+            rules: Self::build_rules(options)?,
+            max_file_size: options
+                .max_file_size
+                .as_ref()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_MAX_FILE_SIZE),
+            scan_archives: options.scan_archives.unwrap_or(true),
+            reveal_matches: options.reveal_matches.unwrap_or(false),
+            findings: ResultLimit::new(options.max_findings.unwrap_or(DEFAULT_MAX_FINDINGS)),
+            scanned_entries: AtomicU32::new(0),
+            skipped: AtomicU32::new(0),
+        };
+
+        // 展开目录目标，已知大小的文件无需再次查询
+        let mut files: Vec<(String, Option<u64>)> = Vec::new();
+        for target in targets {
+            match target {
+                PiiScanTarget::File { path } => files.push((path, None)),
+                PiiScanTarget::Directory { path } => {
+                    let remaining = MAX_SCAN_FILES.saturating_sub(files.len());
+                    let (walked, truncated) = TreeWalker::new(client.clone())
+                        .max_files(remaining)
+                        .collect_files(&path)
+                        .await?;
+                    files.extend(walked.into_iter().map(|f| {
+                        let size = f.size();
+                        (f.path, Some(size))
+                    }));
+                    if truncated {
+                        scanner.findings.mark_truncated();
+                        break;
+                    }
+                }
+            }
+        }
+
+        let total_files = files.len() as u32;
+        let mut results = futures_util::stream::iter(files)
+            .map(|(path, size)| {
+                let client = client.clone();
+                let scanner = &scanner;
+                async move {
+                    let result = scanner.scan_file(client, &path, size).await;
+                    (path, result)
+                }
+            })
+            .buffer_unordered(SCAN_CONCURRENCY);
+
+        let mut findings = Vec::new();
+        let mut failures = Vec::new();
+        let mut scanned_files = 0u32;
+        while let Some((path, result)) = results.next().await {
+            scanned_files += 1;
+            match result {
+                Ok(mut file_findings) => findings.append(&mut file_findings),
+                Err(error) => failures.push(PiiScanFailure { path, error }),
+            }
+            progress(PiiScanProgress {
+                scanned_files,
+                total_files,
+                findings: findings.len() as u32,
+            });
+        }
+
+        let mut counts = HashMap::new();
+        for finding in &findings {
+            *counts.entry(finding.rule.clone()).or_insert(0) += 1;
+        }
+        findings.sort_by(|a, b| {
+            (&a.path, &a.entry_path, a.line, a.column).cmp(&(
+                &b.path,
+                &b.entry_path,
+                b.line,
+                b.column,
+            ))
+        });
+
+        Ok(PiiScanReport {
+            findings,
+            counts,
+            scanned_files,
+            scanned_entries: scanner.scanned_entries.load(Ordering::Relaxed),
+            skipped: scanner.skipped.load(Ordering::Relaxed),
+            failures,
+            truncated: scanner.findings.truncated(),
+        })
+    }
+
+    fn build_rules(options: &PiiScanOptions) -> Result<Vec<Rule>, String> {
+        let detectors = match &options.detectors {
+            Some(detectors) if !detectors.is_empty() => detectors.clone(),
+            _ => PiiDetector::ALL.to_vec(),
+        };
+
+        let mut rules = Vec::new();
+        for detector in detectors {
+            rules.push(Rule {
+                name: detector.name().to_string(),
+                regex: Regex::new(detector.pattern())
+                    .map_err(|e| format!("Invalid built-in pattern: {}", e))?,
+                validate: match detector {
+                    PiiDetector::NationalId => Some(is_valid_national_id),
+                    _ => None,
+                },
+                mask: detector != PiiDetector::PrivateKey,
+            });
+        }
+        for rule in options.custom_rules.iter().flatten() {
+            rules.push(Rule {
+                name: rule.name.clone(),
+                regex: Regex::new(&rule.pattern)
+                    .map_err(|e| format!("Invalid rule '{}': {}", rule.name, e))?,
+                validate: None,
+                mask: true,
+            });
+        }
+        Ok(rules)
+    }
+
+    /// 扫描单个文件，压缩包按条目扫描
+    async fn scan_file(
+        &self,
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        size: Option<u64>,
+    ) -> Result<Vec<PiiFinding>, String> {
+        if self.findings.is_full() {
+            return Ok(Vec::new());
+        }
+
+        let filename = path.rsplit('/').next().unwrap_or(path);
+        if self.scan_archives
+            && CompressionType::from_filename(filename) != CompressionType::Unknown
+        {
+            return self.scan_archive(client, path, filename).await;
+        }
+
+        let size = match size {
+            Some(size) => size,
+            None => client
+                .get_file_size(path)
+                .await
+                .map_err(|e| format!("Failed to get file size: {}", e))?,
+        };
+        if size == 0 || size > self.max_file_size {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return Ok(Vec::new());
+        }
+
+        let data = client
+            .read_file_range(path, 0, size)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        Ok(self.scan_content(path, None, &data))
+    }
+
+    async fn scan_archive(
+        &self,
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        filename: &str,
+    ) -> Result<Vec<PiiFinding>, String> {
+        let handler = ArchiveHandler::new();
+        let info = handler
+            .analyze_archive_with_client(
+                client.clone(),
+                path.to_string(),
+                filename.to_string(),
+                None,
+            )
+            .await?;
+
+        let mut entry_paths = Vec::new();
+        for entry in info.entries.iter().filter(|entry| !entry.is_dir) {
+            let size = entry.size.parse::<u64>().unwrap_or(0);
+            if size == 0 || size > self.max_file_size {
+                self.skipped.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            entry_paths.push(entry.path.clone());
+        }
+
+        // 在一次遍历中读取所有需要扫描的条目，只能顺序解压的格式不会为每个条目从头解压
+        let mut findings = Vec::new();
+        if entry_paths.is_empty() || self.findings.is_full() {
+            return Ok(findings);
+        }
+        let mut scan = handler
+            .scan_entries(
+                client,
+                path.to_string(),
+                filename.to_string(),
+                entry_paths,
+                self.max_file_size,
+            )
+            .await?;
+        while let Some(entry) = scan.next_entry().await {
+            let (entry_path, content) = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::debug!("读取压缩包条目失败 {}: {}", path, e);
+                    break;
+                }
+            };
+            self.scanned_entries.fetch_add(1, Ordering::Relaxed);
+            findings.extend(self.scan_content(path, Some(&entry_path), &content));
+            if self.findings.is_full() {
+                break;
+            }
+        }
+        Ok(findings)
+    }
+
+    /// 逐行匹配所有规则，跳过二进制内容
+    fn scan_content(
+        &self,
+        path: &str,
+        entry_path: Option<&str>,
+        content: &[u8],
+    ) -> Vec<PiiFinding> {
+        if is_binary_content(content) {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return Vec::new();
+        }
+
+        let text = String::from_utf8_lossy(content);
+        let mut findings = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            for rule in &self.rules {
+                for found in rule.regex.find_iter(line) {
+                    let matched = found.as_str();
+                    if rule.validate.is_some_and(|validate| !validate(matched)) {
+                        continue;
+                    }
+                    if !self.findings.claim() {
+                        return findings;
+                    }
+                    findings.push(PiiFinding {
+                        path: path.to_string(),
+                        entry_path: entry_path.map(str::to_string),
+                        line: index as u32 + 1,
+                        column: line[..found.start()].chars().count() as u32 + 1,
+                        rule: rule.name.clone(),
+                        excerpt: if rule.mask && !self.reveal_matches {
+                            mask(matched)
+                        } else {
+                            matched.chars().take(MAX_EXCERPT_CHARS).collect()
+                        },
+                    });
+                }
+            }
+        }
+        findings
+    }
+}
+
+/// 脱敏：只保留首尾各两个字符，较短的内容全部替换
+fn mask(matched: &str) -> String {
+    let chars: Vec<char> = matched.chars().take(MAX_EXCERPT_CHARS).collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let mut masked = String::with_capacity(chars.len());
+    masked.extend(&chars[..2]);
+    masked.push_str(&"*".repeat(chars.len() - 4));
+    masked.extend(&chars[chars.len() - 2..]);
+    masked
+}
+
+/// 18 位居民身份证号按 GB 11643 校验位验证，SSN 格式直接通过
+fn is_valid_national_id(value: &str) -> bool {
+    if value.len() != 18 {
+        return true;
+    }
+    const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+    const CHECK_CODES: &[u8; 11] = b"10X98765432";
+
+    let bytes = value.as_bytes();
+    let sum: u32 = bytes[..17]
+        .iter()
+        .zip(WEIGHTS)
+        .map(|(digit, weight)| (digit - b'0') as u32 * weight)
+        .sum();
+    CHECK_CODES[(sum % 11) as usize] == bytes[17].to_ascii_uppercase()
+}
@@ -0,0 +1,316 @@
+// 快速打开
+// 输入时对最近打开、书签、文件名索引和保存的连接做模糊匹配，返回排序后的结果，
+// 供键盘唤起的快速打开面板逐字调用，需在 50ms 内返回
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::connections;
+use crate::dataset::filename_index::FilenameIndex;
+
+/// 默认最多返回的结果数量
+const DEFAULT_LIMIT: u32 = 50;
+
+/// 结果数量上限
+const MAX_LIMIT: u32 = 500;
+
+/// 从文件名索引中取出参与打分的候选数量
+const INDEX_CANDIDATES: u32 = 2000;
+
+/// 每个匹配字符的基础得分
+const SCORE_MATCH: i32 = 16;
+/// 匹配字符位于单词开头（路径分隔符、下划线、驼峰等之后）
+const BONUS_BOUNDARY: i32 = 12;
+/// 与上一个匹配字符相邻
+const BONUS_CONSECUTIVE: i32 = 16;
+/// 匹配位于文本开头
+const BONUS_FIRST_CHAR: i32 = 8;
+/// 所有关键字都在文件名（而不只是目录）中匹配
+const BONUS_BASENAME: i32 = 24;
+/// 匹配字符之间每跳过一个字符的扣分
+const PENALTY_GAP: i32 = 1;
+/// 第一个匹配字符之前的字符最多扣分
+const MAX_LEADING_PENALTY: i32 = 12;
+
+/// 按来源附加的得分，相同匹配程度时最近打开和书签排在前面
+const SOURCE_BONUS_RECENT: i32 = 20;
+const SOURCE_BONUS_BOOKMARK: i32 = 16;
+const SOURCE_BONUS_CONNECTION: i32 = 8;
+
+/// 最近打开列表中越靠前附加的得分越高，最多附加的得分
+const MAX_RECENCY_BONUS: i32 = 20;
+
+/// 尝试的起始位置数量，避免贪心匹配选中过早出现的首字符
+const MAX_START_POSITIONS: usize = 8;
+
+/// 前端保存的最近打开或书签条目
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickOpenEntry {
+    pub path: String,
+    /// 显示名称，为空时使用路径中的文件名
+    pub name: Option<String>,
+}
+
+/// 快速打开请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickOpenRequest {
+    pub query: String,
+    /// 最近打开的文件，按时间从新到旧
+    #[serde(default)]
+    pub recents: Vec<QuickOpenEntry>,
+    #[serde(default)]
+    pub bookmarks: Vec<QuickOpenEntry>,
+    pub limit: Option<u32>,
+}
+
+/// 结果来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum QuickOpenSource {
+    Recent,
+    Bookmark,
+    /// 当前连接的文件名索引
+    File,
+    /// 保存的连接配置
+    Connection,
+}
+
+/// 一条快速打开结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickOpenItem {
+    pub source: QuickOpenSource,
+    pub title: String,
+    /// 补充信息：文件的完整路径或连接的协议
+    pub detail: Option<String>,
+    /// 文件路径，连接结果为空
+    pub path: Option<String>,
+    /// 连接配置 ID，只有连接结果有值
+    pub connection_id: Option<String>,
+    pub score: i32,
+    /// title 中匹配字符的位置（按字符计），用于高亮
+    pub title_matches: Vec<u32>,
+    /// detail 中匹配字符的位置
+    pub detail_matches: Vec<u32>,
+}
+
+/// 快速打开结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickOpenResult {
+    pub items: Vec<QuickOpenItem>,
+    /// 当前连接是否有可用的文件名索引
+    pub index_available: bool,
+    pub elapsed_ms: u32,
+}
+
+/// 快速打开
+pub struct QuickOpen;
+
+impl QuickOpen {
+    /// 匹配并排序所有来源，connection_key 为空时（未连接）跳过文件名索引
+    pub fn search(
+        request: &QuickOpenRequest,
+        connection_key: Option<&str>,
+    ) -> Result<QuickOpenResult, String> {
+        let started = Instant::now();
+        let tokens = tokenize(&request.query);
+        let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+
+        let mut items = Vec::new();
+        let mut seen_paths = HashSet::new();
+
+        let recent_count = request.recents.len().max(1) as i32;
+        for (index, entry) in request.recents.iter().enumerate() {
+            let recency = MAX_RECENCY_BONUS * (recent_count - index as i32) / recent_count;
+            if let Some(item) = match_entry(entry, QuickOpenSource::Recent, &tokens) {
+                seen_paths.insert(entry.path.clone());
+                items.push(with_bonus(item, SOURCE_BONUS_RECENT + recency));
+            }
+        }
+        for entry in &request.bookmarks {
+            if seen_paths.contains(&entry.path) {
+                continue;
+            }
+            if let Some(item) = match_entry(entry, QuickOpenSource::Bookmark, &tokens) {
+                seen_paths.insert(entry.path.clone());
+                items.push(with_bonus(item, SOURCE_BONUS_BOOKMARK));
+            }
+        }
+
+        for profile in connections::list() {
+            let detail = profile.config.protocol.clone();
+            if let Some((score, title_matches)) = score_text(&profile.name, &tokens) {
+                items.push(QuickOpenItem {
+                    source: QuickOpenSource::Connection,
+                    title: profile.name,
+                    detail: Some(detail),
+                    path: None,
+                    connection_id: Some(profile.id),
+                    score: score + SOURCE_BONUS_CONNECTION,
+                    title_matches,
+                    detail_matches: Vec::new(),
+                });
+            }
+        }
+
+        let mut index_available = false;
+        if let Some(connection_key) = connection_key {
+            index_available = FilenameIndex::status(connection_key)
+                .map(|status| status.built)
+                .unwrap_or(false);
+            // 空查询只列出最近打开、书签和连接，不列出索引中的文件
+            if index_available && !tokens.is_empty() {
+                for file in
+                    FilenameIndex::fuzzy_candidates(connection_key, &tokens, INDEX_CANDIDATES)?
+                {
+                    if seen_paths.contains(&file.path) {
+                        continue;
+                    }
+                    let entry = QuickOpenEntry {
+                        path: file.path,
+                        name: Some(file.name),
+                    };
+                    if let Some(item) = match_entry(&entry, QuickOpenSource::File, &tokens) {
+                        items.push(item);
+                    }
+                }
+            }
+        }
+
+        // 得分相同时标题较短的在前
+        items.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.title.len().cmp(&b.title.len()))
+        });
+        items.truncate(limit);
+        Ok(QuickOpenResult {
+            items,
+            index_available,
+            elapsed_ms: started.elapsed().as_millis() as u32,
+        })
+    }
+}
+
+/// 按空白拆分关键字并转为小写，每个关键字都必须匹配
+fn tokenize(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|token| token.chars().map(fold).collect())
+        .collect()
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn with_bonus(mut item: QuickOpenItem, bonus: i32) -> QuickOpenItem {
+    item.score += bonus;
+    item
+}
+
+/// 匹配文件条目：关键字全部在文件名中匹配时得分更高，否则匹配完整路径
+fn match_entry(
+    entry: &QuickOpenEntry,
+    source: QuickOpenSource,
+    tokens: &[String],
+) -> Option<QuickOpenItem> {
+    let title = entry.name.clone().unwrap_or_else(|| {
+        entry
+            .path
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or(&entry.path)
+            .to_string()
+    });
+    let (score, title_matches, detail_matches) = match score_text(&title, tokens) {
+        Some((score, matches)) => (score + BONUS_BASENAME, matches, Vec::new()),
+        None => {
+            let (score, matches) = score_text(&entry.path, tokens)?;
+            (score, Vec::new(), matches)
+        }
+    };
+    Some(QuickOpenItem {
+        source,
+        title,
+        detail: Some(entry.path.clone()),
+        path: Some(entry.path.clone()),
+        connection_id: None,
+        score,
+        title_matches,
+        detail_matches,
+    })
+}
+
+/// 所有关键字都按字符顺序出现在 text 中时返回总得分和匹配位置
+fn score_text(text: &str, tokens: &[String]) -> Option<(i32, Vec<u32>)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut total = 0;
+    let mut positions = Vec::new();
+    for token in tokens {
+        let query: Vec<char> = token.chars().collect();
+        let (score, matched) = best_match(&query, &chars)?;
+        total += score;
+        positions.extend(matched.into_iter().map(|p| p as u32));
+    }
+    positions.sort_unstable();
+    positions.dedup();
+    // 同样的匹配，较短的文本排在前面
+    total -= (chars.len() as i32).min(64) / 8;
+    Some((total, positions))
+}
+
+/// 从首字符的前几次出现位置分别贪心匹配，取得分最高的一次
+fn best_match(query: &[char], text: &[char]) -> Option<(i32, Vec<usize>)> {
+    let first = *query.first()?;
+    text.iter()
+        .enumerate()
+        .filter(|(_, c)| fold(**c) == first)
+        .take(MAX_START_POSITIONS)
+        .filter_map(|(start, _)| match_from(query, text, start))
+        .max_by_key(|(score, _)| *score)
+}
+
+fn match_from(query: &[char], text: &[char], start: usize) -> Option<(i32, Vec<usize>)> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = -(start as i32).min(MAX_LEADING_PENALTY);
+    let mut next = 0;
+    let mut index = start;
+    while next < query.len() && index < text.len() {
+        if fold(text[index]) == query[next] {
+            score += SCORE_MATCH;
+            if index == 0 {
+                score += BONUS_FIRST_CHAR;
+            }
+            if is_boundary(text, index) {
+                score += BONUS_BOUNDARY;
+            }
+            match positions.last() {
+                Some(&previous) if previous + 1 == index => score += BONUS_CONSECUTIVE,
+                Some(&previous) => score -= (index - previous - 1) as i32 * PENALTY_GAP,
+                None => {}
+            }
+            positions.push(index);
+            next += 1;
+        }
+        index += 1;
+    }
+    (next == query.len()).then_some((score, positions))
+}
+
+/// 字符是否位于单词开头
+fn is_boundary(text: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let previous = text[index - 1];
+    let current = text[index];
+    matches!(previous, '/' | '\\' | '_' | '-' | '.' | ' ')
+        || (previous.is_lowercase() && current.is_uppercase())
+        || (!previous.is_ascii_digit() && current.is_ascii_digit())
+}
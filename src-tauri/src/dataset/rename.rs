@@ -0,0 +1,353 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::path_utils::PathUtils;
+use crate::utils::persist;
+
+/// 批量重命名规则
+/// 各步骤按顺序执行：正则替换 -> 空格替换 -> 小写转换
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameRule {
+    /// 匹配文件名的正则表达式，支持捕获组
+    pub pattern: Option<String>,
+    /// 替换模板，支持 $1 / ${name} 捕获组引用以及 {n} 顺序编号
+    pub replacement: Option<String>,
+    /// 是否转换为小写
+    pub lowercase: bool,
+    /// 将空白字符替换为指定字符串（如 "_"）
+    pub replace_spaces: Option<String>,
+    /// 顺序编号起始值，默认 1
+    pub sequence_start: Option<u32>,
+    /// 顺序编号补零宽度，默认不补零
+    pub sequence_padding: Option<u32>,
+    /// 是否同时重命名子目录
+    pub include_directories: bool,
+}
+
+/// 单个文件的重命名计划
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePlanItem {
+    pub original: String,
+    pub renamed: String,
+    pub changed: bool,
+    /// 冲突原因，None 表示可以安全重命名
+    pub conflict: Option<String>,
+}
+
+/// 重命名预览结果（dry-run）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePlan {
+    pub directory: String,
+    pub items: Vec<RenamePlanItem>,
+    pub changed_count: u32,
+    pub conflict_count: u32,
+}
+
+/// 重命名执行结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameResult {
+    /// 撤销日志 ID，用于 dataset_rename_undo
+    pub journal_id: String,
+    pub renamed_count: u32,
+}
+
+/// 撤销结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameUndoResult {
+    pub restored_count: u32,
+    /// 未能恢复的文件（当前名称已不存在或原始名称已被占用），保留在撤销日志中可以再次撤销
+    pub skipped: Vec<String>,
+}
+
+/// 撤销日志，记录每次批量重命名的映射关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RenameJournal {
+    id: String,
+    directory: String,
+    created_at: String,
+    entries: Vec<(String, String)>,
+}
+
+/// 批量重命名工具
+pub struct BatchRenamer;
+
+impl BatchRenamer {
+    /// 生成重命名计划，不修改任何文件
+    pub fn preview(directory: &str, rule: &RenameRule) -> Result<RenamePlan, String> {
+        let dir_path = Self::resolve_directory(directory)?;
+        let regex = match &rule.pattern {
+            Some(pattern) if !pattern.is_empty() => {
+                Some(Regex::new(pattern).map_err(|e| format!("Invalid pattern: {}", e))?)
+            }
+            _ => None,
+        };
+
+        let mut names = Vec::new();
+        for entry in
+            std::fs::read_dir(&dir_path).map_err(|e| format!("Failed to read directory: {}", e))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            if is_dir && !rule.include_directories {
+                continue;
+            }
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+        // 排序保证顺序编号稳定
+        names.sort();
+
+        let existing: HashSet<String> = names.iter().cloned().collect();
+        let mut sequence = rule.sequence_start.unwrap_or(1);
+        let mut items = Vec::with_capacity(names.len());
+
+        for original in names {
+            let renamed = Self::apply_rule(&original, rule, regex.as_ref(), sequence);
+            if regex.as_ref().is_none_or(|re| re.is_match(&original)) {
+                sequence += 1;
+            }
+            let changed = renamed != original;
+            items.push(RenamePlanItem {
+                original,
+                renamed,
+                changed,
+                conflict: None,
+            });
+        }
+
+        Self::detect_conflicts(&mut items, &existing, &dir_path);
+
+        let changed_count = items.iter().filter(|item| item.changed).count() as u32;
+        let conflict_count = items.iter().filter(|item| item.conflict.is_some()).count() as u32;
+
+        Ok(RenamePlan {
+            directory: dir_path.to_string_lossy().to_string(),
+            items,
+            changed_count,
+            conflict_count,
+        })
+    }
+
+    /// 执行批量重命名并写入撤销日志
+    pub fn apply(directory: &str, rule: &RenameRule) -> Result<RenameResult, String> {
+        let plan = Self::preview(directory, rule)?;
+        if plan.conflict_count > 0 {
+            return Err(format!(
+                "Rename plan has {} conflicts, resolve them before applying",
+                plan.conflict_count
+            ));
+        }
+
+        let entries: Vec<(String, String)> = plan
+            .items
+            .into_iter()
+            .filter(|item| item.changed)
+            .map(|item| (item.original, item.renamed))
+            .collect();
+
+        // 先写撤销日志再改名，改名中途退出时仍可以根据日志找回原始文件名
+        let dir_path = PathBuf::from(&plan.directory);
+        let journal = RenameJournal {
+            id: uuid::Uuid::new_v4().to_string(),
+            directory: plan.directory,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            entries,
+        };
+        Self::save_journal(&journal)?;
+
+        if let Err(e) = Self::rename_two_phase(&dir_path, &journal.entries) {
+            // 改名失败时已经回滚，日志不再需要
+            if let Ok(path) = Self::journal_path(&journal.id) {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(e);
+        }
+
+        Ok(RenameResult {
+            journal_id: journal.id,
+            renamed_count: journal.entries.len() as u32,
+        })
+    }
+
+    /// 根据撤销日志恢复原始文件名
+    /// 只恢复当前名称仍然存在、原始名称没有被其他文件占用的条目，其余条目跳过并保留在日志中
+    pub fn undo(journal_id: &str) -> Result<RenameUndoResult, String> {
+        let journal_path = Self::journal_path(journal_id)?;
+        let content = std::fs::read_to_string(&journal_path)
+            .map_err(|e| format!("Failed to read rename journal: {}", e))?;
+        let mut journal: RenameJournal = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse rename journal: {}", e))?;
+
+        let dir_path = PathBuf::from(&journal.directory);
+        let (restorable, skipped) = Self::restorable_entries(&dir_path, &journal.entries);
+
+        let reversed: Vec<(String, String)> = restorable
+            .iter()
+            .map(|(from, to)| (to.clone(), from.clone()))
+            .collect();
+        Self::rename_two_phase(&dir_path, &reversed)?;
+
+        if skipped.is_empty() {
+            let _ = std::fs::remove_file(&journal_path);
+        } else {
+            journal.entries = skipped.clone();
+            Self::save_journal(&journal)?;
+        }
+
+        Ok(RenameUndoResult {
+            restored_count: reversed.len() as u32,
+            skipped: skipped.into_iter().map(|(_, to)| to).collect(),
+        })
+    }
+
+    /// 将日志条目分为可以恢复的和需要跳过的
+    /// 原始名称被同一次撤销中会移走的文件占用（如 a->b、b->a 互换）时仍可恢复；
+    /// 跳过的条目不会移走，可能使依赖其名称的其他条目也无法恢复，因此反复筛选直到稳定
+    fn restorable_entries(
+        dir: &Path,
+        entries: &[(String, String)],
+    ) -> (Vec<(String, String)>, Vec<(String, String)>) {
+        let (mut restorable, mut skipped): (Vec<_>, Vec<_>) = entries
+            .iter()
+            .cloned()
+            .partition(|(_, to)| dir.join(to).exists());
+
+        loop {
+            let vacated: HashSet<String> =
+                restorable.iter().map(|(_, to)| to.to_lowercase()).collect();
+            let (keep, blocked): (Vec<_>, Vec<_>) =
+                restorable.into_iter().partition(|(from, _)| {
+                    vacated.contains(&from.to_lowercase()) || !dir.join(from).exists()
+                });
+            restorable = keep;
+            if blocked.is_empty() {
+                break;
+            }
+            skipped.extend(blocked);
+        }
+
+        (restorable, skipped)
+    }
+
+    /// 对单个文件名应用规则
+    fn apply_rule(name: &str, rule: &RenameRule, regex: Option<&Regex>, sequence: u32) -> String {
+        let mut result = name.to_string();
+
+        if let (Some(re), Some(replacement)) = (regex, &rule.replacement) {
+            if re.is_match(name) {
+                let width = rule.sequence_padding.unwrap_or(0) as usize;
+                let numbered = replacement.replace("{n}", &format!("{:0width$}", sequence));
+                result = re.replace_all(name, numbered.as_str()).to_string();
+            }
+        }
+
+        if let Some(separator) = &rule.replace_spaces {
+            result = result
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(separator);
+        }
+
+        if rule.lowercase {
+            result = result.to_lowercase();
+        }
+
+        result
+    }
+
+    /// 检测重命名冲突：非法名称、重复目标、与未改名文件同名
+    /// 名称按不区分大小写比较，避免在大小写不敏感的文件系统（Windows、macOS）上互相覆盖；
+    /// 只改变大小写的重命名（A.txt -> a.txt）不算冲突
+    fn detect_conflicts(items: &mut [RenamePlanItem], existing: &HashSet<String>, dir: &Path) {
+        let unchanged: HashSet<String> = items
+            .iter()
+            .filter(|item| !item.changed)
+            .map(|item| item.original.to_lowercase())
+            .collect();
+        let existing: HashSet<String> = existing.iter().map(|name| name.to_lowercase()).collect();
+
+        let mut seen = HashSet::new();
+        for item in items.iter_mut().filter(|item| item.changed) {
+            let target = item.renamed.to_lowercase();
+            if item.renamed.is_empty() || item.renamed.contains(['/', '\\']) {
+                item.conflict = Some("invalid.name".to_string());
+            } else if !seen.insert(target.clone()) {
+                item.conflict = Some("duplicate.target".to_string());
+            } else if unchanged.contains(&target)
+                || (!existing.contains(&target) && dir.join(&item.renamed).exists())
+            {
+                // 目标名与不参与重命名的文件冲突（含被过滤掉的目录）
+                item.conflict = Some("target.exists".to_string());
+            }
+        }
+    }
+
+    /// 两阶段重命名：先移动到临时名称，再移动到目标名称
+    /// 避免 a->b、b->a 互换或大小写不敏感文件系统上的冲突
+    fn rename_two_phase(dir: &Path, entries: &[(String, String)]) -> Result<(), String> {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let mut staged = Vec::with_capacity(entries.len());
+
+        for (index, (from, to)) in entries.iter().enumerate() {
+            let temp_name = format!(".rename-{}-{}", token, index);
+            if let Err(e) = std::fs::rename(dir.join(from), dir.join(&temp_name)) {
+                // 回滚已经移动的文件
+                for (original, temp, _) in staged.iter().rev() {
+                    let _ = std::fs::rename(dir.join(temp), dir.join(original));
+                }
+                return Err(format!("Failed to rename {}: {}", from, e));
+            }
+            staged.push((from.clone(), temp_name, to.clone()));
+        }
+
+        for (index, (_, temp, to)) in staged.iter().enumerate() {
+            if let Err(e) = std::fs::rename(dir.join(temp), dir.join(to)) {
+                // 回滚：已改名的文件先移回临时名称（原始名称可能被其他目标占用），再全部恢复原始名称
+                for (_, temp, to) in staged[..index].iter().rev() {
+                    let _ = std::fs::rename(dir.join(to), dir.join(temp));
+                }
+                for (original, temp, _) in staged.iter().rev() {
+                    let _ = std::fs::rename(dir.join(temp), dir.join(original));
+                }
+                return Err(format!("Failed to rename to {}: {}", to, e));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_directory(directory: &str) -> Result<PathBuf, String> {
+        let path = directory.strip_prefix("local://").unwrap_or(directory);
+        let path = PathBuf::from(PathUtils::expand_home_dir(path).map_err(|e| e.to_string())?);
+        if !path.is_dir() {
+            return Err(format!("Not a directory: {}", path.display()));
+        }
+        Ok(path)
+    }
+
+    fn save_journal(journal: &RenameJournal) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(journal)
+            .map_err(|e| format!("Failed to serialize rename journal: {}", e))?;
+        persist::write_atomic(Self::journal_path(&journal.id)?, content)
+            .map_err(|e| format!("Failed to write rename journal: {}", e))
+    }
+
+    fn journal_path(journal_id: &str) -> Result<PathBuf, String> {
+        // 日志 ID 是 UUID，拒绝任何路径字符
+        if journal_id.is_empty()
+            || !journal_id
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == '-')
+        {
+            return Err(format!("Invalid journal id: {}", journal_id));
+        }
+        Ok(get_app_data_subdir("rename-journals")?.join(format!("{}.json", journal_id)))
+    }
+}
@@ -0,0 +1,198 @@
+// 搜索历史与收藏的搜索
+// 记录每次执行的搜索（目标、条件、结果数量、时间），相同的搜索只保留一条并更新时间和结果数量；
+// 收藏的搜索不会被清理，便于一键重新执行例行检查
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+use crate::archive::search::ArchiveSearchOptions;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 状态文件名
+const STATE_FILE: &str = "search-history.json";
+
+/// 每个连接最多保留的未收藏历史数量
+const MAX_HISTORY_PER_CONNECTION: usize = 200;
+
+static SEARCH_HISTORY: LazyLock<Mutex<Option<HistoryState>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 搜索目标及条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SearchTarget {
+    /// 在多个压缩包中搜索条目路径或内容
+    Archives {
+        paths: Vec<String>,
+        options: ArchiveSearchOptions,
+    },
+    /// 在文件名索引中搜索
+    Filenames { limit: Option<u32> },
+    /// 在内容索引中搜索
+    Contents { limit: Option<u32> },
+}
+
+/// 一条搜索记录
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRecord {
+    pub id: String,
+    pub query: String,
+    pub target: SearchTarget,
+    /// 最近一次执行的结果数量
+    pub result_count: u32,
+    /// 最近一次执行的时间（RFC3339）
+    pub executed_at: String,
+    pub pinned: bool,
+    /// 收藏时的名称
+    pub name: Option<String>,
+    /// 执行搜索时的连接标识，只在同一连接下列出
+    pub connection_key: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryState {
+    records: Vec<SearchRecord>,
+}
+
+/// 记录一次执行的搜索
+pub fn record(connection_key: &str, query: &str, target: SearchTarget, result_count: u32) {
+    let executed_at = chrono::Utc::now().to_rfc3339();
+    update(|state| {
+        let existing = state.records.iter_mut().find(|record| {
+            record.connection_key == connection_key
+                && record.query == query
+                && record.target == target
+        });
+        if let Some(record) = existing {
+            record.result_count = result_count;
+            record.executed_at = executed_at;
+            return;
+        }
+
+        state.records.push(SearchRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            query: query.to_string(),
+            target,
+            result_count,
+            executed_at,
+            pinned: false,
+            name: None,
+            connection_key: connection_key.to_string(),
+        });
+        state.prune(connection_key);
+    });
+}
+
+/// 列出连接的搜索记录，收藏的在前，其余按执行时间从新到旧
+pub fn list(connection_key: &str) -> Vec<SearchRecord> {
+    let Ok(mut guard) = SEARCH_HISTORY.lock() else {
+        return Vec::new();
+    };
+    let mut records: Vec<SearchRecord> = guard
+        .get_or_insert_with(HistoryState::load)
+        .records
+        .iter()
+        .filter(|record| record.connection_key == connection_key)
+        .cloned()
+        .collect();
+    records.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.executed_at.cmp(&a.executed_at))
+    });
+    records
+}
+
+/// 获取搜索记录
+pub fn get(id: &str) -> Option<SearchRecord> {
+    let mut guard = SEARCH_HISTORY.lock().ok()?;
+    guard
+        .get_or_insert_with(HistoryState::load)
+        .records
+        .iter()
+        .find(|record| record.id == id)
+        .cloned()
+}
+
+/// 收藏或取消收藏搜索，收藏时可指定名称
+pub fn pin(id: &str, pinned: bool, name: Option<String>) -> Result<SearchRecord, String> {
+    update(|state| {
+        let record = state
+            .records
+            .iter_mut()
+            .find(|record| record.id == id)
+            .ok_or_else(|| format!("Search not found: {}", id))?;
+        record.pinned = pinned;
+        if name.is_some() || !pinned {
+            record.name = name;
+        }
+        Ok(record.clone())
+    })
+    .unwrap_or_else(|| Err("Failed to access search history".to_string()))
+}
+
+/// 删除搜索记录
+pub fn remove(id: &str) {
+    update(|state| state.records.retain(|record| record.id != id));
+}
+
+/// 清除连接的搜索历史，保留收藏的搜索
+pub fn clear(connection_key: &str) {
+    update(|state| {
+        state
+            .records
+            .retain(|record| record.pinned || record.connection_key != connection_key)
+    });
+}
+
+fn update<T>(f: impl FnOnce(&mut HistoryState) -> T) -> Option<T> {
+    let mut guard = SEARCH_HISTORY.lock().ok()?;
+    let state = guard.get_or_insert_with(HistoryState::load);
+    let result = f(state);
+    state.save();
+    Some(result)
+}
+
+impl HistoryState {
+    /// 超出数量上限时移除该连接最早执行的未收藏记录
+    fn prune(&mut self, connection_key: &str) {
+        let mut history: Vec<(String, String)> = self
+            .records
+            .iter()
+            .filter(|record| !record.pinned && record.connection_key == connection_key)
+            .map(|record| (record.executed_at.clone(), record.id.clone()))
+            .collect();
+        if history.len() <= MAX_HISTORY_PER_CONNECTION {
+            return;
+        }
+        history.sort();
+        let excess = history.len() - MAX_HISTORY_PER_CONNECTION;
+        let stale: Vec<String> = history.into_iter().take(excess).map(|(_, id)| id).collect();
+        self.records.retain(|record| !stale.contains(&record.id));
+    }
+
+    fn state_path() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("search")?.join(STATE_FILE))
+    }
+
+    fn load() -> Self {
+        Self::state_path()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = Self::state_path().and_then(|path| {
+            let data = serde_json::to_vec(self)
+                .map_err(|e| format!("Failed to serialize search history: {}", e))?;
+            persist::write_atomic(path, data)
+                .map_err(|e| format!("Failed to write search history: {}", e))
+        });
+        if let Err(e) = result {
+            log::warn!("保存搜索历史失败: {}", e);
+        }
+    }
+}
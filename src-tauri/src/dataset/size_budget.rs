@@ -0,0 +1,284 @@
+// 数据集容量预算
+// 为连接下的目录（前缀）设置容量阈值，后台定时统计目录总大小，超过阈值时发送提醒事件，
+// 适用于有存储配额的共享存储桶
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::broadcast;
+
+use crate::dataset::stats::DirectoryStatsCalculator;
+use crate::storage::get_storage_manager;
+use crate::storage::traits::StorageClient;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 状态文件名
+const STATE_FILE: &str = "size-budgets.json";
+
+/// 启动后首次检查的延迟，避免与启动时的其他任务争抢网络
+const INITIAL_DELAY: Duration = Duration::from_secs(120);
+
+/// 检查到期预算的间隔，每个预算按自己的检查间隔执行
+const SCHEDULER_TICK: Duration = Duration::from_secs(5 * 60);
+
+/// 默认检查间隔（分钟）
+const DEFAULT_CHECK_INTERVAL_MINUTES: u32 = 60;
+
+/// 最短检查间隔（分钟），避免频繁遍历大型存储桶
+const MIN_CHECK_INTERVAL_MINUTES: u32 = 10;
+
+/// 超过预算时发送的事件
+pub const ALERT_EVENT: &str = "dataset-budget-alert";
+
+static BUDGETS: LazyLock<Mutex<Option<BudgetState>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 容量预算
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBudget {
+    pub id: String,
+    /// 统计的目录，空字符串为连接根目录
+    pub prefix: String,
+    pub threshold_bytes: String, // 使用字符串表示大数字
+    /// 后台检查间隔（分钟）
+    pub check_interval_minutes: u32,
+    /// 最近一次检查结果
+    pub last_check: Option<SizeBudgetCheck>,
+    /// 预算所属的连接标识，只在该连接下检查和列出
+    pub connection_key: String,
+}
+
+/// 保存容量预算的请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBudgetInput {
+    /// 为空时新建，否则更新已有预算
+    pub id: Option<String>,
+    pub prefix: String,
+    pub threshold_bytes: String, // 使用字符串表示大数字
+    pub check_interval_minutes: Option<u32>,
+}
+
+/// 一次检查的结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBudgetCheck {
+    pub total_bytes: String, // 使用字符串表示大数字
+    pub file_count: String,
+    /// 是否超过阈值
+    pub exceeded: bool,
+    /// 是否因文件过多只统计了部分（此时总大小为下限）
+    pub truncated: bool,
+    /// 检查时间（RFC3339）
+    pub checked_at: String,
+}
+
+/// 超过预算的提醒
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBudgetAlert {
+    pub budget: SizeBudget,
+    pub check: SizeBudgetCheck,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BudgetState {
+    budgets: Vec<SizeBudget>,
+}
+
+impl BudgetState {
+    fn state_path() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join(STATE_FILE))
+    }
+
+    fn load() -> Self {
+        Self::state_path()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = Self::state_path().and_then(|path| {
+            let data = serde_json::to_vec_pretty(self)
+                .map_err(|e| format!("Failed to serialize size budgets: {}", e))?;
+            persist::write_atomic(path, data)
+                .map_err(|e| format!("Failed to write size budgets: {}", e))
+        });
+        if let Err(e) = result {
+            log::warn!("保存容量预算失败: {}", e);
+        }
+    }
+}
+
+fn with_state<T>(f: impl FnOnce(&mut BudgetState) -> T) -> Option<T> {
+    let mut guard = BUDGETS.lock().ok()?;
+    Some(f(guard.get_or_insert_with(BudgetState::load)))
+}
+
+fn update<T>(f: impl FnOnce(&mut BudgetState) -> T) -> Option<T> {
+    with_state(|state| {
+        let result = f(state);
+        state.save();
+        result
+    })
+}
+
+/// 列出连接的容量预算
+pub fn list(connection_key: &str) -> Vec<SizeBudget> {
+    with_state(|state| {
+        state
+            .budgets
+            .iter()
+            .filter(|budget| budget.connection_key == connection_key)
+            .cloned()
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// 新建或更新容量预算
+pub fn save(connection_key: &str, input: SizeBudgetInput) -> Result<SizeBudget, String> {
+    let threshold = input
+        .threshold_bytes
+        .parse::<u64>()
+        .ok()
+        .filter(|threshold| *threshold > 0)
+        .ok_or_else(|| format!("Invalid size threshold: {}", input.threshold_bytes))?;
+    let check_interval_minutes = input
+        .check_interval_minutes
+        .unwrap_or(DEFAULT_CHECK_INTERVAL_MINUTES)
+        .max(MIN_CHECK_INTERVAL_MINUTES);
+
+    update(|state| {
+        if let Some(id) = &input.id {
+            let budget = state
+                .budgets
+                .iter_mut()
+                .find(|budget| &budget.id == id && budget.connection_key == connection_key)
+                .ok_or_else(|| format!("Size budget not found: {}", id))?;
+            // 目录或阈值变化后旧的检查结果不再适用
+            if budget.prefix != input.prefix || budget.threshold_bytes != threshold.to_string() {
+                budget.last_check = None;
+            }
+            budget.prefix = input.prefix;
+            budget.threshold_bytes = threshold.to_string();
+            budget.check_interval_minutes = check_interval_minutes;
+            return Ok(budget.clone());
+        }
+
+        let budget = SizeBudget {
+            id: uuid::Uuid::new_v4().to_string(),
+            prefix: input.prefix,
+            threshold_bytes: threshold.to_string(),
+            check_interval_minutes,
+            last_check: None,
+            connection_key: connection_key.to_string(),
+        };
+        state.budgets.push(budget.clone());
+        Ok(budget)
+    })
+    .unwrap_or_else(|| Err("Failed to access size budgets".to_string()))
+}
+
+/// 删除容量预算
+pub fn remove(id: &str) {
+    update(|state| state.budgets.retain(|budget| budget.id != id));
+}
+
+/// 获取容量预算
+pub fn get(id: &str) -> Option<SizeBudget> {
+    with_state(|state| state.budgets.iter().find(|budget| budget.id == id).cloned()).flatten()
+}
+
+/// 统计预算目录的总大小并记录结果，从未超过变为超过时返回提醒
+pub async fn check(
+    client: Arc<dyn StorageClient>,
+    budget: &SizeBudget,
+) -> Result<(SizeBudgetCheck, Option<SizeBudgetAlert>), String> {
+    let threshold = budget.threshold_bytes.parse::<u64>().unwrap_or(u64::MAX);
+    // 与目录统计使用同一套统计，两处报告的总大小一致；后台检查不可取消，发送端保留到统计结束
+    let (_cancel_tx, mut cancel_rx) = broadcast::channel(1);
+    let stats =
+        DirectoryStatsCalculator::compute(client, &budget.prefix, &mut cancel_rx, |_| {}).await?;
+    let total_bytes = stats.total_bytes.parse::<u64>().unwrap_or(0);
+
+    let check = SizeBudgetCheck {
+        exceeded: total_bytes > threshold,
+        total_bytes: stats.total_bytes,
+        file_count: stats.file_count,
+        truncated: stats.truncated,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let updated = update(|state| {
+        let stored = state.budgets.iter_mut().find(|b| b.id == budget.id)?;
+        let was_exceeded = stored.last_check.as_ref().is_some_and(|c| c.exceeded);
+        stored.last_check = Some(check.clone());
+        Some((stored.clone(), was_exceeded))
+    })
+    .flatten();
+
+    // 预算已被删除时不再提醒；持续超出时只在首次超出时提醒
+    let alert = match updated {
+        Some((budget, false)) if check.exceeded => Some(SizeBudgetAlert {
+            budget,
+            check: check.clone(),
+        }),
+        _ => None,
+    };
+    Ok((check, alert))
+}
+
+/// 发送超过预算的提醒事件
+pub fn emit_alert(app: &tauri::AppHandle, alert: &SizeBudgetAlert) {
+    if let Err(e) = app.emit(ALERT_EVENT, alert) {
+        log::warn!("发送容量预算提醒失败: {}", e);
+    }
+}
+
+/// 启动后台定时检查，只检查当前连接下到期的预算
+pub fn start_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            let (client, connection_key) = {
+                let manager_arc = get_storage_manager().await;
+                let manager = manager_arc.read().await;
+                (
+                    manager.get_current_client(),
+                    manager.get_current_connection_key(),
+                )
+            };
+            let (Some(client), Some(connection_key)) = (client, connection_key) else {
+                continue;
+            };
+
+            for budget in list(&connection_key).into_iter().filter(is_due) {
+                match check(client.clone(), &budget).await {
+                    Ok((_, Some(alert))) => emit_alert(&app, &alert),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("检查容量预算 {} 失败: {}", budget.prefix, e),
+                }
+            }
+        }
+    });
+}
+
+/// 距上次检查已超过检查间隔
+fn is_due(budget: &SizeBudget) -> bool {
+    let Some(last_check) = &budget.last_check else {
+        return true;
+    };
+    chrono::DateTime::parse_from_rfc3339(&last_check.checked_at)
+        .map(|checked_at| {
+            let elapsed = chrono::Utc::now().signed_duration_since(checked_at);
+            elapsed.num_minutes() >= budget.check_interval_minutes as i64
+        })
+        .unwrap_or(true)
+}
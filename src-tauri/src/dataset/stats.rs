@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::dataset::facets::{ExtensionTallies, FacetCount, Tally};
+use crate::dataset::walker::TreeWalker;
+use crate::storage::traits::StorageClient;
+
+/// 最多统计的文件数量，超过后结果标记为截断
+const MAX_STATS_FILES: usize = 10_000_000;
+
+/// 每遍历多少个条目发送一次进度
+const PROGRESS_INTERVAL: u64 = 1000;
+
+/// 目录统计结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryStats {
+    pub path: String,
+    pub total_bytes: String,     // 使用字符串表示大数字
+    pub file_count: String,      // 使用字符串表示大数字
+    pub directory_count: String, // 使用字符串表示大数字
+    /// 按数量降序排列，无扩展名的文件键为空字符串
+    pub extensions: Vec<FacetCount>,
+    /// 是否因文件过多而只统计了部分
+    pub truncated: bool,
+}
+
+/// 目录统计进度事件
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryStatsProgress {
+    pub path: String,
+    pub total_bytes: String,     // 使用字符串表示大数字
+    pub file_count: String,      // 使用字符串表示大数字
+    pub directory_count: String, // 使用字符串表示大数字
+}
+
+/// 递归统计目录的总大小、文件数量和扩展名分布
+/// 流式遍历并累计，自动处理分页，不在内存中保留条目
+pub struct DirectoryStatsCalculator;
+
+impl DirectoryStatsCalculator {
+    pub async fn compute<F>(
+        client: Arc<dyn StorageClient>,
+        root: &str,
+        cancel_rx: &mut broadcast::Receiver<()>,
+        mut on_progress: F,
+    ) -> Result<DirectoryStats, String>
+    where
+        F: FnMut(&DirectoryStatsProgress),
+    {
+        let walker = TreeWalker::new(client).max_files(MAX_STATS_FILES);
+
+        let mut total = Tally::default();
+        let mut directory_count = 0u64;
+        let mut extensions = ExtensionTallies::default();
+
+        let walk = walker.walk(root, |entry| {
+            if entry.is_directory() {
+                directory_count += 1;
+            } else {
+                let size = entry.size();
+                total.add(size);
+                extensions.add(&entry.file.basename, size);
+            }
+
+            if (total.count + directory_count) % PROGRESS_INTERVAL == 0 {
+                on_progress(&DirectoryStatsProgress {
+                    path: root.to_string(),
+                    total_bytes: total.bytes.to_string(),
+                    file_count: total.count.to_string(),
+                    directory_count: directory_count.to_string(),
+                });
+            }
+            true
+        });
+
+        // 取消时放弃遍历，列目录请求进行中也会立即返回
+        let truncated = tokio::select! {
+            result = walk => result?,
+            _ = cancel_rx.recv() => return Err("stats.cancelled".to_string()),
+        };
+
+        Ok(DirectoryStats {
+            path: root.to_string(),
+            total_bytes: total.bytes.to_string(),
+            file_count: total.count.to_string(),
+            directory_count: directory_count.to_string(),
+            extensions: extensions.into_facets(),
+            truncated,
+        })
+    }
+}
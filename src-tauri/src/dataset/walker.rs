@@ -0,0 +1,279 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::dataset::ignore_rules::IgnoreRules;
+use crate::storage::traits::{DirectoryResult, ListOptions, StorageClient, StorageFile};
+use crate::storage::visibility::VisibilitySettings;
+
+/// 遍历得到的文件条目
+#[derive(Debug, Clone)]
+pub struct WalkedFile {
+    /// 可直接传给 StorageClient 的完整路径
+    pub path: String,
+    pub file: StorageFile,
+    /// 相对根目录的层级，根目录下的直接子项为 1
+    pub depth: u32,
+}
+
+impl WalkedFile {
+    pub fn size(&self) -> u64 {
+        self.file.size.parse::<u64>().unwrap_or(0)
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.file.file_type == "directory"
+    }
+}
+
+/// 基于 StorageClient 的目录树遍历器
+/// 广度优先遍历，自动处理分页，适用于所有存储后端
+pub struct TreeWalker {
+    client: Arc<dyn StorageClient>,
+    /// 最多收集的文件数量，防止超大目录树耗尽内存
+    max_files: usize,
+    /// 最大遍历深度，None 表示不限制
+    max_depth: Option<u32>,
+    /// gitignore 风格的忽略规则，被忽略的目录不再向下遍历
+    ignore_rules: Vec<IgnoreRules>,
+}
+
+impl TreeWalker {
+    pub fn new(client: Arc<dyn StorageClient>) -> Self {
+        Self {
+            client,
+            max_files: 100_000,
+            max_depth: None,
+            ignore_rules: Vec::new(),
+        }
+    }
+
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: Option<u32>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn ignore_rules(mut self, rules: Vec<IgnoreRules>) -> Self {
+        self.ignore_rules = rules;
+        self
+    }
+
+    /// 收集根目录下的所有文件（不含目录条目）
+    /// 返回 (文件列表, 是否因达到上限而截断)
+    pub async fn collect_files(&self, root: &str) -> Result<(Vec<WalkedFile>, bool), String> {
+        let mut files = Vec::new();
+        let truncated = self
+            .walk(root, |entry| {
+                if !entry.is_directory() {
+                    files.push(entry);
+                }
+                true
+            })
+            .await?;
+        Ok((files, truncated))
+    }
+
+    /// 遍历目录树，对每个条目（文件和目录）调用 visit
+    /// visit 返回 false 时停止遍历；返回值表示遍历是否被提前终止
+    pub async fn walk<F>(&self, root: &str, mut visit: F) -> Result<bool, String>
+    where
+        F: FnMut(WalkedFile) -> bool,
+    {
+        let mut queue = VecDeque::from([(root.to_string(), 0u32)]);
+        let mut visited_files = 0usize;
+        let visibility = VisibilitySettings::current();
+
+        while let Some((dir, depth)) = queue.pop_front() {
+            let mut marker: Option<String> = None;
+
+            loop {
+                let result = self.list_page(&dir, marker.clone()).await?;
+
+                for file in result.files {
+                    let Some(entry) = self.accept(&dir, depth, file, &visibility) else {
+                        continue;
+                    };
+
+                    if entry.is_directory() {
+                        if self.max_depth.is_none_or(|max| depth < max) {
+                            queue.push_back((entry.path.clone(), depth + 1));
+                        }
+                    } else {
+                        visited_files += 1;
+                    }
+
+                    if !visit(entry) || visited_files >= self.max_files {
+                        return Ok(true);
+                    }
+                }
+
+                if !result.has_more || result.next_marker.is_none() {
+                    break;
+                }
+                marker = result.next_marker;
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// 分页遍历目录树，每次最多返回 page_size 个条目
+    /// 游标记录了遍历队列和当前目录的分页位置，不在后端保存状态；返回的游标为 None 表示遍历结束
+    pub async fn walk_page(
+        &self,
+        root: &str,
+        cursor: Option<&str>,
+        page_size: usize,
+    ) -> Result<(Vec<WalkedFile>, Option<String>), String> {
+        let mut state = match cursor {
+            Some(cursor) => WalkCursor::decode(cursor, root)?,
+            None => WalkCursor {
+                root: root.to_string(),
+                queue: VecDeque::from([(root.to_string(), 0u32)]),
+                marker: None,
+                skip: 0,
+            },
+        };
+        let visibility = VisibilitySettings::current();
+        let mut entries = Vec::new();
+
+        while let Some((dir, depth)) = state.queue.front().cloned() {
+            let result = self.list_page(&dir, state.marker.clone()).await?;
+
+            // 跳过当前分页中上次已经处理过的条目，其子目录已在队列中
+            for (index, file) in result.files.into_iter().enumerate().skip(state.skip) {
+                let Some(entry) = self.accept(&dir, depth, file, &visibility) else {
+                    continue;
+                };
+
+                if entry.is_directory() && self.max_depth.is_none_or(|max| depth < max) {
+                    state.queue.push_back((entry.path.clone(), depth + 1));
+                }
+                entries.push(entry);
+
+                if entries.len() >= page_size {
+                    state.skip = index + 1;
+                    return Ok((entries, Some(state.encode()?)));
+                }
+            }
+
+            state.skip = 0;
+            if result.has_more && result.next_marker.is_some() {
+                state.marker = result.next_marker;
+            } else {
+                state.queue.pop_front();
+                state.marker = None;
+            }
+        }
+
+        Ok((entries, None))
+    }
+
+    async fn list_page(
+        &self,
+        dir: &str,
+        marker: Option<String>,
+    ) -> Result<DirectoryResult, String> {
+        let options = ListOptions {
+            page_size: Some(1000),
+            marker,
+            prefix: None,
+            recursive: Some(false),
+            sort_by: None,
+            sort_order: None,
+            natural_sort: None,
+            revision: None,
+        };
+
+        self.client
+            .list_directory(dir, Some(&options))
+            .await
+            .map_err(|e| format!("Failed to list {}: {}", dir, e))
+    }
+
+    /// 过滤隐藏或被忽略的条目，这类目录也不再向下遍历
+    fn accept(
+        &self,
+        dir: &str,
+        depth: u32,
+        mut file: StorageFile,
+        visibility: &VisibilitySettings,
+    ) -> Option<WalkedFile> {
+        file.normalize_lastmod();
+
+        let is_directory = file.file_type == "directory";
+        if !visibility.is_visible(&file.basename, is_directory) {
+            return None;
+        }
+
+        let path = join_path(dir, &file.basename);
+        if IgnoreRules::is_ignored_by(&self.ignore_rules, &path, is_directory) {
+            return None;
+        }
+
+        Some(WalkedFile {
+            path,
+            file,
+            depth: depth + 1,
+        })
+    }
+}
+
+/// 分页遍历的位置，序列化后作为游标返回给前端
+#[derive(Debug, Serialize, Deserialize)]
+struct WalkCursor {
+    root: String,
+    /// 待遍历的目录队列，队首为当前正在列出的目录
+    queue: VecDeque<(String, u32)>,
+    /// 当前目录的分页标记
+    marker: Option<String>,
+    /// 当前分页中已处理的条目数
+    skip: usize,
+}
+
+impl WalkCursor {
+    fn encode(&self) -> Result<String, String> {
+        let json =
+            serde_json::to_vec(self).map_err(|e| format!("Failed to encode cursor: {}", e))?;
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(json))
+    }
+
+    fn decode(cursor: &str, root: &str) -> Result<Self, String> {
+        let state: Self = general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| "Invalid cursor".to_string())?;
+
+        if state.root != root {
+            return Err("Cursor does not belong to this path".to_string());
+        }
+        Ok(state)
+    }
+}
+
+/// 拼接父目录与子条目名称
+pub fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else if parent.ends_with('/') {
+        format!("{}{}", parent, name)
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+/// 获取文件扩展名（小写，不含点）
+pub fn file_extension(name: &str) -> Option<String> {
+    let base = name.rsplit('/').next().unwrap_or(name);
+    base.rsplit_once('.')
+        .filter(|(stem, _)| !stem.is_empty())
+        .map(|(_, ext)| ext.to_lowercase())
+}
@@ -0,0 +1,174 @@
+// 已下载文件的内容索引
+// 以连接 + 远程 ETag + 文件大小标识内容，记录下载到本地的位置，再次下载同一内容时可直接复用本地文件
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::UNIX_EPOCH;
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 索引文件名
+const INDEX_FILE: &str = "content-index.json";
+
+/// 每个内容最多记录的本地位置数量
+const MAX_LOCATIONS: usize = 8;
+
+static CONTENT_INDEX: LazyLock<Mutex<Option<ContentIndex>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 复用已有本地副本的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ReuseMode {
+    /// 创建硬链接，不支持时（如跨文件系统）回退为复制
+    HardLink,
+    /// 复制一份独立的文件
+    Copy,
+}
+
+/// 与远程文件内容相同的本地文件
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistingDownload {
+    pub file_path: String,
+    pub size: String, // 使用字符串表示大数字
+}
+
+/// 一次下载记录，modified 用于判断本地文件下载后是否被修改过
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedCopy {
+    path: PathBuf,
+    size: u64,
+    modified: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ContentIndex {
+    /// 内容标识 -> 本地位置，最近下载的排在最后
+    entries: HashMap<String, Vec<IndexedCopy>>,
+}
+
+/// 生成内容标识，没有 ETag 的存储无法判断内容是否相同
+/// ETag 只在同一存储内有意义（不同账号或服务器上的文件可能恰好相同），所以包含连接标识
+pub fn content_key(connection_key: &str, etag: &str, size: u64) -> String {
+    format!("{}:{}:{}", connection_key, etag.trim_matches('"'), size)
+}
+
+/// 查找内容相同且下载后未被修改的本地文件
+pub fn find_existing(key: &str) -> Option<ExistingDownload> {
+    let mut guard = CONTENT_INDEX.lock().ok()?;
+    let index = guard.get_or_insert_with(ContentIndex::load);
+
+    let copies = index.entries.get_mut(key)?;
+    let before = copies.len();
+    copies.retain(IndexedCopy::is_intact);
+    let found = copies.last().map(|copy| ExistingDownload {
+        file_path: copy.path.display().to_string(),
+        size: copy.size.to_string(),
+    });
+
+    if copies.is_empty() {
+        index.entries.remove(key);
+    }
+    if index.entries.get(key).map_or(0, Vec::len) != before {
+        index.save();
+    }
+    found
+}
+
+/// 记录一次成功的下载
+pub fn record(key: &str, path: &Path) {
+    let Some(copy) = IndexedCopy::from_path(path) else {
+        return;
+    };
+    let Ok(mut guard) = CONTENT_INDEX.lock() else {
+        return;
+    };
+    let index = guard.get_or_insert_with(ContentIndex::load);
+
+    let copies = index.entries.entry(key.to_string()).or_default();
+    copies.retain(|existing| existing.path != copy.path && existing.is_intact());
+    copies.push(copy);
+    if copies.len() > MAX_LOCATIONS {
+        let excess = copies.len() - MAX_LOCATIONS;
+        copies.drain(..excess);
+    }
+    index.save();
+}
+
+/// 用已有的本地文件生成目标文件
+pub fn reuse(source: &Path, target: &Path, mode: ReuseMode) -> Result<(), String> {
+    if source == target {
+        return Ok(());
+    }
+    if target.exists() {
+        std::fs::remove_file(target)
+            .map_err(|e| format!("Failed to replace existing file: {}", e))?;
+    }
+
+    if mode == ReuseMode::HardLink {
+        match std::fs::hard_link(source, target) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::debug!("创建硬链接失败，改为复制: {}", e),
+        }
+    }
+
+    std::fs::copy(source, target)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy existing download: {}", e))
+}
+
+impl IndexedCopy {
+    fn from_path(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            modified: modified_secs(&metadata)?,
+        })
+    }
+
+    fn is_intact(&self) -> bool {
+        std::fs::metadata(&self.path)
+            .ok()
+            .filter(|metadata| metadata.is_file() && metadata.len() == self.size)
+            .and_then(|metadata| modified_secs(&metadata))
+            == Some(self.modified)
+    }
+}
+
+impl ContentIndex {
+    fn index_path() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("downloads")?.join(INDEX_FILE))
+    }
+
+    fn load() -> Self {
+        Self::index_path()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = Self::index_path().and_then(|path| {
+            let data = serde_json::to_vec(self)
+                .map_err(|e| format!("Failed to serialize content index: {}", e))?;
+            persist::write_atomic(path, data)
+                .map_err(|e| format!("Failed to write content index: {}", e))
+        });
+        if let Err(e) = result {
+            log::warn!("保存下载内容索引失败: {}", e);
+        }
+    }
+}
+
+fn modified_secs(metadata: &std::fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
@@ -0,0 +1,138 @@
+// 下载完成后自动解压单文件压缩格式（.gz / .zst / .br / .xz）
+// tar.gz 等归档格式包含多个文件，不在自动解压范围内
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tokio::sync::broadcast;
+
+use crate::archive::formats::common::CountingReader;
+
+/// 解压时的读写缓冲区大小
+const BUFFER_SIZE: usize = 256 * 1024;
+
+/// 下载后自动解压选项
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoDecompress {
+    /// 解压后保留压缩文件
+    pub keep_original: bool,
+}
+
+/// 支持自动解压的单文件压缩格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SingleFileCompression {
+    Gzip,
+    Zstd,
+    Brotli,
+    Xz,
+}
+
+impl SingleFileCompression {
+    /// 根据扩展名识别格式，返回格式和解压后的文件路径
+    pub fn detect(path: &Path) -> Option<(Self, PathBuf)> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        let stem = name.rsplit_once('.')?.0;
+        if stem.is_empty() || stem.ends_with(".tar") {
+            return None;
+        }
+
+        let format = match name.rsplit('.').next()? {
+            "gz" => Self::Gzip,
+            "zst" | "zstd" => Self::Zstd,
+            "br" => Self::Brotli,
+            "xz" => Self::Xz,
+            _ => return None,
+        };
+
+        // 保留原文件名的大小写
+        let original = path.file_name()?.to_str()?;
+        Some((format, path.with_file_name(&original[..stem.len()])))
+    }
+
+    fn decoder<'a, R: Read + 'a>(&self, reader: R) -> Result<Box<dyn Read + 'a>, String> {
+        Ok(match self {
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+            Self::Zstd => Box::new(
+                zstd::stream::read::Decoder::new(reader)
+                    .map_err(|e| format!("Failed to create zstd decoder: {}", e))?,
+            ),
+            Self::Brotli => Box::new(brotli::Decompressor::new(reader, BUFFER_SIZE)),
+            Self::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+        })
+    }
+}
+
+/// 解压下载好的文件，返回解压后的文件路径
+/// progress_callback 参数为（已读取的压缩数据字节数, 压缩文件大小）
+/// 先写入临时文件，成功后再替换目标文件，失败或取消时不会留下不完整的文件
+pub fn decompress_file(
+    path: &Path,
+    keep_original: bool,
+    progress_callback: impl Fn(u64, u64),
+    cancel_rx: &mut broadcast::Receiver<()>,
+) -> Result<PathBuf, String> {
+    let (format, output) = SingleFileCompression::detect(path)
+        .ok_or_else(|| format!("Unsupported compressed file: {}", path.display()))?;
+
+    let file = File::open(path).map_err(|e| format!("Failed to open downloaded file: {}", e))?;
+    let total = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file metadata: {}", e))?
+        .len();
+
+    let reader = CountingReader::new(BufReader::with_capacity(BUFFER_SIZE, file));
+    let processed = reader.counter();
+    let mut decoder = format.decoder(reader)?;
+
+    let partial = output.with_file_name(format!(
+        "{}.part",
+        output.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let result = (|| {
+        let mut writer = BufWriter::with_capacity(
+            BUFFER_SIZE,
+            File::create(&partial).map_err(|e| format!("Failed to create output file: {}", e))?,
+        );
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            if cancel_rx.try_recv().is_ok() {
+                return Err("download.cancelled".to_string());
+            }
+
+            let read = decoder
+                .read(&mut buffer)
+                .map_err(|e| format!("Failed to decompress file: {}", e))?;
+            if read == 0 {
+                break;
+            }
+            writer
+                .write_all(&buffer[..read])
+                .map_err(|e| format!("Failed to write output file: {}", e))?;
+            progress_callback(processed.load(Ordering::Relaxed), total);
+        }
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to write output file: {}", e))
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    std::fs::rename(&partial, &output).map_err(|e| {
+        let _ = std::fs::remove_file(&partial);
+        format!("Failed to move decompressed file: {}", e)
+    })?;
+
+    if !keep_original {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("删除压缩文件失败 {}: {}", path.display(), e);
+        }
+    }
+
+    Ok(output)
+}
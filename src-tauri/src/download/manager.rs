@@ -1,21 +1,54 @@
+use futures_util::StreamExt;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri_plugin_dialog::DialogExt;
 use tokio::sync::broadcast;
 
-use crate::download::{progress::ProgressTracker, provider::DownloadProviderFactory, types::*};
+use crate::dataset::estimate::EgressEstimator;
+use crate::dataset::ignore_rules::IgnoreRules;
+use crate::dataset::walker::TreeWalker;
+use crate::download::{
+    content_index::{self, ExistingDownload, ReuseMode},
+    decompress::{self, AutoDecompress, SingleFileCompression},
+    progress::{DownloadTask, ProgressTracker},
+    provider::{DownloadProvider, DownloadProviderFactory},
+    queue::{DownloadQueue, QueuedDownload},
+    resume::{self, PartialDownload, ResumeEntry},
+    types::*,
+};
+use crate::storage::get_storage_manager;
+use crate::storage::manager::current_connection_key;
 use crate::storage::traits::ProgressCallback;
 
+/// 多连接下载的最小文件大小
+const PARALLEL_DOWNLOAD_THRESHOLD: u64 = 16 * 1024 * 1024; // 16MB
+
+/// 多连接下载的最大并发数
+const MAX_DOWNLOAD_CONCURRENCY: u32 = 16;
+
+/// 目录下载最多包含的文件数量
+const MAX_DIRECTORY_FILES: usize = 100_000;
+
+/// 目录下载同时进入队列的文件数量，避免一次发起大量请求
+const MAX_PENDING_DIRECTORY_FILES: usize = 16;
+
 /// 简化的下载管理器
 /// 专注于任务管理、UI交互和进度跟踪
 pub struct DownloadManager {
+    /// 活跃下载的取消信号（任务标识 -> 发送端），任务标识为下载地址，压缩包内文件为 `压缩包路径!条目路径`，
+    /// 目录下载为远程目录路径
     active_downloads: Arc<Mutex<HashMap<String, broadcast::Sender<()>>>>,
+    /// 活跃下载的进度跟踪器（任务标识 -> 跟踪器），用于列出各任务的速度和剩余时间
+    trackers: Mutex<HashMap<String, ProgressTracker>>,
+    queue: DownloadQueue,
 }
 
 impl DownloadManager {
     pub fn new() -> Self {
         Self {
             active_downloads: Arc::new(Mutex::new(HashMap::new())),
+            trackers: Mutex::new(HashMap::new()),
+            queue: DownloadQueue::new(),
         }
     }
 
@@ -25,6 +58,10 @@ impl DownloadManager {
         app: tauri::AppHandle,
         request: DownloadRequest,
         save_path: Option<String>,
+        reuse: Option<ReuseMode>,
+        concurrency: Option<u32>,
+        priority: Option<i32>,
+        decompress: Option<AutoDecompress>,
     ) -> DownloadResult {
         // 获取合适的下载提供者
         let provider = DownloadProviderFactory::get_provider(&request.url).await?;
@@ -37,52 +74,402 @@ impl DownloadManager {
             );
             0 // 使用 0 表示未知大小
         });
+        let content_key = provider.content_key(&request, file_size).await;
 
         // 设置下载（文件对话框、取消信号、进度跟踪器）
-        let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) =
-            self.setup_download(&app, &request.filename, Some(file_size), save_path)?;
+        let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) = self.setup_download(
+            &app,
+            &request.url,
+            &request.filename,
+            Some(file_size),
+            save_path,
+        )?;
+
+        // 已下载过相同内容时，按要求从本地副本生成文件
+        let existing = reuse.zip(
+            content_key
+                .as_deref()
+                .and_then(content_index::find_existing),
+        );
+        if let Some((mode, existing)) = existing {
+            progress_tracker.emit_started(DownloadStarted {
+                id: request.url.clone(),
+                filename: request.filename.clone(),
+                total_size: file_size,
+            });
+            let source = std::path::PathBuf::from(&existing.file_path);
+            let result = content_index::reuse(&source, &save_path, mode).map(|_| {
+                format!(
+                    "File reused from {} to: {}",
+                    existing.file_path,
+                    save_path.display()
+                )
+            });
+            return self.handle_download_completion(
+                &request.url,
+                &request.filename,
+                result,
+                &save_path,
+                &progress_tracker,
+            );
+        }
 
-        // 发送开始下载事件
-        progress_tracker.emit_started(DownloadStarted {
-            filename: request.filename.clone(),
-            total_size: file_size,
-        });
+        // 记录续传信息，下载中断后可以从已写入的位置继续
+        resume::begin(
+            &save_path,
+            ResumeEntry {
+                connection_key: current_connection_key().await,
+                url: request.url.clone(),
+                filename: request.filename.clone(),
+                total_size: file_size,
+                content_key: content_key.clone(),
+                bytes_written: 0,
+            },
+        );
+
+        // 在队列中等待名额后执行下载，大文件按要求拆分为多个范围并发下载
+        let download_result = self
+            .run_queued_transfer(
+                &*provider,
+                &request,
+                &save_path,
+                file_size,
+                0,
+                Self::effective_concurrency(concurrency, file_size),
+                priority.unwrap_or(0),
+                &progress_tracker,
+                &mut cancel_rx,
+            )
+            .await;
 
-        // 创建进度回调
-        let progress_callback =
-            self.create_progress_callback(&progress_tracker, &request.filename, file_size);
+        self.finish_download(
+            &request,
+            download_result,
+            &save_path,
+            content_key,
+            decompress,
+            &progress_tracker,
+            cancel_rx,
+        )
+        .await
+    }
 
-        // 执行下载
-        let download_result = provider
-            .download(
+    /// 下载整个目录，在 save_dir 下保留文件的相对路径
+    /// 开始前消耗 dataset_estimate_operation 返回的下载估算令牌，需要确认的估算必须已确认；
+    /// 工作区保存的忽略规则和本次指定的规则都会跳过对应的文件；
+    /// 每个文件作为独立的下载进入队列，名称为“目录名/相对路径”，汇总进度通过 download-directory-progress 发送；
+    /// 取消目录路径时停止剩余文件并取消进行中的文件
+    #[allow(clippy::too_many_arguments)]
+    pub async fn download_directory(
+        &self,
+        app: tauri::AppHandle,
+        directory: String,
+        save_dir: std::path::PathBuf,
+        estimate_token: &str,
+        ignore_patterns: Option<&[String]>,
+        concurrency: Option<u32>,
+        priority: Option<i32>,
+    ) -> DownloadResult {
+        let estimate = EgressEstimator::take_confirmed(estimate_token, &directory)?;
+        if estimate.operation != "download" {
+            return Err("estimate.mismatch".to_string());
+        }
+        let ignore_rules = IgnoreRules::for_operation(&directory, ignore_patterns)?;
+
+        let client = {
+            let manager_arc = get_storage_manager().await;
+            let manager = manager_arc.read().await;
+            manager
+                .get_current_client()
+                .ok_or_else(|| "No storage client connected".to_string())?
+        };
+
+        let name = Self::directory_name(&directory);
+        let (files, truncated) = TreeWalker::new(client)
+            .max_files(MAX_DIRECTORY_FILES)
+            .ignore_rules(ignore_rules)
+            .collect_files(&directory)
+            .await?;
+        if truncated {
+            return Err(format!(
+                "Directory contains more than {} files",
+                MAX_DIRECTORY_FILES
+            ));
+        }
+        std::fs::create_dir_all(&save_dir)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+        // 目录本身也以目录路径登记为活跃下载，取消时从列表中移除
+        let root = directory.trim_end_matches('/');
+        let (cancel_tx, _) = broadcast::channel::<()>(1);
+        self.active_downloads
+            .lock()
+            .unwrap()
+            .insert(root.to_string(), cancel_tx);
+
+        let progress_tracker = ProgressTracker::new(app.clone());
+        let mut progress = DirectoryDownloadProgress {
+            id: root.to_string(),
+            directory: name.clone(),
+            total_files: files.len() as u64,
+            completed_files: 0,
+            failed_files: 0,
+            total_bytes: files.iter().map(|file| file.size()).sum(),
+            completed_bytes: 0,
+        };
+        progress_tracker.emit_directory_progress(progress.clone());
+
+        let mut results = futures_util::stream::iter(files)
+            .map(|file| {
+                let relative = file
+                    .path
+                    .strip_prefix(root)
+                    .unwrap_or(&file.path)
+                    .split('/')
+                    .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let save_path = save_dir.join(&relative).to_string_lossy().to_string();
+                let request = DownloadRequest {
+                    url: file.path.clone(),
+                    filename: format!("{}/{}", name, relative),
+                };
+                let size = file.size();
+                let app = app.clone();
+                async move {
+                    if !self.active_downloads.lock().unwrap().contains_key(root) {
+                        return (size, Err("download.cancelled".to_string()));
+                    }
+                    let result = self
+                        .download_with_progress(
+                            app,
+                            request,
+                            Some(save_path),
+                            None,
+                            concurrency,
+                            priority,
+                            None,
+                        )
+                        .await;
+                    (size, result)
+                }
+            })
+            .buffer_unordered(MAX_PENDING_DIRECTORY_FILES);
+
+        while let Some((size, result)) = results.next().await {
+            match result {
+                Ok(_) => {
+                    progress.completed_files += 1;
+                    progress.completed_bytes += size;
+                }
+                Err(_) => progress.failed_files += 1,
+            }
+            progress_tracker.emit_directory_progress(progress.clone());
+        }
+
+        let cancelled = self.active_downloads.lock().unwrap().remove(root).is_none();
+        if cancelled {
+            return Err("download.cancelled".to_string());
+        }
+        if progress.failed_files > 0 {
+            Ok(format!(
+                "Downloaded {} of {} files to: {} ({} failed)",
+                progress.completed_files,
+                progress.total_files,
+                save_dir.display(),
+                progress.failed_files
+            ))
+        } else {
+            Ok(format!(
+                "Downloaded {} files to: {}",
+                progress.completed_files,
+                save_dir.display()
+            ))
+        }
+    }
+
+    /// 目录下载使用的名称（远程目录的最后一级名称）
+    pub fn directory_name(directory: &str) -> String {
+        directory
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty() && !name.ends_with(':'))
+            .unwrap_or("download")
+            .to_string()
+    }
+
+    /// 续传中断的下载
+    /// 远程文件大小或内容标识与开始下载时不同，则放弃已写入的部分重新下载
+    pub async fn resume_download(
+        &self,
+        app: tauri::AppHandle,
+        save_path: String,
+        concurrency: Option<u32>,
+        decompress: Option<AutoDecompress>,
+    ) -> DownloadResult {
+        let entry = resume::get(std::path::Path::new(&save_path))
+            .ok_or_else(|| format!("No resumable download for: {}", save_path))?;
+        // 同一路径在其他连接下是不同的文件，不能在当前连接上续传
+        if entry.connection_key != current_connection_key().await {
+            return Err(format!(
+                "Resumable download belongs to another connection: {}",
+                save_path
+            ));
+        }
+        let request = DownloadRequest {
+            url: entry.url.clone(),
+            filename: entry.filename.clone(),
+        };
+
+        let provider = DownloadProviderFactory::get_provider(&request.url).await?;
+        let file_size = provider.get_file_size(&request).await?;
+        let content_key = provider.content_key(&request, file_size).await;
+        let offset = resume::written_len(std::path::Path::new(&save_path));
+
+        let changed = file_size != entry.total_size
+            || matches!(
+                (&content_key, &entry.content_key),
+                (Some(current), Some(previous)) if current != previous
+            );
+        if changed || offset > file_size {
+            log::info!("远程文件已变化，重新下载: {}", request.filename);
+            resume::finish(std::path::Path::new(&save_path));
+            return self
+                .download_with_progress(
+                    app,
+                    request,
+                    Some(save_path),
+                    None,
+                    concurrency,
+                    None,
+                    decompress,
+                )
+                .await;
+        }
+
+        let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) = self.setup_download(
+            &app,
+            &request.url,
+            &request.filename,
+            Some(file_size),
+            Some(save_path),
+        )?;
+
+        let download_result = self
+            .run_queued_transfer(
+                &*provider,
                 &request,
                 &save_path,
-                Some(progress_callback),
+                file_size,
+                offset,
+                Self::effective_concurrency(concurrency, file_size),
+                0,
+                &progress_tracker,
                 &mut cancel_rx,
             )
             .await;
 
-        // 处理下载完成
-        self.handle_download_completion(
-            &request.filename,
+        self.finish_download(
+            &request,
             download_result,
             &save_path,
+            content_key,
+            decompress,
             &progress_tracker,
+            cancel_rx,
         )
+        .await
+    }
+
+    /// 列出可续传的下载
+    pub async fn list_resumable_downloads(&self) -> Vec<PartialDownload> {
+        resume::list_for_connection(&current_connection_key().await)
+    }
+
+    /// 暂停下载，进行中的下载会中断传输并回到队列
+    pub fn pause_download(&self, id: &str) -> Result<String, String> {
+        if self.queue.pause(id)? {
+            let downloads = self.active_downloads.lock().unwrap();
+            if let Some(cancel_sender) = downloads.get(id) {
+                let _ = cancel_sender.send(());
+            }
+        }
+        Ok(format!("Download paused: {}", id))
+    }
+
+    /// 恢复已暂停的下载
+    pub fn resume_queued_download(&self, id: &str) -> Result<String, String> {
+        self.queue.resume(id)?;
+        Ok(format!("Download resumed: {}", id))
     }
 
-    /// 取消指定文件的下载
-    pub fn cancel_download(&self, filename: &str) -> Result<String, String> {
+    /// 调整排队中下载的顺序
+    pub fn reorder_downloads(&self, ids: &[String]) {
+        self.queue.reorder(ids);
+    }
+
+    /// 设置同时进行的下载数量上限
+    pub fn set_max_concurrent_downloads(&self, max_concurrent: u32) {
+        self.queue.set_max_concurrent(max_concurrent as usize);
+    }
+
+    /// 列出进行中和排队中的下载
+    pub fn get_download_queue(&self) -> Vec<QueuedDownload> {
+        self.queue.list()
+    }
+
+    /// 列出进行中和排队中的下载及其传输速度、剩余时间，按队列顺序排列
+    pub fn list_download_tasks(&self) -> Vec<DownloadTask> {
+        let trackers = self.trackers.lock().unwrap();
+        self.queue
+            .list()
+            .into_iter()
+            .filter_map(|queued| {
+                trackers.get(&queued.id).map(|tracker| {
+                    tracker.task(&queued.id, &queued.filename, queued.priority, queued.state)
+                })
+            })
+            .collect()
+    }
+
+    /// 查找与远程文件内容相同的已下载文件，供前端提示复用
+    pub async fn find_existing_download(
+        &self,
+        url: &str,
+    ) -> Result<Option<ExistingDownload>, String> {
+        let provider = DownloadProviderFactory::get_provider(url).await?;
+        let request = DownloadRequest {
+            url: url.to_string(),
+            filename: String::new(),
+        };
+        let file_size = provider.get_file_size(&request).await?;
+
+        Ok(provider
+            .content_key(&request, file_size)
+            .await
+            .and_then(|key| content_index::find_existing(&key)))
+    }
+
+    /// 取消指定任务的下载
+    pub fn cancel_download(&self, id: &str) -> Result<String, String> {
         let mut downloads = self.active_downloads.lock().unwrap();
 
-        if let Some(cancel_sender) = downloads.remove(filename) {
+        if let Some(cancel_sender) = downloads.remove(id) {
             let _ = cancel_sender.send(());
-            Ok(format!(
-                "Download cancellation signal sent for: {}",
-                filename
-            ))
+            // 取消目录下载时同时取消其中的文件
+            let prefix = format!("{}/", id);
+            downloads.retain(|key, sender| {
+                if key.starts_with(&prefix) {
+                    let _ = sender.send(());
+                    false
+                } else {
+                    true
+                }
+            });
+            Ok(format!("Download cancellation signal sent for: {}", id))
         } else {
-            Err(format!("No active download found for: {}", filename))
+            Err(format!("No active download found for: {}", id))
         }
     }
 
@@ -112,9 +499,10 @@ impl DownloadManager {
         entry_filename: String,
         save_path: Option<String>,
     ) -> DownloadResult {
-        // 设置下载
+        // 设置下载，压缩包内文件以 `压缩包路径!条目路径` 区分
+        let task_id = format!("{}!{}", archive_path, entry_path);
         let (save_path, _cancel_tx, mut cancel_rx, progress_tracker) =
-            self.setup_download(&app, &entry_filename, None, save_path)?;
+            self.setup_download(&app, &task_id, &entry_filename, None, save_path)?;
 
         // 执行压缩包文件下载
         let result = self
@@ -129,11 +517,25 @@ impl DownloadManager {
             )
             .await;
 
-        self.handle_download_completion(&entry_filename, result, &save_path, &progress_tracker)
+        self.handle_download_completion(
+            &task_id,
+            &entry_filename,
+            result,
+            &save_path,
+            &progress_tracker,
+        )
     }
 
     // === 私有辅助方法 ===
 
+    /// 实际使用的并发连接数，小文件和大小未知的文件不拆分
+    fn effective_concurrency(concurrency: Option<u32>, file_size: u64) -> usize {
+        if file_size < PARALLEL_DOWNLOAD_THRESHOLD {
+            return 1;
+        }
+        concurrency.unwrap_or(1).clamp(1, MAX_DOWNLOAD_CONCURRENCY) as usize
+    }
+
     /// 显示文件保存对话框
     fn show_save_file_dialog(
         app: &tauri::AppHandle,
@@ -159,10 +561,11 @@ impl DownloadManager {
         }
     }
 
-    /// 设置下载的公共逻辑
+    /// 设置下载的公共逻辑，取消信号和进度跟踪器按任务标识登记
     fn setup_download(
         &self,
         app: &tauri::AppHandle,
+        task_id: &str,
         filename: &str,
         _file_size: Option<u64>,
         custom_save_path: Option<String>,
@@ -195,23 +598,119 @@ impl DownloadManager {
         let (cancel_tx, cancel_rx) = broadcast::channel::<()>(1);
         {
             let mut downloads = self.active_downloads.lock().unwrap();
-            downloads.insert(filename.to_string(), cancel_tx.clone());
+            downloads.insert(task_id.to_string(), cancel_tx.clone());
         }
 
         // 创建进度跟踪器
         let progress_tracker = ProgressTracker::new(app.clone());
+        self.trackers
+            .lock()
+            .unwrap()
+            .insert(task_id.to_string(), progress_tracker.clone());
 
         Ok((save_path, cancel_tx, cancel_rx, progress_tracker))
     }
 
+    /// 在队列中等待名额后执行传输
+    /// 暂停时释放名额回到队列，恢复后从已写入的位置继续
+    #[allow(clippy::too_many_arguments)]
+    async fn run_queued_transfer(
+        &self,
+        provider: &dyn DownloadProvider,
+        request: &DownloadRequest,
+        save_path: &std::path::Path,
+        file_size: u64,
+        mut offset: u64,
+        concurrency: usize,
+        priority: i32,
+        progress_tracker: &ProgressTracker,
+        cancel_rx: &mut broadcast::Receiver<()>,
+    ) -> Result<String, String> {
+        progress_tracker.emit_queued(DownloadQueueEvent {
+            id: request.url.clone(),
+            filename: request.filename.clone(),
+        });
+        let connection_key = current_connection_key().await;
+        self.queue
+            .acquire(
+                &request.url,
+                &request.filename,
+                &connection_key,
+                priority,
+                false,
+                cancel_rx,
+            )
+            .await?;
+
+        loop {
+            progress_tracker.emit_started(DownloadStarted {
+                id: request.url.clone(),
+                filename: request.filename.clone(),
+                total_size: file_size,
+            });
+            let progress_callback = self.create_progress_callback(
+                progress_tracker,
+                &request.url,
+                &request.filename,
+                file_size,
+            );
+
+            // 从中间继续或多连接下载时按范围下载，大小未知时只能整体下载
+            let result = if file_size > 0 && (offset > 0 || concurrency > 1) {
+                provider
+                    .download_range(
+                        request,
+                        save_path,
+                        offset,
+                        file_size,
+                        concurrency,
+                        Some(progress_callback),
+                        cancel_rx,
+                    )
+                    .await
+            } else {
+                provider
+                    .download(request, save_path, Some(progress_callback), cancel_rx)
+                    .await
+            };
+
+            let paused = self.queue.release(&request.url);
+            if !paused || !matches!(&result, Err(e) if e.contains("cancelled")) {
+                if paused {
+                    // 暂停信号到达前传输已结束，丢弃未被处理的信号
+                    let _ = cancel_rx.try_recv();
+                }
+                return result;
+            }
+
+            progress_tracker.emit_paused(DownloadQueueEvent {
+                id: request.url.clone(),
+                filename: request.filename.clone(),
+            });
+            self.queue
+                .acquire(
+                    &request.url,
+                    &request.filename,
+                    &connection_key,
+                    priority,
+                    true,
+                    cancel_rx,
+                )
+                .await?;
+            offset = resume::written_len(save_path);
+        }
+    }
+
     /// 创建进度回调
     fn create_progress_callback(
         &self,
         progress_tracker: &ProgressTracker,
+        id: &str,
         filename: &str,
         total_size: u64,
     ) -> ProgressCallback {
         let progress_tracker_clone = progress_tracker.clone();
+        let id = id.to_string();
         let filename_clone = filename.to_string();
 
         std::sync::Arc::new(move |downloaded: u64, actual_total: u64| {
@@ -222,22 +721,130 @@ impl DownloadManager {
                 total_size
             };
 
+            let event = progress_tracker_clone.record_progress(
+                &id,
+                &filename_clone,
+                downloaded,
+                effective_total,
+            );
             if progress_tracker_clone.should_emit_progress(downloaded, effective_total) {
-                let progress =
-                    progress_tracker_clone.calculate_progress(downloaded, effective_total);
-                progress_tracker_clone.emit_progress(DownloadProgress {
-                    filename: filename_clone.clone(),
-                    downloaded,
-                    total_size: effective_total,
-                    progress,
-                });
+                progress_tracker_clone.emit_progress(event);
             }
         })
     }
 
+    /// 在后台线程解压下载好的文件，通过 download-decompress-progress 事件报告进度
+    async fn decompress_download(
+        &self,
+        progress_tracker: &ProgressTracker,
+        id: &str,
+        filename: &str,
+        save_path: &std::path::Path,
+        options: AutoDecompress,
+        mut cancel_rx: broadcast::Receiver<()>,
+    ) -> Result<std::path::PathBuf, String> {
+        let progress_tracker = progress_tracker.clone();
+        let id = id.to_string();
+        let filename = filename.to_string();
+        let save_path = save_path.to_path_buf();
+
+        tokio::task::spawn_blocking(move || {
+            let last_progress = std::cell::Cell::new(None);
+            decompress::decompress_file(
+                &save_path,
+                options.keep_original,
+                |processed, total| {
+                    let progress = progress_tracker.calculate_progress(processed, total);
+                    if last_progress.replace(Some(progress)) != Some(progress) {
+                        progress_tracker.emit_decompress_progress(DownloadProgress {
+                            id: id.clone(),
+                            filename: filename.clone(),
+                            downloaded: processed,
+                            total_size: total,
+                            progress,
+                            speed: 0,
+                            eta_seconds: None,
+                        });
+                    }
+                },
+                &mut cancel_rx,
+            )
+        })
+        .await
+        .map_err(|e| format!("Decompression task failed: {}", e))?
+    }
+
+    /// 下载结束后更新续传状态和内容索引，按需自动解压，然后处理下载完成
+    #[allow(clippy::too_many_arguments)]
+    async fn finish_download(
+        &self,
+        request: &DownloadRequest,
+        download_result: Result<String, String>,
+        save_path: &std::path::Path,
+        content_key: Option<String>,
+        decompress: Option<AutoDecompress>,
+        progress_tracker: &ProgressTracker,
+        cancel_rx: broadcast::Receiver<()>,
+    ) -> DownloadResult {
+        match &download_result {
+            Ok(_) => {
+                resume::finish(save_path);
+                if let Some(key) = &content_key {
+                    content_index::record(key, save_path);
+                }
+            }
+            Err(_) => resume::interrupt(save_path),
+        }
+
+        // 单文件压缩格式按需自动解压
+        let (download_result, final_path) = match (download_result, decompress) {
+            (Ok(message), Some(options)) if SingleFileCompression::detect(save_path).is_some() => {
+                match self
+                    .decompress_download(
+                        progress_tracker,
+                        &request.url,
+                        &request.filename,
+                        save_path,
+                        options,
+                        cancel_rx,
+                    )
+                    .await
+                {
+                    Ok(output) => (
+                        Ok(format!(
+                            "File downloaded and decompressed to: {}",
+                            output.display()
+                        )),
+                        output,
+                    ),
+                    Err(e) if e.contains("cancelled") => (Err(e), save_path.to_path_buf()),
+                    // 解压失败时保留已下载的压缩文件
+                    Err(e) => {
+                        log::warn!("自动解压失败 {}: {}", save_path.display(), e);
+                        (
+                            Ok(format!("{} (decompression failed: {})", message, e)),
+                            save_path.to_path_buf(),
+                        )
+                    }
+                }
+            }
+            (result, _) => (result, save_path.to_path_buf()),
+        };
+
+        // 处理下载完成
+        self.handle_download_completion(
+            &request.url,
+            &request.filename,
+            download_result,
+            &final_path,
+            progress_tracker,
+        )
+    }
+
     /// 处理下载完成的公共逻辑
     fn handle_download_completion(
         &self,
+        task_id: &str,
         filename: &str,
         result: Result<String, String>,
         save_path: &std::path::Path,
@@ -245,22 +852,26 @@ impl DownloadManager {
     ) -> DownloadResult {
         {
             let mut downloads = self.active_downloads.lock().unwrap();
-            downloads.remove(filename);
+            downloads.remove(task_id);
         }
+        self.trackers.lock().unwrap().remove(task_id);
 
         match result {
             Ok(success_msg) => {
                 progress_tracker.emit_completed(DownloadCompleted {
+                    id: task_id.to_string(),
                     filename: filename.to_string(),
                     file_path: save_path.display().to_string(),
                 });
                 Ok(success_msg)
             }
             Err(error) => {
-                if !error.contains("cancelled") {
+                // 可续传的下载（包括被取消的）保留已写入的部分
+                if resume::get(save_path).is_none() {
                     let _ = std::fs::remove_file(save_path);
                 }
                 progress_tracker.emit_error(DownloadError {
+                    id: task_id.to_string(),
                     filename: filename.to_string(),
                     error: error.clone(),
                 });
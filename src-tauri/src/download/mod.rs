@@ -1,7 +1,16 @@
+pub mod content_index;
+pub mod decompress;
 pub mod manager;
 pub mod progress;
 pub mod provider;
+pub mod queue;
+pub mod resume;
 pub mod types;
 
+pub use content_index::{ExistingDownload, ReuseMode};
+pub use decompress::AutoDecompress;
 pub use manager::DownloadManager;
+pub use progress::DownloadTask;
+pub use queue::QueuedDownload;
+pub use resume::PartialDownload;
 pub use types::*;
@@ -1,10 +1,94 @@
+use crate::download::queue::QueueState;
 use crate::download::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 use tauri::Emitter;
 
+/// 计算移动平均速度的时间窗口
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+
+/// 两次速度采样的最小间隔，避免小数据块频繁回调时采样过多
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 进度变化不足 1% 时，至少每隔该时间发送一次进度，让速度和剩余时间保持更新
+const MIN_EMIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 下载任务的传输状态，供前端的传输管理面板使用
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadTask {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`），与下载事件中的 id 一致
+    pub id: String,
+    pub filename: String,
+    pub priority: i32,
+    pub state: QueueState,
+    pub downloaded: String, // 使用字符串表示大数字
+    pub total_size: String, // 使用字符串表示大数字
+    pub progress: u32,
+    /// 最近 5 秒的平均速度（字节/秒）
+    pub speed: String, // 使用字符串表示大数字
+    /// 按当前速度估算的剩余秒数，大小未知或没有速度时为空
+    pub eta_seconds: Option<u32>,
+}
+
+/// 按最近一段时间的采样计算移动平均速度
+#[derive(Debug, Default)]
+struct TransferStats {
+    downloaded: u64,
+    total_size: u64,
+    /// (采样时间, 已下载字节数)，按时间顺序
+    samples: VecDeque<(Instant, u64)>,
+    last_emitted_at: Option<Instant>,
+}
+
+impl TransferStats {
+    fn record(&mut self, downloaded: u64, total_size: u64) {
+        let now = Instant::now();
+        self.downloaded = downloaded;
+        self.total_size = total_size;
+
+        let due = self
+            .samples
+            .back()
+            .is_none_or(|(at, _)| now.duration_since(*at) >= SAMPLE_INTERVAL);
+        if due {
+            self.samples.push_back((now, downloaded));
+        }
+        // 保留一个窗口外的采样作为起点
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= SPEED_WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    fn speed(&self) -> u64 {
+        let Some((started_at, started_bytes)) = self.samples.front() else {
+            return 0;
+        };
+        let elapsed = started_at.elapsed().as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0;
+        }
+        (self.downloaded.saturating_sub(*started_bytes) as f64 / elapsed) as u64
+    }
+
+    fn eta_seconds(&self, speed: u64) -> Option<u64> {
+        if self.total_size == 0 || speed == 0 {
+            return None;
+        }
+        Some(
+            self.total_size
+                .saturating_sub(self.downloaded)
+                .div_ceil(speed),
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct ProgressTracker {
     app: tauri::AppHandle,
     last_emitted_progress: std::sync::Arc<std::sync::Mutex<u32>>,
+    stats: std::sync::Arc<std::sync::Mutex<TransferStats>>,
 }
 
 impl ProgressTracker {
@@ -12,6 +96,7 @@ impl ProgressTracker {
         Self {
             app,
             last_emitted_progress: std::sync::Arc::new(std::sync::Mutex::new(0)),
+            stats: std::sync::Arc::new(std::sync::Mutex::new(TransferStats::default())),
         }
     }
 
@@ -21,6 +106,24 @@ impl ProgressTracker {
         if let Ok(mut last_progress) = self.last_emitted_progress.lock() {
             *last_progress = 0;
         }
+        // 暂停后恢复时重新计算速度
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.samples.clear();
+            stats.total_size = event.total_size;
+        }
+    }
+
+    /// 下载进入队列，等待空闲名额
+    pub fn emit_queued(&self, event: DownloadQueueEvent) {
+        let _ = self.app.emit("download-queued", &event);
+    }
+
+    /// 下载被暂停，恢复前保留在队列中
+    pub fn emit_paused(&self, event: DownloadQueueEvent) {
+        let _ = self.app.emit("download-paused", &event);
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.samples.clear();
+        }
     }
 
     pub fn emit_progress(&self, event: DownloadProgress) {
@@ -29,6 +132,19 @@ impl ProgressTracker {
         if let Ok(mut last_progress) = self.last_emitted_progress.lock() {
             *last_progress = event.progress;
         }
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.last_emitted_at = Some(Instant::now());
+        }
+    }
+
+    /// 下载完成后自动解压的进度，downloaded 为已处理的压缩数据字节数
+    pub fn emit_decompress_progress(&self, event: DownloadProgress) {
+        let _ = self.app.emit("download-decompress-progress", &event);
+    }
+
+    /// 目录下载中有文件完成或失败
+    pub fn emit_directory_progress(&self, event: DirectoryDownloadProgress) {
+        let _ = self.app.emit("download-directory-progress", &event);
     }
 
     pub fn emit_completed(&self, event: DownloadCompleted) {
@@ -39,15 +155,81 @@ impl ProgressTracker {
         let _ = self.app.emit("download-error", &event);
     }
 
+    /// 记录传输进度并生成带速度和剩余时间的进度事件
+    pub fn record_progress(
+        &self,
+        id: &str,
+        filename: &str,
+        downloaded: u64,
+        total_size: u64,
+    ) -> DownloadProgress {
+        let (speed, eta_seconds) = match self.stats.lock() {
+            Ok(mut stats) => {
+                stats.record(downloaded, total_size);
+                let speed = stats.speed();
+                (speed, stats.eta_seconds(speed))
+            }
+            Err(_) => (0, None),
+        };
+        DownloadProgress {
+            id: id.to_string(),
+            filename: filename.to_string(),
+            downloaded,
+            total_size,
+            progress: self.calculate_progress(downloaded, total_size),
+            speed,
+            eta_seconds,
+        }
+    }
+
+    /// 当前的传输状态，state 和 priority 来自下载队列
+    pub fn task(&self, id: &str, filename: &str, priority: i32, state: QueueState) -> DownloadTask {
+        let (downloaded, total_size, speed, eta_seconds) = match self.stats.lock() {
+            Ok(stats) => {
+                // 暂停和排队时没有速度
+                let speed = if state == QueueState::Running {
+                    stats.speed()
+                } else {
+                    0
+                };
+                (
+                    stats.downloaded,
+                    stats.total_size,
+                    speed,
+                    stats.eta_seconds(speed),
+                )
+            }
+            Err(_) => (0, 0, 0, None),
+        };
+        DownloadTask {
+            id: id.to_string(),
+            filename: filename.to_string(),
+            priority,
+            state,
+            downloaded: downloaded.to_string(),
+            total_size: total_size.to_string(),
+            progress: self.calculate_progress(downloaded, total_size),
+            speed: speed.to_string(),
+            eta_seconds: eta_seconds.map(|eta| eta.min(u32::MAX as u64) as u32),
+        }
+    }
+
     pub fn should_emit_progress(&self, downloaded: u64, total_size: u64) -> bool {
         let current_progress = self.calculate_progress(downloaded, total_size);
+        let emit_overdue = self.stats.lock().is_ok_and(|stats| {
+            stats
+                .last_emitted_at
+                .is_none_or(|at| at.elapsed() >= MIN_EMIT_INTERVAL)
+        });
 
         // 检查是否有显著的进度变化（至少1%的变化或每64KB）
         if let Ok(last_progress) = self.last_emitted_progress.lock() {
-            // 进度变化至少1%，或者每64KB发送一次（但避免在开始时重复发送0%），或者是最后的数据块
+            // 进度变化至少1%，或者每64KB发送一次（但避免在开始时重复发送0%），或者是最后的数据块，
+            // 或者距上次发送已超过 1 秒（更新速度）
             current_progress > *last_progress
                 || (downloaded > 0 && downloaded % (64 * 1024) == 0)
                 || (total_size > 0 && downloaded == total_size)
+                || emit_overdue
         } else {
             true // 如果无法获取锁，就发送进度
         }
@@ -1,8 +1,12 @@
 use async_trait::async_trait;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::broadcast;
 
-use crate::download::types::DownloadRequest;
+use crate::download::{content_index, types::DownloadRequest};
+use crate::storage::manager::current_connection_key;
 use crate::storage::{get_storage_manager, traits::ProgressCallback};
 
 /// 下载提供者接口
@@ -12,6 +16,12 @@ pub trait DownloadProvider: Send + Sync {
     /// 获取文件大小
     async fn get_file_size(&self, request: &DownloadRequest) -> Result<u64, String>;
 
+    /// 获取远程内容标识，用于识别已下载过的相同内容
+    /// 默认实现：无法标识内容，每次都重新下载
+    async fn content_key(&self, _request: &DownloadRequest, _file_size: u64) -> Option<String> {
+        None
+    }
+
     /// 执行下载
     async fn download(
         &self,
@@ -20,8 +30,25 @@ pub trait DownloadProvider: Send + Sync {
         progress_callback: Option<ProgressCallback>,
         cancel_rx: &mut broadcast::Receiver<()>,
     ) -> Result<String, String>;
+
+    /// 从 offset 开始按范围下载，将数据追加到已有的部分文件（offset 为 0 时重新创建文件）
+    /// concurrency 大于 1 时同时请求多个分块，按顺序写入，中断后仍可续传
+    #[allow(clippy::too_many_arguments)]
+    async fn download_range(
+        &self,
+        request: &DownloadRequest,
+        save_path: &Path,
+        offset: u64,
+        total_size: u64,
+        concurrency: usize,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: &mut broadcast::Receiver<()>,
+    ) -> Result<String, String>;
 }
 
+/// 按范围下载时每次请求的数据块大小
+const RANGE_CHUNK_SIZE: u64 = 8 * 1024 * 1024; // 8MB
+
 /// 下载提供者工厂
 pub struct DownloadProviderFactory;
 
@@ -37,7 +64,7 @@ impl DownloadProviderFactory {
 /// 统一的存储下载提供者
 /// 所有下载都通过存储客户端的流式 download_file 方法处理
 pub struct StorageDownloadProvider {
-    client: std::sync::Arc<dyn crate::storage::traits::StorageClient + Send + Sync>,
+    client: Arc<dyn crate::storage::traits::StorageClient + Send + Sync>,
 }
 
 impl StorageDownloadProvider {
@@ -61,6 +88,19 @@ impl DownloadProvider for StorageDownloadProvider {
             .map_err(|e| format!("Failed to get file size: {}", e))
     }
 
+    async fn content_key(&self, request: &DownloadRequest, file_size: u64) -> Option<String> {
+        if file_size == 0 {
+            return None;
+        }
+        let etag = self.client.get_object_etag(&request.url).await.ok()?;
+        let connection_key = current_connection_key().await;
+        Some(content_index::content_key(
+            &connection_key,
+            &etag,
+            file_size,
+        ))
+    }
+
     async fn download(
         &self,
         request: &DownloadRequest,
@@ -80,4 +120,95 @@ impl DownloadProvider for StorageDownloadProvider {
                 }
             })
     }
+
+    async fn download_range(
+        &self,
+        request: &DownloadRequest,
+        save_path: &Path,
+        offset: u64,
+        total_size: u64,
+        concurrency: usize,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: &mut broadcast::Receiver<()>,
+    ) -> Result<String, String> {
+        use futures_util::StreamExt;
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(save_path)
+            .await
+            .map_err(|e| format!("Failed to open partial file: {}", e))?;
+        if offset == 0 {
+            file.set_len(0)
+                .await
+                .map_err(|e| format!("Failed to truncate file: {}", e))?;
+        }
+
+        // 所有存储客户端都支持范围读取，分块并发请求，按顺序追加写入
+        let downloaded = Arc::new(AtomicU64::new(offset));
+        let chunks = (offset..total_size)
+            .step_by(RANGE_CHUNK_SIZE as usize)
+            .map(|start| (start, RANGE_CHUNK_SIZE.min(total_size - start)));
+        let mut chunks = futures_util::stream::iter(chunks)
+            .map(|(start, length)| {
+                // 汇总各分块的进度
+                let chunk_callback = progress_callback.clone().map(|callback| {
+                    let downloaded = downloaded.clone();
+                    let chunk_downloaded = AtomicU64::new(0);
+                    Arc::new(move |current: u64, _total: u64| {
+                        let delta = current
+                            .saturating_sub(chunk_downloaded.swap(current, Ordering::Relaxed));
+                        let total = downloaded.fetch_add(delta, Ordering::Relaxed) + delta;
+                        callback(total, total_size);
+                    }) as ProgressCallback
+                });
+                let client = self.client.clone();
+                let url = request.url.clone();
+                async move {
+                    let data = client
+                        .read_file_range_with_progress(&url, start, length, chunk_callback, None)
+                        .await
+                        .map_err(|e| format!("Storage client download failed: {}", e))?;
+                    // 每块必须恰好返回请求的长度，否则合并后的文件会错位或不完整
+                    let received = data.len() as u64;
+                    if received != length {
+                        return Err(format!(
+                            "Range read returned {} bytes at offset {}, expected {} of {} bytes",
+                            received, start, length, total_size
+                        ));
+                    }
+                    Ok::<_, String>(data)
+                }
+            })
+            .buffered(concurrency.max(1));
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = chunks.next() => chunk,
+                _ = cancel_rx.recv() => return Err("download.cancelled".to_string()),
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            file.write_all(&chunk?)
+                .await
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+
+        Ok(if offset > 0 {
+            format!(
+                "File resumed from {} bytes and downloaded to: {}",
+                offset,
+                save_path.display()
+            )
+        } else {
+            format!("File downloaded successfully to: {}", save_path.display())
+        })
+    }
 }
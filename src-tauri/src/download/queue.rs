@@ -0,0 +1,239 @@
+// 下载队列
+// 按优先级调度下载，优先级相同时按队列顺序；同时进行的下载数量有上限，
+// 排队中的下载可以暂停、恢复和调整顺序，进行中的下载暂停后回到队列，恢复时从已写入的位置继续；
+// 下载按任务标识（下载地址）区分，不同目录中的同名文件互不影响
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tokio::sync::{broadcast, oneshot};
+
+/// 默认同时进行的下载数量
+const DEFAULT_MAX_CONCURRENT: usize = 3;
+
+/// 队列中下载的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueState {
+    Running,
+    Queued,
+    Paused,
+}
+
+/// 队列中的下载
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedDownload {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`），不同目录中的同名文件不会冲突
+    pub id: String,
+    pub filename: String,
+    /// 下载所属连接的标识，切换连接后队列中仍可能有之前连接的下载
+    pub connection_key: String,
+    pub priority: i32,
+    pub state: QueueState,
+}
+
+struct WaitingTask {
+    id: String,
+    filename: String,
+    connection_key: String,
+    priority: i32,
+    paused: bool,
+    ready: Option<oneshot::Sender<()>>,
+}
+
+struct RunningTask {
+    id: String,
+    filename: String,
+    connection_key: String,
+    priority: i32,
+    pause_requested: bool,
+}
+
+struct QueueInner {
+    max_concurrent: usize,
+    waiting: Vec<WaitingTask>,
+    running: Vec<RunningTask>,
+}
+
+/// 下载队列
+pub struct DownloadQueue {
+    inner: Mutex<QueueInner>,
+}
+
+impl DownloadQueue {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(QueueInner {
+                max_concurrent: DEFAULT_MAX_CONCURRENT,
+                waiting: Vec::new(),
+                running: Vec::new(),
+            }),
+        }
+    }
+
+    /// 加入队列并等待轮到该下载，paused 为 true 时需要恢复后才会开始
+    /// 等待期间收到取消信号时移出队列并返回 download.cancelled
+    pub async fn acquire(
+        &self,
+        id: &str,
+        filename: &str,
+        connection_key: &str,
+        priority: i32,
+        paused: bool,
+        cancel_rx: &mut broadcast::Receiver<()>,
+    ) -> Result<(), String> {
+        let (ready_tx, ready_rx) = oneshot::channel();
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.waiting.push(WaitingTask {
+                id: id.to_string(),
+                filename: filename.to_string(),
+                connection_key: connection_key.to_string(),
+                priority,
+                paused,
+                ready: Some(ready_tx),
+            });
+            inner.schedule();
+        }
+
+        tokio::select! {
+            result = ready_rx => result.map_err(|_| "download.cancelled".to_string()),
+            _ = cancel_rx.recv() => {
+                self.remove(id);
+                Err("download.cancelled".to_string())
+            }
+        }
+    }
+
+    /// 下载结束或中断时释放名额，返回是否因暂停而中断
+    pub fn release(&self, id: &str) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let paused = inner
+            .running
+            .iter()
+            .position(|task| task.id == id)
+            .map(|index| inner.running.remove(index).pause_requested)
+            .unwrap_or(false);
+        inner.schedule();
+        paused
+    }
+
+    /// 暂停下载，返回下载是否正在进行（需要中断传输）
+    pub fn pause(&self, id: &str) -> Result<bool, String> {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(task) = inner.waiting.iter_mut().find(|task| task.id == id) {
+            task.paused = true;
+            return Ok(false);
+        }
+        if let Some(task) = inner.running.iter_mut().find(|task| task.id == id) {
+            task.pause_requested = true;
+            return Ok(true);
+        }
+        Err(format!("No queued download found for: {}", id))
+    }
+
+    /// 恢复已暂停的下载，有空闲名额时立即开始
+    pub fn resume(&self, id: &str) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        let task = inner
+            .waiting
+            .iter_mut()
+            .find(|task| task.id == id && task.paused)
+            .ok_or_else(|| format!("No paused download found for: {}", id))?;
+        task.paused = false;
+        inner.schedule();
+        Ok(())
+    }
+
+    /// 调整排队顺序：列出的下载按给定顺序排到最前，其余保持原有顺序
+    pub fn reorder(&self, ids: &[String]) {
+        let mut inner = self.inner.lock().unwrap();
+        let mut waiting = std::mem::take(&mut inner.waiting);
+        let mut ordered = Vec::with_capacity(waiting.len());
+        for id in ids {
+            if let Some(index) = waiting.iter().position(|task| &task.id == id) {
+                ordered.push(waiting.remove(index));
+            }
+        }
+        ordered.append(&mut waiting);
+        inner.waiting = ordered;
+        inner.schedule();
+    }
+
+    /// 设置同时进行的下载数量上限
+    /// 调小时正在进行的下载不会中断，完成后才按新上限调度
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.max_concurrent = max_concurrent.max(1);
+        inner.schedule();
+    }
+
+    /// 列出进行中和排队中的下载
+    pub fn list(&self) -> Vec<QueuedDownload> {
+        let inner = self.inner.lock().unwrap();
+        let running = inner.running.iter().map(|task| QueuedDownload {
+            id: task.id.clone(),
+            filename: task.filename.clone(),
+            connection_key: task.connection_key.clone(),
+            priority: task.priority,
+            state: QueueState::Running,
+        });
+        let waiting = inner.waiting.iter().map(|task| QueuedDownload {
+            id: task.id.clone(),
+            filename: task.filename.clone(),
+            connection_key: task.connection_key.clone(),
+            priority: task.priority,
+            state: if task.paused {
+                QueueState::Paused
+            } else {
+                QueueState::Queued
+            },
+        });
+        running.chain(waiting).collect()
+    }
+
+    fn remove(&self, id: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.waiting.retain(|task| task.id != id);
+        inner.running.retain(|task| task.id != id);
+        inner.schedule();
+    }
+}
+
+impl QueueInner {
+    /// 有空闲名额时启动优先级最高的排队下载，优先级相同时取队列中靠前的
+    fn schedule(&mut self) {
+        while self.running.len() < self.max_concurrent {
+            let next = self
+                .waiting
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| !task.paused)
+                .max_by_key(|(index, task)| (task.priority, std::cmp::Reverse(*index)))
+                .map(|(index, _)| index);
+            let Some(index) = next else {
+                break;
+            };
+
+            let mut task = self.waiting.remove(index);
+            // 等待方已放弃（如命令被取消）时跳过
+            if let Some(ready) = task.ready.take() {
+                if ready.send(()).is_ok() {
+                    self.running.push(RunningTask {
+                        id: task.id,
+                        filename: task.filename,
+                        connection_key: task.connection_key,
+                        priority: task.priority,
+                        pause_requested: false,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Default for DownloadQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,181 @@
+// 未完成下载的续传状态
+// 下载开始时记录所属连接、远程文件大小和内容标识（ETag + 大小），中断后保留已写入的部分，
+// 续传时只接受同一连接，远程文件未变化则从已写入的位置继续，否则重新下载
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 状态文件名
+const STATE_FILE: &str = "resume-state.json";
+
+static RESUME_STATE: LazyLock<Mutex<Option<ResumeState>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 可续传的下载
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialDownload {
+    pub url: String,
+    pub filename: String,
+    pub save_path: String,
+    pub bytes_written: String, // 使用字符串表示大数字
+    pub total_size: String,    // 使用字符串表示大数字
+}
+
+/// 续传所需的信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeEntry {
+    /// 开始下载时的连接标识，同一路径在不同连接下是不同的文件
+    #[serde(default)]
+    pub connection_key: String,
+    pub url: String,
+    pub filename: String,
+    pub total_size: u64,
+    /// 下载开始时的内容标识，存储不提供 ETag 时只按大小判断
+    pub content_key: Option<String>,
+    /// 最近一次中断时已写入的字节数
+    pub bytes_written: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ResumeState {
+    /// 保存路径 -> 续传信息
+    entries: HashMap<PathBuf, ResumeEntry>,
+}
+
+/// 下载开始时记录续传信息，大小未知的下载无法续传
+pub fn begin(save_path: &Path, entry: ResumeEntry) {
+    if entry.total_size == 0 {
+        return;
+    }
+    update(|state| {
+        state.entries.insert(save_path.to_path_buf(), entry);
+    });
+}
+
+/// 下载中断时记录已写入的字节数
+pub fn interrupt(save_path: &Path) {
+    let bytes_written = written_len(save_path);
+    update(|state| {
+        if let Some(entry) = state.entries.get_mut(save_path) {
+            entry.bytes_written = bytes_written;
+        }
+    });
+}
+
+/// 下载完成或放弃续传时移除记录
+pub fn finish(save_path: &Path) {
+    update(|state| {
+        state.entries.remove(save_path);
+    });
+}
+
+/// 移除部分文件超过 max_age 未写入的续传记录（只处理 filter 选中的保存路径），部分文件本身保留
+pub fn forget_stale(max_age: Duration, filter: impl Fn(&Path) -> bool) {
+    let now = SystemTime::now();
+    update(|state| {
+        state.entries.retain(|path, _| {
+            if !filter(path) {
+                return true;
+            }
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| {
+                    now.duration_since(modified)
+                        .map_or(true, |age| age <= max_age)
+                })
+        });
+    });
+}
+
+/// 获取指定保存路径的续传信息
+pub fn get(save_path: &Path) -> Option<ResumeEntry> {
+    let mut guard = RESUME_STATE.lock().ok()?;
+    guard
+        .get_or_insert_with(ResumeState::load)
+        .entries
+        .get(save_path)
+        .cloned()
+}
+
+/// 列出指定连接下本地部分文件仍然存在的可续传下载
+pub fn list_for_connection(connection_key: &str) -> Vec<PartialDownload> {
+    collect(|entry| entry.connection_key == connection_key)
+}
+
+/// 列出本地部分文件仍然存在的可续传下载
+pub fn list() -> Vec<PartialDownload> {
+    collect(|_| true)
+}
+
+fn collect(filter: impl Fn(&ResumeEntry) -> bool) -> Vec<PartialDownload> {
+    let Ok(mut guard) = RESUME_STATE.lock() else {
+        return Vec::new();
+    };
+    let state = guard.get_or_insert_with(ResumeState::load);
+
+    let before = state.entries.len();
+    state.entries.retain(|path, _| path.is_file());
+    if state.entries.len() != before {
+        state.save();
+    }
+
+    state
+        .entries
+        .iter()
+        .filter(|(_, entry)| filter(entry))
+        .map(|(path, entry)| PartialDownload {
+            url: entry.url.clone(),
+            filename: entry.filename.clone(),
+            save_path: path.display().to_string(),
+            bytes_written: written_len(path).to_string(),
+            total_size: entry.total_size.to_string(),
+        })
+        .collect()
+}
+
+/// 本地部分文件的实际长度，以此作为续传起点
+pub fn written_len(save_path: &Path) -> u64 {
+    std::fs::metadata(save_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0)
+}
+
+fn update(f: impl FnOnce(&mut ResumeState)) {
+    let Ok(mut guard) = RESUME_STATE.lock() else {
+        return;
+    };
+    let state = guard.get_or_insert_with(ResumeState::load);
+    f(state);
+    state.save();
+}
+
+impl ResumeState {
+    fn state_path() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("downloads")?.join(STATE_FILE))
+    }
+
+    fn load() -> Self {
+        Self::state_path()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = Self::state_path().and_then(|path| {
+            let data = serde_json::to_vec(self)
+                .map_err(|e| format!("Failed to serialize resume state: {}", e))?;
+            persist::write_atomic(path, data)
+                .map_err(|e| format!("Failed to write resume state: {}", e))
+        });
+        if let Err(e) = result {
+            log::warn!("保存下载续传状态失败: {}", e);
+        }
+    }
+}
@@ -8,26 +8,59 @@ pub struct DownloadRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`）
+    pub id: String,
     pub filename: String,
     pub downloaded: u64,
     pub total_size: u64,
     pub progress: u32,
+    /// 最近 5 秒的平均速度（字节/秒）
+    pub speed: u64,
+    /// 按当前速度估算的剩余秒数
+    pub eta_seconds: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadStarted {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`）
+    pub id: String,
     pub filename: String,
     pub total_size: u64,
 }
 
+/// 下载进入队列或被暂停
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadQueueEvent {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`）
+    pub id: String,
+    pub filename: String,
+}
+
+/// 目录下载的汇总进度，每个文件结束时发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryDownloadProgress {
+    /// 目录下载的任务标识（远程目录路径），取消时传入
+    pub id: String,
+    pub directory: String,
+    pub total_files: u64,
+    pub completed_files: u64,
+    pub failed_files: u64,
+    pub total_bytes: u64,
+    pub completed_bytes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadCompleted {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`）
+    pub id: String,
     pub filename: String,
     pub file_path: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadError {
+    /// 下载任务标识（下载地址，压缩包内文件为 `压缩包路径!条目路径`）
+    pub id: String,
     pub filename: String,
     pub error: String,
 }
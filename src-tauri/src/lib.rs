@@ -1,6 +1,13 @@
 mod archive; // 压缩包处理功能
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench_support; // 基准测试入口
 pub mod commands;
+mod connections; // 连接配置管理
+mod dataset; // 数据集维护工具
 mod download; // 下载管理功能
+mod media; // 媒体缩略图和流式播放
+mod preview; // 内容预览管线
 mod storage;
 mod utils; // 通用工具模块 // Tauri 命令模块 - 公开以便外部访问
 
@@ -22,55 +29,16 @@ struct FrontendState {
     pending_files: Vec<String>,
 }
 
-// 创建文件查看窗口的内部函数
-async fn create_file_viewer_window(
-    app: tauri::AppHandle,
-    file_path: String,
-) -> Result<String, String> {
-    use tauri::{WebviewUrl, WebviewWindowBuilder};
-
-    // 为每个文件创建唯一的窗口标签
-    let window_label = format!(
-        "file-viewer-{}",
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis()
-    );
-
-    // 获取文件名作为窗口标题
-    let file_name = std::path::Path::new(&file_path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("File Viewer");
-
-    // 创建新窗口，URL 参数传递文件路径
-    let encoded_path = urlencoding::encode(&file_path);
-    let window_url = format!("/?mode=file-viewer&file={}", encoded_path);
-
-    match WebviewWindowBuilder::new(&app, &window_label, WebviewUrl::App(window_url.into()))
-        .title(file_name) // 只显示文件名
-        .inner_size(1200.0, 800.0) // 与主窗口保持一致
-        .min_inner_size(400.0, 600.0) // 与主窗口保持一致
-        .build()
-    {
-        Ok(_window) => {
-            // 窗口创建成功，文件路径已通过 URL 传递
-            Ok(window_label)
-        }
-        Err(e) => Err(format!("Failed to create window: {}", e)),
-    }
-}
-
 // 处理文件打开请求的辅助函数
 fn handle_file_open_request(app: &tauri::AppHandle, file_path: String) {
     // 检查前端是否就绪
     if let Ok(mut state) = FRONTEND_STATE.lock() {
         if state.is_ready {
-            // 前端已就绪，创建独立的文件查看窗口
+            // 前端已就绪，在文件查看窗口中打开（优先复用已有窗口）
             let app_handle = app.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = create_file_viewer_window(app_handle, file_path).await {
+                let file = utils::viewer_tabs::ViewerFile::local(&file_path);
+                if let Err(e) = utils::viewer_tabs::open(&app_handle, file, false).await {
                     eprintln!("Failed to create file viewer window: {}", e);
                 }
             });
@@ -83,6 +51,12 @@ fn handle_file_open_request(app: &tauri::AppHandle, file_path: String) {
 
 // 处理前端就绪事件的辅助函数
 fn handle_frontend_ready(app: &tauri::AppHandle) {
+    if let Some(report) = utils::self_check::last_report() {
+        if let Err(e) = app.emit("app-self-check", &report) {
+            log::warn!("发送自检结果失败: {}", e);
+        }
+    }
+
     if let Ok(mut state) = FRONTEND_STATE.lock() {
         state.is_ready = true;
 
@@ -109,8 +83,50 @@ pub fn create_specta_builder() -> Builder<tauri::Wry> {
         storage_connect,
         storage_disconnect,
         storage_list,
+        storage_restore_object,
+        storage_list_object_versions,
+        storage_list_revisions,
+        huggingface_get_dataset_info,
+        huggingface_list_parquet_splits,
+        storage_detect_mime,
+        storage_get_metadata,
+        generate_thumbnail,
+        storage_upload,
+        storage_upload_cancel,
+        storage_delete,
+        storage_rename,
+        storage_mkdir,
+        storage_compute_stats,
+        storage_compute_stats_cancel,
+        storage_watch_start,
+        storage_watch_stop,
+        storage_get_capabilities,
+        storage_sso_open,
+        storage_sso_capture,
+        storage_sso_clear,
+        storage_get_visibility_settings,
+        storage_set_visibility_settings,
+        storage_get_http_pool_settings,
+        storage_set_http_pool_settings,
+        // 连接配置管理命令
+        connection_save,
+        connection_list,
+        connection_delete,
+        connection_test,
+        connection_connect,
         // 下载管理命令
         download_start,
+        download_directory,
+        download_resume,
+        download_list_resumable,
+        download_pause,
+        download_resume_task,
+        download_reorder,
+        download_set_max_concurrent,
+        download_get_queue,
+        download_list_tasks,
+        download_find_existing,
+        download_to_memory,
         download_cancel,
         download_cancel_all,
         download_extract_file,
@@ -119,11 +135,78 @@ pub fn create_specta_builder() -> Builder<tauri::Wry> {
         system_select_file,
         // 压缩包处理命令（统一接口）
         archive_get_file_info,
+        archive_get_entry_thumbnail,
+        archive_search,
+        archive_search_cancel,
+        archive_extract_all,
+        archive_extract_cancel,
+        archive_preview_pause,
+        archive_preview_resume,
+        archive_preview_cancel,
+        archive_get_settings,
+        archive_set_settings,
+        // 数据集维护命令
+        dataset_rename_preview,
+        dataset_rename_apply,
+        dataset_rename_undo,
+        dataset_find_duplicates,
+        dataset_chunk_signature,
+        dataset_compare_signatures,
+        dataset_estimate_operation,
+        dataset_confirm_operation,
+        dataset_verify_integrity,
+        dataset_get_ignore_rules,
+        dataset_set_ignore_rules,
+        dataset_list_flat,
+        dataset_compute_facets,
+        dataset_index_build,
+        dataset_index_refresh,
+        dataset_index_status,
+        dataset_index_search,
+        dataset_index_search_content,
+        dataset_index_get_content_settings,
+        dataset_index_set_content_settings,
+        dataset_index_purge,
+        dataset_scan_pii,
+        dataset_detect_licenses,
+        dataset_budget_list,
+        dataset_budget_save,
+        dataset_budget_delete,
+        dataset_budget_check,
+        // 搜索历史命令
+        search_history_list,
+        search_history_pin,
+        search_history_delete,
+        search_history_clear,
+        search_history_rerun,
+        // 快速打开命令
+        quick_open_search,
+        // 内容预览命令
+        preview_run,
+        preview_list_sheets,
+        preview_read_sheet_range,
+        dataset_preview_table,
+        parquet_get_metadata,
+        parquet_read_rows,
+        arrow_get_schema,
+        arrow_read_batch,
+        jsonl_read_records,
+        file_search_content,
+        file_search_cancel,
         // 插件发现命令
         plugin_discover,
         // 插件文件加载命令
         load_plugin_file,
         plugin_check_file_exists,
+        // 插件文件随机读取命令
+        file_open,
+        file_pread,
+        file_close,
+        plugin_storage_read,
+        plugin_viewer_attach,
+        plugin_viewer_detach,
+        vfs_stat,
+        vfs_read,
         // 插件管理命令
         plugin_install,
         plugin_uninstall,
@@ -133,7 +216,24 @@ pub fn create_specta_builder() -> Builder<tauri::Wry> {
         plugin_check_updates,
         plugin_update,
         // 窗口主题设置命令
-        system_set_theme
+        system_set_theme,
+        // 缓存清理命令
+        system_get_retention_policies,
+        system_set_retention_policies,
+        system_cleanup_now,
+        cache_clear,
+        // 应用数据自检命令
+        system_get_self_check_report,
+        // 网络配置命令
+        system_get_network_config,
+        system_set_network_config,
+        // 文件查看窗口命令
+        system_get_viewer_window_settings,
+        system_set_viewer_window_settings,
+        system_set_window_zoom,
+        viewer_open_file,
+        viewer_get_tabs,
+        viewer_set_tabs
     ])
 }
 
@@ -168,12 +268,25 @@ pub fn run() {
     let tauri_builder = tauri_builder
         .invoke_handler(builder.invoke_handler())
         .setup(|app| {
+            // 检查状态文件是否损坏，在任何状态被读取之前同步完成，避免与懒加载同时读写同一文件；
+            // 结果在前端就绪后通过 app-self-check 事件发送
+            let report = utils::self_check::run();
+            if !report.issues.is_empty() {
+                log::warn!("自检发现 {} 个损坏的状态文件", report.issues.len());
+            }
+
             // 监听前端就绪事件
             let app_handle = app.handle().clone();
             app.listen("frontend-ready", move |_event| {
                 handle_frontend_ready(&app_handle);
             });
 
+            // 按保留策略定时清理缓存和临时文件
+            utils::retention::start_scheduler();
+
+            // 定时检查当前连接的容量预算，超过阈值时发送 dataset-budget-alert 事件
+            dataset::size_budget::start_scheduler(app.handle().clone());
+
             // 处理命令行参数，支持文件关联
             let args: Vec<String> = std::env::args().collect();
             if args.len() > 1 {
@@ -213,6 +326,54 @@ pub fn run() {
         },
     );
 
+    // 图片缩略图协议：thumbnail://localhost/<编码后的文件路径>?size=<边长>
+    let tauri_builder = tauri_builder.register_asynchronous_uri_scheme_protocol(
+        "thumbnail",
+        move |_app, request, responder| {
+            let uri = request.uri().clone();
+
+            tauri::async_runtime::spawn(async move {
+                match media::thumbnails::handle_thumbnail_request(&uri).await {
+                    Ok(response) => responder.respond(response),
+                    Err(e) => {
+                        log::warn!("生成缩略图失败 {}: {}", uri, e);
+                        let error_response = tauri::http::Response::builder()
+                            .status(404)
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body(e.into_bytes())
+                            .unwrap();
+                        responder.respond(error_response);
+                    }
+                }
+            });
+        },
+    );
+
+    // 音视频流式播放协议：media-stream://localhost/<编码后的文件路径>，支持 Range 请求
+    let tauri_builder = tauri_builder.register_asynchronous_uri_scheme_protocol(
+        "media-stream",
+        move |_app, request, responder| {
+            let uri = request.uri().clone();
+            let method = request.method().as_str().to_string();
+            let headers = request.headers().clone();
+
+            tauri::async_runtime::spawn(async move {
+                match media::stream::handle_media_stream_request(&uri, &method, &headers).await {
+                    Ok(response) => responder.respond(response),
+                    Err(e) => {
+                        log::warn!("媒体流请求失败 {}: {}", uri, e);
+                        let error_response = ProtocolHandler::response_builder()
+                            .status(404)
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body(e.into_bytes())
+                            .unwrap();
+                        responder.respond(error_response);
+                    }
+                }
+            });
+        },
+    );
+
     tauri_builder
         .build(tauri::generate_context!())
         .expect("error building tauri application")
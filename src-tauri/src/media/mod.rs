@@ -0,0 +1,14 @@
+// 媒体文件处理
+// 图片缩略图的生成、缓存和 thumbnail:// 协议，音视频的 media-stream:// 协议
+
+pub mod stream;
+pub mod thumbnails;
+
+/// 从自定义协议地址中取出编码后的文件路径
+/// 地址格式为 `<协议>://localhost/<编码后的文件路径>`（Windows 为 `http://<协议>.localhost/...`）
+pub fn decode_uri_path(uri: &tauri::http::Uri) -> Result<String, String> {
+    let encoded_path = uri.path().strip_prefix('/').unwrap_or(uri.path());
+    urlencoding::decode(encoded_path)
+        .map(|path| path.into_owned())
+        .map_err(|e| format!("Invalid path in {}: {}", uri, e))
+}
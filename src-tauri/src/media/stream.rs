@@ -0,0 +1,208 @@
+// 音视频流式播放
+// media-stream:// 协议把 <video> / <audio> 发出的 HTTP Range 请求转换为存储客户端的范围读取，
+// 存储中的文件和压缩包内的条目（`archive.zip!videos/a.mp4`）都可以直接拖动进度播放
+
+use std::collections::VecDeque;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::archive::nested::{resolve_nested_entry, ArchiveEntryClient};
+use crate::media::decode_uri_path;
+use crate::storage::get_storage_manager;
+use crate::storage::manager::current_connection_key;
+use crate::storage::traits::StorageClient;
+use crate::storage::vfs::split_archive_entry;
+use crate::utils::protocol_handler::ProtocolHandler;
+
+/// 单次响应最多返回的字节数，开放式范围（bytes=N-）按该大小分段返回
+const MAX_CHUNK_SIZE: u64 = 8 * 1024 * 1024; // 8MB
+
+/// 缓存的压缩包条目客户端数量上限
+const MAX_CACHED_ENTRY_CLIENTS: usize = 4;
+
+/// 最近播放的压缩包条目客户端（连接标识 + 路径，客户端），最近使用的在末尾；
+/// 同一条目的多次 Range 请求复用条目大小和预读的数据块，不必每次从头解压
+static ENTRY_CLIENTS: LazyLock<Mutex<VecDeque<(String, Arc<ArchiveEntryClient>)>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// 处理 media-stream:// 协议请求
+/// 地址格式为 `media-stream://localhost/<编码后的文件路径>`（Windows 为 `http://media-stream.localhost/...`），
+/// 文件从当前连接的存储中读取
+pub async fn handle_media_stream_request(
+    uri: &tauri::http::Uri,
+    method: &str,
+    headers: &tauri::http::HeaderMap,
+) -> Result<tauri::http::Response<Vec<u8>>, String> {
+    let path = decode_uri_path(uri)?;
+    let content_type = ProtocolHandler::get_content_type(&path);
+
+    let builder = ProtocolHandler::response_builder()
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Access-Control-Allow-Methods", "GET, HEAD, OPTIONS")
+        .header("Access-Control-Allow-Headers", "Range, Content-Type")
+        .header(
+            "Access-Control-Expose-Headers",
+            "Content-Length, Content-Range, Accept-Ranges",
+        );
+    let builder = match method {
+        "GET" | "HEAD" => builder,
+        "OPTIONS" => {
+            return builder
+                .status(200)
+                .header("Access-Control-Max-Age", "86400")
+                .body(Vec::new())
+                .map_err(|e| format!("Failed to build response: {}", e));
+        }
+        _ => {
+            return builder
+                .status(405)
+                .header("Allow", "GET, HEAD, OPTIONS")
+                .body(Vec::new())
+                .map_err(|e| format!("Failed to build response: {}", e));
+        }
+    };
+
+    let client = get_storage_manager()
+        .await
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client available".to_string())?;
+    let (client, path) = stream_target(client, &path).await;
+
+    let size = client
+        .get_file_size(&path)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let builder = builder
+        .header("Content-Type", content_type)
+        .header("Accept-Ranges", "bytes");
+
+    if method == "HEAD" {
+        return builder
+            .status(200)
+            .header("Content-Length", size.to_string())
+            .body(Vec::new())
+            .map_err(|e| format!("Failed to build response: {}", e));
+    }
+
+    // 未带 Range 时返回第一段，播放器随后按 Range 请求其余部分
+    let range = headers.get("Range").and_then(|value| value.to_str().ok());
+    let (start, end) = match range {
+        Some(range) => match parse_range(range, size) {
+            Some(range) => range,
+            None => {
+                return builder
+                    .status(416)
+                    .header("Content-Range", format!("bytes */{}", size))
+                    .body(Vec::new())
+                    .map_err(|e| format!("Failed to build response: {}", e));
+            }
+        },
+        None if size == 0 => {
+            return builder
+                .status(200)
+                .header("Content-Length", "0")
+                .body(Vec::new())
+                .map_err(|e| format!("Failed to build response: {}", e));
+        }
+        None => (0, size - 1),
+    };
+    let end = end.min(start + MAX_CHUNK_SIZE - 1);
+
+    let data = client
+        .read_file_range(&path, start, end - start + 1)
+        .await
+        .map_err(|e| format!("Failed to read file range: {}", e))?;
+    if data.is_empty() {
+        return Err(format!("No data returned for range {}-{}", start, end));
+    }
+    let end = start + data.len() as u64 - 1;
+
+    let builder = if range.is_none() && data.len() as u64 == size {
+        builder.status(200)
+    } else {
+        builder
+            .status(206)
+            .header("Content-Range", format!("bytes {}-{}/{}", start, end, size))
+    };
+    builder
+        .header("Content-Length", data.len().to_string())
+        .body(data)
+        .map_err(|e| format!("Failed to build response: {}", e))
+}
+
+/// 压缩包内的条目包装为单个文件，范围读取转换为条目的预览读取
+async fn stream_target(
+    client: Arc<dyn StorageClient>,
+    path: &str,
+) -> (Arc<dyn StorageClient>, String) {
+    let Some((archive_path, entry_path)) = split_archive_entry(path) else {
+        return (client, path.to_string());
+    };
+
+    let key = format!("{}\0{}", current_connection_key().await, path);
+    {
+        let mut clients = ENTRY_CLIENTS.lock().unwrap();
+        if let Some(index) = clients.iter().position(|(cached, _)| *cached == key) {
+            let cached = clients.remove(index).unwrap();
+            let entry_client = cached.1.clone();
+            clients.push_back(cached);
+            let path = entry_client.path().to_string();
+            return (entry_client, path);
+        }
+    }
+
+    let (client, archive_path, entry_path) = resolve_nested_entry(
+        client,
+        archive_path.to_string(),
+        entry_path.trim_start_matches('/').to_string(),
+        None,
+    );
+    let entry_client = Arc::new(ArchiveEntryClient::new(
+        client,
+        archive_path,
+        entry_path,
+        None,
+    ));
+    let path = entry_client.path().to_string();
+
+    let mut clients = ENTRY_CLIENTS.lock().unwrap();
+    clients.retain(|(cached, _)| *cached != key);
+    clients.push_back((key, entry_client.clone()));
+    while clients.len() > MAX_CACHED_ENTRY_CLIENTS {
+        clients.pop_front();
+    }
+    (entry_client, path)
+}
+
+/// 解析 Range 头，返回闭区间 [start, end]；只支持单个范围，范围无效或超出文件大小时返回 None
+fn parse_range(range: &str, size: u64) -> Option<(u64, u64)> {
+    if size == 0 {
+        return None;
+    }
+    let spec = range.trim().strip_prefix("bytes=")?;
+    let (start, end) = spec.split(',').next()?.trim().split_once('-')?;
+
+    let (start, end) = if start.is_empty() {
+        // bytes=-N 表示最后 N 个字节
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 {
+            return None;
+        }
+        (size.saturating_sub(suffix), size - 1)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() {
+            size - 1
+        } else {
+            end.parse::<u64>().ok()?.min(size - 1)
+        };
+        (start, end)
+    };
+
+    if start > end {
+        None
+    } else {
+        Some((start, end))
+    }
+}
@@ -0,0 +1,265 @@
+// 图片缩略图
+// 读取存储中的图片文件或压缩包内的图片条目并缩放为 JPEG，同时生成的数量有上限，
+// 结果按连接 + 文件版本（ETag、修改时间、大小）+ 路径缓存到磁盘；网格浏览时前端通过 thumbnail:// 协议直接加载
+
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use tokio::sync::Semaphore;
+
+use crate::archive::handlers::ArchiveHandler;
+use crate::media::decode_uri_path;
+use crate::storage::get_storage_manager;
+use crate::storage::manager::current_connection_key;
+use crate::storage::traits::StorageClient;
+use crate::storage::vfs::split_archive_entry;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist::write_atomic;
+
+/// 默认缩略图边长
+const DEFAULT_MAX_DIMENSION: u32 = 256;
+
+/// 缩略图边长上限
+const MAX_DIMENSION: u32 = 1024;
+
+/// 可生成缩略图的原图大小上限
+const MAX_SOURCE_SIZE: u32 = 32 * 1024 * 1024; // 32MB
+
+/// 同时生成的缩略图数量上限，避免滚动浏览时占满网络和 CPU
+const MAX_CONCURRENT_THUMBNAILS: usize = 4;
+
+/// 缩略图 JPEG 质量
+const JPEG_QUALITY: u8 = 80;
+
+static THUMBNAIL_PERMITS: LazyLock<Arc<Semaphore>> =
+    LazyLock::new(|| Arc::new(Semaphore::new(MAX_CONCURRENT_THUMBNAILS)));
+
+/// 支持生成缩略图的图片扩展名
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "tif", "tiff"];
+
+/// 文件或条目是否为支持生成缩略图的图片
+pub fn is_supported_image(path: &str) -> bool {
+    path.rsplit_once('.')
+        .map(|(_, ext)| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 生成存储中图片文件的缩略图，返回 JPEG 数据
+/// 路径指向压缩包内的条目（`archive.zip!images/a.png`）时从压缩包中读取
+pub async fn file_thumbnail(
+    client: Arc<dyn StorageClient>,
+    path: &str,
+    max_dimension: Option<u32>,
+) -> Result<Vec<u8>, String> {
+    if let Some((archive_path, entry_path)) = split_archive_entry(path) {
+        let entry_path = entry_path.trim_start_matches('/');
+        return archive_entry_thumbnail(client, archive_path, entry_path, max_dimension).await;
+    }
+
+    if !is_supported_image(path) {
+        return Err(format!("Unsupported image format: {}", path));
+    }
+    let max_dimension = max_dimension
+        .unwrap_or(DEFAULT_MAX_DIMENSION)
+        .clamp(1, MAX_DIMENSION);
+
+    let cache_path = cache_path(&*client, path, "", max_dimension).await?;
+    cached_thumbnail(cache_path, max_dimension, || async {
+        // 本地文件直接读取，其他存储按文件大小一次范围读取
+        if let Some(local_path) = client.local_path(path) {
+            let size = tokio::fs::metadata(&local_path)
+                .await
+                .map_err(|e| format!("Failed to read file metadata: {}", e))?
+                .len();
+            check_source_size(size)?;
+            return tokio::fs::read(&local_path)
+                .await
+                .map_err(|e| format!("Failed to read file: {}", e));
+        }
+
+        let size = client
+            .get_file_size(path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        check_source_size(size)?;
+        client
+            .read_file_range(path, 0, size)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))
+    })
+    .await
+}
+
+/// 生成压缩包内图片条目的缩略图，返回 JPEG 数据
+pub async fn archive_entry_thumbnail(
+    client: Arc<dyn StorageClient>,
+    archive_path: &str,
+    entry_path: &str,
+    max_dimension: Option<u32>,
+) -> Result<Vec<u8>, String> {
+    if !is_supported_image(entry_path) {
+        return Err(format!("Unsupported image format: {}", entry_path));
+    }
+    let max_dimension = max_dimension
+        .unwrap_or(DEFAULT_MAX_DIMENSION)
+        .clamp(1, MAX_DIMENSION);
+
+    let cache_path = cache_path(&*client, archive_path, entry_path, max_dimension).await?;
+    cached_thumbnail(cache_path, max_dimension, || async {
+        let filename = archive_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(archive_path)
+            .to_string();
+        let preview = ArchiveHandler::new()
+            .get_file_preview_with_client(
+                client.clone(),
+                archive_path.to_string(),
+                filename,
+                entry_path.to_string(),
+                Some(MAX_SOURCE_SIZE),
+                None,
+                None::<fn(u64, u64)>,
+                None,
+            )
+            .await?;
+        if preview.is_truncated {
+            return Err(format!(
+                "Image too large for thumbnail: {} bytes",
+                preview.total_size
+            ));
+        }
+        Ok(preview.content)
+    })
+    .await
+}
+
+/// 处理 thumbnail:// 协议请求
+/// 地址格式为 `thumbnail://localhost/<编码后的文件路径>?size=<边长>`（Windows 为 `http://thumbnail.localhost/...`），
+/// 文件路径与 generate_thumbnail 命令相同，从当前连接的存储中读取
+pub async fn handle_thumbnail_request(
+    uri: &tauri::http::Uri,
+) -> Result<tauri::http::Response<Vec<u8>>, String> {
+    let path = decode_uri_path(uri)?;
+    let max_dimension = uri.query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("size="))
+            .and_then(|size| size.parse::<u32>().ok())
+    });
+
+    let client = get_storage_manager()
+        .await
+        .read()
+        .await
+        .get_current_client()
+        .ok_or_else(|| "No storage client available".to_string())?;
+    let thumbnail = file_thumbnail(client, &path, max_dimension).await?;
+
+    tauri::http::Response::builder()
+        .status(200)
+        .header("Content-Type", "image/jpeg")
+        .header("Content-Length", thumbnail.len().to_string())
+        // 地址中不含文件版本和连接，文件更新或切换连接后同一地址对应不同的图片，不能让 WebView 长期缓存；
+        // 重复请求命中磁盘缓存
+        .header("Cache-Control", "no-cache")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(thumbnail)
+        .map_err(|e| format!("Failed to build response: {}", e))
+}
+
+/// 优先返回磁盘缓存，否则在并发上限内读取原图、生成并写入缓存
+async fn cached_thumbnail<F, Fut>(
+    cache_path: PathBuf,
+    max_dimension: u32,
+    load: F,
+) -> Result<Vec<u8>, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    if let Ok(data) = tokio::fs::read(&cache_path).await {
+        return Ok(data);
+    }
+
+    let _permit = THUMBNAIL_PERMITS
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to acquire thumbnail permit: {}", e))?;
+
+    // 等待期间可能已有相同的请求生成了缩略图
+    if let Ok(data) = tokio::fs::read(&cache_path).await {
+        return Ok(data);
+    }
+
+    let source = load().await?;
+    let thumbnail = tokio::task::spawn_blocking(move || encode_thumbnail(&source, max_dimension))
+        .await
+        .map_err(|e| format!("Thumbnail task failed: {}", e))??;
+
+    // 先写临时文件再替换，并发的读取不会读到写了一半的缓存
+    let data = thumbnail.clone();
+    let path = cache_path.clone();
+    let written = tokio::task::spawn_blocking(move || write_atomic(&path, &data))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result.map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        log::warn!("写入缩略图缓存失败 {}: {}", cache_path.display(), e);
+    }
+    Ok(thumbnail)
+}
+
+fn check_source_size(size: u64) -> Result<(), String> {
+    if size > MAX_SOURCE_SIZE as u64 {
+        return Err(format!("Image too large for thumbnail: {} bytes", size));
+    }
+    Ok(())
+}
+
+/// 缓存路径，按连接和文件的 ETag、修改时间、大小区分版本，无法获取元数据时只按大小区分；
+/// 普通文件的条目路径为空
+async fn cache_path(
+    client: &dyn StorageClient,
+    file_path: &str,
+    entry_path: &str,
+    max_dimension: u32,
+) -> Result<PathBuf, String> {
+    let version = match client.get_metadata(file_path).await {
+        Ok(metadata) => metadata.cache_version(),
+        Err(_) => client
+            .get_file_size(file_path)
+            .await
+            .map(|size| format!("size:{}", size))
+            .map_err(|e| format!("Failed to get file size: {}", e))?,
+    };
+
+    let connection_key = current_connection_key().await;
+    let mut hasher = Sha256::new();
+    for part in [
+        connection_key.as_str(),
+        file_path,
+        version.as_str(),
+        entry_path,
+        max_dimension.to_string().as_str(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let name = format!("{}.jpg", hex::encode(hasher.finalize()));
+
+    Ok(get_app_data_subdir("thumbnails")?.join(name))
+}
+
+fn encode_thumbnail(data: &[u8], max_dimension: u32) -> Result<Vec<u8>, String> {
+    let image =
+        image::load_from_memory(data).map_err(|e| format!("Failed to decode image: {}", e))?;
+    let thumbnail = image.thumbnail(max_dimension, max_dimension).to_rgb8();
+
+    let mut output = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, JPEG_QUALITY)
+        .encode_image(&thumbnail)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(output)
+}
@@ -0,0 +1,245 @@
+use regex::bytes::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::preview::source::PreviewSource;
+use crate::storage::traits::StorageClient;
+use crate::utils::mime::is_binary_content;
+
+/// 默认最多返回的匹配数量
+const DEFAULT_MAX_RESULTS: u32 = 1000;
+
+/// 匹配数量上限
+const HARD_MAX_RESULTS: u32 = 100_000;
+
+/// 超过该长度仍没有换行时按该长度切分，跨越切分点的匹配无法找到
+const MAX_LINE_BYTES: usize = 1024 * 1024; // 1MB
+
+/// 匹配行在结果中保留的最大字符数
+const MAX_LINE_CHARS: usize = 500;
+
+/// 文件内容搜索请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSearchRequest {
+    /// 搜索标识，用于取消搜索和区分进度事件
+    pub search_id: String,
+    pub path: String,
+    /// 压缩包内的条目路径，为空时搜索文件本身
+    pub entry_path: Option<String>,
+    pub query: String,
+    /// 是否按正则表达式匹配，默认按字面文本匹配
+    pub regex: Option<bool>,
+    /// 是否区分大小写，默认不区分
+    pub case_sensitive: Option<bool>,
+    /// 最多返回的匹配数量，默认 1000，上限 100000
+    pub max_results: Option<u32>,
+}
+
+/// 一处匹配
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSearchMatch {
+    /// 匹配所在行（从 1 开始）
+    pub line: String, // 使用字符串表示大数字
+    /// 匹配在文件中的字节偏移
+    pub offset: String,
+    /// 匹配在行内的字节偏移
+    pub column: u32,
+    /// 匹配的字节长度
+    pub length: u32,
+    /// 匹配行的内容（过长时截断）
+    pub text: String,
+}
+
+/// 搜索进度，每读取一块数据发送一次
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSearchProgress {
+    pub search_id: String,
+    pub bytes_scanned: String, // 使用字符串表示大数字
+    pub total_bytes: String,
+    pub matches: u32,
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSearchResult {
+    pub search_id: String,
+    pub matches: Vec<FileSearchMatch>,
+    pub bytes_scanned: String, // 使用字符串表示大数字
+    pub total_bytes: String,
+    /// 是否因达到匹配数量上限而提前结束
+    pub truncated: bool,
+    pub cancelled: bool,
+}
+
+/// 远程文件内容搜索：顺序分块读取文件或压缩包条目，逐行匹配，不需要下载整个文件
+pub struct ContentSearcher {
+    pattern: Regex,
+    max_results: usize,
+    matches: Vec<FileSearchMatch>,
+    /// 当前行的行号
+    line: u64,
+    /// 当前行的起始偏移
+    line_start: u64,
+    /// 当前行是否已按长度上限切分过
+    split: bool,
+    truncated: bool,
+}
+
+impl ContentSearcher {
+    pub async fn search<F>(
+        client: Arc<dyn StorageClient>,
+        request: FileSearchRequest,
+        mut cancel_rx: broadcast::Receiver<()>,
+        on_progress: F,
+    ) -> Result<FileSearchResult, String>
+    where
+        F: Fn(FileSearchProgress),
+    {
+        if request.query.is_empty() {
+            return Err("Search query is required".to_string());
+        }
+        let pattern = if request.regex.unwrap_or(false) {
+            request.query.clone()
+        } else {
+            regex::escape(&request.query)
+        };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(!request.case_sensitive.unwrap_or(false))
+            .build()
+            .map_err(|e| format!("Invalid search pattern: {}", e))?;
+
+        let mut searcher = Self {
+            pattern,
+            max_results: request
+                .max_results
+                .unwrap_or(DEFAULT_MAX_RESULTS)
+                .clamp(1, HARD_MAX_RESULTS) as usize,
+            matches: Vec::new(),
+            line: 1,
+            line_start: 0,
+            split: false,
+            truncated: false,
+        };
+
+        let source = PreviewSource::new(request.path.clone(), request.entry_path.clone());
+        let mut stream = source.open_stream(client).await?;
+        let mut offset = 0u64;
+        let mut total_size = 0u64;
+        // 上一块末尾不完整的行及其在文件中的偏移
+        let mut carry_offset = 0u64;
+        let mut carry: Vec<u8> = Vec::new();
+        let mut cancelled = false;
+
+        loop {
+            if !matches!(
+                cancel_rx.try_recv(),
+                Err(broadcast::error::TryRecvError::Empty)
+            ) {
+                cancelled = true;
+                break;
+            }
+
+            let Some(bytes) = stream.next_chunk().await else {
+                searcher.search_lines(&carry, carry_offset, true);
+                total_size = total_size.max(offset);
+                break;
+            };
+            let bytes = bytes?;
+            if offset == 0 && is_binary_content(&bytes) {
+                return Err("Binary files cannot be searched".to_string());
+            }
+            if carry.is_empty() {
+                carry_offset = offset;
+            }
+            offset += bytes.len() as u64;
+            // 部分压缩格式无法提前得知条目大小
+            total_size = stream.size().unwrap_or(0);
+
+            carry.extend_from_slice(&bytes);
+            let consumed = searcher.search_lines(&carry, carry_offset, false);
+            carry.drain(..consumed);
+            carry_offset += consumed as u64;
+
+            on_progress(FileSearchProgress {
+                search_id: request.search_id.clone(),
+                bytes_scanned: offset.to_string(),
+                total_bytes: total_size.to_string(),
+                matches: searcher.matches.len() as u32,
+            });
+            if searcher.truncated {
+                break;
+            }
+        }
+
+        Ok(FileSearchResult {
+            search_id: request.search_id,
+            matches: searcher.matches,
+            bytes_scanned: offset.to_string(),
+            total_bytes: total_size.to_string(),
+            truncated: searcher.truncated,
+            cancelled,
+        })
+    }
+
+    /// 匹配 buffer 中的完整行，返回已处理的字节数
+    /// at_end 为 true 时末尾没有换行的内容也作为一行处理
+    fn search_lines(&mut self, buffer: &[u8], base_offset: u64, at_end: bool) -> usize {
+        let mut start = 0usize;
+        while start < buffer.len() && !self.truncated {
+            let rest = &buffer[start..];
+            let (line, next) = match rest.iter().position(|b| *b == b'\n') {
+                Some(newline) => (&rest[..newline], start + newline + 1),
+                None if at_end => (rest, buffer.len()),
+                // 超长的行按上限切分，剩余部分仍属于同一行
+                None if rest.len() >= MAX_LINE_BYTES => {
+                    self.search_line(&rest[..MAX_LINE_BYTES], base_offset + start as u64);
+                    self.split = true;
+                    start += MAX_LINE_BYTES;
+                    continue;
+                }
+                None => break,
+            };
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            self.search_line(line, base_offset + start as u64);
+            self.line += 1;
+            self.split = false;
+            start = next;
+        }
+        start
+    }
+
+    /// 匹配一行或超长行中的一段，segment_offset 为该段在文件中的偏移
+    fn search_line(&mut self, line: &[u8], segment_offset: u64) {
+        if !self.split {
+            self.line_start = segment_offset;
+        }
+        let mut text = None;
+        for found in self.pattern.find_iter(line) {
+            if self.matches.len() >= self.max_results {
+                self.truncated = true;
+                return;
+            }
+            let text = text
+                .get_or_insert_with(|| {
+                    String::from_utf8_lossy(line)
+                        .chars()
+                        .take(MAX_LINE_CHARS)
+                        .collect::<String>()
+                })
+                .clone();
+            let offset = segment_offset + found.start() as u64;
+            self.matches.push(FileSearchMatch {
+                line: self.line.to_string(),
+                offset: offset.to_string(),
+                column: (offset - self.line_start) as u32,
+                length: found.len() as u32,
+                text,
+            });
+        }
+    }
+}
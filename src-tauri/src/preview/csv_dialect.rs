@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+
+/// 参与识别的候选分隔符，按优先级排列
+const DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+/// 候选引号字符
+const QUOTES: [char; 2] = ['"', '\''];
+
+/// 参与识别的最大记录数
+const SAMPLE_RECORDS: usize = 100;
+
+/// 参与识别的最大数据量
+const SAMPLE_BYTES: usize = 64 * 1024;
+
+/// CSV / TSV 方言
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvDialect {
+    pub delimiter: String,
+    pub quote: String,
+    /// 首行是否为表头
+    pub has_header: bool,
+    /// 数值的小数分隔符："." 或 ","（欧洲地区常见 1.234,56 写法）
+    pub decimal_separator: String,
+    /// "\n" 或 "\r\n"
+    pub line_terminator: String,
+}
+
+/// 数值字段的写法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum NumberStyle {
+    Integer,
+    DotDecimal,
+    CommaDecimal,
+}
+
+impl CsvDialect {
+    /// 根据文件开头的数据识别方言
+    /// truncated 为 true 时忽略末尾可能不完整的记录；数据无法识别为表格时返回 None
+    pub fn sniff(bytes: &[u8], truncated: bool) -> Option<Self> {
+        let sample = &bytes[..bytes.len().min(SAMPLE_BYTES)];
+        let text = String::from_utf8_lossy(sample);
+        let text = text.trim_start_matches('\u{feff}');
+        let text = if truncated || sample.len() < bytes.len() {
+            text.rsplit_once('\n').map(|(head, _)| head).unwrap_or(text)
+        } else {
+            text
+        };
+        if text.trim().is_empty() {
+            return None;
+        }
+
+        let (delimiter, quote, records) = Self::best_candidate(text)?;
+
+        Some(Self {
+            delimiter: delimiter.to_string(),
+            quote: quote.to_string(),
+            has_header: Self::detect_header(&records),
+            decimal_separator: Self::detect_decimal_separator(&records, delimiter).to_string(),
+            line_terminator: if text.contains("\r\n") { "\r\n" } else { "\n" }.to_string(),
+        })
+    }
+
+    /// 选择使各记录字段数最一致的分隔符和引号组合
+    fn best_candidate(text: &str) -> Option<(char, char, Vec<Vec<String>>)> {
+        let mut best: Option<(f64, char, char, Vec<Vec<String>>)> = None;
+
+        for delimiter in DELIMITERS {
+            for quote in QUOTES {
+                let (records, quoted_fields) =
+                    split_records(text, delimiter, quote, SAMPLE_RECORDS);
+                let score = consistency_score(&records) + quoted_fields as f64 * 1e-6;
+                if score <= 0.0 {
+                    continue;
+                }
+                // 分数相同时保留优先级更高的候选
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_score, ..)| score > *best_score)
+                {
+                    best = Some((score, delimiter, quote, records));
+                }
+            }
+        }
+
+        best.map(|(_, delimiter, quote, records)| (delimiter, quote, records))
+    }
+
+    /// 参考 Python csv.Sniffer：数据行为数值而首行不是数值的列投赞成票，反之投反对票
+    /// 没有数值列时，首行各字段非空且互不重复则视为表头
+    fn detect_header(records: &[Vec<String>]) -> bool {
+        let Some((header, rows)) = records.split_first() else {
+            return false;
+        };
+        if rows.is_empty() {
+            return false;
+        }
+
+        let mut votes = 0i32;
+        for (column, name) in header.iter().enumerate() {
+            let values: Vec<&str> = rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .collect();
+            if values.is_empty() || !values.iter().all(|v| number_style(v).is_some()) {
+                continue;
+            }
+            if number_style(name.trim()).is_some() {
+                votes -= 1;
+            } else {
+                votes += 1;
+            }
+        }
+
+        if votes != 0 {
+            return votes > 0;
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        header.iter().all(|name| {
+            let name = name.trim();
+            !name.is_empty() && number_style(name).is_none() && seen.insert(name)
+        })
+    }
+
+    /// 分隔符为逗号时小数点只能是 "."，否则按数值字段中两种写法的出现次数判断
+    fn detect_decimal_separator(records: &[Vec<String>], delimiter: char) -> char {
+        if delimiter == ',' {
+            return '.';
+        }
+
+        let (mut dot, mut comma) = (0usize, 0usize);
+        for value in records.iter().flatten() {
+            match number_style(value.trim()) {
+                Some(NumberStyle::DotDecimal) => dot += 1,
+                Some(NumberStyle::CommaDecimal) => comma += 1,
+                _ => {}
+            }
+        }
+
+        if comma > dot {
+            ','
+        } else {
+            '.'
+        }
+    }
+}
+
+/// 按分隔符和引号拆分最多 limit 条记录（引号内的分隔符和换行不拆分，"" 表示转义的引号）
+/// 返回 (记录, 带引号的字段数)
+pub(crate) fn split_records(
+    text: &str,
+    delimiter: char,
+    quote: char,
+    limit: usize,
+) -> (Vec<Vec<String>>, usize) {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted_fields = 0usize;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == quote {
+                if chars.peek() == Some(&quote) {
+                    field.push(quote);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            c if c == quote && field.is_empty() => {
+                in_quotes = true;
+                quoted_fields += 1;
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                    if records.len() >= limit {
+                        return (records, quoted_fields);
+                    }
+                } else {
+                    record.clear();
+                }
+            }
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    (records, quoted_fields)
+}
+
+/// 字段数等于众数的记录占比；字段数不超过 1 时不是有效的拆分
+fn consistency_score(records: &[Vec<String>]) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    for record in records {
+        *counts.entry(record.len()).or_insert(0usize) += 1;
+    }
+
+    match counts.into_iter().max_by_key(|(len, count)| (*count, *len)) {
+        Some((len, count)) if len > 1 => count as f64 / records.len() as f64,
+        _ => 0.0,
+    }
+}
+
+/// 识别数值写法：1234、-12.5、1,5、1.234,56、1,234.56
+pub(crate) fn number_style(value: &str) -> Option<NumberStyle> {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    if digits.is_empty()
+        || !digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+    {
+        return None;
+    }
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let last_dot = digits.rfind('.');
+    let last_comma = digits.rfind(',');
+    let (decimal, grouping, style) = match (last_dot, last_comma) {
+        (None, None) => return Some(NumberStyle::Integer),
+        (Some(dot), Some(comma)) if dot > comma => (dot, ',', NumberStyle::DotDecimal),
+        (Some(_), Some(comma)) => (comma, '.', NumberStyle::CommaDecimal),
+        (Some(dot), None) => (dot, ',', NumberStyle::DotDecimal),
+        (None, Some(comma)) => (comma, '.', NumberStyle::CommaDecimal),
+    };
+
+    let (integer, fraction) = digits.split_at(decimal);
+    let fraction = &fraction[1..];
+    if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // 整数部分可以带千位分隔符，分组必须是 3 位
+    let mut groups = integer.split(grouping);
+    let first = groups.next().unwrap_or_default();
+    let valid_integer = !first.is_empty()
+        && first.chars().all(|c| c.is_ascii_digit())
+        && groups.all(|g| g.len() == 3 && g.chars().all(|c| c.is_ascii_digit()));
+
+    valid_integer.then_some(style)
+}
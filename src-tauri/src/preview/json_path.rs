@@ -0,0 +1,397 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// JSONPath 的子集
+/// 支持 $、.key、['key']、[n]、[-n]、[*]、.*、[start:end]、..key（递归查找）
+/// 以及 [?(@.field)] / [?(@.field op literal)] 过滤（op 为 ==、!=、<、<=、>、>=）
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Select(Selector),
+    /// 在当前节点及其所有后代中选择
+    Descendant(Selector),
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    Slice(Option<i64>, Option<i64>),
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone)]
+struct Filter {
+    /// @ 之后的相对路径
+    path: Vec<Selector>,
+    /// 为空时只判断字段是否存在
+    comparison: Option<(CompareOp, Value)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JsonPath {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        // 允许省略开头的 $（如 "data[0].name"）
+        let normalized = match expression.chars().next() {
+            Some('$') => expression.to_string(),
+            Some('.') | Some('[') => format!("${}", expression),
+            Some(_) => format!("$.{}", expression),
+            None => return Err("Empty JSONPath expression".to_string()),
+        };
+
+        let mut parser = Parser {
+            chars: normalized.chars().collect(),
+            pos: 1,
+        };
+        let mut segments = Vec::new();
+        while !parser.is_done() {
+            segments.push(parser.parse_segment()?);
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// 返回所有匹配的节点
+    pub fn query<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut nodes = vec![root];
+        for segment in &self.segments {
+            nodes = match segment {
+                Segment::Select(selector) => nodes
+                    .into_iter()
+                    .flat_map(|node| selector.select(node))
+                    .collect(),
+                Segment::Descendant(selector) => nodes
+                    .into_iter()
+                    .flat_map(descendants_and_self)
+                    .flat_map(|node| selector.select(node))
+                    .collect(),
+            };
+        }
+        nodes
+    }
+}
+
+impl Selector {
+    fn select<'a>(&self, node: &'a Value) -> Vec<&'a Value> {
+        match self {
+            Self::Key(key) => node.get(key).into_iter().collect(),
+            Self::Index(index) => match node {
+                Value::Array(items) => resolve_index(*index, items.len())
+                    .and_then(|i| items.get(i))
+                    .into_iter()
+                    .collect(),
+                _ => Vec::new(),
+            },
+            Self::Wildcard => children(node),
+            Self::Slice(start, end) => match node {
+                Value::Array(items) => {
+                    let len = items.len();
+                    let start = start.map_or(0, |s| clamp_index(s, len));
+                    let end = end.map_or(len, |e| clamp_index(e, len));
+                    items
+                        .get(start..end.max(start))
+                        .unwrap_or(&[])
+                        .iter()
+                        .collect()
+                }
+                _ => Vec::new(),
+            },
+            Self::Filter(filter) => children(node)
+                .into_iter()
+                .filter(|child| filter.matches(child))
+                .collect(),
+        }
+    }
+}
+
+impl Filter {
+    fn matches(&self, node: &Value) -> bool {
+        let mut targets = vec![node];
+        for selector in &self.path {
+            targets = targets
+                .into_iter()
+                .flat_map(|target| selector.select(target))
+                .collect();
+        }
+
+        match &self.comparison {
+            None => !targets.is_empty(),
+            Some((op, literal)) => targets.iter().any(|value| compare(value, *op, literal)),
+        }
+    }
+}
+
+fn compare(value: &Value, op: CompareOp, literal: &Value) -> bool {
+    let ordering = match (value, literal) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .zip(b.as_f64())
+            .and_then(|(a, b)| a.partial_cmp(&b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => (value == literal).then_some(Ordering::Equal),
+    };
+
+    match op {
+        CompareOp::Eq => ordering == Some(Ordering::Equal),
+        CompareOp::Ne => ordering != Some(Ordering::Equal),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn children(node: &Value) -> Vec<&Value> {
+    match node {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn descendants_and_self(node: &Value) -> Vec<&Value> {
+    let mut result = vec![node];
+    let mut index = 0;
+    while index < result.len() {
+        let current = result[index];
+        result.extend(children(current));
+        index += 1;
+    }
+    result
+}
+
+/// 负数索引从末尾计算
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)
+    } else {
+        Some(index as usize)
+    }
+}
+
+fn clamp_index(index: i64, len: usize) -> usize {
+    resolve_index(index, len).unwrap_or(0).min(len)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn is_done(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' at position {}", c, self.pos))
+        }
+    }
+
+    fn parse_segment(&mut self) -> Result<Segment, String> {
+        if self.starts_with("..") {
+            self.pos += 2;
+            let selector = if self.peek() == Some('[') {
+                self.parse_bracket()?
+            } else {
+                self.parse_dot_selector()?
+            };
+            return Ok(Segment::Descendant(selector));
+        }
+
+        match self.peek() {
+            Some('.') => {
+                self.pos += 1;
+                Ok(Segment::Select(self.parse_dot_selector()?))
+            }
+            Some('[') => Ok(Segment::Select(self.parse_bracket()?)),
+            Some(c) => Err(format!("Unexpected '{}' at position {}", c, self.pos)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+
+    /// 解析 . 之后的 key 或 *
+    fn parse_dot_selector(&mut self) -> Result<Selector, String> {
+        if self.peek() == Some('*') {
+            self.pos += 1;
+            return Ok(Selector::Wildcard);
+        }
+
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '.' || c == '[' || c.is_whitespace() || "=!<>)".contains(c) {
+                break;
+            }
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(format!("Expected key at position {}", start));
+        }
+        Ok(Selector::Key(self.chars[start..self.pos].iter().collect()))
+    }
+
+    fn parse_bracket(&mut self) -> Result<Selector, String> {
+        self.expect('[')?;
+        let selector = match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Selector::Wildcard
+            }
+            Some(quote @ ('\'' | '"')) => Selector::Key(self.parse_quoted(quote)?),
+            Some('?') => {
+                self.pos += 1;
+                Selector::Filter(self.parse_filter()?)
+            }
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c != ']') {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                parse_index_or_slice(text.trim())?
+            }
+        };
+        self.expect(']')?;
+        Ok(selector)
+    }
+
+    fn parse_quoted(&mut self, quote: char) -> Result<String, String> {
+        self.expect(quote)?;
+        let mut value = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    if let Some(escaped) = self.peek() {
+                        value.push(escaped);
+                        self.pos += 1;
+                    }
+                }
+                c if c == quote => return Ok(value),
+                c => value.push(c),
+            }
+        }
+        Err("Unterminated string in JSONPath expression".to_string())
+    }
+
+    /// 解析 ?(@.path op literal)
+    fn parse_filter(&mut self) -> Result<Filter, String> {
+        self.expect('(')?;
+        self.skip_whitespace();
+        self.expect('@')?;
+
+        let mut path = Vec::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '.' => {
+                    self.pos += 1;
+                    path.push(self.parse_dot_selector()?);
+                }
+                '[' => path.push(self.parse_bracket()?),
+                _ => break,
+            }
+        }
+
+        self.skip_whitespace();
+        let comparison = if self.peek() == Some(')') {
+            None
+        } else {
+            let op = self.parse_operator()?;
+            self.skip_whitespace();
+            Some((op, self.parse_literal()?))
+        };
+
+        self.skip_whitespace();
+        self.expect(')')?;
+        Ok(Filter { path, comparison })
+    }
+
+    fn parse_operator(&mut self) -> Result<CompareOp, String> {
+        let operators = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+        for (token, op) in operators {
+            if self.starts_with(token) {
+                self.pos += token.len();
+                return Ok(op);
+            }
+        }
+        Err(format!(
+            "Expected comparison operator at position {}",
+            self.pos
+        ))
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, String> {
+        if let Some(quote @ ('\'' | '"')) = self.peek() {
+            return Ok(Value::String(self.parse_quoted(quote)?));
+        }
+
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != ')' && !c.is_whitespace()) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        serde_json::from_str(&text).map_err(|_| format!("Invalid literal '{}'", text))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+}
+
+fn parse_index_or_slice(text: &str) -> Result<Selector, String> {
+    let parse = |s: &str| -> Result<Option<i64>, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            Ok(None)
+        } else {
+            s.parse::<i64>()
+                .map(Some)
+                .map_err(|_| format!("Invalid index '{}'", s))
+        }
+    };
+
+    match text.split_once(':') {
+        Some((start, end)) => Ok(Selector::Slice(parse(start)?, parse(end)?)),
+        None => parse(text)?
+            .map(Selector::Index)
+            .ok_or_else(|| "Empty index in JSONPath expression".to_string()),
+    }
+}
@@ -0,0 +1,324 @@
+// JSONL / NDJSON 记录读取
+// 通过范围读取逐步建立稀疏的行偏移索引，大文件可以直接跳转到任意位置的记录
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::preview::window::{HARD_MAX_BYTES, HARD_MAX_ROWS};
+use crate::storage::manager::current_connection_key;
+use crate::storage::traits::StorageClient;
+
+/// 每隔多少条记录保存一个检查点
+const CHECKPOINT_INTERVAL: u64 = 1024;
+
+/// 每次范围读取的大小
+const READ_CHUNK_SIZE: u64 = 4 * 1024 * 1024; // 4MB
+
+/// 单条记录最多返回的字节数，超出部分被截断
+const MAX_RECORD_BYTES: usize = 256 * 1024;
+
+/// 最多缓存的文件索引数量，超出后移除最久未使用的索引
+const MAX_CACHED_INDEXES: usize = 16;
+
+/// 已构建的行偏移索引（连接标识 + 文件路径, 索引），按使用时间排列
+static INDEXES: LazyLock<Mutex<Vec<(String, LineIndex)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// JSONL 中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonlRecord {
+    /// 记录序号（从 0 开始，不计空行）
+    pub index: String, // 使用字符串表示大数字
+    /// 记录在文件中的字节偏移
+    pub offset: String,
+    pub text: String,
+    /// 记录超过 256KB 时只返回开头部分
+    pub truncated: bool,
+    /// 是否为合法的 JSON，被截断的记录为 false
+    pub valid: bool,
+}
+
+/// 一段连续的记录
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonlRecords {
+    pub records: Vec<JsonlRecord>,
+    pub start_record: String, // 使用字符串表示大数字
+    /// 下一页的起始记录，已读到末尾时为空
+    pub next_record: Option<String>,
+    /// 总记录数，索引扫描到文件末尾之前为空
+    pub total_records: Option<String>,
+    /// 索引已扫描的记录数
+    pub indexed_records: String,
+    pub file_size: String,
+}
+
+/// 稀疏的行偏移索引，只记录每 CHECKPOINT_INTERVAL 条记录的起始偏移
+/// 按需向后扩展，跳转到已扫描的范围时不需要从头读取
+#[derive(Debug, Clone)]
+struct LineIndex {
+    file_size: u64,
+    /// 第 k * CHECKPOINT_INTERVAL 条记录的起始偏移
+    checkpoints: Vec<u64>,
+    /// 已扫描的完整记录数
+    records: u64,
+    /// 已扫描到的位置，总是位于行首
+    scanned: u64,
+}
+
+impl LineIndex {
+    fn new(file_size: u64) -> Self {
+        Self {
+            file_size,
+            checkpoints: Vec::new(),
+            records: 0,
+            scanned: 0,
+        }
+    }
+
+    fn complete(&self) -> bool {
+        self.scanned >= self.file_size
+    }
+
+    /// 向后扫描，直到第 checkpoint 个检查点可用或到达文件末尾
+    async fn extend_to(
+        &mut self,
+        client: &Arc<dyn StorageClient>,
+        path: &str,
+        checkpoint: usize,
+    ) -> Result<(), String> {
+        let mut pos = self.scanned;
+        let mut line_start = self.scanned;
+        let mut has_content = false;
+
+        while self.checkpoints.len() <= checkpoint && pos < self.file_size {
+            let length = READ_CHUNK_SIZE.min(self.file_size - pos);
+            let chunk = client
+                .read_file_range(path, pos, length)
+                .await
+                .map_err(|e| format!("Failed to read JSONL file: {}", e))?;
+            if chunk.is_empty() {
+                break;
+            }
+
+            for (i, byte) in chunk.iter().enumerate() {
+                if *byte == b'\n' {
+                    if has_content {
+                        self.push_record(line_start);
+                    }
+                    line_start = pos + i as u64 + 1;
+                    has_content = false;
+                } else if !byte.is_ascii_whitespace() {
+                    has_content = true;
+                }
+            }
+            pos += chunk.len() as u64;
+        }
+
+        if pos >= self.file_size {
+            // 末行没有换行符
+            if has_content {
+                self.push_record(line_start);
+            }
+            self.scanned = self.file_size;
+        } else {
+            self.scanned = line_start;
+        }
+        Ok(())
+    }
+
+    fn push_record(&mut self, offset: u64) {
+        if self.records % CHECKPOINT_INTERVAL == 0 {
+            self.checkpoints.push(offset);
+        }
+        self.records += 1;
+    }
+}
+
+/// JSONL / NDJSON 记录读取器
+/// 通过范围读取逐步构建行偏移索引，可跳转到大文件中任意位置的记录
+pub struct JsonlReader;
+
+impl JsonlReader {
+    pub async fn read_records(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        start_record: u64,
+        count: u32,
+    ) -> Result<JsonlRecords, String> {
+        let count = count.clamp(1, HARD_MAX_ROWS) as usize;
+        let file_size = client
+            .get_file_size(path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        // 不同连接下的同名路径是不同的文件；文件大小变化时重建索引
+        let key = format!("{}\0{}", current_connection_key().await, path);
+        let mut index = take_index(&key)
+            .filter(|index| index.file_size == file_size)
+            .unwrap_or_else(|| LineIndex::new(file_size));
+        let checkpoint = (start_record / CHECKPOINT_INTERVAL) as usize;
+        let result = index.extend_to(&client, path, checkpoint).await;
+        let records = match result {
+            Ok(()) => match index.checkpoints.get(checkpoint).copied() {
+                Some(offset) => {
+                    let skip = start_record - checkpoint as u64 * CHECKPOINT_INTERVAL;
+                    read_from(&client, path, file_size, offset, skip, count).await
+                }
+                None => Ok((Vec::new(), true)),
+            },
+            Err(e) => Err(e),
+        };
+        let indexed_records = index.records;
+        let total_records = index.complete().then_some(index.records);
+        put_index(&key, index);
+
+        let (records, at_end) = records?;
+        let returned = records.len() as u64;
+        let records = records
+            .into_iter()
+            .enumerate()
+            .map(|(i, (offset, bytes, truncated))| {
+                let text = String::from_utf8_lossy(&bytes);
+                let text = text.trim_start_matches('\u{feff}');
+                JsonlRecord {
+                    index: (start_record + i as u64).to_string(),
+                    offset: offset.to_string(),
+                    valid: !truncated
+                        && serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok(),
+                    text: text.to_string(),
+                    truncated,
+                }
+            })
+            .collect();
+        let next_record = start_record + returned;
+        let has_more = match total_records {
+            Some(total) => next_record < total,
+            None => !at_end,
+        };
+
+        Ok(JsonlRecords {
+            records,
+            start_record: start_record.to_string(),
+            next_record: has_more.then(|| next_record.to_string()),
+            total_records: total_records.map(|total| total.to_string()),
+            indexed_records: indexed_records.to_string(),
+            file_size: file_size.to_string(),
+        })
+    }
+}
+
+/// 从 offset 处的记录开始，跳过 skip 条后读取最多 count 条记录
+/// 返回 ([(偏移, 内容, 是否截断)], 是否已读到文件末尾)
+async fn read_from(
+    client: &Arc<dyn StorageClient>,
+    path: &str,
+    file_size: u64,
+    offset: u64,
+    skip: u64,
+    count: usize,
+) -> Result<(Vec<(u64, Vec<u8>, bool)>, bool), String> {
+    let mut collector = RecordCollector {
+        records: Vec::new(),
+        skip,
+        count,
+        total_bytes: 0,
+    };
+    let mut pos = offset;
+    let mut line_start = offset;
+    let mut line = Vec::new();
+    let mut truncated = false;
+
+    while pos < file_size {
+        let length = READ_CHUNK_SIZE.min(file_size - pos);
+        let chunk = client
+            .read_file_range(path, pos, length)
+            .await
+            .map_err(|e| format!("Failed to read JSONL file: {}", e))?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        let mut rest = chunk.as_slice();
+        let mut chunk_pos = pos;
+        while let Some(newline) = rest.iter().position(|b| *b == b'\n') {
+            append_capped(&mut line, &rest[..newline], &mut truncated);
+            if collector.finish_line(line_start, &mut line, truncated) {
+                return Ok((collector.records, false));
+            }
+            chunk_pos += newline as u64 + 1;
+            line_start = chunk_pos;
+            truncated = false;
+            rest = &rest[newline + 1..];
+        }
+        append_capped(&mut line, rest, &mut truncated);
+        pos += chunk.len() as u64;
+    }
+
+    collector.finish_line(line_start, &mut line, truncated);
+    Ok((collector.records, true))
+}
+
+struct RecordCollector {
+    records: Vec<(u64, Vec<u8>, bool)>,
+    skip: u64,
+    count: usize,
+    total_bytes: usize,
+}
+
+impl RecordCollector {
+    /// 处理一个完整的行，空行不计为记录；返回 true 表示已读取足够的记录
+    fn finish_line(&mut self, line_start: u64, line: &mut Vec<u8>, truncated: bool) -> bool {
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if line.iter().all(u8::is_ascii_whitespace) {
+            line.clear();
+            return false;
+        }
+        if self.skip > 0 {
+            self.skip -= 1;
+            line.clear();
+            return false;
+        }
+        self.total_bytes += line.len();
+        self.records
+            .push((line_start, std::mem::take(line), truncated));
+        self.records.len() >= self.count || self.total_bytes >= HARD_MAX_BYTES as usize
+    }
+}
+
+fn append_capped(line: &mut Vec<u8>, data: &[u8], truncated: &mut bool) {
+    let available = MAX_RECORD_BYTES.saturating_sub(line.len());
+    if data.len() > available {
+        *truncated = true;
+    }
+    line.extend_from_slice(&data[..data.len().min(available)]);
+}
+
+/// 取出缓存的索引，读取期间其他请求会重新构建，读取完成后放回
+fn take_index(key: &str) -> Option<LineIndex> {
+    let mut cached = INDEXES.lock().ok()?;
+    let position = cached
+        .iter()
+        .position(|(cached_key, _)| cached_key == key)?;
+    Some(cached.remove(position).1)
+}
+
+fn put_index(key: &str, index: LineIndex) {
+    if let Ok(mut cached) = INDEXES.lock() {
+        // 并发请求可能已放回同一文件的索引，保留扫描得更远的一个
+        if let Some(position) = cached.iter().position(|(cached_key, _)| cached_key == key) {
+            let (_, existing) = cached.remove(position);
+            if existing.file_size == index.file_size && existing.scanned > index.scanned {
+                cached.push((key.to_string(), existing));
+                return;
+            }
+        }
+        cached.push((key.to_string(), index));
+        if cached.len() > MAX_CACHED_INDEXES {
+            cached.remove(0);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+// 内容预览管线
+// 按 source → decode → transform → serialize 的顺序组合预览步骤
+
+pub mod content_search;
+pub mod csv_dialect;
+pub mod json_path;
+pub mod jsonl;
+pub mod parquet;
+pub mod pipeline;
+pub mod source;
+pub mod spreadsheet;
+pub mod steps;
+pub mod table;
+pub mod window;
@@ -0,0 +1,540 @@
+// Parquet 文件预览
+// 通过范围读取解析文件尾部的元数据，按行组分页读取行数据，只下载请求的列对应的数据块
+
+use bytes::{Buf, Bytes};
+use futures_util::{StreamExt, TryStreamExt};
+use parquet::basic::LogicalType;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, Length, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use parquet::schema::printer::print_schema;
+use parquet::schema::types::Type as SchemaType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::preview::window::{DEFAULT_MAX_ROWS, HARD_MAX_ROWS};
+use crate::storage::traits::StorageClient;
+
+/// 文件尾部固定长度：4 字节元数据长度 + 4 字节魔数
+const FOOTER_SIZE: u64 = 8;
+
+/// 首次从文件末尾读取的数据量，大多数文件的元数据可一次读完
+const INITIAL_TAIL_SIZE: u64 = 64 * 1024; // 64KB
+
+/// 元数据大小上限，防止损坏的文件导致大量读取
+const MAX_METADATA_SIZE: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 读取一页行数据时最多下载的列数据量（压缩后）
+const MAX_ROW_READ_BYTES: u64 = 512 * 1024 * 1024; // 512MB
+
+/// 同时下载的列数据块数量
+const FETCH_CONCURRENCY: usize = 4;
+
+/// 单元格中字符串的最大字符数，超出部分截断（如图片等二进制列）
+const MAX_CELL_CHARS: usize = 4096;
+
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+const ENCRYPTED_MAGIC: &[u8; 4] = b"PARE";
+
+/// Parquet 文件元数据
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetMetadata {
+    pub file_size: String, // 使用字符串表示大数字
+    pub metadata_size: String,
+    pub num_rows: String,
+    pub num_row_groups: u32,
+    pub version: i32,
+    /// 写入程序，如 "parquet-cpp-arrow version 14.0.1"
+    pub created_by: Option<String>,
+    /// 文件中使用的所有压缩编码
+    pub codecs: Vec<String>,
+    pub columns: Vec<ParquetColumn>,
+    pub row_groups: Vec<ParquetRowGroup>,
+    pub key_value_metadata: Vec<ParquetKeyValue>,
+    /// message 格式的完整 schema 文本
+    pub schema: String,
+}
+
+/// 叶子列信息，大小和空值数按所有行组汇总
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetColumn {
+    /// 点分隔的列路径，如 "meta.tags.list.element"
+    pub path: String,
+    pub physical_type: String,
+    pub logical_type: Option<String>,
+    /// "REQUIRED"、"OPTIONAL" 或 "REPEATED"
+    pub repetition: String,
+    pub codecs: Vec<String>,
+    pub compressed_size: String,
+    pub uncompressed_size: String,
+    /// 所有行组都带有统计信息时的空值总数
+    pub null_count: Option<String>,
+}
+
+/// 行组信息
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetRowGroup {
+    pub index: u32,
+    pub num_rows: String,
+    pub total_byte_size: String,
+    pub compressed_size: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetKeyValue {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+/// 行数据读取请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetRowsRequest {
+    pub path: String,
+    /// 起始行（从 0 开始），指定 row_group 时相对于该行组
+    pub offset: Option<String>,
+    /// 默认 100 行，上限 10000 行
+    pub limit: Option<u32>,
+    /// 只读取的顶层列，为空时读取所有列
+    pub columns: Option<Vec<String>>,
+    /// 只读取指定的行组
+    pub row_group: Option<u32>,
+}
+
+/// 一页行数据
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetRows {
+    pub columns: Vec<String>,
+    /// JSON 数组，每个元素是一行数据对象，过长的字符串会被截断
+    pub rows: String,
+    pub row_count: u32,
+    pub offset: String, // 使用字符串表示大数字
+    /// 文件（或指定行组）的总行数
+    pub total_rows: String,
+    /// 下一页的起始行，已读到末尾时为空
+    pub next_offset: Option<String>,
+    /// 实际下载的列数据大小
+    pub bytes_read: String,
+}
+
+/// Parquet 文件读取器，只通过范围读取访问文件尾部的元数据
+pub struct ParquetReader;
+
+impl ParquetReader {
+    /// 读取并汇总文件元数据
+    pub async fn get_metadata(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+    ) -> Result<ParquetMetadata, String> {
+        let (metadata, file_size, metadata_size) = Self::read_metadata(client, path).await?;
+        Ok(Self::summarize(&metadata, file_size, metadata_size))
+    }
+
+    /// 读取文件尾部并解析元数据，返回元数据、文件大小和元数据大小
+    pub async fn read_metadata(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+    ) -> Result<(ParquetMetaData, u64, u64), String> {
+        let file_size = client
+            .get_file_size(path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+        if file_size < FOOTER_SIZE + PARQUET_MAGIC.len() as u64 {
+            return Err("File is too small to be a Parquet file".to_string());
+        }
+
+        let tail_size = INITIAL_TAIL_SIZE.min(file_size);
+        let mut tail = client
+            .read_file_range(path, file_size - tail_size, tail_size)
+            .await
+            .map_err(|e| format!("Failed to read Parquet footer: {}", e))?;
+        if (tail.len() as u64) < FOOTER_SIZE {
+            return Err("Failed to read Parquet footer: incomplete data".to_string());
+        }
+
+        let footer: [u8; FOOTER_SIZE as usize] = tail[tail.len() - FOOTER_SIZE as usize..]
+            .try_into()
+            .map_err(|_| "Failed to read Parquet footer".to_string())?;
+        if &footer[4..] == ENCRYPTED_MAGIC {
+            return Err("Encrypted Parquet footers are not supported".to_string());
+        }
+        let metadata_size = ParquetMetaDataReader::decode_footer(&footer)
+            .map_err(|e| format!("Invalid Parquet footer: {}", e))?
+            as u64;
+        if metadata_size + FOOTER_SIZE > file_size || metadata_size > MAX_METADATA_SIZE {
+            return Err(format!(
+                "Invalid Parquet metadata size: {} bytes",
+                metadata_size
+            ));
+        }
+
+        // 元数据超出首次读取的范围时补读前面的部分
+        let needed = metadata_size + FOOTER_SIZE;
+        if needed > tail.len() as u64 {
+            let missing = needed - tail.len() as u64;
+            let mut head = client
+                .read_file_range(path, file_size - needed, missing)
+                .await
+                .map_err(|e| format!("Failed to read Parquet metadata: {}", e))?;
+            head.extend_from_slice(&tail);
+            tail = head;
+        }
+
+        let start = tail.len() - needed as usize;
+        let metadata =
+            ParquetMetaDataReader::decode_metadata(&tail[start..tail.len() - FOOTER_SIZE as usize])
+                .map_err(|e| format!("Failed to parse Parquet metadata: {}", e))?;
+        Ok((metadata, file_size, metadata_size))
+    }
+
+    /// 读取一页行数据
+    /// 只下载所选行组中所选列的数据块，然后在后台线程解码
+    pub async fn read_rows(
+        client: Arc<dyn StorageClient>,
+        request: ParquetRowsRequest,
+    ) -> Result<ParquetRows, String> {
+        let (metadata, file_size, _) = Self::read_metadata(client.clone(), &request.path).await?;
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_MAX_ROWS)
+            .clamp(1, HARD_MAX_ROWS) as u64;
+        let offset = match &request.offset {
+            Some(offset) => offset
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid offset: {}", offset))?,
+            None => 0,
+        };
+
+        // 参与分页的行组及其起始行
+        let groups: Vec<(usize, u64)> = match request.row_group {
+            Some(index) if index as usize >= metadata.num_row_groups() => {
+                return Err(format!("Row group out of range: {}", index));
+            }
+            Some(index) => vec![(index as usize, 0)],
+            None => {
+                let mut start = 0u64;
+                metadata
+                    .row_groups()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, row_group)| {
+                        let group = (index, start);
+                        start += row_group.num_rows() as u64;
+                        group
+                    })
+                    .collect()
+            }
+        };
+        let total_rows: u64 = groups
+            .iter()
+            .map(|(index, _)| metadata.row_group(*index).num_rows() as u64)
+            .sum();
+
+        let schema = metadata.file_metadata().schema_descr_ptr();
+        let root = schema.root_schema();
+        let fields = match &request.columns {
+            Some(columns) if !columns.is_empty() => columns
+                .iter()
+                .map(|name| {
+                    root.get_fields()
+                        .iter()
+                        .find(|field| field.name() == name)
+                        .cloned()
+                        .ok_or_else(|| format!("Column not found: {}", name))
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            _ => root.get_fields().to_vec(),
+        };
+        let columns: Vec<String> = fields
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect();
+        let projection = SchemaType::group_type_builder(root.name())
+            .with_fields(fields)
+            .build()
+            .map_err(|e| format!("Failed to build Parquet projection: {}", e))?;
+        let leaf_columns: Vec<usize> = schema
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| columns.contains(&column.path().parts()[0]))
+            .map(|(index, _)| index)
+            .collect();
+
+        // 与 [offset, offset + limit) 相交的行组：(行组序号, 跳过行数, 读取行数)
+        let end = offset.saturating_add(limit).min(total_rows);
+        let selected: Vec<(usize, u64, u64)> = groups
+            .iter()
+            .filter_map(|&(index, start)| {
+                let group_end = start + metadata.row_group(index).num_rows() as u64;
+                if group_end <= offset || start >= end {
+                    return None;
+                }
+                let first = start.max(offset);
+                Some((index, first - start, end.min(group_end) - first))
+            })
+            .collect();
+
+        let ranges: Vec<(u64, u64)> = selected
+            .iter()
+            .flat_map(|&(index, _, _)| {
+                let row_group = metadata.row_group(index);
+                leaf_columns
+                    .iter()
+                    .map(move |&column| row_group.column(column).byte_range())
+            })
+            .collect();
+        let bytes_read: u64 = ranges.iter().map(|(_, length)| length).sum();
+        if bytes_read > MAX_ROW_READ_BYTES {
+            return Err(format!(
+                "Selected columns are too large to preview ({} bytes), select fewer columns",
+                bytes_read
+            ));
+        }
+
+        let path = request.path.clone();
+        let chunks: Vec<(u64, Bytes)> = futures_util::stream::iter(ranges)
+            .map(|(start, length)| {
+                let client = client.clone();
+                let path = path.clone();
+                async move {
+                    client
+                        .read_file_range(&path, start, length)
+                        .await
+                        .map(|data| (start, Bytes::from(data)))
+                        .map_err(|e| format!("Failed to read Parquet column chunk: {}", e))
+                }
+            })
+            .buffer_unordered(FETCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+        let reader = Arc::new(RangeChunks { file_size, chunks });
+
+        let rows = tokio::task::spawn_blocking(move || {
+            let properties = Arc::new(ReaderProperties::builder().build());
+            let mut rows = Vec::new();
+            for (index, skip, take) in selected {
+                let row_group = SerializedRowGroupReader::new(
+                    reader.clone(),
+                    metadata.row_group(index),
+                    None,
+                    properties.clone(),
+                )
+                .map_err(|e| format!("Failed to open Parquet row group: {}", e))?;
+                let iter = row_group
+                    .get_row_iter(Some(projection.clone()))
+                    .map_err(|e| format!("Failed to read Parquet rows: {}", e))?;
+                for row in iter.skip(skip as usize).take(take as usize) {
+                    let row = row.map_err(|e| format!("Failed to decode Parquet row: {}", e))?;
+                    let mut value = row.to_json_value();
+                    truncate_strings(&mut value);
+                    rows.push(value);
+                }
+            }
+            Ok::<_, String>(rows)
+        })
+        .await
+        .map_err(|e| format!("Parquet read task failed: {}", e))??;
+
+        let row_count = rows.len() as u32;
+        let next = offset + row_count as u64;
+        Ok(ParquetRows {
+            columns,
+            rows: serde_json::to_string(&rows)
+                .map_err(|e| format!("Failed to serialize rows: {}", e))?,
+            row_count,
+            offset: offset.to_string(),
+            total_rows: total_rows.to_string(),
+            next_offset: (next < total_rows).then(|| next.to_string()),
+            bytes_read: bytes_read.to_string(),
+        })
+    }
+
+    fn summarize(
+        metadata: &ParquetMetaData,
+        file_size: u64,
+        metadata_size: u64,
+    ) -> ParquetMetadata {
+        let file_metadata = metadata.file_metadata();
+        let schema = file_metadata.schema_descr();
+
+        let mut codecs = BTreeSet::new();
+        let columns = schema
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let mut column_codecs = BTreeSet::new();
+                let mut compressed_size = 0i64;
+                let mut uncompressed_size = 0i64;
+                let mut null_count = Some(0u64);
+                for row_group in metadata.row_groups() {
+                    let chunk = row_group.column(index);
+                    column_codecs.insert(chunk.compression().to_string());
+                    compressed_size += chunk.compressed_size();
+                    uncompressed_size += chunk.uncompressed_size();
+                    null_count = null_count
+                        .zip(
+                            chunk
+                                .statistics()
+                                .and_then(|statistics| statistics.null_count_opt()),
+                        )
+                        .map(|(total, count)| total + count);
+                }
+                codecs.extend(column_codecs.iter().cloned());
+
+                ParquetColumn {
+                    path: column.path().string(),
+                    physical_type: column.physical_type().to_string(),
+                    logical_type: column.logical_type().map(|t| logical_type_name(&t)),
+                    repetition: column.self_type().get_basic_info().repetition().to_string(),
+                    codecs: column_codecs.into_iter().collect(),
+                    compressed_size: compressed_size.to_string(),
+                    uncompressed_size: uncompressed_size.to_string(),
+                    null_count: null_count.map(|count| count.to_string()),
+                }
+            })
+            .collect();
+
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .enumerate()
+            .map(|(index, row_group)| ParquetRowGroup {
+                index: index as u32,
+                num_rows: row_group.num_rows().to_string(),
+                total_byte_size: row_group.total_byte_size().to_string(),
+                compressed_size: row_group.compressed_size().to_string(),
+            })
+            .collect();
+
+        let key_value_metadata = file_metadata
+            .key_value_metadata()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|entry| ParquetKeyValue {
+                        key: entry.key.clone(),
+                        value: entry.value.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut schema_text = Vec::new();
+        print_schema(&mut schema_text, schema.root_schema());
+
+        ParquetMetadata {
+            file_size: file_size.to_string(),
+            metadata_size: metadata_size.to_string(),
+            num_rows: file_metadata.num_rows().to_string(),
+            num_row_groups: metadata.num_row_groups() as u32,
+            version: file_metadata.version(),
+            created_by: file_metadata.created_by().map(str::to_string),
+            codecs: codecs.into_iter().collect(),
+            columns,
+            row_groups,
+            key_value_metadata,
+            schema: String::from_utf8_lossy(&schema_text).into_owned(),
+        }
+    }
+}
+
+/// 预先下载的列数据块，供 parquet 解码器按偏移读取
+struct RangeChunks {
+    file_size: u64,
+    /// (文件偏移, 数据)
+    chunks: Vec<(u64, Bytes)>,
+}
+
+impl RangeChunks {
+    fn locate(&self, start: u64) -> parquet::errors::Result<(usize, &Bytes)> {
+        self.chunks
+            .iter()
+            .find(|(offset, data)| start >= *offset && start < offset + data.len() as u64)
+            .map(|(offset, data)| ((start - offset) as usize, data))
+            .ok_or_else(|| ParquetError::General(format!("Range at {} was not fetched", start)))
+    }
+}
+
+impl Length for RangeChunks {
+    fn len(&self) -> u64 {
+        self.file_size
+    }
+}
+
+impl ChunkReader for RangeChunks {
+    type T = bytes::buf::Reader<Bytes>;
+
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        let (offset, data) = self.locate(start)?;
+        Ok(data.slice(offset..).reader())
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        let (offset, data) = self.locate(start)?;
+        if offset + length > data.len() {
+            return Err(ParquetError::EOF(format!(
+                "Range {}+{} exceeds fetched column chunk",
+                start, length
+            )));
+        }
+        Ok(data.slice(offset..offset + length))
+    }
+}
+
+/// 截断过长的字符串，避免二进制列（图片、音频等）撑大返回结果
+pub(crate) fn truncate_strings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => {
+            if let Some((index, _)) = text.char_indices().nth(MAX_CELL_CHARS) {
+                text.truncate(index);
+                text.push('…');
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(truncate_strings),
+        serde_json::Value::Object(fields) => fields.values_mut().for_each(truncate_strings),
+        _ => {}
+    }
+}
+
+/// 逻辑类型名称，带参数的类型附加关键参数，如 "TIMESTAMP(MICROS, UTC)"、"DECIMAL(10, 2)"
+fn logical_type_name(logical_type: &LogicalType) -> String {
+    match logical_type {
+        LogicalType::Decimal { scale, precision } => format!("DECIMAL({}, {})", precision, scale),
+        LogicalType::Time {
+            is_adjusted_to_u_t_c,
+            unit,
+        }
+        | LogicalType::Timestamp {
+            is_adjusted_to_u_t_c,
+            unit,
+        } => {
+            let name = if matches!(logical_type, LogicalType::Time { .. }) {
+                "TIME"
+            } else {
+                "TIMESTAMP"
+            };
+            let unit = format!("{:?}", unit)
+                .split('(')
+                .next()
+                .unwrap_or("")
+                .to_uppercase();
+            let zone = if *is_adjusted_to_u_t_c { ", UTC" } else { "" };
+            format!("{}({}{})", name, unit, zone)
+        }
+        LogicalType::Integer {
+            bit_width,
+            is_signed,
+        } => format!("{}INT{}", if *is_signed { "" } else { "U" }, bit_width),
+        other => format!("{:?}", other).to_uppercase(),
+    }
+}
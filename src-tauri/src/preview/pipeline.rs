@@ -0,0 +1,375 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::archive::stream_control::StreamControl;
+use crate::preview::csv_dialect::CsvDialect;
+use crate::preview::source::{PreviewData, PreviewSource};
+use crate::preview::steps::{
+    text_encoding, CsvHead, JsonPathQuery, JsonPretty, LineHead, PreviewStep, Transcode,
+};
+use crate::preview::window::{Continuation, PreviewWindow, JSON_QUERY_MAX_BYTES};
+use crate::storage::traits::StorageClient;
+use crate::utils::mime::detect_mime;
+
+/// 预览步骤配置，按数组顺序依次执行
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PreviewStepSpec {
+    /// 解压，只能作为第一个步骤，由对应压缩格式的处理器读取来源；format 为空时按文件名或文件头识别
+    Decompress { format: Option<String> },
+    /// 转码为 UTF-8，encoding 为空时自动检测
+    Transcode { encoding: Option<String> },
+    /// JSON 格式化
+    JsonPretty,
+    /// 执行 JSONPath 表达式，只返回匹配的片段
+    JsonPath { expression: String },
+    /// 只保留 CSV 表头和前 N 行
+    CsvHead { rows: u32 },
+    /// 只保留前 N 行
+    LineHead { lines: u32 },
+}
+
+impl PreviewStepSpec {
+    /// 解压在读取来源时完成，不构建步骤
+    fn build(&self) -> Option<Box<dyn PreviewStep>> {
+        let step: Box<dyn PreviewStep> = match self {
+            Self::Decompress { .. } => return None,
+            Self::Transcode { encoding } => Box::new(Transcode {
+                encoding: encoding.clone(),
+            }),
+            Self::JsonPretty => Box::new(JsonPretty),
+            Self::JsonPath { expression } => Box::new(JsonPathQuery {
+                expression: expression.clone(),
+            }),
+            Self::CsvHead { rows } => Box::new(CsvHead { rows: *rows }),
+            Self::LineHead { lines } => Box::new(LineHead { lines: *lines }),
+        };
+        Some(step)
+    }
+}
+
+/// 预览请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewRequest {
+    pub path: String,
+    /// 压缩包内的条目路径，为空时预览 path 本身
+    pub entry_path: Option<String>,
+    /// 读取窗口；maxRows 指定时按完整的行截断，续读从下一行开头开始
+    #[serde(flatten)]
+    pub window: PreviewWindow,
+    pub steps: Vec<PreviewStepSpec>,
+    /// 输出格式："text"（默认）或 "base64"
+    pub output: Option<String>,
+    /// 加密压缩包条目的密码
+    pub password: Option<String>,
+    /// 文本编码，指定时覆盖转码步骤的自动识别；步骤中没有转码时在解压之后转码
+    pub encoding: Option<String>,
+    /// 请求标识，指定后可通过 archive_preview_pause / resume / cancel 控制进行中的读取
+    pub request_id: Option<String>,
+}
+
+/// 预览结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewResult {
+    pub content: String,
+    /// 实际使用的输出格式："text" 或 "base64"
+    pub output: String,
+    /// 处理后内容的 MIME 类型
+    pub mime: String,
+    pub encoding: Option<String>,
+    pub total_size: String, // 使用字符串表示大数字
+    pub truncated: bool,
+    /// 实际生效的步骤（不适用于该数据的步骤会被跳过）
+    pub applied_steps: Vec<String>,
+    /// CSV / TSV 内容识别出的方言，其他类型为空
+    pub csv_dialect: Option<CsvDialect>,
+    /// 本段第一行在来源中的行号（从 0 开始），只在按行续读时有意义
+    pub start_row: u32,
+    /// 读取下一段的续读标记，已读到末尾或来源不支持续读（解压后的数据）时为空
+    pub next_continuation: Option<String>,
+}
+
+/// 内容预览管线：source → decode → transform → serialize
+pub struct PreviewPipeline {
+    steps: Vec<Box<dyn PreviewStep>>,
+    max_bytes: usize,
+}
+
+impl PreviewPipeline {
+    pub fn new(specs: &[PreviewStepSpec], max_bytes: usize) -> Self {
+        Self {
+            steps: specs.iter().filter_map(|spec| spec.build()).collect(),
+            max_bytes,
+        }
+    }
+
+    /// 依次执行各步骤
+    pub fn process(&self, mut data: PreviewData) -> Result<(PreviewData, Vec<String>), String> {
+        let mut applied = Vec::new();
+        for step in &self.steps {
+            if let Some(next) = step
+                .apply(&data, self.max_bytes)
+                .map_err(|e| format!("Preview step '{}' failed: {}", step.name(), e))?
+            {
+                data = next;
+                applied.push(step.name().to_string());
+            }
+        }
+        Ok((data, applied))
+    }
+
+    /// 读取来源、执行步骤并序列化结果；指定预览流时读取和解压遵循其暂停和取消
+    pub async fn run(
+        client: Arc<dyn StorageClient>,
+        request: PreviewRequest,
+        control: Option<&StreamControl>,
+    ) -> Result<PreviewResult, String> {
+        let source = PreviewSource::new(request.path.clone(), request.entry_path.clone())
+            .with_password(request.password.clone());
+        let position = request.window.position(&source.key())?;
+        let row_limit = request.window.row_limit();
+        let decompress = match request.steps.first() {
+            Some(PreviewStepSpec::Decompress { format }) => Some(format.clone()),
+            _ => None,
+        };
+        if request
+            .steps
+            .iter()
+            .skip(1)
+            .any(|step| matches!(step, PreviewStepSpec::Decompress { .. }))
+        {
+            return Err("Decompress must be the first preview step".to_string());
+        }
+        // 压缩数据的字节偏移和行边界对解压后的内容没有意义，只能从头读取
+        let decompressing = decompress.is_some();
+        if decompressing && position.offset > 0 {
+            return Err("Continuation is not supported for compressed previews".to_string());
+        }
+
+        let steps = with_encoding(
+            &request.steps,
+            request.encoding.as_deref(),
+            request.output.as_deref(),
+        );
+        // JSONPath 需要完整的文档才能查询，读取和中间步骤按查询的输入上限处理，返回的结果再按读取窗口截断
+        let output_bytes = request.window.max_bytes();
+        let querying = steps
+            .iter()
+            .any(|step| matches!(step, PreviewStepSpec::JsonPath { .. }));
+        let input_bytes = if querying {
+            output_bytes.max(JSON_QUERY_MAX_BYTES as usize)
+        } else {
+            output_bytes
+        };
+        let pipeline = Self::new(&steps, input_bytes);
+        let max_bytes = pipeline.max_bytes as u64;
+        let (mut data, decompressed) = match &decompress {
+            Some(format) => {
+                source
+                    .read_decompressed(client, format.as_deref(), 0, max_bytes, control)
+                    .await?
+            }
+            None => (
+                source
+                    .read_controlled(client, position.offset, max_bytes, control)
+                    .await?,
+                false,
+            ),
+        };
+
+        // 按行预览时只保留完整的行，下一段从下一行开头读取
+        let mut rows = 0;
+        if let (Some(limit), false) = (row_limit, decompressing) {
+            let at_end = position.offset + data.bytes.len() as u64 >= data.total_size;
+            let (end, count) = take_rows(&data.bytes, limit, at_end);
+            data.bytes.truncate(end);
+            rows = count;
+        }
+        let total_size = data.total_size;
+        let read_end = position.offset + data.bytes.len() as u64;
+        // 解压后的完整大小可能只是估算，以处理器的判断为准
+        if !decompressed {
+            data.truncated = position.offset > 0 || read_end < total_size;
+        }
+        // 按行截取的步骤可能只返回读取内容的前几行，续读位置需要按步骤执行后的内容换算
+        let source_bytes = steps
+            .iter()
+            .any(|step| {
+                matches!(
+                    step,
+                    PreviewStepSpec::CsvHead { .. } | PreviewStepSpec::LineHead { .. }
+                )
+            })
+            .then(|| data.bytes.clone());
+
+        // 转码、格式化等步骤是纯 CPU 计算，放到阻塞线程中执行
+        let (mut data, mut applied_steps) =
+            tokio::task::spawn_blocking(move || pipeline.process(data))
+                .await
+                .map_err(|e| format!("Preview task failed: {}", e))??;
+        if decompressed {
+            applied_steps.insert(0, "decompress".to_string());
+        }
+        if data.bytes.len() > output_bytes {
+            data.bytes.truncate(output_bytes);
+            data.truncated = true;
+        }
+        if let (Some(limit), true) = (row_limit, decompressing) {
+            let (end, _) = take_rows(&data.bytes, limit, true);
+            data.truncated |= end < data.bytes.len();
+            data.bytes.truncate(end);
+        }
+
+        // base64 输出保留原始字节，报告请求指定或识别出的编码，由前端按该编码解码
+        let encoding = match (request.output.as_deref(), data.encoding.take()) {
+            (Some("base64"), None) => request
+                .encoding
+                .as_deref()
+                .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+                .or_else(|| text_encoding(&data.bytes, data.truncated))
+                .map(|encoding| encoding.name().to_string()),
+            (_, encoding) => encoding,
+        };
+
+        // 续读位置取步骤执行后的内容在来源中的结束位置；解压、格式化和 JSONPath 的结果与来源中的位置无法对应，
+        // UTF-16 转码后的换行也无法与来源的字节对应，这些情况不支持续读
+        let transformed = decompressing
+            || applied_steps
+                .iter()
+                .any(|step| step == "jsonPretty" || step == "jsonPath")
+            || encoding
+                .as_deref()
+                .is_some_and(|encoding| encoding.starts_with("UTF-16"));
+        let row_limited = applied_steps
+            .iter()
+            .any(|step| step == "csvHead" || step == "lineHead");
+        let next_offset = if transformed {
+            None
+        } else if let (Some(source_bytes), true) = (&source_bytes, row_limited) {
+            let returned_rows = count_rows(&data.bytes).max(1);
+            let (end, count) = take_rows(source_bytes, returned_rows, read_end >= total_size);
+            rows = count;
+            Some(position.offset + end as u64)
+        } else {
+            Some(read_end)
+        };
+        let next_continuation = match next_offset {
+            Some(offset) if offset < total_size => {
+                Some(Continuation::new(&source.key(), offset, position.row + rows).encode()?)
+            }
+            _ => None,
+        };
+
+        // 解压后按去掉压缩扩展名的文件名识别类型（data.csv.gz -> data.csv）
+        let name = request.entry_path.as_deref().unwrap_or(&request.path);
+        let name = if applied_steps.iter().any(|s| s == "decompress") {
+            name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name)
+        } else {
+            name
+        };
+        let mime = detect_mime(name, Some(&data.bytes));
+        let csv_dialect = match mime.as_str() {
+            "text/csv" | "text/tab-separated-values" => {
+                CsvDialect::sniff(&data.bytes, data.truncated)
+            }
+            _ => None,
+        };
+
+        let (output, content) = match request.output.as_deref() {
+            Some("base64") => (
+                "base64".to_string(),
+                general_purpose::STANDARD.encode(&data.bytes),
+            ),
+            _ => (
+                "text".to_string(),
+                String::from_utf8_lossy(&data.bytes).into_owned(),
+            ),
+        };
+
+        Ok(PreviewResult {
+            content,
+            output,
+            mime,
+            encoding,
+            total_size: data.total_size.to_string(),
+            truncated: data.truncated,
+            applied_steps,
+            csv_dialect,
+            start_row: position.row,
+            next_continuation,
+        })
+    }
+}
+
+/// 按请求指定的编码调整步骤：覆盖已有转码步骤的编码，没有转码步骤时加在最后一个解压步骤之后；
+/// base64 输出需要原始字节，不做转码
+fn with_encoding(
+    steps: &[PreviewStepSpec],
+    encoding: Option<&str>,
+    output: Option<&str>,
+) -> Vec<PreviewStepSpec> {
+    if output == Some("base64") {
+        return steps
+            .iter()
+            .filter(|step| !matches!(step, PreviewStepSpec::Transcode { .. }))
+            .cloned()
+            .collect();
+    }
+    let Some(encoding) = encoding else {
+        return steps.to_vec();
+    };
+    let transcode = PreviewStepSpec::Transcode {
+        encoding: Some(encoding.to_string()),
+    };
+
+    let mut steps = steps.to_vec();
+    let mut found = false;
+    for step in steps.iter_mut() {
+        if matches!(step, PreviewStepSpec::Transcode { .. }) {
+            *step = transcode.clone();
+            found = true;
+        }
+    }
+    if !found {
+        let position = steps
+            .iter()
+            .rposition(|step| matches!(step, PreviewStepSpec::Decompress { .. }))
+            .map_or(0, |index| index + 1);
+        steps.insert(position, transcode);
+    }
+    steps
+}
+
+/// 内容的行数，末尾没有换行的内容也算一行
+fn count_rows(bytes: &[u8]) -> u32 {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count() as u32;
+    match bytes.last() {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    }
+}
+
+/// 取前 limit 行，返回 (结束位置, 行数)
+/// 未到来源末尾时不保留最后一行不完整的内容；整段都没有换行时保留全部，避免无法前进
+fn take_rows(bytes: &[u8], limit: u32, at_end: bool) -> (usize, u32) {
+    let mut rows = 0;
+    let mut end = 0;
+    for (index, _) in bytes.iter().enumerate().filter(|(_, &b)| b == b'\n') {
+        rows += 1;
+        end = index + 1;
+        if rows >= limit {
+            return (end, rows);
+        }
+    }
+
+    if end < bytes.len() && (at_end || rows == 0) {
+        (bytes.len(), rows + 1)
+    } else {
+        (end, rows)
+    }
+}
@@ -0,0 +1,280 @@
+use std::sync::Arc;
+
+use crate::archive::entry_stream::{EntryStream, STREAM_CHUNK_SIZE};
+use crate::archive::formats::get_handler;
+use crate::archive::handlers::ArchiveHandler;
+use crate::archive::nested::ArchiveEntryClient;
+use crate::archive::stream_control::StreamControl;
+use crate::archive::types::CompressionType;
+use crate::storage::traits::StorageClient;
+
+/// 识别压缩格式时读取的文件头大小
+const SNIFF_SIZE: u64 = 16;
+
+/// 预览管线的原始数据
+#[derive(Debug, Clone)]
+pub struct PreviewData {
+    pub bytes: Vec<u8>,
+    /// 来源的完整大小
+    pub total_size: u64,
+    /// 数据是否只是来源的一部分
+    pub truncated: bool,
+    /// 已识别的文本编码，解码前为 None
+    pub encoding: Option<String>,
+}
+
+/// 预览数据来源
+pub enum PreviewSource {
+    /// 存储中的文件
+    File { path: String },
+    /// 压缩包中的条目
+    ArchiveEntry {
+        archive_path: String,
+        entry_path: String,
+        /// 加密条目的密码
+        password: Option<String>,
+    },
+}
+
+impl PreviewSource {
+    pub fn new(path: String, entry_path: Option<String>) -> Self {
+        match entry_path {
+            Some(entry_path) => Self::ArchiveEntry {
+                archive_path: path,
+                entry_path,
+                password: None,
+            },
+            None => Self::File { path },
+        }
+    }
+
+    /// 设置读取加密压缩包条目使用的密码，对普通文件无效
+    pub fn with_password(mut self, password: Option<String>) -> Self {
+        if let Self::ArchiveEntry { password: slot, .. } = &mut self {
+            *slot = password;
+        }
+        self
+    }
+
+    /// 来源标识，用于校验续读标记
+    pub fn key(&self) -> String {
+        match self {
+            Self::File { path } => path.clone(),
+            Self::ArchiveEntry {
+                archive_path,
+                entry_path,
+                ..
+            } => format!("{}#{}", archive_path, entry_path),
+        }
+    }
+
+    /// 从 offset 开始读取最多 max_bytes 字节
+    pub async fn read(
+        &self,
+        client: Arc<dyn StorageClient>,
+        offset: u64,
+        max_bytes: u64,
+    ) -> Result<PreviewData, String> {
+        self.read_controlled(client, offset, max_bytes, None).await
+    }
+
+    /// 与 read 相同，指定预览流时压缩包条目的解压循环也遵循暂停和取消
+    pub async fn read_controlled(
+        &self,
+        client: Arc<dyn StorageClient>,
+        offset: u64,
+        max_bytes: u64,
+        control: Option<&StreamControl>,
+    ) -> Result<PreviewData, String> {
+        match self {
+            Self::File { path } => {
+                let total_size = client
+                    .get_file_size(path)
+                    .await
+                    .map_err(|e| format!("Failed to get file size: {}", e))?;
+                let read_size = max_bytes.min(total_size.saturating_sub(offset));
+                let bytes = client
+                    .read_file_range(path, offset, read_size)
+                    .await
+                    .map_err(|e| format!("Failed to read file: {}", e))?;
+
+                Ok(PreviewData {
+                    truncated: offset > 0 || offset + (bytes.len() as u64) < total_size,
+                    bytes,
+                    total_size,
+                    encoding: None,
+                })
+            }
+            Self::ArchiveEntry {
+                archive_path,
+                entry_path,
+                password,
+            } => {
+                let filename = archive_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(archive_path)
+                    .to_string();
+                let mut cancel_rx = control.map(|control| control.cancel_receiver());
+                let preview = ArchiveHandler::new()
+                    .with_password(password.clone())
+                    .get_file_preview_with_client(
+                        client,
+                        archive_path.clone(),
+                        filename,
+                        entry_path.clone(),
+                        Some(max_bytes.min(u32::MAX as u64) as u32),
+                        Some(offset),
+                        control.map(|control| control.pause_gate()),
+                        cancel_rx.as_mut(),
+                    )
+                    .await?;
+
+                Ok(PreviewData {
+                    total_size: preview.total_size.parse().unwrap_or(0),
+                    truncated: offset > 0 || preview.is_truncated,
+                    bytes: preview.content,
+                    encoding: None,
+                })
+            }
+        }
+    }
+
+    /// 读取来源解压后从 offset 开始的最多 max_bytes 字节，返回 (数据, 是否经过解压)
+    /// 解压复用单文件压缩格式（gzip / zstd / lz4 / brotli）的处理器，按需读取压缩数据；
+    /// format 为空时按文件名或文件头识别，来源不是单文件压缩格式时按原样读取
+    pub async fn read_decompressed(
+        &self,
+        client: Arc<dyn StorageClient>,
+        format: Option<&str>,
+        offset: u64,
+        max_bytes: u64,
+        control: Option<&StreamControl>,
+    ) -> Result<(PreviewData, bool), String> {
+        // 压缩包条目包装为单个文件交给处理器
+        let (file_client, path, name): (Arc<dyn StorageClient>, String, &str) = match self {
+            Self::File { path } => (client.clone(), path.clone(), path.as_str()),
+            Self::ArchiveEntry {
+                archive_path,
+                entry_path,
+                password,
+            } => {
+                let entry_client = ArchiveEntryClient::new(
+                    client.clone(),
+                    archive_path.clone(),
+                    entry_path.clone(),
+                    password.clone(),
+                );
+                let path = entry_client.path().to_string();
+                (Arc::new(entry_client), path, entry_path.as_str())
+            }
+        };
+        let name = name.rsplit('/').next().unwrap_or(name);
+
+        let compression_type = match format {
+            Some(format) => match format.to_lowercase().as_str() {
+                "gzip" | "gz" => CompressionType::Gzip,
+                "zstd" | "zst" => CompressionType::Zstd,
+                "lz4" => CompressionType::Lz4,
+                "brotli" | "br" => CompressionType::Brotli,
+                other => return Err(format!("Unsupported compression format: {}", other)),
+            },
+            None => match CompressionType::from_filename(name) {
+                CompressionType::Unknown => {
+                    let header = file_client
+                        .read_file_range(&path, 0, SNIFF_SIZE)
+                        .await
+                        .map_err(|e| format!("Failed to read file header: {}", e))?;
+                    CompressionType::from_content(&header)
+                }
+                compression_type => compression_type,
+            },
+        };
+        let handler = match compression_type {
+            CompressionType::Gzip
+            | CompressionType::Zstd
+            | CompressionType::Lz4
+            | CompressionType::Brotli => get_handler(&compression_type),
+            _ => None,
+        };
+        let Some(handler) = handler else {
+            let data = self
+                .read_controlled(client, offset, max_bytes, control)
+                .await?;
+            return Ok((data, false));
+        };
+
+        let mut cancel_rx = control.map(|control| control.cancel_receiver());
+        let progress_callback = control
+            .map(|control| Box::new(control.pause_gate()) as Box<dyn Fn(u64, u64) + Send + Sync>);
+        let preview = handler
+            .extract_preview_with_client(
+                file_client,
+                &path,
+                "",
+                max_bytes.min(usize::MAX as u64) as usize,
+                Some(offset),
+                progress_callback,
+                cancel_rx.as_mut(),
+            )
+            .await?;
+
+        let data = PreviewData {
+            total_size: preview.total_size.parse().unwrap_or(0),
+            truncated: offset > 0 || preview.is_truncated,
+            bytes: preview.content,
+            encoding: None,
+        };
+        Ok((data, true))
+    }
+
+    /// 从头顺序读取来源的全部内容
+    /// 文件只获取一次大小后按块范围读取，压缩包条目尽量在一次解压中读完
+    pub async fn open_stream(&self, client: Arc<dyn StorageClient>) -> Result<EntryStream, String> {
+        match self {
+            Self::File { path } => {
+                let total_size = client
+                    .get_file_size(path)
+                    .await
+                    .map_err(|e| format!("Failed to get file size: {}", e))?;
+                let (sender, stream) = EntryStream::channel();
+                sender.set_size(total_size);
+                let path = path.clone();
+                tokio::spawn(async move {
+                    let mut offset = 0u64;
+                    while offset < total_size {
+                        let read_size = (STREAM_CHUNK_SIZE as u64).min(total_size - offset);
+                        let bytes = client
+                            .read_file_range(&path, offset, read_size)
+                            .await
+                            .map_err(|e| format!("Failed to read file: {}", e));
+                        let done = match &bytes {
+                            Ok(bytes) => bytes.is_empty(),
+                            Err(_) => true,
+                        };
+                        offset += bytes.as_ref().map(|b| b.len() as u64).unwrap_or(0);
+                        if !sender.send(bytes).await || done {
+                            break;
+                        }
+                    }
+                });
+                Ok(stream)
+            }
+            Self::ArchiveEntry {
+                archive_path,
+                entry_path,
+                password,
+            } => {
+                let filename = archive_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(archive_path)
+                    .to_string();
+                ArchiveHandler::new()
+                    .with_password(password.clone())
+                    .open_entry_stream(client, archive_path.clone(), filename, entry_path.clone())
+                    .await
+            }
+        }
+    }
+}
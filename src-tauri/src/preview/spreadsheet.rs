@@ -0,0 +1,437 @@
+use calamine::{open_workbook_auto, Data, Reader, SheetType, SheetVisible};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::preview::window::{Continuation, PreviewWindow};
+use crate::storage::local_copy::{local_copy, sampled_copy, LocalCopy, SampleInfo};
+use crate::storage::traits::StorageClient;
+
+/// 默认返回的列数
+const DEFAULT_MAX_COLUMNS: u32 = 50;
+
+/// 单次最多返回的单元格数量
+const MAX_CELLS: u64 = 200_000;
+
+/// 超过该大小的表格文件默认只抽样下载，避免为预览下载整个超大文件
+const FULL_DOWNLOAD_LIMIT: u64 = 256 * 1024 * 1024; // 256MB
+
+/// 抽样下载的字节数上限
+const SAMPLE_BYTES: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 查找 ZIP 中央目录时读取的文件尾部大小
+const ZIP_TAIL_BYTES: u64 = 1024 * 1024; // 1MB
+
+/// 工作表信息
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetInfo {
+    pub name: String,
+    pub index: u32,
+    /// "worksheet"、"dialogsheet"、"macrosheet"、"chartsheet" 或 "vba"
+    pub sheet_type: String,
+    pub visible: bool,
+    /// 表格只抽样下载了部分内容，该工作表可能无法读取
+    pub partial: bool,
+}
+
+/// 单元格值
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum CellValue {
+    Empty,
+    String(String),
+    Number(f64),
+    Bool(bool),
+    /// ISO 8601 格式的日期时间
+    DateTime(String),
+    /// 公式错误，如 #DIV/0!
+    Error(String),
+}
+
+/// 单元格区域请求，行列均从 0 开始（A1 为 0, 0）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetRangeRequest {
+    pub path: String,
+    /// 工作表名称，为空时读取第一个工作表
+    pub sheet: Option<String>,
+    pub start_column: Option<u32>,
+    /// 默认 50 列
+    pub max_columns: Option<u32>,
+    /// 读取窗口：maxRows 默认 100 行，续读从上一段之后的行开始
+    #[serde(flatten)]
+    pub window: PreviewWindow,
+    /// 为 true 时忽略大小阈值完整下载文件，默认超大文件只抽样下载
+    pub full_download: Option<bool>,
+}
+
+/// 单元格区域数据
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetRange {
+    pub sheet: String,
+    /// 工作表已使用区域的行数和列数（从 A1 起算）
+    pub total_rows: u32,
+    pub total_columns: u32,
+    pub start_row: u32,
+    pub start_column: u32,
+    pub rows: Vec<Vec<CellValue>>,
+    /// 请求的区域之外是否还有数据
+    pub truncated: bool,
+    /// 读取后续行的续读标记，已读到最后一行时为空
+    pub next_continuation: Option<String>,
+    /// 只抽样下载了部分文件时的说明，为空时数据来自完整文件
+    pub sample: Option<SampleInfo>,
+}
+
+/// 基于 calamine 的表格文件读取器，支持 .xlsx / .xlsm / .xlsb / .xls / .ods
+pub struct SpreadsheetReader;
+
+impl SpreadsheetReader {
+    /// 列出所有工作表
+    pub async fn list_sheets(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        full_download: bool,
+    ) -> Result<Vec<SheetInfo>, String> {
+        let local = Self::prepare(client, path, full_download).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let workbook = open_workbook_auto(&local.path)
+                .map_err(|e| Self::parse_error("Failed to open spreadsheet", e, &local.sample))?;
+
+            Ok(workbook
+                .sheets_metadata()
+                .iter()
+                .enumerate()
+                .map(|(index, sheet)| SheetInfo {
+                    name: sheet.name.clone(),
+                    index: index as u32,
+                    sheet_type: Self::sheet_type_name(&sheet.typ).to_string(),
+                    visible: matches!(sheet.visible, SheetVisible::Visible),
+                    partial: local.sample.is_some(),
+                })
+                .collect())
+        })
+        .await
+        .map_err(|e| format!("Spreadsheet task failed: {}", e))?
+    }
+
+    /// 读取工作表中的一个单元格区域
+    pub async fn read_range(
+        client: Arc<dyn StorageClient>,
+        request: SheetRangeRequest,
+    ) -> Result<SheetRange, String> {
+        let full_download = request.full_download.unwrap_or(false);
+        let local = Self::prepare(client, &request.path, full_download).await?;
+
+        tokio::task::spawn_blocking(move || {
+            Self::read_local_range(&local.path, &request, local.sample)
+        })
+        .await
+        .map_err(|e| format!("Spreadsheet task failed: {}", e))?
+    }
+
+    /// 获取本地副本：超过阈值的文件只下载抽样区间，ZIP 格式（xlsx / ods 等）
+    /// 优先下载工作簿结构、共享字符串和排在前面的工作表，使这些部分可以正常解析
+    async fn prepare(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        full_download: bool,
+    ) -> Result<LocalCopy, String> {
+        let extension = Self::extension(path)?;
+        let file_size = client
+            .get_file_size(path)
+            .await
+            .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+        if full_download || file_size <= FULL_DOWNLOAD_LIMIT {
+            return Ok(LocalCopy {
+                path: local_copy(client, path, &extension, None, None).await?,
+                sample: None,
+            });
+        }
+
+        let ranges = match extension.as_str() {
+            "xlsx" | "xlsm" | "xlsb" | "ods" => {
+                Self::zip_sample_ranges(client.clone(), path, file_size).await?
+            }
+            // xls 为 OLE 复合文档，只能下载文件开头
+            _ => vec![0..SAMPLE_BYTES],
+        };
+        sampled_copy(client, path, &extension, file_size, ranges).await
+    }
+
+    /// 根据 ZIP 中央目录选择抽样下载的条目区间
+    async fn zip_sample_ranges(
+        client: Arc<dyn StorageClient>,
+        path: &str,
+        file_size: u64,
+    ) -> Result<Vec<Range<u64>>, String> {
+        let tail_start = file_size.saturating_sub(ZIP_TAIL_BYTES);
+        let tail = client
+            .read_file_range(path, tail_start, file_size - tail_start)
+            .await
+            .map_err(|e| format!("Failed to read file range: {}", e))?;
+        let mut ranges = vec![tail_start..file_size];
+
+        // 找不到中央目录（如 ZIP64）或中央目录过大时只下载文件开头
+        let directory = match zip_central_directory(&tail) {
+            Some((offset, size)) if size <= SAMPLE_BYTES => offset..offset + size,
+            _ => {
+                ranges.push(0..SAMPLE_BYTES);
+                return Ok(ranges);
+            }
+        };
+        let directory_data = if directory.start >= tail_start {
+            let start = (directory.start - tail_start) as usize;
+            let end = ((directory.end - tail_start) as usize).min(tail.len());
+            tail.get(start..end).unwrap_or_default().to_vec()
+        } else {
+            ranges.push(directory.clone());
+            client
+                .read_file_range(path, directory.start, directory.end - directory.start)
+                .await
+                .map_err(|e| format!("Failed to read file range: {}", e))?
+        };
+
+        // 条目从本地文件头开始，到下一个条目或中央目录为止
+        let mut entries = zip_entry_offsets(&directory_data);
+        entries.sort_by_key(|(_, offset)| *offset);
+        let mut extents: Vec<(ZipPart, Range<u64>)> = entries
+            .iter()
+            .enumerate()
+            .map(|(index, (name, offset))| {
+                let end = entries
+                    .get(index + 1)
+                    .map(|(_, next)| *next)
+                    .unwrap_or(directory.start);
+                (zip_part(name), *offset..end.max(*offset))
+            })
+            .filter(|(part, _)| *part != ZipPart::Unused)
+            .collect();
+        // 先下载解析必需的条目，再按文件中的顺序下载工作表，直到用完抽样额度
+        extents.sort_by_key(|(part, range)| (*part == ZipPart::Sheet, range.start));
+
+        let mut budget = SAMPLE_BYTES;
+        for (_, range) in extents {
+            let length = range.end - range.start;
+            if length <= budget {
+                budget -= length;
+                ranges.push(range);
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// 解析失败的错误信息，抽样副本额外说明只下载了部分内容
+    fn parse_error(
+        context: &str,
+        error: impl std::fmt::Display,
+        sample: &Option<SampleInfo>,
+    ) -> String {
+        match sample {
+            Some(sample) => format!(
+                "{}: {} (only {} of {} bytes were downloaded because the file exceeds the preview size limit; request a full download to read the rest)",
+                context, error, sample.sampled_bytes, sample.total_size
+            ),
+            None => format!("{}: {}", context, error),
+        }
+    }
+
+    fn read_local_range(
+        local: &Path,
+        request: &SheetRangeRequest,
+        sample: Option<SampleInfo>,
+    ) -> Result<SheetRange, String> {
+        let mut workbook = open_workbook_auto(local)
+            .map_err(|e| Self::parse_error("Failed to open spreadsheet", e, &sample))?;
+
+        let sheet = match &request.sheet {
+            Some(sheet) => sheet.clone(),
+            None => workbook
+                .sheet_names()
+                .first()
+                .cloned()
+                .ok_or_else(|| "Spreadsheet has no sheets".to_string())?,
+        };
+        let range = workbook.worksheet_range(&sheet).map_err(|e| {
+            Self::parse_error(&format!("Failed to read sheet '{}'", sheet), e, &sample)
+        })?;
+
+        let (total_rows, total_columns) = range
+            .end()
+            .map(|(row, column)| (row + 1, column + 1))
+            .unwrap_or((0, 0));
+
+        let source = format!("{}#{}", request.path, sheet);
+        let start_row = request.window.position(&source)?.row;
+        let start_column = request.start_column.unwrap_or(0);
+        let max_columns = request.max_columns.unwrap_or(DEFAULT_MAX_COLUMNS).max(1);
+        let max_rows = request
+            .window
+            .max_rows()
+            .min((MAX_CELLS / max_columns as u64).max(1) as u32);
+
+        let end_row = total_rows.min(start_row.saturating_add(max_rows));
+        let end_column = total_columns.min(start_column.saturating_add(max_columns));
+
+        let rows = (start_row..end_row)
+            .map(|row| {
+                (start_column..end_column)
+                    .map(|column| {
+                        range
+                            .get_value((row, column))
+                            .map(Self::cell_value)
+                            .unwrap_or(CellValue::Empty)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let next_continuation = if end_row < total_rows {
+            Some(Continuation::new(&source, 0, end_row).encode()?)
+        } else {
+            None
+        };
+
+        Ok(SheetRange {
+            sheet,
+            total_rows,
+            total_columns,
+            start_row,
+            start_column,
+            rows,
+            truncated: end_row < total_rows || end_column < total_columns,
+            next_continuation,
+            sample,
+        })
+    }
+
+    fn cell_value(data: &Data) -> CellValue {
+        match data {
+            Data::Empty => CellValue::Empty,
+            Data::String(value) => CellValue::String(value.clone()),
+            Data::Int(value) => CellValue::Number(*value as f64),
+            Data::Float(value) => CellValue::Number(*value),
+            Data::Bool(value) => CellValue::Bool(*value),
+            Data::DateTime(value) => match value.as_datetime() {
+                Some(datetime) => {
+                    CellValue::DateTime(datetime.format("%Y-%m-%dT%H:%M:%S").to_string())
+                }
+                None => CellValue::Number(value.as_f64()),
+            },
+            Data::DateTimeIso(value) | Data::DurationIso(value) => {
+                CellValue::DateTime(value.clone())
+            }
+            Data::Error(error) => CellValue::Error(error.to_string()),
+        }
+    }
+
+    fn sheet_type_name(sheet_type: &SheetType) -> &'static str {
+        match sheet_type {
+            SheetType::WorkSheet => "worksheet",
+            SheetType::DialogSheet => "dialogsheet",
+            SheetType::MacroSheet => "macrosheet",
+            SheetType::ChartSheet => "chartsheet",
+            SheetType::Vba => "vba",
+        }
+    }
+
+    /// 本地副本沿用原扩展名，calamine 按扩展名选择解析器
+    fn extension(path: &str) -> Result<String, String> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let extension = name
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "xlsx" | "xlsm" | "xlsb" | "xla" | "xls" | "ods" => Ok(extension),
+            _ => Err(format!("Unsupported spreadsheet format: {}", name)),
+        }
+    }
+}
+
+/// ZIP 条目在抽样下载中的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZipPart {
+    /// 工作簿结构、共享字符串、样式等，打开表格时必须读取
+    Required,
+    /// 工作表数据，只在读取对应工作表时需要
+    Sheet,
+    /// 图片、嵌入对象等，预览单元格时不需要
+    Unused,
+}
+
+fn zip_part(name: &str) -> ZipPart {
+    const UNUSED_PREFIXES: [&str; 6] = [
+        "xl/media/",
+        "xl/embeddings/",
+        "xl/printerSettings/",
+        "docProps/thumbnail",
+        "Pictures/",
+        "Thumbnails/",
+    ];
+    if UNUSED_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        ZipPart::Unused
+    } else if (name.starts_with("xl/worksheets/") && !name.contains("/_rels/"))
+        || name == "content.xml"
+    {
+        ZipPart::Sheet
+    } else {
+        ZipPart::Required
+    }
+}
+
+/// 在文件尾部数据中查找 EOCD 记录，返回中央目录的偏移量和大小，ZIP64 文件返回空
+fn zip_central_directory(tail: &[u8]) -> Option<(u64, u64)> {
+    const EOCD_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x05, 0x06];
+    const EOCD_SIZE: usize = 22;
+
+    let position = (0..=tail.len().checked_sub(EOCD_SIZE)?)
+        .rev()
+        .find(|&i| tail[i..i + 4] == EOCD_SIGNATURE)?;
+    let read_u32 = |at: usize| u32::from_le_bytes(tail[at..at + 4].try_into().unwrap());
+    let size = read_u32(position + 12);
+    let offset = read_u32(position + 16);
+    if size == u32::MAX || offset == u32::MAX {
+        return None;
+    }
+    Some((offset as u64, size as u64))
+}
+
+/// 解析中央目录，返回每个条目的名称和本地文件头偏移量
+fn zip_entry_offsets(directory: &[u8]) -> Vec<(String, u64)> {
+    const ENTRY_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x01, 0x02];
+    const ENTRY_HEADER_SIZE: usize = 46;
+
+    let mut entries = Vec::new();
+    let mut position = 0;
+    while position + ENTRY_HEADER_SIZE <= directory.len()
+        && directory[position..position + 4] == ENTRY_SIGNATURE
+    {
+        let header = &directory[position..position + ENTRY_HEADER_SIZE];
+        let read_u16 = |at: usize| u16::from_le_bytes([header[at], header[at + 1]]) as usize;
+        let name_length = read_u16(28);
+        let extra_length = read_u16(30);
+        let comment_length = read_u16(32);
+        let offset = u32::from_le_bytes(header[42..46].try_into().unwrap());
+
+        let name_start = position + ENTRY_HEADER_SIZE;
+        let Some(name) = directory.get(name_start..name_start + name_length) else {
+            break;
+        };
+        // ZIP64 条目的偏移量在扩展字段中，跳过
+        if offset != u32::MAX {
+            entries.push((String::from_utf8_lossy(name).into_owned(), offset as u64));
+        }
+        position = name_start + name_length + extra_length + comment_length;
+    }
+    entries
+}
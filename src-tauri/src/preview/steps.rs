@@ -0,0 +1,312 @@
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+
+use crate::preview::json_path::JsonPath;
+use crate::preview::source::PreviewData;
+use crate::preview::window::JSON_QUERY_MAX_BYTES;
+use crate::utils::mime::is_text_content;
+
+/// 预览管线中的一个处理步骤
+pub trait PreviewStep: Send + Sync {
+    /// 步骤名称，记录在预览结果中
+    fn name(&self) -> &'static str;
+
+    /// 处理数据；返回 Ok(None) 表示步骤不适用于该数据，数据保持不变
+    fn apply(&self, data: &PreviewData, max_bytes: usize) -> Result<Option<PreviewData>, String>;
+}
+
+/// 猜测编码时最多分析的字节数，足以区分常见编码，避免大预览窗口拖慢识别
+const DETECT_SAMPLE_SIZE: usize = 256 * 1024;
+
+/// 转码为 UTF-8
+/// 未指定编码时依次根据 BOM、UTF-8 有效性判断，仍无法确定时按字节分布猜测（GBK、Shift_JIS、Windows-1252 等），
+/// 二进制数据保持不变
+pub struct Transcode {
+    pub encoding: Option<String>,
+}
+
+impl PreviewStep for Transcode {
+    fn name(&self) -> &'static str {
+        "transcode"
+    }
+
+    fn apply(&self, data: &PreviewData, _max_bytes: usize) -> Result<Option<PreviewData>, String> {
+        let encoding = match &self.encoding {
+            Some(label) => Encoding::for_label(label.trim().as_bytes())
+                .ok_or_else(|| format!("Unsupported encoding: {}", label))?,
+            None => match text_encoding(&data.bytes, data.truncated) {
+                Some(encoding) => encoding,
+                None => return Ok(None),
+            },
+        };
+
+        // 截断可能切断多字节字符，丢弃末尾不完整的字节
+        let bytes = trim_incomplete_tail(&data.bytes, encoding);
+        let (text, _, _) = encoding.decode(bytes);
+
+        Ok(Some(PreviewData {
+            bytes: text.into_owned().into_bytes(),
+            total_size: data.total_size,
+            truncated: data.truncated,
+            encoding: Some(encoding.name().to_string()),
+        }))
+    }
+}
+
+/// 识别文本数据的编码，二进制数据返回 None；truncated 表示数据只是内容的开头
+pub(crate) fn text_encoding(bytes: &[u8], truncated: bool) -> Option<&'static Encoding> {
+    // UTF-16 文本包含大量 0 字节，先按 BOM 判断，避免被当作二进制
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return Some(encoding);
+    }
+    if !is_text_content(bytes) {
+        return None;
+    }
+    Some(detect_encoding(bytes, truncated))
+}
+
+fn detect_encoding(bytes: &[u8], truncated: bool) -> &'static Encoding {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => encoding_rs::UTF_8,
+        // 只在末尾出现不完整序列时仍视为 UTF-8
+        Err(e) if e.error_len().is_none() => encoding_rs::UTF_8,
+        Err(_) => {
+            let sample = &bytes[..bytes.len().min(DETECT_SAMPLE_SIZE)];
+            let mut detector = EncodingDetector::new();
+            detector.feed(sample, !truncated && sample.len() == bytes.len());
+            detector.guess(None, true)
+        }
+    }
+}
+
+fn trim_incomplete_tail<'a>(bytes: &'a [u8], encoding: &'static Encoding) -> &'a [u8] {
+    if encoding != encoding_rs::UTF_8 {
+        return bytes;
+    }
+    match std::str::from_utf8(bytes) {
+        Err(e) if e.error_len().is_none() => &bytes[..e.valid_up_to()],
+        _ => bytes,
+    }
+}
+
+/// JSON 格式化输出
+/// 完整的 JSON 解析后格式化；截断的数据（如大体积压缩 JSON 的开头）按字符逐个缩进
+pub struct JsonPretty;
+
+impl PreviewStep for JsonPretty {
+    fn name(&self) -> &'static str {
+        "jsonPretty"
+    }
+
+    fn apply(&self, data: &PreviewData, _max_bytes: usize) -> Result<Option<PreviewData>, String> {
+        let pretty = match serde_json::from_slice::<serde_json::Value>(&data.bytes) {
+            Ok(value) => serde_json::to_vec_pretty(&value)
+                .map_err(|e| format!("Failed to format JSON: {}", e))?,
+            Err(e) if data.truncated || e.is_eof() => reindent_json(&data.bytes),
+            Err(e) => return Err(format!("Invalid JSON: {}", e)),
+        };
+
+        Ok(Some(PreviewData {
+            bytes: pretty,
+            total_size: data.total_size,
+            truncated: data.truncated,
+            encoding: data.encoding.clone(),
+        }))
+    }
+}
+
+/// 不解析语法直接按括号和逗号缩进，可处理不完整的 JSON
+fn reindent_json(input: &[u8]) -> Vec<u8> {
+    const INDENT: &[u8] = b"  ";
+
+    let mut output = Vec::with_capacity(input.len() + input.len() / 4);
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let newline = |output: &mut Vec<u8>, depth: usize| {
+        output.push(b'\n');
+        for _ in 0..depth {
+            output.extend_from_slice(INDENT);
+        }
+    };
+
+    let mut iter = input.iter().copied().peekable();
+    while let Some(byte) = iter.next() {
+        if in_string {
+            output.push(byte);
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => {
+                in_string = true;
+                output.push(byte);
+            }
+            b'{' | b'[' => {
+                output.push(byte);
+                // 空对象和空数组保持在同一行
+                while iter.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+                    iter.next();
+                }
+                if matches!(iter.peek(), Some(b'}' | b']')) {
+                    output.push(iter.next().unwrap_or_default());
+                } else {
+                    depth += 1;
+                    newline(&mut output, depth);
+                }
+            }
+            b'}' | b']' => {
+                depth = depth.saturating_sub(1);
+                newline(&mut output, depth);
+                output.push(byte);
+            }
+            b',' => {
+                output.push(byte);
+                newline(&mut output, depth);
+            }
+            b':' => output.extend_from_slice(b": "),
+            b if b.is_ascii_whitespace() => {}
+            _ => output.push(byte),
+        }
+    }
+
+    output
+}
+
+/// 在服务端执行 JSONPath 表达式，只返回匹配的片段（JSON 数组）
+/// 数据不是单个完整的 JSON 时按 JSONL 逐行匹配，截断产生的不完整末行会被忽略
+pub struct JsonPathQuery {
+    pub expression: String,
+}
+
+impl PreviewStep for JsonPathQuery {
+    fn name(&self) -> &'static str {
+        "jsonPath"
+    }
+
+    fn apply(&self, data: &PreviewData, max_bytes: usize) -> Result<Option<PreviewData>, String> {
+        let path = JsonPath::parse(&self.expression)?;
+
+        let matches: Vec<serde_json::Value> =
+            match serde_json::from_slice::<serde_json::Value>(&data.bytes) {
+                Ok(value) => path.query(&value).into_iter().cloned().collect(),
+                Err(e) => {
+                    let lines: Vec<&[u8]> = data
+                        .bytes
+                        .split(|&b| b == b'\n')
+                        .filter(|line| !line.trim_ascii().is_empty())
+                        .collect();
+                    if lines.len() < 2 {
+                        return Err(if data.truncated {
+                            query_limit_error()
+                        } else {
+                            format!("Invalid JSON: {}", e)
+                        });
+                    }
+
+                    let mut matches = Vec::new();
+                    for (index, line) in lines.iter().enumerate() {
+                        match serde_json::from_slice::<serde_json::Value>(line) {
+                            Ok(value) => matches.extend(path.query(&value).into_iter().cloned()),
+                            Err(_) if data.truncated && index == lines.len() - 1 => {}
+                            Err(e) => {
+                                return Err(format!("Invalid JSON on line {}: {}", index + 1, e))
+                            }
+                        }
+                    }
+                    matches
+                }
+            };
+
+        let mut bytes = serde_json::to_vec_pretty(&matches)
+            .map_err(|e| format!("Failed to serialize JSONPath result: {}", e))?;
+        let truncated = data.truncated || bytes.len() > max_bytes;
+        bytes.truncate(max_bytes);
+
+        Ok(Some(PreviewData {
+            total_size: data.total_size,
+            truncated,
+            bytes,
+            encoding: data.encoding.clone(),
+        }))
+    }
+}
+
+/// 文档超过查询输入上限时的错误
+fn query_limit_error() -> String {
+    format!(
+        "JSON document exceeds the {}MB query limit",
+        JSON_QUERY_MAX_BYTES / 1024 / 1024
+    )
+}
+
+/// 只保留 CSV 的表头和前 rows 行记录（引号内的换行不会被当作记录分隔）
+pub struct CsvHead {
+    pub rows: u32,
+}
+
+impl PreviewStep for CsvHead {
+    fn name(&self) -> &'static str {
+        "csvHead"
+    }
+
+    fn apply(&self, data: &PreviewData, _max_bytes: usize) -> Result<Option<PreviewData>, String> {
+        let mut in_quotes = false;
+        let mut records = 0u32;
+        let limit = self.rows.saturating_add(1); // 表头 + rows 行
+
+        for (index, &byte) in data.bytes.iter().enumerate() {
+            match byte {
+                b'"' => in_quotes = !in_quotes,
+                b'\n' if !in_quotes => {
+                    records += 1;
+                    if records >= limit {
+                        return Ok(Some(head(data, index + 1)));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// 只保留前 lines 行（适用于 JSONL、日志等按行组织的文本）
+pub struct LineHead {
+    pub lines: u32,
+}
+
+impl PreviewStep for LineHead {
+    fn name(&self) -> &'static str {
+        "lineHead"
+    }
+
+    fn apply(&self, data: &PreviewData, _max_bytes: usize) -> Result<Option<PreviewData>, String> {
+        let end = data
+            .bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| b == b'\n')
+            .nth(self.lines.saturating_sub(1) as usize)
+            .map(|(index, _)| index + 1);
+
+        Ok(end.map(|end| head(data, end)))
+    }
+}
+
+/// 截取数据开头 end 字节
+fn head(data: &PreviewData, end: usize) -> PreviewData {
+    PreviewData {
+        truncated: data.truncated || end < data.bytes.len(),
+        bytes: data.bytes[..end].to_vec(),
+        total_size: data.total_size,
+        encoding: data.encoding.clone(),
+    }
+}
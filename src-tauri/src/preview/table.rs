@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::preview::csv_dialect::{number_style, split_records, CsvDialect, NumberStyle};
+use crate::preview::source::PreviewSource;
+use crate::preview::steps::{PreviewStep, Transcode};
+use crate::preview::window::{HARD_MAX_BYTES, HARD_MAX_ROWS};
+use crate::storage::traits::StorageClient;
+
+/// 默认读取的数据量
+const DEFAULT_TABLE_BYTES: u32 = 4 * 1024 * 1024; // 4MB
+
+/// 默认返回的行数
+const DEFAULT_TABLE_ROWS: u32 = 1000;
+
+/// 视为空值的字段内容
+const NULL_TOKENS: &[&str] = &["", "NA", "N/A", "NaN", "null", "NULL", "None", "nil"];
+
+/// 前端（JavaScript）能精确表示的最大整数，超出的整数按字符串返回
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// 表格预览请求
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TablePreviewRequest {
+    pub path: String,
+    /// 压缩包内的条目路径，为空时读取文件本身
+    pub entry_path: Option<String>,
+    /// 最多读取的字节数（解压后），默认 4MB，上限 64MB
+    pub max_bytes: Option<u32>,
+    /// 最多返回的行数（不含表头），默认 1000 行，上限 10000 行
+    pub max_rows: Option<u32>,
+    /// 文本编码，为空时自动识别
+    pub encoding: Option<String>,
+    /// 分隔符，为空时自动识别
+    pub delimiter: Option<String>,
+    /// 首行是否为表头，为空时自动识别
+    pub has_header: Option<bool>,
+}
+
+/// 推断的列类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnType {
+    Boolean,
+    Integer,
+    Float,
+    /// YYYY-MM-DD
+    Date,
+    /// RFC 3339 或 YYYY-MM-DD HH:MM:SS
+    DateTime,
+    String,
+    /// 所有值都为空
+    Null,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TableColumn {
+    pub name: String,
+    pub data_type: ColumnType,
+    pub null_count: u32,
+}
+
+/// 结构化的表格预览
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TablePreview {
+    pub columns: Vec<TableColumn>,
+    /// JSON 数组，每个元素是一行的值数组，按列类型输出数字、布尔值或 null
+    pub rows: String,
+    pub row_count: u32,
+    pub dialect: CsvDialect,
+    pub encoding: Option<String>,
+    pub total_size: String, // 使用字符串表示大数字
+    /// 是否只读取了文件的一部分
+    pub truncated: bool,
+}
+
+/// CSV / TSV 表格预览：读取文件开头，识别编码和方言，按列推断类型
+pub struct TableReader;
+
+impl TableReader {
+    pub async fn preview(
+        client: Arc<dyn StorageClient>,
+        request: TablePreviewRequest,
+    ) -> Result<TablePreview, String> {
+        let max_bytes = request
+            .max_bytes
+            .unwrap_or(DEFAULT_TABLE_BYTES)
+            .clamp(1, HARD_MAX_BYTES) as usize;
+        let max_rows = request
+            .max_rows
+            .unwrap_or(DEFAULT_TABLE_ROWS)
+            .clamp(1, HARD_MAX_ROWS) as usize;
+
+        let source = PreviewSource::new(request.path.clone(), request.entry_path.clone());
+        // 压缩的 CSV（.csv.gz 等）读取解压后的开头
+        let (data, _) = source
+            .read_decompressed(client, None, 0, max_bytes as u64, None)
+            .await?;
+
+        tokio::task::spawn_blocking(move || {
+            let data = Transcode {
+                encoding: request.encoding.clone(),
+            }
+            .apply(&data, max_bytes)?
+            .unwrap_or(data);
+
+            let text = String::from_utf8_lossy(&data.bytes);
+            let text = text.trim_start_matches('\u{feff}');
+            let name = request.entry_path.as_deref().unwrap_or(&request.path);
+            let mut dialect = CsvDialect::sniff(text.as_bytes(), data.truncated)
+                .unwrap_or_else(|| default_dialect(name));
+            if let Some(delimiter) = &request.delimiter {
+                dialect.delimiter = delimiter.clone();
+            }
+            if let Some(has_header) = request.has_header {
+                dialect.has_header = has_header;
+            }
+
+            let delimiter = single_char(&dialect.delimiter, "delimiter")?;
+            let quote = single_char(&dialect.quote, "quote")?;
+            let limit = max_rows + dialect.has_header as usize;
+            let (mut records, _) = split_records(text, delimiter, quote, limit);
+            // 数据被截断且未达到行数上限时，最后一条记录可能不完整
+            let mut truncated = data.truncated;
+            if records.len() < limit && data.truncated {
+                records.pop();
+            } else if records.len() >= limit {
+                truncated = true;
+            }
+
+            let header = if dialect.has_header && !records.is_empty() {
+                Some(records.remove(0))
+            } else {
+                None
+            };
+            let width = records
+                .iter()
+                .map(Vec::len)
+                .chain(header.as_ref().map(Vec::len))
+                .max()
+                .unwrap_or(0);
+
+            let decimal = dialect.decimal_separator.clone();
+            let columns: Vec<TableColumn> = (0..width)
+                .map(|index| {
+                    let name = header
+                        .as_ref()
+                        .and_then(|header| header.get(index))
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or_else(|| format!("column_{}", index + 1));
+                    let values = records.iter().map(|record| record.get(index));
+                    let (data_type, null_count) = infer_type(values, &decimal);
+                    TableColumn {
+                        name,
+                        data_type,
+                        null_count,
+                    }
+                })
+                .collect();
+
+            let rows: Vec<Vec<serde_json::Value>> = records
+                .iter()
+                .map(|record| {
+                    columns
+                        .iter()
+                        .enumerate()
+                        .map(|(index, column)| {
+                            typed_value(record.get(index), column.data_type, &decimal)
+                        })
+                        .collect()
+                })
+                .collect();
+
+            Ok(TablePreview {
+                row_count: rows.len() as u32,
+                rows: serde_json::to_string(&rows)
+                    .map_err(|e| format!("Failed to serialize rows: {}", e))?,
+                columns,
+                dialect,
+                encoding: data.encoding,
+                total_size: data.total_size.to_string(),
+                truncated,
+            })
+        })
+        .await
+        .map_err(|e| format!("Table preview task failed: {}", e))?
+    }
+}
+
+/// 无法识别方言时按扩展名选择分隔符
+fn default_dialect(name: &str) -> CsvDialect {
+    let lower = name.to_lowercase();
+    let tab = lower.ends_with(".tsv") || lower.contains(".tsv.") || lower.ends_with(".tab");
+    CsvDialect {
+        delimiter: if tab { "\t" } else { "," }.to_string(),
+        quote: "\"".to_string(),
+        has_header: true,
+        decimal_separator: ".".to_string(),
+        line_terminator: "\n".to_string(),
+    }
+}
+
+fn single_char(value: &str, name: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("Invalid {}: {:?}", name, value)),
+    }
+}
+
+fn is_null(value: Option<&String>) -> bool {
+    value.is_none_or(|value| NULL_TOKENS.contains(&value.trim()))
+}
+
+/// 按列中所有非空值推断类型，返回 (类型, 空值数)
+fn infer_type<'a>(
+    values: impl Iterator<Item = Option<&'a String>>,
+    decimal: &str,
+) -> (ColumnType, u32) {
+    let mut null_count = 0u32;
+    let mut candidates = [
+        ColumnType::Boolean,
+        ColumnType::Integer,
+        ColumnType::Float,
+        ColumnType::Date,
+        ColumnType::DateTime,
+    ]
+    .to_vec();
+    let mut seen = false;
+
+    for value in values {
+        if is_null(value) {
+            null_count += 1;
+            continue;
+        }
+        seen = true;
+        let value = value.map(|v| v.trim()).unwrap_or_default();
+        candidates.retain(|candidate| matches_type(value, *candidate, decimal));
+    }
+
+    let data_type = if !seen {
+        ColumnType::Null
+    } else if candidates.contains(&ColumnType::Boolean) {
+        ColumnType::Boolean
+    } else if candidates.contains(&ColumnType::Integer) {
+        ColumnType::Integer
+    } else {
+        candidates.first().copied().unwrap_or(ColumnType::String)
+    };
+    (data_type, null_count)
+}
+
+fn matches_type(value: &str, data_type: ColumnType, decimal: &str) -> bool {
+    match data_type {
+        ColumnType::Boolean => parse_bool(value).is_some(),
+        ColumnType::Integer => {
+            number_style(value) == Some(NumberStyle::Integer) && !has_leading_zero(value)
+        }
+        ColumnType::Float => parse_float(value, decimal).is_some() && !has_leading_zero(value),
+        ColumnType::Date => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        ColumnType::DateTime => parse_datetime(value),
+        ColumnType::String | ColumnType::Null => true,
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// 按识别出的小数分隔符解析数值，去掉千位分隔符
+fn parse_float(value: &str, decimal: &str) -> Option<f64> {
+    let style = number_style(value)?;
+    let normalized = match (style, decimal) {
+        (NumberStyle::CommaDecimal, ",") => value.replace('.', "").replace(',', "."),
+        (NumberStyle::CommaDecimal, _) => return None,
+        _ => value.replace(',', ""),
+    };
+    normalized.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// 带前导零的编号（如邮编、工号 007）按数值解析会丢失前导零，保留为字符串
+fn has_leading_zero(value: &str) -> bool {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn parse_datetime(value: &str) -> bool {
+    chrono::DateTime::parse_from_rfc3339(value).is_ok()
+        || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").is_ok()
+        || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").is_ok()
+}
+
+/// 按列类型转换单元格，无法转换或转换后会丢失信息的值保留为字符串
+fn typed_value(value: Option<&String>, data_type: ColumnType, decimal: &str) -> serde_json::Value {
+    if is_null(value) {
+        return serde_json::Value::Null;
+    }
+    let raw = value.map(String::as_str).unwrap_or_default();
+    let trimmed = raw.trim();
+    match data_type {
+        ColumnType::Boolean => parse_bool(trimmed).map(serde_json::Value::Bool),
+        ColumnType::Integer => trimmed
+            .parse::<i64>()
+            .ok()
+            .filter(|v| v.unsigned_abs() <= MAX_SAFE_INTEGER && !has_leading_zero(trimmed))
+            .map(|v| serde_json::Value::Number(v.into())),
+        ColumnType::Float => parse_float(trimmed, decimal)
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number),
+        _ => None,
+    }
+    .unwrap_or_else(|| serde_json::Value::String(raw.to_string()))
+}
@@ -0,0 +1,103 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// 默认读取的数据量
+pub const DEFAULT_MAX_BYTES: u32 = 1024 * 1024; // 1MB
+
+/// 单次预览允许读取的最大数据量，超出的请求会被截到该值
+pub const HARD_MAX_BYTES: u32 = 64 * 1024 * 1024; // 64MB
+
+/// JSONPath 查询读取的输入上限，查询需要完整的文档，不受 HARD_MAX_BYTES 限制，返回的结果仍按读取窗口截断
+/// 文档会整体解析为内存中的 JSON 树，解析后的树约为输入大小的 3～5 倍，
+/// 达到上限时单次查询的峰值内存约为 1～1.5GB
+pub const JSON_QUERY_MAX_BYTES: u32 = 256 * 1024 * 1024; // 256MB
+
+/// 默认返回的行数
+pub const DEFAULT_MAX_ROWS: u32 = 100;
+
+/// 单次预览允许返回的最大行数
+pub const HARD_MAX_ROWS: u32 = 10_000;
+
+/// 所有预览命令共用的读取窗口参数
+/// 返回结果中的 next_continuation 原样传回 continuation 即可读取下一段
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewWindow {
+    /// 最多读取的字节数，默认 1MB，上限 64MB
+    pub max_bytes: Option<u32>,
+    /// 最多返回的行数；文本预览未指定时不按行截断，表格预览默认 100 行，上限 10000 行
+    pub max_rows: Option<u32>,
+    /// 上一次预览返回的续读标记，为空时从头读取
+    pub continuation: Option<String>,
+}
+
+impl PreviewWindow {
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+            .unwrap_or(DEFAULT_MAX_BYTES)
+            .clamp(1, HARD_MAX_BYTES) as usize
+    }
+
+    /// 按行预览时的行数上限，未指定时为 None
+    pub fn row_limit(&self) -> Option<u32> {
+        self.max_rows.map(|rows| rows.clamp(1, HARD_MAX_ROWS))
+    }
+
+    /// 表格预览的行数，未指定时使用默认值
+    pub fn max_rows(&self) -> u32 {
+        self.row_limit().unwrap_or(DEFAULT_MAX_ROWS)
+    }
+
+    /// 解析续读位置，续读标记必须来自同一个预览来源
+    pub fn position(&self, source: &str) -> Result<Continuation, String> {
+        match &self.continuation {
+            Some(token) => Continuation::decode(token, source),
+            None => Ok(Continuation {
+                source: source.to_string(),
+                offset: 0,
+                row: 0,
+            }),
+        }
+    }
+}
+
+/// 续读位置，编码为不透明的字符串在前端和后端之间传递
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Continuation {
+    /// 预览来源标识（文件路径、压缩包条目、工作表等）
+    source: String,
+    /// 字节偏移
+    pub offset: u64,
+    /// 行偏移
+    pub row: u32,
+}
+
+impl Continuation {
+    pub fn new(source: &str, offset: u64, row: u32) -> Self {
+        Self {
+            source: source.to_string(),
+            offset,
+            row,
+        }
+    }
+
+    pub fn encode(&self) -> Result<String, String> {
+        let json = serde_json::to_vec(self)
+            .map_err(|e| format!("Failed to encode continuation: {}", e))?;
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(json))
+    }
+
+    fn decode(token: &str, source: &str) -> Result<Self, String> {
+        let position: Self = general_purpose::URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| "Invalid continuation".to_string())?;
+
+        if position.source != source {
+            return Err("Continuation does not belong to this preview".to_string());
+        }
+        Ok(position)
+    }
+}
@@ -0,0 +1,512 @@
+// 压缩包挂载
+// 把压缩包当作只读目录：`<压缩包路径>!<条目路径>` 形式的路径在列表和读取时自动转换为压缩包处理器调用，
+// 预览、下载、协议读取等使用存储客户端的功能无需区分普通文件和压缩包条目
+
+use async_trait::async_trait;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
+
+use crate::archive::handlers::ArchiveHandler;
+use crate::archive::nested::resolve_nested_entry;
+use crate::archive::types::{ArchiveInfo, CompressionType};
+use crate::preview::source::PreviewSource;
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, ObjectMetadata, ObjectVersion, ParquetSplit,
+    ProgressCallback, RepositoryInfo, RepositoryRevision, StorageCapabilities, StorageClient,
+    StorageError, StorageFile,
+};
+use crate::storage::validators::Validators;
+use crate::storage::vfs::split_archive_entry;
+use crate::utils::mime::{detect_mime, mime_from_extension, mime_from_filename, MAGIC_SNIFF_LEN};
+
+/// 缓存的压缩包条目列表数量，展开目录时不必反复分析同一个压缩包
+const MAX_CACHED_ARCHIVES: usize = 8;
+
+/// 可以挂载为目录的压缩格式（单文件压缩格式只有一个条目，不挂载）
+fn is_mountable(filename: &str) -> bool {
+    matches!(
+        CompressionType::from_filename(filename),
+        CompressionType::Zip
+            | CompressionType::Tar
+            | CompressionType::TarGz
+            | CompressionType::TarZst
+            | CompressionType::SevenZip
+            | CompressionType::Rar
+    )
+}
+
+/// 把目录列表中可挂载的压缩包显示为目录
+/// filename 追加 `!`，前端拼接子路径后得到 `data.zip!/images` 形式的路径
+pub fn mount_archives(files: &mut [StorageFile]) {
+    for file in files
+        .iter_mut()
+        .filter(|file| file.file_type == "file" && is_mountable(&file.basename))
+    {
+        file.file_type = "directory".to_string();
+        file.filename.push('!');
+    }
+}
+
+/// 包装存储客户端，把压缩包条目路径转换为压缩包处理器调用，其余路径原样转发
+pub struct ArchiveMountClient {
+    inner: Arc<dyn StorageClient + Send + Sync>,
+    /// 最近分析的压缩包（路径、大小、条目列表），最近使用的在末尾
+    archives: Mutex<VecDeque<(String, u64, Arc<ArchiveInfo>)>>,
+}
+
+impl ArchiveMountClient {
+    pub fn new(inner: Arc<dyn StorageClient + Send + Sync>) -> Self {
+        Self {
+            inner,
+            archives: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 分析条目所在的压缩包（条目在内层压缩包中时为最内层压缩包），压缩包大小不变时使用缓存
+    /// 返回条目列表和条目在该压缩包内的路径
+    async fn archive_info(
+        &self,
+        archive_path: &str,
+        entry_path: &str,
+    ) -> Result<(Arc<ArchiveInfo>, String), StorageError> {
+        let (client, archive_path, entry_path) = resolve_nested_entry(
+            self.inner.clone(),
+            archive_path.to_string(),
+            entry_path.to_string(),
+            None,
+        );
+        let size = client.get_file_size(&archive_path).await?;
+        {
+            let mut archives = self.archives.lock().unwrap();
+            if let Some(index) = archives
+                .iter()
+                .position(|(path, cached_size, _)| *path == archive_path && *cached_size == size)
+            {
+                let cached = archives.remove(index).unwrap();
+                let info = cached.2.clone();
+                archives.push_back(cached);
+                return Ok((info, entry_path));
+            }
+        }
+
+        let filename = archive_path
+            .rsplit(['/', '!'])
+            .next()
+            .unwrap_or(&archive_path)
+            .to_string();
+        let info = ArchiveHandler::new()
+            .analyze_archive_with_client(client, archive_path.clone(), filename, None)
+            .await
+            .map(Arc::new)
+            .map_err(StorageError::RequestFailed)?;
+
+        let mut archives = self.archives.lock().unwrap();
+        archives.retain(|(path, _, _)| *path != archive_path);
+        archives.push_back((archive_path, size, info.clone()));
+        while archives.len() > MAX_CACHED_ARCHIVES {
+            archives.pop_front();
+        }
+        Ok((info, entry_path))
+    }
+
+    /// 列出压缩包内某个目录的直接子项，目录条目缺失时根据文件路径补全
+    async fn list_archive(
+        &self,
+        path: &str,
+        archive_path: &str,
+        entry_prefix: &str,
+    ) -> Result<DirectoryResult, StorageError> {
+        let (info, entry_prefix) = self.archive_info(archive_path, entry_prefix).await?;
+        let prefix = match entry_prefix.trim_matches('/') {
+            "" => String::new(),
+            dir => format!("{}/", dir),
+        };
+
+        let mut children: BTreeMap<String, StorageFile> = BTreeMap::new();
+        for entry in &info.entries {
+            let Some(rest) = entry.path.strip_prefix(&prefix) else {
+                continue;
+            };
+            let rest = rest.trim_end_matches('/');
+            if rest.is_empty() {
+                continue;
+            }
+            let (name, is_dir) = match rest.split_once('/') {
+                Some((dir, _)) => (dir, true),
+                None => (rest, entry.is_dir),
+            };
+            if is_dir && children.contains_key(name) {
+                continue;
+            }
+            children.insert(
+                name.to_string(),
+                StorageFile {
+                    filename: name.to_string(),
+                    basename: name.to_string(),
+                    lastmod: entry
+                        .modified_time
+                        .clone()
+                        .filter(|_| !is_dir || rest == name)
+                        .unwrap_or_default(),
+                    size: if is_dir {
+                        "0".to_string()
+                    } else {
+                        entry.size.clone()
+                    },
+                    file_type: if is_dir { "directory" } else { "file" }.to_string(),
+                    mime: (!is_dir).then(|| mime_from_filename(name)),
+                    etag: None,
+                    storage_class: None,
+                    lastmod_raw: None,
+                },
+            );
+        }
+
+        if children.is_empty() && !prefix.is_empty() {
+            return Err(StorageError::NotFound(path.to_string()));
+        }
+        let files: Vec<StorageFile> = children.into_values().collect();
+        Ok(DirectoryResult {
+            total_count: Some(files.len().to_string()),
+            files,
+            has_more: false,
+            next_marker: None,
+            path: path.to_string(),
+        })
+    }
+
+    /// 读取压缩包条目的指定范围
+    async fn read_entry(
+        &self,
+        archive_path: &str,
+        entry_path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<(Vec<u8>, u64), StorageError> {
+        let data = PreviewSource::new(archive_path.to_string(), Some(entry_path.to_string()))
+            .read(self.inner.clone(), start, length)
+            .await
+            .map_err(StorageError::RequestFailed)?;
+        Ok((data.bytes, data.total_size))
+    }
+
+    /// 拆分压缩包条目路径，条目路径为空时表示压缩包根目录
+    fn split(path: &str) -> Option<(&str, &str)> {
+        split_archive_entry(path).map(|(archive, entry)| (archive, entry.trim_start_matches('/')))
+    }
+
+    /// 压缩包被覆盖、删除或移动后丢弃缓存的条目列表
+    fn forget(&self, path: &str) {
+        let dir_prefix = format!("{}/", path.trim_end_matches('/'));
+        let nested_prefix = format!("{}!", path);
+        self.archives.lock().unwrap().retain(|(archive, _, _)| {
+            archive != path
+                && !archive.starts_with(&dir_prefix)
+                && !archive.starts_with(&nested_prefix)
+        });
+    }
+
+    fn read_only(path: &str) -> StorageError {
+        StorageError::ProtocolNotSupported(format!("{} is inside a mounted archive", path))
+    }
+}
+
+#[async_trait]
+impl StorageClient for ArchiveMountClient {
+    async fn connect(&mut self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        // 包装的客户端在创建前已经连接
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.inner.is_connected().await
+    }
+
+    async fn list_directory(
+        &self,
+        path: &str,
+        options: Option<&ListOptions>,
+    ) -> Result<DirectoryResult, StorageError> {
+        match Self::split(path) {
+            Some((archive, entry)) => self.list_archive(path, archive, entry).await,
+            None => self.inner.list_directory(path, options).await,
+        }
+    }
+
+    async fn read_file_range(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        match Self::split(path) {
+            Some((archive, entry)) => Ok(self.read_entry(archive, entry, start, length).await?.0),
+            None => self.inner.read_file_range(path, start, length).await,
+        }
+    }
+
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        if Self::split(path).is_none() {
+            return self
+                .inner
+                .read_file_range_with_progress(path, start, length, progress_callback, cancel_rx)
+                .await;
+        }
+        let data = self.read_file_range(path, start, length).await?;
+        if let Some(callback) = progress_callback {
+            callback(data.len() as u64, length);
+        }
+        Ok(data)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let Some((archive, entry)) = Self::split(path) else {
+            return self.inner.read_full_file(path).await;
+        };
+        let (bytes, total_size) = self.read_entry(archive, entry, 0, u32::MAX as u64).await?;
+        if (bytes.len() as u64) < total_size {
+            return Err(StorageError::RequestFailed(format!(
+                "Archive entry {} is too large to read at once",
+                path
+            )));
+        }
+        Ok(bytes)
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        let Some((archive, entry)) = Self::split(path) else {
+            return self.inner.get_file_size(path).await;
+        };
+        if entry.is_empty() {
+            return Ok(0);
+        }
+        let (info, inner_entry) = self.archive_info(archive, entry).await?;
+        if inner_entry.is_empty() {
+            // 内层压缩包的根目录
+            return Ok(0);
+        }
+        match info.entries.iter().find(|e| e.path == inner_entry) {
+            Some(found) => found.size.parse().map_err(|_| {
+                StorageError::RequestFailed(format!("Invalid entry size: {}", found.size))
+            }),
+            // 流式分析可能没有列出全部条目，直接读取获取大小
+            None => Ok(self.read_entry(archive, entry, 0, 1).await?.1),
+        }
+    }
+
+    async fn detect_mime(&self, path: &str) -> Result<String, StorageError> {
+        let Some((_, entry)) = Self::split(path) else {
+            return self.inner.detect_mime(path).await;
+        };
+        if let Some(mime) = mime_from_extension(entry) {
+            return Ok(mime.to_string());
+        }
+        let head = self.read_file_range(path, 0, MAGIC_SNIFF_LEN).await?;
+        Ok(detect_mime(entry, Some(&head)))
+    }
+
+    async fn download_file(
+        &self,
+        path: &str,
+        save_path: &std::path::Path,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        let Some((archive, entry)) = Self::split(path) else {
+            return self
+                .inner
+                .download_file(path, save_path, progress_callback, cancel_rx)
+                .await;
+        };
+
+        // 条目在一次解压中顺序读完，不按偏移反复从头解压
+        let mut stream = PreviewSource::new(archive.to_string(), Some(entry.to_string()))
+            .open_stream(self.inner.clone())
+            .await
+            .map_err(StorageError::RequestFailed)?;
+        let mut file = tokio::fs::File::create(save_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to create file: {}", e)))?;
+        let mut offset = 0u64;
+        loop {
+            if let Some(rx) = cancel_rx.as_mut() {
+                if rx.try_recv().is_ok() {
+                    drop(file);
+                    let _ = tokio::fs::remove_file(save_path).await;
+                    return Err(StorageError::RequestFailed(
+                        "download.cancelled".to_string(),
+                    ));
+                }
+            }
+            let Some(bytes) = stream.next_chunk().await else {
+                break;
+            };
+            let bytes = bytes.map_err(StorageError::RequestFailed)?;
+            file.write_all(&bytes)
+                .await
+                .map_err(|e| StorageError::IoError(format!("Failed to write file: {}", e)))?;
+            offset += bytes.len() as u64;
+            if let Some(callback) = &progress_callback {
+                callback(offset, stream.size().unwrap_or(offset));
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to write file: {}", e)))
+    }
+
+    async fn upload_file(
+        &self,
+        local_path: &std::path::Path,
+        path: &str,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        let result = self
+            .inner
+            .upload_file(local_path, path, progress_callback, cancel_rx)
+            .await;
+        self.forget(path);
+        result
+    }
+
+    async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        let result = self.inner.delete_file(path).await;
+        self.forget(path);
+        result
+    }
+
+    async fn delete_directory(&self, path: &str) -> Result<(), StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        let result = self.inner.delete_directory(path).await;
+        self.forget(path);
+        result
+    }
+
+    async fn move_to_trash(&self, path: &str) -> Result<(), StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        let result = self.inner.move_to_trash(path).await;
+        self.forget(path);
+        result
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        if Self::split(from).is_some() {
+            return Err(Self::read_only(from));
+        }
+        if Self::split(to).is_some() {
+            return Err(Self::read_only(to));
+        }
+        let result = self.inner.rename(from, to).await;
+        self.forget(from);
+        self.forget(to);
+        result
+    }
+
+    async fn create_directory(&self, path: &str) -> Result<(), StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.create_directory(path).await
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn restore_object(
+        &self,
+        path: &str,
+        days: u32,
+        tier: Option<&str>,
+    ) -> Result<String, StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.restore_object(path, days, tier).await
+    }
+
+    async fn list_object_versions(&self, path: &str) -> Result<Vec<ObjectVersion>, StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.list_object_versions(path).await
+    }
+
+    async fn list_revisions(&self, path: &str) -> Result<Vec<RepositoryRevision>, StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.list_revisions(path).await
+    }
+
+    async fn get_repository_info(&self, path: &str) -> Result<RepositoryInfo, StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.get_repository_info(path).await
+    }
+
+    async fn list_parquet_splits(&self, path: &str) -> Result<Vec<ParquetSplit>, StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.list_parquet_splits(path).await
+    }
+
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        if Self::split(path).is_some() {
+            return Err(Self::read_only(path));
+        }
+        self.inner.get_object_etag(path).await
+    }
+
+    async fn get_metadata(&self, path: &str) -> Result<ObjectMetadata, StorageError> {
+        let Some((archive, _)) = Self::split(path) else {
+            return self.inner.get_metadata(path).await;
+        };
+        // 条目随压缩包变化，使用压缩包的 ETag 和修改时间
+        let archive_metadata = self.inner.get_metadata(archive).await?;
+        Ok(ObjectMetadata {
+            size: self.get_file_size(path).await?.to_string(),
+            ..archive_metadata
+        })
+    }
+
+    fn observed_validators(&self, path: &str) -> Option<Validators> {
+        match Self::split(path) {
+            Some((archive, _)) => self.inner.observed_validators(archive),
+            None => self.inner.observed_validators(path),
+        }
+    }
+
+    fn local_path(&self, path: &str) -> Option<std::path::PathBuf> {
+        // 压缩包内的条目没有对应的本地路径
+        match Self::split(path) {
+            Some(_) => None,
+            None => self.inner.local_path(path),
+        }
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        self.inner.validate_config(config)
+    }
+}
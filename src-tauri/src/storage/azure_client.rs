@@ -0,0 +1,636 @@
+use async_trait::async_trait;
+use base64::Engine;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use url::Url;
+
+use crate::storage::manager::{shared_http_client, HttpClientKind};
+use crate::storage::range_read::read_range_body;
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
+    StorageFile,
+};
+use crate::utils::crypto::hmac_sha256_bytes;
+use crate::utils::http_downloader::{HttpDownloadConfig, HttpDownloader};
+use crate::utils::http_guard;
+use crate::utils::mime::mime_from_filename;
+
+/// 请求使用的 Blob 服务 REST API 版本
+const API_VERSION: &str = "2021-08-06";
+
+/// Azure Blob 认证方式
+#[derive(Debug, Clone)]
+enum AzureAuth {
+    /// 存储账户共享密钥（base64 解码后的密钥）
+    SharedKey(Vec<u8>),
+    /// SAS 令牌（不含开头的 ?）
+    Sas(String),
+    /// 匿名访问公开容器
+    Anonymous,
+}
+
+/// Azure Blob Storage 客户端
+/// 连接配置：
+/// - url: 账户端点（如 https://account.blob.core.windows.net），为空时由账户名生成
+/// - access_key: 存储账户名称
+/// - secret_key: 账户共享密钥；password: SAS 令牌；都未提供时匿名访问
+/// - bucket: 容器名称，支持 "container/path/prefix" 格式
+pub struct AzureBlobClient {
+    client: Client,
+    connected: AtomicBool,
+    endpoint: String,
+    account: String,
+    container: String,
+    prefix: String, // 从 bucket 字段解析出的路径前缀
+    auth: AzureAuth,
+}
+
+impl AzureBlobClient {
+    pub fn new(config: ConnectionConfig) -> Result<Self, StorageError> {
+        let container_input = config
+            .bucket
+            .clone()
+            .filter(|container| !container.trim().is_empty())
+            .ok_or_else(|| {
+                StorageError::InvalidConfig("Azure container is required".to_string())
+            })?;
+
+        // 解析 bucket 字段，支持 "container/path/prefix" 格式
+        let (container, prefix) = match container_input.trim_matches('/').split_once('/') {
+            Some((container, prefix)) => (container.to_string(), format!("{}/", prefix)),
+            None => (container_input.trim_matches('/').to_string(), String::new()),
+        };
+
+        let configured_endpoint = config
+            .url
+            .clone()
+            .or_else(|| config.endpoint.clone())
+            .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+            .map(|url| url.trim_end_matches('/').to_string());
+
+        // 账户名优先取配置，否则从端点主机名的第一段解析
+        let account = config
+            .access_key
+            .clone()
+            .filter(|account| !account.is_empty())
+            .or_else(|| {
+                configured_endpoint.as_deref().and_then(|endpoint| {
+                    Url::parse(endpoint)
+                        .ok()?
+                        .host_str()?
+                        .split('.')
+                        .next()
+                        .map(str::to_string)
+                })
+            })
+            .ok_or_else(|| {
+                StorageError::InvalidConfig("Azure storage account name is required".to_string())
+            })?;
+
+        let endpoint = configured_endpoint
+            .unwrap_or_else(|| format!("https://{}.blob.core.windows.net", account));
+
+        let auth = Self::parse_auth(&config)?;
+
+        Ok(Self {
+            // 同一客户端同时用于列表和数据读取，使用传输超时
+            client: shared_http_client(HttpClientKind::Transfer),
+            connected: AtomicBool::new(false),
+            endpoint,
+            account,
+            container,
+            prefix,
+            auth,
+        })
+    }
+
+    fn parse_auth(config: &ConnectionConfig) -> Result<AzureAuth, StorageError> {
+        if let Some(key) = config.secret_key.as_deref().filter(|key| !key.is_empty()) {
+            let key = base64::engine::general_purpose::STANDARD
+                .decode(key.trim())
+                .map_err(|e| StorageError::InvalidConfig(format!("Invalid account key: {}", e)))?;
+            return Ok(AzureAuth::SharedKey(key));
+        }
+
+        Ok(
+            match config.password.as_deref().filter(|token| !token.is_empty()) {
+                Some(token) => AzureAuth::Sas(token.trim_start_matches('?').to_string()),
+                None => AzureAuth::Anonymous,
+            },
+        )
+    }
+
+    /// 解析 Blob 名称，支持 azure://container/blob 协议 URL 和相对路径（自动添加前缀）
+    fn blob_name(&self, path: &str) -> Result<String, StorageError> {
+        match path.strip_prefix("azure://") {
+            Some(rest) => {
+                let rest = rest.split('?').next().unwrap_or(rest);
+                let (container, blob) = rest.split_once('/').unwrap_or((rest, ""));
+                if container != self.container {
+                    return Err(StorageError::RequestFailed(format!(
+                        "Container mismatch: url='{}' != configured='{}'",
+                        container, self.container
+                    )));
+                }
+                Ok(blob.to_string())
+            }
+            None => Ok(format!("{}{}", self.prefix, path.trim_start_matches('/'))),
+        }
+    }
+
+    /// 编码后的资源路径（/container 或 /container/blob）
+    fn resource_path(&self, blob: Option<&str>) -> String {
+        let mut path = format!("/{}", urlencoding::encode(&self.container));
+        if let Some(blob) = blob {
+            for segment in blob.split('/') {
+                path.push('/');
+                path.push_str(&urlencoding::encode(segment));
+            }
+        }
+        path
+    }
+
+    /// 构建请求 URL 和请求头，共享密钥认证时计算签名
+    /// query 为未编码的查询参数
+    fn sign_request(
+        &self,
+        method: &str,
+        resource_path: &str,
+        query: &[(&str, String)],
+        range: Option<(u64, u64)>,
+    ) -> (String, HashMap<String, String>) {
+        let mut headers = HashMap::new();
+        headers.insert("x-ms-version".to_string(), API_VERSION.to_string());
+        headers.insert(
+            "x-ms-date".to_string(),
+            chrono::Utc::now()
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+        );
+        if let Some((start, end)) = range {
+            headers.insert("x-ms-range".to_string(), format!("bytes={}-{}", start, end));
+        }
+
+        let mut query_string = query
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        match &self.auth {
+            AzureAuth::SharedKey(key) => {
+                let string_to_sign = self.string_to_sign(method, resource_path, query, &headers);
+                let signature = base64::engine::general_purpose::STANDARD
+                    .encode(hmac_sha256_bytes(key, &string_to_sign));
+                headers.insert(
+                    "Authorization".to_string(),
+                    format!("SharedKey {}:{}", self.account, signature),
+                );
+            }
+            AzureAuth::Sas(token) => {
+                if !query_string.is_empty() {
+                    query_string.push('&');
+                }
+                query_string.push_str(token);
+            }
+            AzureAuth::Anonymous => {}
+        }
+
+        let url = if query_string.is_empty() {
+            format!("{}{}", self.endpoint, resource_path)
+        } else {
+            format!("{}{}?{}", self.endpoint, resource_path, query_string)
+        };
+        (url, headers)
+    }
+
+    /// 共享密钥签名字符串
+    /// GET/HEAD 请求没有请求体，标准头部字段均为空，Range 通过 x-ms-range 传递
+    fn string_to_sign(
+        &self,
+        method: &str,
+        resource_path: &str,
+        query: &[(&str, String)],
+        headers: &HashMap<String, String>,
+    ) -> String {
+        let mut ms_headers: Vec<_> = headers
+            .iter()
+            .filter(|(name, _)| name.starts_with("x-ms-"))
+            .collect();
+        ms_headers.sort();
+        let canonical_headers: String = ms_headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+
+        let mut canonical_resource = format!("/{}{}", self.account, resource_path);
+        let mut params: Vec<_> = query
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.as_str()))
+            .collect();
+        params.sort();
+        for (name, value) in params {
+            canonical_resource.push_str(&format!("\n{}:{}", name, value));
+        }
+
+        format!(
+            "{}\n\n\n\n\n\n\n\n\n\n\n\n{}{}",
+            method, canonical_headers, canonical_resource
+        )
+    }
+
+    /// 发送请求，非成功状态转换为对应的错误
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        resource_path: &str,
+        query: &[(&str, String)],
+        range: Option<(u64, u64)>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let (url, headers) = self.sign_request(method.as_str(), resource_path, query, range);
+
+        let mut request = self.client.request(method, &url);
+        for (name, value) in headers {
+            request = request.header(&name, &value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Azure request failed: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = http_guard::error_body(response).await;
+        Err(match status {
+            reqwest::StatusCode::NOT_FOUND => StorageError::NotFound(resource_path.to_string()),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                StorageError::AuthenticationFailed(format!("Status {}: {}", status, body))
+            }
+            _ => StorageError::RequestFailed(format!(
+                "Azure request failed with status {}: {}",
+                status, body
+            )),
+        })
+    }
+
+    /// 获取 Blob 属性（HEAD 请求的响应头）
+    async fn get_properties(&self, path: &str) -> Result<reqwest::header::HeaderMap, StorageError> {
+        let blob = self.blob_name(path)?;
+        let response = self
+            .send(
+                reqwest::Method::HEAD,
+                &self.resource_path(Some(&blob)),
+                &[],
+                None,
+            )
+            .await?;
+        Ok(response.headers().clone())
+    }
+}
+
+/// 解析 List Blobs 的 XML 响应，只保留 prefix 下的直接子项
+fn parse_list_blobs_response(xml: &str, prefix: &str) -> Result<DirectoryResult, StorageError> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut files = Vec::new();
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut blob: Option<StorageFile> = None;
+    let mut next_marker = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if name == "Blob" {
+                    blob = Some(StorageFile {
+                        filename: String::new(),
+                        basename: String::new(),
+                        lastmod: String::new(),
+                        size: "0".to_string(),
+                        file_type: "file".to_string(),
+                        mime: None,
+                        etag: None,
+                        storage_class: None,
+                        lastmod_raw: None,
+                    });
+                }
+                path.push(name);
+                text.clear();
+            }
+            Ok(Event::Text(e)) => {
+                text = e.unescape().unwrap_or_default().to_string();
+            }
+            Ok(Event::End(_)) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str).unwrap_or("");
+
+                match (parent, name.as_str()) {
+                    ("Blob", "Name") => {
+                        if let Some(ref mut blob) = blob {
+                            let relative = text.strip_prefix(prefix).unwrap_or(&text);
+                            blob.filename = relative.to_string();
+                            blob.basename =
+                                relative.rsplit('/').next().unwrap_or(relative).to_string();
+                            blob.mime = Some(mime_from_filename(&blob.basename));
+                        }
+                    }
+                    ("Properties", field) => {
+                        if let Some(ref mut blob) = blob {
+                            match field {
+                                "Last-Modified" => blob.lastmod = text.clone(),
+                                "Content-Length" => {
+                                    blob.size = text.parse::<u64>().unwrap_or(0).to_string()
+                                }
+                                "Etag" => blob.etag = Some(text.trim_matches('"').to_string()),
+                                "AccessTier" => blob.storage_class = Some(text.clone()),
+                                _ => {}
+                            }
+                        }
+                    }
+                    (_, "Blob") => {
+                        if let Some(blob) = blob.take() {
+                            if !blob.filename.is_empty() && !blob.filename.contains('/') {
+                                files.push(blob);
+                            }
+                        }
+                    }
+                    ("BlobPrefix", "Name") => {
+                        let relative = text.strip_prefix(prefix).unwrap_or(&text);
+                        let dir = relative.trim_end_matches('/');
+                        if !dir.is_empty() && !dir.contains('/') {
+                            files.push(StorageFile {
+                                filename: dir.to_string(),
+                                basename: dir.to_string(),
+                                lastmod: String::new(),
+                                size: "0".to_string(),
+                                file_type: "directory".to_string(),
+                                mime: None,
+                                etag: None,
+                                storage_class: None,
+                                lastmod_raw: None,
+                            });
+                        }
+                    }
+                    ("EnumerationResults", "NextMarker") => {
+                        if !text.is_empty() {
+                            next_marker = Some(text.clone());
+                        }
+                    }
+                    _ => {}
+                }
+                text.clear();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(StorageError::RequestFailed(format!(
+                    "XML parsing error: {}",
+                    e
+                )))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(DirectoryResult {
+        files,
+        has_more: next_marker.is_some(),
+        next_marker,
+        total_count: None,
+        path: prefix.to_string(),
+    })
+}
+
+#[async_trait]
+impl StorageClient for AzureBlobClient {
+    async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        let updated = Self::new(config.clone())?;
+        self.endpoint = updated.endpoint;
+        self.account = updated.account;
+        self.container = updated.container;
+        self.prefix = updated.prefix;
+        self.auth = updated.auth;
+
+        // 列出一个 Blob 验证认证和容器访问权限
+        let query = [
+            ("comp", "list".to_string()),
+            ("maxresults", "1".to_string()),
+            ("restype", "container".to_string()),
+        ];
+        self.send(
+            reqwest::Method::GET,
+            &self.resource_path(None),
+            &query,
+            None,
+        )
+        .await
+        .map_err(|e| match e {
+            StorageError::NotFound(_) => {
+                StorageError::ConnectionFailed(format!("Container not found: {}", self.container))
+            }
+            other => other,
+        })?;
+
+        self.connected.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    async fn list_directory(
+        &self,
+        path: &str,
+        options: Option<&ListOptions>,
+    ) -> Result<DirectoryResult, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let directory = match self.blob_name(path)?.trim_end_matches('/') {
+            "" => String::new(),
+            dir => format!("{}/", dir),
+        };
+        let mut prefix = directory.clone();
+        if let Some(filter) = options.and_then(|o| o.prefix.as_ref()) {
+            prefix.push_str(filter);
+        }
+
+        let page_size = options.and_then(|o| o.page_size).unwrap_or(1000);
+        let mut query = vec![
+            ("comp", "list".to_string()),
+            ("maxresults", page_size.to_string()),
+            ("restype", "container".to_string()),
+        ];
+        if !prefix.is_empty() {
+            query.push(("prefix", prefix));
+        }
+        if !options.and_then(|o| o.recursive).unwrap_or(false) {
+            query.push(("delimiter", "/".to_string()));
+        }
+        if let Some(marker) = options.and_then(|o| o.marker.as_ref()) {
+            if !marker.is_empty() {
+                query.push(("marker", marker.clone()));
+            }
+        }
+
+        let response = self
+            .send(
+                reqwest::Method::GET,
+                &self.resource_path(None),
+                &query,
+                None,
+            )
+            .await?;
+        let xml = http_guard::read_text(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read response: {}", e)))?;
+
+        parse_list_blobs_response(&xml, &directory)
+    }
+
+    async fn read_file_range(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.read_file_range_with_progress(path, start, length, None, None)
+            .await
+    }
+
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let blob = self.blob_name(path)?;
+        let response = self
+            .send(
+                reqwest::Method::GET,
+                &self.resource_path(Some(&blob)),
+                &[],
+                Some((start, start + length - 1)),
+            )
+            .await?;
+
+        // 流式读取以支持进度回调，最多读取请求的长度，长时间没有数据时结束
+        let (result, _) =
+            read_range_body(response, start, length, progress_callback, cancel_rx).await?;
+        Ok(result)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let blob = self.blob_name(path)?;
+        let response = self
+            .send(
+                reqwest::Method::GET,
+                &self.resource_path(Some(&blob)),
+                &[],
+                None,
+            )
+            .await?;
+        http_guard::read_body(response, http_guard::MAX_FULL_FILE_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read file: {}", e)))
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        self.get_properties(path)
+            .await?
+            .get("content-length")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .ok_or_else(|| StorageError::RequestFailed("Content-Length not available".to_string()))
+    }
+
+    /// 优先返回 Content-MD5（十六进制），分块上传的 Blob 没有 MD5 时返回 ETag
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let headers = self.get_properties(path).await?;
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        header("content-md5")
+            .and_then(|md5| base64::engine::general_purpose::STANDARD.decode(md5).ok())
+            .map(hex::encode)
+            .or_else(|| header("etag").map(|etag| etag.trim_matches('"').to_string()))
+            .ok_or_else(|| StorageError::RequestFailed("ETag not available".to_string()))
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        if config.protocol != "azure" {
+            return Err(StorageError::InvalidConfig(
+                "Protocol must be 'azure'".to_string(),
+            ));
+        }
+        if config.bucket.as_deref().unwrap_or("").trim().is_empty() {
+            return Err(StorageError::InvalidConfig(
+                "Azure container is required".to_string(),
+            ));
+        }
+        Self::parse_auth(config).map(|_| ())
+    }
+
+    async fn download_file(
+        &self,
+        path: &str,
+        save_path: &std::path::Path,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let blob = self.blob_name(path)?;
+        let (url, headers) = self.sign_request("GET", &self.resource_path(Some(&blob)), &[], None);
+        let mut config = HttpDownloadConfig::new(url);
+        config.headers = headers;
+
+        HttpDownloader::download_stream(
+            &self.client,
+            config,
+            save_path,
+            progress_callback,
+            cancel_rx,
+        )
+        .await
+    }
+}
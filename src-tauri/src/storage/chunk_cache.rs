@@ -0,0 +1,596 @@
+// 远程范围读取的本地磁盘缓存
+// 范围读取的数据按固定大小分块，以 (连接, 路径, 版本, 块序号) 的哈希为文件名保存在应用数据目录的 cache/ 下，
+// 反复浏览同一压缩包或预览同一 Parquet 文件时直接读取磁盘，不再重复下载；总大小超过上限时淘汰最久未使用的块
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, ObjectMetadata, ObjectVersion, ParquetSplit,
+    ProgressCallback, RepositoryInfo, RepositoryRevision, StorageCapabilities, StorageClient,
+    StorageError,
+};
+use crate::storage::validators::Validators;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+use crate::utils::retention::{self, CacheCategory};
+
+/// 缓存块大小，范围读取按块对齐后缓存
+const CHUNK_SIZE: u64 = 1024 * 1024; // 1MB
+
+/// 超过该长度的范围读取不经过缓存
+const MAX_CACHED_READ: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 文件版本（ETag、修改时间或大小）在内存中的有效时间，期间读取同一文件不再重复查询
+const VERSION_TTL: Duration = Duration::from_secs(30);
+
+/// 超过上限时淘汰到上限的该百分比，避免每写入一块都触发淘汰
+const EVICT_TARGET_PERCENT: u64 = 90;
+
+/// 磁盘上的缓存块索引，首次使用时扫描缓存目录建立
+static INDEX: LazyLock<Mutex<Option<ChunkIndex>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Default)]
+struct ChunkIndex {
+    /// 块文件名 -> (大小, 最近使用时间)
+    chunks: HashMap<String, (u64, SystemTime)>,
+    total_size: u64,
+}
+
+impl ChunkIndex {
+    fn load() -> Self {
+        let mut index = Self::default();
+        let Ok(entries) = cache_dir().and_then(|dir| {
+            std::fs::read_dir(dir).map_err(|e| format!("Failed to read cache directory: {}", e))
+        }) else {
+            return index;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_file() {
+                let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                index.insert(
+                    entry.file_name().to_string_lossy().into_owned(),
+                    metadata.len(),
+                    used,
+                );
+            }
+        }
+        index
+    }
+
+    fn insert(&mut self, name: String, size: u64, used: SystemTime) {
+        if let Some((previous, _)) = self.chunks.insert(name, (size, used)) {
+            self.total_size -= previous;
+        }
+        self.total_size += size;
+    }
+
+    fn remove(&mut self, name: &str) {
+        if let Some((size, _)) = self.chunks.remove(name) {
+            self.total_size -= size;
+        }
+    }
+}
+
+fn with_index<T>(f: impl FnOnce(&mut ChunkIndex) -> T) -> Option<T> {
+    let mut guard = INDEX.lock().ok()?;
+    Some(f(guard.get_or_insert_with(ChunkIndex::load)))
+}
+
+/// 缓存块所在的目录
+pub fn cache_dir() -> Result<PathBuf, String> {
+    get_app_data_subdir("cache")
+}
+
+/// 删除所有缓存块，返回回收的字节数
+pub fn clear() -> Result<u64, String> {
+    let dir = cache_dir()?;
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("Failed to read cache directory: {}", e))?;
+
+    let mut reclaimed = 0u64;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_file() {
+            std::fs::remove_file(entry.path())
+                .map_err(|e| format!("Failed to remove {}: {}", entry.path().display(), e))?;
+            reclaimed += metadata.len();
+        }
+    }
+    if let Ok(mut index) = INDEX.lock() {
+        *index = Some(ChunkIndex::default());
+    }
+    Ok(reclaimed)
+}
+
+/// 读取缓存块，文件已被清理时从索引中移除
+fn load_chunk(name: &str) -> Option<Vec<u8>> {
+    let path = cache_dir().ok()?.join(name);
+    match std::fs::read(&path) {
+        Ok(data) => {
+            with_index(|index| {
+                index.insert(name.to_string(), data.len() as u64, SystemTime::now())
+            });
+            Some(data)
+        }
+        Err(_) => {
+            with_index(|index| index.remove(name));
+            None
+        }
+    }
+}
+
+/// 写入一批缓存块，全部写完后统一更新索引，超过上限时淘汰最久未使用的块。写入失败不影响读取
+fn store_chunks(chunks: Vec<(String, Vec<u8>)>) {
+    let dir = match cache_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("写入范围读取缓存失败: {}", e);
+            return;
+        }
+    };
+
+    let mut stored = Vec::with_capacity(chunks.len());
+    for (name, data) in chunks {
+        match persist::write_atomic(dir.join(&name), &data) {
+            Ok(()) => stored.push((name, data.len() as u64)),
+            Err(e) => log::warn!("写入范围读取缓存失败 {}: {}", name, e),
+        }
+    }
+    if stored.is_empty() {
+        return;
+    }
+
+    let max_size = retention::max_size(CacheCategory::RangeCache);
+    let evicted = with_index(|index| {
+        let now = SystemTime::now();
+        for (name, size) in stored {
+            index.insert(name, size, now);
+        }
+        match max_size {
+            Some(max_size) if index.total_size > max_size => {
+                evict(index, max_size / 100 * EVICT_TARGET_PERCENT)
+            }
+            _ => Vec::new(),
+        }
+    })
+    .unwrap_or_default();
+
+    for name in evicted {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+}
+
+/// 从最久未使用的块开始移出索引，直到总大小不超过 target，返回需要删除的块
+fn evict(index: &mut ChunkIndex, target: u64) -> Vec<String> {
+    let mut chunks: Vec<(String, SystemTime)> = index
+        .chunks
+        .iter()
+        .map(|(name, (_, used))| (name.clone(), *used))
+        .collect();
+    chunks.sort_by_key(|(_, used)| *used);
+
+    let mut evicted = Vec::new();
+    for (name, _) in chunks {
+        if index.total_size <= target {
+            break;
+        }
+        index.remove(&name);
+        evicted.push(name);
+    }
+    evicted
+}
+
+/// 删除缓存块
+fn remove_chunk(name: &str) {
+    with_index(|index| index.remove(name));
+    if let Ok(dir) = cache_dir() {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+}
+
+/// 元数据对应的缓存版本：优先使用 ETag / 修改时间，都没有时使用文件大小
+fn metadata_version(metadata: &ObjectMetadata) -> String {
+    Validators {
+        etag: metadata.etag.clone(),
+        last_modified: metadata.last_modified.clone(),
+    }
+    .version()
+    .unwrap_or_else(|| format!("size:{}", metadata.size))
+}
+
+/// 文件当前的缓存版本及该版本已缓存的块
+struct PathState {
+    version: String,
+    checked: Instant,
+    chunks: HashSet<u64>,
+}
+
+/// 包装存储客户端，范围读取优先使用磁盘缓存，其余操作原样转发
+/// 文件的 ETag / 修改时间变化时（查询元数据或读取响应中发现），删除旧版本的缓存块
+pub struct ChunkCacheClient {
+    inner: Arc<dyn StorageClient + Send + Sync>,
+    connection_key: String,
+    /// 最近查询的文件版本（路径 -> 版本状态）
+    versions: Mutex<HashMap<String, PathState>>,
+}
+
+impl ChunkCacheClient {
+    pub fn new(inner: Arc<dyn StorageClient + Send + Sync>, connection_key: String) -> Self {
+        Self {
+            inner,
+            connection_key,
+            versions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 文件的缓存版本，超过有效时间后重新查询元数据
+    async fn version(&self, path: &str) -> Result<String, StorageError> {
+        if let Ok(versions) = self.versions.lock() {
+            if let Some(state) = versions.get(path) {
+                if state.checked.elapsed() < VERSION_TTL {
+                    return Ok(state.version.clone());
+                }
+            }
+        }
+
+        let metadata = self.inner.get_metadata(path).await?;
+        let version = metadata_version(&metadata);
+        self.update_version(path, &version);
+        Ok(version)
+    }
+
+    /// 记录文件的最新版本，版本变化时删除旧版本的缓存块
+    /// 重启后之前会话缓存的旧版本块不在记录中，由容量淘汰和保留策略清理
+    fn update_version(&self, path: &str, version: &str) {
+        let stale = {
+            let Ok(mut versions) = self.versions.lock() else {
+                return;
+            };
+            let state = versions
+                .entry(path.to_string())
+                .or_insert_with(|| PathState {
+                    version: version.to_string(),
+                    checked: Instant::now(),
+                    chunks: HashSet::new(),
+                });
+            state.checked = Instant::now();
+            if state.version == version {
+                return;
+            }
+            let old_version = std::mem::replace(&mut state.version, version.to_string());
+            let chunks = std::mem::take(&mut state.chunks);
+            (old_version, chunks)
+        };
+
+        let (old_version, chunks) = stale;
+        log::debug!("远程文件已变化，删除 {} 个缓存块: {}", chunks.len(), path);
+        for chunk in chunks {
+            remove_chunk(&self.chunk_name(path, &old_version, chunk));
+        }
+    }
+
+    /// 删除文件（或目录下所有文件）的全部缓存块，用于修改远程文件之后
+    fn forget(&self, path: &str) {
+        let removed: Vec<(String, PathState)> = match self.versions.lock() {
+            Ok(mut versions) => {
+                let dir_prefix = format!("{}/", path.trim_end_matches('/'));
+                let paths: Vec<String> = versions
+                    .keys()
+                    .filter(|key| *key == path || key.starts_with(&dir_prefix))
+                    .cloned()
+                    .collect();
+                paths
+                    .into_iter()
+                    .filter_map(|key| versions.remove(&key).map(|state| (key, state)))
+                    .collect()
+            }
+            Err(_) => return,
+        };
+        for (path, state) in removed {
+            for chunk in state.chunks {
+                remove_chunk(&self.chunk_name(&path, &state.version, chunk));
+            }
+        }
+    }
+
+    fn track_chunk(&self, path: &str, version: &str, chunk: u64) {
+        if let Ok(mut versions) = self.versions.lock() {
+            if let Some(state) = versions.get_mut(path).filter(|s| s.version == version) {
+                state.chunks.insert(chunk);
+            }
+        }
+    }
+
+    fn chunk_name(&self, path: &str, version: &str, chunk: u64) -> String {
+        let mut hasher = Sha256::new();
+        for part in [self.connection_key.as_str(), path, version] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(chunk.to_le_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// 按块读取，读取过程中发现文件已变化时按新版本重新读取一次
+    async fn read_cached(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        for _ in 0..2 {
+            let version = self.version(path).await?;
+            if let Some(data) = self.read_version(path, &version, start, length).await? {
+                return Ok(data);
+            }
+        }
+        // 文件仍在频繁变化，不使用缓存
+        self.inner.read_file_range(path, start, length).await
+    }
+
+    /// 读取指定版本的块，缺失的连续块合并为一次范围读取
+    /// 响应中的校验信息与版本不一致时返回空（新数据已按新版本缓存）
+    async fn read_version(
+        &self,
+        path: &str,
+        version: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        let first = start / CHUNK_SIZE;
+        let last = (start + length - 1) / CHUNK_SIZE;
+        let mut chunks: Vec<Option<Vec<u8>>> = (first..=last)
+            .map(|chunk| {
+                let data = load_chunk(&self.chunk_name(path, version, chunk))?;
+                self.track_chunk(path, version, chunk);
+                Some(data)
+            })
+            .collect();
+
+        let mut index = 0;
+        while index < chunks.len() {
+            if chunks[index].is_some() {
+                index += 1;
+                continue;
+            }
+            let run_end = (index..chunks.len())
+                .find(|&i| chunks[i].is_some())
+                .unwrap_or(chunks.len());
+            let run_first = first + index as u64;
+            let data = self
+                .inner
+                .read_file_range(
+                    path,
+                    run_first * CHUNK_SIZE,
+                    (run_end - index) as u64 * CHUNK_SIZE,
+                )
+                .await?;
+
+            let observed = self
+                .inner
+                .observed_validators(path)
+                .and_then(|validators| validators.version());
+            let data_version = observed.as_deref().unwrap_or(version);
+            if data_version != version {
+                self.update_version(path, data_version);
+            }
+
+            let mut pieces = data.chunks(CHUNK_SIZE as usize);
+            let mut fetched = Vec::with_capacity(run_end - index);
+            for (offset, slot) in chunks[index..run_end].iter_mut().enumerate() {
+                // 读到文件末尾后剩余的块为空，不写入缓存
+                let piece = pieces.next().unwrap_or_default();
+                let chunk = run_first + offset as u64;
+                if !piece.is_empty() {
+                    fetched.push((self.chunk_name(path, data_version, chunk), piece.to_vec()));
+                    self.track_chunk(path, data_version, chunk);
+                }
+                *slot = Some(piece.to_vec());
+            }
+            // 同一次读取的块在后台线程中一起写入
+            if !fetched.is_empty() {
+                if let Err(e) = tokio::task::spawn_blocking(move || store_chunks(fetched)).await {
+                    log::warn!("写入范围读取缓存失败: {}", e);
+                }
+            }
+            if data_version != version {
+                return Ok(None);
+            }
+            index = run_end;
+        }
+
+        // 拼接各块，遇到不完整的块（文件末尾）时停止
+        let mut data = Vec::with_capacity(length as usize);
+        for chunk in chunks.into_iter().flatten() {
+            let complete = chunk.len() as u64 == CHUNK_SIZE;
+            data.extend_from_slice(&chunk);
+            if !complete {
+                break;
+            }
+        }
+        let skip = ((start - first * CHUNK_SIZE) as usize).min(data.len());
+        let end = (skip + length as usize).min(data.len());
+        Ok(Some(data[skip..end].to_vec()))
+    }
+}
+
+#[async_trait]
+impl StorageClient for ChunkCacheClient {
+    async fn connect(&mut self, _config: &ConnectionConfig) -> Result<(), StorageError> {
+        // 包装的客户端在创建前已经连接
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.inner.is_connected().await
+    }
+
+    async fn list_directory(
+        &self,
+        path: &str,
+        options: Option<&ListOptions>,
+    ) -> Result<DirectoryResult, StorageError> {
+        self.inner.list_directory(path, options).await
+    }
+
+    async fn read_file_range(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        if length == 0 || length > MAX_CACHED_READ {
+            return self.inner.read_file_range(path, start, length).await;
+        }
+        self.read_cached(path, start, length).await
+    }
+
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        // 带进度回调的读取是分段下载或整段解压，数据只用一次，不写入缓存
+        if length == 0 || length > MAX_CACHED_READ || progress_callback.is_some() {
+            return self
+                .inner
+                .read_file_range_with_progress(path, start, length, progress_callback, cancel_rx)
+                .await;
+        }
+        self.read_cached(path, start, length).await
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        self.inner.read_full_file(path).await
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        self.inner.get_file_size(path).await
+    }
+
+    async fn detect_mime(&self, path: &str) -> Result<String, StorageError> {
+        self.inner.detect_mime(path).await
+    }
+
+    async fn download_file(
+        &self,
+        path: &str,
+        save_path: &std::path::Path,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        self.inner
+            .download_file(path, save_path, progress_callback, cancel_rx)
+            .await
+    }
+
+    async fn upload_file(
+        &self,
+        local_path: &std::path::Path,
+        path: &str,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        let result = self
+            .inner
+            .upload_file(local_path, path, progress_callback, cancel_rx)
+            .await;
+        // 上传失败时远程文件也可能已被部分覆盖
+        self.forget(path);
+        result
+    }
+
+    async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+        let result = self.inner.delete_file(path).await;
+        self.forget(path);
+        result
+    }
+
+    async fn delete_directory(&self, path: &str) -> Result<(), StorageError> {
+        let result = self.inner.delete_directory(path).await;
+        self.forget(path);
+        result
+    }
+
+    async fn move_to_trash(&self, path: &str) -> Result<(), StorageError> {
+        let result = self.inner.move_to_trash(path).await;
+        self.forget(path);
+        result
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        let result = self.inner.rename(from, to).await;
+        self.forget(from);
+        self.forget(to);
+        result
+    }
+
+    async fn create_directory(&self, path: &str) -> Result<(), StorageError> {
+        self.inner.create_directory(path).await
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn restore_object(
+        &self,
+        path: &str,
+        days: u32,
+        tier: Option<&str>,
+    ) -> Result<String, StorageError> {
+        self.inner.restore_object(path, days, tier).await
+    }
+
+    async fn list_object_versions(&self, path: &str) -> Result<Vec<ObjectVersion>, StorageError> {
+        self.inner.list_object_versions(path).await
+    }
+
+    async fn list_revisions(&self, path: &str) -> Result<Vec<RepositoryRevision>, StorageError> {
+        self.inner.list_revisions(path).await
+    }
+
+    async fn get_repository_info(&self, path: &str) -> Result<RepositoryInfo, StorageError> {
+        self.inner.get_repository_info(path).await
+    }
+
+    async fn list_parquet_splits(&self, path: &str) -> Result<Vec<ParquetSplit>, StorageError> {
+        self.inner.list_parquet_splits(path).await
+    }
+
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        self.inner.get_object_etag(path).await
+    }
+
+    async fn get_metadata(&self, path: &str) -> Result<ObjectMetadata, StorageError> {
+        // 总是查询最新的元数据，顺便使已变化文件的缓存失效
+        let metadata = self.inner.get_metadata(path).await?;
+        self.update_version(path, &metadata_version(&metadata));
+        Ok(metadata)
+    }
+
+    fn observed_validators(&self, path: &str) -> Option<Validators> {
+        self.inner.observed_validators(path)
+    }
+
+    fn local_path(&self, path: &str) -> Option<std::path::PathBuf> {
+        self.inner.local_path(path)
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        self.inner.validate_config(config)
+    }
+}
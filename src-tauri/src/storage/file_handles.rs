@@ -0,0 +1,196 @@
+// 供插件使用的远程文件随机读取句柄
+// 插件通过句柄按偏移读取文件，读取结果按块缓存，适合点云等需要自行定位的查看器
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::storage::traits::StorageClient;
+
+/// 缓存块大小
+const BLOCK_SIZE: u64 = 1024 * 1024; // 1MB
+
+/// 每个句柄最多缓存的块数
+const MAX_CACHED_BLOCKS: usize = 8;
+
+/// 每个插件同时打开的句柄数上限
+const MAX_HANDLES_PER_PLUGIN: usize = 8;
+
+/// 单次读取的最大长度
+pub const MAX_READ_LENGTH: u32 = 16 * 1024 * 1024; // 16MB
+
+/// 句柄闲置超过该时间后自动关闭
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+static HANDLES: LazyLock<Mutex<HashMap<String, Arc<FileHandle>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 打开的文件句柄
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHandleInfo {
+    pub handle: String,
+    pub size: String, // 使用字符串表示大数字
+}
+
+struct FileHandle {
+    plugin_id: String,
+    path: String,
+    size: u64,
+    client: Arc<dyn StorageClient>,
+    last_used: Mutex<Instant>,
+    /// 块序号 -> 数据，队列记录最近使用顺序（最近使用的在末尾）
+    cache: tokio::sync::Mutex<(HashMap<u64, Arc<Vec<u8>>>, VecDeque<u64>)>,
+}
+
+/// 打开文件，返回句柄
+pub async fn open(
+    client: Arc<dyn StorageClient>,
+    plugin_id: &str,
+    path: &str,
+) -> Result<FileHandleInfo, String> {
+    if plugin_id.is_empty() {
+        return Err("Plugin id is required".to_string());
+    }
+
+    let size = client
+        .get_file_size(path)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+    let mut handles = HANDLES
+        .lock()
+        .map_err(|_| "File handle table is poisoned".to_string())?;
+
+    // 清理闲置的句柄
+    handles.retain(|_, handle| {
+        handle
+            .last_used
+            .lock()
+            .map(|last_used| last_used.elapsed() < IDLE_TIMEOUT)
+            .unwrap_or(false)
+    });
+
+    let open_count = handles
+        .values()
+        .filter(|handle| handle.plugin_id == plugin_id)
+        .count();
+    if open_count >= MAX_HANDLES_PER_PLUGIN {
+        return Err(format!(
+            "Too many open files for plugin {} (limit {})",
+            plugin_id, MAX_HANDLES_PER_PLUGIN
+        ));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    handles.insert(
+        id.clone(),
+        Arc::new(FileHandle {
+            plugin_id: plugin_id.to_string(),
+            path: path.to_string(),
+            size,
+            client,
+            last_used: Mutex::new(Instant::now()),
+            cache: tokio::sync::Mutex::new((HashMap::new(), VecDeque::new())),
+        }),
+    );
+
+    Ok(FileHandleInfo {
+        handle: id,
+        size: size.to_string(),
+    })
+}
+
+/// 从指定偏移读取数据，超出文件末尾的部分被截断
+pub async fn pread(handle: &str, offset: u64, length: u32) -> Result<Vec<u8>, String> {
+    if length > MAX_READ_LENGTH {
+        return Err(format!(
+            "Read length {} exceeds limit of {} bytes",
+            length, MAX_READ_LENGTH
+        ));
+    }
+
+    let handle = get(handle)?;
+    if let Ok(mut last_used) = handle.last_used.lock() {
+        *last_used = Instant::now();
+    }
+
+    if offset >= handle.size || length == 0 {
+        return Ok(Vec::new());
+    }
+    let end = handle.size.min(offset + length as u64);
+
+    // 超过缓存容量的大块读取直接请求，避免挤掉已缓存的块
+    let first_block = offset / BLOCK_SIZE;
+    let last_block = (end - 1) / BLOCK_SIZE;
+    if (last_block - first_block + 1) as usize > MAX_CACHED_BLOCKS {
+        return handle.read(offset, end - offset).await;
+    }
+
+    let mut cache = handle.cache.lock().await;
+    let (blocks, order) = &mut *cache;
+
+    // 连续缺失的块合并为一次请求
+    let missing: Vec<u64> = (first_block..=last_block)
+        .filter(|block| !blocks.contains_key(block))
+        .collect();
+    for run in missing.chunk_by(|a, b| b == &(a + 1)) {
+        let run_start = run[0] * BLOCK_SIZE;
+        let run_end = handle.size.min((run[run.len() - 1] + 1) * BLOCK_SIZE);
+        let data = handle.read(run_start, run_end - run_start).await?;
+        for (block, chunk) in run.iter().zip(data.chunks(BLOCK_SIZE as usize)) {
+            blocks.insert(*block, Arc::new(chunk.to_vec()));
+        }
+    }
+
+    let mut result = Vec::with_capacity((end - offset) as usize);
+    for block in first_block..=last_block {
+        let data = blocks
+            .get(&block)
+            .cloned()
+            .ok_or_else(|| format!("Short read from {}", handle.path))?;
+        let block_start = block * BLOCK_SIZE;
+        let from = (offset.max(block_start) - block_start) as usize;
+        let to = ((end.min(block_start + BLOCK_SIZE) - block_start) as usize).min(data.len());
+        result.extend_from_slice(&data[from.min(to)..to]);
+
+        order.retain(|cached| *cached != block);
+        order.push_back(block);
+    }
+
+    while order.len() > MAX_CACHED_BLOCKS {
+        if let Some(evicted) = order.pop_front() {
+            blocks.remove(&evicted);
+        }
+    }
+
+    Ok(result)
+}
+
+/// 关闭句柄，释放缓存
+pub fn close(handle: &str) -> Result<(), String> {
+    HANDLES
+        .lock()
+        .map_err(|_| "File handle table is poisoned".to_string())?
+        .remove(handle)
+        .map(|_| ())
+        .ok_or_else(|| format!("Invalid file handle: {}", handle))
+}
+
+fn get(handle: &str) -> Result<Arc<FileHandle>, String> {
+    HANDLES
+        .lock()
+        .map_err(|_| "File handle table is poisoned".to_string())?
+        .get(handle)
+        .cloned()
+        .ok_or_else(|| format!("Invalid file handle: {}", handle))
+}
+
+impl FileHandle {
+    async fn read(&self, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+        self.client
+            .read_file_range(&self.path, offset, length)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", self.path, e))
+    }
+}
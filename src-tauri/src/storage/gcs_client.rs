@@ -0,0 +1,580 @@
+use async_trait::async_trait;
+use base64::Engine;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::storage::manager::{shared_http_client, HttpClientKind};
+use crate::storage::range_read::read_range_body;
+use crate::storage::traits::{
+    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
+    StorageFile,
+};
+use crate::utils::http_downloader::{HttpDownloadConfig, HttpDownloader};
+use crate::utils::http_guard;
+use crate::utils::mime::mime_from_filename;
+
+/// 默认的 GCS JSON API 端点
+const DEFAULT_ENDPOINT: &str = "https://storage.googleapis.com";
+
+/// 服务账号换取访问令牌时申请的权限范围
+const READ_ONLY_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_only";
+
+/// 访问令牌提前刷新的时间，避免请求途中过期
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// 服务账号密钥文件（JSON）中用到的字段
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+/// 服务账号 JWT 声明
+#[derive(Debug, Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// GCS 认证方式
+#[derive(Debug, Clone)]
+enum GCSAuth {
+    /// 服务账号密钥，按需换取并缓存访问令牌
+    ServiceAccount(ServiceAccountKey),
+    /// 直接使用的 OAuth 访问令牌
+    Token(String),
+    /// 匿名访问公开 bucket
+    Anonymous,
+}
+
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+/// objects.list 响应
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectList {
+    #[serde(default)]
+    items: Vec<ObjectResource>,
+    #[serde(default)]
+    prefixes: Vec<String>,
+    next_page_token: Option<String>,
+}
+
+/// 对象元数据，size 在 JSON API 中为字符串
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectResource {
+    name: String,
+    size: Option<String>,
+    updated: Option<String>,
+    etag: Option<String>,
+    md5_hash: Option<String>,
+    storage_class: Option<String>,
+}
+
+/// Google Cloud Storage 客户端，基于 JSON API
+/// 认证方式按以下顺序选择：
+/// - private_key_path: 服务账号密钥文件路径
+/// - secret_key: 服务账号密钥 JSON 内容
+/// - password: OAuth 访问令牌
+/// - 都未提供时匿名访问（仅适用于公开 bucket）
+pub struct GCSClient {
+    client: Client,
+    connected: AtomicBool,
+    endpoint: String,
+    bucket: String,
+    prefix: String, // 从 bucket 字段解析出的路径前缀
+    auth: GCSAuth,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl GCSClient {
+    pub fn new(config: ConnectionConfig) -> Result<Self, StorageError> {
+        let bucket_input = config
+            .bucket
+            .clone()
+            .filter(|bucket| !bucket.trim().is_empty())
+            .ok_or_else(|| StorageError::InvalidConfig("GCS bucket is required".to_string()))?;
+
+        // 解析 bucket 字段，支持 "bucket/path/prefix" 格式
+        let (bucket, prefix) = match bucket_input.trim_matches('/').split_once('/') {
+            Some((bucket, prefix)) => (bucket.to_string(), format!("{}/", prefix)),
+            None => (bucket_input.trim_matches('/').to_string(), String::new()),
+        };
+
+        let endpoint = config
+            .url
+            .clone()
+            .or_else(|| config.endpoint.clone())
+            .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let auth = Self::parse_auth(&config)?;
+
+        Ok(Self {
+            // 同一客户端同时用于列表和数据读取，使用传输超时
+            client: shared_http_client(HttpClientKind::Transfer),
+            connected: AtomicBool::new(false),
+            endpoint,
+            bucket,
+            prefix,
+            auth,
+            token: Mutex::new(None),
+        })
+    }
+
+    fn parse_auth(config: &ConnectionConfig) -> Result<GCSAuth, StorageError> {
+        let key_json = match config
+            .private_key_path
+            .as_deref()
+            .filter(|path| !path.is_empty())
+        {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|e| {
+                StorageError::InvalidConfig(format!("Failed to read service account key: {}", e))
+            })?),
+            None => config.secret_key.clone().filter(|key| !key.is_empty()),
+        };
+
+        if let Some(key_json) = key_json {
+            let key: ServiceAccountKey = serde_json::from_str(&key_json).map_err(|e| {
+                StorageError::InvalidConfig(format!("Invalid service account key: {}", e))
+            })?;
+            return Ok(GCSAuth::ServiceAccount(key));
+        }
+
+        Ok(
+            match config.password.clone().filter(|token| !token.is_empty()) {
+                Some(token) => GCSAuth::Token(token),
+                None => GCSAuth::Anonymous,
+            },
+        )
+    }
+
+    /// 获取 Authorization 头，服务账号的访问令牌过期前自动刷新
+    async fn authorization(&self) -> Result<Option<String>, StorageError> {
+        let key = match &self.auth {
+            GCSAuth::Anonymous => return Ok(None),
+            GCSAuth::Token(token) => return Ok(Some(format!("Bearer {}", token))),
+            GCSAuth::ServiceAccount(key) => key,
+        };
+
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > Instant::now() + TOKEN_REFRESH_MARGIN {
+                return Ok(Some(format!("Bearer {}", token.token)));
+            }
+        }
+
+        let token = self.fetch_service_account_token(key).await?;
+        let header = format!("Bearer {}", token.token);
+        *cached = Some(token);
+        Ok(Some(header))
+    }
+
+    /// 使用服务账号私钥签发 JWT，换取访问令牌
+    async fn fetch_service_account_token(
+        &self,
+        key: &ServiceAccountKey,
+    ) -> Result<CachedToken, StorageError> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let claims = JwtClaims {
+            iss: &key.client_email,
+            scope: READ_ONLY_SCOPE,
+            aud: &key.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let encoding_key = jsonwebtoken::EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| StorageError::InvalidConfig(format!("Invalid private key: {}", e)))?;
+        let assertion = jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+            &claims,
+            &encoding_key,
+        )
+        .map_err(|e| StorageError::AuthenticationFailed(format!("Failed to sign JWT: {}", e)))?;
+
+        let response = self
+            .client
+            .post(&key.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Token request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = http_guard::error_body(response).await;
+            return Err(StorageError::AuthenticationFailed(format!(
+                "Token request failed with status {}: {}",
+                status, body
+            )));
+        }
+
+        let token: TokenResponse = http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(format!("Invalid token response: {}", e)))?;
+
+        Ok(CachedToken {
+            token: token.access_token,
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in.unwrap_or(3600)),
+        })
+    }
+
+    /// 解析对象键，支持 gcs://bucket/key 协议 URL 和相对路径（自动添加前缀）
+    fn object_key(&self, path: &str) -> Result<String, StorageError> {
+        match path.strip_prefix("gcs://") {
+            Some(rest) => {
+                let rest = rest.split('?').next().unwrap_or(rest);
+                let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+                if bucket != self.bucket {
+                    return Err(StorageError::RequestFailed(format!(
+                        "Bucket mismatch: url='{}' != configured='{}'",
+                        bucket, self.bucket
+                    )));
+                }
+                Ok(key.to_string())
+            }
+            None => Ok(format!("{}{}", self.prefix, path.trim_start_matches('/'))),
+        }
+    }
+
+    fn object_url(&self, object_key: &str) -> String {
+        format!(
+            "{}/storage/v1/b/{}/o/{}",
+            self.endpoint,
+            urlencoding::encode(&self.bucket),
+            urlencoding::encode(object_key)
+        )
+    }
+
+    fn media_url(&self, object_key: &str) -> String {
+        format!("{}?alt=media", self.object_url(object_key))
+    }
+
+    /// 发送带认证的 GET 请求，非成功状态转换为对应的错误
+    async fn get(
+        &self,
+        url: &str,
+        range: Option<(u64, u64)>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let mut request = self.client.get(url);
+        if let Some(auth) = self.authorization().await? {
+            request = request.header("Authorization", auth);
+        }
+        if let Some((start, end)) = range {
+            request = request.header("Range", format!("bytes={}-{}", start, end));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("GCS request failed: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = http_guard::error_body(response).await;
+        Err(match status {
+            reqwest::StatusCode::NOT_FOUND => StorageError::NotFound(url.to_string()),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                StorageError::AuthenticationFailed(format!("Status {}: {}", status, body))
+            }
+            _ => StorageError::RequestFailed(format!(
+                "GCS request failed with status {}: {}",
+                status, body
+            )),
+        })
+    }
+
+    async fn get_metadata(&self, path: &str) -> Result<ObjectResource, StorageError> {
+        let object_key = self.object_key(path)?;
+        let response = self.get(&self.object_url(&object_key), None).await?;
+        http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(format!("Invalid object metadata: {}", e)))
+    }
+
+    /// 将列表响应转换为当前目录的直接子项
+    fn to_directory_result(&self, list: ObjectList, prefix: &str) -> DirectoryResult {
+        let mut files: Vec<StorageFile> = list
+            .prefixes
+            .iter()
+            .filter_map(|dir| {
+                let name = dir.strip_prefix(prefix)?.trim_end_matches('/');
+                (!name.is_empty()).then(|| StorageFile {
+                    filename: name.to_string(),
+                    basename: name.to_string(),
+                    lastmod: String::new(),
+                    size: "0".to_string(),
+                    file_type: "directory".to_string(),
+                    mime: None,
+                    etag: None,
+                    storage_class: None,
+                    lastmod_raw: None,
+                })
+            })
+            .collect();
+
+        files.extend(list.items.into_iter().filter_map(|object| {
+            // 控制台创建的"文件夹"是以 / 结尾的空对象
+            let relative = object.name.strip_prefix(prefix)?;
+            if relative.is_empty() || relative.ends_with('/') {
+                return None;
+            }
+            let basename = relative.rsplit('/').next().unwrap_or(relative).to_string();
+            Some(StorageFile {
+                filename: relative.to_string(),
+                mime: Some(mime_from_filename(&basename)),
+                basename,
+                lastmod: object.updated.unwrap_or_default(),
+                size: object
+                    .size
+                    .and_then(|size| size.parse::<u64>().ok())
+                    .unwrap_or(0)
+                    .to_string(),
+                file_type: "file".to_string(),
+                etag: object.etag,
+                storage_class: object.storage_class,
+                lastmod_raw: None,
+            })
+        }));
+
+        DirectoryResult {
+            files,
+            has_more: list.next_page_token.is_some(),
+            next_marker: list.next_page_token,
+            total_count: None,
+            path: prefix.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl StorageClient for GCSClient {
+    async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        let updated = Self::new(config.clone())?;
+        self.endpoint = updated.endpoint;
+        self.bucket = updated.bucket;
+        self.prefix = updated.prefix;
+        self.auth = updated.auth;
+        *self.token.get_mut() = None;
+
+        // 列出一个对象验证认证和 bucket 访问权限
+        let url = format!(
+            "{}/storage/v1/b/{}/o?maxResults=1&prefix={}",
+            self.endpoint,
+            urlencoding::encode(&self.bucket),
+            urlencoding::encode(&self.prefix)
+        );
+        self.get(&url, None).await.map_err(|e| match e {
+            StorageError::NotFound(_) => {
+                StorageError::ConnectionFailed(format!("Bucket not found: {}", self.bucket))
+            }
+            other => other,
+        })?;
+
+        self.connected.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    async fn list_directory(
+        &self,
+        path: &str,
+        options: Option<&ListOptions>,
+    ) -> Result<DirectoryResult, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let key = self.object_key(path)?;
+        let mut prefix = key.trim_end_matches('/').to_string();
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        if let Some(filter) = options.and_then(|o| o.prefix.as_ref()) {
+            prefix.push_str(filter);
+        }
+
+        let page_size = options.and_then(|o| o.page_size).unwrap_or(1000);
+        let mut url = format!(
+            "{}/storage/v1/b/{}/o?prefix={}&maxResults={}",
+            self.endpoint,
+            urlencoding::encode(&self.bucket),
+            urlencoding::encode(&prefix),
+            page_size
+        );
+        if !options.and_then(|o| o.recursive).unwrap_or(false) {
+            url.push_str("&delimiter=%2F");
+        }
+        if let Some(marker) = options.and_then(|o| o.marker.as_ref()) {
+            if !marker.is_empty() {
+                url.push_str(&format!("&pageToken={}", urlencoding::encode(marker)));
+            }
+        }
+
+        let response = self.get(&url, None).await?;
+        let list: ObjectList = http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(format!("Invalid list response: {}", e)))?;
+
+        // 目录前缀不包含名称过滤部分
+        let directory = match key.trim_end_matches('/') {
+            "" => String::new(),
+            dir => format!("{}/", dir),
+        };
+        Ok(self.to_directory_result(list, &directory))
+    }
+
+    async fn read_file_range(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.read_file_range_with_progress(path, start, length, None, None)
+            .await
+    }
+
+    async fn read_file_range_with_progress(
+        &self,
+        path: &str,
+        start: u64,
+        length: u64,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let object_key = self.object_key(path)?;
+        let response = self
+            .get(
+                &self.media_url(&object_key),
+                Some((start, start + length - 1)),
+            )
+            .await?;
+
+        // 流式读取以支持进度回调，最多读取请求的长度，长时间没有数据时结束
+        let (result, _) =
+            read_range_body(response, start, length, progress_callback, cancel_rx).await?;
+        Ok(result)
+    }
+
+    async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let object_key = self.object_key(path)?;
+        let response = self.get(&self.media_url(&object_key), None).await?;
+        http_guard::read_body(response, http_guard::MAX_FULL_FILE_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(format!("Failed to read file: {}", e)))
+    }
+
+    async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        self.get_metadata(path)
+            .await?
+            .size
+            .and_then(|size| size.parse::<u64>().ok())
+            .ok_or_else(|| StorageError::RequestFailed("Object size not available".to_string()))
+    }
+
+    /// 优先返回 MD5（十六进制），复合对象没有 MD5 时返回 GCS 的 ETag
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let metadata = self.get_metadata(path).await?;
+        let md5 = metadata.md5_hash.and_then(|hash| {
+            base64::engine::general_purpose::STANDARD
+                .decode(hash)
+                .ok()
+                .map(hex::encode)
+        });
+        md5.or(metadata.etag)
+            .ok_or_else(|| StorageError::RequestFailed("ETag not available".to_string()))
+    }
+
+    fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        if config.protocol != "gcs" {
+            return Err(StorageError::InvalidConfig(
+                "Protocol must be 'gcs'".to_string(),
+            ));
+        }
+        if config.bucket.as_deref().unwrap_or("").trim().is_empty() {
+            return Err(StorageError::InvalidConfig(
+                "GCS bucket is required".to_string(),
+            ));
+        }
+        Self::parse_auth(config).map(|_| ())
+    }
+
+    async fn download_file(
+        &self,
+        path: &str,
+        save_path: &std::path::Path,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        if !self.is_connected().await {
+            return Err(StorageError::NotConnected);
+        }
+
+        let object_key = self.object_key(path)?;
+        let mut config = HttpDownloadConfig::new(self.media_url(&object_key));
+        if let Some(auth) = self.authorization().await? {
+            config = config.with_auth(auth);
+        }
+
+        HttpDownloader::download_stream(
+            &self.client,
+            config,
+            save_path,
+            progress_callback,
+            cancel_rx,
+        )
+        .await
+    }
+}
@@ -1,14 +1,21 @@
 use async_trait::async_trait;
-use reqwest::Client;
 use serde::Deserialize;
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use crate::storage::manager::{shared_http_client, HttpClientKind};
+use crate::storage::range_read::read_range_body;
 use crate::storage::traits::{
-    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
-    StorageFile,
+    ConnectionConfig, DirectoryResult, ListOptions, ObjectMetadata, ParquetSplit, ProgressCallback,
+    RepositoryInfo, RepositoryRevision, RepositorySplit, StorageClient, StorageError, StorageFile,
 };
+use crate::storage::validators::{ValidatorTracker, Validators};
 use crate::utils::http_downloader::HttpDownloader;
+use crate::utils::http_guard;
+use crate::utils::mime::mime_from_filename;
 
 /// HuggingFace 数据集信息
 #[derive(Debug, Deserialize)]
@@ -23,11 +30,208 @@ struct DatasetInfo {
 pub struct DatasetFile {
     #[serde(rename = "type")]
     pub file_type: String, // "file" 或 "directory"
-    pub oid: String,  // Git 对象 ID
-    pub size: u64,    // 文件大小
+    pub oid: String, // Git 对象 ID
+    #[serde(default)]
+    pub size: u64, // 文件大小
     pub path: String, // 文件路径
 }
 
+/// 未指定版本时使用的默认分支
+const DEFAULT_REVISION: &str = "main";
+
+/// 自动转换的 Parquet 文件所在的分支
+const PARQUET_REVISION: &str = "refs/convert/parquet";
+
+/// tree API 分页结果的缓存有效期
+const TREE_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// 最多缓存的 tree 分页数量
+const TREE_CACHE_MAX_PAGES: usize = 1024;
+
+/// 仓库类型，可通过连接配置的 extra_options 中的 hfRepoType 选择：
+/// "dataset"（默认）、"model" 或 "space"，三者的列表、tree、下载流程相同，只是 URL 前缀不同
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum RepoType {
+    #[default]
+    Dataset,
+    Model,
+    Space,
+}
+
+impl RepoType {
+    fn from_options(options: Option<&HashMap<String, String>>) -> Result<Self, StorageError> {
+        let Some(repo_type) = options.and_then(|options| options.get("hfRepoType")) else {
+            return Ok(Self::default());
+        };
+        match repo_type.trim().to_lowercase().as_str() {
+            "" | "dataset" | "datasets" => Ok(Self::Dataset),
+            "model" | "models" => Ok(Self::Model),
+            "space" | "spaces" => Ok(Self::Space),
+            other => Err(StorageError::InvalidConfig(format!(
+                "Unknown HuggingFace repo type: {}. Expected dataset, model or space",
+                other
+            ))),
+        }
+    }
+
+    /// API 路径中的仓库类型段
+    fn api_segment(self) -> &'static str {
+        match self {
+            Self::Dataset => "datasets",
+            Self::Model => "models",
+            Self::Space => "spaces",
+        }
+    }
+
+    /// 文件下载 URL 的前缀，模型仓库没有类型前缀
+    fn resolve_prefix(self) -> &'static str {
+        match self {
+            Self::Dataset => "/datasets",
+            Self::Model => "",
+            Self::Space => "/spaces",
+        }
+    }
+}
+
+/// HuggingFace 仓库引用（来自 refs API）
+#[derive(Debug, Deserialize)]
+struct GitRef {
+    name: String,
+    #[serde(rename = "ref")]
+    git_ref: String,
+    #[serde(rename = "targetCommit")]
+    target_commit: String,
+}
+
+/// refs API 的响应
+#[derive(Debug, Deserialize)]
+struct GitRefs {
+    #[serde(default)]
+    branches: Vec<GitRef>,
+    #[serde(default)]
+    tags: Vec<GitRef>,
+    #[serde(default)]
+    converts: Vec<GitRef>,
+}
+
+/// 仓库详情（来自 /api/{type}/{id}）
+#[derive(Debug, Deserialize)]
+struct RepoDetails {
+    id: String,
+    #[serde(rename = "lastModified")]
+    last_modified: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    downloads: u64,
+    #[serde(default)]
+    likes: u64,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// false，或 "auto" / "manual"
+    #[serde(default)]
+    gated: serde_json::Value,
+    /// README 头部的 YAML 元数据
+    #[serde(rename = "cardData")]
+    card_data: Option<serde_json::Value>,
+}
+
+/// 解析后的数据集路径
+/// 路径格式：{owner}~{dataset}[@{revision}]/{file_path}，revision 中的 / 需编码为 %2F
+/// （协议 URL 会先整体解码一次，其中的 revision 需要再编码一次）
+#[derive(Debug, Clone)]
+struct DatasetPath {
+    /// owner/dataset
+    dataset_id: String,
+    /// 分支、标签或提交 SHA，未指定时使用默认分支
+    revision: Option<String>,
+    file_path: String,
+}
+
+impl DatasetPath {
+    /// tree 缓存的分组键：(数据集, 版本)
+    fn cache_key(&self) -> (String, String) {
+        (self.dataset_id.clone(), self.revision().to_string())
+    }
+
+    fn revision(&self) -> &str {
+        self.revision.as_deref().unwrap_or(DEFAULT_REVISION)
+    }
+
+    /// 用于 URL 的版本名，refs/convert/parquet 这类包含斜杠的版本需要编码
+    fn encoded_revision(&self) -> String {
+        urlencoding::encode(self.revision()).into_owned()
+    }
+
+    /// 带 @revision 后缀的数据集标识，separator 为 owner 与 dataset 之间的分隔符
+    fn qualified_id(&self, separator: &str) -> String {
+        let id = self.dataset_id.replace('/', separator);
+        match &self.revision {
+            Some(revision) => format!("{}@{}", id, urlencoding::encode(revision)),
+            None => id,
+        }
+    }
+}
+
+/// 缓存的 tree 分页，键为 (目录, cursor)
+type TreePages = HashMap<(String, String), (Instant, DirectoryResult)>;
+
+/// tree API 的分页缓存，按 (数据集, 版本) 分组
+/// 大仓库中返回上级目录、翻回已加载的分页或查询文件大小时不再重复请求
+#[derive(Default)]
+struct TreeCache {
+    repos: Mutex<HashMap<(String, String), TreePages>>,
+}
+
+impl TreeCache {
+    fn get(&self, repo: &(String, String), page: &(String, String)) -> Option<DirectoryResult> {
+        let repos = self.repos.lock().ok()?;
+        let (fetched_at, result) = repos.get(repo)?.get(page)?;
+        (fetched_at.elapsed() < TREE_CACHE_TTL).then(|| result.clone())
+    }
+
+    fn insert(&self, repo: (String, String), page: (String, String), result: DirectoryResult) {
+        let Ok(mut repos) = self.repos.lock() else {
+            return;
+        };
+
+        // 超过上限时先清理过期分页，仍然超出则全部清空
+        if repos.values().map(HashMap::len).sum::<usize>() >= TREE_CACHE_MAX_PAGES {
+            for pages in repos.values_mut() {
+                pages.retain(|_, (fetched_at, _)| fetched_at.elapsed() < TREE_CACHE_TTL);
+            }
+            repos.retain(|_, pages| !pages.is_empty());
+            if repos.values().map(HashMap::len).sum::<usize>() >= TREE_CACHE_MAX_PAGES {
+                repos.clear();
+            }
+        }
+
+        repos
+            .entry(repo)
+            .or_default()
+            .insert(page, (Instant::now(), result));
+    }
+
+    /// 在目录已缓存的分页中查找文件大小
+    fn find_file_size(&self, repo: &(String, String), dir: &str, name: &str) -> Option<u64> {
+        let repos = self.repos.lock().ok()?;
+        repos
+            .get(repo)?
+            .iter()
+            .filter(|((page_dir, _), (fetched_at, _))| {
+                page_dir == dir && fetched_at.elapsed() < TREE_CACHE_TTL
+            })
+            .flat_map(|(_, (_, result))| result.files.iter())
+            .find(|file| file.basename == name && file.file_type == "file")
+            .and_then(|file| file.size.parse().ok())
+    }
+
+    fn clear(&self) {
+        if let Ok(mut repos) = self.repos.lock() {
+            repos.clear();
+        }
+    }
+}
+
 // HuggingFace API 直接返回数组，不需要包装结构体
 pub struct HuggingFaceClient {
     client: reqwest::Client,
@@ -35,7 +239,11 @@ pub struct HuggingFaceClient {
     base_url: String,
     api_url: String,
     api_token: Option<String>,
+    repo_type: RepoType,
+    tree_cache: TreeCache,
     connected: AtomicBool,
+    /// 读取响应中返回的 ETag / Last-Modified
+    validators: ValidatorTracker,
 }
 
 impl HuggingFaceClient {
@@ -43,18 +251,23 @@ impl HuggingFaceClient {
         let api_token = config.password.clone(); // API token 存储在 password 字段
         let base_url = "https://huggingface.co".to_string();
         let api_url = "https://huggingface.co/api".to_string();
+        let repo_type = RepoType::from_options(config.extra_options.as_ref())?;
 
         Ok(Self {
-            client: Client::new(),
+            // 同一客户端同时用于列表和数据读取，使用传输超时
+            client: shared_http_client(HttpClientKind::Transfer),
             config,
             api_token,
             base_url,
             api_url,
+            repo_type,
+            tree_cache: TreeCache::default(),
             connected: AtomicBool::new(false),
+            validators: ValidatorTracker::default(),
         })
     }
 
-    /// 获取热门数据集
+    /// 获取热门仓库（按配置的仓库类型，默认为数据集）
     async fn list_popular_datasets(
         &self,
         options: Option<&ListOptions>,
@@ -62,7 +275,12 @@ impl HuggingFaceClient {
         let page_size = options.and_then(|o| o.page_size).unwrap_or(20);
 
         // 构建基础 URL
-        let mut url = format!("{}/datasets?limit={}", self.api_url, page_size);
+        let mut url = format!(
+            "{}/{}?limit={}",
+            self.api_url,
+            self.repo_type.api_segment(),
+            page_size
+        );
 
         // 如果有 marker，添加为 cursor 参数（HuggingFace API 的分页参数）
         if let Some(marker) = options.and_then(|o| o.marker.as_ref()) {
@@ -87,61 +305,25 @@ impl HuggingFaceClient {
         }
 
         // 提取 Link header 信息以及下一页的 cursor（在消耗 response 之前）
-        let (has_more, next_cursor) = if let Some(link_header) = response.headers().get("Link") {
-            if let Ok(link_str) = link_header.to_str() {
-                let has_more = link_str.contains("rel=\"next\"");
-
-                // 从 Link header 中提取 cursor 参数
-                let next_cursor = if has_more {
-                    // 提取形如 <https://huggingface.co/api/datasets?cursor=xxx&limit=20>; rel="next" 的链接
-                    link_str
-                        .split(',')
-                        .find(|part| part.contains("rel=\"next\""))
-                        .and_then(|next_part| {
-                            // 提取 URL 部分
-                            next_part
-                                .trim()
-                                .strip_prefix('<')
-                                .and_then(|s| s.split('>').next())
-                        })
-                        .and_then(|url| {
-                            // 从 URL 中提取 cursor 参数
-                            url.split('&')
-                                .find(|param| param.starts_with("cursor="))
-                                .and_then(|cursor_param| cursor_param.strip_prefix("cursor="))
-                                .map(|cursor| {
-                                    urlencoding::decode(cursor).unwrap_or_default().into_owned()
-                                })
-                        })
-                } else {
-                    None
-                };
-
-                (has_more, next_cursor)
-            } else {
-                (false, None)
-            }
-        } else {
-            (false, None)
-        };
+        let (has_more, next_cursor) = next_page_cursor(response.headers());
 
-        let datasets: Vec<DatasetInfo> = response
-            .json()
-            .await
-            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+        let datasets: Vec<DatasetInfo> =
+            http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                .await
+                .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
 
         let files: Vec<StorageFile> = datasets
             .into_iter()
             .map(|dataset| StorageFile {
                 filename: dataset.id.replace('/', ":"), // 使用 : 替代 / 来避免路径解析问题
                 basename: dataset.id.replace('/', ":"), // 统一使用 : 分隔符格式
-                lastmod: dataset
-                    .last_modified
-                    .unwrap_or_else(|| "unknown".to_string()),
+                lastmod: dataset.last_modified.unwrap_or_default(),
                 size: "0".to_string(),
                 file_type: "directory".to_string(),
                 mime: Some("application/x-directory".to_string()),
                 etag: None,
+                storage_class: None,
+                lastmod_raw: None,
             })
             .collect();
 
@@ -172,8 +354,9 @@ impl HuggingFaceClient {
 
         // 构建基础 URL
         let mut url = format!(
-            "{}/datasets?search={}&limit={}",
+            "{}/{}?search={}&limit={}",
             self.api_url,
+            self.repo_type.api_segment(),
             urlencoding::encode(query),
             page_size
         );
@@ -201,61 +384,25 @@ impl HuggingFaceClient {
         }
 
         // 提取 Link header 信息以及下一页的 cursor（在消耗 response 之前）
-        let (has_more, next_cursor) = if let Some(link_header) = response.headers().get("Link") {
-            if let Ok(link_str) = link_header.to_str() {
-                let has_more = link_str.contains("rel=\"next\"");
-
-                // 从 Link header 中提取 cursor 参数
-                let next_cursor = if has_more {
-                    // 提取形如 <https://huggingface.co/api/datasets?cursor=xxx&limit=20>; rel="next" 的链接
-                    link_str
-                        .split(',')
-                        .find(|part| part.contains("rel=\"next\""))
-                        .and_then(|next_part| {
-                            // 提取 URL 部分
-                            next_part
-                                .trim()
-                                .strip_prefix('<')
-                                .and_then(|s| s.split('>').next())
-                        })
-                        .and_then(|url| {
-                            // 从 URL 中提取 cursor 参数
-                            url.split('&')
-                                .find(|param| param.starts_with("cursor="))
-                                .and_then(|cursor_param| cursor_param.strip_prefix("cursor="))
-                                .map(|cursor| {
-                                    urlencoding::decode(cursor).unwrap_or_default().into_owned()
-                                })
-                        })
-                } else {
-                    None
-                };
-
-                (has_more, next_cursor)
-            } else {
-                (false, None)
-            }
-        } else {
-            (false, None)
-        };
+        let (has_more, next_cursor) = next_page_cursor(response.headers());
 
-        let datasets: Vec<DatasetInfo> = response
-            .json()
-            .await
-            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+        let datasets: Vec<DatasetInfo> =
+            http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                .await
+                .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
 
         let files: Vec<StorageFile> = datasets
             .into_iter()
             .map(|dataset| StorageFile {
                 filename: dataset.id.replace('/', ":"), // 用于前端路径导航
                 basename: dataset.id.replace('/', ":"), // 统一使用 : 分隔符格式
-                lastmod: dataset
-                    .last_modified
-                    .unwrap_or_else(|| "unknown".to_string()),
+                lastmod: dataset.last_modified.unwrap_or_default(),
                 size: "0".to_string(),
                 file_type: "directory".to_string(),
                 mime: Some("application/x-directory".to_string()),
                 etag: None,
+                storage_class: None,
+                lastmod_raw: None,
             })
             .collect();
 
@@ -286,8 +433,9 @@ impl HuggingFaceClient {
 
         // 构建基础 URL
         let mut url = format!(
-            "{}/datasets?author={}&limit={}",
+            "{}/{}?author={}&limit={}",
             self.api_url,
+            self.repo_type.api_segment(),
             urlencoding::encode(org_name),
             page_size
         );
@@ -315,61 +463,25 @@ impl HuggingFaceClient {
         }
 
         // 提取 Link header 信息以及下一页的 cursor（在消耗 response 之前）
-        let (has_more, next_cursor) = if let Some(link_header) = response.headers().get("link") {
-            if let Ok(link_str) = link_header.to_str() {
-                let has_more = link_str.contains("rel=\"next\"");
-
-                // 从 Link header 中提取 cursor 参数
-                let next_cursor = if has_more {
-                    // 提取形如 <https://huggingface.co/api/datasets?cursor=xxx&limit=20>; rel="next" 的链接
-                    link_str
-                        .split(',')
-                        .find(|part| part.contains("rel=\"next\""))
-                        .and_then(|next_part| {
-                            // 提取 URL 部分
-                            next_part
-                                .trim()
-                                .strip_prefix('<')
-                                .and_then(|s| s.split('>').next())
-                        })
-                        .and_then(|url| {
-                            // 从 URL 中提取 cursor 参数
-                            url.split('&')
-                                .find(|param| param.starts_with("cursor="))
-                                .and_then(|cursor_param| cursor_param.strip_prefix("cursor="))
-                                .map(|cursor| {
-                                    urlencoding::decode(cursor).unwrap_or_default().into_owned()
-                                })
-                        })
-                } else {
-                    None
-                };
-
-                (has_more, next_cursor)
-            } else {
-                (false, None)
-            }
-        } else {
-            (false, None)
-        };
+        let (has_more, next_cursor) = next_page_cursor(response.headers());
 
-        let datasets: Vec<DatasetInfo> = response
-            .json()
-            .await
-            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+        let datasets: Vec<DatasetInfo> =
+            http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                .await
+                .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
 
         let files: Vec<StorageFile> = datasets
             .into_iter()
             .map(|dataset| StorageFile {
                 filename: dataset.id.replace('/', ":"), // 用于前端路径导航
                 basename: dataset.id.replace('/', ":"), // 统一使用 : 分隔符格式
-                lastmod: dataset
-                    .last_modified
-                    .unwrap_or_else(|| "unknown".to_string()),
+                lastmod: dataset.last_modified.unwrap_or_default(),
                 size: "0".to_string(),
                 file_type: "directory".to_string(),
                 mime: Some("application/x-directory".to_string()),
                 etag: None,
+                storage_class: None,
+                lastmod_raw: None,
             })
             .collect();
 
@@ -383,23 +495,33 @@ impl HuggingFaceClient {
     }
 
     /// 列出数据集文件
+    /// tree API 每次只返回一层目录，按 cursor 分页（每页由服务端决定大小），分页结果会被缓存
     async fn list_dataset_files(
         &self,
-        owner: &str,
-        dataset: &str,
-        subpath: &str,
-        _options: Option<&ListOptions>,
+        target: &DatasetPath,
+        options: Option<&ListOptions>,
     ) -> Result<DirectoryResult, StorageError> {
-        let dataset_id = format!("{}/{}", owner, dataset);
-        // 使用 tree API 获取完整的文件信息
-        let url = if subpath.is_empty() {
-            format!("{}/datasets/{}/tree/main", self.api_url, dataset_id)
-        } else {
-            format!(
-                "{}/datasets/{}/tree/main/{}",
-                self.api_url, dataset_id, subpath
-            )
-        };
+        let dataset_id = &target.dataset_id;
+        let subpath = target.file_path.trim_end_matches('/');
+        let cursor = options
+            .and_then(|o| o.marker.clone())
+            .filter(|marker| !marker.is_empty());
+
+        let repo_key = target.cache_key();
+        let page_key = (subpath.to_string(), cursor.clone().unwrap_or_default());
+        if let Some(cached) = self.tree_cache.get(&repo_key, &page_key) {
+            return Ok(cached);
+        }
+
+        // 目录路径放在 URL 中，由服务端过滤
+        let mut url = self.build_tree_url(target);
+        if !subpath.is_empty() {
+            url.push('/');
+            url.push_str(subpath);
+        }
+        if let Some(cursor) = &cursor {
+            url.push_str(&format!("?cursor={}", urlencoding::encode(cursor)));
+        }
 
         let response = self
             .client
@@ -416,11 +538,14 @@ impl HuggingFaceClient {
             ));
         }
 
-        let files_data: Vec<DatasetFile> = response
-            .json()
-            .await
-            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+        let (has_more, next_cursor) = next_page_cursor(response.headers());
+        let files_data: Vec<DatasetFile> =
+            http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                .await
+                .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
 
+        // 去重（因为可能有多个深层文件属于同一个中间目录）
+        let mut seen = HashSet::new();
         let files: Vec<StorageFile> = files_data
             .into_iter()
             .filter_map(|file| {
@@ -429,102 +554,93 @@ impl HuggingFaceClient {
                     file.path.clone()
                 } else {
                     // 移除子路径前缀
-                    if file.path.starts_with(&format!("{}/", subpath)) {
-                        file.path[subpath.len() + 1..].to_string()
-                    } else {
-                        return None; // 不是当前目录的子项
-                    }
+                    file.path
+                        .strip_prefix(&format!("{}/", subpath))?
+                        .to_string()
                 };
 
                 // 只显示直接子项（不包含更深层的路径）
-                if relative_path.contains('/') {
-                    // 这是更深层的文件/目录，获取第一级目录名
-                    let first_part = relative_path.split('/').next().unwrap();
-                    // 检查是否已经有同名目录
+                if let Some((first_part, _)) = relative_path.split_once('/') {
+                    // 这是更深层的文件/目录，取第一级目录名
                     Some(StorageFile {
                         filename: first_part.to_string(),
                         basename: first_part.to_string(),
-                        lastmod: "unknown".to_string(),
+                        lastmod: String::new(),
                         size: "0".to_string(), // 目录大小设为0
                         file_type: "directory".to_string(),
                         mime: Some("application/x-directory".to_string()),
                         etag: None,
+                        storage_class: None,
+                        lastmod_raw: None,
                     })
                 } else {
-                    // 这是当前目录的直接子项
+                    let is_directory = file.file_type == "directory";
                     Some(StorageFile {
                         filename: relative_path.clone(),
                         basename: relative_path.clone(),
-                        lastmod: "unknown".to_string(),
+                        lastmod: String::new(),
                         size: file.size.to_string(),
-                        file_type: if file.file_type == "directory" {
-                            "directory"
-                        } else {
-                            "file"
-                        }
-                        .to_string(),
-                        mime: if file.file_type == "directory" {
+                        file_type: if is_directory { "directory" } else { "file" }.to_string(),
+                        mime: if is_directory {
                             Some("application/x-directory".to_string())
                         } else {
-                            Some(self.get_mime_type(&relative_path))
+                            Some(mime_from_filename(&relative_path))
                         },
                         etag: Some(file.oid),
+                        storage_class: None,
+                        lastmod_raw: None,
                     })
                 }
             })
+            .filter(|file| seen.insert(file.filename.clone()))
             .collect();
 
-        // 去重（因为可能有多个深层文件属于同一个中间目录）
-        let mut unique_files: Vec<StorageFile> = Vec::new();
-        for file in files {
-            if !unique_files.iter().any(|f| f.filename == file.filename) {
-                unique_files.push(file);
-            }
-        }
-
         let path = if subpath.is_empty() {
-            dataset_id.replace('/', ":")
+            target.qualified_id(":")
         } else {
-            format!("{}/{}", dataset_id.replace('/', ":"), subpath)
+            format!("{}/{}", target.qualified_id(":"), subpath)
         };
 
-        let total_count = unique_files.len().to_string();
+        // 只有单页目录才知道总数
+        let has_more = has_more && next_cursor.is_some();
+        let total_count = (!has_more && cursor.is_none()).then(|| files.len().to_string());
 
-        Ok(DirectoryResult {
-            files: unique_files,
-            has_more: false,
-            next_marker: None,
-            total_count: Some(total_count),
+        let result = DirectoryResult {
+            files,
+            has_more,
+            next_marker: if has_more { next_cursor } else { None },
+            total_count,
             path,
-        })
+        };
+        self.tree_cache.insert(repo_key, page_key, result.clone());
+        Ok(result)
     }
 
-    /// 获取 MIME 类型
-    fn get_mime_type(&self, filename: &str) -> String {
-        let ext = filename.split('.').last().unwrap_or("").to_lowercase();
-        match ext.as_str() {
-            "json" => "application/json".to_string(),
-            "csv" => "text/csv".to_string(),
-            "txt" => "text/plain".to_string(),
-            "md" => "text/markdown".to_string(),
-            "parquet" => "application/octet-stream".to_string(),
-            "arrow" => "application/octet-stream".to_string(),
-            "jsonl" => "application/jsonlines".to_string(),
-            "tsv" => "text/tab-separated-values".to_string(),
-            _ => "application/octet-stream".to_string(),
-        }
+    /// 构建文件下载 URL
+    fn build_download_url(&self, target: &DatasetPath) -> String {
+        format!(
+            "{}{}/{}/resolve/{}/{}",
+            self.base_url,
+            self.repo_type.resolve_prefix(),
+            target.dataset_id,
+            target.encoded_revision(),
+            target.file_path
+        )
     }
 
-    /// 构建文件下载 URL
-    fn build_download_url(&self, dataset_id: &str, file_path: &str) -> String {
+    /// 构建 tree API 的 URL（不含子路径）
+    fn build_tree_url(&self, target: &DatasetPath) -> String {
         format!(
-            "{}/datasets/{}/resolve/main/{}",
-            self.base_url, dataset_id, file_path
+            "{}/{}/{}/tree/{}",
+            self.api_url,
+            self.repo_type.api_segment(),
+            target.dataset_id,
+            target.encoded_revision()
         )
     }
 
     /// 解析路径 - 处理前端传来的协议URL或简单路径格式
-    fn parse_path(&self, path: &str) -> Result<(String, String), StorageError> {
+    fn parse_path(&self, path: &str) -> Result<DatasetPath, StorageError> {
         if path == "/" || path.is_empty() {
             return Err(StorageError::InvalidConfig(
                 "Root path not supported".to_string(),
@@ -549,14 +665,29 @@ impl HuggingFaceClient {
             ));
         }
 
-        // 路径格式：{owner}~{dataset}/{file_path}
+        // 路径格式：{owner}~{dataset}[@{revision}]/{file_path}
         let parts: Vec<&str> = path_to_parse.split('/').collect();
 
         if parts.is_empty() {
             return Err(StorageError::InvalidConfig("Empty path".to_string()));
         }
 
-        let dataset_id_part = parts[0];
+        // 数据集标识后可以带 @revision 指定分支、标签或提交 SHA
+        let (dataset_id_part, revision) = match parts[0].split_once('@') {
+            Some((id, revision)) => {
+                let revision = urlencoding::decode(revision)
+                    .map(|decoded| decoded.into_owned())
+                    .unwrap_or_else(|_| revision.to_string());
+                if revision.is_empty() {
+                    return Err(StorageError::InvalidConfig(format!(
+                        "Revision cannot be empty: {}",
+                        parts[0]
+                    )));
+                }
+                (id, Some(revision))
+            }
+            None => (parts[0], None),
+        };
 
         // 必须包含 ~ 分隔符
         if !dataset_id_part.contains('~') {
@@ -590,7 +721,11 @@ impl HuggingFaceClient {
             String::new()
         };
 
-        Ok((dataset_id, file_path))
+        Ok(DatasetPath {
+            dataset_id,
+            revision,
+            file_path,
+        })
     }
 
     /// 转换为 reqwest 头
@@ -620,8 +755,10 @@ impl HuggingFaceClient {
 #[async_trait]
 impl StorageClient for HuggingFaceClient {
     async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        self.repo_type = RepoType::from_options(config.extra_options.as_ref())?;
         self.config = config.clone();
         self.api_token = config.password.clone();
+        self.tree_cache.clear();
         self.connected.store(true, Ordering::Relaxed);
         Ok(())
     }
@@ -670,18 +807,14 @@ impl StorageClient for HuggingFaceClient {
 
         // 尝试解析数据集路径
         match self.parse_path(path) {
-            Ok((dataset_id, file_path)) => {
-                // 分解 dataset_id (owner/dataset) 为 owner 和 dataset
-                let parts: Vec<&str> = dataset_id.split('/').collect();
-                if parts.len() != 2 {
-                    return Err(StorageError::InvalidConfig(format!(
-                        "Invalid dataset ID format, expected 'owner/dataset': {}",
-                        dataset_id
-                    )));
+            Ok(mut target) => {
+                // 路径中未指定版本时使用列表选项中的版本
+                if target.revision.is_none() {
+                    target.revision = options
+                        .and_then(|o| o.revision.clone())
+                        .filter(|revision| !revision.is_empty());
                 }
-                let (owner, dataset) = (parts[0], parts[1]);
-                self.list_dataset_files(owner, dataset, &file_path, options)
-                    .await
+                self.list_dataset_files(&target, options).await
             }
             Err(_) => {
                 // 如果路径解析失败，尝试将其视为组织名称
@@ -706,12 +839,10 @@ impl StorageClient for HuggingFaceClient {
         start: u64,
         length: u64,
         progress_callback: Option<ProgressCallback>,
-        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<Vec<u8>, StorageError> {
-        use futures_util::StreamExt; // 这里需要StreamExt用于内存读取
-
-        let (dataset_id, file_path) = self.parse_path(path)?;
-        let download_url = self.build_download_url(&dataset_id, &file_path);
+        let target = self.parse_path(path)?;
+        let download_url = self.build_download_url(&target);
 
         // 直接使用 HTTP 客户端，不通过 request_binary
         let mut req_builder = self.client.get(&download_url);
@@ -734,40 +865,17 @@ impl StorageClient for HuggingFaceClient {
                     .unwrap_or("error.unknown")
             )));
         }
+        self.validators.record(path, response.headers());
 
-        // 使用流式读取以支持进度回调
-        let mut result = Vec::with_capacity(length as usize);
-        let mut downloaded = 0u64;
-        let mut stream = response.bytes_stream();
-
-        while let Some(chunk_result) = stream.next().await {
-            // 检查取消信号
-            if let Some(ref mut cancel_rx) = cancel_rx {
-                if cancel_rx.try_recv().is_ok() {
-                    return Err(StorageError::RequestFailed(
-                        "download.cancelled".to_string(),
-                    ));
-                }
-            }
-
-            let chunk = chunk_result
-                .map_err(|e| StorageError::NetworkError(format!("Failed to read chunk: {}", e)))?;
-
-            result.extend_from_slice(&chunk);
-            downloaded += chunk.len() as u64;
-
-            // 调用进度回调
-            if let Some(ref callback) = progress_callback {
-                callback(downloaded, length);
-            }
-        }
-
+        // 流式读取以支持进度回调，最多读取请求的长度，长时间没有数据时结束
+        let (result, _) =
+            read_range_body(response, start, length, progress_callback, cancel_rx).await?;
         Ok(result)
     }
 
     async fn read_full_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
-        let (dataset_id, file_path) = self.parse_path(path)?;
-        let download_url = self.build_download_url(&dataset_id, &file_path);
+        let target = self.parse_path(path)?;
+        let download_url = self.build_download_url(&target);
 
         // 直接使用 HTTP 客户端，不通过 request_binary
         let mut req_builder = self.client.get(&download_url);
@@ -789,35 +897,40 @@ impl StorageClient for HuggingFaceClient {
             )));
         }
 
-        let bytes = response.bytes().await.map_err(|e| {
-            StorageError::NetworkError(format!("Failed to read response body: {}", e))
-        })?;
+        self.validators.record(path, response.headers());
 
-        Ok(bytes.to_vec())
+        http_guard::read_body(response, http_guard::MAX_FULL_FILE_BYTES)
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Failed to read response body: {}", e)))
     }
 
     async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
-        let (dataset_id, file_path) = self.parse_path(path)?;
-
-        // 使用 tree API 获取文件信息
-        let tree_url = format!("{}/datasets/{}/tree/main", self.api_url, dataset_id);
-        let url = if !file_path.is_empty() {
-            // 如果文件路径包含目录分隔符，则添加 path 参数
-            if file_path.contains('/') {
-                let dir_path = file_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
-                format!("{}?path={}", tree_url, urlencoding::encode(dir_path))
-            } else {
-                // 根目录文件，不添加 path 参数
-                tree_url
-            }
-        } else {
-            tree_url
-        };
+        let target = self.parse_path(path)?;
+        let file_path = target.file_path.as_str();
+
+        // 先在已缓存的目录分页中查找
+        let (dir, name) = file_path.rsplit_once('/').unwrap_or(("", file_path));
+        if let Some(size) = self
+            .tree_cache
+            .find_file_size(&target.cache_key(), dir, name)
+        {
+            return Ok(size);
+        }
+
+        // paths-info 只返回指定路径的信息，不需要列出整个目录
+        let url = format!(
+            "{}/{}/{}/paths-info/{}",
+            self.api_url,
+            self.repo_type.api_segment(),
+            target.dataset_id,
+            target.encoded_revision()
+        );
 
         let response = self
             .client
-            .get(&url)
+            .post(&url)
             .headers(self.get_reqwest_headers())
+            .form(&[("paths", file_path)])
             .send()
             .await
             .map_err(|e| StorageError::NetworkError(e.to_string()))?;
@@ -829,10 +942,10 @@ impl StorageClient for HuggingFaceClient {
             )));
         }
 
-        let files: Vec<DatasetFile> = response
-            .json()
-            .await
-            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+        let files: Vec<DatasetFile> =
+            http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                .await
+                .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
 
         // 找到目标文件
         if let Some(file) = files
@@ -842,7 +955,7 @@ impl StorageClient for HuggingFaceClient {
             Ok(file.size)
         } else {
             // 降级到 HEAD 请求
-            let download_url = self.build_download_url(&dataset_id, &file_path);
+            let download_url = self.build_download_url(&target);
 
             let response = self
                 .client
@@ -873,12 +986,279 @@ impl StorageClient for HuggingFaceClient {
         }
     }
 
+    async fn get_metadata(&self, path: &str) -> Result<ObjectMetadata, StorageError> {
+        let target = self.parse_path(path)?;
+        let download_url = self.build_download_url(&target);
+
+        let response = self
+            .client
+            .head(&download_url)
+            .headers(self.get_reqwest_headers())
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "HEAD request failed: {}",
+                response.status()
+            )));
+        }
+        self.validators.record(path, response.headers());
+
+        let size = match response
+            .headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse().ok())
+        {
+            Some(size) => size,
+            None => self.get_file_size(path).await?,
+        };
+        Ok(ObjectMetadata::new(
+            size,
+            Validators::from_headers(response.headers()),
+        ))
+    }
+
+    async fn list_revisions(&self, path: &str) -> Result<Vec<RepositoryRevision>, StorageError> {
+        let target = self.parse_path(path)?;
+        let url = format!(
+            "{}/{}/{}/refs",
+            self.api_url,
+            self.repo_type.api_segment(),
+            target.dataset_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_reqwest_headers())
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "Failed to fetch revisions for {}: {}",
+                target.dataset_id,
+                response.status()
+            )));
+        }
+
+        let refs: GitRefs = http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+
+        // 转换分支（如 refs/convert/parquet）只能通过完整引用名访问
+        let branches = refs
+            .branches
+            .into_iter()
+            .map(|r| ("branch", r.name.clone(), r));
+        let tags = refs.tags.into_iter().map(|r| ("tag", r.name.clone(), r));
+        let converts = refs
+            .converts
+            .into_iter()
+            .map(|r| ("convert", r.git_ref.clone(), r));
+
+        Ok(branches
+            .chain(tags)
+            .chain(converts)
+            .map(|(kind, revision, git_ref)| {
+                let revision_path = DatasetPath {
+                    dataset_id: target.dataset_id.clone(),
+                    revision: Some(revision),
+                    file_path: String::new(),
+                };
+                RepositoryRevision {
+                    name: git_ref.name,
+                    kind: kind.to_string(),
+                    target_commit: git_ref.target_commit,
+                    path: revision_path.qualified_id("~"),
+                }
+            })
+            .collect())
+    }
+
+    async fn get_repository_info(&self, path: &str) -> Result<RepositoryInfo, StorageError> {
+        let target = self.parse_path(path)?;
+        let mut url = format!(
+            "{}/{}/{}",
+            self.api_url,
+            self.repo_type.api_segment(),
+            target.dataset_id
+        );
+        if target.revision.is_some() {
+            url.push_str(&format!("/revision/{}", target.encoded_revision()));
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_reqwest_headers())
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "Failed to fetch repository info for {}: {}",
+                target.dataset_id,
+                response.status()
+            )));
+        }
+
+        let details: RepoDetails = http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+
+        let card = details.card_data.unwrap_or(serde_json::Value::Null);
+        Ok(RepositoryInfo {
+            id: details.id,
+            pretty_name: card
+                .get("pretty_name")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            description: details.description.filter(|d| !d.trim().is_empty()),
+            license: card_strings(card.get("license")),
+            tags: details.tags,
+            downloads: details.downloads.to_string(),
+            likes: details.likes.to_string(),
+            lastmod: details.last_modified,
+            gated: !matches!(
+                details.gated,
+                serde_json::Value::Null | serde_json::Value::Bool(false)
+            ),
+            splits: card_splits(card.get("dataset_info")),
+        })
+    }
+
+    async fn list_parquet_splits(&self, path: &str) -> Result<Vec<ParquetSplit>, StorageError> {
+        if self.repo_type != RepoType::Dataset {
+            return Err(StorageError::ProtocolNotSupported(
+                "Parquet conversion is only available for datasets".to_string(),
+            ));
+        }
+        let target = self.parse_path(path)?;
+
+        // 配置 -> 划分 -> 分片 URL
+        let url = format!("{}/datasets/{}/parquet", self.api_url, target.dataset_id);
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.get_reqwest_headers())
+            .send()
+            .await
+            .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "Failed to fetch parquet splits for {}: {}",
+                target.dataset_id,
+                response.status()
+            )));
+        }
+
+        let splits: BTreeMap<String, BTreeMap<String, Vec<String>>> =
+            http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                .await
+                .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+
+        // 转换分支中的实际文件，用于得到可预览的路径和文件大小
+        let convert = DatasetPath {
+            dataset_id: target.dataset_id.clone(),
+            revision: Some(PARQUET_REVISION.to_string()),
+            file_path: String::new(),
+        };
+        let tree_url = format!("{}?recursive=true", self.build_tree_url(&convert));
+        let mut parquet_files: Vec<DatasetFile> = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let url = match &cursor {
+                Some(cursor) => format!("{}&cursor={}", tree_url, urlencoding::encode(cursor)),
+                None => tree_url.clone(),
+            };
+            let response = self
+                .client
+                .get(&url)
+                .headers(self.get_reqwest_headers())
+                .send()
+                .await
+                .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+            if !response.status().is_success() {
+                return Err(StorageError::RequestFailed(format!(
+                    "Failed to list parquet files for {}: {}",
+                    target.dataset_id,
+                    response.status()
+                )));
+            }
+
+            let (has_more, next_cursor) = next_page_cursor(response.headers());
+            let page: Vec<DatasetFile> =
+                http_guard::read_json(response, http_guard::MAX_METADATA_BYTES)
+                    .await
+                    .map_err(|e| StorageError::RequestFailed(e.to_string()))?;
+            parquet_files.extend(page);
+
+            match next_cursor {
+                Some(next_cursor) if has_more => cursor = Some(next_cursor),
+                _ => break,
+            }
+        }
+        parquet_files.retain(|file| file.file_type == "file" && file.path.ends_with(".parquet"));
+        parquet_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let root = convert.qualified_id("~");
+        let mut result = Vec::new();
+        for (config, config_splits) in splits {
+            for (split, urls) in config_splits {
+                // 数据过大时只转换了前一部分，目录名为 partial-{split}
+                let dirs = [
+                    format!("{}/{}/", config, split),
+                    format!("{}/partial-{}/", config, split),
+                ];
+                let files = parquet_files
+                    .iter()
+                    .filter(|file| dirs.iter().any(|dir| file.path.starts_with(dir.as_str())))
+                    .map(|file| {
+                        let basename = file.path.rsplit('/').next().unwrap_or(&file.path);
+                        StorageFile {
+                            filename: format!("{}/{}", root, file.path),
+                            basename: basename.to_string(),
+                            lastmod: String::new(),
+                            size: file.size.to_string(),
+                            file_type: "file".to_string(),
+                            mime: Some(mime_from_filename(basename)),
+                            etag: Some(file.oid.clone()),
+                            storage_class: None,
+                            lastmod_raw: None,
+                        }
+                    })
+                    .collect();
+
+                result.push(ParquetSplit {
+                    config: config.clone(),
+                    split,
+                    urls,
+                    files,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    fn observed_validators(&self, path: &str) -> Option<Validators> {
+        self.validators.get(path)
+    }
+
     fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
         if config.protocol != "huggingface" {
             return Err(StorageError::InvalidConfig(
                 "Invalid protocol for HuggingFace client".to_string(),
             ));
         }
+        RepoType::from_options(config.extra_options.as_ref())?;
         // API token 是可选的
         Ok(())
     }
@@ -891,8 +1271,8 @@ impl StorageClient for HuggingFaceClient {
         progress_callback: Option<ProgressCallback>,
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<(), StorageError> {
-        let (dataset_id, file_path) = self.parse_path(path)?;
-        let download_url = self.build_download_url(&dataset_id, &file_path);
+        let target = self.parse_path(path)?;
+        let download_url = self.build_download_url(&target);
 
         // 准备认证头（如果有 API token）
         let auth_header = self
@@ -913,3 +1293,86 @@ impl StorageClient for HuggingFaceClient {
         .await
     }
 }
+
+/// 卡片中既可以是单个字符串也可以是字符串列表的字段
+fn card_strings(value: Option<&serde_json::Value>) -> Vec<String> {
+    match value {
+        Some(serde_json::Value::String(value)) => vec![value.clone()],
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 从卡片的 dataset_info 中提取各划分的大小
+/// 单配置数据集为对象，多配置数据集为带 config_name 的对象列表
+fn card_splits(dataset_info: Option<&serde_json::Value>) -> Vec<RepositorySplit> {
+    let configs = match dataset_info {
+        Some(serde_json::Value::Array(configs)) => configs.iter().collect(),
+        Some(config @ serde_json::Value::Object(_)) => vec![config],
+        _ => Vec::new(),
+    };
+
+    configs
+        .into_iter()
+        .flat_map(|config| {
+            let config_name = config
+                .get("config_name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("default")
+                .to_string();
+            config
+                .get("splits")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(move |split| {
+                    Some(RepositorySplit {
+                        config: config_name.clone(),
+                        name: split.get("name")?.as_str()?.to_string(),
+                        num_examples: split
+                            .get("num_examples")
+                            .and_then(|v| v.as_u64())
+                            .map(|n| n.to_string()),
+                        num_bytes: split
+                            .get("num_bytes")
+                            .and_then(|v| v.as_u64())
+                            .map(|n| n.to_string()),
+                    })
+                })
+        })
+        .collect()
+}
+
+/// 从 Link 头中提取下一页的 cursor，返回 (是否有下一页, cursor)
+/// 形如 <https://huggingface.co/api/datasets?cursor=xxx&limit=20>; rel="next"
+fn next_page_cursor(headers: &reqwest::header::HeaderMap) -> (bool, Option<String>) {
+    let Some(link_str) = headers
+        .get(reqwest::header::LINK)
+        .and_then(|link| link.to_str().ok())
+    else {
+        return (false, None);
+    };
+
+    let Some(next_part) = link_str
+        .split(',')
+        .find(|part| part.contains("rel=\"next\""))
+    else {
+        return (false, None);
+    };
+
+    let next_cursor = next_part
+        .trim()
+        .strip_prefix('<')
+        .and_then(|s| s.split('>').next())
+        .and_then(|url| url.split_once('?'))
+        .and_then(|(_, query)| {
+            query
+                .split('&')
+                .find_map(|param| param.strip_prefix("cursor="))
+        })
+        .map(|cursor| urlencoding::decode(cursor).unwrap_or_default().into_owned());
+    (true, next_cursor)
+}
@@ -1,15 +1,62 @@
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::fs;
 use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
 
 use super::traits::{
-    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
-    StorageFile,
+    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageCapabilities,
+    StorageClient, StorageError, StorageFile,
 };
 use crate::utils::chunk_size;
+use crate::utils::mime::mime_from_filename;
 use crate::utils::path_utils::PathUtils;
+use crate::utils::timestamp::unix_to_rfc3339;
+
+/// 收到文件事件后等待的时间，合并短时间内的连续事件（如写入大文件时的多次修改）
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 本地目录监听器（不递归），基于系统文件事件，被丢弃时停止监听
+pub struct LocalDirectoryWatcher {
+    _watcher: notify::RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<()>,
+}
+
+impl LocalDirectoryWatcher {
+    pub fn new(path: &Path) -> Result<Self, StorageError> {
+        use notify::Watcher;
+
+        let (tx, events) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                // 只关心创建、修改和删除，忽略读取文件产生的访问事件
+                if matches!(event, Ok(ref event) if !event.kind.is_access()) {
+                    let _ = tx.send(());
+                }
+            })
+            .map_err(|e| StorageError::IoError(format!("Failed to create watcher: {}", e)))?;
+        watcher
+            .watch(path, notify::RecursiveMode::NonRecursive)
+            .map_err(|e| StorageError::IoError(format!("Failed to watch directory: {}", e)))?;
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// 等待目录发生变化，合并随后短时间内的事件；监听失效时返回 false
+    pub async fn changed(&mut self) -> bool {
+        if self.events.recv().await.is_none() {
+            return false;
+        }
+        tokio::time::sleep(WATCH_DEBOUNCE).await;
+        while self.events.try_recv().is_ok() {}
+        true
+    }
+}
 
 /// 本机文件系统存储客户端
 pub struct LocalFileSystemClient {
@@ -47,30 +94,31 @@ impl LocalFileSystemClient {
         Ok(path_buf)
     }
 
-    /// 获取文件的 MIME 类型
-    fn get_mime_type(path: &Path) -> Option<String> {
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| match ext.to_lowercase().as_str() {
-                "txt" | "md" | "log" => "text/plain",
-                "html" | "htm" => "text/html",
-                "css" => "text/css",
-                "js" => "application/javascript",
-                "json" => "application/json",
-                "xml" => "application/xml",
-                "pdf" => "application/pdf",
-                "jpg" | "jpeg" => "image/jpeg",
-                "png" => "image/png",
-                "gif" => "image/gif",
-                "svg" => "image/svg+xml",
-                "mp3" => "audio/mpeg",
-                "mp4" => "video/mp4",
-                "zip" => "application/zip",
-                "tar" => "application/x-tar",
-                "gz" => "application/gzip",
-                _ => "application/octet-stream",
-            })
-            .map(|s| s.to_string())
+    /// 拒绝删除文件系统根目录、用户主目录和连接的根目录，以及包含它们的上级目录
+    fn check_deletable(&self, target: &Path) -> Result<(), StorageError> {
+        if target.parent().is_none() {
+            return Err(StorageError::RequestFailed(
+                "Cannot delete the root directory".to_string(),
+            ));
+        }
+
+        // 符号链接和 ..、末尾斜杠等写法统一后再比较
+        let canonical =
+            |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let target = canonical(target);
+        let protected = dirs::home_dir()
+            .into_iter()
+            .chain(self.root_path.clone())
+            .map(|path| canonical(&path));
+        for path in protected {
+            if path.starts_with(&target) {
+                return Err(StorageError::RequestFailed(format!(
+                    "Cannot delete protected directory: {}",
+                    target.display()
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// 格式化文件修改时间
@@ -79,13 +127,8 @@ impl LocalFileSystemClient {
             .modified()
             .ok()
             .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|duration| {
-                let seconds = duration.as_secs();
-                chrono::DateTime::from_timestamp(seconds as i64, 0)
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
-            })
-            .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+            .map(|duration| unix_to_rfc3339(duration.as_secs() as i64))
+            .unwrap_or_else(|| unix_to_rfc3339(0))
     }
 }
 
@@ -190,7 +233,7 @@ impl StorageClient for LocalFileSystemClient {
             let mime_type = if is_directory {
                 None
             } else {
-                Self::get_mime_type(&file_path)
+                Some(mime_from_filename(&file_name))
             };
 
             let storage_file = StorageFile {
@@ -201,6 +244,8 @@ impl StorageClient for LocalFileSystemClient {
                 file_type: if is_directory { "directory" } else { "file" }.to_string(),
                 mime: mime_type,
                 etag: None, // 本机文件系统不需要 ETag
+                storage_class: None,
+                lastmod_raw: None,
             };
 
             files.push(storage_file);
@@ -352,6 +397,78 @@ impl StorageClient for LocalFileSystemClient {
         Ok(metadata.len())
     }
 
+    async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let file_path = self.build_safe_path(path)?;
+        fs::remove_file(&file_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to delete file: {}", e)))
+    }
+
+    async fn delete_directory(&self, path: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let dir_path = self.build_safe_path(path)?;
+        self.check_deletable(&dir_path)?;
+        fs::remove_dir_all(&dir_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to delete directory: {}", e)))
+    }
+
+    async fn move_to_trash(&self, path: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let target = self.build_safe_path(path)?;
+        self.check_deletable(&target)?;
+        tokio::task::spawn_blocking(move || trash::delete(&target))
+            .await
+            .map_err(|e| StorageError::IoError(format!("Trash task failed: {}", e)))?
+            .map_err(|e| StorageError::IoError(format!("Failed to move to trash: {}", e)))
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let source_path = self.build_safe_path(from)?;
+        let target_path = self.build_safe_path(to)?;
+        fs::rename(&source_path, &target_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to rename: {}", e)))
+    }
+
+    async fn create_directory(&self, path: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let dir_path = self.build_safe_path(path)?;
+        fs::create_dir_all(&dir_path)
+            .await
+            .map_err(|e| StorageError::IoError(format!("Failed to create directory: {}", e)))
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            upload: false,
+            delete: true,
+            rename: true,
+            create_directory: true,
+        }
+    }
+
+    fn local_path(&self, path: &str) -> Option<PathBuf> {
+        self.build_safe_path(path).ok()
+    }
+
     fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
         if config.protocol != "local" {
             return Err(StorageError::InvalidConfig(format!(
@@ -409,7 +526,7 @@ impl StorageClient for LocalFileSystemClient {
             // 检查取消信号
             if let Some(ref mut cancel_rx) = cancel_rx {
                 if cancel_rx.try_recv().is_ok() {
-                    let _ = fs::remove_file(save_path).await;
+                    // 保留已写入的部分，由下载管理器决定续传还是删除
                     return Err(StorageError::RequestFailed(
                         "download.cancelled".to_string(),
                     ));
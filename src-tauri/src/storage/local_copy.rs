@@ -0,0 +1,266 @@
+// 远程文件的本地副本缓存
+// 部分解析库（unrar、calamine 等）只能读取本地文件，需要先把文件完整下载到临时目录；
+// 超大文件可以只下载部分区间（抽样副本），其余部分以空洞填充，由调用方尝试部分解析
+
+use serde::{Deserialize, Serialize};
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::storage::manager::current_connection_key;
+use crate::storage::traits::{ProgressCallback, StorageClient};
+
+/// 最多保留的本地副本数量，超出后删除最早下载的文件
+const MAX_CACHED_COPIES: usize = 4;
+
+/// 抽样下载时每次读取的大小
+const SAMPLE_CHUNK_SIZE: u64 = 8 * 1024 * 1024; // 8MB
+
+/// 已下载的本地副本，按下载时间排列
+/// 列出内容后通常紧接着预览同一文件，缓存可避免重复下载
+static LOCAL_COPIES: LazyLock<Mutex<Vec<CachedCopy>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+struct CachedCopy {
+    /// 下载时的连接标识，不同连接下的同名路径是不同的文件
+    connection_key: String,
+    remote_path: String,
+    file_size: u64,
+    /// 抽样副本已下载的字节数，完整副本为空
+    sampled_bytes: Option<u64>,
+    local: PathBuf,
+}
+
+/// 抽样副本的说明，返回给前端提示内容不完整
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleInfo {
+    pub total_size: String,    // 使用字符串表示大数字
+    pub sampled_bytes: String, // 使用字符串表示大数字
+}
+
+/// 本地副本
+pub struct LocalCopy {
+    pub path: PathBuf,
+    /// 只下载了部分区间时的抽样说明
+    pub sample: Option<SampleInfo>,
+}
+
+/// 获取文件的本地副本，同一文件未变化时复用上次下载的副本
+/// extension 用于本地文件名，部分解析库依赖扩展名识别格式
+pub async fn local_copy(
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    extension: &str,
+    progress_callback: Option<ProgressCallback>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<PathBuf, String> {
+    let file_size = client
+        .get_file_size(file_path)
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+
+    let connection_key = current_connection_key().await;
+    if let Some(local) = cached_copy(&connection_key, file_path, file_size, false) {
+        return Ok(local.path);
+    }
+
+    let local = new_local_path(extension).await?;
+
+    log::debug!("下载文件到本地: {} ({} 字节)", file_path, file_size);
+    if let Err(e) = client
+        .download_file(file_path, &local, progress_callback, cancel_rx)
+        .await
+    {
+        let _ = tokio::fs::remove_file(&local).await;
+        return Err(format!("Failed to download file: {}", e));
+    }
+
+    remember(&connection_key, file_path, file_size, None, &local).await;
+    Ok(local)
+}
+
+/// 只下载文件的指定区间，生成区间之间为空洞（全零）、在最后一个区间结束处截止的抽样副本
+/// 用于超过完整下载阈值的文件：解析库按偏移读取时，已下载区间内的结构可以正常解析。
+/// 同一文件已有完整副本时直接复用完整副本
+pub async fn sampled_copy(
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    extension: &str,
+    file_size: u64,
+    ranges: Vec<Range<u64>>,
+) -> Result<LocalCopy, String> {
+    let connection_key = current_connection_key().await;
+    if let Some(local) = cached_copy(&connection_key, file_path, file_size, true) {
+        return Ok(local);
+    }
+
+    let ranges = merge_ranges(ranges, file_size);
+    let sampled_bytes: u64 = ranges.iter().map(|range| range.end - range.start).sum();
+    let local = new_local_path(extension).await?;
+
+    log::debug!(
+        "抽样下载文件到本地: {} ({} / {} 字节)",
+        file_path,
+        sampled_bytes,
+        file_size
+    );
+    let result = write_sample(client, file_path, &ranges, &local).await;
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&local).await;
+        return Err(e);
+    }
+
+    let sample = (sampled_bytes < file_size).then_some(sampled_bytes);
+    remember(&connection_key, file_path, file_size, sample, &local).await;
+    Ok(LocalCopy {
+        path: local,
+        sample: sample.map(|sampled_bytes| SampleInfo {
+            total_size: file_size.to_string(),
+            sampled_bytes: sampled_bytes.to_string(),
+        }),
+    })
+}
+
+/// 只下载文件的指定区间到新的临时文件，区间之间为空洞；副本不加入缓存，调用方用完后自行删除
+pub async fn range_copy(
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    extension: &str,
+    file_size: u64,
+    ranges: Vec<Range<u64>>,
+) -> Result<PathBuf, String> {
+    let ranges = merge_ranges(ranges, file_size);
+    let local = new_local_path(extension).await?;
+    if let Err(e) = write_sample(client, file_path, &ranges, &local).await {
+        let _ = tokio::fs::remove_file(&local).await;
+        return Err(e);
+    }
+    Ok(local)
+}
+
+/// 按区间写入下载的数据，区间之间跳过的部分在支持稀疏文件的文件系统上成为空洞，不占用磁盘空间；
+/// 不预先设置文件大小，避免在不支持稀疏文件的文件系统上先写满整个文件
+async fn write_sample(
+    client: Arc<dyn StorageClient>,
+    file_path: &str,
+    ranges: &[Range<u64>],
+    local: &Path,
+) -> Result<(), String> {
+    let mut file = tokio::fs::File::create(local)
+        .await
+        .map_err(|e| format!("Failed to create file: {}", e))?;
+
+    for range in ranges {
+        let mut offset = range.start;
+        while offset < range.end {
+            let length = (range.end - offset).min(SAMPLE_CHUNK_SIZE);
+            let data = client
+                .read_file_range(file_path, offset, length)
+                .await
+                .map_err(|e| format!("Failed to download file range: {}", e))?;
+            if data.is_empty() {
+                break;
+            }
+            file.seek(SeekFrom::Start(offset))
+                .await
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+            file.write_all(&data)
+                .await
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+            offset += data.len() as u64;
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// 排序并合并重叠的区间，裁剪到文件大小以内
+pub(crate) fn merge_ranges(mut ranges: Vec<Range<u64>>, file_size: u64) -> Vec<Range<u64>> {
+    ranges.retain(|range| range.start < range.end.min(file_size));
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<u64>> = Vec::new();
+    for range in ranges {
+        let range = range.start..range.end.min(file_size);
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// 查找文件未变化的缓存副本，allow_sample 为 false 时只接受完整副本
+fn cached_copy(
+    connection_key: &str,
+    file_path: &str,
+    file_size: u64,
+    allow_sample: bool,
+) -> Option<LocalCopy> {
+    let cached = LOCAL_COPIES.lock().ok()?;
+    cached
+        .iter()
+        .find(|copy| {
+            copy.connection_key == connection_key
+                && copy.remote_path == file_path
+                && copy.file_size == file_size
+                && (allow_sample || copy.sampled_bytes.is_none())
+                && copy.local.exists()
+        })
+        .map(|copy| LocalCopy {
+            path: copy.local.clone(),
+            sample: copy.sampled_bytes.map(|sampled_bytes| SampleInfo {
+                total_size: file_size.to_string(),
+                sampled_bytes: sampled_bytes.to_string(),
+            }),
+        })
+}
+
+/// 记录新下载的副本，替换同一文件的旧副本，超出数量时删除最早的副本
+async fn remember(
+    connection_key: &str,
+    file_path: &str,
+    file_size: u64,
+    sampled_bytes: Option<u64>,
+    local: &Path,
+) {
+    let mut removed = Vec::new();
+    if let Ok(mut cached) = LOCAL_COPIES.lock() {
+        cached.retain(|copy| {
+            let stale = copy.connection_key == connection_key && copy.remote_path == file_path;
+            if stale {
+                removed.push(copy.local.clone());
+            }
+            !stale
+        });
+        cached.push(CachedCopy {
+            connection_key: connection_key.to_string(),
+            remote_path: file_path.to_string(),
+            file_size,
+            sampled_bytes,
+            local: local.to_path_buf(),
+        });
+        while cached.len() > MAX_CACHED_COPIES {
+            removed.push(cached.remove(0).local);
+        }
+    }
+    // 在锁外删除文件
+    for path in removed {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+}
+
+async fn new_local_path(extension: &str) -> Result<PathBuf, String> {
+    let temp_dir = local_copy_dir();
+    tokio::fs::create_dir_all(&temp_dir)
+        .await
+        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    Ok(temp_dir.join(format!("{}.{}", uuid::Uuid::new_v4(), extension)))
+}
+
+/// 本地副本所在的临时目录
+pub fn local_copy_dir() -> PathBuf {
+    std::env::temp_dir().join("dataset-viewer-cache")
+}
@@ -1,17 +1,35 @@
+use super::archive_mount::{self, ArchiveMountClient};
+use super::azure_client::AzureBlobClient;
+use super::chunk_cache::ChunkCacheClient;
+use super::gcs_client::GCSClient;
 use super::huggingface_client::HuggingFaceClient;
 use super::local_client::LocalFileSystemClient;
 use super::oss_client::OSSClient;
 use super::smb_client::SMBClient;
+use super::sorting::sort_files;
 use super::ssh_client::SSHClient;
 use super::traits::{ConnectionConfig, DirectoryResult, ListOptions, StorageClient, StorageError};
+use super::visibility::VisibilitySettings;
 use super::webdav_client::WebDAVClient;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::network;
+use crate::utils::persist;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 use tokio::sync::{RwLock, Semaphore};
 
 pub struct StorageManager {
     clients: HashMap<String, Arc<dyn StorageClient + Send + Sync>>,
     active_client: Option<String>,
+    // 当前连接的协议名称
+    active_protocol: Option<String>,
+    // 当前连接的标识，同一存储位置和账号多次连接时保持不变
+    active_connection_key: Option<String>,
     // 缓存的活跃客户端引用，减少HashMap查找
     cached_client: Option<Arc<dyn StorageClient + Send + Sync>>,
     // 并发控制：限制同时进行的请求数量
@@ -23,12 +41,33 @@ impl StorageManager {
         Self {
             clients: HashMap::new(),
             active_client: None,
+            active_protocol: None,
+            active_connection_key: None,
             cached_client: None,
             request_semaphore: Arc::new(Semaphore::new(10)), // 限制最多10个并发请求
         }
     }
 
     pub async fn connect(&mut self, config: &ConnectionConfig) -> Result<(), StorageError> {
+        let client = Self::create_client(config).await?;
+
+        let client_id = format!("{}_{}", config.protocol, chrono::Utc::now().timestamp());
+
+        self.clients.insert(client_id.clone(), client.clone());
+        self.active_client = Some(client_id);
+        self.active_protocol = Some(config.protocol.clone());
+        self.active_connection_key = Some(connection_key(config));
+
+        // 更新缓存的客户端引用
+        self.cached_client = Some(client.clone());
+
+        Ok(())
+    }
+
+    /// 按配置创建并连接客户端，不改变当前连接（用于测试连接配置）
+    pub async fn create_client(
+        config: &ConnectionConfig,
+    ) -> Result<Arc<dyn StorageClient + Send + Sync>, StorageError> {
         let client: Arc<dyn StorageClient + Send + Sync> = match config.protocol.as_str() {
             "webdav" => {
                 let mut client = WebDAVClient::new(config.clone())?;
@@ -45,6 +84,16 @@ impl StorageManager {
                 client.connect(config).await?;
                 Arc::new(client)
             }
+            "azure" => {
+                let mut client = AzureBlobClient::new(config.clone())?;
+                client.connect(config).await?;
+                Arc::new(client)
+            }
+            "gcs" => {
+                let mut client = GCSClient::new(config.clone())?;
+                client.connect(config).await?;
+                Arc::new(client)
+            }
             "huggingface" => {
                 let mut client = HuggingFaceClient::new(config.clone())?;
                 client.connect(config).await?;
@@ -62,16 +111,14 @@ impl StorageManager {
             }
             _ => return Err(StorageError::UnsupportedProtocol(config.protocol.clone())),
         };
-
-        let client_id = format!("{}_{}", config.protocol, chrono::Utc::now().timestamp());
-
-        self.clients.insert(client_id.clone(), client.clone());
-        self.active_client = Some(client_id);
-
-        // 更新缓存的客户端引用
-        self.cached_client = Some(client.clone());
-
-        Ok(())
+        // 远程文件的范围读取使用磁盘缓存，本地文件直接读取
+        let client: Arc<dyn StorageClient + Send + Sync> = if config.protocol == "local" {
+            client
+        } else {
+            Arc::new(ChunkCacheClient::new(client, connection_key(config)))
+        };
+        // 压缩包条目路径（data.zip!images/001.png）在所有协议上都可以直接列出和读取
+        Ok(Arc::new(ArchiveMountClient::new(client)))
     }
 
     pub async fn disconnect(&mut self) -> Result<(), StorageError> {
@@ -84,6 +131,8 @@ impl StorageManager {
             }
         }
         self.active_client = None;
+        self.active_protocol = None;
+        self.active_connection_key = None;
 
         // 清空缓存的客户端引用
         self.cached_client = None;
@@ -109,12 +158,204 @@ impl StorageManager {
         };
 
         // 直接执行请求，client 本身就是线程安全的
-        client.list_directory(path, options).await
+        let mut result = client.list_directory(path, options).await?;
+
+        // 按用户设置隐藏系统文件、检查点目录等
+        let settings = VisibilitySettings::current();
+        settings.filter_files(&mut result.files);
+        // 开启挂载时压缩包显示为目录，压缩包内的压缩包同样可以展开
+        if settings.mount_archives {
+            archive_mount::mount_archives(&mut result.files);
+        }
+        // 各后端时间格式不一致，统一为 RFC3339 UTC 便于排序和本地化显示
+        for file in &mut result.files {
+            file.normalize_lastmod();
+        }
+        // 指定排序方式时在后端统一排序（分页的后端只对当前页排序）
+        if let Some(options) =
+            options.filter(|o| o.sort_by.is_some() || o.natural_sort == Some(true))
+        {
+            sort_files(&mut result.files, options);
+        }
+        Ok(result)
     }
 
     pub fn get_current_client(&self) -> Option<Arc<dyn StorageClient + Send + Sync>> {
         self.cached_client.clone()
     }
+
+    pub fn get_current_protocol(&self) -> Option<String> {
+        self.active_protocol.clone()
+    }
+
+    pub fn get_current_connection_key(&self) -> Option<String> {
+        self.active_connection_key.clone()
+    }
+}
+
+/// 当前连接的标识，未连接时为空
+/// 磁盘缓存（压缩包索引、缩略图等）按该标识区分不同存储上的同名路径
+pub async fn current_connection_key() -> String {
+    get_storage_manager()
+        .await
+        .read()
+        .await
+        .get_current_connection_key()
+        .unwrap_or_default()
+}
+
+/// 根据连接配置中标识存储位置和账号的字段计算连接标识，不包含密钥等敏感信息
+pub(crate) fn connection_key(config: &ConnectionConfig) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        Some(config.protocol.as_str()),
+        config.url.as_deref(),
+        config.endpoint.as_deref(),
+        config.region.as_deref(),
+        config.bucket.as_deref(),
+        config.access_key.as_deref(),
+        config.username.as_deref(),
+        config.share.as_deref(),
+        config.domain.as_deref(),
+        config.root_path.as_deref(),
+    ] {
+        hasher.update(part.unwrap_or("").as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update(config.port.unwrap_or(0).to_string().as_bytes());
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// 共享 HTTP 客户端的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpClientKind {
+    /// 列表、元数据等普通请求，使用较短的总超时
+    Request,
+    /// 文件下载、上传和范围读取，不设总超时（传输时间随文件大小增长），
+    /// 连接卡住由读取时的停顿检测（http_guard::STALL_TIMEOUT）结束
+    Transfer,
+}
+
+/// 共享 HTTP 客户端的连接池设置，修改后对之后建立的连接生效
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpPoolSettings {
+    /// 建立连接的超时（秒）
+    pub connect_timeout_secs: u32,
+    /// 普通请求的总超时（秒），0 表示不限制
+    pub request_timeout_secs: u32,
+    /// 空闲连接保留时间（秒）
+    pub pool_idle_timeout_secs: u32,
+    /// 每个主机最多保留的空闲连接数
+    pub pool_max_idle_per_host: u32,
+}
+
+impl Default for HttpPoolSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 10,
+            request_timeout_secs: 30,
+            pool_idle_timeout_secs: 90,
+            pool_max_idle_per_host: 10,
+        }
+    }
+}
+
+/// 共享的 HTTP 客户端，所有存储后端、下载和插件安装复用同一组连接池，
+/// 同一主机上的重复请求（如反复读取同一压缩包的不同范围）可以复用已建立的 TLS 连接
+struct HttpClientPool {
+    settings: HttpPoolSettings,
+    request: Client,
+    transfer: Client,
+}
+
+impl HttpClientPool {
+    fn settings_file() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join("http-pool.json"))
+    }
+
+    fn load() -> Self {
+        let settings: HttpPoolSettings = Self::settings_file()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default();
+        Self::build(settings.clone()).unwrap_or_else(|e| {
+            log::warn!("HTTP 连接池设置无效，使用默认设置: {}", e);
+            Self::build(HttpPoolSettings::default()).expect("default HTTP client")
+        })
+    }
+
+    fn build(settings: HttpPoolSettings) -> Result<Self, String> {
+        Ok(Self {
+            request: build_http_client(&settings, settings.request_timeout_secs)?,
+            transfer: build_http_client(&settings, 0)?,
+            settings,
+        })
+    }
+}
+
+fn build_http_client(settings: &HttpPoolSettings, timeout_secs: u32) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(
+            settings.connect_timeout_secs.max(1) as u64
+        ))
+        .pool_idle_timeout(Duration::from_secs(settings.pool_idle_timeout_secs as u64))
+        .pool_max_idle_per_host(settings.pool_max_idle_per_host as usize)
+        .tcp_keepalive(Duration::from_secs(60));
+    if timeout_secs > 0 {
+        builder = builder.timeout(Duration::from_secs(timeout_secs as u64));
+    }
+    // 代理和自定义 CA 证书来自全局网络配置
+    network::configure(builder)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+static HTTP_CLIENT_POOL: LazyLock<std::sync::RwLock<HttpClientPool>> =
+    LazyLock::new(|| std::sync::RwLock::new(HttpClientPool::load()));
+
+/// 获取共享的 HTTP 客户端（克隆只增加引用计数，连接池在所有克隆之间共享）
+pub fn shared_http_client(kind: HttpClientKind) -> Client {
+    let pool = HTTP_CLIENT_POOL
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match kind {
+        HttpClientKind::Request => pool.request.clone(),
+        HttpClientKind::Transfer => pool.transfer.clone(),
+    }
+}
+
+/// 获取当前的连接池设置
+pub fn http_pool_settings() -> HttpPoolSettings {
+    HTTP_CLIENT_POOL
+        .read()
+        .map(|pool| pool.settings.clone())
+        .unwrap_or_default()
+}
+
+/// 更新并持久化连接池设置，重新创建共享客户端（已建立的存储连接继续使用原客户端）
+pub fn set_http_pool_settings(settings: HttpPoolSettings) -> Result<(), String> {
+    let pool = HttpClientPool::build(settings)?;
+    let content = serde_json::to_string_pretty(&pool.settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    persist::write_atomic(HttpClientPool::settings_file()?, content)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    let mut current = HTTP_CLIENT_POOL
+        .write()
+        .map_err(|e| format!("Failed to lock HTTP client pool: {}", e))?;
+    *current = pool;
+    Ok(())
+}
+
+/// 按当前设置重新创建共享客户端（网络配置变化后调用）
+pub fn reload_http_clients() -> Result<(), String> {
+    let pool = HttpClientPool::build(http_pool_settings())?;
+    let mut current = HTTP_CLIENT_POOL
+        .write()
+        .map_err(|e| format!("Failed to lock HTTP client pool: {}", e))?;
+    *current = pool;
+    Ok(())
 }
 
 // 全局存储管理器
@@ -1,12 +1,27 @@
+pub mod archive_mount;
+pub mod azure_client;
+pub mod chunk_cache;
+pub mod file_handles;
+pub mod gcs_client;
 pub mod huggingface_client;
 pub mod local_client;
+pub mod local_copy;
 pub mod manager;
 pub mod oss;
 pub mod oss_client;
+pub mod range_read;
+pub mod session;
 pub mod smb_client;
+pub mod sorting;
 pub mod ssh_client;
 pub mod traits;
+pub mod upload;
+pub mod validators;
+pub mod vfs;
+pub mod visibility;
+pub mod watch;
 pub mod webdav_client;
+pub mod webdav_quirks;
 
 pub use manager::get_storage_manager;
 #[allow(unused_imports)] // 这些类型通过Serde序列化在Tauri命令中使用
@@ -1,15 +1,22 @@
+use super::AddressingStyle;
 use crate::utils::crypto::{hmac_sha256, hmac_sha256_bytes, sha256_hex};
 use chrono::Utc;
 use std::collections::HashMap;
 
+/// AWS 访问凭证，临时凭证（STS / AssumeRole）需要同时提供会话令牌
+pub struct AwsCredentials<'a> {
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+    pub session_token: Option<&'a str>,
+}
+
 /// 构建AWS S3的认证头
 pub fn build_aws_auth_headers(
     method: &str,
     uri: &str,
     extra_headers: &HashMap<String, String>,
     query_string: Option<&str>,
-    access_key: &str,
-    secret_key: &str,
+    credentials: &AwsCredentials,
     region: &str,
     host: &str,
 ) -> HashMap<String, String> {
@@ -17,13 +24,20 @@ pub fn build_aws_auth_headers(
     let date_stamp = now.format("%Y%m%d").to_string();
     let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
 
-    // 计算请求体的SHA256哈希（空请求体）
-    let payload_hash = sha256_hex("");
+    // 计算请求体的SHA256哈希（调用方可通过 x-amz-content-sha256 传入非空请求体的哈希）
+    let payload_hash = extra_headers
+        .get("x-amz-content-sha256")
+        .cloned()
+        .unwrap_or_else(|| sha256_hex(""));
 
     let mut headers = extra_headers.clone();
     headers.insert("Host".to_string(), host.to_string());
     headers.insert("X-Amz-Date".to_string(), amz_date.clone());
     headers.insert("x-amz-content-sha256".to_string(), payload_hash.clone());
+    // 会话令牌需要参与签名
+    if let Some(token) = credentials.session_token {
+        headers.insert("x-amz-security-token".to_string(), token.to_string());
+    }
 
     // 构建规范请求
     let canonical_request = build_canonical_request_with_payload(
@@ -44,12 +58,13 @@ pub fn build_aws_auth_headers(
     );
 
     // 计算签名
-    let signature = calculate_aws_signature(&string_to_sign, &date_stamp, region, secret_key);
+    let signature =
+        calculate_aws_signature(&string_to_sign, &date_stamp, region, credentials.secret_key);
 
     // 构建Authorization头
     let authorization = format!(
         "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
-        access_key,
+        credentials.access_key,
         credential_scope,
         get_signed_headers(&headers),
         signature
@@ -133,14 +148,17 @@ fn calculate_aws_signature(
 }
 
 /// 生成AWS S3预签名URL
+/// endpoint 需与 style 一致：virtual-hosted 风格下主机名已包含 bucket
+/// request_payer 为 true 时在 URL 中声明由请求方付费（访问 requester-pays bucket）
 pub fn generate_aws_presigned_url(
     endpoint: &str,
     object_key: &str,
     expires_in_seconds: i64,
-    access_key: &str,
-    secret_key: &str,
+    credentials: &AwsCredentials,
     region: &str,
     bucket: &str,
+    style: AddressingStyle,
+    request_payer: bool,
 ) -> Result<String, String> {
     let now = Utc::now();
     let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
@@ -156,7 +174,7 @@ pub fn generate_aws_presigned_url(
 
     // 构建查询参数
     let credential_scope = format!("{}/{}/s3/aws4_request", date_stamp, region);
-    let credential = format!("{}/{}", access_key, credential_scope);
+    let credential = format!("{}/{}", credentials.access_key, credential_scope);
 
     let mut query_params = vec![
         (
@@ -171,6 +189,15 @@ pub fn generate_aws_presigned_url(
         ("X-Amz-Expires".to_string(), expires.to_string()),
         ("X-Amz-SignedHeaders".to_string(), "host".to_string()),
     ];
+    if let Some(token) = credentials.session_token {
+        query_params.push((
+            "X-Amz-Security-Token".to_string(),
+            urlencoding::encode(token).to_string(),
+        ));
+    }
+    if request_payer {
+        query_params.push(("x-amz-request-payer".to_string(), "requester".to_string()));
+    }
 
     // 排序查询参数
     query_params.sort_by(|a, b| a.0.cmp(&b.0));
@@ -180,16 +207,12 @@ pub fn generate_aws_presigned_url(
         .collect::<Vec<_>>()
         .join("&");
 
-    // 检查是否为虚拟主机风格
-    let host = if let Ok(url) = url::Url::parse(endpoint) {
-        url.host_str().unwrap_or("").to_string()
-    } else {
-        "".to_string()
-    };
-
-    let is_virtual_hosted = host.starts_with(&format!("{}.s3", bucket));
+    // 签名的 host 必须与实际请求的 Host 头一致，非默认端口需要包含在内
+    let host = url::Url::parse(endpoint)
+        .map(|url| super::host_header(&url))
+        .unwrap_or_default();
 
-    let (canonical_uri, object_url) = if is_virtual_hosted {
+    let (canonical_uri, object_url) = if style == AddressingStyle::VirtualHosted {
         // 虚拟主机风格 - 对于S3预签名URL，路径需要进行URI编码，但保持斜杠
         let encoded_key = object_key
             .split('/')
@@ -226,7 +249,8 @@ pub fn generate_aws_presigned_url(
     );
 
     // 计算签名
-    let signature = calculate_aws_signature(&string_to_sign, &date_stamp, region, secret_key);
+    let signature =
+        calculate_aws_signature(&string_to_sign, &date_stamp, region, credentials.secret_key);
 
     // 构建最终URL
     Ok(format!(
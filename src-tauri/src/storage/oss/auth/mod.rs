@@ -2,5 +2,23 @@ pub mod aws_signer;
 pub mod oss_signer;
 
 // 重新导出主要的签名函数，方便使用
-pub use aws_signer::{build_aws_auth_headers, generate_aws_presigned_url};
+pub use aws_signer::{build_aws_auth_headers, generate_aws_presigned_url, AwsCredentials};
 pub use oss_signer::{build_oss_auth_headers, generate_oss_presigned_url};
+
+/// 请求地址风格，决定 bucket 出现在主机名还是路径中
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressingStyle {
+    /// https://endpoint/bucket/key
+    Path,
+    /// https://bucket.endpoint/key
+    VirtualHosted,
+}
+
+/// 请求的 Host 头，非默认端口时包含端口号（与 HTTP 客户端实际发送的一致）
+pub fn host_header(url: &url::Url) -> String {
+    let host = url.host_str().unwrap_or("");
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
@@ -1,3 +1,4 @@
+use super::AddressingStyle;
 use crate::utils::crypto::hmac_sha1_base64;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -44,7 +45,10 @@ pub fn generate_oss_signature(
     // 根据OSS文档，签名中的URI应该是解码后的UTF-8形式
     let normalized_uri = normalize_uri_for_signing(uri);
 
-    let canonicalized_resource = if normalized_uri == "/" {
+    // 服务级请求（如 ListBuckets）不包含 bucket
+    let canonicalized_resource = if bucket.is_empty() {
+        normalized_uri
+    } else if normalized_uri == "/" {
         format!("/{}/", bucket)
     } else {
         format!("/{}{}", bucket, normalized_uri)
@@ -106,6 +110,7 @@ fn normalize_uri_for_signing(uri: &str) -> String {
 }
 
 /// 生成OSS预签名URL（阿里云等）
+/// endpoint 需与 style 一致：virtual-hosted 风格下主机名已包含 bucket
 pub fn generate_oss_presigned_url(
     endpoint: &str,
     object_key: &str,
@@ -113,17 +118,26 @@ pub fn generate_oss_presigned_url(
     access_key: &str,
     secret_key: &str,
     bucket: &str,
+    style: AddressingStyle,
 ) -> Result<String, String> {
     // 计算过期时间戳
     let now = Utc::now().timestamp();
     let expires = now + expires_in_seconds;
 
-    // 构建对象 URL
-    let object_url = format!(
-        "{}/{}",
-        endpoint.trim_end_matches('/'),
-        urlencoding::encode(object_key)
-    );
+    // 构建对象 URL，CanonicalizedResource 与地址风格无关，始终包含 bucket
+    let object_url = match style {
+        AddressingStyle::VirtualHosted => format!(
+            "{}/{}",
+            endpoint.trim_end_matches('/'),
+            urlencoding::encode(object_key)
+        ),
+        AddressingStyle::Path => format!(
+            "{}/{}/{}",
+            endpoint.trim_end_matches('/'),
+            bucket,
+            urlencoding::encode(object_key)
+        ),
+    };
 
     // 构建查询参数 - 使用OSS格式
     let mut query_params = HashMap::new();
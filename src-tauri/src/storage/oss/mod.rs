@@ -4,8 +4,12 @@ pub mod parser;
 // 重新导出认证相关功能
 pub use auth::{
     build_aws_auth_headers, build_oss_auth_headers, generate_aws_presigned_url,
-    generate_oss_presigned_url,
+    generate_oss_presigned_url, host_header, AddressingStyle, AwsCredentials,
 };
 
 // 重新导出解析相关功能
-pub use parser::{build_full_path, extract_object_key, parse_list_objects_response};
+pub use parser::{
+    build_full_path, extract_object_key, parse_archived_object_error, parse_list_buckets_response,
+    parse_list_objects_response, parse_list_versions_response, parse_object_keys, parse_upload_id,
+    split_version_id, BucketLocation,
+};
@@ -1,8 +1,11 @@
 use chrono::Utc;
 use quick_xml::events::Event;
 use quick_xml::Reader;
+use std::collections::HashMap;
 
-use crate::storage::traits::{DirectoryResult, StorageError, StorageFile};
+use crate::storage::traits::{DirectoryResult, ObjectVersion, StorageError, StorageFile};
+use crate::utils::mime::mime_from_filename;
+use crate::utils::timestamp::{format_utc, normalize_timestamp};
 
 /// 解析 OSS 协议 URL 并返回对象键和实际 URL
 ///
@@ -122,6 +125,8 @@ pub fn parse_list_objects_response(
                         file_type: "file".to_string(),
                         mime: None,
                         etag: None,
+                        storage_class: None,
+                        lastmod_raw: None,
                     });
                 } else if element_name == "CommonPrefixes" {
                     current_prefix = Some(String::new());
@@ -147,6 +152,7 @@ pub fn parse_list_objects_response(
                                 .next()
                                 .unwrap_or(&current_text)
                                 .to_string();
+                            obj.mime = Some(mime_from_filename(&obj.basename));
                         }
                         "LastModified" => {
                             obj.lastmod = current_text.clone();
@@ -157,6 +163,9 @@ pub fn parse_list_objects_response(
                         "ETag" => {
                             obj.etag = Some(current_text.trim_matches('"').to_string());
                         }
+                        "StorageClass" => {
+                            obj.storage_class = Some(current_text.clone());
+                        }
                         "Contents" => {
                             if let Some(obj) = current_object.take() {
                                 // 只添加当前前缀下的直接子项
@@ -189,11 +198,13 @@ pub fn parse_list_objects_response(
                                     files.push(StorageFile {
                                         filename: dir_name.to_string(),
                                         basename: dir_name.to_string(),
-                                        lastmod: Utc::now().to_rfc3339(),
+                                        lastmod: format_utc(Utc::now()),
                                         size: "0".to_string(),
                                         file_type: "directory".to_string(),
                                         mime: None,
                                         etag: None,
+                                        storage_class: None,
+                                        lastmod_raw: None,
                                     });
                                 }
                             }
@@ -236,3 +247,305 @@ pub fn parse_list_objects_response(
         path: prefix.to_string(),
     })
 }
+
+/// 解析不带分隔符的 ListObjects 响应，只提取对象 key
+/// 返回 (key 列表, 下一页的标记)，没有更多结果时标记为空
+pub fn parse_object_keys(xml_content: &str) -> Result<(Vec<String>, Option<String>), StorageError> {
+    let mut reader = Reader::from_str(xml_content);
+    reader.trim_text(true);
+
+    let mut keys = Vec::new();
+    let mut buf = Vec::new();
+    let mut current_text = String::new();
+    let mut in_contents = false;
+    let mut is_truncated = false;
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                if e.name().as_ref() == b"Contents" {
+                    in_contents = true;
+                }
+                current_text.clear();
+            }
+            Ok(Event::Text(e)) => {
+                current_text = e.unescape().unwrap_or_default().to_string();
+            }
+            Ok(Event::End(ref e)) => match e.name().as_ref() {
+                b"Key" if in_contents => keys.push(current_text.clone()),
+                b"Contents" => in_contents = false,
+                b"IsTruncated" => is_truncated = current_text == "true",
+                b"NextMarker" | b"NextContinuationToken" => {
+                    next_marker = Some(current_text.clone())
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(StorageError::RequestFailed(format!(
+                    "XML parsing error: {}",
+                    e
+                )))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !is_truncated {
+        return Ok((keys, None));
+    }
+    // 旧版 ListObjects 不带分隔符时可能不返回 NextMarker，使用最后一个 key 继续
+    let next_marker = next_marker.or_else(|| keys.last().cloned());
+    Ok((keys, next_marker))
+}
+
+/// ListBuckets 的一页结果：(bucket 列表, bucket 名称到区域的映射, 下一页的标记)
+pub type ListBucketsPage = (
+    Vec<StorageFile>,
+    HashMap<String, BucketLocation>,
+    Option<String>,
+);
+
+/// ListBuckets 响应中 bucket 所在的区域
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BucketLocation {
+    /// OSS、COS、OBS 返回的 Location（如 oss-cn-hangzhou、ap-beijing），S3 返回的 BucketRegion
+    pub region: Option<String>,
+    /// OSS 返回的外网访问端点（如 oss-cn-hangzhou.aliyuncs.com）
+    pub extranet_endpoint: Option<String>,
+}
+
+/// 解析 ListBuckets 响应，每个 bucket 作为一个目录返回
+/// 返回 (bucket 列表, 各 bucket 的区域, 下一页的标记)，S3 使用 ContinuationToken，OSS 使用 NextMarker
+pub fn parse_list_buckets_response(xml_content: &str) -> Result<ListBucketsPage, StorageError> {
+    let mut reader = Reader::from_str(xml_content);
+    reader.trim_text(true);
+
+    let mut buckets = Vec::new();
+    let mut locations = HashMap::new();
+    let mut buf = Vec::new();
+    let mut current_text = String::new();
+    let mut current_bucket: Option<StorageFile> = None;
+    let mut current_location = BucketLocation::default();
+    let mut is_truncated = false;
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                if e.name().as_ref() == b"Bucket" {
+                    current_bucket = Some(StorageFile {
+                        filename: String::new(),
+                        basename: String::new(),
+                        lastmod: String::new(),
+                        size: "0".to_string(),
+                        file_type: "directory".to_string(),
+                        mime: None,
+                        etag: None,
+                        storage_class: None,
+                        lastmod_raw: None,
+                    });
+                    current_location = BucketLocation::default();
+                }
+                current_text.clear();
+            }
+            Ok(Event::Text(e)) => {
+                current_text = e.unescape().unwrap_or_default().to_string();
+            }
+            Ok(Event::End(ref e)) => match (e.name().as_ref(), current_bucket.as_mut()) {
+                (b"Location", Some(_)) | (b"BucketRegion", Some(_)) => {
+                    current_location.region = Some(current_text.clone()).filter(|r| !r.is_empty())
+                }
+                (b"ExtranetEndpoint", Some(_)) => {
+                    current_location.extranet_endpoint =
+                        Some(current_text.clone()).filter(|e| !e.is_empty())
+                }
+                (b"Name", Some(bucket)) => {
+                    bucket.filename = current_text.clone();
+                    bucket.basename = current_text.clone();
+                }
+                (b"CreationDate", Some(bucket)) => bucket.lastmod = current_text.clone(),
+                (b"StorageClass", Some(bucket)) => {
+                    bucket.storage_class = Some(current_text.clone())
+                }
+                (b"Bucket", _) => {
+                    if let Some(bucket) = current_bucket.take() {
+                        if !bucket.filename.is_empty() {
+                            let location = std::mem::take(&mut current_location);
+                            if location != BucketLocation::default() {
+                                locations.insert(bucket.filename.clone(), location);
+                            }
+                            buckets.push(bucket);
+                        }
+                    }
+                }
+                (b"IsTruncated", None) => is_truncated = current_text == "true",
+                (b"NextMarker", None) | (b"ContinuationToken", None) => {
+                    if !current_text.is_empty() {
+                        next_marker = Some(current_text.clone());
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(StorageError::RequestFailed(format!(
+                    "XML parsing error: {}",
+                    e
+                )))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // OSS 通过 IsTruncated 标记是否还有更多，S3 只在有下一页时返回 ContinuationToken
+    if xml_content.contains("<IsTruncated>") && !is_truncated {
+        next_marker = None;
+    }
+    Ok((buckets, locations, next_marker))
+}
+
+/// 从错误响应中识别归档对象读取失败（InvalidObjectState）
+/// 返回带存储类别的 ObjectArchived 错误，其他错误返回 None
+pub fn parse_archived_object_error(body: &str) -> Option<StorageError> {
+    if !body.contains("InvalidObjectState") {
+        return None;
+    }
+
+    let storage_class = body
+        .split("<StorageClass>")
+        .nth(1)
+        .and_then(|rest| rest.split("</StorageClass>").next())
+        .filter(|class| !class.is_empty())
+        .unwrap_or("ARCHIVE");
+
+    Some(StorageError::ObjectArchived(storage_class.to_string()))
+}
+
+/// 从 InitiateMultipartUpload 响应中提取 UploadId
+pub fn parse_upload_id(body: &str) -> Option<String> {
+    body.split("<UploadId>")
+        .nth(1)
+        .and_then(|rest| rest.split("</UploadId>").next())
+        .filter(|upload_id| !upload_id.is_empty())
+        .map(|upload_id| upload_id.to_string())
+}
+
+/// 从路径中拆分版本号后缀（path?versionId=xxx）
+/// 返回 (对象路径, 版本号)
+pub fn split_version_id(path: &str) -> (&str, Option<&str>) {
+    match path.rsplit_once("?versionId=") {
+        Some((object_path, version_id)) if !version_id.is_empty() => {
+            (object_path, Some(version_id))
+        }
+        _ => (path, None),
+    }
+}
+
+/// 解析 ListObjectVersions 响应
+/// 只保留 key 完全匹配的版本，返回 (版本列表, 下一页的 key-marker, version-id-marker)
+pub fn parse_list_versions_response(
+    xml_content: &str,
+    object_key: &str,
+    display_path: &str,
+) -> Result<(Vec<ObjectVersion>, Option<(String, String)>), StorageError> {
+    let mut reader = Reader::from_str(xml_content);
+    reader.trim_text(true);
+
+    let mut versions = Vec::new();
+    let mut buf = Vec::new();
+    let mut current: Option<ObjectVersion> = None;
+    let mut current_text = String::new();
+    let mut is_truncated = false;
+    let mut next_key_marker: Option<String> = None;
+    let mut next_version_marker: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let element_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if element_name == "Version" || element_name == "DeleteMarker" {
+                    current = Some(ObjectVersion {
+                        key: String::new(),
+                        version_id: String::new(),
+                        is_latest: false,
+                        is_delete_marker: element_name == "DeleteMarker",
+                        lastmod: String::new(),
+                        size: "0".to_string(),
+                        etag: None,
+                        storage_class: None,
+                        path: String::new(),
+                    });
+                }
+                current_text.clear();
+            }
+            Ok(Event::Text(e)) => {
+                current_text = e.unescape().unwrap_or_default().to_string();
+            }
+            Ok(Event::End(ref e)) => {
+                let element_name_bytes = e.name();
+                let element_name = String::from_utf8_lossy(element_name_bytes.as_ref());
+
+                if let Some(ref mut version) = current {
+                    match element_name.as_ref() {
+                        "Key" => version.key = current_text.clone(),
+                        "VersionId" => version.version_id = current_text.clone(),
+                        "IsLatest" => version.is_latest = current_text == "true",
+                        "LastModified" => {
+                            version.lastmod = normalize_timestamp(&current_text)
+                                .unwrap_or_else(|| current_text.clone());
+                        }
+                        "Size" => {
+                            version.size = current_text.parse::<u64>().unwrap_or(0).to_string();
+                        }
+                        "ETag" => {
+                            version.etag = Some(current_text.trim_matches('"').to_string());
+                        }
+                        "StorageClass" => version.storage_class = Some(current_text.clone()),
+                        "Version" | "DeleteMarker" => {
+                            if let Some(mut version) = current.take() {
+                                // 前缀查询会返回同前缀的其他对象，需要精确匹配
+                                if version.key == object_key {
+                                    version.path = format!(
+                                        "{}?versionId={}",
+                                        display_path, version.version_id
+                                    );
+                                    versions.push(version);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                } else {
+                    match element_name.as_ref() {
+                        "IsTruncated" => is_truncated = current_text == "true",
+                        "NextKeyMarker" => next_key_marker = Some(current_text.clone()),
+                        "NextVersionIdMarker" => {
+                            next_version_marker = Some(current_text.clone());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(StorageError::RequestFailed(format!(
+                    "XML parsing error: {}",
+                    e
+                )))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let next_page = match (is_truncated, next_key_marker, next_version_marker) {
+        (true, Some(key), Some(version)) => Some((key, version)),
+        _ => None,
+    };
+
+    Ok((versions, next_page))
+}
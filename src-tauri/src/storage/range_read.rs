@@ -0,0 +1,135 @@
+// HTTP 范围读取的响应处理
+// 部分 HTTP / WebDAV 服务器忽略 Range 请求头，直接返回 200 和完整内容，流式分析压缩包时会读到错误的数据。
+// 检测到这种情况时跳过请求范围之前的数据、读够后立即停止；需要跳过的数据过多时返回 RangeNotSupported 错误，
+// 并把检测结果记录到连接配置中
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::connections::{self, RangeSupport};
+use crate::storage::manager::connection_key;
+use crate::storage::traits::{ConnectionConfig, ProgressCallback, StorageError};
+use crate::utils::http_guard::next_chunk;
+
+/// 服务器不支持 Range 时最多读取（包括跳过）的字节数，超出时返回错误而不是下载整个文件
+pub const UNRANGED_READ_LIMIT: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 读取范围请求的响应体，返回数据和检测到的 Range 支持情况（无法判断时为空）
+/// 最多读取 length 字节，长时间没有收到数据时返回错误
+pub async fn read_range_body(
+    response: reqwest::Response,
+    start: u64,
+    length: u64,
+    progress_callback: Option<ProgressCallback>,
+    mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<(Vec<u8>, Option<RangeSupport>), StorageError> {
+    let content_length = response.content_length();
+    let support = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        Some(RangeSupport::Supported)
+    } else if start == 0 && content_length.is_some_and(|total| total <= length) {
+        // 文件不超过请求的长度，完整内容就是请求的范围
+        None
+    } else {
+        Some(RangeSupport::Unsupported)
+    };
+
+    // 不支持 Range 时需要先跳过 start 之前的数据
+    let skip = if support == Some(RangeSupport::Unsupported) {
+        if start.saturating_add(length) > UNRANGED_READ_LIMIT {
+            return Err(StorageError::RangeNotSupported(format!(
+                "reading bytes {}-{} would download {} bytes",
+                start,
+                start + length - 1,
+                content_length
+                    .map(|total| total.to_string())
+                    .unwrap_or_else(|| "the whole file".to_string())
+            )));
+        }
+        start
+    } else {
+        0
+    };
+
+    let mut result = Vec::with_capacity(length.min(UNRANGED_READ_LIMIT) as usize);
+    let mut skipped = 0u64;
+    let mut stream = response.bytes_stream();
+    let mut cancellable = true;
+
+    loop {
+        // 等待下一块数据，同时监听取消信号，避免服务器停止发送数据时无法取消
+        let chunk = match cancel_rx.as_mut().filter(|_| cancellable) {
+            Some(cancel_rx) => tokio::select! {
+                chunk = next_chunk(&mut stream) => chunk,
+                signal = cancel_rx.recv() => match signal {
+                    // 发送端已关闭表示没有取消来源，继续读取
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        cancellable = false;
+                        continue;
+                    }
+                    _ => {
+                        return Err(StorageError::RequestFailed(
+                            "download.cancelled".to_string(),
+                        ));
+                    }
+                },
+            },
+            None => next_chunk(&mut stream).await,
+        };
+        let chunk = match chunk.map_err(StorageError::NetworkError)? {
+            Some(chunk) => chunk,
+            None => break,
+        };
+
+        let mut data = &chunk[..];
+        if skipped < skip {
+            let count = (skip - skipped).min(data.len() as u64) as usize;
+            skipped += count as u64;
+            data = &data[count..];
+        }
+        let take = (length - result.len() as u64).min(data.len() as u64) as usize;
+        result.extend_from_slice(&data[..take]);
+
+        // 调用进度回调
+        if let Some(ref callback) = progress_callback {
+            callback(result.len() as u64, length);
+        }
+        // 读够即停止，丢弃服务器多返回的数据
+        if result.len() as u64 >= length {
+            break;
+        }
+    }
+
+    Ok((result, support))
+}
+
+/// 记录客户端检测到的 Range 支持情况，变化时写入对应的连接配置
+#[derive(Default)]
+pub struct RangeSupportTracker {
+    /// 0 未检测，1 支持，2 不支持
+    state: AtomicU8,
+}
+
+impl RangeSupportTracker {
+    pub fn observe(&self, config: &ConnectionConfig, support: Option<RangeSupport>) {
+        let Some(support) = support else {
+            return;
+        };
+        let value = match support {
+            RangeSupport::Supported => 1,
+            RangeSupport::Unsupported => 2,
+        };
+        if self.state.swap(value, Ordering::Relaxed) == value {
+            return;
+        }
+        if support == RangeSupport::Unsupported {
+            log::warn!(
+                "服务器不支持 Range 请求，范围读取将跳过前面的数据: {:?}",
+                config.url
+            );
+        }
+        let key = connection_key(config);
+        // 写入连接配置文件是阻塞 IO
+        tauri::async_runtime::spawn_blocking(move || {
+            connections::record_range_support(&key, support)
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 持久化的会话 Cookie
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    /// 过期时间（Unix 秒），会话 Cookie 为 None
+    pub expires: Option<i64>,
+}
+
+/// SSO 登录后捕获的会话 Cookie
+/// 按连接地址的 origin（协议 + 主机 + 端口）分别保存，同一服务器的不同路径共享会话
+pub struct SessionStore;
+
+impl SessionStore {
+    /// 获取 URL 对应的 origin，作为会话的存储键
+    pub fn origin(url: &str) -> Option<String> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        Some(match parsed.port() {
+            Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+            None => format!("{}://{}", parsed.scheme(), host),
+        })
+    }
+
+    fn session_file(url: &str) -> Result<PathBuf, String> {
+        let origin = Self::origin(url).ok_or_else(|| format!("Invalid session URL: {}", url))?;
+        let file_name: String = origin
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        Ok(get_app_data_subdir("sessions")?.join(format!("{}.json", file_name)))
+    }
+
+    /// 保存会话 Cookie，覆盖该 origin 之前的会话
+    pub fn save(url: &str, cookies: &[StoredCookie]) -> Result<(), String> {
+        let path = Self::session_file(url)?;
+        let content = serde_json::to_string_pretty(cookies)
+            .map_err(|e| format!("Failed to serialize session: {}", e))?;
+        persist::write_atomic(&path, content).map_err(|e| format!("Failed to save session: {}", e))
+    }
+
+    /// 读取未过期的会话 Cookie，没有保存的会话时返回空列表
+    pub fn load(url: &str) -> Vec<StoredCookie> {
+        let Ok(path) = Self::session_file(url) else {
+            return Vec::new();
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        persist::read_json::<Vec<StoredCookie>>(&path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| c.expires.map_or(true, |expires| expires > now))
+            .collect()
+    }
+
+    /// 删除保存的会话
+    pub fn clear(url: &str) -> Result<(), String> {
+        let path = Self::session_file(url)?;
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("Failed to clear session: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 生成 Cookie 请求头，没有可用 Cookie 时返回 None
+    pub fn cookie_header(url: &str) -> Option<String> {
+        let cookies = Self::load(url);
+        if cookies.is_empty() {
+            return None;
+        }
+
+        Some(
+            cookies
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+}
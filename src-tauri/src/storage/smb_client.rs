@@ -265,7 +265,7 @@ impl StorageClient for SMBClient {
             // 检查取消信号
             if let Some(ref mut cancel_rx) = cancel_rx {
                 if cancel_rx.try_recv().is_ok() {
-                    let _ = tokio::fs::remove_file(save_path).await;
+                    // 保留已写入的部分，由下载管理器决定续传还是删除
                     return Err(StorageError::RequestFailed(
                         "download.cancelled".to_string(),
                     ));
@@ -0,0 +1,49 @@
+use std::cmp::Ordering;
+
+use crate::storage::traits::{ListOptions, StorageFile};
+use crate::utils::natural_sort::natural_cmp;
+
+/// 按列表选项对文件排序，目录总是排在文件前面
+/// sort_by 支持 "name"、"size"、"modified"，natural_sort 为 true 时名称按自然顺序比较
+pub fn sort_files(files: &mut [StorageFile], options: &ListOptions) {
+    let sort_by = options.sort_by.as_deref().unwrap_or("name");
+    let descending = options.sort_order.as_deref() == Some("desc");
+    let natural = options.natural_sort.unwrap_or(false);
+
+    files.sort_by(|a, b| {
+        let a_is_dir = a.file_type == "directory";
+        let b_is_dir = b.file_type == "directory";
+        if a_is_dir != b_is_dir {
+            return b_is_dir.cmp(&a_is_dir);
+        }
+
+        let ordering = match sort_by {
+            "size" => parse_size(&a.size).cmp(&parse_size(&b.size)),
+            // lastmod 已统一为 RFC3339 UTC，可以直接按字符串比较
+            "modified" => a.lastmod.cmp(&b.lastmod),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| compare_names(&a.basename, &b.basename, natural));
+
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// 比较文件名，非自然排序时忽略大小写
+pub fn compare_names(a: &str, b: &str, natural: bool) -> Ordering {
+    if natural {
+        natural_cmp(a, b)
+    } else {
+        a.to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| a.cmp(b))
+    }
+}
+
+fn parse_size(size: &str) -> u64 {
+    size.parse::<u64>().unwrap_or(0)
+}
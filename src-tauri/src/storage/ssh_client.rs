@@ -8,11 +8,14 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::Mutex;
 
+use crate::storage::sorting::sort_files;
 use crate::storage::traits::{
     ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
     StorageFile,
 };
+use crate::utils::mime::mime_from_filename;
 use crate::utils::path_utils::PathUtils;
+use crate::utils::timestamp::unix_to_rfc3339;
 
 pub struct SSHClient {
     config: ConnectionConfig,
@@ -227,13 +230,8 @@ impl SSHClient {
     /// 格式化修改时间
     fn format_mtime(mtime: SystemTime) -> String {
         match mtime.duration_since(UNIX_EPOCH) {
-            Ok(duration) => {
-                let secs = duration.as_secs();
-                let dt = chrono::DateTime::from_timestamp(secs as i64, 0)
-                    .unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap());
-                dt.format("%Y-%m-%d %H:%M:%S").to_string()
-            }
-            Err(_) => "1970-01-01 00:00:00".to_string(),
+            Ok(duration) => unix_to_rfc3339(duration.as_secs() as i64),
+            Err(_) => unix_to_rfc3339(0),
         }
     }
 }
@@ -282,6 +280,11 @@ impl StorageClient for SSHClient {
             let metadata = entry.metadata();
             let is_dir = metadata.is_dir();
             let file_type = if is_dir { "directory" } else { "file" };
+            let mime = if is_dir {
+                None
+            } else {
+                Some(mime_from_filename(&filename))
+            };
 
             let file = StorageFile {
                 filename: filename.clone(),
@@ -289,42 +292,18 @@ impl StorageClient for SSHClient {
                 lastmod: Self::format_mtime(metadata.modified().unwrap_or(UNIX_EPOCH)),
                 size: Self::format_file_size(metadata.len()),
                 file_type: file_type.to_string(),
-                mime: if is_dir {
-                    None
-                } else {
-                    Some("application/octet-stream".to_string())
-                },
+                mime,
                 etag: None,
+                storage_class: None,
+                lastmod_raw: None,
             };
 
             files.push(file);
         }
 
         // 按选项排序
-        let sort_by = options
-            .and_then(|o| o.sort_by.clone())
-            .unwrap_or_else(|| "name".to_string());
-        let sort_order = options
-            .and_then(|o| o.sort_order.clone())
-            .unwrap_or_else(|| "asc".to_string());
-
-        files.sort_by(|a, b| {
-            let cmp = match sort_by.as_str() {
-                "size" => a
-                    .size
-                    .parse::<u64>()
-                    .unwrap_or(0)
-                    .cmp(&b.size.parse::<u64>().unwrap_or(0)),
-                "modified" => a.lastmod.cmp(&b.lastmod),
-                _ => a.filename.cmp(&b.filename),
-            };
-
-            if sort_order == "desc" {
-                cmp.reverse()
-            } else {
-                cmp
-            }
-        });
+        let default_options = ListOptions::default();
+        sort_files(&mut files, options.unwrap_or(&default_options));
 
         Ok(DirectoryResult {
             files,
@@ -521,7 +500,7 @@ impl StorageClient for SSHClient {
             // 避免所有权问题，每次传递 None 而不是 cancel_rx 的引用
             let chunk_data = if let Some(ref mut rx) = cancel_rx {
                 if rx.try_recv().is_ok() {
-                    let _ = tokio::fs::remove_file(save_path).await;
+                    // 保留已写入的部分，由下载管理器决定续传还是删除
                     return Err(StorageError::RequestFailed(
                         "download.cancelled".to_string(),
                     ));
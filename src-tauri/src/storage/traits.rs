@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::storage::validators::Validators;
+use crate::utils::mime::{detect_mime, mime_from_extension, DEFAULT_MIME, MAGIC_SNIFF_LEN};
+use crate::utils::timestamp::normalize_timestamp;
+
 /// 进度回调函数类型
 pub type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
 
@@ -11,12 +15,35 @@ pub type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
 pub struct StorageFile {
     pub filename: String,
     pub basename: String,
+    /// 最后修改时间，统一为 RFC3339 UTC（如 2024-01-02T03:04:05Z），未知时为空字符串
     pub lastmod: String,
     pub size: String, // 使用字符串表示大数字
     #[serde(rename = "type")]
     pub file_type: String, // "file" or "directory"
     pub mime: Option<String>,
     pub etag: Option<String>,
+    /// 对象存储类别（如 STANDARD、GLACIER），仅对象存储提供
+    pub storage_class: Option<String>,
+    /// 后端返回的原始时间字符串，仅在与规范化结果不同时保留
+    pub lastmod_raw: Option<String>,
+}
+
+impl StorageFile {
+    /// 将 lastmod 规范化为 RFC3339 UTC，原始值保存在 lastmod_raw 中
+    pub fn normalize_lastmod(&mut self) {
+        if self.lastmod_raw.is_some() || self.lastmod.is_empty() {
+            return;
+        }
+
+        match normalize_timestamp(&self.lastmod) {
+            Some(normalized) if normalized == self.lastmod => {}
+            Some(normalized) => {
+                self.lastmod_raw = Some(std::mem::replace(&mut self.lastmod, normalized));
+            }
+            // 无法识别的时间（如 "unknown"）不参与排序和显示
+            None => self.lastmod_raw = Some(std::mem::take(&mut self.lastmod)),
+        }
+    }
 }
 
 /// 统一的目录列表结果
@@ -30,9 +57,118 @@ pub struct DirectoryResult {
     pub path: String,
 }
 
-/// 统一的列表选项
+/// 对象的历史版本（开启版本控制的 bucket）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectVersion {
+    pub key: String,
+    pub version_id: String,
+    pub is_latest: bool,
+    /// 删除标记没有内容，不能读取
+    pub is_delete_marker: bool,
+    pub lastmod: String,
+    pub size: String, // 使用字符串表示大数字
+    pub etag: Option<String>,
+    pub storage_class: Option<String>,
+    /// 可直接用于读取/下载该版本的路径（带 ?versionId= 后缀）
+    pub path: String,
+}
+
+/// 仓库的分支、标签等版本（HuggingFace 等基于 Git 的存储）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryRevision {
+    pub name: String,
+    pub kind: String, // "branch", "tag", "convert"
+    pub target_commit: String,
+    /// 可直接用于浏览和读取该版本的路径（带 @revision 后缀）
+    pub path: String,
+}
+
+/// 仓库概要信息（HuggingFace 数据集卡片）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryInfo {
+    pub id: String,
+    pub pretty_name: Option<String>,
+    pub description: Option<String>,
+    pub license: Vec<String>,
+    pub tags: Vec<String>,
+    pub downloads: String, // 使用字符串表示大数字
+    pub likes: String,     // 使用字符串表示大数字
+    pub lastmod: Option<String>,
+    /// 需要申请访问权限
+    pub gated: bool,
+    pub splits: Vec<RepositorySplit>,
+}
+
+/// 数据集配置下的一个划分（train、validation 等）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositorySplit {
+    pub config: String,
+    pub name: String,
+    pub num_examples: Option<String>, // 使用字符串表示大数字
+    pub num_bytes: Option<String>,    // 使用字符串表示大数字
+}
+
+/// 自动转换的 Parquet 划分（HuggingFace refs/convert/parquet）
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParquetSplit {
+    pub config: String,
+    pub split: String,
+    /// 各分片的下载 URL
+    pub urls: Vec<String>,
+    /// 各分片在转换分支中的文件，filename 为完整路径，可直接用于表格预览
+    pub files: Vec<StorageFile>,
+}
+
+/// 对象元数据，用于判断已打开的文件是否在远程发生变化
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
+pub struct ObjectMetadata {
+    pub size: String, // 使用字符串表示大数字
+    pub etag: Option<String>,
+    /// 修改时间（RFC3339），协议不提供时为空
+    pub last_modified: Option<String>,
+}
+
+impl ObjectMetadata {
+    pub fn new(size: u64, validators: Validators) -> Self {
+        Self {
+            size: size.to_string(),
+            etag: validators.etag,
+            last_modified: validators
+                .last_modified
+                .and_then(|value| normalize_timestamp(&value)),
+        }
+    }
+
+    /// 磁盘缓存（压缩包索引、缩略图等）使用的文件版本，ETag、修改时间和大小任一变化都视为新版本
+    pub fn cache_version(&self) -> String {
+        format!(
+            "etag:{};lastmod:{};size:{}",
+            self.etag.as_deref().unwrap_or(""),
+            self.last_modified.as_deref().unwrap_or(""),
+            self.size
+        )
+    }
+}
+
+/// 存储连接支持的写操作，前端据此隐藏不支持的操作
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCapabilities {
+    pub upload: bool,
+    pub delete: bool,
+    pub rename: bool,
+    pub create_directory: bool,
+}
+
+/// 统一的列表选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
 pub struct ListOptions {
     pub page_size: Option<u32>,
     pub marker: Option<String>,
@@ -40,6 +176,10 @@ pub struct ListOptions {
     pub recursive: Option<bool>,
     pub sort_by: Option<String>,    // "name", "size", "modified"
     pub sort_order: Option<String>, // "asc", "desc"
+    /// 名称按自然顺序排序（shard2 < shard10）
+    pub natural_sort: Option<bool>,
+    /// 分支、标签或提交 SHA（HuggingFace），路径中已带 @revision 时以路径为准
+    pub revision: Option<String>,
 }
 
 /// 统一的存储响应结构
@@ -73,6 +213,14 @@ pub struct ConnectionConfig {
     pub endpoint: Option<String>,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// HTTP 认证方式："basic"、"digest"、"bearer" 或 "none"，未指定时按是否提供用户名密码推断
+    /// bearer 使用 password 字段作为令牌
+    pub auth_scheme: Option<String>,
+    // S3 特定字段
+    /// 临时凭证（STS / AssumeRole）的会话令牌
+    pub session_token: Option<String>,
+    /// 访问请求方付费（requester-pays）的 bucket，流量费用由请求方承担
+    pub request_payer: Option<bool>,
     // SSH 特定字段
     pub port: Option<u16>,
     pub private_key_path: Option<String>,
@@ -116,6 +264,12 @@ pub enum StorageError {
 
     #[error("Network error: {0}")]
     NetworkError(String),
+
+    #[error("Object is archived ({0}), restore it before reading")]
+    ObjectArchived(String),
+
+    #[error("Server does not support range requests: {0}")]
+    RangeNotSupported(String),
 }
 
 /// 统一存储客户端接口
@@ -166,6 +320,24 @@ pub trait StorageClient: Send + Sync {
     /// 获取文件大小
     async fn get_file_size(&self, path: &str) -> Result<u64, StorageError>;
 
+    /// 检测文件的 MIME 类型
+    /// 扩展名无法识别时读取文件头，根据魔数和内容判断
+    async fn detect_mime(&self, path: &str) -> Result<String, StorageError> {
+        if let Some(mime) = mime_from_extension(path) {
+            return Ok(mime.to_string());
+        }
+
+        let size = self.get_file_size(path).await?;
+        if size == 0 {
+            return Ok(DEFAULT_MIME.to_string());
+        }
+
+        let head = self
+            .read_file_range(path, 0, size.min(MAGIC_SNIFF_LEN))
+            .await?;
+        Ok(detect_mime(path, Some(&head)))
+    }
+
     /// 下载文件到指定路径，支持进度回调和取消
     /// 各个存储客户端应该实现高效的流式下载策略
     /// 默认实现使用分块读取，但建议各客户端根据协议特性优化
@@ -177,6 +349,157 @@ pub trait StorageClient: Send + Sync {
         cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<(), StorageError>;
 
+    /// 上传本地文件到指定路径，已存在的文件会被覆盖，支持进度回调和取消
+    async fn upload_file(
+        &self,
+        local_path: &std::path::Path,
+        path: &str,
+        progress_callback: Option<ProgressCallback>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        // 默认实现：存储只读
+        let _ = (local_path, progress_callback, cancel_rx);
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Upload is not supported for {}",
+            path
+        )))
+    }
+
+    /// 删除文件
+    async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+        // 默认实现：存储只读
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Delete is not supported for {}",
+            path
+        )))
+    }
+
+    /// 递归删除目录及其中的所有内容
+    async fn delete_directory(&self, path: &str) -> Result<(), StorageError> {
+        // 默认实现：存储只读
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Delete is not supported for {}",
+            path
+        )))
+    }
+
+    /// 把文件或目录移到系统回收站，可以从回收站恢复
+    async fn move_to_trash(&self, path: &str) -> Result<(), StorageError> {
+        // 默认实现：只有本地文件系统有回收站
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Trash is not available for {}",
+            path
+        )))
+    }
+
+    /// 重命名或移动文件、目录，目标已存在时覆盖
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        // 默认实现：存储只读
+        let _ = to;
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Rename is not supported for {}",
+            from
+        )))
+    }
+
+    /// 创建目录，父目录不存在时一并创建，目录已存在时视为成功
+    async fn create_directory(&self, path: &str) -> Result<(), StorageError> {
+        // 默认实现：存储只读
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Creating directories is not supported for {}",
+            path
+        )))
+    }
+
+    /// 支持的写操作，与上面的写方法保持一致
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities::default()
+    }
+
+    /// 恢复归档存储类别中的对象（如 S3 Glacier / OSS Archive）
+    /// 返回恢复状态："initiated"、"in_progress" 或 "already_restored"
+    async fn restore_object(
+        &self,
+        path: &str,
+        days: u32,
+        tier: Option<&str>,
+    ) -> Result<String, StorageError> {
+        // 默认实现：非对象存储不支持归档恢复
+        let _ = (days, tier);
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Restore is not supported for {}",
+            path
+        )))
+    }
+
+    /// 列出对象的所有历史版本（包括删除标记），按时间从新到旧排列
+    async fn list_object_versions(&self, path: &str) -> Result<Vec<ObjectVersion>, StorageError> {
+        // 默认实现：非对象存储不支持版本控制
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Object versions are not supported for {}",
+            path
+        )))
+    }
+
+    /// 列出仓库的分支和标签，返回的 path 可直接用于浏览对应版本
+    async fn list_revisions(&self, path: &str) -> Result<Vec<RepositoryRevision>, StorageError> {
+        // 默认实现：只有基于 Git 的存储有版本分支
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Revisions are not supported for {}",
+            path
+        )))
+    }
+
+    /// 获取仓库的概要信息（描述、许可证、标签、各划分大小）
+    async fn get_repository_info(&self, path: &str) -> Result<RepositoryInfo, StorageError> {
+        // 默认实现：只有 HuggingFace 提供仓库卡片
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Repository info is not available for {}",
+            path
+        )))
+    }
+
+    /// 列出自动转换为 Parquet 的配置和划分
+    async fn list_parquet_splits(&self, path: &str) -> Result<Vec<ParquetSplit>, StorageError> {
+        // 默认实现：只有 HuggingFace 数据集提供自动转换
+        Err(StorageError::ProtocolNotSupported(format!(
+            "Parquet conversion is not available for {}",
+            path
+        )))
+    }
+
+    /// 获取对象的 ETag（不含引号），用于与本地文件做完整性比较
+    async fn get_object_etag(&self, path: &str) -> Result<String, StorageError> {
+        // 默认实现：只有对象存储提供 ETag
+        Err(StorageError::ProtocolNotSupported(format!(
+            "ETag is not available for {}",
+            path
+        )))
+    }
+
+    /// 获取对象元数据（大小、ETag、修改时间）
+    async fn get_metadata(&self, path: &str) -> Result<ObjectMetadata, StorageError> {
+        // 默认实现：只能获取大小和对象存储的 ETag
+        let size = self.get_file_size(path).await?;
+        let validators = Validators {
+            etag: self.get_object_etag(path).await.ok(),
+            last_modified: None,
+        };
+        Ok(ObjectMetadata::new(size, validators))
+    }
+
+    /// 最近一次读取响应中返回的校验信息，客户端没有记录时为空
+    fn observed_validators(&self, path: &str) -> Option<Validators> {
+        let _ = path;
+        None
+    }
+
+    /// 路径对应的本地文件系统路径，只有本地客户端提供，用于监听系统文件事件
+    fn local_path(&self, path: &str) -> Option<std::path::PathBuf> {
+        let _ = path;
+        None
+    }
+
     /// 验证配置是否有效
     #[allow(dead_code)] // API 保留方法
     fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError>;
@@ -0,0 +1,89 @@
+// 文件上传辅助
+// 把本地文件包装为带进度回调的流式请求体，等待请求时同时监听取消信号；
+// 对象存储的大文件按分片上传，分片大小根据文件大小和分片数量上限计算
+
+use serde::Serialize;
+use std::future::Future;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+use crate::storage::traits::{ProgressCallback, StorageError};
+
+/// 超过该大小的文件使用分片上传
+pub const MULTIPART_THRESHOLD: u64 = 32 * 1024 * 1024; // 32MB
+
+/// 最小分片大小（S3 要求除最后一片外不小于 5MB）
+const MIN_PART_SIZE: u64 = 16 * 1024 * 1024; // 16MB
+
+/// 单个文件的最大分片数量（S3 / OSS 的限制）
+const MAX_PARTS: u64 = 10_000;
+
+/// 流式请求体每次读取的大小
+const STREAM_CHUNK_SIZE: usize = 256 * 1024; // 256KB
+
+/// 上传进度事件
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadProgress {
+    pub upload_id: String,
+    pub uploaded: String, // 使用字符串表示大数字
+    pub total: String,    // 使用字符串表示大数字
+}
+
+/// 计算分片大小，保证分片数量不超过上限
+pub fn part_size(file_size: u64) -> u64 {
+    MIN_PART_SIZE.max(file_size.div_ceil(MAX_PARTS))
+}
+
+/// 打开要上传的本地文件，返回文件和大小
+pub async fn open_local_file(local_path: &Path) -> Result<(tokio::fs::File, u64), StorageError> {
+    let file = tokio::fs::File::open(local_path)
+        .await
+        .map_err(|e| StorageError::IoError(format!("Failed to open file: {}", e)))?;
+    let size = file
+        .metadata()
+        .await
+        .map_err(|e| StorageError::IoError(format!("Failed to read file metadata: {}", e)))?
+        .len();
+    Ok((file, size))
+}
+
+/// 把本地文件包装为流式请求体，每发送一块数据调用一次进度回调
+pub fn file_body(
+    file: tokio::fs::File,
+    total: u64,
+    progress_callback: Option<ProgressCallback>,
+) -> reqwest::Body {
+    let stream = futures_util::stream::try_unfold((file, 0u64), move |(mut file, sent)| {
+        let progress_callback = progress_callback.clone();
+        async move {
+            let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+            let read = file.read(&mut buf).await?;
+            if read == 0 {
+                return Ok::<_, std::io::Error>(None);
+            }
+            buf.truncate(read);
+
+            let sent = sent + read as u64;
+            if let Some(ref callback) = progress_callback {
+                callback(sent, total);
+            }
+            Ok(Some((bytes::Bytes::from(buf), (file, sent))))
+        }
+    });
+    reqwest::Body::wrap_stream(stream)
+}
+
+/// 等待上传请求完成，同时监听取消信号
+pub async fn with_cancel<T>(
+    future: impl Future<Output = Result<T, StorageError>>,
+    cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+) -> Result<T, StorageError> {
+    match cancel_rx {
+        Some(cancel_rx) => tokio::select! {
+            result = future => result,
+            _ = cancel_rx.recv() => Err(StorageError::RequestFailed("upload.cancelled".to_string())),
+        },
+        None => future.await,
+    }
+}
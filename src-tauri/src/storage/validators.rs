@@ -0,0 +1,78 @@
+// 远程对象的校验信息
+// 记录 HTTP 响应中返回的 ETag / Last-Modified，供读取缓存判断远程文件是否已变化
+
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::utils::timestamp::normalize_timestamp;
+
+/// 每个客户端最多记录的路径数量，超出后清空重新记录
+const MAX_TRACKED_PATHS: usize = 10_000;
+
+/// 对象的校验信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// ETag（不含引号和弱校验前缀）
+    pub etag: Option<String>,
+    /// Last-Modified 原始值（HTTP 日期格式）
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// 从响应头读取校验信息，Hugging Face 的 LFS 文件使用 X-Linked-Etag 标识文件内容
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        Self {
+            etag: header("x-linked-etag")
+                .or_else(|| header("etag"))
+                .map(|etag| etag.trim_start_matches("W/").trim_matches('"').to_string())
+                .filter(|etag| !etag.is_empty()),
+            last_modified: header("last-modified").filter(|value| !value.is_empty()),
+        }
+    }
+
+    /// 用作缓存版本的标识：优先使用 ETag，其次使用修改时间，都没有时为空
+    /// 修改时间统一为 RFC3339，使响应头和元数据中的不同格式得到相同的标识
+    pub fn version(&self) -> Option<String> {
+        match (&self.etag, &self.last_modified) {
+            (Some(etag), _) => Some(format!("etag:{}", etag)),
+            (None, Some(last_modified)) => Some(format!(
+                "lastmod:{}",
+                normalize_timestamp(last_modified).unwrap_or_else(|| last_modified.clone())
+            )),
+            (None, None) => None,
+        }
+    }
+}
+
+/// 按路径记录客户端最近一次响应中的校验信息
+#[derive(Default)]
+pub struct ValidatorTracker {
+    entries: Mutex<HashMap<String, Validators>>,
+}
+
+impl ValidatorTracker {
+    /// 记录响应头中的校验信息，没有校验信息时不记录
+    pub fn record(&self, path: &str, headers: &HeaderMap) {
+        let validators = Validators::from_headers(headers);
+        if validators.version().is_none() {
+            return;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= MAX_TRACKED_PATHS && !entries.contains_key(path) {
+                entries.clear();
+            }
+            entries.insert(path.to_string(), validators);
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<Validators> {
+        self.entries.lock().ok()?.get(path).cloned()
+    }
+}
@@ -0,0 +1,138 @@
+// 插件使用的虚拟文件系统路径
+// 用同一种写法表示存储中的文件和压缩包内的条目：`storage://<路径>[!<条目路径>]`
+// 例如 storage://bucket/data.zip!images/001.png，storage:// 表示当前连接；
+// 也可以使用当前连接的协议前缀（oss://...），不带前缀的路径直接交给当前存储客户端
+// 条目可以是内层压缩包中的条目：data.zip!inner.tar.gz!001.png
+
+use std::sync::Arc;
+
+use crate::archive::types::CompressionType;
+use crate::preview::source::PreviewSource;
+use crate::storage::file_handles::MAX_READ_LENGTH;
+use crate::storage::traits::StorageClient;
+use crate::utils::protocol_handler::ProtocolHandler;
+
+/// 表示当前连接的虚拟路径前缀
+pub const STORAGE_SCHEME: &str = "storage://";
+
+/// 虚拟路径对应的文件信息
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VfsStat {
+    pub size: String, // 使用字符串表示大数字
+    /// 是否为压缩包内的条目
+    pub in_archive: bool,
+}
+
+/// 解析后的虚拟路径
+pub struct VfsPath {
+    /// 存储中的路径（压缩包条目时为压缩包路径）
+    pub path: String,
+    /// 压缩包内的条目路径
+    pub entry_path: Option<String>,
+}
+
+impl VfsPath {
+    /// 解析虚拟路径，带协议前缀（storage:// 除外）时必须与当前连接的协议一致
+    pub fn parse(
+        vfs_path: &str,
+        protocol: Option<&str>,
+        client: &dyn StorageClient,
+    ) -> Result<Self, String> {
+        let vfs_path = vfs_path.strip_prefix(STORAGE_SCHEME).unwrap_or(vfs_path);
+        if vfs_path.is_empty() {
+            return Err("Path is required".to_string());
+        }
+
+        if let Some((scheme, _)) = vfs_path.split_once("://") {
+            if protocol != Some(scheme) {
+                return Err(format!(
+                    "Path {} does not belong to the current connection",
+                    vfs_path
+                ));
+            }
+        }
+
+        let (storage_part, entry_path) = match split_archive_entry(vfs_path) {
+            Some((archive, entry)) => {
+                let entry = entry.trim_start_matches('/');
+                if entry.is_empty() {
+                    return Err(format!("Archive entry path is empty: {}", vfs_path));
+                }
+                (archive, Some(entry.to_string()))
+            }
+            None => (vfs_path, None),
+        };
+
+        let path = if storage_part.contains("://") {
+            ProtocolHandler::extract_relative_path(storage_part, client)
+        } else {
+            storage_part.to_string()
+        };
+
+        Ok(Self { path, entry_path })
+    }
+
+    /// 获取文件或压缩包条目的大小
+    pub async fn stat(&self, client: Arc<dyn StorageClient>) -> Result<VfsStat, String> {
+        let size = match &self.entry_path {
+            Some(_) => self.source().read(client, 0, 1).await?.total_size,
+            None => client
+                .get_file_size(&self.path)
+                .await
+                .map_err(|e| format!("Failed to get file size: {}", e))?,
+        };
+
+        Ok(VfsStat {
+            size: size.to_string(),
+            in_archive: self.entry_path.is_some(),
+        })
+    }
+
+    /// 从 offset 开始读取 length 字节，未指定 length 时读取到末尾，单次最多 16MB
+    pub async fn read(
+        &self,
+        client: Arc<dyn StorageClient>,
+        offset: u64,
+        length: Option<u32>,
+    ) -> Result<Vec<u8>, String> {
+        let max_bytes = length.unwrap_or(MAX_READ_LENGTH);
+        if max_bytes > MAX_READ_LENGTH {
+            return Err(format!(
+                "Read length {} exceeds limit of {} bytes",
+                max_bytes, MAX_READ_LENGTH
+            ));
+        }
+
+        let data = self.source().read(client, offset, max_bytes as u64).await?;
+        if length.is_none() && offset + (data.bytes.len() as u64) < data.total_size {
+            return Err(format!(
+                "File size {} exceeds limit of {} bytes, read it in ranges",
+                data.total_size, MAX_READ_LENGTH
+            ));
+        }
+
+        Ok(data.bytes)
+    }
+
+    fn source(&self) -> PreviewSource {
+        PreviewSource::new(self.path.clone(), self.entry_path.clone())
+    }
+}
+
+/// 在第一个以压缩包扩展名结尾的路径段后的 `!` 处拆分
+/// 文件名中的 `!` 只要前面不是压缩包名就保持原样
+pub fn split_archive_entry(path: &str) -> Option<(&str, &str)> {
+    path.match_indices('!').find_map(|(index, _)| {
+        let archive = &path[..index];
+        let filename = archive.rsplit('/').next().unwrap_or(archive);
+        if matches!(
+            CompressionType::from_filename(filename),
+            CompressionType::Unknown
+        ) {
+            None
+        } else {
+            Some((archive, &path[index + 1..]))
+        }
+    })
+}
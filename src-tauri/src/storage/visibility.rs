@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+use crate::storage::traits::StorageFile;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 操作系统自动生成的文件
+const SYSTEM_FILES: [&str; 6] = [
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+    "ehthumbs.db",
+    "Icon\r",
+    ".localized",
+];
+
+/// 工具生成的缓存和检查点目录
+const CHECKPOINT_DIRS: [&str; 5] = [
+    ".ipynb_checkpoints",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    "__MACOSX",
+];
+
+/// 隐藏文件与系统文件的显示设置
+/// 对目录列表和递归操作（去重、估算等）统一生效
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VisibilitySettings {
+    /// 显示以 "." 开头的文件和目录
+    pub show_dotfiles: bool,
+    /// 显示 .DS_Store、Thumbs.db 等系统文件
+    pub show_system_files: bool,
+    /// 显示 .ipynb_checkpoints、__pycache__ 等检查点和缓存目录
+    pub show_checkpoint_dirs: bool,
+    /// 把 zip、tar.gz 等压缩包显示为可展开的只读目录
+    pub mount_archives: bool,
+}
+
+impl Default for VisibilitySettings {
+    fn default() -> Self {
+        Self {
+            show_dotfiles: true,
+            show_system_files: false,
+            show_checkpoint_dirs: false,
+            mount_archives: false,
+        }
+    }
+}
+
+impl VisibilitySettings {
+    /// 判断条目是否应该显示
+    pub fn is_visible(&self, name: &str, is_directory: bool) -> bool {
+        let name = name.trim_end_matches('/');
+        let name = name.rsplit('/').next().unwrap_or(name);
+
+        if !self.show_system_files && !is_directory && SYSTEM_FILES.contains(&name) {
+            return false;
+        }
+        if !self.show_checkpoint_dirs && is_directory && CHECKPOINT_DIRS.contains(&name) {
+            return false;
+        }
+        if !self.show_dotfiles && name.starts_with('.') {
+            return false;
+        }
+
+        true
+    }
+
+    /// 过滤文件列表
+    pub fn filter_files(&self, files: &mut Vec<StorageFile>) {
+        files.retain(|f| self.is_visible(&f.basename, f.file_type == "directory"));
+    }
+
+    fn settings_file() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join("visibility.json"))
+    }
+
+    /// 从设置文件加载，文件不存在或损坏时使用默认值
+    fn load() -> Self {
+        Self::settings_file()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    /// 获取当前生效的设置
+    pub fn current() -> Self {
+        VISIBILITY_SETTINGS
+            .read()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// 更新并持久化设置
+    pub fn update(settings: VisibilitySettings) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        persist::write_atomic(Self::settings_file()?, content)
+            .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+        if let Ok(mut current) = VISIBILITY_SETTINGS.write() {
+            *current = settings;
+        }
+        Ok(())
+    }
+}
+
+static VISIBILITY_SETTINGS: LazyLock<RwLock<VisibilitySettings>> =
+    LazyLock::new(|| RwLock::new(VisibilitySettings::load()));
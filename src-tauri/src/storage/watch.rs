@@ -0,0 +1,155 @@
+// 目录变化监听
+// 本地文件系统通过系统文件事件触发检查，其他协议定期列出目录（WebDAV 即 PROPFIND）；
+// 两者都以目录列表快照对比得出新增、删除和修改的条目，条目有 ETag 时按 ETag 比较，
+// 否则按大小和修改时间比较
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::storage::local_client::LocalDirectoryWatcher;
+use crate::storage::traits::{ListOptions, StorageClient, StorageError, StorageFile};
+
+/// 远程目录的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 单次快照最多列出的条目数量，超过后不再翻页
+const MAX_SNAPSHOT_ENTRIES: usize = 50_000;
+
+/// 目录变化事件
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryChanged {
+    pub path: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+}
+
+/// 目录列表快照（文件名 -> 指纹）
+struct Snapshot(HashMap<String, String>);
+
+impl Snapshot {
+    async fn capture(client: &dyn StorageClient, path: &str) -> Result<Self, StorageError> {
+        let mut entries = HashMap::new();
+        let mut marker = None;
+
+        loop {
+            let options = ListOptions {
+                page_size: Some(1000),
+                marker,
+                ..Default::default()
+            };
+            let result = client.list_directory(path, Some(&options)).await?;
+            for file in &result.files {
+                entries.insert(file.filename.clone(), Self::fingerprint(file));
+            }
+
+            match result.next_marker {
+                Some(next) if result.has_more && entries.len() < MAX_SNAPSHOT_ENTRIES => {
+                    marker = Some(next)
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Self(entries))
+    }
+
+    /// WebDAV 和对象存储的 ETag 在内容变化时一定会变，没有 ETag 时退回到大小和修改时间
+    fn fingerprint(file: &StorageFile) -> String {
+        match &file.etag {
+            Some(etag) => format!("{}:{}", file.file_type, etag),
+            None => format!("{}:{}:{}", file.file_type, file.size, file.lastmod),
+        }
+    }
+
+    fn diff(&self, newer: &Snapshot, path: &str) -> Option<DirectoryChanged> {
+        let mut added = Vec::new();
+        let mut modified = Vec::new();
+        for (name, fingerprint) in &newer.0 {
+            match self.0.get(name) {
+                None => added.push(name.clone()),
+                Some(previous) if previous != fingerprint => modified.push(name.clone()),
+                _ => {}
+            }
+        }
+        let mut removed: Vec<String> = self
+            .0
+            .keys()
+            .filter(|name| !newer.0.contains_key(*name))
+            .cloned()
+            .collect();
+
+        if added.is_empty() && removed.is_empty() && modified.is_empty() {
+            return None;
+        }
+        added.sort();
+        removed.sort();
+        modified.sort();
+        Some(DirectoryChanged {
+            path: path.to_string(),
+            added,
+            removed,
+            modified,
+        })
+    }
+}
+
+/// 监听目录变化直到收到取消信号或连接断开，每次检测到变化调用一次 on_change
+pub async fn watch_directory<F>(
+    client: Arc<dyn StorageClient>,
+    path: &str,
+    cancel_rx: &mut broadcast::Receiver<()>,
+    mut on_change: F,
+) -> Result<(), String>
+where
+    F: FnMut(DirectoryChanged),
+{
+    let mut snapshot = Snapshot::capture(client.as_ref(), path)
+        .await
+        .map_err(|e| format!("Failed to list directory: {}", e))?;
+
+    // 本地目录使用系统文件事件，无法创建监听器时退回到轮询
+    let mut local_watcher = client.local_path(path).and_then(|local| {
+        LocalDirectoryWatcher::new(&local)
+            .map_err(|e| log::warn!("无法监听本地目录 {}: {}", path, e))
+            .ok()
+    });
+
+    loop {
+        tokio::select! {
+            _ = cancel_rx.recv() => return Ok(()),
+            _ = next_tick(&mut local_watcher) => {}
+        }
+
+        let latest = match Snapshot::capture(client.as_ref(), path).await {
+            Ok(latest) => latest,
+            Err(StorageError::NotConnected) => return Ok(()),
+            // 目录被删除或网络暂时不可用时保留旧快照，下次再比较
+            Err(e) => {
+                log::debug!("刷新目录快照失败 {}: {}", path, e);
+                continue;
+            }
+        };
+        if let Some(changed) = snapshot.diff(&latest, path) {
+            on_change(changed);
+        }
+        snapshot = latest;
+    }
+}
+
+/// 等待下一次检查：本地目录等待文件事件，远程目录等待轮询间隔
+async fn next_tick(local_watcher: &mut Option<LocalDirectoryWatcher>) {
+    match local_watcher {
+        Some(watcher) => {
+            if !watcher.changed().await {
+                // 监听器已失效，挂起直到取消
+                std::future::pending::<()>().await;
+            }
+        }
+        None => tokio::time::sleep(POLL_INTERVAL).await,
+    }
+}
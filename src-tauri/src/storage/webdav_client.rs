@@ -1,76 +1,128 @@
 use async_trait::async_trait;
-use base64::engine::general_purpose;
-use base64::Engine;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use reqwest::Client;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
 
+use crate::storage::manager::{shared_http_client, HttpClientKind};
+use crate::storage::range_read::{read_range_body, RangeSupportTracker};
+use crate::storage::session::SessionStore;
 use crate::storage::traits::{
-    ConnectionConfig, DirectoryResult, ListOptions, ProgressCallback, StorageClient, StorageError,
-    StorageFile, StorageRequest, StorageResponse,
+    ConnectionConfig, DirectoryResult, ListOptions, ObjectMetadata, ProgressCallback,
+    StorageCapabilities, StorageClient, StorageError, StorageFile, StorageRequest, StorageResponse,
 };
-use crate::utils::http_downloader::HttpDownloader;
+use crate::storage::upload::{self, with_cancel};
+use crate::storage::validators::{ValidatorTracker, Validators};
+use crate::storage::webdav_quirks::{encode_url_path, href_path, is_direct_child, WebDAVQuirks};
+use crate::utils::http_auth::HttpAuth;
+use crate::utils::http_downloader::{HttpDownloadConfig, HttpDownloader};
+use crate::utils::http_guard;
+use crate::utils::mime::{mime_from_filename, DEFAULT_MIME};
 
 pub struct WebDAVClient {
     client: Client,
     download_client: Client, // 专门用于下载的客户端，配置更长超时
     config: ConnectionConfig,
-    auth_header: Option<String>,
+    auth: HttpAuth,
+    /// SSO 登录捕获的会话 Cookie
+    session_cookie: Option<String>,
+    quirks: WebDAVQuirks,
     connected: AtomicBool,
+    /// 读取响应中返回的 ETag / Last-Modified
+    validators: ValidatorTracker,
+    /// 服务器是否支持 Range 请求
+    range_support: RangeSupportTracker,
 }
 
 impl WebDAVClient {
     pub fn new(config: ConnectionConfig) -> Result<Self, StorageError> {
-        let _base_url = config
+        let base_url = config
             .url
             .clone()
             .ok_or_else(|| StorageError::InvalidConfig("WebDAV URL is required".to_string()))?;
+        let detected = WebDAVQuirks::detect_server(&base_url, &HashMap::new());
+        let quirks = WebDAVQuirks::from_options(&detected, config.extra_options.as_ref());
 
-        let auth_header =
-            if let (Some(username), Some(password)) = (&config.username, &config.password) {
-                let credentials =
-                    general_purpose::STANDARD.encode(format!("{}:{}", username, password));
-                Some(format!("Basic {}", credentials))
-            } else {
-                None
-            };
+        let auth = HttpAuth::new(&config).map_err(StorageError::InvalidConfig)?;
+        let session_cookie = SessionStore::cookie_header(&base_url);
 
-        // 配置普通请求的HTTP客户端
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30)) // 总超时时间
-            .connect_timeout(Duration::from_secs(10)) // 连接超时
-            .pool_idle_timeout(Duration::from_secs(90)) // 连接池空闲超时
-            .pool_max_idle_per_host(10) // 每个主机最大空闲连接数
-            .tcp_keepalive(Duration::from_secs(60)) // TCP keepalive
-            .build()
-            .map_err(|e| {
-                StorageError::InvalidConfig(format!("Failed to create HTTP client: {}", e))
-            })?;
-
-        // 配置下载专用的HTTP客户端，使用更长的超时时间
-        let download_client = Client::builder()
-            .timeout(Duration::from_secs(600)) // 下载总超时时间：10分钟
-            .connect_timeout(Duration::from_secs(10)) // 连接超时保持不变
-            .pool_idle_timeout(Duration::from_secs(300)) // 连接池空闲超时：5分钟
-            .pool_max_idle_per_host(5) // 下载连接数较少
-            .tcp_keepalive(Duration::from_secs(60)) // TCP keepalive
-            .build()
-            .map_err(|e| {
-                StorageError::InvalidConfig(format!("Failed to create download HTTP client: {}", e))
-            })?;
+        // 使用共享连接池：普通请求和下载分别使用不同超时的客户端
+        let client = shared_http_client(HttpClientKind::Request);
+        let download_client = shared_http_client(HttpClientKind::Transfer);
 
         Ok(WebDAVClient {
             client,
             download_client,
             config,
-            auth_header,
+            auth,
+            session_cookie,
+            quirks,
             connected: AtomicBool::new(false),
+            validators: ValidatorTracker::default(),
+            range_support: RangeSupportTracker::default(),
         })
     }
 
+    /// 发送带认证的请求
+    /// Digest 认证的首个请求会收到 401 质询，计算响应后自动重试一次
+    async fn send_authorized(
+        &self,
+        client: &Client,
+        method: reqwest::Method,
+        url: &str,
+        headers: &HashMap<String, String>,
+        body: Option<&str>,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let mut retried = false;
+
+        loop {
+            let mut req_builder = self.authorize(client.request(method.clone(), url), &method, url);
+
+            // 添加其他头部
+            for (key, value) in headers {
+                req_builder = req_builder.header(key, value);
+            }
+
+            // 添加请求体
+            if let Some(body) = body {
+                req_builder = req_builder.body(body.to_string());
+            }
+
+            let response = req_builder.send().await?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED && !retried {
+                let challenge = response
+                    .headers()
+                    .get("www-authenticate")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                if self.auth.handle_challenge(challenge) {
+                    retried = true;
+                    continue;
+                }
+            }
+
+            return Ok(response);
+        }
+    }
+
+    /// 添加认证头和会话 Cookie
+    fn authorize(
+        &self,
+        mut req_builder: reqwest::RequestBuilder,
+        method: &reqwest::Method,
+        url: &str,
+    ) -> reqwest::RequestBuilder {
+        if let Some(auth) = self.auth.authorization(method.as_str(), url) {
+            req_builder = req_builder.header("Authorization", auth);
+        }
+        if let Some(cookie) = &self.session_cookie {
+            req_builder = req_builder.header("Cookie", cookie);
+        }
+        req_builder
+    }
+
     /// 执行单次请求
     async fn execute_request_internal(
         &self,
@@ -79,16 +131,13 @@ impl WebDAVClient {
         // 处理 webdav:// 协议 URL
         let actual_url = self.parse_webdav_url(&request.url)?;
 
-        let mut req_builder = match request.method.as_str() {
-            "GET" => self.client.get(&actual_url),
-            "POST" => self.client.post(&actual_url),
-            "PUT" => self.client.put(&actual_url),
-            "DELETE" => self.client.delete(&actual_url),
-            "HEAD" => self.client.head(&actual_url),
-            "PROPFIND" => self.client.request(
-                reqwest::Method::from_bytes(b"PROPFIND").unwrap(),
-                &actual_url,
-            ),
+        let method = match request.method.as_str() {
+            "GET" => reqwest::Method::GET,
+            "POST" => reqwest::Method::POST,
+            "PUT" => reqwest::Method::PUT,
+            "DELETE" => reqwest::Method::DELETE,
+            "HEAD" => reqwest::Method::HEAD,
+            "PROPFIND" => reqwest::Method::from_bytes(b"PROPFIND").unwrap(),
             _ => {
                 return Err(StorageError::RequestFailed(format!(
                     "Unsupported method: {}",
@@ -97,30 +146,24 @@ impl WebDAVClient {
             }
         };
 
-        // 添加认证头
-        if let Some(ref auth) = self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
-        }
-
-        // 添加其他头部
-        for (key, value) in &request.headers {
-            req_builder = req_builder.header(key, value);
-        }
-
-        // 添加请求体
-        if let Some(ref body) = request.body {
-            req_builder = req_builder.body(body.clone());
-        }
-
-        let response = req_builder.send().await.map_err(|e| {
-            if e.is_timeout() {
-                StorageError::NetworkError(format!("Request timeout: {}", e))
-            } else if e.is_connect() {
-                StorageError::ConnectionFailed(format!("Connection failed: {}", e))
-            } else {
-                StorageError::NetworkError(e.to_string())
-            }
-        })?;
+        let response = self
+            .send_authorized(
+                &self.client,
+                method,
+                &actual_url,
+                &request.headers,
+                request.body.as_deref(),
+            )
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    StorageError::NetworkError(format!("Request timeout: {}", e))
+                } else if e.is_connect() {
+                    StorageError::ConnectionFailed(format!("Connection failed: {}", e))
+                } else {
+                    StorageError::NetworkError(e.to_string())
+                }
+            })?;
 
         let status = response.status().as_u16();
         let headers = response
@@ -129,9 +172,11 @@ impl WebDAVClient {
             .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
             .collect();
 
-        let body = response.text().await.map_err(|e| {
-            StorageError::NetworkError(format!("Failed to read response body: {}", e))
-        })?;
+        let body = http_guard::read_text(response, http_guard::MAX_METADATA_BYTES)
+            .await
+            .map_err(|e| {
+                StorageError::NetworkError(format!("Failed to read response body: {}", e))
+            })?;
 
         Ok(StorageResponse {
             status,
@@ -154,15 +199,13 @@ impl StorageClient for WebDAVClient {
         }
         self.config = clean_config;
 
-        // 重新生成认证头
-        self.auth_header =
-            if let (Some(username), Some(password)) = (&config.username, &config.password) {
-                let credentials =
-                    general_purpose::STANDARD.encode(format!("{}:{}", username, password));
-                Some(format!("Basic {}", credentials))
-            } else {
-                None
-            };
+        // 重新生成认证状态
+        self.auth = HttpAuth::new(config).map_err(StorageError::InvalidConfig)?;
+        self.session_cookie = self
+            .config
+            .url
+            .as_deref()
+            .and_then(SessionStore::cookie_header);
 
         // 测试连接
         let test_request = StorageRequest {
@@ -186,7 +229,19 @@ impl StorageClient for WebDAVClient {
         };
 
         match self.execute_request_internal(&test_request).await {
-            Ok(_) => {
+            Ok(response) => {
+                // 根据响应头识别服务器类型，再应用用户指定的兼容性选项
+                let detected = WebDAVQuirks::detect_server(
+                    self.config.url.as_deref().unwrap_or(""),
+                    &response.headers,
+                );
+                self.quirks =
+                    WebDAVQuirks::from_options(&detected, self.config.extra_options.as_ref());
+                log::debug!(
+                    "WebDAV quirks for {} server: {:?}",
+                    self.quirks.server,
+                    self.quirks
+                );
                 self.connected.store(true, Ordering::Relaxed);
                 Ok(())
             }
@@ -215,7 +270,13 @@ impl StorageClient for WebDAVClient {
 
         let actual_url = self.parse_path_to_url_with_type(path, true)?; // 目录列表按目录处理
 
-        let propfind_body = r#"<?xml version="1.0" encoding="utf-8" ?>
+        let propfind_body = if self.quirks.allprop {
+            r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propfind xmlns:D="DAV:">
+  <D:allprop/>
+</D:propfind>"#
+        } else {
+            r#"<?xml version="1.0" encoding="utf-8" ?>
 <D:propfind xmlns:D="DAV:">
   <D:prop>
     <D:resourcetype/>
@@ -224,7 +285,8 @@ impl StorageClient for WebDAVClient {
     <D:getcontenttype/>
     <D:getetag/>
   </D:prop>
-</D:propfind>"#;
+</D:propfind>"#
+        };
 
         let request = StorageRequest {
             method: "PROPFIND".to_string(),
@@ -304,10 +366,8 @@ impl StorageClient for WebDAVClient {
         start: u64,
         length: u64,
         progress_callback: Option<ProgressCallback>,
-        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+        cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
     ) -> Result<Vec<u8>, StorageError> {
-        use futures_util::StreamExt; // 这里需要StreamExt用于内存读取
-
         if !self.connected.load(Ordering::Relaxed) {
             return Err(StorageError::NotConnected);
         }
@@ -317,18 +377,20 @@ impl StorageClient for WebDAVClient {
 
         // 添加调试日志
 
-        // 使用下载专用客户端进行文件范围读取
-        let mut request = self.download_client.get(&actual_url);
-        if let Some(auth) = &self.auth_header {
-            request = request.header("Authorization", auth);
-        }
-
         // 设置 Range 头
+        let mut headers = HashMap::new();
         let range_header = format!("bytes={}-{}", start, start + length - 1);
-        request = request.header("Range", range_header.clone());
+        headers.insert("Range".to_string(), range_header);
 
-        let response = request
-            .send()
+        // 使用下载专用客户端进行文件范围读取
+        let response = self
+            .send_authorized(
+                &self.download_client,
+                reqwest::Method::GET,
+                &actual_url,
+                &headers,
+                None,
+            )
             .await
             .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
 
@@ -339,34 +401,12 @@ impl StorageClient for WebDAVClient {
                 response.status().canonical_reason().unwrap_or("Unknown")
             )));
         }
+        self.validators.record(path, response.headers());
 
-        // 使用流式读取以支持进度回调
-        let mut result = Vec::with_capacity(length as usize);
-        let mut downloaded = 0u64;
-        let mut stream = response.bytes_stream();
-
-        while let Some(chunk_result) = stream.next().await {
-            // 检查取消信号
-            if let Some(ref mut cancel_rx) = cancel_rx {
-                if cancel_rx.try_recv().is_ok() {
-                    return Err(StorageError::RequestFailed(
-                        "download.cancelled".to_string(),
-                    ));
-                }
-            }
-
-            let chunk = chunk_result
-                .map_err(|e| StorageError::NetworkError(format!("Failed to read chunk: {}", e)))?;
-
-            result.extend_from_slice(&chunk);
-            downloaded += chunk.len() as u64;
-
-            // 调用进度回调
-            if let Some(ref callback) = progress_callback {
-                callback(downloaded, length);
-            }
-        }
-
+        // 流式读取以支持进度回调，服务器忽略 Range 时只读取请求的部分
+        let (result, support) =
+            read_range_body(response, start, length, progress_callback, cancel_rx).await?;
+        self.range_support.observe(&self.config, support);
         Ok(result)
     }
 
@@ -380,13 +420,14 @@ impl StorageClient for WebDAVClient {
 
         // 添加调试日志
 
-        let mut request = self.download_client.get(&actual_url);
-        if let Some(auth) = &self.auth_header {
-            request = request.header("Authorization", auth);
-        }
-
-        let response = request
-            .send()
+        let response = self
+            .send_authorized(
+                &self.download_client,
+                reqwest::Method::GET,
+                &actual_url,
+                &HashMap::new(),
+                None,
+            )
             .await
             .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
 
@@ -398,11 +439,11 @@ impl StorageClient for WebDAVClient {
             )));
         }
 
-        let bytes = response.bytes().await.map_err(|e| {
-            StorageError::NetworkError(format!("Failed to read response body: {}", e))
-        })?;
+        self.validators.record(path, response.headers());
 
-        Ok(bytes.to_vec())
+        http_guard::read_body(response, http_guard::MAX_FULL_FILE_BYTES)
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Failed to read response body: {}", e)))
     }
 
     async fn get_file_size(&self, path: &str) -> Result<u64, StorageError> {
@@ -413,13 +454,14 @@ impl StorageClient for WebDAVClient {
         // 处理协议URL格式 - 文件操作，不添加尾部斜杠
         let actual_url = self.parse_path_to_url_with_type(path, false)?;
 
-        let mut request = self.client.head(&actual_url);
-        if let Some(auth) = &self.auth_header {
-            request = request.header("Authorization", auth);
-        }
-
-        let response = request
-            .send()
+        let response = self
+            .send_authorized(
+                &self.client,
+                reqwest::Method::HEAD,
+                &actual_url,
+                &HashMap::new(),
+                None,
+            )
             .await
             .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
 
@@ -445,6 +487,199 @@ impl StorageClient for WebDAVClient {
         ))
     }
 
+    async fn get_metadata(&self, path: &str) -> Result<ObjectMetadata, StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let actual_url = self.parse_path_to_url_with_type(path, false)?;
+        let response = self
+            .send_authorized(
+                &self.client,
+                reqwest::Method::HEAD,
+                &actual_url,
+                &HashMap::new(),
+                None,
+            )
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "HTTP {}: {}",
+                response.status(),
+                response.status().canonical_reason().unwrap_or("Unknown")
+            )));
+        }
+        self.validators.record(path, response.headers());
+
+        let size = response
+            .headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| {
+                StorageError::RequestFailed("Unable to determine file size".to_string())
+            })?;
+        Ok(ObjectMetadata::new(
+            size,
+            Validators::from_headers(response.headers()),
+        ))
+    }
+
+    fn observed_validators(&self, path: &str) -> Option<Validators> {
+        self.validators.get(path)
+    }
+
+    /// 使用 PUT 流式上传，Digest 认证收到质询后重新打开文件重试一次
+    async fn upload_file(
+        &self,
+        local_path: &std::path::Path,
+        path: &str,
+        progress_callback: Option<ProgressCallback>,
+        mut cancel_rx: Option<&mut tokio::sync::broadcast::Receiver<()>>,
+    ) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let actual_url = self.parse_path_to_url_with_type(path, false)?;
+        let mut retried = false;
+
+        loop {
+            let (file, size) = upload::open_local_file(local_path).await?;
+            let req_builder = self
+                .authorize(
+                    self.download_client.put(&actual_url),
+                    &reqwest::Method::PUT,
+                    &actual_url,
+                )
+                .header("Content-Length", size)
+                .body(upload::file_body(file, size, progress_callback.clone()));
+
+            let response = with_cancel(
+                async {
+                    req_builder.send().await.map_err(|e| {
+                        StorageError::NetworkError(format!("Upload request failed: {}", e))
+                    })
+                },
+                cancel_rx.as_deref_mut(),
+            )
+            .await?;
+
+            let status = response.status();
+            if status == reqwest::StatusCode::UNAUTHORIZED && !retried {
+                let challenge = response
+                    .headers()
+                    .get("www-authenticate")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                if self.auth.handle_challenge(challenge) {
+                    retried = true;
+                    continue;
+                }
+            }
+
+            if !status.is_success() {
+                return Err(StorageError::RequestFailed(format!(
+                    "HTTP {}: {}",
+                    status,
+                    status.canonical_reason().unwrap_or("Unknown")
+                )));
+            }
+            return Ok(());
+        }
+    }
+
+    async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+        let url = self.parse_path_to_url_with_type(path, false)?;
+        self.delete_resource(&url).await
+    }
+
+    /// WebDAV 删除集合时服务器会递归删除其中的所有内容
+    async fn delete_directory(&self, path: &str) -> Result<(), StorageError> {
+        let url = self.parse_path_to_url_with_type(path, true)?;
+        let base_url = self.config.url.as_deref().unwrap_or_default();
+        if url.trim_end_matches('/') == base_url.trim_end_matches('/') {
+            return Err(StorageError::RequestFailed(
+                "Cannot delete the storage root directory".to_string(),
+            ));
+        }
+        self.delete_resource(&url).await
+    }
+
+    /// 使用 MOVE 方法，Destination 为目标的完整 URL
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let source_url = self.parse_path_to_url_with_type(from, false)?;
+        let target_url = self.parse_path_to_url_with_type(to, false)?;
+        let mut headers = HashMap::new();
+        headers.insert("Destination".to_string(), target_url);
+        headers.insert("Overwrite".to_string(), "T".to_string());
+
+        let response = self
+            .send_authorized(
+                &self.client,
+                reqwest::Method::from_bytes(b"MOVE").unwrap(),
+                &source_url,
+                &headers,
+                None,
+            )
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "HTTP {}: {}",
+                response.status(),
+                response.status().canonical_reason().unwrap_or("Unknown")
+            )));
+        }
+        Ok(())
+    }
+
+    /// MKCOL 不会创建缺失的父目录（返回 409），此时先逐级创建父目录再重试
+    async fn create_directory(&self, path: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let mut pending = vec![self.parse_path_to_url_with_type(path, true)?];
+        while let Some(url) = pending.last().cloned() {
+            let status = self.make_collection(&url).await?;
+            if status == reqwest::StatusCode::CONFLICT {
+                let parent = parent_collection(&url).ok_or_else(|| {
+                    StorageError::RequestFailed(format!("HTTP {}: {}", status, url))
+                })?;
+                pending.push(parent);
+                continue;
+            }
+
+            // 405 表示目录已存在
+            if !status.is_success() && status != reqwest::StatusCode::METHOD_NOT_ALLOWED {
+                return Err(StorageError::RequestFailed(format!(
+                    "HTTP {}: {}",
+                    status,
+                    status.canonical_reason().unwrap_or("Unknown")
+                )));
+            }
+            pending.pop();
+        }
+        Ok(())
+    }
+
+    fn capabilities(&self) -> StorageCapabilities {
+        StorageCapabilities {
+            upload: true,
+            delete: true,
+            rename: true,
+            create_directory: true,
+        }
+    }
+
     fn validate_config(&self, config: &ConnectionConfig) -> Result<(), StorageError> {
         if config.protocol != "webdav" {
             return Err(StorageError::InvalidConfig(format!(
@@ -471,11 +706,18 @@ impl StorageClient for WebDAVClient {
     ) -> Result<(), StorageError> {
         let url = self.parse_path_to_url(path)?;
 
-        // 使用通用HTTP下载工具
-        HttpDownloader::download_with_auth(
+        // 使用通用HTTP下载工具，Digest 认证使用连接时收到的质询计算请求头
+        let mut config = HttpDownloadConfig::new(url.clone());
+        if let Some(auth) = self.auth.authorization("GET", &url) {
+            config = config.with_auth(auth);
+        }
+        if let Some(cookie) = &self.session_cookie {
+            config.headers.insert("Cookie".to_string(), cookie.clone());
+        }
+
+        HttpDownloader::download_stream(
             &self.client,
-            &url,
-            self.auth_header.as_deref(),
+            config,
             save_path,
             progress_callback,
             cancel_rx,
@@ -526,43 +768,38 @@ impl WebDAVClient {
         let mut in_getcontentlength = false;
         let mut in_getlastmodified = false;
         let mut in_getcontenttype = false;
+        let mut in_getetag = false;
 
         let mut buf = Vec::new();
 
+        // 按本地名匹配元素，兼容 D:、d:、ns0:、lp1: 等任意命名空间前缀
         loop {
             match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) => match e.name().as_ref() {
-                    b"D:response" | b"d:response" | b"response" => {
+                Ok(Event::Start(ref e)) => match e.local_name().as_ref() {
+                    b"response" => {
                         in_response = true;
                         current_response = WebDAVResponse::default();
                     }
-                    b"D:href" | b"d:href" | b"href" if in_response => in_href = true,
-                    b"D:prop" | b"d:prop" | b"prop" if in_response => in_prop = true,
-                    b"D:resourcetype" | b"d:resourcetype" | b"resourcetype" if in_prop => {
-                        in_resourcetype = true
-                    }
-                    tag if in_prop
-                        && (tag.ends_with(b":getcontentlength") || tag == b"getcontentlength") =>
-                    {
-                        in_getcontentlength = true;
-                    }
-                    tag if in_prop
-                        && (tag.ends_with(b":getlastmodified") || tag == b"getlastmodified") =>
-                    {
-                        in_getlastmodified = true
-                    }
-                    tag if in_prop
-                        && (tag.ends_with(b":getcontenttype") || tag == b"getcontenttype") =>
-                    {
-                        in_getcontenttype = true
-                    }
-                    b"D:collection" | b"d:collection" | b"collection" if in_resourcetype => {
+                    b"href" if in_response && !in_prop => in_href = true,
+                    b"prop" if in_response => in_prop = true,
+                    b"resourcetype" if in_prop => in_resourcetype = true,
+                    b"getcontentlength" if in_prop => in_getcontentlength = true,
+                    b"getlastmodified" if in_prop => in_getlastmodified = true,
+                    b"getcontenttype" if in_prop => in_getcontenttype = true,
+                    b"getetag" if in_prop => in_getetag = true,
+                    b"collection" if in_resourcetype => {
                         current_response.is_directory = true;
                     }
                     _ => {}
                 },
-                Ok(Event::End(ref e)) => match e.name().as_ref() {
-                    b"D:response" | b"d:response" | b"response" => {
+                // <D:collection/> 是自闭合元素
+                Ok(Event::Empty(ref e)) => {
+                    if in_resourcetype && e.local_name().as_ref() == b"collection" {
+                        current_response.is_directory = true;
+                    }
+                }
+                Ok(Event::End(ref e)) => match e.local_name().as_ref() {
+                    b"response" => {
                         if in_response {
                             if let Some(file) = self.webdav_response_to_storage_file(
                                 current_response.clone(),
@@ -573,32 +810,27 @@ impl WebDAVClient {
                             in_response = false;
                         }
                     }
-                    b"D:href" | b"d:href" | b"href" => in_href = false,
-                    b"D:prop" | b"d:prop" | b"prop" => in_prop = false,
-                    b"D:resourcetype" | b"d:resourcetype" | b"resourcetype" => {
-                        in_resourcetype = false
-                    }
-                    tag if tag.ends_with(b":getcontentlength") || tag == b"getcontentlength" => {
-                        in_getcontentlength = false
-                    }
-                    tag if tag.ends_with(b":getlastmodified") || tag == b"getlastmodified" => {
-                        in_getlastmodified = false
-                    }
-                    tag if tag.ends_with(b":getcontenttype") || tag == b"getcontenttype" => {
-                        in_getcontenttype = false
-                    }
+                    b"href" => in_href = false,
+                    b"prop" => in_prop = false,
+                    b"resourcetype" => in_resourcetype = false,
+                    b"getcontentlength" => in_getcontentlength = false,
+                    b"getlastmodified" => in_getlastmodified = false,
+                    b"getcontenttype" => in_getcontenttype = false,
+                    b"getetag" => in_getetag = false,
                     _ => {}
                 },
                 Ok(Event::Text(e)) => {
                     let text = e.unescape().unwrap_or_default();
                     if in_href {
-                        current_response.href = text.to_string();
+                        current_response.href = text.trim().to_string();
                     } else if in_getcontentlength {
-                        current_response.size = text.parse().unwrap_or(0);
+                        current_response.size = text.trim().parse().unwrap_or(0);
                     } else if in_getlastmodified {
                         current_response.lastmod = text.to_string();
                     } else if in_getcontenttype {
                         current_response.content_type = Some(text.to_string());
+                    } else if in_getetag {
+                        current_response.etag = Some(text.trim_matches('"').to_string());
                     }
                 }
                 Ok(Event::Eof) => break,
@@ -624,18 +856,15 @@ impl WebDAVClient {
             return None;
         }
 
-        // 解码URL
-        let decoded_href = urlencoding::decode(&resp.href).ok()?.to_string();
+        // 解码 href，绝对 URL 形式的 href 只取路径部分
+        let decoded_href = href_path(&resp.href);
 
         // 提取文件名
-        let filename = if decoded_href.ends_with('/') {
-            // 目录
-            let path_without_slash = decoded_href.trim_end_matches('/');
-            path_without_slash.split('/').last()?.to_string()
-        } else {
-            // 文件
-            decoded_href.split('/').last()?.to_string()
-        };
+        let filename = decoded_href
+            .trim_end_matches('/')
+            .split('/')
+            .last()?
+            .to_string();
 
         // 跳过当前目录本身
         if filename.is_empty() || filename == "." {
@@ -650,17 +879,22 @@ impl WebDAVClient {
                 .map_or(false, |ct| ct == "httpd/unix-directory")
             || decoded_href.ends_with('/');
 
-        // 检查是否是当前目录本身（通过比较URL和路径）
-        let current_url_normalized = current_url.trim_end_matches('/');
+        // 比较请求路径和条目路径，判断是否是当前目录本身
+        let request_path = href_path(current_url);
+        let request_normalized = request_path.trim_end_matches('/');
         let href_normalized = decoded_href.trim_end_matches('/');
 
-        // 如果完全匹配，说明是当前目录本身
-        if href_normalized == current_url_normalized {
+        if href_normalized == request_normalized {
             return None;
         }
 
-        // 额外检查：如果文件名与当前路径的最后一部分相同，可能也是自引用
-        if let Some(current_dir_name) = current_url_normalized.split('/').last() {
+        if href_normalized.starts_with(&format!("{}/", request_normalized)) {
+            // 部分服务器忽略 Depth: 1，返回更深层的条目
+            if self.quirks.filter_depth && !is_direct_child(&request_path, &decoded_href) {
+                return None;
+            }
+        } else if let Some(current_dir_name) = request_normalized.split('/').last() {
+            // 路径前缀被反向代理改写时无法比较完整路径，退回按名称判断自引用
             if !current_dir_name.is_empty() && filename == current_dir_name && is_directory {
                 return None;
             }
@@ -673,10 +907,14 @@ impl WebDAVClient {
         };
 
         // 调整MIME类型：目录不应该有MIME类型或使用标准目录MIME
+        // 服务器未提供或只返回通用类型时按扩展名推断
         let mime = if is_directory {
             None // 目录通常不需要MIME类型
         } else {
-            resp.content_type
+            match resp.content_type {
+                Some(ct) if !ct.is_empty() && ct != DEFAULT_MIME => Some(ct),
+                _ => Some(mime_from_filename(&filename)),
+            }
         };
 
         Some(StorageFile {
@@ -690,7 +928,9 @@ impl WebDAVClient {
             }, // 目录大小为0
             file_type,
             mime,
-            etag: None,
+            etag: resp.etag,
+            storage_class: None,
+            lastmod_raw: None,
         })
     }
 
@@ -720,6 +960,48 @@ impl WebDAVClient {
         files
     }
 
+    /// 发送 MKCOL 请求，返回响应状态
+    async fn make_collection(&self, url: &str) -> Result<reqwest::StatusCode, StorageError> {
+        let response = self
+            .send_authorized(
+                &self.client,
+                reqwest::Method::from_bytes(b"MKCOL").unwrap(),
+                url,
+                &HashMap::new(),
+                None,
+            )
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
+        Ok(response.status())
+    }
+
+    /// 发送 DELETE 请求
+    async fn delete_resource(&self, url: &str) -> Result<(), StorageError> {
+        if !self.connected.load(Ordering::Relaxed) {
+            return Err(StorageError::NotConnected);
+        }
+
+        let response = self
+            .send_authorized(
+                &self.client,
+                reqwest::Method::DELETE,
+                url,
+                &HashMap::new(),
+                None,
+            )
+            .await
+            .map_err(|e| StorageError::NetworkError(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(StorageError::RequestFailed(format!(
+                "HTTP {}: {}",
+                response.status(),
+                response.status().canonical_reason().unwrap_or("Unknown")
+            )));
+        }
+        Ok(())
+    }
+
     fn parse_webdav_url(&self, webdav_url: &str) -> Result<String, StorageError> {
         // 如果已经是 http/https URL，直接返回
         if webdav_url.starts_with("http://") || webdav_url.starts_with("https://") {
@@ -774,8 +1056,12 @@ impl WebDAVClient {
         // 所有路径都应该是协议URL（统一规范）
         if path.starts_with("webdav://") || path.starts_with("webdavs://") {
             let mut url = self.parse_webdav_url(path)?;
+            // 路径中的 #、? 等字符不编码会被当作 URL 片段或查询参数
+            if self.quirks.encode_paths {
+                url = encode_url_path(&url);
+            }
             // PROPFIND 目录列举需要尾随斜杠
-            if is_directory && !url.ends_with('/') {
+            if is_directory && self.quirks.trailing_slash && !url.ends_with('/') {
                 url.push('/');
             }
             return Ok(url);
@@ -789,11 +1075,21 @@ impl WebDAVClient {
     }
 }
 
+/// 集合 URL 的父集合，已经是根路径时返回 None
+fn parent_collection(url: &str) -> Option<String> {
+    let mut parsed = url::Url::parse(url).ok()?;
+    let path = parsed.path().trim_end_matches('/').to_string();
+    let (parent, _) = path.rsplit_once('/')?;
+    parsed.set_path(&format!("{}/", parent));
+    Some(parsed.to_string())
+}
+
 #[derive(Default, Clone)]
 struct WebDAVResponse {
     href: String,
     size: u64,
     lastmod: String,
     content_type: Option<String>,
+    etag: Option<String>,
     is_directory: bool,
 }
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+/// WebDAV 服务器兼容性选项
+/// 不同服务器对 PROPFIND、Depth 和路径编码的实现存在差异，
+/// 默认值根据服务器类型选择，也可以通过连接配置的 extra_options 逐项覆盖：
+/// - webdavServer: 强制指定服务器类型（nextcloud、alist、rclone、sharepoint、generic）
+/// - webdavEncodePaths / webdavAllprop / webdavFilterDepth / webdavTrailingSlash: "true" 或 "false"
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebDAVQuirks {
+    /// 识别出的服务器类型
+    pub server: String,
+    /// 对路径中的空格、#、? 等特殊字符进行百分号编码
+    pub encode_paths: bool,
+    /// PROPFIND 使用 allprop，部分服务器对指定属性列表返回空结果
+    pub allprop: bool,
+    /// 只保留请求目录的直接子项（部分服务器忽略 Depth: 1 返回整棵树）
+    pub filter_depth: bool,
+    /// 列目录时在 URL 末尾添加斜杠
+    pub trailing_slash: bool,
+}
+
+impl Default for WebDAVQuirks {
+    fn default() -> Self {
+        Self::for_server("generic")
+    }
+}
+
+impl WebDAVQuirks {
+    /// 获取指定服务器类型的默认兼容性选项
+    pub fn for_server(server: &str) -> Self {
+        let server = server.to_lowercase();
+        let mut quirks = Self {
+            server: server.clone(),
+            encode_paths: true,
+            allprop: false,
+            filter_depth: true,
+            trailing_slash: true,
+        };
+
+        // SharePoint 的自定义属性不在 DAV: 命名空间，指定属性列表时经常缺失
+        if server == "sharepoint" {
+            quirks.allprop = true;
+        }
+
+        quirks
+    }
+
+    /// 根据连接 URL 和服务器响应头推断服务器类型
+    pub fn detect_server(url: &str, headers: &HashMap<String, String>) -> String {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.to_lowercase())
+        };
+
+        if header("microsoftsharepointteamservices").is_some() || header("sprequestguid").is_some()
+        {
+            return "sharepoint".to_string();
+        }
+
+        let server_header = header("server").unwrap_or_default();
+        if server_header.contains("rclone") {
+            return "rclone".to_string();
+        }
+        if server_header.contains("alist") {
+            return "alist".to_string();
+        }
+
+        let lower_url = url.to_lowercase();
+        if lower_url.contains("/remote.php/dav") || lower_url.contains("/remote.php/webdav") {
+            return "nextcloud".to_string();
+        }
+        if lower_url.trim_end_matches('/').ends_with("/dav") {
+            return "alist".to_string();
+        }
+
+        "generic".to_string()
+    }
+
+    /// 解析连接配置中的兼容性选项
+    /// detected 为自动识别出的服务器类型，webdavServer 选项优先
+    pub fn from_options(detected: &str, options: Option<&HashMap<String, String>>) -> Self {
+        let Some(options) = options else {
+            return Self::for_server(detected);
+        };
+
+        let server = options
+            .get("webdavServer")
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|| detected.to_string());
+        let mut quirks = Self::for_server(&server);
+
+        let flag = |key: &str| match options.get(key).map(|v| v.trim().to_lowercase()) {
+            Some(v) if v == "true" || v == "1" => Some(true),
+            Some(v) if v == "false" || v == "0" => Some(false),
+            _ => None,
+        };
+
+        if let Some(value) = flag("webdavEncodePaths") {
+            quirks.encode_paths = value;
+        }
+        if let Some(value) = flag("webdavAllprop") {
+            quirks.allprop = value;
+        }
+        if let Some(value) = flag("webdavFilterDepth") {
+            quirks.filter_depth = value;
+        }
+        if let Some(value) = flag("webdavTrailingSlash") {
+            quirks.trailing_slash = value;
+        }
+
+        quirks
+    }
+}
+
+/// 对 URL 的路径部分进行百分号编码
+/// 已经编码的 %XX 序列保持不变，避免重复编码
+pub fn encode_url_path(url: &str) -> String {
+    let path_start = url
+        .find("://")
+        .and_then(|i| url[i + 3..].find('/').map(|p| i + 3 + p))
+        .unwrap_or(url.len());
+    let (origin, path) = url.split_at(path_start);
+
+    let bytes = path.as_bytes();
+    let mut encoded = String::with_capacity(path.len());
+    for (i, &b) in bytes.iter().enumerate() {
+        let is_escape = b == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit();
+
+        if b.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/".contains(&b) || is_escape {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    format!("{}{}", origin, encoded)
+}
+
+/// 提取 href 的路径部分并解码
+/// 服务器可能返回绝对 URL（http://host/dav/a）或绝对路径（/dav/a）
+pub fn href_path(href: &str) -> String {
+    let path = match href.find("://") {
+        Some(i) => match href[i + 3..].find('/') {
+            Some(p) => &href[i + 3 + p..],
+            None => "/",
+        },
+        None => href,
+    };
+
+    // 部分服务器返回未编码的 href，解码失败时按原样使用
+    urlencoding::decode(path)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// 判断条目是否为请求目录的直接子项（目录本身返回 false）
+/// 条目不在请求目录下（如反向代理改写了路径前缀）时无法判断，返回 true
+pub fn is_direct_child(request_path: &str, entry_path: &str) -> bool {
+    let parent = request_path.trim_end_matches('/');
+    let entry = entry_path.trim_end_matches('/');
+
+    match entry.strip_prefix(parent) {
+        Some(rest) => rest
+            .strip_prefix('/')
+            .is_some_and(|name| !name.is_empty() && !name.contains('/')),
+        None => true,
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+/// 应用数据目录标识，与插件安装目录保持一致
+const APP_IDENTIFIER: &str = "ai.stardust.dataset-viewer";
+
+/// 获取应用数据目录下的子目录，不存在时自动创建
+///
+/// # 示例
+/// ```rust
+/// // get_app_data_subdir("rename-journals")
+/// // -> "~/Library/Application Support/ai.stardust.dataset-viewer/rename-journals"
+/// ```
+pub fn get_app_data_subdir(name: &str) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("Failed to get app data directory")?
+        .join(APP_IDENTIFIER)
+        .join(name);
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(dir)
+}
@@ -11,6 +11,15 @@ pub fn sha256_hex(data: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// SHA256 哈希函数（字节数组）
+/// 用于计算请求体哈希
+pub fn sha256_hex_bytes(data: &[u8]) -> String {
+    use sha2::Digest;
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
 /// HMAC-SHA1 计算函数（返回base64编码）
 /// 用于阿里云OSS、华为OBS等标准OSS签名
 pub fn hmac_sha1_base64(key: &str, data: &str) -> String {
@@ -0,0 +1,247 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+
+use crate::storage::traits::ConnectionConfig;
+
+/// HTTP 认证方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuthScheme {
+    None,
+    Basic,
+    Digest,
+    Bearer,
+}
+
+impl AuthScheme {
+    /// 从连接配置解析认证方式
+    /// 未指定时保持原有行为：提供了用户名和密码则使用 Basic
+    pub fn from_config(config: &ConnectionConfig) -> Result<Self, String> {
+        match config
+            .auth_scheme
+            .as_deref()
+            .map(|s| s.trim().to_lowercase())
+        {
+            Some(scheme) if scheme.is_empty() => Ok(Self::infer(config)),
+            Some(scheme) => match scheme.as_str() {
+                "none" => Ok(Self::None),
+                "basic" => Ok(Self::Basic),
+                "digest" => Ok(Self::Digest),
+                "bearer" => Ok(Self::Bearer),
+                other => Err(format!("Unsupported auth scheme: {}", other)),
+            },
+            None => Ok(Self::infer(config)),
+        }
+    }
+
+    fn infer(config: &ConnectionConfig) -> Self {
+        if config.username.is_some() && config.password.is_some() {
+            Self::Basic
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// 服务器下发的 Digest 质询（WWW-Authenticate: Digest ...）
+#[derive(Debug, Clone, Default)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: Option<String>,
+    pub qop: Option<String>,
+}
+
+/// 解析 WWW-Authenticate 头中的 Digest 质询
+/// 服务器可能同时返回多个质询，只取 Digest 部分
+pub fn parse_digest_challenge(header: &str) -> Option<DigestChallenge> {
+    let start = header.to_ascii_lowercase().find("digest ")?;
+    let params = &header[start + 7..];
+
+    let mut challenge = DigestChallenge::default();
+    let mut rest = params.trim_start();
+
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_ascii_lowercase();
+        // 遇到下一个认证方案（如 "Basic realm=..."）时停止
+        if key.contains(char::is_whitespace) {
+            break;
+        }
+
+        let after = rest[eq + 1..].trim_start();
+        let (value, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            (&quoted[..end], &quoted[end + 1..])
+        } else {
+            let end = after.find(',').unwrap_or(after.len());
+            (after[..end].trim(), &after[end..])
+        };
+
+        match key.as_str() {
+            "realm" => challenge.realm = value.to_string(),
+            "nonce" => challenge.nonce = value.to_string(),
+            "opaque" => challenge.opaque = Some(value.to_string()),
+            "algorithm" => challenge.algorithm = Some(value.to_string()),
+            "qop" => challenge.qop = Some(value.to_string()),
+            _ => {}
+        }
+
+        rest = remaining.trim_start().trim_start_matches(',').trim_start();
+    }
+
+    if challenge.nonce.is_empty() {
+        return None;
+    }
+
+    Some(challenge)
+}
+
+/// 每个连接的 HTTP 认证状态
+/// Basic 和 Bearer 直接生成固定头，Digest 需要先收到服务器质询再按请求计算
+pub struct HttpAuth {
+    scheme: AuthScheme,
+    username: String,
+    password: String,
+    digest: Mutex<Option<(DigestChallenge, u32)>>,
+}
+
+impl HttpAuth {
+    pub fn new(config: &ConnectionConfig) -> Result<Self, String> {
+        let scheme = AuthScheme::from_config(config)?;
+        let username = config.username.clone().unwrap_or_default();
+        let password = config.password.clone().unwrap_or_default();
+
+        if scheme == AuthScheme::Bearer && password.is_empty() {
+            return Err("Bearer auth requires a token in the password field".to_string());
+        }
+
+        Ok(Self {
+            scheme,
+            username,
+            password,
+            digest: Mutex::new(None),
+        })
+    }
+
+    /// 生成请求的 Authorization 头
+    /// Digest 在收到首个质询前返回 None
+    pub fn authorization(&self, method: &str, url: &str) -> Option<String> {
+        match self.scheme {
+            AuthScheme::None => None,
+            AuthScheme::Basic => {
+                let credentials = general_purpose::STANDARD
+                    .encode(format!("{}:{}", self.username, self.password));
+                Some(format!("Basic {}", credentials))
+            }
+            AuthScheme::Bearer => Some(format!("Bearer {}", self.password)),
+            AuthScheme::Digest => {
+                let mut guard = self.digest.lock().ok()?;
+                let (challenge, nonce_count) = guard.as_mut()?;
+                *nonce_count += 1;
+                Some(self.digest_authorization(challenge, *nonce_count, method, url))
+            }
+        }
+    }
+
+    /// 处理 401 响应中的质询，返回是否应该重试请求
+    pub fn handle_challenge(&self, www_authenticate: &str) -> bool {
+        if self.scheme != AuthScheme::Digest {
+            return false;
+        }
+
+        let Some(challenge) = parse_digest_challenge(www_authenticate) else {
+            return false;
+        };
+
+        if let Ok(mut guard) = self.digest.lock() {
+            // 同一 nonce 再次被拒绝说明凭据错误，不再重试
+            let same_nonce = guard
+                .as_ref()
+                .is_some_and(|(current, _)| current.nonce == challenge.nonce)
+                && !www_authenticate.to_ascii_lowercase().contains("stale=true");
+            if same_nonce {
+                return false;
+            }
+            *guard = Some((challenge, 0));
+            return true;
+        }
+
+        false
+    }
+
+    /// 按 RFC 7616 计算 Digest 响应，支持 MD5、MD5-sess、SHA-256 及 qop=auth
+    fn digest_authorization(
+        &self,
+        challenge: &DigestChallenge,
+        nonce_count: u32,
+        method: &str,
+        url: &str,
+    ) -> String {
+        let algorithm = challenge
+            .algorithm
+            .clone()
+            .unwrap_or_else(|| "MD5".to_string());
+        let upper_algorithm = algorithm.to_uppercase();
+        let hash = |data: String| -> String {
+            if upper_algorithm.starts_with("SHA-256") {
+                hex::encode(Sha256::digest(data.as_bytes()))
+            } else {
+                hex::encode(Md5::digest(data.as_bytes()))
+            }
+        };
+
+        let uri = request_target(url);
+        let cnonce = uuid::Uuid::new_v4().simple().to_string();
+        let nc = format!("{:08x}", nonce_count);
+        let qop = challenge.qop.as_ref().and_then(|q| {
+            q.split(',')
+                .map(|s| s.trim())
+                .find(|s| s.eq_ignore_ascii_case("auth"))
+                .map(|s| s.to_string())
+        });
+
+        let mut ha1 = hash(format!(
+            "{}:{}:{}",
+            self.username, challenge.realm, self.password
+        ));
+        if upper_algorithm.ends_with("-SESS") {
+            ha1 = hash(format!("{}:{}:{}", ha1, challenge.nonce, cnonce));
+        }
+        let ha2 = hash(format!("{}:{}", method, uri));
+
+        let response = match &qop {
+            Some(qop) => hash(format!(
+                "{}:{}:{}:{}:{}:{}",
+                ha1, challenge.nonce, nc, cnonce, qop, ha2
+            )),
+            None => hash(format!("{}:{}:{}", ha1, challenge.nonce, ha2)),
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            self.username, challenge.realm, challenge.nonce, uri, algorithm, response
+        );
+        if let Some(qop) = qop {
+            header.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce));
+        }
+        if let Some(opaque) = &challenge.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+
+        header
+    }
+}
+
+/// 提取请求目标（路径和查询字符串），用作 Digest 的 uri 参数
+fn request_target(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
@@ -1,9 +1,9 @@
-use futures_util::StreamExt;
 use reqwest::Client;
 use std::collections::HashMap;
 use tokio::io::AsyncWriteExt;
 
 use crate::storage::traits::{ProgressCallback, StorageError};
+use crate::utils::http_guard::next_chunk;
 
 /// HTTP下载配置
 #[derive(Debug, Clone)]
@@ -93,22 +93,34 @@ impl HttpDownloader {
         // 开始流式下载
         let mut stream = response.bytes_stream();
         let mut downloaded = 0u64;
-
-        while let Some(chunk_result) = stream.next().await {
-            // 检查取消信号
-            if let Some(ref mut cancel_rx) = cancel_rx {
-                if cancel_rx.try_recv().is_ok() {
-                    // 删除部分下载的文件
-                    let _ = tokio::fs::remove_file(save_path).await;
-                    return Err(StorageError::RequestFailed(
-                        "download.cancelled".to_string(),
-                    ));
-                }
-            }
-
-            // 处理chunk
-            let bytes = chunk_result
-                .map_err(|e| StorageError::NetworkError(format!("Stream error: {}", e)))?;
+        let mut cancellable = true;
+
+        loop {
+            // 等待下一块数据，同时监听取消信号，长时间没有数据时结束下载
+            let chunk = match cancel_rx.as_mut().filter(|_| cancellable) {
+                Some(cancel_rx) => tokio::select! {
+                    chunk = next_chunk(&mut stream) => chunk,
+                    signal = cancel_rx.recv() => match signal {
+                        // 发送端已关闭表示没有取消来源，继续下载
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                            cancellable = false;
+                            continue;
+                        }
+                        _ => {
+                            // 删除部分下载的文件
+                            let _ = tokio::fs::remove_file(save_path).await;
+                            return Err(StorageError::RequestFailed(
+                                "download.cancelled".to_string(),
+                            ));
+                        }
+                    },
+                },
+                None => next_chunk(&mut stream).await,
+            };
+            let bytes = match chunk.map_err(StorageError::NetworkError)? {
+                Some(bytes) => bytes,
+                None => break,
+            };
 
             // 写入文件
             file.write_all(&bytes)
@@ -0,0 +1,90 @@
+// 网络响应读取保护
+// 读取响应体时限制总大小，并要求持续收到数据：服务器返回超出预期的数据时立即停止，
+// 连续一段时间没有收到任何数据时视为连接卡住并结束读取，避免内存无限增长或操作永久挂起
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// 连续没有收到数据的最长时间，超过后结束读取
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// JSON、XML 列表等元数据响应的大小上限
+pub const MAX_METADATA_BYTES: u64 = 64 * 1024 * 1024; // 64MB
+
+/// 一次读入内存的完整文件的大小上限，更大的文件应使用范围读取或下载
+pub const MAX_FULL_FILE_BYTES: u64 = 1024 * 1024 * 1024; // 1GB
+
+/// 错误响应体最多读取的大小，只用于错误信息
+const MAX_ERROR_BODY_BYTES: u64 = 64 * 1024; // 64KB
+
+/// 读取下一块数据，超过 STALL_TIMEOUT 没有收到数据时返回错误
+pub async fn next_chunk<S>(stream: &mut S) -> Result<Option<Bytes>, String>
+where
+    S: Stream<Item = reqwest::Result<Bytes>> + Unpin,
+{
+    match tokio::time::timeout(STALL_TIMEOUT, stream.next()).await {
+        Err(_) => Err(format!(
+            "No data received for {} seconds",
+            STALL_TIMEOUT.as_secs()
+        )),
+        Ok(None) => Ok(None),
+        Ok(Some(Err(e))) => Err(format!("Failed to read chunk: {}", e)),
+        Ok(Some(Ok(chunk))) => Ok(Some(chunk)),
+    }
+}
+
+/// 读取完整响应体，超过 limit 字节时返回错误
+pub async fn read_body(response: reqwest::Response, limit: u64) -> Result<Vec<u8>, String> {
+    let content_length = response.content_length();
+    if let Some(length) = content_length.filter(|length| *length > limit) {
+        return Err(too_large(length, limit));
+    }
+
+    let mut body = Vec::with_capacity(content_length.unwrap_or(0) as usize);
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = next_chunk(&mut stream).await? {
+        if body.len() as u64 + chunk.len() as u64 > limit {
+            return Err(too_large(body.len() as u64 + chunk.len() as u64, limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// 读取文本响应体（按 UTF-8 解码）
+pub async fn read_text(response: reqwest::Response, limit: u64) -> Result<String, String> {
+    let body = read_body(response, limit).await?;
+    String::from_utf8(body).map_err(|e| format!("Response is not valid UTF-8: {}", e))
+}
+
+/// 读取并解析 JSON 响应体
+pub async fn read_json<T: DeserializeOwned>(
+    response: reqwest::Response,
+    limit: u64,
+) -> Result<T, String> {
+    let body = read_body(response, limit).await?;
+    serde_json::from_slice(&body).map_err(|e| format!("Failed to parse JSON response: {}", e))
+}
+
+/// 读取错误响应体用于错误信息，只读取开头部分，读取失败时返回已读到的内容
+pub async fn error_body(response: reqwest::Response) -> String {
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Ok(Some(chunk)) = next_chunk(&mut stream).await {
+        body.extend_from_slice(&chunk);
+        if body.len() as u64 >= MAX_ERROR_BODY_BYTES {
+            body.truncate(MAX_ERROR_BODY_BYTES as usize);
+            break;
+        }
+    }
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+fn too_large(size: u64, limit: u64) -> String {
+    format!(
+        "Response is too large ({} bytes, limit {} bytes)",
+        size, limit
+    )
+}
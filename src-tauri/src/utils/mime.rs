@@ -0,0 +1,218 @@
+/// 魔数检测需要读取的文件头长度（tar 的 ustar 标记位于 257 字节处）
+pub const MAGIC_SNIFF_LEN: u64 = 512;
+
+/// 未知类型的默认 MIME
+pub const DEFAULT_MIME: &str = "application/octet-stream";
+
+/// 根据文件扩展名获取 MIME 类型，未知扩展名返回 None
+pub fn mime_from_extension(filename: &str) -> Option<&'static str> {
+    let name = filename.rsplit('/').next().unwrap_or(filename);
+    let (_, ext) = name.rsplit_once('.')?;
+
+    let mime = match ext.to_lowercase().as_str() {
+        // 文本
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "js" | "mjs" => "application/javascript",
+        "ts" => "application/typescript",
+        "py" => "text/x-python",
+        "rs" => "text/x-rust",
+        "sh" => "application/x-sh",
+        // 结构化数据
+        "json" => "application/json",
+        "jsonl" | "ndjson" => "application/jsonlines",
+        "parquet" => "application/vnd.apache.parquet",
+        "arrow" | "feather" => "application/vnd.apache.arrow.file",
+        "avro" => "application/avro",
+        "orc" => "application/x-orc",
+        "h5" | "hdf5" => "application/x-hdf5",
+        "npy" | "npz" => "application/x-numpy",
+        "sqlite" | "db" => "application/vnd.sqlite3",
+        "pdf" => "application/pdf",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "xls" => "application/vnd.ms-excel",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        // 图片
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "avif" => "image/avif",
+        // 音视频
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "ogg" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "avi" => "video/x-msvideo",
+        // 点云
+        "pcd" => "application/x-pcd",
+        "ply" => "application/x-ply",
+        "las" | "laz" => "application/vnd.las",
+        // 压缩包
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        "gz" | "tgz" => "application/gzip",
+        "bz2" => "application/x-bzip2",
+        "xz" => "application/x-xz",
+        "zst" => "application/zstd",
+        "lz4" => "application/x-lz4",
+        "br" => "application/x-brotli",
+        "7z" => "application/x-7z-compressed",
+        "rar" => "application/vnd.rar",
+        _ => return None,
+    };
+
+    Some(mime)
+}
+
+/// 根据文件头魔数识别 MIME 类型，无法识别时返回 None
+pub fn mime_from_magic(data: &[u8]) -> Option<&'static str> {
+    let starts = |magic: &[u8]| data.starts_with(magic);
+
+    let mime = if starts(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if starts(b"\xff\xd8\xff") {
+        "image/jpeg"
+    } else if starts(b"GIF87a") || starts(b"GIF89a") {
+        "image/gif"
+    } else if starts(b"RIFF") && data.get(8..12) == Some(&b"WEBP"[..]) {
+        "image/webp"
+    } else if starts(b"RIFF") && data.get(8..12) == Some(&b"WAVE"[..]) {
+        "audio/wav"
+    } else if starts(b"II*\x00") || starts(b"MM\x00*") {
+        "image/tiff"
+    } else if starts(b"%PDF-") {
+        "application/pdf"
+    } else if starts(b"PAR1") {
+        "application/vnd.apache.parquet"
+    } else if starts(b"ARROW1") {
+        "application/vnd.apache.arrow.file"
+    } else if starts(b"\x93NUMPY") {
+        "application/x-numpy"
+    } else if starts(b"\x89HDF\r\n\x1a\n") {
+        "application/x-hdf5"
+    } else if starts(b"SQLite format 3\x00") {
+        "application/vnd.sqlite3"
+    } else if starts(b"Obj\x01") {
+        "application/avro"
+    } else if starts(b"PK\x03\x04") || starts(b"PK\x05\x06") {
+        "application/zip"
+    } else if starts(b"\x1f\x8b") {
+        "application/gzip"
+    } else if starts(b"BZh") {
+        "application/x-bzip2"
+    } else if starts(b"\xfd7zXZ\x00") {
+        "application/x-xz"
+    } else if starts(b"\x28\xb5\x2f\xfd") {
+        "application/zstd"
+    } else if starts(b"\x04\x22\x4d\x18") {
+        "application/x-lz4"
+    } else if starts(b"7z\xbc\xaf\x27\x1c") {
+        "application/x-7z-compressed"
+    } else if starts(b"Rar!\x1a\x07") {
+        "application/vnd.rar"
+    } else if data.get(257..262) == Some(&b"ustar"[..]) {
+        "application/x-tar"
+    } else if data.get(4..8) == Some(&b"ftyp"[..]) {
+        "video/mp4"
+    } else if starts(b"\x1a\x45\xdf\xa3") {
+        "video/x-matroska"
+    } else if starts(b"ID3") || starts(b"\xff\xfb") {
+        "audio/mpeg"
+    } else if starts(b"fLaC") {
+        "audio/flac"
+    } else if starts(b"OggS") {
+        "audio/ogg"
+    } else {
+        return None;
+    };
+
+    Some(mime)
+}
+
+/// 检测 MIME 类型：优先使用扩展名，无法识别时根据文件头魔数判断，
+/// 仍无法识别时按内容是否为文本返回 text/plain 或默认类型
+pub fn detect_mime(filename: &str, head: Option<&[u8]>) -> String {
+    if let Some(mime) = mime_from_extension(filename) {
+        return mime.to_string();
+    }
+
+    match head {
+        Some(data) => mime_from_magic(data)
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| {
+                if is_text_content(data) {
+                    "text/plain".to_string()
+                } else {
+                    DEFAULT_MIME.to_string()
+                }
+            }),
+        None => DEFAULT_MIME.to_string(),
+    }
+}
+
+/// 列表场景下的 MIME 类型（只根据扩展名，不读取文件内容）
+pub fn mime_from_filename(filename: &str) -> String {
+    mime_from_extension(filename)
+        .unwrap_or(DEFAULT_MIME)
+        .to_string()
+}
+
+/// 二进制内容检测读取的数据长度
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// 数据开头含有 NUL 字节时视为二进制内容，搜索、扫描和索引据此跳过文本格式以外的文件
+pub fn is_binary_content(data: &[u8]) -> bool {
+    data[..data.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+/// 检查是否为文本内容
+pub fn is_text_content(data: &[u8]) -> bool {
+    if data.is_empty() {
+        return true;
+    }
+
+    // 检查前1024字节或全部数据
+    let check_len = data.len().min(1024);
+    let sample = &data[0..check_len];
+
+    // 统计非文本字符数量
+    let mut non_text_count = 0;
+    let mut total_checked = 0;
+
+    for &byte in sample {
+        total_checked += 1;
+
+        // 允许的文本字符：
+        // - ASCII 可打印字符 (32-126)
+        // - 常见空白字符 (9, 10, 13)
+        // - UTF-8 序列起始字节 (128-255)
+        if !(32..=126).contains(&byte) && // 可打印ASCII
+           ![9, 10, 13].contains(&byte) && // 制表符、换行符、回车符
+           byte < 128
+        {
+            // 非UTF-8起始字节
+            non_text_count += 1;
+        }
+    }
+
+    // 如果非文本字符比例小于10%，认为是文本
+    (non_text_count as f64 / total_checked as f64) < 0.1
+}
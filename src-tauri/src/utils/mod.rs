@@ -1,5 +1,18 @@
+pub mod app_paths;
 pub mod chunk_size;
 pub mod crypto;
+pub mod http_auth;
 pub mod http_downloader;
+pub mod http_guard;
+pub mod mime;
+pub mod natural_sort;
+pub mod network;
 pub mod path_utils;
+pub mod persist;
 pub mod protocol_handler;
+pub mod result_limit;
+pub mod retention;
+pub mod self_check;
+pub mod timestamp;
+pub mod viewer_tabs;
+pub mod window_state;
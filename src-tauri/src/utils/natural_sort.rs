@@ -0,0 +1,56 @@
+use std::cmp::Ordering;
+
+/// 自然排序比较：连续数字按数值比较（shard2 < shard10），其余字符忽略大小写比较
+/// 数值相同但前导零不同时（01 与 1），前导零较少的排在前面
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => break,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let a_num = take_digits(&mut a_chars);
+                let b_num = take_digits(&mut b_chars);
+                let ordering = compare_digits(&a_num, &b_num);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                let ordering = ca.to_lowercase().cmp(cb.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+
+    // 忽略大小写后相同时按原始字符串比较，保证排序稳定
+    a.cmp(b)
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_digit()) {
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
+
+/// 比较两段数字，不转换为整数以支持任意长度
+fn compare_digits(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+
+    a_trimmed
+        .len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+        .then_with(|| a.len().cmp(&b.len()))
+}
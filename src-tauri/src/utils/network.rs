@@ -0,0 +1,182 @@
+// 全局网络配置
+// HTTP / HTTPS / SOCKS5 代理（支持认证）和自定义 CA 证书，
+// 统一应用到存储、下载和插件安装使用的所有 HTTP 客户端，便于在企业防火墙后使用
+
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+use crate::connections::keychain;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 代理密码在系统钥匙串中的账号
+const PROXY_PASSWORD_ACCOUNT: &str = "network:proxy";
+
+static NETWORK_CONFIG: LazyLock<RwLock<NetworkConfig>> =
+    LazyLock::new(|| RwLock::new(NetworkConfig::load()));
+
+/// 代理类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ProxyKind {
+    Http,
+    Https,
+    Socks5,
+}
+
+/// 代理设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    /// 代理密码，只在保存时传入并写入系统钥匙串，不会保存到配置文件或返回给前端；
+    /// 为空时保留已保存的密码，空字符串清除密码
+    pub password: Option<String>,
+    /// 钥匙串中是否已保存密码
+    #[serde(default)]
+    pub password_stored: bool,
+    /// 不经过代理的主机（如 "localhost"、".corp.example.com"）
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+/// 全局网络配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkConfig {
+    /// 为空时使用系统环境变量（HTTP_PROXY 等）中的代理
+    pub proxy: Option<ProxyConfig>,
+    /// 额外信任的 CA 证书文件（PEM 或 DER）
+    pub ca_cert_paths: Vec<String>,
+}
+
+impl NetworkConfig {
+    fn config_file() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join("network.json"))
+    }
+
+    fn load() -> Self {
+        Self::config_file()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+}
+
+/// 获取当前的网络配置（不含代理密码）
+pub fn current() -> NetworkConfig {
+    NETWORK_CONFIG
+        .read()
+        .map(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// 校验并保存网络配置，代理密码写入系统钥匙串
+/// 先校验证书和代理地址，校验失败时不修改钥匙串中已保存的密码；
+/// 调用方需重新创建 HTTP 客户端，新配置才会生效
+pub fn set(mut config: NetworkConfig) -> Result<NetworkConfig, String> {
+    for path in &config.ca_cert_paths {
+        load_certificates(path)?;
+    }
+    if let Some(proxy) = config.proxy.as_mut() {
+        if proxy.host.trim().is_empty() {
+            return Err("Proxy host is required".to_string());
+        }
+        proxy.host = proxy.host.trim().to_string();
+        // 校验代理地址，避免保存后所有客户端都无法创建
+        build_proxy(proxy, "")?;
+    }
+
+    match config.proxy.as_mut() {
+        Some(proxy) => {
+            match proxy.password.take() {
+                Some(password) if password.is_empty() => {
+                    keychain::delete_secret(PROXY_PASSWORD_ACCOUNT)?
+                }
+                Some(password) => keychain::save_secret(PROXY_PASSWORD_ACCOUNT, &password)?,
+                None => {}
+            }
+            proxy.password_stored = keychain::load_secret(PROXY_PASSWORD_ACCOUNT)?.is_some();
+        }
+        None => keychain::delete_secret(PROXY_PASSWORD_ACCOUNT)?,
+    }
+
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize network config: {}", e))?;
+    persist::write_atomic(NetworkConfig::config_file()?, content)
+        .map_err(|e| format!("Failed to save network config: {}", e))?;
+
+    let mut current = NETWORK_CONFIG
+        .write()
+        .map_err(|e| format!("Failed to lock network config: {}", e))?;
+    *current = config.clone();
+    Ok(config)
+}
+
+/// 把代理和 CA 证书应用到 HTTP 客户端构建器
+/// 保存时已校验过配置，之后证书文件被删除等情况只记录警告并跳过，不影响创建客户端
+pub fn configure(mut builder: ClientBuilder) -> ClientBuilder {
+    let config = current();
+    if let Some(proxy) = &config.proxy {
+        match proxy_password(proxy).and_then(|password| build_proxy(proxy, &password)) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => log::warn!("代理配置无效，已忽略: {}", e),
+        }
+    }
+    for path in &config.ca_cert_paths {
+        match load_certificates(path) {
+            Ok(certificates) => {
+                for certificate in certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+            Err(e) => log::warn!("CA 证书无效，已忽略: {}", e),
+        }
+    }
+    builder
+}
+
+/// 钥匙串中保存的代理密码，未保存时为空
+fn proxy_password(config: &ProxyConfig) -> Result<String, String> {
+    if config.password_stored {
+        Ok(keychain::load_secret(PROXY_PASSWORD_ACCOUNT)?.unwrap_or_default())
+    } else {
+        Ok(String::new())
+    }
+}
+
+fn build_proxy(config: &ProxyConfig, password: &str) -> Result<Proxy, String> {
+    let scheme = match config.kind {
+        ProxyKind::Http => "http",
+        ProxyKind::Https => "https",
+        // socks5h：由代理服务器解析域名，内网域名在本机可能无法解析
+        ProxyKind::Socks5 => "socks5h",
+    };
+    let url = format!("{}://{}:{}", scheme, config.host, config.port);
+    let mut proxy = Proxy::all(&url).map_err(|e| format!("Invalid proxy {}: {}", url, e))?;
+
+    if let Some(username) = config.username.as_deref().filter(|u| !u.is_empty()) {
+        proxy = proxy.basic_auth(username, password);
+    }
+    if !config.no_proxy.is_empty() {
+        proxy = proxy.no_proxy(NoProxy::from_string(&config.no_proxy.join(",")));
+    }
+    Ok(proxy)
+}
+
+/// 读取 CA 证书文件中的所有证书，先按 PEM 证书包解析，没有 PEM 证书时按单个 DER 证书解析
+fn load_certificates(path: &str) -> Result<Vec<Certificate>, String> {
+    let data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read CA certificate {}: {}", path, e))?;
+    match Certificate::from_pem_bundle(&data) {
+        Ok(certificates) if !certificates.is_empty() => Ok(certificates),
+        _ => Certificate::from_der(&data)
+            .map(|certificate| vec![certificate])
+            .map_err(|e| format!("Invalid CA certificate {}: {}", path, e)),
+    }
+}
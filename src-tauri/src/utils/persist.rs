@@ -0,0 +1,64 @@
+// 状态文件的原子写入与损坏回退
+// 先写入同目录的临时文件并 fsync，再 rename 覆盖目标文件，写入中途崩溃或断电不会留下截断的文件；
+// 读取时目标文件无法解析则回退到启动自检保存的备份
+
+use serde::de::DeserializeOwned;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 备份文件的扩展名，由启动自检在文件检查通过时更新
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// 原子写入文件，用法与 std::fs::write 相同
+pub fn write_atomic(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let temp = sibling_path(path, &format!("tmp-{}", uuid::Uuid::new_v4().simple()));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(data.as_ref())?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&temp, path)
+    })();
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    // 同步目录项，确保 rename 本身落盘（Windows 不支持打开目录）
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// 读取并解析 JSON 状态文件，文件损坏时回退到备份
+/// 文件不存在或文件和备份都无法解析时返回 None
+pub fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> Option<T> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).ok()?;
+    match serde_json::from_slice(&data) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("状态文件损坏，尝试使用备份 {}: {}", path.display(), e);
+            let backup = std::fs::read(backup_path(path)).ok()?;
+            serde_json::from_slice(&backup).ok()
+        }
+    }
+}
+
+/// 状态文件的备份路径（state.json -> state.json.bak）
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling_path(path, BACKUP_EXTENSION)
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
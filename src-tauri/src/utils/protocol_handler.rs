@@ -1,4 +1,5 @@
 use crate::archive::handlers::ArchiveHandler;
+use crate::archive::types::FilePreview;
 use crate::storage::manager::StorageManager;
 use crate::storage::traits::StorageClient;
 use std::sync::Arc;
@@ -9,7 +10,7 @@ pub struct ProtocolHandler;
 impl ProtocolHandler {
     /// 创建带有默认 HTTP 版本的 Response Builder
     /// Windows 平台要求明确指定 HTTP 版本
-    fn response_builder() -> tauri::http::response::Builder {
+    pub fn response_builder() -> tauri::http::response::Builder {
         tauri::http::Response::builder().version(tauri::http::Version::HTTP_11)
     }
 
@@ -91,11 +92,16 @@ impl ProtocolHandler {
             Some("otf") => "font/otf",
             Some("webp") => "image/webp",
             Some("avif") => "image/avif",
-            Some("mp4") => "video/mp4",
+            Some("mp4") | Some("m4v") => "video/mp4",
             Some("webm") => "video/webm",
+            Some("mov") => "video/quicktime",
+            Some("mkv") => "video/x-matroska",
             Some("mp3") => "audio/mpeg",
+            Some("m4a") => "audio/mp4",
+            Some("aac") => "audio/aac",
+            Some("flac") => "audio/flac",
             Some("wav") => "audio/wav",
-            Some("ogg") => "audio/ogg",
+            Some("ogg") | Some("opus") => "audio/ogg",
             Some("wasm") => "application/wasm", // WebAssembly
             _ => "application/octet-stream",
         }
@@ -354,10 +360,12 @@ impl ProtocolHandler {
     }
 
     /// 处理压缩包内文件请求
-    /// 支持标准HTTP Range头进行分块读取
+    /// 支持标准HTTP Range头进行分块读取；文本内容的编码通过 X-Text-Encoding 头返回，
+    /// 请求中指定 encoding 参数时以该编码为准
     pub async fn handle_archive_file_request(
         archive_url: String,
         entry_path: String,
+        encoding: Option<String>,
         method: String,
         headers: tauri::http::HeaderMap,
         responder: tauri::UriSchemeResponder,
@@ -398,6 +406,7 @@ impl ProtocolHandler {
                         &archive_handler,
                         &archive_path,
                         &entry_path,
+                        encoding.as_deref(),
                         headers,
                         responder,
                     )
@@ -466,6 +475,7 @@ impl ProtocolHandler {
         archive_handler: &ArchiveHandler,
         archive_path: &str,
         entry_path: &str,
+        encoding: Option<&str>,
         headers: tauri::http::HeaderMap,
         responder: tauri::UriSchemeResponder,
     ) {
@@ -505,10 +515,14 @@ impl ProtocolHandler {
                                 .header("Access-Control-Allow-Headers", "Range, Content-Type")
                                 .header(
                                     "Access-Control-Expose-Headers",
-                                    "Content-Length, Content-Range, Accept-Ranges",
+                                    "Content-Length, Content-Range, Accept-Ranges, X-Text-Encoding",
                                 )
                                 .status(206)
                                 .header("Content-Type", Self::get_content_type(entry_path))
+                                .header(
+                                    "X-Text-Encoding",
+                                    Self::text_encoding(&preview, encoding).unwrap_or_default(),
+                                )
                                 .header("Content-Length", preview.content.len().to_string())
                                 .header(
                                     "Content-Range",
@@ -568,10 +582,14 @@ impl ProtocolHandler {
                         .header("Access-Control-Allow-Headers", "Range, Content-Type")
                         .header(
                             "Access-Control-Expose-Headers",
-                            "Content-Length, Accept-Ranges",
+                            "Content-Length, Accept-Ranges, X-Text-Encoding",
                         )
                         .status(200)
                         .header("Content-Type", Self::get_content_type(entry_path))
+                        .header(
+                            "X-Text-Encoding",
+                            Self::text_encoding(&preview, encoding).unwrap_or_default(),
+                        )
                         .header("Content-Length", preview.content.len().to_string())
                         .header("Accept-Ranges", "bytes")
                         .body(preview.content.to_vec())
@@ -585,6 +603,14 @@ impl ProtocolHandler {
         }
     }
 
+    /// 压缩包内文本条目的编码：请求指定的编码优先，否则使用预览时识别出的编码
+    fn text_encoding(preview: &FilePreview, encoding: Option<&str>) -> Option<String> {
+        encoding
+            .and_then(|label| encoding_rs::Encoding::for_label(label.trim().as_bytes()))
+            .map(|encoding| encoding.name().to_string())
+            .or_else(|| preview.encoding.clone())
+    }
+
     /// 通用的协议请求处理入口
     /// 这个方法可以被任何存储客户端使用
     pub async fn handle_protocol_request(
@@ -627,6 +653,7 @@ impl ProtocolHandler {
                     Self::handle_archive_file_request(
                         protocol_url,
                         entry_path.clone(),
+                        query_pairs.get("encoding").cloned(),
                         method,
                         headers,
                         responder,
@@ -710,11 +737,20 @@ impl ProtocolHandler {
     }
 
     /// 批量注册所有存储协议
-    /// 注册所有支持的存储协议：webdav, oss, local, ssh, huggingface, smb
+    /// 注册所有支持的存储协议：webdav, oss, gcs, azure, local, ssh, huggingface, smb
     pub fn register_all_storage_protocols(
         builder: tauri::Builder<tauri::Wry>,
     ) -> tauri::Builder<tauri::Wry> {
-        let storage_protocols = ["webdav", "oss", "local", "ssh", "huggingface", "smb"];
+        let storage_protocols = [
+            "webdav",
+            "oss",
+            "gcs",
+            "azure",
+            "local",
+            "ssh",
+            "huggingface",
+            "smb",
+        ];
         let mut tauri_builder = builder;
         for protocol in storage_protocols {
             tauri_builder = Self::register_storage_protocol(tauri_builder, protocol);
@@ -0,0 +1,54 @@
+// 并发任务共享的结果数量上限
+// 搜索、扫描等并发处理多个文件时，各任务从同一组名额中占用结果，用完后记录结果已截断
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// 共享的结果名额
+pub struct ResultLimit {
+    max: u32,
+    found: AtomicU32,
+    truncated: AtomicBool,
+}
+
+impl ResultLimit {
+    pub fn new(max: u32) -> Self {
+        Self {
+            max,
+            found: AtomicU32::new(0),
+            truncated: AtomicBool::new(false),
+        }
+    }
+
+    /// 占用一个结果名额，达到上限时记录截断并返回 false
+    pub fn claim(&self) -> bool {
+        let claimed = self
+            .found
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |found| {
+                (found < self.max).then_some(found + 1)
+            })
+            .is_ok();
+        if !claimed {
+            self.mark_truncated();
+        }
+        claimed
+    }
+
+    /// 名额是否已用完
+    pub fn is_full(&self) -> bool {
+        self.found.load(Ordering::Relaxed) >= self.max
+    }
+
+    /// 已占用的名额数量
+    pub fn found(&self) -> u32 {
+        self.found.load(Ordering::Relaxed)
+    }
+
+    /// 记录结果不完整（名额用完或输入被截断）
+    pub fn mark_truncated(&self) {
+        self.truncated.store(true, Ordering::Relaxed);
+    }
+
+    pub fn truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
+}
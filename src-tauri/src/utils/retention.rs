@@ -0,0 +1,386 @@
+// 缓存与临时文件的保留策略
+// 按类别（预览缓存、缩略图、临时文件、旧版本插件包、未完成的下载、范围读取缓存、压缩包索引）配置最长保留时间和最大占用空间，
+// 后台定时清理，也可以立即执行并返回回收的空间
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::archive::entry_index;
+use crate::commands::plugin_installer::get_plugin_cache_dir;
+use crate::download::resume;
+use crate::storage::chunk_cache;
+use crate::storage::local_copy::local_copy_dir;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 启动后首次清理的延迟，避免与启动时的其他任务争抢 IO
+const INITIAL_DELAY: Duration = Duration::from_secs(60);
+
+/// 定时清理的间隔
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// 缓存类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheCategory {
+    /// 预览时下载到临时目录的本地副本
+    PreviewCache,
+    /// 压缩包图片条目的缩略图
+    Thumbnails,
+    /// 中断的索引构建等过程遗留的临时文件
+    TempFiles,
+    /// 已被新版本替换、不再使用的插件包
+    PluginPackages,
+    /// 应用下载目录中未完成下载的部分文件，用户选择的保存位置中的文件不会被清理，
+    /// 超过保留期限后只移除其续传记录
+    DownloadFragments,
+    /// 远程文件范围读取的磁盘缓存块
+    RangeCache,
+    /// 压缩包的随机访问索引（TAR 条目位置和 gzip 解压检查点）
+    ArchiveIndex,
+}
+
+impl CacheCategory {
+    pub const ALL: [CacheCategory; 7] = [
+        Self::PreviewCache,
+        Self::Thumbnails,
+        Self::TempFiles,
+        Self::PluginPackages,
+        Self::DownloadFragments,
+        Self::RangeCache,
+        Self::ArchiveIndex,
+    ];
+
+    /// 默认策略：(最长保留天数, 最大占用空间)
+    fn default_policy(&self) -> (Option<u32>, Option<u64>) {
+        match self {
+            Self::PreviewCache => (Some(7), Some(2 * 1024 * 1024 * 1024)),
+            Self::Thumbnails => (Some(30), Some(512 * 1024 * 1024)),
+            Self::TempFiles => (Some(1), None),
+            Self::PluginPackages => (Some(30), None),
+            Self::DownloadFragments => (Some(30), None),
+            Self::RangeCache => (Some(30), Some(2 * 1024 * 1024 * 1024)),
+            Self::ArchiveIndex => (Some(30), Some(256 * 1024 * 1024)),
+        }
+    }
+}
+
+/// 单个类别的保留策略
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub category: CacheCategory,
+    /// 最长保留天数，为空时不按时间清理
+    pub max_age_days: Option<u32>,
+    /// 最大占用空间，超出时从最旧的文件开始删除，为空时不限制
+    pub max_size: Option<String>, // 使用字符串表示大数字
+}
+
+/// 单个类别的清理结果
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryCleanup {
+    pub category: CacheCategory,
+    pub removed_files: u32,
+    pub reclaimed_bytes: String, // 使用字符串表示大数字
+    /// 清理后剩余的占用空间
+    pub remaining_bytes: String,
+    pub errors: Vec<String>,
+}
+
+/// 清理报告
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub categories: Vec<CategoryCleanup>,
+    pub reclaimed_bytes: String, // 使用字符串表示大数字
+}
+
+/// 可清理的缓存项
+struct CacheItem {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// 获取所有类别的保留策略，未配置的类别使用默认值
+pub fn get_policies() -> Vec<RetentionPolicy> {
+    let saved = load_policies();
+    CacheCategory::ALL
+        .iter()
+        .map(|category| {
+            saved.get(category).cloned().unwrap_or_else(|| {
+                let (max_age_days, max_size) = category.default_policy();
+                RetentionPolicy {
+                    category: *category,
+                    max_age_days,
+                    max_size: max_size.map(|size| size.to_string()),
+                }
+            })
+        })
+        .collect()
+}
+
+/// 类别当前的最大占用空间，未限制时为空
+pub fn max_size(category: CacheCategory) -> Option<u64> {
+    get_policies()
+        .into_iter()
+        .find(|policy| policy.category == category)
+        .and_then(|policy| policy.max_size)
+        .and_then(|size| size.parse().ok())
+}
+
+/// 保存保留策略，下次清理时生效
+pub fn set_policies(policies: Vec<RetentionPolicy>) -> Result<(), String> {
+    let mut saved = load_policies();
+    for policy in policies {
+        if let Some(size) = &policy.max_size {
+            size.parse::<u64>()
+                .map_err(|_| format!("Invalid max size: {}", size))?;
+        }
+        saved.insert(policy.category, policy);
+    }
+
+    let content = serde_json::to_string_pretty(&saved.into_values().collect::<Vec<_>>())
+        .map_err(|e| format!("Failed to serialize retention policies: {}", e))?;
+    persist::write_atomic(policies_file()?, content)
+        .map_err(|e| format!("Failed to save retention policies: {}", e))
+}
+
+/// 按保留策略清理指定类别，为空时清理所有类别
+pub fn cleanup(categories: Option<Vec<CacheCategory>>) -> CleanupReport {
+    let categories = categories.unwrap_or_else(|| CacheCategory::ALL.to_vec());
+    let policies = get_policies();
+
+    let mut results = Vec::new();
+    let mut total = 0u64;
+    for policy in policies
+        .iter()
+        .filter(|policy| categories.contains(&policy.category))
+    {
+        let result = cleanup_category(policy);
+        total += result.reclaimed_bytes.parse::<u64>().unwrap_or(0);
+        results.push(result);
+    }
+
+    CleanupReport {
+        categories: results,
+        reclaimed_bytes: total.to_string(),
+    }
+}
+
+/// 启动后台定时清理
+pub fn start_scheduler() {
+    tauri::async_runtime::spawn(async {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match tokio::task::spawn_blocking(|| cleanup(None)).await {
+                Ok(report) => log::info!("定时清理缓存完成，回收 {} 字节", report.reclaimed_bytes),
+                Err(e) => log::warn!("定时清理缓存失败: {}", e),
+            }
+        }
+    });
+}
+
+fn cleanup_category(policy: &RetentionPolicy) -> CategoryCleanup {
+    let mut errors = Vec::new();
+    let mut items = match collect_items(policy.category) {
+        Ok(items) => items,
+        Err(e) => {
+            errors.push(e);
+            Vec::new()
+        }
+    };
+    // 从最旧的开始处理
+    items.sort_by_key(|item| item.modified);
+
+    let now = SystemTime::now();
+    let max_age = policy
+        .max_age_days
+        .map(|days| Duration::from_secs(days as u64 * DAY_SECS));
+    let max_size = policy
+        .max_size
+        .as_ref()
+        .and_then(|size| size.parse::<u64>().ok());
+
+    let mut remaining: u64 = items.iter().map(|item| item.size).sum();
+    let mut removed_files = 0u32;
+    let mut reclaimed = 0u64;
+    for item in &items {
+        let expired = max_age.is_some_and(|max_age| {
+            now.duration_since(item.modified)
+                .is_ok_and(|age| age > max_age)
+        });
+        let over_quota = max_size.is_some_and(|max_size| remaining > max_size);
+        if !expired && !over_quota {
+            continue;
+        }
+
+        match remove_item(policy.category, &item.path) {
+            Ok(()) => {
+                removed_files += 1;
+                reclaimed += item.size;
+                remaining -= item.size;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    // 用户保存位置中的部分文件不删除，超过保留期限的续传记录不再保留
+    if policy.category == CacheCategory::DownloadFragments {
+        if let Some(max_age) = max_age {
+            match get_app_data_subdir("downloads") {
+                Ok(own_dir) => resume::forget_stale(max_age, |path| !path.starts_with(&own_dir)),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    CategoryCleanup {
+        category: policy.category,
+        removed_files,
+        reclaimed_bytes: reclaimed.to_string(),
+        remaining_bytes: remaining.to_string(),
+        errors,
+    }
+}
+
+fn collect_items(category: CacheCategory) -> Result<Vec<CacheItem>, String> {
+    match category {
+        CacheCategory::PreviewCache => Ok(files_in(&local_copy_dir(), |_| true)),
+        CacheCategory::Thumbnails => Ok(files_in(&get_app_data_subdir("thumbnails")?, |_| true)),
+        CacheCategory::TempFiles => Ok(files_in(&get_app_data_subdir("filename-index")?, |path| {
+            path.extension().is_some_and(|ext| ext == "building")
+        })),
+        CacheCategory::PluginPackages => Ok(stale_plugin_versions(&get_plugin_cache_dir()?)),
+        CacheCategory::DownloadFragments => {
+            // 部分文件直接写在保存位置，只清理位于应用自身下载目录中的，不删除用户目录中的文件
+            let own_dir = get_app_data_subdir("downloads")?;
+            Ok(resume::list()
+                .into_iter()
+                .map(|partial| PathBuf::from(partial.save_path))
+                .filter(|path| path.starts_with(&own_dir))
+                .filter_map(item_for)
+                .collect())
+        }
+        CacheCategory::RangeCache => Ok(files_in(&chunk_cache::cache_dir()?, |_| true)),
+        CacheCategory::ArchiveIndex => Ok(files_in(&entry_index::index_dir()?, |_| true)),
+    }
+}
+
+fn remove_item(category: CacheCategory, path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+
+    if category == CacheCategory::DownloadFragments {
+        resume::finish(path);
+    }
+    Ok(())
+}
+
+/// 目录下（不递归）满足条件的文件
+fn files_in(dir: &Path, filter: impl Fn(&Path) -> bool) -> Vec<CacheItem> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && filter(path))
+        .filter_map(item_for)
+        .collect()
+}
+
+/// 插件缓存目录中不是当前版本链接目标的 name@version 目录
+/// 当前版本不是符号链接（如复制安装）时无法判断，跳过该插件
+fn stale_plugin_versions(cache_dir: &Path) -> Vec<CacheItem> {
+    let mut dirs = Vec::new();
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return dirs;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // 作用域包（@scope/name@version）位于下一级目录
+        if name.starts_with('@') && !name[1..].contains('@') && path.is_dir() {
+            dirs.extend(stale_plugin_versions(&path));
+            continue;
+        }
+        let Some((package, _)) = name
+            .rsplit_once('@')
+            .filter(|(package, _)| !package.is_empty())
+        else {
+            continue;
+        };
+        if !path.is_dir() || path.is_symlink() {
+            continue;
+        }
+
+        let current = cache_dir.join(package);
+        let Ok(target) = std::fs::read_link(&current) else {
+            continue;
+        };
+        if target.file_name() != Some(entry.file_name().as_os_str()) {
+            if let Some(item) = item_for(path) {
+                dirs.push(item);
+            }
+        }
+    }
+    dirs
+}
+
+/// 读取大小和修改时间，目录按其中所有文件的总大小计算
+fn item_for(path: PathBuf) -> Option<CacheItem> {
+    let metadata = std::fs::symlink_metadata(&path).ok()?;
+    let size = if metadata.is_dir() {
+        dir_size(&path)
+    } else {
+        metadata.len()
+    };
+    Some(CacheItem {
+        size,
+        modified: metadata.modified().ok()?,
+        path,
+    })
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = std::fs::symlink_metadata(entry.path()).ok()?;
+            Some(if metadata.is_dir() {
+                dir_size(&entry.path())
+            } else {
+                metadata.len()
+            })
+        })
+        .sum()
+}
+
+fn policies_file() -> Result<PathBuf, String> {
+    Ok(get_app_data_subdir("settings")?.join("retention.json"))
+}
+
+fn load_policies() -> HashMap<CacheCategory, RetentionPolicy> {
+    policies_file()
+        .ok()
+        .and_then(persist::read_json::<Vec<RetentionPolicy>>)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|policy| (policy.category, policy))
+        .collect()
+}
@@ -0,0 +1,220 @@
+// 启动时的应用数据自检
+// 检查后端管理的状态文件（设置、连接会话、下载状态、搜索历史、索引、插件启用列表）能否正常解析，
+// 损坏的文件移入隔离目录并尝试从上次检查通过时保存的备份恢复，检查结果通过事件通知前端
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use crate::commands::plugin_installer::get_plugin_cache_dir;
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist::{self, backup_path};
+
+/// 保存 JSON 状态文件的子目录
+const JSON_STATE_DIRS: &[&str] = &[
+    "settings",
+    "connections",
+    "sessions",
+    "downloads",
+    "search",
+    "filename-index",
+    "rename-journals",
+];
+
+static LAST_REPORT: LazyLock<Mutex<Option<SelfCheckReport>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 对损坏文件采取的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairAction {
+    /// 已从备份恢复
+    RestoredFromBackup,
+    /// 没有可用的备份，已移除，下次使用时按默认值重建
+    Reset,
+    /// 无法处理，需要手动检查
+    None,
+}
+
+/// 一个损坏的状态文件
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheckIssue {
+    pub path: String,
+    pub error: String,
+    pub action: RepairAction,
+    /// 损坏文件在隔离目录中的路径
+    pub quarantined_path: Option<String>,
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheckReport {
+    pub checked_files: u32,
+    pub issues: Vec<SelfCheckIssue>,
+    /// 检查时间（RFC3339）
+    pub checked_at: String,
+}
+
+/// 执行自检并记录结果
+pub fn run() -> SelfCheckReport {
+    let mut checked_files = 0u32;
+    let mut issues = Vec::new();
+
+    for (path, kind) in state_files() {
+        checked_files += 1;
+        match verify(&path, kind) {
+            Ok(()) => update_backup(&path, kind),
+            Err(error) => {
+                log::warn!("状态文件损坏 {}: {}", path.display(), error);
+                issues.push(repair(&path, kind, error));
+            }
+        }
+    }
+
+    let report = SelfCheckReport {
+        checked_files,
+        issues,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+    report
+}
+
+/// 最近一次自检的结果，启动事件发出时前端可能尚未开始监听
+pub fn last_report() -> Option<SelfCheckReport> {
+    LAST_REPORT.lock().ok()?.clone()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StateKind {
+    Json,
+    Sqlite,
+}
+
+/// 需要检查的状态文件
+fn state_files() -> Vec<(PathBuf, StateKind)> {
+    let mut files = Vec::new();
+    for dir in JSON_STATE_DIRS {
+        let Ok(dir) = get_app_data_subdir(dir) else {
+            continue;
+        };
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_file() {
+                continue;
+            }
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => files.push((path, StateKind::Json)),
+                Some("sqlite") => files.push((path, StateKind::Sqlite)),
+                _ => {}
+            }
+        }
+    }
+
+    if let Ok(cache_dir) = get_plugin_cache_dir() {
+        let enabled_plugins = cache_dir.join("enabled_plugins.json");
+        if enabled_plugins.is_file() {
+            files.push((enabled_plugins, StateKind::Json));
+        }
+    }
+    files
+}
+
+fn verify(path: &Path, kind: StateKind) -> Result<(), String> {
+    match kind {
+        StateKind::Json => {
+            let data = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+            serde_json::from_slice::<serde_json::Value>(&data)
+                .map(|_| ())
+                .map_err(|e| format!("Invalid JSON: {}", e))
+        }
+        StateKind::Sqlite => {
+            let conn = rusqlite::Connection::open_with_flags(
+                path,
+                rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+            )
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+            let result: String = conn
+                .query_row("PRAGMA quick_check", [], |row| row.get(0))
+                .map_err(|e| format!("Integrity check failed: {}", e))?;
+            if result == "ok" {
+                Ok(())
+            } else {
+                Err(format!("Integrity check failed: {}", result))
+            }
+        }
+    }
+}
+
+/// 检查通过的 JSON 文件更新备份，内容未变化时不重复写入
+/// 索引数据库可以重建，不做备份
+fn update_backup(path: &Path, kind: StateKind) {
+    if kind != StateKind::Json {
+        return;
+    }
+    let backup = backup_path(path);
+    let Ok(data) = std::fs::read(path) else {
+        return;
+    };
+    if std::fs::read(&backup).ok().as_deref() == Some(data.as_slice()) {
+        return;
+    }
+    if let Err(e) = persist::write_atomic(&backup, data) {
+        log::warn!("写入状态文件备份失败 {}: {}", backup.display(), e);
+    }
+}
+
+/// 隔离损坏的文件，有可用备份时恢复备份
+fn repair(path: &Path, kind: StateKind, error: String) -> SelfCheckIssue {
+    let mut issue = SelfCheckIssue {
+        path: path.display().to_string(),
+        error,
+        action: RepairAction::None,
+        quarantined_path: None,
+    };
+
+    match quarantine(path) {
+        Ok(quarantined) => issue.quarantined_path = Some(quarantined.display().to_string()),
+        Err(e) => {
+            log::warn!("隔离损坏的状态文件失败 {}: {}", path.display(), e);
+            return issue;
+        }
+    }
+
+    let backup = backup_path(path);
+    let restored = kind == StateKind::Json
+        && verify(&backup, kind).is_ok()
+        && std::fs::copy(&backup, path).is_ok();
+    issue.action = if restored {
+        RepairAction::RestoredFromBackup
+    } else {
+        RepairAction::Reset
+    };
+    issue
+}
+
+/// 移动到 app-data/quarantine，文件名附加时间戳避免覆盖
+fn quarantine(path: &Path) -> Result<PathBuf, String> {
+    let dir = get_app_data_subdir("quarantine")?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "state".to_string());
+    let target = dir.join(format!(
+        "{}.{}",
+        name,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+
+    // 跨文件系统（如开发模式的插件目录）时 rename 会失败，改为复制后删除
+    if std::fs::rename(path, &target).is_err() {
+        std::fs::copy(path, &target).map_err(|e| format!("Failed to copy file: {}", e))?;
+        std::fs::remove_file(path).map_err(|e| format!("Failed to remove file: {}", e))?;
+    }
+    Ok(target)
+}
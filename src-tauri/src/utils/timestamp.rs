@@ -0,0 +1,85 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+
+/// 带时区偏移、但不完全符合 RFC3339 的 ISO 8601 变体
+const OFFSET_FORMATS: [&str; 3] = [
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f %z",
+];
+
+/// 不带时区的日期时间，按 UTC 处理
+const NAIVE_FORMATS: [&str; 6] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%d-%b-%Y %H:%M",
+];
+
+/// 解析各后端返回的时间字符串
+/// 支持 RFC3339 / ISO 8601、RFC 1123 / RFC 2822（HTTP 日期）、无时区的日期时间以及 Unix 时间戳（秒或毫秒）
+pub fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    // 部分 WebDAV 服务器使用 "UTC" 而不是 HTTP 日期要求的 "GMT"
+    let http_date = raw
+        .strip_suffix(" UTC")
+        .map(|s| format!("{} GMT", s))
+        .unwrap_or_else(|| raw.to_string());
+    if let Ok(dt) = DateTime::parse_from_rfc2822(&http_date) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    for format in OFFSET_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(raw, format) {
+            return Some(dt.with_timezone(&Utc));
+        }
+    }
+
+    for format in NAIVE_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(dt.and_utc());
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    }
+
+    if raw.bytes().all(|b| b.is_ascii_digit()) {
+        let value = raw.parse::<i64>().ok()?;
+        // 超过 11 位的数字按毫秒处理
+        return if value >= 100_000_000_000 {
+            DateTime::from_timestamp_millis(value)
+        } else {
+            DateTime::from_timestamp(value, 0)
+        };
+    }
+
+    None
+}
+
+/// 格式化为统一的 RFC3339 UTC 字符串（如 2024-01-02T03:04:05Z）
+pub fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// 将时间字符串规范化为 RFC3339 UTC，无法识别时返回 None
+pub fn normalize_timestamp(raw: &str) -> Option<String> {
+    parse_timestamp(raw).map(format_utc)
+}
+
+/// Unix 秒转换为 RFC3339 UTC 字符串
+pub fn unix_to_rfc3339(secs: i64) -> String {
+    DateTime::from_timestamp(secs, 0)
+        .map(format_utc)
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string())
+}
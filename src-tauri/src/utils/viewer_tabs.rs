@@ -0,0 +1,277 @@
+// 文件查看窗口的标签页管理
+// 记录每个查看窗口打开的文件，打开新文件时优先放入已打开同一压缩包或同一连接文件的窗口，
+// 通过定向事件通知目标窗口新增标签页，不再为每个文件创建新的系统窗口
+
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
+use tauri::{Emitter, Manager, WebviewWindow, WindowEvent};
+
+use crate::utils::{mime, window_state};
+
+/// 文件查看窗口的标签前缀
+pub const FILE_VIEWER_LABEL_PREFIX: &str = "file-viewer-";
+
+/// 在已有窗口中打开文件时发送给该窗口的事件
+const OPEN_FILE_EVENT: &str = "viewer-open-file";
+
+/// 已打开的查看窗口，按窗口创建顺序排列
+static VIEWERS: LazyLock<Mutex<Vec<ViewerWindow>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// 查看窗口中的一个文件（标签页），打开前已解析好元数据
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerFile {
+    pub path: String,
+    /// 压缩包内的条目路径，为空时为文件本身
+    pub entry_path: Option<String>,
+    pub file_name: String,
+    /// 文件大小，未知时（如未解析的压缩包条目）为空
+    pub size: Option<String>, // 使用字符串表示大数字
+    /// 修改时间（RFC3339），未知时为空
+    pub modified: Option<String>,
+    pub mime_type: String,
+    /// 文件所属的连接标识，本地文件（如文件关联打开）为空
+    pub connection_key: Option<String>,
+    /// 文件所属的已保存连接，查看窗口用它重新连接；本地文件为空
+    pub profile_id: Option<String>,
+}
+
+impl ViewerFile {
+    /// 本地文件（文件关联或拖放打开），从文件系统读取元数据
+    pub fn local(path: &str) -> Self {
+        let metadata = std::fs::metadata(path).ok();
+        let file_name = display_name(path);
+        Self {
+            path: path.to_string(),
+            entry_path: None,
+            mime_type: mime::mime_from_filename(&file_name),
+            file_name,
+            size: metadata.as_ref().map(|m| m.len().to_string()),
+            modified: metadata
+                .and_then(|m| m.modified().ok())
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+            connection_key: None,
+            profile_id: None,
+        }
+    }
+
+    fn same_file(&self, other: &ViewerFile) -> bool {
+        self.path == other.path
+            && self.entry_path == other.entry_path
+            && self.connection_key == other.connection_key
+    }
+
+    fn same_archive(&self, other: &ViewerFile) -> bool {
+        self.entry_path.is_some()
+            && other.entry_path.is_some()
+            && self.path == other.path
+            && self.connection_key == other.connection_key
+    }
+}
+
+/// 打开文件时选中的窗口
+pub enum ViewerTarget {
+    /// 该文件已在窗口中打开，切换到对应标签页
+    AlreadyOpen(String),
+    /// 在已有窗口中新增标签页
+    NewTab(String),
+    /// 没有合适的窗口，需要创建新窗口
+    NewWindow,
+}
+
+struct ViewerWindow {
+    label: String,
+    tabs: Vec<ViewerFile>,
+    last_focused: Instant,
+}
+
+/// 为文件选择目标窗口：已打开该文件的窗口 > 打开了同一压缩包条目的窗口 >
+/// 最近使用的同一连接的窗口
+pub fn find_target(file: &ViewerFile) -> ViewerTarget {
+    let Ok(viewers) = VIEWERS.lock() else {
+        return ViewerTarget::NewWindow;
+    };
+    if let Some(viewer) = viewers
+        .iter()
+        .find(|viewer| viewer.tabs.iter().any(|tab| tab.same_file(file)))
+    {
+        return ViewerTarget::AlreadyOpen(viewer.label.clone());
+    }
+    if let Some(viewer) = viewers
+        .iter()
+        .find(|viewer| viewer.tabs.iter().any(|tab| tab.same_archive(file)))
+    {
+        return ViewerTarget::NewTab(viewer.label.clone());
+    }
+    viewers
+        .iter()
+        .filter(|viewer| {
+            viewer
+                .tabs
+                .iter()
+                .any(|tab| tab.connection_key == file.connection_key)
+        })
+        .max_by_key(|viewer| viewer.last_focused)
+        .map(|viewer| ViewerTarget::NewTab(viewer.label.clone()))
+        .unwrap_or(ViewerTarget::NewWindow)
+}
+
+/// 登记新创建的查看窗口及其第一个文件，窗口关闭时自动移除
+pub fn track(window: &WebviewWindow, file: ViewerFile) {
+    if let Ok(mut viewers) = VIEWERS.lock() {
+        viewers.push(ViewerWindow {
+            label: window.label().to_string(),
+            tabs: vec![file],
+            last_focused: Instant::now(),
+        });
+    }
+
+    let label = window.label().to_string();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Focused(true) => {
+            if let Ok(mut viewers) = VIEWERS.lock() {
+                if let Some(viewer) = viewers.iter_mut().find(|viewer| viewer.label == label) {
+                    viewer.last_focused = Instant::now();
+                }
+            }
+        }
+        WindowEvent::Destroyed => {
+            if let Ok(mut viewers) = VIEWERS.lock() {
+                viewers.retain(|viewer| viewer.label != label);
+            }
+        }
+        _ => {}
+    });
+}
+
+/// 在窗口中新增标签页，已存在时不重复添加
+pub fn add_tab(label: &str, file: ViewerFile) {
+    if let Ok(mut viewers) = VIEWERS.lock() {
+        if let Some(viewer) = viewers.iter_mut().find(|viewer| viewer.label == label) {
+            if !viewer.tabs.iter().any(|tab| tab.same_file(&file)) {
+                viewer.tabs.push(file);
+            }
+        }
+    }
+}
+
+/// 窗口当前的标签页
+pub fn tabs(label: &str) -> Vec<ViewerFile> {
+    VIEWERS
+        .lock()
+        .ok()
+        .and_then(|viewers| {
+            viewers
+                .iter()
+                .find(|viewer| viewer.label == label)
+                .map(|viewer| viewer.tabs.clone())
+        })
+        .unwrap_or_default()
+}
+
+/// 用前端上报的标签页替换窗口的记录（关闭或调整标签页后调用）
+pub fn set_tabs(label: &str, tabs: Vec<ViewerFile>) -> Result<(), String> {
+    let mut viewers = VIEWERS
+        .lock()
+        .map_err(|e| format!("Failed to lock viewer windows: {}", e))?;
+    let viewer = viewers
+        .iter_mut()
+        .find(|viewer| viewer.label == label)
+        .ok_or_else(|| format!("Not a viewer window: {}", label))?;
+    viewer.tabs = tabs;
+    Ok(())
+}
+
+/// 打开文件：已打开时切换到对应窗口，否则在合适的窗口中新增标签页，
+/// 没有合适的窗口或 new_window 为 true 时创建新窗口。返回目标窗口标签
+pub async fn open(
+    app: &tauri::AppHandle,
+    file: ViewerFile,
+    new_window: bool,
+) -> Result<String, String> {
+    let target = if new_window {
+        ViewerTarget::NewWindow
+    } else {
+        find_target(&file)
+    };
+    let label = match target {
+        ViewerTarget::AlreadyOpen(label) | ViewerTarget::NewTab(label) => label,
+        ViewerTarget::NewWindow => return create_window(app, file).await,
+    };
+
+    // 窗口可能刚刚关闭，找不到时改为新建窗口
+    let Some(window) = app.get_webview_window(&label) else {
+        return create_window(app, file).await;
+    };
+    add_tab(&label, file.clone());
+    app.emit_to(label.as_str(), OPEN_FILE_EVENT, &file)
+        .map_err(|e| format!("Failed to emit {}: {}", OPEN_FILE_EVENT, e))?;
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+    Ok(label)
+}
+
+/// 创建文件查看窗口，文件路径通过 URL 传递
+async fn create_window(app: &tauri::AppHandle, file: ViewerFile) -> Result<String, String> {
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+    // 为每个窗口创建唯一的标签
+    // 标签前缀与 capabilities/file-viewer.json 匹配，窗口只能调用预览所需的命令
+    let window_label = format!(
+        "{}{}",
+        FILE_VIEWER_LABEL_PREFIX,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+
+    // 创建新窗口，URL 参数传递文件路径（压缩包条目额外传递条目路径，连接中的文件额外传递连接配置）
+    let mut window_url = format!(
+        "/?mode=file-viewer&file={}",
+        urlencoding::encode(&file.path)
+    );
+    if let Some(entry_path) = &file.entry_path {
+        window_url.push_str(&format!("&entry={}", urlencoding::encode(entry_path)));
+    }
+    if let Some(profile_id) = &file.profile_id {
+        window_url.push_str(&format!("&profile={}", urlencoding::encode(profile_id)));
+    }
+
+    // 恢复同类文件上次关闭时的窗口尺寸、位置和缩放
+    let state = window_state::restore(&file.file_name);
+    let settings = window_state::settings();
+
+    let mut builder =
+        WebviewWindowBuilder::new(app, &window_label, WebviewUrl::App(window_url.into()))
+            .title(&file.file_name) // 只显示文件名
+            .inner_size(state.width, state.height)
+            .min_inner_size(400.0, 600.0) // 与主窗口保持一致
+            .maximized(settings.open_maximized || state.maximized);
+    builder = match (state.x, state.y) {
+        (Some(x), Some(y)) if window_state::is_on_screen(app, x, y) => builder.position(x, y),
+        _ => builder.center(),
+    };
+
+    match builder.build() {
+        Ok(window) => {
+            if state.zoom != 1.0 {
+                let _ = window.set_zoom(state.zoom);
+            }
+            window_state::track(&window, &file.file_name);
+            track(&window, file);
+            Ok(window_label)
+        }
+        Err(e) => Err(format!("Failed to create window: {}", e)),
+    }
+}
+
+/// 窗口标题和标签页使用的文件名，压缩包条目使用条目名
+pub fn display_name(path: &str) -> String {
+    std::path::Path::new(path.trim_end_matches('/'))
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("File Viewer")
+        .to_string()
+}
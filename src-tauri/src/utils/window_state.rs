@@ -0,0 +1,221 @@
+// 文件查看窗口的尺寸、位置和缩放记忆
+// 按文件类型（扩展名）保存该类文件最后一次关闭时的窗口状态，重新打开同类文件时恢复
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use tauri::{WebviewWindow, WindowEvent};
+
+use crate::utils::app_paths::get_app_data_subdir;
+use crate::utils::persist;
+
+/// 没有保存的状态时使用的窗口尺寸
+const DEFAULT_WIDTH: f64 = 1200.0;
+const DEFAULT_HEIGHT: f64 = 800.0;
+
+/// 恢复位置时窗口左上角至少要在某个显示器内留出的距离，避免窗口落在屏幕外无法拖动
+const VISIBLE_MARGIN: f64 = 50.0;
+
+static STORE: LazyLock<Mutex<WindowStateStore>> =
+    LazyLock::new(|| Mutex::new(WindowStateStore::load()));
+
+/// 已打开的查看窗口（窗口标签 -> 状态键）
+static OPEN_WINDOWS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 查看窗口状态，尺寸和位置使用逻辑像素
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerWindowState {
+    pub width: f64,
+    pub height: f64,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub maximized: bool,
+    pub zoom: f64,
+}
+
+impl Default for ViewerWindowState {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            x: None,
+            y: None,
+            maximized: false,
+            zoom: 1.0,
+        }
+    }
+}
+
+/// 查看窗口设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerWindowSettings {
+    /// 总是以最大化方式打开查看窗口
+    pub open_maximized: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WindowStateStore {
+    #[serde(default)]
+    settings: ViewerWindowSettings,
+    #[serde(default)]
+    windows: HashMap<String, ViewerWindowState>,
+}
+
+impl WindowStateStore {
+    fn state_file() -> Result<PathBuf, String> {
+        Ok(get_app_data_subdir("settings")?.join("window-state.json"))
+    }
+
+    fn load() -> Self {
+        Self::state_file()
+            .ok()
+            .and_then(persist::read_json)
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+        persist::write_atomic(Self::state_file()?, content)
+            .map_err(|e| format!("Failed to save window state: {}", e))
+    }
+}
+
+/// 获取查看窗口设置
+pub fn settings() -> ViewerWindowSettings {
+    STORE
+        .lock()
+        .map(|store| store.settings.clone())
+        .unwrap_or_default()
+}
+
+/// 更新并持久化查看窗口设置
+pub fn set_settings(settings: ViewerWindowSettings) -> Result<(), String> {
+    let mut store = STORE
+        .lock()
+        .map_err(|e| format!("Failed to lock window state: {}", e))?;
+    store.settings = settings;
+    store.save()
+}
+
+/// 该类文件上次保存的窗口状态
+pub fn restore(file_path: &str) -> ViewerWindowState {
+    STORE
+        .lock()
+        .ok()
+        .and_then(|store| store.windows.get(&state_key(file_path)).cloned())
+        .unwrap_or_default()
+}
+
+/// 跟踪查看窗口的尺寸和位置变化，窗口关闭时保存
+pub fn track(window: &WebviewWindow, file_path: &str) {
+    if let Ok(mut windows) = OPEN_WINDOWS.lock() {
+        windows.insert(window.label().to_string(), state_key(file_path));
+    }
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Resized(_) | WindowEvent::Moved(_) => capture(&handle),
+        WindowEvent::Destroyed => {
+            let key = OPEN_WINDOWS
+                .lock()
+                .ok()
+                .and_then(|mut windows| windows.remove(handle.label()));
+            if key.is_some() {
+                save_store();
+            }
+        }
+        _ => {}
+    });
+}
+
+/// 设置窗口缩放并记住该类文件的缩放比例
+pub fn set_zoom(window: &WebviewWindow, zoom: f64) -> Result<(), String> {
+    window
+        .set_zoom(zoom)
+        .map_err(|e| format!("Failed to set zoom: {}", e))?;
+    update(window.label(), |state| state.zoom = zoom);
+    Ok(())
+}
+
+/// 保存的位置是否仍在某个显示器内（显示器可能已断开或分辨率已改变）
+pub fn is_on_screen(app: &tauri::AppHandle, x: f64, y: f64) -> bool {
+    let Ok(monitors) = app.available_monitors() else {
+        return false;
+    };
+    monitors.iter().any(|monitor| {
+        let scale = monitor.scale_factor();
+        let position = monitor.position().to_logical::<f64>(scale);
+        let size = monitor.size().to_logical::<f64>(scale);
+        x >= position.x - VISIBLE_MARGIN
+            && y >= position.y
+            && x + VISIBLE_MARGIN <= position.x + size.width
+            && y + VISIBLE_MARGIN <= position.y + size.height
+    })
+}
+
+/// 状态键：小写扩展名，没有扩展名时为空字符串
+fn state_key(file_path: &str) -> String {
+    std::path::Path::new(file_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 记录窗口当前的尺寸和位置，最大化或最小化时只更新最大化状态，保留还原后的尺寸
+fn capture(window: &WebviewWindow) {
+    let Ok(scale) = window.scale_factor() else {
+        return;
+    };
+    let maximized = window.is_maximized().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    let size = window.inner_size().ok();
+    let position = window.outer_position().ok();
+
+    update(window.label(), |state| {
+        if minimized {
+            return;
+        }
+        state.maximized = maximized;
+        if maximized {
+            return;
+        }
+        if let Some(size) = size {
+            let size = size.to_logical::<f64>(scale);
+            state.width = size.width;
+            state.height = size.height;
+        }
+        if let Some(position) = position {
+            let position = position.to_logical::<f64>(scale);
+            state.x = Some(position.x);
+            state.y = Some(position.y);
+        }
+    });
+}
+
+/// 修改窗口对应的状态（只修改内存，窗口关闭时保存）
+fn update(label: &str, f: impl FnOnce(&mut ViewerWindowState)) {
+    let Some(key) = OPEN_WINDOWS
+        .lock()
+        .ok()
+        .and_then(|windows| windows.get(label).cloned())
+    else {
+        return;
+    };
+    if let Ok(mut store) = STORE.lock() {
+        f(store.windows.entry(key).or_default());
+    }
+}
+
+fn save_store() {
+    let result = STORE
+        .lock()
+        .map_err(|e| format!("Failed to lock window state: {}", e))
+        .and_then(|store| store.save());
+    if let Err(e) = result {
+        log::warn!("保存窗口状态失败: {}", e);
+    }
+}
@@ -5,13 +5,16 @@ import ErrorBoundary from './components/common/ErrorBoundary';
 import DownloadProgress from './components/DownloadProgress';
 import { FileBrowser } from './components/FileBrowser';
 import { FileViewer } from './components/FileViewer';
+import { ViewerTabBar } from './components/FileViewer/ViewerTabBar';
 import { UpdateNotification, useUpdateNotification } from './components/UpdateNotification';
 import { useTheme } from './hooks/useTheme';
+import { useViewerTabs } from './hooks/useViewerTabs';
 import { fileAssociationService } from './services/fileAssociationService';
 import { navigationHistoryService } from './services/navigationHistory';
 import { pluginInitialization } from './services/plugin/pluginInitialization';
 import { useStorageStore } from './stores/storageStore';
 import type { StorageFile } from './types';
+import type { ViewerFile } from './types/tauri-commands';
 import './i18n';
 import './App.css';
 import type { StorageClient } from './services/storage/types';
@@ -22,6 +25,25 @@ function App() {
   // 检测是否为文件查看模式
   const urlParams = new URLSearchParams(window.location.search);
   const isFileViewerMode = urlParams.get('mode') === 'file-viewer';
+  const filePathFromUrl = urlParams.get('file');
+
+  // 文件查看窗口的标签页，后端未记录时使用 URL 中的文件
+  const urlTab: ViewerFile | null = filePathFromUrl
+    ? {
+        path: filePathFromUrl,
+        entryPath: urlParams.get('entry'),
+        fileName: filePathFromUrl.split(/[/\\]/).pop() || filePathFromUrl,
+        size: null,
+        modified: null,
+        mimeType: '',
+        connectionKey: null,
+        profileId: urlParams.get('profile'),
+      }
+    : null;
+  const { tabs, activeTab, selectTab, closeTab } = useViewerTabs(
+    isFileViewerMode && !!filePathFromUrl,
+    urlTab
+  );
 
   // 初始化主题系统
   useTheme();
@@ -45,6 +67,8 @@ function App() {
   const [isFileAssociationMode, setIsFileAssociationMode] = useState(false);
   const [forceTextMode, setForceTextMode] = useState(false);
   const [selectedPluginId, setSelectedPluginId] = useState<string | undefined>(undefined);
+  // 文件查看窗口中已打开的标签页，切换标签页时重新创建查看器
+  const [openedTab, setOpenedTab] = useState<ViewerFile | null>(null);
 
   // 用于跟踪文件关联是否已处理的 ref，必须在顶层声明
   const fileAssociationHandledRef = useRef(false);
@@ -99,33 +123,9 @@ function App() {
 
     initializePlugins();
 
-    // 如果是文件查看模式且URL中有文件参数，直接处理
-    if (isFileViewerMode) {
-      const filePathFromUrl = urlParams.get('file');
-      if (filePathFromUrl) {
-        const initFileViewer = async () => {
-          try {
-            // 直接处理文件路径
-            const decodedFilePath = decodeURIComponent(filePathFromUrl);
-            const result = await fileAssociationService.openFile(decodedFilePath);
-
-            if (result.success && result.file) {
-              const currentStorageClient = getCurrentClient();
-              // 标记当前是文件关联模式
-              setIsFileAssociationMode(true);
-              handleFileSelect(result.file, result.fileName, currentStorageClient);
-            } else {
-              setAppState('connecting');
-            }
-          } catch (error) {
-            console.error('File association error:', error);
-            setAppState('connecting');
-          }
-        };
-
-        initFileViewer();
-        return; // 文件查看模式下不执行后续的自动连接逻辑
-      }
+    // 文件查看模式下由当前标签页打开文件，不执行后续的自动连接逻辑
+    if (isFileViewerMode && filePathFromUrl) {
+      return;
     }
 
     // 监听文件打开事件
@@ -206,6 +206,38 @@ function App() {
     tryAutoConnect();
   }, []);
 
+  // 文件查看窗口打开当前标签页：连接中的文件使用已保存的连接配置重新连接，本地文件连接到所在目录
+  useEffect(() => {
+    if (!activeTab) return;
+    let cancelled = false;
+
+    const openTab = async () => {
+      try {
+        const result = activeTab.profileId
+          ? await fileAssociationService.openConnectionFile(activeTab.profileId, activeTab.path)
+          : await fileAssociationService.openFile(activeTab.path);
+        if (cancelled) return;
+
+        if (result.success && result.file) {
+          // 标记当前是文件关联模式
+          setIsFileAssociationMode(true);
+          setOpenedTab(activeTab);
+          handleFileSelect(result.file, result.fileName, getCurrentClient());
+        } else {
+          setAppState('connecting');
+        }
+      } catch (error) {
+        console.error('File association error:', error);
+        if (!cancelled) setAppState('connecting');
+      }
+    };
+
+    openTab();
+    return () => {
+      cancelled = true;
+    };
+  }, [activeTab]);
+
   const handleConnect = () => {
     // 连接成功时清除断开连接标记
     localStorage.removeItem('userDisconnected');
@@ -289,17 +321,29 @@ function App() {
 
         {/* 文件查看器 - 只在查看状态时显示 */}
         {appState === 'viewing' && selectedFile && (
-          <div className="page-transition h-full">
-            <FileViewer
-              file={selectedFile}
-              filePath={selectedFilePath}
-              storageClient={selectedStorageClient}
-              hasAssociatedFiles={hasAssociatedFiles}
-              onBack={handleBackToBrowser}
-              hideBackButton={isFileViewerMode} // 如果是文件查看模式则隐藏返回按钮
-              forceTextMode={forceTextMode}
-              pluginId={selectedPluginId}
-            />
+          <div className="page-transition h-full flex flex-col">
+            {isFileViewerMode && tabs.length > 1 && (
+              <ViewerTabBar
+                tabs={tabs}
+                activeTab={activeTab}
+                onSelect={selectTab}
+                onClose={closeTab}
+              />
+            )}
+            <div className="flex-1 min-h-0">
+              <FileViewer
+                key={openedTab ? `${openedTab.path}\0${openedTab.entryPath ?? ''}` : undefined}
+                file={selectedFile}
+                filePath={selectedFilePath}
+                storageClient={selectedStorageClient}
+                hasAssociatedFiles={hasAssociatedFiles}
+                onBack={handleBackToBrowser}
+                hideBackButton={isFileViewerMode} // 如果是文件查看模式则隐藏返回按钮
+                forceTextMode={forceTextMode}
+                pluginId={selectedPluginId}
+                initialEntry={openedTab?.entryPath ?? undefined}
+              />
+            </div>
           </div>
         )}
 
@@ -133,10 +133,6 @@ export const OSSConnectionForm: FC<OSSConnectionFormProps> = ({
       newErrors.secretKey = t('error.secret.key.required');
     }
 
-    if (!currentConfig.bucket?.trim()) {
-      newErrors.bucket = t('error.bucket.required');
-    }
-
     setErrors(newErrors);
     return Object.keys(newErrors).length === 0;
   };
@@ -65,10 +65,12 @@ export default function useConnectionLogic(onConnectSuccess?: () => void) {
 
   useEffect(() => {
     // 使用最近的连接信息预填表单，但不自动连接
-    const defaultConnection = connectionStorage.getDefaultConnection();
-    if (defaultConnection) {
-      handleSelectStoredConnection(defaultConnection);
-    }
+    connectionStorage.loadConnections().then(() => {
+      const defaultConnection = connectionStorage.getDefaultConnection();
+      if (defaultConnection) {
+        handleSelectStoredConnection(defaultConnection);
+      }
+    });
   }, []);
 
   // 通用连接方法 - 使用 adapter 配置构建，移除前端验证
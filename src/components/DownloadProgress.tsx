@@ -23,10 +23,16 @@ interface DownloadProgressProps {
 }
 
 interface DownloadState {
+  // 下载任务标识（下载地址），不同目录中的同名文件分别显示
+  id: string;
   filename: string;
   progress: number;
   downloaded: number;
   totalSize: number;
+  // 最近 5 秒的平均速度（字节/秒）
+  speed?: number;
+  // 按当前速度估算的剩余秒数
+  etaSeconds?: number;
   status: 'preparing' | 'downloading' | 'completed' | 'error' | 'stopped';
   filePath?: string;
   error?: string;
@@ -48,6 +54,14 @@ const translateDownloadError = (error: string, t: (key: string) => string): stri
   return error;
 };
 
+// 剩余时间格式化为 m:ss 或 h:mm:ss
+const formatEta = (seconds: number): string => {
+  const h = Math.floor(seconds / 3600);
+  const m = Math.floor((seconds % 3600) / 60);
+  const s = String(seconds % 60).padStart(2, '0');
+  return h > 0 ? `${h}:${String(m).padStart(2, '0')}:${s}` : `${m}:${s}`;
+};
+
 // 下载项组件
 const DownloadItem = ({
   download,
@@ -57,8 +71,8 @@ const DownloadItem = ({
   t,
 }: {
   download: DownloadState;
-  onCancel: (filename: string) => void;
-  onRemove: (filename: string) => void;
+  onCancel: (id: string) => void;
+  onRemove: (id: string) => void;
   onOpenLocation: (filePath: string) => void;
   t: (key: string) => string;
 }) => (
@@ -92,7 +106,12 @@ const DownloadItem = ({
             ) : (
               <>
                 <div className="flex justify-between text-xs text-gray-500 dark:text-gray-400 mb-1">
-                  <span>{download.progress}%</span>
+                  <span>
+                    {download.progress}%
+                    {!!download.speed && ` · ${formatFileSize(download.speed)}/s`}
+                    {download.etaSeconds !== undefined &&
+                      ` · ${t('download.eta')} ${formatEta(download.etaSeconds)}`}
+                  </span>
                   <span>
                     {formatFileSize(download.downloaded)}
                     {download.totalSize > 0 && ` / ${formatFileSize(download.totalSize)}`}
@@ -142,7 +161,7 @@ const DownloadItem = ({
 
       {(download.status === 'downloading' || download.status === 'preparing') && (
         <button
-          onClick={() => onCancel(download.filename)}
+          onClick={() => onCancel(download.id)}
           className="ml-2 text-gray-400 dark:text-gray-500 hover:text-red-600 dark:hover:text-red-400 p-1 rounded hover:bg-gray-100 dark:hover:bg-gray-700"
           title={t('download.cancel.tooltip')}
         >
@@ -154,7 +173,7 @@ const DownloadItem = ({
         download.status === 'error' ||
         download.status === 'stopped') && (
         <button
-          onClick={() => onRemove(download.filename)}
+          onClick={() => onRemove(download.id)}
           className="ml-2 text-gray-400 dark:text-gray-500 hover:text-gray-600 dark:hover:text-gray-300 p-1 rounded hover:bg-gray-100 dark:hover:bg-gray-700"
           title={download.status === 'stopped' ? t('download.remove.stopped') : undefined}
         >
@@ -175,11 +194,16 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
     if (!isVisible) return;
 
     const unlistenStart = listen('download-started', event => {
-      const { filename, total_size } = event.payload as { filename: string; total_size: number };
+      const { id, filename, total_size } = event.payload as {
+        id: string;
+        filename: string;
+        total_size: number;
+      };
       setDownloads(
         prev =>
           new Map(
-            prev.set(filename, {
+            prev.set(id, {
+              id,
               filename,
               progress: 0,
               downloaded: 0,
@@ -190,36 +214,83 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
       );
     });
 
+    // 排队和暂停的下载显示为准备状态，开始传输时由 download-started 更新
+    const markWaiting = (id: string, filename: string, message: string) => {
+      setDownloads(prev => {
+        const newMap = new Map(prev);
+        const existing = newMap.get(id);
+        newMap.set(id, {
+          id,
+          filename,
+          progress: existing?.progress ?? 0,
+          downloaded: existing?.downloaded ?? 0,
+          totalSize: existing?.totalSize ?? 0,
+          status: 'preparing',
+          currentFile: message,
+        });
+        return newMap;
+      });
+    };
+
+    const unlistenQueued = listen('download-queued', event => {
+      const { id, filename } = event.payload as { id: string; filename: string };
+      markWaiting(id, filename, t('download.status.queued'));
+    });
+
+    const unlistenPaused = listen('download-paused', event => {
+      const { id, filename } = event.payload as { id: string; filename: string };
+      markWaiting(id, filename, t('download.status.paused'));
+    });
+
     const unlistenProgress = listen('download-progress', event => {
-      const { filename, downloaded, total_size, progress } = event.payload as {
-        filename: string;
-        downloaded: number;
-        total_size: number;
-        progress: number;
-      };
+      const { id, downloaded, total_size, progress, speed, eta_seconds } =
+        event.payload as {
+          id: string;
+          downloaded: number;
+          total_size: number;
+          progress: number;
+          speed: number;
+          eta_seconds: number | null;
+        };
 
       setDownloads(prev => {
         const newMap = new Map(prev);
-        const existing = newMap.get(filename);
+        const existing = newMap.get(id);
         if (existing) {
-          newMap.set(filename, {
+          newMap.set(id, {
             ...existing,
             progress,
             downloaded,
             totalSize: total_size,
+            speed,
+            etaSeconds: eta_seconds ?? undefined,
           });
         }
         return newMap;
       });
     });
 
+    // 下载完成后自动解压的进度，沿用下载进度条显示
+    const unlistenDecompress = listen('download-decompress-progress', event => {
+      const { id, progress } = event.payload as { id: string; progress: number };
+
+      setDownloads(prev => {
+        const newMap = new Map(prev);
+        const existing = newMap.get(id);
+        if (existing) {
+          newMap.set(id, { ...existing, progress });
+        }
+        return newMap;
+      });
+    });
+
     const unlistenCompleted = listen('download-completed', event => {
-      const { filename, file_path } = event.payload as { filename: string; file_path: string };
+      const { id, file_path } = event.payload as { id: string; file_path: string };
       setDownloads(prev => {
         const newMap = new Map(prev);
-        const existing = newMap.get(filename);
+        const existing = newMap.get(id);
         if (existing) {
-          newMap.set(filename, {
+          newMap.set(id, {
             ...existing,
             status: 'completed',
             filePath: file_path,
@@ -231,15 +302,15 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
     });
 
     const unlistenError = listen('download-error', event => {
-      const { filename, error } = event.payload as { filename: string; error: string };
+      const { id, error } = event.payload as { id: string; error: string };
 
       setDownloads(prev => {
         const newMap = new Map(prev);
-        const existing = newMap.get(filename);
+        const existing = newMap.get(id);
         if (existing) {
           // 如果错误信息包含 "canceled/cancelled"（忽略大小写），则标记为 stopped 而不是 error
           const isCancelled = /cancell?ed/i.test(error);
-          newMap.set(filename, {
+          newMap.set(id, {
             ...existing,
             status: isCancelled ? 'stopped' : 'error',
             error: isCancelled ? undefined : error,
@@ -251,18 +322,50 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
 
     return () => {
       unlistenStart.then(fn => fn());
+      unlistenQueued.then(fn => fn());
+      unlistenPaused.then(fn => fn());
       unlistenProgress.then(fn => fn());
+      unlistenDecompress.then(fn => fn());
       unlistenCompleted.then(fn => fn());
       unlistenError.then(fn => fn());
     };
+  }, [isVisible, t]);
+
+  // 进度事件至少间隔 1 秒且只在有新数据时发送，传输停滞时定期查询各任务的速度和剩余时间
+  useEffect(() => {
+    if (!isVisible) return;
+
+    const refreshTasks = async () => {
+      const result = await commands.downloadListTasks();
+      if (result.status !== 'ok') return;
+      setDownloads(prev => {
+        const newMap = new Map(prev);
+        result.data.forEach(task => {
+          const existing = newMap.get(task.id);
+          if (existing && existing.status === 'downloading') {
+            newMap.set(task.id, {
+              ...existing,
+              speed: Number(task.speed),
+              etaSeconds: task.etaSeconds ?? undefined,
+            });
+          }
+        });
+        return newMap;
+      });
+    };
+
+    const interval = setInterval(() => {
+      refreshTasks().catch(error => console.error('Failed to list download tasks:', error));
+    }, 2000);
+    return () => clearInterval(interval);
   }, [isVisible]);
 
-  const cancelDownload = async (filename: string) => {
+  const cancelDownload = async (id: string) => {
     try {
       const timeoutMs = 5000; // 5秒
 
       await Promise.race([
-        commands.downloadCancel(filename),
+        commands.downloadCancel(id),
         new Promise<never>((_, reject) => {
           setTimeout(() => {
             reject(new Error(`取消下载超时 (${timeoutMs}ms)`));
@@ -272,9 +375,9 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
       // 立即更新为stopped状态，与全部取消保持一致
       setDownloads(prev => {
         const newMap = new Map(prev);
-        const existing = newMap.get(filename);
+        const existing = newMap.get(id);
         if (existing && existing.status === 'downloading') {
-          newMap.set(filename, {
+          newMap.set(id, {
             ...existing,
             status: 'stopped',
           });
@@ -286,10 +389,10 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
     }
   };
 
-  const removeDownload = (filename: string) => {
+  const removeDownload = (id: string) => {
     setDownloads(prev => {
       const newMap = new Map(prev);
-      newMap.delete(filename);
+      newMap.delete(id);
       return newMap;
     });
   };
@@ -305,9 +408,9 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
   const clearCompleted = () => {
     setDownloads(prev => {
       const newMap = new Map();
-      prev.forEach((download, filename) => {
+      prev.forEach((download, id) => {
         if (download.status === 'downloading') {
-          newMap.set(filename, download);
+          newMap.set(id, download);
         }
       });
       return newMap;
@@ -316,10 +419,10 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
 
   const stopAllDownloads = async () => {
     try {
-      // 先获取当前正在下载的文件名列表
+      // 先获取当前正在下载的任务标识列表
       const activeDownloads = Array.from(downloads.values())
         .filter(d => d.status === 'downloading' || d.status === 'preparing')
-        .map(d => d.filename);
+        .map(d => d.id);
 
       await FolderDownloadService.stopAllDownloads();
       // 也取消后端的所有下载
@@ -329,10 +432,10 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
       if (activeDownloads.length > 0) {
         setDownloads(prev => {
           const newMap = new Map(prev);
-          activeDownloads.forEach(filename => {
-            const existing = newMap.get(filename);
+          activeDownloads.forEach(id => {
+            const existing = newMap.get(id);
             if (existing && existing.status === 'downloading') {
-              newMap.set(filename, {
+              newMap.set(id, {
                 ...existing,
                 status: 'stopped',
               });
@@ -506,7 +609,7 @@ export default function DownloadProgress({ isVisible, onClose }: DownloadProgres
         ) : (
           downloadList.map(download => (
             <DownloadItem
-              key={download.filename}
+              key={download.id}
               download={download}
               onCancel={cancelDownload}
               onRemove={removeDownload}
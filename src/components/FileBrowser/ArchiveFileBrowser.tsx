@@ -5,7 +5,6 @@ import { useTranslation } from 'react-i18next';
 import type { ArchiveInfo, StorageFile } from '../../types';
 import { buildArchiveFileTree, getFilesAtPath } from '../../utils/archiveUtils';
 import { cleanPath } from '../../utils/pathUtils';
-import { compareFileSize } from '../../utils/typeUtils';
 import {
   BreadcrumbNavigation,
   EmptyDisplay,
@@ -38,7 +37,6 @@ export const ArchiveFileBrowser: FC<ArchiveFileBrowserProps> = ({
   const { t } = useTranslation();
   const [currentPath, setCurrentPath] = useState('');
   const [searchTerm, setSearchTerm] = useState('');
-  const [sortField, setSortField] = useState<'name' | 'size' | 'modified'>('name');
   const [sortDirection, setSortDirection] = useState<'asc' | 'desc'>('asc');
 
   // 构建虚拟文件系统树
@@ -67,29 +65,14 @@ export const ArchiveFileBrowser: FC<ArchiveFileBrowserProps> = ({
       );
     }
 
-    // 排序
-    return [...filtered].sort((a, b) => {
-      // 目录总是在文件前面
-      if (a.type !== b.type) {
-        return a.type === 'directory' ? -1 : 1;
-      }
-
-      let comparison = 0;
-      switch (sortField) {
-        case 'name':
-          comparison = a.basename.localeCompare(b.basename);
-          break;
-        case 'size':
-          comparison = compareFileSize(a.size, b.size);
-          break;
-        case 'modified':
-          comparison = new Date(a.lastmod).getTime() - new Date(b.lastmod).getTime();
-          break;
-      }
-
-      return sortDirection === 'asc' ? comparison : -comparison;
-    });
-  }, [currentFiles, searchTerm, sortField, sortDirection, showHidden]);
+    // 条目已由后端按自然顺序排序，降序时分别倒转目录和文件，目录仍在文件前面
+    if (sortDirection === 'asc') {
+      return filtered;
+    }
+    const directories = filtered.filter(file => file.type === 'directory');
+    const files = filtered.filter(file => file.type !== 'directory');
+    return [...directories.reverse(), ...files.reverse()];
+  }, [currentFiles, searchTerm, sortDirection, showHidden]);
 
   const handleItemClick = (file: StorageFile) => {
     if (file.type === 'directory') {
@@ -145,13 +128,8 @@ export const ArchiveFileBrowser: FC<ArchiveFileBrowserProps> = ({
     }
   };
 
-  const handleSort = (field: 'name' | 'size' | 'modified') => {
-    if (sortField === field) {
-      setSortDirection(sortDirection === 'asc' ? 'desc' : 'asc');
-    } else {
-      setSortField(field);
-      setSortDirection('asc');
-    }
+  const handleSort = () => {
+    setSortDirection(sortDirection === 'asc' ? 'desc' : 'asc');
   };
 
   if (loading) {
@@ -219,49 +197,30 @@ export const ArchiveFileBrowser: FC<ArchiveFileBrowserProps> = ({
               <div className="flex-1 pr-2 lg:pr-4">
                 <div
                   className="flex items-center cursor-pointer hover:text-gray-700 dark:hover:text-gray-300 select-none"
-                  onClick={() => handleSort('name')}
+                  onClick={handleSort}
                 >
                   <span className="text-xs font-medium text-gray-500 dark:text-gray-400 uppercase tracking-wider">
                     {t('name')}
                   </span>
-                  {sortField === 'name' &&
-                    (sortDirection === 'asc' ? (
-                      <ChevronUp className="ml-1 w-3 h-3" />
-                    ) : (
-                      <ChevronDown className="ml-1 w-3 h-3" />
-                    ))}
+                  {sortDirection === 'asc' ? (
+                    <ChevronUp className="ml-1 w-3 h-3" />
+                  ) : (
+                    <ChevronDown className="ml-1 w-3 h-3" />
+                  )}
                 </div>
               </div>
               <div className="w-16 sm:w-20 lg:w-24 text-right pr-2 lg:pr-4">
-                <div
-                  className="flex items-center justify-end cursor-pointer hover:text-gray-700 dark:hover:text-gray-300 select-none"
-                  onClick={() => handleSort('size')}
-                >
+                <div className="flex items-center justify-end select-none">
                   <span className="text-xs font-medium text-gray-500 dark:text-gray-400 uppercase tracking-wider">
                     {t('size')}
                   </span>
-                  {sortField === 'size' &&
-                    (sortDirection === 'asc' ? (
-                      <ChevronUp className="ml-1 w-3 h-3" />
-                    ) : (
-                      <ChevronDown className="ml-1 w-3 h-3" />
-                    ))}
                 </div>
               </div>
               <div className="w-24 sm:w-32 lg:w-48 text-right">
-                <div
-                  className="flex items-center justify-end cursor-pointer hover:text-gray-700 dark:hover:text-gray-300 select-none"
-                  onClick={() => handleSort('modified')}
-                >
+                <div className="flex items-center justify-end select-none">
                   <span className="text-xs font-medium text-gray-500 dark:text-gray-400 uppercase tracking-wider">
                     {t('modified')}
                   </span>
-                  {sortField === 'modified' &&
-                    (sortDirection === 'asc' ? (
-                      <ChevronUp className="ml-1 w-3 h-3" />
-                    ) : (
-                      <ChevronDown className="ml-1 w-3 h-3" />
-                    ))}
                 </div>
               </div>
             </div>
@@ -77,7 +77,7 @@ export const SettingsPanel: FC<SettingsPanelProps> = ({ isOpen, onClose }) => {
       navigationHistoryService.clearDirectoryCache();
 
       // 清理保存的连接
-      connectionStorage.clearAllConnections();
+      await connectionStorage.clearAllConnections();
 
       // 清理其他本地存储缓存（不清理用户设置）
       const keysToRemove = [];
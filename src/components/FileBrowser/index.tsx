@@ -26,7 +26,6 @@ import type { StorageFile } from '../../types';
 import { commands } from '../../types/tauri-commands';
 import { copyToClipboard, showCopyToast, showErrorToast } from '../../utils/clipboard';
 import { cleanPath } from '../../utils/pathUtils';
-import { compareFileSize } from '../../utils/typeUtils';
 import {
   BreadcrumbNavigation,
   EmptyDisplay,
@@ -171,37 +170,16 @@ export const FileBrowser: FC<FileBrowserProps> = ({
       filteredFiles = getFilteredFiles();
     }
 
-    // 检查当前客户端是否使用服务端排序
-    const currentClient = getCurrentClient();
-    const defaultSortOptions = currentClient?.getDefaultSortOptions?.();
+    // 排序由后端在列出目录时完成（见 getSortOptions），这里保持返回的顺序
+    return filteredFiles;
+  };
 
-    // 如果客户端指定了默认排序选项，则不应用前端排序，直接返回服务端排序的结果
-    if (defaultSortOptions) {
-      return filteredFiles;
+  // 列出目录时的排序选项：客户端指定了默认排序时沿用服务端的顺序，否则由后端按当前排序字段自然排序
+  const getSortOptions = (): Pick<ListOptions, 'sortBy' | 'sortOrder' | 'naturalSort'> => {
+    if (getCurrentClient()?.getDefaultSortOptions?.()) {
+      return { sortBy: null, sortOrder: null, naturalSort: null };
     }
-
-    // 否则对过滤后的文件进行前端排序
-    return [...filteredFiles].sort((a, b) => {
-      // 目录总是排在文件前面
-      if (a.type === 'directory' && b.type !== 'directory') return -1;
-      if (a.type !== 'directory' && b.type === 'directory') return 1;
-
-      let compareValue = 0;
-
-      switch (sortField) {
-        case 'name':
-          compareValue = a.basename.toLowerCase().localeCompare(b.basename.toLowerCase());
-          break;
-        case 'size':
-          compareValue = compareFileSize(a.size || '0', b.size || '0');
-          break;
-        case 'modified':
-          compareValue = new Date(a.lastmod).getTime() - new Date(b.lastmod).getTime();
-          break;
-      }
-
-      return sortDirection === 'asc' ? compareValue : -compareValue;
-    });
+    return { sortBy: sortField, sortOrder: sortDirection, naturalSort: true };
   };
 
   // 处理全局搜索（如HuggingFace数据集搜索）
@@ -416,8 +394,7 @@ export const FileBrowser: FC<FileBrowserProps> = ({
         marker: null,
         prefix: null,
         recursive: null,
-        sortBy: null,
-        sortOrder: null,
+        ...getSortOptions(),
       };
 
       const fileList = await listDirectory(path, listOptions);
@@ -583,8 +560,7 @@ export const FileBrowser: FC<FileBrowserProps> = ({
           pageSize: defaultPageSize || 1000, // 使用客户端默认页面大小，fallback 到 1000
           prefix: null,
           recursive: null,
-          sortBy: null,
-          sortOrder: null,
+          ...getSortOptions(),
         });
 
         // 将新文件追加到现有文件列表
@@ -690,6 +666,7 @@ export const FileBrowser: FC<FileBrowserProps> = ({
           recursive: null,
           sortBy: null,
           sortOrder: null,
+          naturalSort: null,
         });
 
         allFiles.push(...result.files);
@@ -772,6 +749,18 @@ export const FileBrowser: FC<FileBrowserProps> = ({
     };
   }, [initialPath]);
 
+  // 排序方式变化时按新的排序重新列出当前目录
+  const sortInitializedRef = useRef(false);
+  useEffect(() => {
+    if (!sortInitializedRef.current) {
+      sortInitializedRef.current = true;
+      return;
+    }
+    if (currentView === 'directory' && isConnected()) {
+      loadDirectory(currentPath, false, true);
+    }
+  }, [sortField, sortDirection]);
+
   // 响应从文件查看器返回的刷新请求（文件关联模式）
   useEffect(() => {
     if (shouldRefresh && isConnected()) {
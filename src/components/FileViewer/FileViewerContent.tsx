@@ -75,6 +75,7 @@ interface FileViewerContentProps {
   loadFileContent: (forceLoad?: boolean) => Promise<void>;
   forceTextMode?: boolean; // 新增属性，用于强制以文本格式打开
   pluginId?: string; // 新增属性，指定使用的插件ID
+  initialEntry?: string; // 压缩包打开后自动选中的条目路径
 }
 
 export const FileViewerContent = forwardRef<
@@ -112,6 +113,7 @@ export const FileViewerContent = forwardRef<
       loadFileContent,
       forceTextMode,
       pluginId,
+      initialEntry,
     },
     ref
   ) => {
@@ -179,6 +181,7 @@ export const FileViewerContent = forwardRef<
             url: getFileUrl(filePath),
             filename: file.basename,
             storageClient,
+            initialEntry,
           },
         },
         'builtin:pointcloud': {
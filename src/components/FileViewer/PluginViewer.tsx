@@ -1,10 +1,16 @@
-import type { FileAccessor, PluginViewerProps } from '@dataset-viewer/sdk';
+import type {
+  FileAccessor,
+  PluginViewerProps,
+  RandomAccessFile,
+  VirtualFileSystem,
+} from '@dataset-viewer/sdk';
 import type { ComponentType, FC } from 'react';
 import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import i18n from '../../i18n';
 import { pluginManager } from '../../services/plugin/pluginManager';
 import type { StorageClient } from '../../services/storage/types';
+import { commands } from '../../types/tauri-commands';
 import type { StorageFile } from '../../types';
 import { ErrorDisplay, LoadingDisplay } from '../common/StatusDisplay';
 
@@ -17,10 +23,69 @@ interface LocalPluginViewerProps {
   pluginId?: string; // 新增：指定使用的插件ID
 }
 
+/**
+ * 通过后端句柄打开随机读取，句柄数按插件限制
+ */
+const openRandomAccess = async (pluginId: string, filePath: string): Promise<RandomAccessFile> => {
+  const opened = await commands.fileOpen(pluginId, filePath);
+  if (opened.status === 'error') throw new Error(opened.error);
+  const { handle, size } = opened.data;
+
+  return {
+    size: parseInt(size) || 0,
+    pread: async (offset: number, length: number): Promise<ArrayBuffer> => {
+      const result = await commands.filePread(handle, String(offset), length);
+      if (result.status === 'error') throw new Error(result.error);
+      return new Uint8Array(result.data).buffer;
+    },
+    close: async () => {
+      const result = await commands.fileClose(handle);
+      if (result.status === 'error') throw new Error(result.error);
+    },
+  };
+};
+
+/**
+ * 通过后端解析虚拟路径，支持压缩包内的条目
+ */
+const createVirtualFileSystem = (pluginId: string): VirtualFileSystem => ({
+  stat: async (path: string) => {
+    const result = await commands.vfsStat(pluginId, path);
+    if (result.status === 'error') throw new Error(result.error);
+    return { size: parseInt(result.data.size) || 0, inArchive: result.data.inArchive };
+  },
+  read: async (path: string, offset?: number, length?: number): Promise<ArrayBuffer> => {
+    const result = await commands.vfsRead(
+      pluginId,
+      path,
+      offset !== undefined ? String(offset) : null,
+      length ?? null
+    );
+    if (result.status === 'error') throw new Error(result.error);
+    return new Uint8Array(result.data).buffer;
+  },
+});
+
 /**
  * 创建文件访问器适配器，将 storageClient 包装为 FileAccessor 接口
  */
-const createFileAccessor = (storageClient: StorageClient, filePath: string): FileAccessor => ({
+const createFileAccessor = (
+  storageClient: StorageClient,
+  filePath: string,
+  pluginId: string | null
+): FileAccessor => ({
+  openRandomAccess: pluginId ? () => openRandomAccess(pluginId, filePath) : undefined,
+
+  readRelativeFile: pluginId
+    ? async (relativePath: string): Promise<ArrayBuffer> => {
+        const result = await commands.pluginStorageRead(pluginId, relativePath, null, null);
+        if (result.status === 'error') throw new Error(result.error);
+        return new Uint8Array(result.data).buffer;
+      }
+    : undefined,
+
+  vfs: pluginId ? createVirtualFileSystem(pluginId) : undefined,
+
   getFullContent: async (): Promise<ArrayBuffer> => {
     const blob = await storageClient.getFileAsBlob(filePath);
     return await blob.arrayBuffer();
@@ -68,11 +133,23 @@ export const PluginViewer: FC<LocalPluginViewerProps> = ({
   }, []);
 
   // 使用 useMemo 缓存 fileAccessor，避免每次渲染都创建新实例
+  const activePluginId = pluginNamespace?.replace(/^plugin:/, '') ?? null;
   const fileAccessor = useMemo(
-    () => createFileAccessor(storageClient, filePath),
-    [storageClient, filePath]
+    () => createFileAccessor(storageClient, filePath, activePluginId),
+    [storageClient, filePath, activePluginId]
   );
 
+  // 向后端登记插件打开的文件，插件读取同目录文件时以该文件所在目录为范围
+  useEffect(() => {
+    if (!activePluginId) return;
+    commands.pluginViewerAttach(activePluginId, filePath).then(result => {
+      if (result.status === 'error') console.error('Failed to attach plugin viewer:', result.error);
+    });
+    return () => {
+      commands.pluginViewerDetach(activePluginId, filePath);
+    };
+  }, [activePluginId, filePath]);
+
   // 缓存文件对象，避免每次渲染都创建新实例
   const fileObj = useMemo(
     () => ({
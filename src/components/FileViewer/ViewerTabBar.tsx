@@ -0,0 +1,43 @@
+import { X } from 'lucide-react';
+import type { FC } from 'react';
+import { isSameViewerFile } from '../../hooks/useViewerTabs';
+import type { ViewerFile } from '../../types/tauri-commands';
+
+interface ViewerTabBarProps {
+  tabs: ViewerFile[];
+  activeTab: ViewerFile | null;
+  onSelect: (tab: ViewerFile) => void;
+  onClose: (tab: ViewerFile) => void;
+}
+
+// 文件查看窗口的标签栏，同一窗口中打开多个文件时显示
+export const ViewerTabBar: FC<ViewerTabBarProps> = ({ tabs, activeTab, onSelect, onClose }) => (
+  <div className="flex items-center overflow-x-auto border-b border-gray-200 dark:border-gray-700 bg-gray-50 dark:bg-gray-800">
+    {tabs.map(tab => {
+      const isActive = !!activeTab && isSameViewerFile(tab, activeTab);
+      return (
+        <div
+          key={`${tab.connectionKey ?? ''}\0${tab.path}\0${tab.entryPath ?? ''}`}
+          className={`group flex items-center max-w-xs px-3 py-1.5 text-sm border-r border-gray-200 dark:border-gray-700 cursor-pointer ${
+            isActive
+              ? 'bg-white dark:bg-gray-900 text-gray-900 dark:text-gray-100'
+              : 'text-gray-600 dark:text-gray-400 hover:bg-gray-100 dark:hover:bg-gray-700'
+          }`}
+          onClick={() => onSelect(tab)}
+          title={tab.entryPath ? `${tab.path}!${tab.entryPath}` : tab.path}
+        >
+          <span className="truncate">{tab.fileName}</span>
+          <button
+            onClick={event => {
+              event.stopPropagation();
+              onClose(tab);
+            }}
+            className="ml-2 p-0.5 rounded text-gray-400 hover:text-gray-700 dark:hover:text-gray-200 hover:bg-gray-200 dark:hover:bg-gray-600"
+          >
+            <X className="w-3 h-3" />
+          </button>
+        </div>
+      );
+    })}
+  </div>
+);
@@ -24,6 +24,7 @@ interface FileViewerProps {
   hideBackButton?: boolean; // 新增属性，用于隐藏返回按钮
   forceTextMode?: boolean; // 新增属性，用于强制以文本格式打开
   pluginId?: string; // 新增属性，指定使用的插件ID
+  initialEntry?: string; // 压缩包打开后自动选中的条目路径
 }
 
 export const FileViewer: FC<FileViewerProps> = ({
@@ -35,6 +36,7 @@ export const FileViewer: FC<FileViewerProps> = ({
   hideBackButton,
   forceTextMode,
   pluginId,
+  initialEntry,
 }) => {
   const fileLoader = useFileLoader(file, filePath, forceTextMode);
 
@@ -216,6 +218,7 @@ export const FileViewer: FC<FileViewerProps> = ({
         loadFileContent={loadFileContent}
         forceTextMode={forceTextMode}
         pluginId={pluginId}
+        initialEntry={initialEntry}
       />
     </div>
   );
@@ -1,6 +1,6 @@
 import { Archive, Copy, Download, Folder, Loader2 } from 'lucide-react';
 import type { FC } from 'react';
-import { useCallback, useEffect, useState } from 'react';
+import { useCallback, useEffect, useRef, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { CompressionService } from '../../../services/compression';
 import type { StorageClient } from '../../../services/storage/types';
@@ -30,6 +30,9 @@ import { PointCloudViewer } from './PointCloudViewer';
 import { UniversalDataTableViewer } from './UniversalDataTableViewer';
 import { VirtualizedTextViewer } from './VirtualizedTextViewer';
 
+// 滚动到底部时每次续读的大小
+const LOAD_MORE_SIZE = 1024 * 1024; // 1MB
+
 // 错误信息翻译辅助函数
 const translateError = (error: string, t: (key: string) => string): string => {
   // 检查是否是翻译键（以字母开头，包含点号）
@@ -57,16 +60,32 @@ const extractErrorMessage = (
   }
 };
 
+// 按后端识别出的编码解码文本内容，编码为空（二进制内容）或浏览器不支持时按 UTF-8 解码
+const decodePreviewText = (content: Uint8Array, encoding?: string): string => {
+  try {
+    return new TextDecoder(encoding || 'utf-8', { fatal: false }).decode(content);
+  } catch {
+    return new TextDecoder('utf-8', { fatal: false }).decode(content);
+  }
+};
+
 interface ArchiveViewerProps {
   url: string;
   filename: string;
   // 存储客户端，包含档案处理方法
   storageClient?: StorageClient;
+  // 压缩包加载完成后自动选中并预览的条目路径
+  initialEntry?: string;
 }
 
 // 移除不再需要的LoadMoreProgress接口
 
-export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageClient }) => {
+export const ArchiveViewer: FC<ArchiveViewerProps> = ({
+  url,
+  filename,
+  storageClient,
+  initialEntry,
+}) => {
   const { t } = useTranslation();
   const { isConnected, getCurrentClient } = useStorageStore();
   const [archiveInfo, setArchiveInfo] = useState<ArchiveInfo | null>(null);
@@ -252,7 +271,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
 
         // 小媒体文件和数据文件自动加载完整内容
         const loadSize = fileSize;
-        const preview = await CompressionService.extractFilePreviewViaProtocol(
+        const preview = await CompressionService.extractFilePreview(
           url,
           entry.path,
           loadSize
@@ -271,7 +290,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
 
       let preview: FilePreview;
 
-      preview = await CompressionService.extractFilePreviewViaProtocol(
+      preview = await CompressionService.extractFilePreview(
         url,
         entry.path,
         initialLoadSize
@@ -282,7 +301,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
       // 解码文本内容用于文本查看器
       if (preview.content) {
         try {
-          const textContent = new TextDecoder('utf-8', { fatal: false }).decode(preview.content);
+          const textContent = decodePreviewText(preview.content, preview.encoding);
           setFileContent(textContent);
           setFileLoadState(prev => ({
             ...prev,
@@ -303,6 +322,17 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
     }
   };
 
+  // 从查看窗口打开压缩包条目时，压缩包加载完成后自动预览该条目
+  const initialEntryHandledRef = useRef(false);
+  useEffect(() => {
+    if (!archiveInfo || !initialEntry || initialEntryHandledRef.current) return;
+    const entry = archiveInfo.entries.find(e => e.path === initialEntry);
+    if (entry) {
+      initialEntryHandledRef.current = true;
+      previewFile(entry);
+    }
+  }, [archiveInfo, initialEntry]);
+
   // 获取文件类型信息
   const getFileTypeInfo = (entry: ArchiveEntry) => {
     const fileType = getFileType(entry.path);
@@ -335,29 +365,38 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
           return;
         }
 
-        // 使用新的协议URL方法进行加载
-        console.warn('New protocol method does not support offset loading yet, loading full file');
-        const fullPreview = await CompressionService.extractFilePreviewViaProtocol(
+        if (!filePreview.next_continuation) {
+          setFileLoadState(prev => ({ ...prev, loadingMore: false }));
+          return;
+        }
+
+        // 按续读标记读取下一段，沿用首段识别出的编码，保证前后两段按同一编码解码
+        const nextPreview = await CompressionService.extractFilePreview(
           url,
           entry.path,
-          fileLoadState.totalSize
+          LOAD_MORE_SIZE,
+          filePreview.encoding,
+          filePreview.next_continuation
         );
-        if (fullPreview.content && fullPreview.content.length > fileLoadState.loadedContentSize) {
-          const remainingContent = fullPreview.content.slice(fileLoadState.loadedContentSize);
-          const additionalText = new TextDecoder('utf-8', { fatal: false }).decode(
-            remainingContent
-          );
+        if (nextPreview.content.length > 0) {
+          const additionalText = decodePreviewText(nextPreview.content, filePreview.encoding);
           setFileContent(prev => prev + additionalText);
-          setFilePreview(fullPreview); // 更新filePreview状态，包括is_truncated
-          setFileLoadState(prev => ({
-            ...prev,
-            loadedContentSize: fullPreview.content!.length,
-            loadedChunks: prev.loadedChunks + 1,
-            loadingMore: false,
-          }));
-        } else {
-          setFileLoadState(prev => ({ ...prev, loadingMore: false }));
         }
+        setFilePreview(prev =>
+          prev
+            ? {
+                ...prev,
+                is_truncated: !!nextPreview.next_continuation,
+                next_continuation: nextPreview.next_continuation,
+              }
+            : prev
+        );
+        setFileLoadState(prev => ({
+          ...prev,
+          loadedContentSize: prev.loadedContentSize + nextPreview.content.length,
+          loadedChunks: prev.loadedChunks + 1,
+          loadingMore: false,
+        }));
         return;
       } catch (err) {
         console.error('Failed to load more content:', err);
@@ -375,6 +414,8 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
       fileLoadState.loadedContentSize,
       fileLoadState.totalSize,
       filePreview?.is_truncated,
+      filePreview?.encoding,
+      filePreview?.next_continuation,
       t,
     ]
   );
@@ -417,7 +458,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
 
       setFileLoadState(prev => ({ ...prev, manualLoading: true }));
       try {
-        const fullPreview = await CompressionService.extractFilePreviewViaProtocol(
+        const fullPreview = await CompressionService.extractFilePreview(
           url,
           entry.path,
           safeParseInt(entry.size) || undefined // 加载完整文件
@@ -429,9 +470,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
         // 如果是文本文件，也更新文本内容
         if (isTextLikeFile(entry.path) && fullPreview.content) {
           try {
-            const textContent = new TextDecoder('utf-8', { fatal: false }).decode(
-              fullPreview.content
-            );
+            const textContent = decodePreviewText(fullPreview.content, fullPreview.encoding);
             setFileContent(textContent);
             setFileLoadState(prev => ({ ...prev, loadedContentSize: fullPreview.content!.length }));
           } catch (decodeError) {
@@ -455,7 +494,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
 
       setFileLoadState(prev => ({ ...prev, manualLoading: true }));
       try {
-        const fullPreview = await CompressionService.extractFilePreviewViaProtocol(
+        const fullPreview = await CompressionService.extractFilePreview(
           url,
           entry.path,
           safeParseInt(entry.size) || undefined // 加载完整文件
@@ -467,9 +506,7 @@ export const ArchiveViewer: FC<ArchiveViewerProps> = ({ url, filename, storageCl
         // 强制以文本方式解码内容
         if (fullPreview.content) {
           try {
-            const textContent = new TextDecoder('utf-8', { fatal: false }).decode(
-              fullPreview.content
-            );
+            const textContent = decodePreviewText(fullPreview.content, fullPreview.encoding);
             setFileContent(textContent);
             setFileLoadState(prev => ({ ...prev, loadedContentSize: fullPreview.content!.length }));
             setForceTextView(true); // 标记为强制文本查看
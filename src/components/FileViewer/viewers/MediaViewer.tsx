@@ -8,6 +8,7 @@ import {
   getFileUrl,
   getMimeType,
 } from '../../../utils/fileDataUtils';
+import { getMediaStreamUrl } from '../../../utils/protocolUtils';
 import { formatFileSize } from '../../../utils/typeUtils';
 import { ErrorDisplay, LoadingDisplay, UnsupportedFormatDisplay } from '../../common/StatusDisplay';
 import { AV1VideoPlayer } from './AV1VideoPlayer';
@@ -240,27 +241,28 @@ export const MediaViewer: FC<MediaViewerProps> = ({
                 // 对于需要 WASM 解码器的 AV1 视频，不需要 mediaUrl
                 mediaUrl = '';
               } else {
-                // 对于原生支持的 AV1 视频，使用普通的文件 URL
-                mediaUrl = await getFileUrl(filePath);
+                // 对于原生支持的 AV1 视频，通过 media-stream:// 按范围流式播放
+                mediaUrl = getMediaStreamUrl(filePath);
               }
             } else {
-              // 不是 AV1 视频，使用普通的文件 URL
+              // 不是 AV1 视频，通过 media-stream:// 按范围流式播放
               setUseWasmDecoder(false);
-              mediaUrl = await getFileUrl(filePath);
+              mediaUrl = getMediaStreamUrl(filePath);
             }
           } catch (err) {
             console.warn('Failed to pre-detect AV1 video, falling back to normal loading:', err);
             // 检测失败时回退到普通加载方式
             setIsAV1Video(false);
             setUseWasmDecoder(false);
-            mediaUrl = await getFileUrl(filePath);
+            mediaUrl = getMediaStreamUrl(filePath);
           }
         } else {
-          // 非视频文件，使用普通的文件 URL
+          // 音频同样流式播放，其他文件使用普通的文件 URL
           setLoadingProgress(50);
           setIsAV1Video(false);
           setUseWasmDecoder(false);
-          mediaUrl = await getFileUrl(filePath);
+          mediaUrl =
+            fileType === 'audio' ? getMediaStreamUrl(filePath) : await getFileUrl(filePath);
         }
 
         cleanup();
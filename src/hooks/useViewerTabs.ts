@@ -0,0 +1,82 @@
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import { useCallback, useEffect, useState } from 'react';
+import { commands, type ViewerFile } from '../types/tauri-commands';
+
+// 后端打开到本窗口的文件的事件名
+const OPEN_FILE_EVENT = 'viewer-open-file';
+
+export const isSameViewerFile = (a: ViewerFile, b: ViewerFile): boolean =>
+  a.path === b.path && a.entryPath === b.entryPath && a.connectionKey === b.connectionKey;
+
+/**
+ * 文件查看窗口的标签页
+ * 加载时恢复后端记录的标签页，接收后端通过 viewer-open-file 事件放入本窗口的文件，
+ * 关闭标签页后同步给后端，关闭最后一个标签页时关闭窗口
+ * @param enabled 是否为文件查看窗口
+ * @param fallbackTab 后端没有记录时使用的标签页（来自窗口 URL）
+ */
+export const useViewerTabs = (enabled: boolean, fallbackTab: ViewerFile | null) => {
+  const [tabs, setTabs] = useState<ViewerFile[]>([]);
+  const [activeTab, setActiveTab] = useState<ViewerFile | null>(null);
+
+  useEffect(() => {
+    if (!enabled) return;
+    let cancelled = false;
+
+    commands
+      .viewerGetTabs()
+      .then(result => {
+        if (cancelled) return;
+        const restored =
+          result.status === 'ok' && result.data.length > 0
+            ? result.data
+            : fallbackTab
+              ? [fallbackTab]
+              : [];
+        // 事件可能先于标签页列表到达，保留已收到的标签页
+        setTabs(prev => [
+          ...restored,
+          ...prev.filter(tab => !restored.some(file => isSameViewerFile(file, tab))),
+        ]);
+        setActiveTab(prev => prev ?? restored[0] ?? null);
+      })
+      .catch(error => console.error('Failed to get viewer tabs:', error));
+
+    const unlisten = getCurrentWebviewWindow().listen<ViewerFile>(OPEN_FILE_EVENT, event => {
+      const file = event.payload;
+      setTabs(prev => (prev.some(tab => isSameViewerFile(tab, file)) ? prev : [...prev, file]));
+      setActiveTab(file);
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten.then(fn => fn());
+    };
+  }, [enabled]);
+
+  const closeTab = useCallback(
+    (file: ViewerFile) => {
+      const index = tabs.findIndex(tab => isSameViewerFile(tab, file));
+      if (index === -1) return;
+      const remaining = tabs.filter((_, i) => i !== index);
+
+      if (remaining.length === 0) {
+        getCurrentWebviewWindow()
+          .close()
+          .catch(error => console.error('Failed to close viewer window:', error));
+        return;
+      }
+
+      setTabs(remaining);
+      if (activeTab && isSameViewerFile(activeTab, file)) {
+        setActiveTab(remaining[Math.min(index, remaining.length - 1)]);
+      }
+      commands
+        .viewerSetTabs(remaining)
+        .catch(error => console.error('Failed to sync viewer tabs:', error));
+    },
+    [tabs, activeTab]
+  );
+
+  return { tabs, activeTab, selectTab: setActiveTab, closeTab };
+};
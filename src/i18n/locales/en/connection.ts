@@ -89,7 +89,6 @@ export const connection = {
   'error.endpoint.invalid': 'Please enter a valid endpoint',
   'error.access.key.required': 'Please enter Access Key',
   'error.secret.key.required': 'Please enter Secret Key',
-  'error.bucket.required': 'Please enter Bucket name',
 
   // Connection management
   'no.saved.connections': 'No saved connections',
@@ -14,6 +14,9 @@ export const download = {
 
   // Download status
   'download.status.stopped': 'Download stopped',
+  'download.status.queued': 'Waiting in queue...',
+  'download.status.paused': 'Paused',
+  'download.eta': 'ETA',
 
   // Download actions
   'download.remove.stopped': 'Remove stopped download',
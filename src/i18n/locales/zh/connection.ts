@@ -89,7 +89,6 @@ export const connection = {
   'error.endpoint.invalid': '请输入有效的端点地址',
   'error.access.key.required': '请输入 Access Key',
   'error.secret.key.required': '请输入 Secret Key',
-  'error.bucket.required': '请输入 Bucket 名称',
 
   // 连接管理
   'no.saved.connections': '暂无已保存的连接',
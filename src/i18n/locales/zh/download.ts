@@ -14,6 +14,9 @@ export const download = {
 
   // 下载状态
   'download.status.stopped': '下载已停止',
+  'download.status.queued': '排队等待中...',
+  'download.status.paused': '已暂停',
+  'download.eta': '剩余',
 
   // 下载操作
   'download.remove.stopped': '移除停止的下载',
@@ -1,6 +1,7 @@
 import { useStorageStore } from '../stores/storageStore';
 import type { ArchiveInfo, FilePreview } from '../types';
 import { commands } from '../types/tauri-commands';
+import { base64ToBytes } from '../utils/typeUtils';
 
 export class CompressionService {
   /**
@@ -14,7 +15,8 @@ export class CompressionService {
     const timeoutMs = 30000; // 30秒
 
     const result = await Promise.race([
-      commands.archiveGetFileInfo(url, filename, maxSize || null),
+      // 条目由后端按自然顺序排序（shard2 < shard10）
+      commands.archiveGetFileInfo(url, filename, maxSize || null, true, null, null),
       new Promise<never>((_, reject) => {
         setTimeout(() => {
           reject(new Error(`压缩文件分析超时 (${timeoutMs}ms)`));
@@ -23,27 +25,26 @@ export class CompressionService {
     ]);
 
     if (result.status === 'error') {
-      throw new Error(result.error);
+      throw new Error(result.error.message);
     }
 
     return result.data;
   }
 
   /**
-   * 从压缩文件中提取文件预览（使用自定义协议）
+   * 从压缩文件中提取文件预览（使用预览管线）
+   * 最多读取 maxBytes 字节（后端上限 64MB），返回的 next_continuation 传回 continuation 即可读取下一段
    */
-  static async extractFilePreviewViaProtocol(
+  static async extractFilePreview(
     archivePath: string,
     entryPath: string,
-    maxPreviewSize?: number
+    maxBytes?: number,
+    encoding?: string,
+    continuation?: string
   ): Promise<FilePreview> {
-    // 使用 useStorageStore 构建协议URL
-    const archiveProtocolUrl = useStorageStore.getState().getFileUrl(archivePath);
-
-    // 检查 entryPath 是否已经是编码格式，如果是则先解码再重新编码
+    // 检查 entryPath 是否已经是编码格式，如果是则先解码
     let normalizedEntryPath = entryPath;
     try {
-      // 尝试解码，如果成功且与原始不同，说明已经编码过
       const decoded = decodeURIComponent(entryPath);
       if (decoded !== entryPath) {
         normalizedEntryPath = decoded;
@@ -52,63 +53,33 @@ export class CompressionService {
       // 解码失败，使用原始路径
     }
 
-    // 构建协议URL：protocol://host/path/to/archive.zip?entry=internal/file.txt
-    const protocolUrl = `${archiveProtocolUrl}?entry=${encodeURIComponent(normalizedEntryPath)}`;
-
-    // Extract protocol name from URL (e.g., 'local' from 'local://')
-    const protocolMatch = archiveProtocolUrl.match(/^([a-z]+):\/\//);
-    const protocol = protocolMatch ? protocolMatch[1] : 'local';
-
-    // Convert protocol URL to Tauri-compatible URL (handles Windows conversion)
-    const { convertProtocolUrl } = await import('../utils/protocolUtils');
-    const fetchUrl = await convertProtocolUrl(protocolUrl, protocol);
-
-    try {
-      const response = await fetch(fetchUrl, {
-        headers: maxPreviewSize ? { Range: `bytes=0-${maxPreviewSize - 1}` } : {},
-      });
-
-      if (!response.ok) {
-        throw new Error(`HTTP ${response.status}: ${response.statusText}`);
-      }
-
-      const content = new Uint8Array(await response.arrayBuffer());
-      const totalSizeHeader = response.headers.get('Content-Range');
-      // 默认使用已读长度
-      let totalSize = content.length.toString();
-      let total = content.length;
+    const result = await commands.previewRun({
+      path: useStorageStore.getState().getFileUrl(archivePath),
+      entryPath: normalizedEntryPath,
+      maxBytes: maxBytes ?? null,
+      maxRows: null,
+      continuation: continuation ?? null,
+      steps: [],
+      // 原始字节以 base64 返回，由调用方按 encoding 解码
+      output: 'base64',
+      password: null,
+      encoding: encoding ?? null,
+      requestId: null,
+    });
 
-      // 解析总大小
-      if (totalSizeHeader) {
-        const match = totalSizeHeader.match(/bytes \d+-\d+\/(\d+)/);
-        if (match) {
-          totalSize = match[1];
-          total = parseInt(match[1], 10);
-        }
-      } else {
-        const contentLength = response.headers.get('Content-Length');
-        if (contentLength) {
-          totalSize = contentLength;
-          total = parseInt(contentLength, 10);
-        }
-      }
+    if (result.status === 'error') {
+      throw new Error(`文件预览提取失败: ${result.error}`);
+    }
 
-      // 优先用"总大小 vs 已读大小"判断截断；无法确定总大小时再回退到 maxPreviewSize
-      const isTruncated =
-        Number.isFinite(total) && total > 0
-          ? content.length < total
-          : !!maxPreviewSize && content.length >= (maxPreviewSize || 0);
+    const content = base64ToBytes(result.data.content);
 
-      return {
-        content,
-        is_truncated: isTruncated,
-        total_size: totalSize,
-        preview_size: content.length,
-      } as FilePreview;
-    } catch (error) {
-      throw new Error(
-        `文件预览提取失败: ${error instanceof Error ? error.message : String(error)}`
-      );
-    }
+    return {
+      content,
+      is_truncated: result.data.truncated,
+      total_size: result.data.totalSize,
+      preview_size: content.length,
+      encoding: result.data.encoding ?? undefined,
+      next_continuation: result.data.nextContinuation ?? undefined,
+    };
   }
 }
@@ -1,82 +1,71 @@
+import {
+  commands,
+  type ConnectionProfile,
+  type ConnectionConfig as TauriConnectionConfig,
+} from '../types/tauri-commands';
 import { getHostnameFromUrl } from '../utils/urlUtils';
-import type { ConnectionConfig } from './storage/types';
+import { StorageClient } from './storage/StorageClient';
+import type { ConnectionConfig, StorageClientType } from './storage/types';
 
 export interface StoredConnection {
   id: string;
   name: string;
-  config: ConnectionConfig; // 直接存储完整的连接配置
+  config: ConnectionConfig; // 不含敏感字段，密码、密钥和令牌保存在系统钥匙串中
   lastConnected?: string;
   isDefault?: boolean;
 }
 
-class ConnectionStorageService {
-  private readonly STORAGE_KEY = 'saved-connections';
+// 删除连接后可撤销的时间，长于撤销提示的显示时间，超时后才从后端删除
+const UNDO_DELETE_DELAY = 5000;
 
-  // 获取所有保存的连接
-  getStoredConnections(): StoredConnection[] {
-    try {
-      const stored = localStorage.getItem(this.STORAGE_KEY);
-      const connections = stored ? JSON.parse(stored) : [];
-
-      // 过滤无效的连接数据
-      const validConnections = connections.filter((conn: any) => {
-        return conn.id && conn.name && conn.config && conn.config.type;
-      });
+/**
+ * 已保存连接的管理
+ * 连接配置通过 connection_* 命令保存在后端，敏感字段存放在系统钥匙串中；
+ * 这里缓存最近一次加载的列表，供同步读取
+ */
+class ConnectionStorageService {
+  // 旧版本保存在 localStorage 中的连接（包含密码），加载时迁移到后端后删除
+  private readonly LEGACY_STORAGE_KEY = 'saved-connections';
+  private readonly DEFAULT_CONNECTION_KEY = 'default-connection-id';
+
+  private connections: StoredConnection[] = [];
+  private profiles = new Map<string, ConnectionProfile>();
+  private pendingDeletes = new Map<string, ReturnType<typeof setTimeout>>();
+  private migration: Promise<void> | null = null;
+
+  // 从后端重新加载连接列表
+  async loadConnections(): Promise<StoredConnection[]> {
+    this.migration ??= this.migrateLegacyConnections();
+    await this.migration;
+
+    const result = await commands.connectionList();
+    if (result.status === 'error') {
+      console.error('Failed to load stored connections:', result.error);
+      return this.getStoredConnections();
+    }
 
+    const defaultId = localStorage.getItem(this.DEFAULT_CONNECTION_KEY);
+    this.profiles = new Map(result.data.map(profile => [profile.id, profile]));
+    this.connections = result.data
+      .map(profile => this.fromProfile(profile, defaultId))
       // 按照最后连接时间排序，最近的在前面
-      return validConnections.sort((a: StoredConnection, b: StoredConnection) => {
-        const aTime = new Date(a.lastConnected || new Date().toISOString()).getTime();
-        const bTime = new Date(b.lastConnected || new Date().toISOString()).getTime();
-        return bTime - aTime; // 降序排列，最新的在前面
+      .sort((a, b) => {
+        const aTime = a.lastConnected ? new Date(a.lastConnected).getTime() : 0;
+        const bTime = b.lastConnected ? new Date(b.lastConnected).getTime() : 0;
+        return bTime - aTime;
       });
-    } catch (error) {
-      console.error('Failed to load stored connections:', error);
-      return [];
-    }
+    return this.getStoredConnections();
   }
 
-  // 保存连接配置
-  async saveConnection(config: ConnectionConfig, name?: string): Promise<string> {
-    const connections = this.getStoredConnections();
-
-    // 检查是否已存在相同的连接
-    const existingConnection = this.findConnection(config);
-    if (existingConnection) {
-      // 重新获取最新的连接列表并更新
-      const updatedConnections = this.getStoredConnections();
-      const targetConnection = updatedConnections.find(c => c.id === existingConnection.id);
-      if (targetConnection) {
-        targetConnection.config = { ...config };
-        targetConnection.lastConnected = new Date().toISOString();
-        localStorage.setItem(this.STORAGE_KEY, JSON.stringify(updatedConnections));
-      }
-
-      return existingConnection.id;
-    }
-
-    const id = this.generateId();
-
-    // 生成连接名称
-    let connectionName = name || config.name;
-    if (!connectionName) {
-      connectionName = this.generateConnectionName(config);
-    }
-
-    // 直接保存完整的连接配置（包含密码信息）
-    const storedConnection: StoredConnection = {
-      id,
-      name: connectionName,
-      config: { ...config },
-      lastConnected: new Date().toISOString(),
-    };
-
-    // 如果是第一个连接，设为默认
-    if (connections.length === 0) {
-      storedConnection.isDefault = true;
-    }
+  // 获取最近一次加载的连接，不含等待删除的连接
+  getStoredConnections(): StoredConnection[] {
+    return this.connections.filter(c => !this.pendingDeletes.has(c.id));
+  }
 
-    connections.push(storedConnection);
-    localStorage.setItem(this.STORAGE_KEY, JSON.stringify(connections));
+  // 保存连接配置，敏感字段由后端写入系统钥匙串
+  async saveConnection(config: ConnectionConfig, name?: string): Promise<string> {
+    const id = await this.saveProfile(config, name);
+    await this.loadConnections();
     return id;
   }
 
@@ -192,40 +181,38 @@ class ConnectionStorageService {
     }
   }
 
-  // 更新最后连接时间
-  updateLastConnected(id: string): void {
-    const connections = this.getStoredConnections();
-    const connection = connections.find(c => c.id === id);
-    if (connection) {
-      connection.lastConnected = new Date().toISOString();
-      localStorage.setItem(this.STORAGE_KEY, JSON.stringify(connections));
-    }
-  }
-
-  // 删除连接
+  // 删除连接，UNDO_DELETE_DELAY 内可通过 restoreConnection 撤销
   deleteConnection(id: string): void {
-    const connections = this.getStoredConnections();
-    const filtered = connections.filter(c => c.id !== id);
-    localStorage.setItem(this.STORAGE_KEY, JSON.stringify(filtered));
+    const existing = this.pendingDeletes.get(id);
+    if (existing) {
+      clearTimeout(existing);
+    }
+    const timer = setTimeout(async () => {
+      this.pendingDeletes.delete(id);
+      const result = await commands.connectionDelete(id);
+      if (result.status === 'error') {
+        console.error('Failed to delete connection:', result.error);
+      }
+      this.connections = this.connections.filter(c => c.id !== id);
+    }, UNDO_DELETE_DELAY);
+    this.pendingDeletes.set(id, timer);
   }
 
-  // 恢复已删除的连接
+  // 撤销尚未生效的删除
   restoreConnection(connection: StoredConnection): void {
-    const connections = this.getStoredConnections();
-    const exists = connections.some(c => c.id === connection.id);
-    if (!exists) {
-      connections.push(connection);
-      localStorage.setItem(this.STORAGE_KEY, JSON.stringify(connections));
+    const timer = this.pendingDeletes.get(connection.id);
+    if (timer) {
+      clearTimeout(timer);
+      this.pendingDeletes.delete(connection.id);
     }
   }
 
   // 设置默认连接
   setDefaultConnection(id: string): void {
-    const connections = this.getStoredConnections();
-    connections.forEach(c => {
+    localStorage.setItem(this.DEFAULT_CONNECTION_KEY, id);
+    this.connections.forEach(c => {
       c.isDefault = c.id === id;
     });
-    localStorage.setItem(this.STORAGE_KEY, JSON.stringify(connections));
   }
 
   // 获取默认连接
@@ -235,24 +222,135 @@ class ConnectionStorageService {
   }
 
   // 重命名连接
-  renameConnection(id: string, newName: string): boolean {
-    const connections = this.getStoredConnections();
-    const connection = connections.find(c => c.id === id);
-    if (connection) {
-      connection.name = newName;
-      localStorage.setItem(this.STORAGE_KEY, JSON.stringify(connections));
-      return true;
+  async renameConnection(id: string, newName: string): Promise<boolean> {
+    const profile = this.profiles.get(id);
+    if (!profile) {
+      return false;
     }
-    return false;
+    const result = await commands.connectionSave({
+      id,
+      name: newName,
+      config: profile.config,
+      uiConfig: null,
+    });
+    if (result.status === 'error') {
+      console.error('Failed to rename connection:', result.error);
+      return false;
+    }
+    await this.loadConnections();
+    return true;
   }
 
   // 清空所有连接
-  clearAllConnections(): void {
-    localStorage.removeItem(this.STORAGE_KEY);
+  async clearAllConnections(): Promise<void> {
+    const connections = await this.loadConnections();
+    for (const connection of connections) {
+      const result = await commands.connectionDelete(connection.id);
+      if (result.status === 'error') {
+        console.error('Failed to delete connection:', result.error);
+      }
+    }
+    localStorage.removeItem(this.DEFAULT_CONNECTION_KEY);
+    localStorage.removeItem(this.LEGACY_STORAGE_KEY);
+    await this.loadConnections();
+  }
+
+  // 新建或更新后端保存的连接，返回连接 ID
+  private async saveProfile(config: ConnectionConfig, name?: string): Promise<string> {
+    // 已保存的连接按 ID 更新，否则查找相同服务器和用户的连接
+    const id = config.profileId ?? this.findConnection(config)?.id ?? null;
+    const existing = id ? this.connections.find(c => c.id === id) : undefined;
+
+    const result = await commands.connectionSave({
+      id,
+      name: existing?.name || name || config.name || this.generateConnectionName(config),
+      config: new StorageClient(config.type).toBackendConfig(config),
+      uiConfig: JSON.stringify(this.stripSecrets(config)),
+    });
+    if (result.status === 'error') {
+      throw new Error(result.error);
+    }
+    return result.data.id;
+  }
+
+  // 把旧版本 localStorage 中的连接迁移到后端，密码随之移入系统钥匙串
+  private async migrateLegacyConnections(): Promise<void> {
+    const stored = localStorage.getItem(this.LEGACY_STORAGE_KEY);
+    if (!stored) {
+      return;
+    }
+
+    try {
+      const legacy: Array<{ name?: string; config?: ConnectionConfig; isDefault?: boolean }> =
+        JSON.parse(stored);
+      for (const connection of legacy) {
+        if (!connection?.config?.type) continue;
+        try {
+          const id = await this.saveProfile(connection.config, connection.name);
+          if (connection.isDefault) {
+            localStorage.setItem(this.DEFAULT_CONNECTION_KEY, id);
+          }
+        } catch (error) {
+          console.error('Failed to migrate stored connection:', error);
+        }
+      }
+    } catch (error) {
+      console.error('Failed to load legacy stored connections:', error);
+    }
+    localStorage.removeItem(this.LEGACY_STORAGE_KEY);
+  }
+
+  private fromProfile(profile: ConnectionProfile, defaultId: string | null): StoredConnection {
+    let config: ConnectionConfig | null = null;
+    if (profile.uiConfig) {
+      try {
+        config = JSON.parse(profile.uiConfig);
+      } catch (error) {
+        console.warn('Invalid stored connection config:', error);
+      }
+    }
+
+    return {
+      id: profile.id,
+      name: profile.name,
+      config: {
+        ...(config ?? this.fromBackendConfig(profile.config)),
+        profileId: profile.id,
+        hasStoredSecret: profile.storedSecrets.length > 0,
+      },
+      lastConnected: profile.lastUsedAt ?? undefined,
+      isDefault: profile.id === defaultId,
+    };
+  }
+
+  // 没有前端表单配置的连接（如由其他窗口保存）按后端配置还原
+  private fromBackendConfig(config: TauriConnectionConfig): ConnectionConfig {
+    return {
+      type: config.protocol as StorageClientType,
+      url: config.url ?? undefined,
+      username: config.username ?? config.accessKey ?? undefined,
+      authScheme: (config.authScheme as ConnectionConfig['authScheme']) ?? undefined,
+      rootPath: config.rootPath ?? undefined,
+      bucket: config.bucket ?? undefined,
+      region: config.region ?? undefined,
+      endpoint: config.endpoint ?? undefined,
+      port: config.port ?? undefined,
+      privateKeyPath: config.privateKeyPath ?? undefined,
+      share: config.share ?? undefined,
+      domain: config.domain ?? undefined,
+    };
   }
 
-  private generateId(): string {
-    return Date.now().toString(36) + Math.random().toString(36).substr(2);
+  // 前端表单配置只保存非敏感字段
+  private stripSecrets(config: ConnectionConfig): ConnectionConfig {
+    const uiConfig = { ...config };
+    delete uiConfig.password;
+    delete uiConfig.apiToken;
+    delete uiConfig.passphrase;
+    delete uiConfig.profileId;
+    delete uiConfig.hasStoredSecret;
+    delete uiConfig.isTemporary;
+    return uiConfig;
   }
 }
 
@@ -1,6 +1,7 @@
 import { listen } from '@tauri-apps/api/event';
 import { useStorageStore } from '../stores/storageStore';
 import type { StorageFile } from '../types';
+import { connectionStorage } from './connectionStorage';
 import { navigationHistoryService } from './navigationHistory';
 
 /**
@@ -127,6 +128,48 @@ export class FileAssociationService {
     }
   }
 
+  /**
+   * 在查看窗口中打开已保存连接中的文件，先使用该连接配置重新连接
+   * @param profileId 已保存连接的 ID
+   * @param filePath 文件在连接中的路径
+   * @returns 处理结果
+   */
+  public async openConnectionFile(profileId: string, filePath: string): Promise<OpenFileResult> {
+    try {
+      const store = useStorageStore.getState();
+      const connections = await connectionStorage.loadConnections();
+      const connection = connections.find(c => c.id === profileId);
+      if (!connection) {
+        return { success: false, fileName: '', error: `Connection not found: ${profileId}` };
+      }
+
+      // 当前已连接到该配置时不重复连接
+      if (!store.isConnected() || store.currentConnection?.profileId !== profileId) {
+        navigationHistoryService.clearDirectoryCache();
+        const success = await store.connectWithConfig({
+          ...connection.config,
+          profileId,
+          isTemporary: true,
+        });
+        if (!success) {
+          return { success: false, fileName: '', error: 'Failed to connect to storage' };
+        }
+      }
+
+      const file = await this.createStorageFile(filePath);
+      file.basename = filePath.split('/').pop() || filePath;
+      return { success: true, file, fileName: filePath };
+    } catch (error) {
+      console.error('Error in openConnectionFile:', error);
+
+      return {
+        success: false,
+        fileName: '',
+        error: error instanceof Error ? error.message : 'Unknown error',
+      };
+    }
+  }
+
   /**
    * 创建 StorageFile 对象
    * @param fileName 文件名
@@ -428,6 +428,7 @@ export class FolderDownloadService {
             recursive: null,
             sortBy: null,
             sortOrder: null,
+            naturalSort: null,
           });
 
           // 类型转换：将 tauri-commands StorageFile[] 转换为前端 StorageFile[]
@@ -6,6 +6,7 @@ import {
   type ConnectionConfig as TauriConnectionConfig,
 } from '../../types/tauri-commands';
 import { detectEncodingWithFallback } from '../../utils/textEncodingDetection';
+import { base64ToBytes } from '../../utils/typeUtils';
 import type {
   ConnectionConfig,
   FileContent,
@@ -27,6 +28,9 @@ import { sshStorageAdapter } from './adapters/SSHAdapter';
 // 导入平台特定的适配器
 import { webdavStorageAdapter } from './adapters/WebDAVAdapter';
 
+// 预览管线单次读取的上限，与后端的 HARD_MAX_BYTES 一致
+const PREVIEW_MAX_BYTES = 64 * 1024 * 1024; // 64MB
+
 /**
  * 存储客户端排序选项
  */
@@ -190,17 +194,16 @@ export class StorageClient implements IStorageClient {
       this.connectionConfig = config;
 
       // 适配器预处理连接配置
-      let processedConnection = config;
-      if (this.adapter.preprocessConnection) {
-        const preprocessed = this.adapter.preprocessConnection(config);
-        processedConnection = { ...config, ...preprocessed };
-      }
+      const processedConnection = this.preprocessConnection(config);
 
       // 构建后端连接配置
       const backendConfig = this.buildBackendConfig(processedConnection);
 
-      // 调用后端连接
-      const connected = await this.connectToBackend(backendConfig);
+      // 调用后端连接，已保存的连接未重新填写敏感字段时由后端从系统钥匙串读取
+      const connected =
+        config.profileId && !hasSecretInput(config)
+          ? await this.connectWithProfile(config.profileId)
+          : await this.connectToBackend(backendConfig);
 
       if (connected) {
         // 适配器后处理连接状态
@@ -219,6 +222,13 @@ export class StorageClient implements IStorageClient {
     }
   }
 
+  /**
+   * 构建保存到后端的连接配置（经过适配器预处理）
+   */
+  toBackendConfig(config: ConnectionConfig): TauriConnectionConfig {
+    return this.buildBackendConfig(this.preprocessConnection(config));
+  }
+
   disconnect(): void {
     this.disconnectFromBackend();
     this.connection = null;
@@ -257,6 +267,7 @@ export class StorageClient implements IStorageClient {
               recursive: options.recursive || null,
               sortBy: options.sortBy || null,
               sortOrder: options.sortOrder || null,
+              naturalSort: options.naturalSort ?? null,
             }
           : undefined
       );
@@ -272,7 +283,10 @@ export class StorageClient implements IStorageClient {
     }
 
     try {
-      const data = await this.readFileBytes(path, options?.start, options?.length);
+      // 从头读取时使用预览管线（后端限制单次读取的大小），指定起始位置时按范围读取
+      const data = options?.start
+        ? await this.readFileBytes(path, options.start, options.length)
+        : await this.readPreviewBytes(path, options?.length);
 
       // 先检测编码
       const { encoding: detectedEncoding } = detectEncodingWithFallback(data);
@@ -364,7 +378,7 @@ export class StorageClient implements IStorageClient {
     const result = await commands.storageList(protocolUrl, options || null);
 
     if (result.status === 'error') {
-      throw new Error(result.error);
+      throw new Error(result.error.message);
     }
 
     return result.data;
@@ -382,10 +396,18 @@ export class StorageClient implements IStorageClient {
     // 确保 savePath 不是 undefined，如果是则设为 null
     const normalizedSavePath = savePath === undefined ? null : savePath;
 
-    const result = await commands.downloadStart(url, filename, normalizedSavePath);
+    const result = await commands.downloadStart(
+      url,
+      filename,
+      normalizedSavePath,
+      null,
+      null,
+      null,
+      null
+    );
 
     if (result.status === 'error') {
-      throw new Error(result.error);
+      throw new Error(result.error.message);
     }
 
     return result.data;
@@ -403,10 +425,18 @@ export class StorageClient implements IStorageClient {
     const protocolUrl = this.toProtocolUrl(path);
 
     // 通过Tauri命令调用后端的存储客户端接口
-    const result = await commands.archiveGetFileInfo(protocolUrl, filename, maxSize || null);
+    // 条目由后端按自然顺序排序（shard2 < shard10）
+    const result = await commands.archiveGetFileInfo(
+      protocolUrl,
+      filename,
+      maxSize || null,
+      true,
+      null,
+      null
+    );
 
     if (result.status === 'error') {
-      throw new Error(result.error);
+      throw new Error(result.error.message);
     }
 
     return result.data;
@@ -473,6 +503,28 @@ export class StorageClient implements IStorageClient {
     }
   }
 
+  /**
+   * 使用已保存的连接配置连接，敏感字段由后端从系统钥匙串读取
+   */
+  protected async connectWithProfile(profileId: string): Promise<boolean> {
+    try {
+      const result = await commands.connectionConnect(profileId);
+
+      if (result.status === 'error') {
+        console.error(`${this.protocol} connection failed:`, result.error);
+        this.connected = false;
+        return false;
+      }
+
+      this.connected = result.data;
+      return result.data;
+    } catch (error) {
+      console.error(`${this.protocol} connection failed:`, error);
+      this.connected = false;
+      return false;
+    }
+  }
+
   /**
    * 通用断开连接方法
    */
@@ -489,6 +541,31 @@ export class StorageClient implements IStorageClient {
     this.connected = false;
   }
 
+  /**
+   * 通过预览管线从头读取文件，最多读取 maxBytes 字节（未指定时读取到后端上限 64MB）
+   * @param path 文件路径
+   * @param maxBytes 最多读取的字节数（可选）
+   * @returns 二进制数据
+   */
+  private async readPreviewBytes(path: string, maxBytes?: number): Promise<Uint8Array> {
+    const result = await commands.previewRun({
+      path: this.toProtocolUrl(path),
+      entryPath: null,
+      maxBytes: maxBytes ?? PREVIEW_MAX_BYTES,
+      maxRows: null,
+      continuation: null,
+      steps: [],
+      output: 'base64',
+      password: null,
+      encoding: null,
+      requestId: null,
+    });
+    if (result.status === 'error') {
+      throw new Error(result.error);
+    }
+    return base64ToBytes(result.data.content);
+  }
+
   /**
    * 读取文件内容（统一二进制接口）
    * @param path 文件路径
@@ -639,6 +716,16 @@ export class StorageClient implements IStorageClient {
 
   // ========== 辅助方法 ==========
 
+  /**
+   * 适配器预处理连接配置
+   */
+  private preprocessConnection(config: ConnectionConfig): BaseConnection {
+    if (!this.adapter.preprocessConnection) {
+      return config;
+    }
+    return { ...config, ...this.adapter.preprocessConnection(config) };
+  }
+
   /**
    * 构建后端连接配置
    */
@@ -648,6 +735,7 @@ export class StorageClient implements IStorageClient {
       url: config.url || null,
       username: config.username || null,
       password: config.password || config.apiToken || null,
+      authScheme: config.authScheme || null,
       accessKey: config.accessKey || null,
       secretKey: config.secretKey || null,
       region: config.region || null,
@@ -666,6 +754,13 @@ export class StorageClient implements IStorageClient {
   }
 }
 
+/**
+ * 连接配置中是否填写了敏感字段（密码、密钥、令牌或私钥密码）
+ */
+export function hasSecretInput(config: ConnectionConfig): boolean {
+  return !!(config.password || config.apiToken || config.passphrase);
+}
+
 // 导出适配器映射，供外部使用
 export { STORAGE_ADAPTERS };
 
@@ -90,10 +90,12 @@ export const huggingfaceStorageAdapter: StorageAdapter = {
     const config: ConnectionConfig = {
       type: 'huggingface',
       organization: formData.organization?.trim() || undefined,
+      // 使用已保存的令牌时不回传，连接时由后端从系统钥匙串读取
       apiToken:
-        formData.isApiTokenFromStorage && existingConnection?.config.apiToken
-          ? existingConnection.config.apiToken
+        formData.isApiTokenFromStorage && existingConnection
+          ? undefined
           : formData.apiToken?.trim() || undefined,
+      profileId: existingConnection?.id,
       name: existingConnection
         ? existingConnection.name
         : formData.organization?.trim()
@@ -106,8 +108,8 @@ export const huggingfaceStorageAdapter: StorageAdapter = {
 
   extractFormData: (config: ConnectionConfig) => ({
     organization: config.organization || '',
-    apiToken: config.apiToken ? '******' : '', // 使用占位符回显已保存的令牌
-    isApiTokenFromStorage: !!config.apiToken, // 如果有令牌，标记为来自存储
+    apiToken: config.hasStoredSecret ? '******' : '', // 使用占位符回显已保存的令牌
+    isApiTokenFromStorage: !!config.hasStoredSecret, // 令牌保存在系统钥匙串中
   }),
 };
 
@@ -114,6 +114,7 @@ export const localStorageAdapter: StorageAdapter = {
       type: 'local',
       url: rootPath,
       rootPath: rootPath,
+      profileId: existingConnection?.id,
       name: existingConnection
         ? existingConnection.name
         : `Local (${rootPath?.split('/').pop() || 'unknown'})`,
@@ -15,7 +15,8 @@ export const ossStorageAdapter: StorageAdapter = {
 
   preprocessConnection: (config: ConnectionConfig) => {
     // OSS 特有的配置解析和验证
-    if (!config.username || !config.password) {
+    // 已保存的连接未重新填写密钥时，由后端从系统钥匙串读取
+    if (!config.username || (!config.password && !config.profileId)) {
       throw new Error('OSS requires accessKey (username) and secretKey (password)');
     }
 
@@ -32,15 +33,22 @@ export const ossStorageAdapter: StorageAdapter = {
       }
     }
 
-    if (!bucket) {
-      throw new Error('OSS bucket is required');
-    }
-
     // 从端点 URL 中提取主机名，而不是从 oss:// URL
     if (!config.url) {
       throw new Error('OSS endpoint URL is required');
     }
 
+    // 未指定 bucket 时直接使用服务端点，根目录列出账号下的所有 bucket
+    if (!bucket) {
+      return {
+        url: config.url,
+        endpoint: config.url,
+        accessKey: config.username,
+        secretKey: config.password,
+        region: config.region,
+      };
+    }
+
     // 从 HTTP/HTTPS 端点 URL 中提取主机名
     let hostname = '';
     try {
@@ -90,10 +98,15 @@ export const ossStorageAdapter: StorageAdapter = {
   },
 
   buildProtocolUrl: (path: string, connection: any) => {
-    if (!connection?.bucket) {
+    if (!connection) {
       throw new Error('Not connected to OSS');
     }
 
+    // 未指定 bucket 时路径的第一段就是 bucket
+    if (!connection.bucket) {
+      return `oss://${path.replace(/^\/+/, '')}`;
+    }
+
     // 获取实际的桶名（不包含路径前缀）
     const actualBucket = connection.bucket.split('/')[0];
 
@@ -144,10 +157,10 @@ export const ossStorageAdapter: StorageAdapter = {
       region: formData.region?.trim() || 'cn-hangzhou', // 默认使用阿里云杭州区域
       bucket: cleanBucket, // 使用清理后的 bucket
       username: formData.accessKey?.trim(),
+      // 使用已保存的密码时不回传，连接时由后端从系统钥匙串读取
       password:
-        formData.isPasswordFromStorage && existingConnection?.config.password
-          ? existingConnection.config.password
-          : formData.secretKey,
+        formData.isPasswordFromStorage && existingConnection ? undefined : formData.secretKey,
+      profileId: existingConnection?.id,
       name: existingConnection
         ? existingConnection.name
         : `OSS (${cleanBucket.split('/')[0] || 'unknown'})`,
@@ -161,7 +174,7 @@ export const ossStorageAdapter: StorageAdapter = {
     region: config.region || 'cn-hangzhou', // 默认使用阿里云杭州区域
     bucket: config.bucket || '',
     accessKey: config.username || '', // 将 config.username 映射到 accessKey 字段
-    secretKey: config.password ? '******' : '', // 使用占位符回显已保存的密钥，映射到 secretKey 字段
-    isPasswordFromStorage: !!config.password, // 如果有密钥，标记为来自存储
+    secretKey: config.hasStoredSecret ? '******' : '', // 使用占位符回显已保存的密钥，映射到 secretKey 字段
+    isPasswordFromStorage: !!config.hasStoredSecret, // 密钥保存在系统钥匙串中
   }),
 };
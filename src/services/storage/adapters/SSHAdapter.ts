@@ -98,10 +98,10 @@ export const sshStorageAdapter: StorageAdapter = {
       type: 'ssh',
       url: formData.url?.trim(),
       username: formData.username?.trim(),
+      // 使用已保存的密码时不回传，连接时由后端从系统钥匙串读取
       password:
-        formData.isPasswordFromStorage && existingConnection?.config.password
-          ? existingConnection.config.password
-          : formData.password,
+        formData.isPasswordFromStorage && existingConnection ? undefined : formData.password,
+      profileId: existingConnection?.id,
       port: formData.port || 22,
       privateKeyPath: formData.privateKeyPath?.trim() || undefined,
       passphrase: formData.passphrase?.trim() || undefined,
@@ -117,11 +117,11 @@ export const sshStorageAdapter: StorageAdapter = {
   extractFormData: (config: ConnectionConfig) => ({
     url: config.url || '',
     username: config.username || '',
-    password: config.password ? '******' : '', // 使用占位符回显已保存的密码
+    password: config.hasStoredSecret ? '******' : '', // 使用占位符回显已保存的密码
     port: config.port || 22,
     privateKeyPath: config.privateKeyPath || '',
     passphrase: config.passphrase || '',
     rootPath: config.rootPath || '/',
-    isPasswordFromStorage: !!config.password, // 如果有密码，标记为来自存储
+    isPasswordFromStorage: !!config.hasStoredSecret, // 密码保存在系统钥匙串中
   }),
 };
@@ -61,10 +61,10 @@ export const webdavStorageAdapter: StorageAdapter = {
       type: 'webdav',
       url: formData.url?.trim(),
       username: formData.username?.trim(),
+      // 使用已保存的密码时不回传，连接时由后端从系统钥匙串读取
       password:
-        formData.isPasswordFromStorage && existingConnection?.config.password
-          ? existingConnection.config.password
-          : formData.password,
+        formData.isPasswordFromStorage && existingConnection ? undefined : formData.password,
+      profileId: existingConnection?.id,
       name: existingConnection
         ? existingConnection.name
         : `WebDAV (${formData.url?.trim() || 'unknown'})`,
@@ -76,7 +76,7 @@ export const webdavStorageAdapter: StorageAdapter = {
   extractFormData: (config: ConnectionConfig) => ({
     url: config.url || '',
     username: config.username || '',
-    password: config.password ? '******' : '', // 使用占位符回显已保存的密码
-    isPasswordFromStorage: !!config.password, // 如果有密码，标记为来自存储
+    password: config.hasStoredSecret ? '******' : '', // 使用占位符回显已保存的密码
+    isPasswordFromStorage: !!config.hasStoredSecret, // 密码保存在系统钥匙串中
   }),
 };
@@ -29,6 +29,7 @@ export interface ConnectionConfig {
   url?: string;
   username?: string;
   password?: string;
+  authScheme?: 'basic' | 'digest' | 'bearer' | 'none'; // HTTP 认证方式，bearer 使用 password 作为令牌
   name?: string; // 连接名称，用于显示和保存
   // 本机文件系统特定配置
   rootPath?: string; // 本机文件系统的根目录路径
@@ -49,6 +50,8 @@ export interface ConnectionConfig {
   domain?: string; // SMB 域名或工作组
   // 连接元数据
   isTemporary?: boolean; // 临时连接，不保存到已保存连接中（如文件关联）
+  profileId?: string; // 已保存连接的 ID，未填写敏感字段时使用系统钥匙串中保存的值连接
+  hasStoredSecret?: boolean; // 密码、密钥或令牌已保存在系统钥匙串中
 }
 
 // 文件内容接口
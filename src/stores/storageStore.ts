@@ -3,7 +3,7 @@ import { create } from 'zustand';
 import { subscribeWithSelector } from 'zustand/middleware';
 import { immer } from 'zustand/middleware/immer';
 import { connectionStorage, type StoredConnection } from '../services/connectionStorage';
-import type { StorageClient } from '../services/storage/StorageClient';
+import { hasSecretInput, type StorageClient } from '../services/storage/StorageClient';
 import { StorageClientFactory } from '../services/storage/StorageManager';
 import type {
   ConnectionConfig,
@@ -74,16 +74,16 @@ export interface StorageState {
  */
 export interface StorageActions {
   // === 连接管理操作 ===
-  loadConnections: () => void;
+  loadConnections: () => Promise<void>;
   connectWithConfig: (config: ConnectionConfig) => Promise<boolean>;
   disconnect: () => void;
   autoConnect: () => Promise<boolean>;
   setCurrentConnection: (connection: ConnectionConfig | null) => void;
 
   // === 连接CRUD操作 ===
-  addConnection: (connection: StoredConnection) => void;
+  addConnection: (connection: StoredConnection) => Promise<void>;
   removeConnection: (id: string) => void;
-  updateConnection: (id: string, updates: Partial<StoredConnection>) => void;
+  updateConnection: (id: string, updates: Partial<StoredConnection>) => Promise<void>;
   setDefaultConnection: (id: string) => void;
 
   // === 文件操作 ===
@@ -158,9 +158,10 @@ export const useStorageStore = create<StorageStore>()(
       _currentClient: null,
 
       // === 连接管理操作 ===
-      loadConnections: () => {
+      loadConnections: async () => {
+        const connections = await connectionStorage.loadConnections();
         set(state => {
-          state.connections = connectionStorage.getStoredConnections();
+          state.connections = connections;
         });
       },
 
@@ -178,6 +179,13 @@ export const useStorageStore = create<StorageStore>()(
             state._currentClient.disconnect();
           }
 
+          // 已保存的连接未重新填写敏感字段时由后端使用钥匙串中的值连接，先保存修改过的其他字段
+          // （临时连接，如查看窗口重新连接，不修改已保存的配置）
+          const usesStoredSecret = !!config.profileId && !hasSecretInput(config);
+          if (usesStoredSecret && !config.isTemporary) {
+            await connectionStorage.saveConnection(config);
+          }
+
           // 连接新的存储
           const client = await StorageClientFactory.connectToStorage(config);
 
@@ -193,15 +201,16 @@ export const useStorageStore = create<StorageStore>()(
 
           // 自动保存连接信息（除非明确标记为临时连接）
           if (!config.isTemporary) {
-            const connectionId = await connectionStorage.saveConnection(
-              config,
-              client.generateConnectionName(config)
-            );
-            if (connectionId) {
-              connectionStorage.setDefaultConnection(connectionId);
-              // 重新加载连接列表
-              get().loadConnections();
-            }
+            const connectionId =
+              usesStoredSecret && config.profileId
+                ? config.profileId
+                : await connectionStorage.saveConnection(
+                    config,
+                    client.generateConnectionName(config)
+                  );
+            connectionStorage.setDefaultConnection(connectionId);
+            // 重新加载连接列表
+            await get().loadConnections();
           }
 
           return true;
@@ -238,6 +247,8 @@ export const useStorageStore = create<StorageStore>()(
 
       autoConnect: async (): Promise<boolean> => {
         try {
+          await get().loadConnections();
+
           // 尝试使用默认连接
           const defaultConnection = connectionStorage.getDefaultConnection();
           if (defaultConnection) {
@@ -264,36 +275,44 @@ export const useStorageStore = create<StorageStore>()(
       },
 
       // === 连接CRUD操作 ===
-      addConnection: (connection: StoredConnection) => {
+      addConnection: async (connection: StoredConnection) => {
         // connectionStorage 没有 addConnection 方法，直接保存连接
-        connectionStorage.saveConnection(connection.config, connection.name);
-        get().loadConnections();
+        await connectionStorage.saveConnection(connection.config, connection.name);
+        await get().loadConnections();
       },
 
       removeConnection: (id: string) => {
+        // 删除在撤销时间结束后才提交到后端，列表中立即隐藏
         connectionStorage.deleteConnection(id);
-        get().loadConnections();
+        set(state => {
+          state.connections = connectionStorage.getStoredConnections();
+        });
       },
 
-      updateConnection: (id: string, updates: Partial<StoredConnection>) => {
+      updateConnection: async (id: string, updates: Partial<StoredConnection>) => {
         const connections = connectionStorage.getStoredConnections();
         const connection = connections.find(c => c.id === id);
         if (connection) {
           // 更新连接名称
           if (updates.name) {
-            connectionStorage.renameConnection(id, updates.name);
+            await connectionStorage.renameConnection(id, updates.name);
           }
           // 如果需要更新配置，重新保存连接
           if (updates.config) {
-            connectionStorage.saveConnection(updates.config, updates.name || connection.name);
+            await connectionStorage.saveConnection(
+              { ...updates.config, profileId: id },
+              updates.name || connection.name
+            );
           }
-          get().loadConnections();
+          await get().loadConnections();
         }
       },
 
       setDefaultConnection: (id: string) => {
         connectionStorage.setDefaultConnection(id);
-        get().loadConnections();
+        set(state => {
+          state.connections = connectionStorage.getStoredConnections();
+        });
       },
 
       // === 文件操作 ===
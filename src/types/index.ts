@@ -50,4 +50,6 @@ export interface FilePreview {
   is_truncated: boolean;
   total_size: string; // 改为字符串类型，与后端保持一致
   preview_size: number;
+  encoding?: string; // 文本内容的编码（如 UTF-8、GBK），二进制内容为空
+  next_continuation?: string; // 读取下一段的续读标记，已读到末尾时为空
 }
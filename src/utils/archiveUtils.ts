@@ -21,6 +21,7 @@ function archiveEntryToStorageFile(entry: ArchiveEntry): StorageFile {
 
 /**
  * 构建压缩文件的虚拟文件系统树
+ * 每个目录下目录在前、文件在后，各自保持条目的顺序（后端已按自然顺序排序）
  */
 export function buildArchiveFileTree(entries: ArchiveEntry[]): Map<string, StorageFile[]> {
   const tree = new Map<string, StorageFile[]>();
@@ -81,16 +82,6 @@ export function buildArchiveFileTree(entries: ArchiveEntry[]): Map<string, Stora
     }
   });
 
-  // 对每个目录的文件进行排序（目录在前，然后按名称排序）
-  tree.forEach(files => {
-    files.sort((a, b) => {
-      if (a.type !== b.type) {
-        return a.type === 'directory' ? -1 : 1;
-      }
-      return a.basename.localeCompare(b.basename);
-    });
-  });
-
   return tree;
 }
 
@@ -141,6 +141,7 @@ const FILE_EXTENSIONS: Record<string, { type: FileType; mime: string }> = {
   lz4: { type: 'archive', mime: 'application/x-lz4' },
   zst: { type: 'archive', mime: 'application/zstd' },
   zstd: { type: 'archive', mime: 'application/zstd' },
+  tzst: { type: 'archive', mime: 'application/zstd' },
   br: { type: 'archive', mime: 'application/x-brotli' },
 };
 
@@ -26,3 +26,14 @@ export async function convertProtocolUrl(protocolUrl: string, protocol: string):
   // If it doesn't start with the expected protocol, return as-is
   return protocolUrl;
 }
+
+/**
+ * Build a seekable media-stream:// URL for <video>/<audio> playback
+ * The path can point to a file in the current storage or an archive entry (archive.zip!videos/a.mp4)
+ *
+ * @param path - The storage path of the media file
+ * @returns Tauri-compatible URL that supports HTTP Range requests
+ */
+export function getMediaStreamUrl(path: string): string {
+  return convertFileSrc(path, 'media-stream');
+}
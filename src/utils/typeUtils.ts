@@ -88,3 +88,17 @@ export const formatFileSize = (bytes: number | string): string => {
   const i = Math.min(Math.floor(Math.log(numBytes) / Math.log(k)), sizes.length - 1);
   return parseFloat((numBytes / k ** i).toFixed(2)) + ' ' + sizes[i];
 };
+
+/**
+ * 将 base64 字符串转换为字节数组
+ * @param value base64 字符串
+ * @returns 字节数组
+ */
+export const base64ToBytes = (value: string): Uint8Array => {
+  const binary = atob(value);
+  const bytes = new Uint8Array(binary.length);
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i);
+  }
+  return bytes;
+};